@@ -0,0 +1,92 @@
+//! Cache-line alignment for state shared between cores
+//!
+//! Two atomics on the same cache line written from different cores make the
+//! line bounce between them (false sharing) even though neither reads the
+//! other's value. `CachePadded` gives a value a line of its own.
+//!
+//! Audit of shared hot state:
+//! - `channel::bounded` - the senders' `head` and the receiver's `tail` are
+//!   each padded; before, every enqueue invalidated the receiver's line and
+//!   vice versa. Slots stay unpadded: a handoff touches the slot anyway and
+//!   padding 8192 slots would cost 1MB per queue.
+//! - `channel::broadcast` - `next_seq` is written by senders only and sits
+//!   apart from the subscriber list behind its mutex; no change.
+//! - Connection health and the metrics registry sit behind a `Mutex`, whose
+//!   lock word already serializes writers, so padding wouldn't help.
+//!
+//! The `cross_core_handoff` and `false_sharing` benchmarks in
+//! `tests/benchmarks` measure the effect; run them pinned to two physical
+//! cores, since on one core there is no line to bounce.
+
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+/// Bytes to pad to: 128 where the prefetcher pulls lines in pairs
+/// (x86_64, aarch64), 64 elsewhere
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+pub const CACHE_LINE_SIZE: usize = 128;
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+pub const CACHE_LINE_SIZE: usize = 64;
+
+/// A value aligned to, and padded out to, `CACHE_LINE_SIZE`
+#[cfg_attr(any(target_arch = "x86_64", target_arch = "aarch64"), repr(align(128)))]
+#[cfg_attr(not(any(target_arch = "x86_64", target_arch = "aarch64")), repr(align(64)))]
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct CachePadded<T> {
+    value: T,
+}
+
+impl<T> CachePadded<T> {
+    pub const fn new(value: T) -> Self {
+        Self { value }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Deref for CachePadded<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for CachePadded<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl<T> From<T> for CachePadded<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for CachePadded<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CachePadded").field(&self.value).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_padded_values_get_their_own_line() {
+        assert_eq!(std::mem::align_of::<CachePadded<u8>>(), CACHE_LINE_SIZE);
+        assert_eq!(std::mem::size_of::<CachePadded<AtomicUsize>>(), CACHE_LINE_SIZE);
+
+        let pair = [CachePadded::new(AtomicUsize::new(0)), CachePadded::new(AtomicUsize::new(0))];
+        let distance = (&*pair[1] as *const AtomicUsize as usize) - (&*pair[0] as *const AtomicUsize as usize);
+        assert!(distance >= CACHE_LINE_SIZE);
+
+        pair[0].fetch_add(1, Ordering::Relaxed);
+        assert_eq!(pair[0].load(Ordering::Relaxed), 1);
+    }
+}
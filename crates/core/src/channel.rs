@@ -0,0 +1,868 @@
+//! Channels between monoio runtimes on different cores
+//!
+//! Each core runs its own single-threaded monoio runtime, so tasks on
+//! different cores can't share `Rc`/`RefCell` state or monoio's local
+//! channels. These primitives are `Send + Sync` and wake the receiving task
+//! on whichever runtime it is parked:
+//!
+//! - `oneshot` - a single reply, e.g. an order ack back to the strategy core
+//! - `broadcast` - fan-out of control events such as a kill switch or a
+//!   config reload; slow subscribers skip ahead and are told how much they missed
+//! - `bounded` - a bounded MPSC built on a Vyukov array queue; neither side
+//!   takes a lock unless a sender has to wait for capacity
+//!
+//! Plain threads outside any runtime (a disk writer) use the `_blocking`
+//! variants, which park the thread instead of a task.
+
+use crate::cache::CachePadded;
+use std::cell::UnsafeCell;
+use std::collections::VecDeque;
+use std::future::{poll_fn, Future};
+use std::mem::MaybeUninit;
+use std::pin::Pin;
+use std::sync::atomic::{fence, AtomicBool, AtomicU8, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock, Weak};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::Thread;
+
+/// The sending side is gone and nothing more will arrive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("channel closed")]
+pub struct RecvError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum TryRecvError {
+    #[error("channel empty")]
+    Empty,
+    #[error("channel disconnected")]
+    Disconnected,
+}
+
+/// The receiver is gone; the value is handed back
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("channel disconnected")]
+pub struct SendError<T>(pub T);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum TrySendError<T> {
+    #[error("channel full")]
+    Full(T),
+    #[error("channel disconnected")]
+    Disconnected(T),
+}
+
+impl<T> TrySendError<T> {
+    pub fn into_inner(self) -> T {
+        match self {
+            TrySendError::Full(value) | TrySendError::Disconnected(value) => value,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum BroadcastRecvError {
+    /// The subscriber fell behind and this many messages were dropped
+    #[error("lagged behind by {0} messages")]
+    Lagged(u64),
+    #[error("channel closed")]
+    Closed,
+}
+
+const WAITING: usize = 0;
+const REGISTERING: usize = 0b01;
+const WAKING: usize = 0b10;
+
+/// Waker slot shared between one registering task and any number of wakers
+/// (the algorithm from `futures::task::AtomicWaker`)
+struct AtomicWaker {
+    state: AtomicUsize,
+    waker: UnsafeCell<Option<Waker>>,
+}
+
+// The waker cell is only accessed by whoever holds REGISTERING or WAKING
+unsafe impl Send for AtomicWaker {}
+unsafe impl Sync for AtomicWaker {}
+
+impl AtomicWaker {
+    fn new() -> Self {
+        Self {
+            state: AtomicUsize::new(WAITING),
+            waker: UnsafeCell::new(None),
+        }
+    }
+
+    fn register(&self, waker: &Waker) {
+        match self.state.compare_exchange(WAITING, REGISTERING, Ordering::Acquire, Ordering::Acquire) {
+            Ok(_) => {
+                // SAFETY: REGISTERING gives exclusive access to the cell
+                let slot = unsafe { &mut *self.waker.get() };
+                if !slot.as_ref().is_some_and(|old| old.will_wake(waker)) {
+                    *slot = Some(waker.clone());
+                }
+                if self.state.compare_exchange(REGISTERING, WAITING, Ordering::AcqRel, Ordering::Acquire).is_err() {
+                    // A wake arrived while registering; deliver it now
+                    let waker = slot.take();
+                    self.state.swap(WAITING, Ordering::AcqRel);
+                    if let Some(waker) = waker {
+                        waker.wake();
+                    }
+                }
+            }
+            Err(WAKING) => waker.wake_by_ref(),
+            // Concurrent registration is a caller bug; the other register wins
+            Err(_) => {}
+        }
+    }
+
+    fn wake(&self) {
+        if self.state.fetch_or(WAKING, Ordering::AcqRel) == WAITING {
+            // SAFETY: WAKING from WAITING gives exclusive access to the cell
+            let waker = unsafe { (*self.waker.get()).take() };
+            self.state.fetch_and(!WAKING, Ordering::Release);
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+        }
+    }
+}
+
+/// A value that can be taken exactly once, from any thread, without a lock
+///
+/// Used to hand out the single receiver of a channel owned by a shared struct.
+pub struct TakeOnce<T> {
+    taken: AtomicBool,
+    value: UnsafeCell<Option<T>>,
+}
+
+// Only the caller that flips `taken` touches the cell
+unsafe impl<T: Send> Send for TakeOnce<T> {}
+unsafe impl<T: Send> Sync for TakeOnce<T> {}
+
+impl<T> TakeOnce<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            taken: AtomicBool::new(false),
+            value: UnsafeCell::new(Some(value)),
+        }
+    }
+
+    /// The value on the first call, `None` afterwards
+    pub fn take(&self) -> Option<T> {
+        if self.taken.swap(true, Ordering::AcqRel) {
+            return None;
+        }
+        // SAFETY: the swap above admits exactly one caller
+        unsafe { (*self.value.get()).take() }
+    }
+}
+
+/// Wakes a thread parked in `block_on`
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Drive a channel future on the current thread, parking between polls
+fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = std::pin::pin!(future);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        // Spurious unparks just poll again
+        std::thread::park();
+    }
+}
+
+// ---------------------------------------------------------------------------
+// oneshot
+
+const EMPTY: u8 = 0;
+const FULL: u8 = 1;
+const CLOSED: u8 = 2;
+
+struct OneshotInner<T> {
+    state: AtomicU8,
+    value: UnsafeCell<Option<T>>,
+    waker: AtomicWaker,
+}
+
+// The sender writes the value before publishing FULL; the receiver reads it only after
+unsafe impl<T: Send> Send for OneshotInner<T> {}
+unsafe impl<T: Send> Sync for OneshotInner<T> {}
+
+/// Channel carrying exactly one value
+pub fn oneshot<T>() -> (OneshotSender<T>, OneshotReceiver<T>) {
+    let inner = Arc::new(OneshotInner {
+        state: AtomicU8::new(EMPTY),
+        value: UnsafeCell::new(None),
+        waker: AtomicWaker::new(),
+    });
+    (OneshotSender { inner: Some(Arc::clone(&inner)) }, OneshotReceiver { inner })
+}
+
+pub struct OneshotSender<T> {
+    inner: Option<Arc<OneshotInner<T>>>,
+}
+
+impl<T> OneshotSender<T> {
+    /// Deliver the value; hands it back if the receiver was dropped
+    pub fn send(mut self, value: T) -> Result<(), T> {
+        let Some(inner) = self.inner.take() else { return Err(value) };
+        if inner.state.load(Ordering::Acquire) == CLOSED {
+            return Err(value);
+        }
+        // SAFETY: state is not FULL, so the receiver isn't reading the cell
+        unsafe { *inner.value.get() = Some(value) };
+        match inner.state.compare_exchange(EMPTY, FULL, Ordering::AcqRel, Ordering::Acquire) {
+            Ok(_) => {
+                inner.waker.wake();
+                Ok(())
+            }
+            // SAFETY: the receiver closed without reading, the cell is still ours
+            Err(_) => Err(unsafe { (*inner.value.get()).take() }.expect("value stored above")),
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.inner.as_ref().is_none_or(|inner| inner.state.load(Ordering::Acquire) == CLOSED)
+    }
+}
+
+impl<T> Drop for OneshotSender<T> {
+    fn drop(&mut self) {
+        if let Some(inner) = self.inner.take()
+            && inner.state.compare_exchange(EMPTY, CLOSED, Ordering::AcqRel, Ordering::Acquire).is_ok()
+        {
+            inner.waker.wake();
+        }
+    }
+}
+
+/// Await the value directly: `let ack = rx.await?;`
+pub struct OneshotReceiver<T> {
+    inner: Arc<OneshotInner<T>>,
+}
+
+impl<T> OneshotReceiver<T> {
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        match self.inner.state.load(Ordering::Acquire) {
+            // SAFETY: after FULL the sender never touches the cell again
+            FULL => unsafe { (*self.inner.value.get()).take() }.ok_or(TryRecvError::Disconnected),
+            CLOSED => Err(TryRecvError::Disconnected),
+            _ => Err(TryRecvError::Empty),
+        }
+    }
+}
+
+impl<T> Future for OneshotReceiver<T> {
+    type Output = Result<T, RecvError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        match this.try_recv() {
+            Ok(value) => return Poll::Ready(Ok(value)),
+            Err(TryRecvError::Disconnected) => return Poll::Ready(Err(RecvError)),
+            Err(TryRecvError::Empty) => {}
+        }
+        this.inner.waker.register(cx.waker());
+        match this.try_recv() {
+            Ok(value) => Poll::Ready(Ok(value)),
+            Err(TryRecvError::Disconnected) => Poll::Ready(Err(RecvError)),
+            Err(TryRecvError::Empty) => Poll::Pending,
+        }
+    }
+}
+
+impl<T> Drop for OneshotReceiver<T> {
+    fn drop(&mut self) {
+        let _ = self.inner.state.compare_exchange(EMPTY, CLOSED, Ordering::AcqRel, Ordering::Acquire);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// broadcast
+
+struct BroadcastShared<T> {
+    /// Retained messages tagged with their sequence number
+    buffer: RwLock<VecDeque<(u64, T)>>,
+    capacity: usize,
+    /// Sequence number of the next message
+    next_seq: AtomicU64,
+    senders: AtomicUsize,
+    closed: AtomicBool,
+    subscribers: Mutex<Vec<Weak<AtomicWaker>>>,
+}
+
+impl<T> BroadcastShared<T> {
+    fn wake_subscribers(&self) -> usize {
+        let mut subscribers = self.subscribers.lock().unwrap_or_else(|e| e.into_inner());
+        subscribers.retain(|weak| match weak.upgrade() {
+            Some(waker) => {
+                waker.wake();
+                true
+            }
+            None => false,
+        });
+        subscribers.len()
+    }
+}
+
+/// Fan-out channel retaining the last `capacity` messages
+///
+/// Receivers are created with `subscribe` and see messages sent after that.
+pub fn broadcast<T: Clone>(capacity: usize) -> BroadcastSender<T> {
+    BroadcastSender {
+        shared: Arc::new(BroadcastShared {
+            buffer: RwLock::new(VecDeque::with_capacity(capacity.max(1))),
+            capacity: capacity.max(1),
+            next_seq: AtomicU64::new(0),
+            senders: AtomicUsize::new(1),
+            closed: AtomicBool::new(false),
+            subscribers: Mutex::new(Vec::new()),
+        }),
+    }
+}
+
+pub struct BroadcastSender<T> {
+    shared: Arc<BroadcastShared<T>>,
+}
+
+impl<T: Clone> BroadcastSender<T> {
+    /// Publish to every subscriber; returns how many are subscribed
+    pub fn send(&self, value: T) -> usize {
+        {
+            let mut buffer = self.shared.buffer.write().unwrap_or_else(|e| e.into_inner());
+            let seq = self.shared.next_seq.load(Ordering::Relaxed);
+            if buffer.len() == self.shared.capacity {
+                buffer.pop_front();
+            }
+            buffer.push_back((seq, value));
+            self.shared.next_seq.store(seq + 1, Ordering::Release);
+        }
+        self.shared.wake_subscribers()
+    }
+
+    pub fn subscribe(&self) -> BroadcastReceiver<T> {
+        let waker = Arc::new(AtomicWaker::new());
+        self.shared
+            .subscribers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(Arc::downgrade(&waker));
+        BroadcastReceiver {
+            shared: Arc::clone(&self.shared),
+            next: self.shared.next_seq.load(Ordering::Acquire),
+            waker,
+        }
+    }
+}
+
+impl<T> Clone for BroadcastSender<T> {
+    fn clone(&self) -> Self {
+        self.shared.senders.fetch_add(1, Ordering::Relaxed);
+        Self { shared: Arc::clone(&self.shared) }
+    }
+}
+
+impl<T> Drop for BroadcastSender<T> {
+    fn drop(&mut self) {
+        if self.shared.senders.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.shared.closed.store(true, Ordering::Release);
+            self.shared.wake_subscribers();
+        }
+    }
+}
+
+pub struct BroadcastReceiver<T> {
+    shared: Arc<BroadcastShared<T>>,
+    next: u64,
+    waker: Arc<AtomicWaker>,
+}
+
+impl<T: Clone> BroadcastReceiver<T> {
+    /// Next message, `Ok(None)` if there is none yet
+    pub fn try_recv(&mut self) -> Result<Option<T>, BroadcastRecvError> {
+        // Read `closed` first: once set, `next_seq` is final
+        let closed = self.shared.closed.load(Ordering::Acquire);
+        if self.next == self.shared.next_seq.load(Ordering::Acquire) {
+            return if closed { Err(BroadcastRecvError::Closed) } else { Ok(None) };
+        }
+
+        let buffer = self.shared.buffer.read().unwrap_or_else(|e| e.into_inner());
+        let oldest = buffer.front().map_or(self.next, |(seq, _)| *seq);
+        if self.next < oldest {
+            let missed = oldest - self.next;
+            self.next = oldest;
+            return Err(BroadcastRecvError::Lagged(missed));
+        }
+        let value = buffer.get((self.next - oldest) as usize).map(|(_, value)| value.clone());
+        if value.is_some() {
+            self.next += 1;
+        }
+        Ok(value)
+    }
+
+    pub async fn recv(&mut self) -> Result<T, BroadcastRecvError> {
+        poll_fn(|cx| {
+            if let Some(ready) = self.try_recv().transpose() {
+                return Poll::Ready(ready);
+            }
+            self.waker.register(cx.waker());
+            match self.try_recv().transpose() {
+                Some(ready) => Poll::Ready(ready),
+                None => Poll::Pending,
+            }
+        })
+        .await
+    }
+}
+
+// ---------------------------------------------------------------------------
+// bounded MPSC
+
+struct Slot<T> {
+    seq: AtomicUsize,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+struct MpscShared<T> {
+    slots: Box<[Slot<T>]>,
+    mask: usize,
+    /// Next enqueue position, claimed by senders with CAS
+    head: CachePadded<AtomicUsize>,
+    /// Next dequeue position, only advanced by the receiver; on its own line
+    /// so sender CAS traffic doesn't evict it
+    tail: CachePadded<AtomicUsize>,
+    senders: AtomicUsize,
+    receiver_alive: AtomicBool,
+    receiver_waker: AtomicWaker,
+    /// Senders parked on a full queue; only touched on that slow path
+    waiting_senders: AtomicUsize,
+    send_waiters: Mutex<VecDeque<Arc<SendWaiter>>>,
+}
+
+/// A parked `send`'s place in the wait queue, owned by its future
+struct SendWaiter {
+    waker: AtomicWaker,
+    /// In `send_waiters`; only changed with that lock held
+    queued: AtomicBool,
+}
+
+// Slot values are handed over through the per-slot sequence numbers
+unsafe impl<T: Send> Send for MpscShared<T> {}
+unsafe impl<T: Send> Sync for MpscShared<T> {}
+
+impl<T> MpscShared<T> {
+    fn push(&self, value: T) -> Result<(), T> {
+        let mut pos = self.head.load(Ordering::Relaxed);
+        loop {
+            let slot = &self.slots[pos & self.mask];
+            let seq = slot.seq.load(Ordering::Acquire);
+            match (seq as isize).wrapping_sub(pos as isize) {
+                0 => match self.head.compare_exchange_weak(pos, pos + 1, Ordering::Relaxed, Ordering::Relaxed) {
+                    Ok(_) => {
+                        // SAFETY: winning the CAS on a free slot gives exclusive write access
+                        unsafe { (*slot.value.get()).write(value) };
+                        slot.seq.store(pos + 1, Ordering::Release);
+                        return Ok(());
+                    }
+                    Err(current) => pos = current,
+                },
+                diff if diff < 0 => return Err(value),
+                _ => pos = self.head.load(Ordering::Relaxed),
+            }
+        }
+    }
+
+    /// Single consumer only
+    fn pop(&self) -> Option<T> {
+        let pos = self.tail.load(Ordering::Relaxed);
+        let slot = &self.slots[pos & self.mask];
+        if slot.seq.load(Ordering::Acquire) != pos + 1 {
+            return None;
+        }
+        // SAFETY: seq == pos + 1 means the slot holds a fully written value
+        let value = unsafe { (*slot.value.get()).assume_init_read() };
+        slot.seq.store(pos + self.mask + 1, Ordering::Release);
+        self.tail.store(pos + 1, Ordering::Relaxed);
+        Some(value)
+    }
+
+    fn lock_waiters(&self) -> std::sync::MutexGuard<'_, VecDeque<Arc<SendWaiter>>> {
+        self.send_waiters.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn wake_one_sender(&self) {
+        fence(Ordering::SeqCst);
+        if self.waiting_senders.load(Ordering::SeqCst) > 0 {
+            let waiter = {
+                let mut waiters = self.lock_waiters();
+                let waiter = waiters.pop_front();
+                if let Some(waiter) = &waiter {
+                    waiter.queued.store(false, Ordering::SeqCst);
+                    self.waiting_senders.fetch_sub(1, Ordering::SeqCst);
+                }
+                waiter
+            };
+            if let Some(waiter) = waiter {
+                waiter.waker.wake();
+            }
+        }
+    }
+
+    fn wake_all_senders(&self) {
+        let waiters: Vec<_> = {
+            let mut waiters = self.lock_waiters();
+            self.waiting_senders.store(0, Ordering::SeqCst);
+            waiters.drain(..).inspect(|w| w.queued.store(false, Ordering::SeqCst)).collect()
+        };
+        waiters.iter().for_each(|w| w.waker.wake());
+    }
+}
+
+impl<T> Drop for MpscShared<T> {
+    fn drop(&mut self) {
+        while self.pop().is_some() {}
+    }
+}
+
+/// Bounded multi-producer single-consumer channel
+///
+/// Capacity is rounded up to a power of two (minimum 2).
+pub fn bounded<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    let capacity = capacity.max(2).next_power_of_two();
+    let slots = (0..capacity)
+        .map(|i| Slot { seq: AtomicUsize::new(i), value: UnsafeCell::new(MaybeUninit::uninit()) })
+        .collect();
+    let shared = Arc::new(MpscShared {
+        slots,
+        mask: capacity - 1,
+        head: CachePadded::new(AtomicUsize::new(0)),
+        tail: CachePadded::new(AtomicUsize::new(0)),
+        senders: AtomicUsize::new(1),
+        receiver_alive: AtomicBool::new(true),
+        receiver_waker: AtomicWaker::new(),
+        waiting_senders: AtomicUsize::new(0),
+        send_waiters: Mutex::new(VecDeque::new()),
+    });
+    (Sender { shared: Arc::clone(&shared) }, Receiver { shared })
+}
+
+pub struct Sender<T> {
+    shared: Arc<MpscShared<T>>,
+}
+
+impl<T> Sender<T> {
+    /// Enqueue without waiting
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        if !self.shared.receiver_alive.load(Ordering::Acquire) {
+            return Err(TrySendError::Disconnected(value));
+        }
+        self.shared.push(value).map_err(TrySendError::Full)?;
+        self.shared.receiver_waker.wake();
+        Ok(())
+    }
+
+    /// Enqueue, waiting for capacity if the queue is full
+    ///
+    /// Dropping the future while it waits (a timeout or `select!`) takes it
+    /// out of the wait queue, and a wakeup it was given passes to the next sender.
+    pub async fn send(&self, value: T) -> Result<(), SendError<T>> {
+        let mut value = Some(value);
+        let mut wait = SendWait { shared: &self.shared, waiter: None, sent: false };
+        poll_fn(|cx| {
+            let Some(pending) = value.take() else { return Poll::Ready(Ok(())) };
+            let pending = match self.try_send(pending) {
+                Ok(()) => {
+                    wait.sent = true;
+                    return Poll::Ready(Ok(()));
+                }
+                Err(TrySendError::Disconnected(v)) => return Poll::Ready(Err(SendError(v))),
+                Err(TrySendError::Full(v)) => v,
+            };
+
+            wait.park(cx.waker());
+            fence(Ordering::SeqCst);
+
+            // Re-check: the receiver may have freed a slot before seeing our waker
+            match self.try_send(pending) {
+                Ok(()) => {
+                    wait.sent = true;
+                    Poll::Ready(Ok(()))
+                }
+                Err(TrySendError::Disconnected(v)) => Poll::Ready(Err(SendError(v))),
+                Err(TrySendError::Full(v)) => {
+                    value = Some(v);
+                    Poll::Pending
+                }
+            }
+        })
+        .await
+    }
+
+    /// `send` for a thread outside any runtime; parks it while the queue is full
+    pub fn send_blocking(&self, value: T) -> Result<(), SendError<T>> {
+        block_on(self.send(value))
+    }
+
+    pub fn is_closed(&self) -> bool {
+        !self.shared.receiver_alive.load(Ordering::Acquire)
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.shared.slots.len()
+    }
+}
+
+/// One `send` call's hold on the wait queue, released when the call ends
+struct SendWait<'a, T> {
+    shared: &'a MpscShared<T>,
+    waiter: Option<Arc<SendWaiter>>,
+    sent: bool,
+}
+
+impl<T> SendWait<'_, T> {
+    /// Queue once, or just update the waker if still queued from an earlier poll
+    fn park(&mut self, waker: &Waker) {
+        let waiter = self.waiter.get_or_insert_with(|| {
+            Arc::new(SendWaiter { waker: AtomicWaker::new(), queued: AtomicBool::new(false) })
+        });
+        waiter.waker.register(waker);
+        let mut waiters = self.shared.lock_waiters();
+        if !waiter.queued.load(Ordering::SeqCst) {
+            waiter.queued.store(true, Ordering::SeqCst);
+            waiters.push_back(Arc::clone(waiter));
+            self.shared.waiting_senders.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+}
+
+impl<T> Drop for SendWait<'_, T> {
+    fn drop(&mut self) {
+        let Some(waiter) = &self.waiter else { return };
+        let mut waiters = self.shared.lock_waiters();
+        if waiter.queued.load(Ordering::SeqCst) {
+            waiters.retain(|w| !Arc::ptr_eq(w, waiter));
+            waiter.queued.store(false, Ordering::SeqCst);
+            self.shared.waiting_senders.fetch_sub(1, Ordering::SeqCst);
+        } else if !self.sent {
+            // Woken for a free slot we won't use: hand the wakeup on
+            drop(waiters);
+            self.shared.wake_one_sender();
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Sender").field("capacity", &self.capacity()).field("closed", &self.is_closed()).finish()
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.shared.senders.fetch_add(1, Ordering::Relaxed);
+        Self { shared: Arc::clone(&self.shared) }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        if self.shared.senders.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.shared.receiver_waker.wake();
+        }
+    }
+}
+
+pub struct Receiver<T> {
+    shared: Arc<MpscShared<T>>,
+}
+
+impl<T> Receiver<T> {
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        if let Some(value) = self.shared.pop() {
+            self.shared.wake_one_sender();
+            return Ok(value);
+        }
+        if self.shared.senders.load(Ordering::Acquire) == 0 {
+            // Every send happened before the last sender dropped
+            return self.shared.pop().ok_or(TryRecvError::Disconnected);
+        }
+        Err(TryRecvError::Empty)
+    }
+
+    /// Next value, or `None` once every sender is gone and the queue is drained
+    pub async fn recv(&mut self) -> Option<T> {
+        poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    /// Poll for the next value, registering `cx` for a wakeup if there is none
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        match self.try_recv() {
+            Ok(value) => return Poll::Ready(Some(value)),
+            Err(TryRecvError::Disconnected) => return Poll::Ready(None),
+            Err(TryRecvError::Empty) => {}
+        }
+        self.shared.receiver_waker.register(cx.waker());
+        match self.try_recv() {
+            Ok(value) => Poll::Ready(Some(value)),
+            Err(TryRecvError::Disconnected) => Poll::Ready(None),
+            Err(TryRecvError::Empty) => Poll::Pending,
+        }
+    }
+
+    /// `recv` for a thread outside any runtime; parks it until a value arrives
+    pub fn recv_blocking(&mut self) -> Option<T> {
+        block_on(self.recv())
+    }
+
+    /// Every sender is gone; queued values can still be received
+    pub fn is_disconnected(&self) -> bool {
+        self.shared.senders.load(Ordering::Acquire) == 0
+    }
+
+    /// Values currently queued
+    pub fn len(&self) -> usize {
+        self.shared.head.load(Ordering::Acquire).saturating_sub(self.shared.tail.load(Ordering::Relaxed))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> std::fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Receiver").field("len", &self.len()).field("disconnected", &self.is_disconnected()).finish()
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.shared.receiver_alive.store(false, Ordering::Release);
+        self.shared.wake_all_senders();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        monoio::RuntimeBuilder::<monoio::FusionDriver>::new()
+            .build()
+            .expect("runtime")
+            .block_on(future)
+    }
+
+    #[test]
+    fn test_bounded_across_runtimes() {
+        let (tx, mut rx) = bounded::<u64>(4);
+        let producers: Vec<_> = (0..4u64)
+            .map(|p| {
+                let tx = tx.clone();
+                thread::spawn(move || {
+                    block_on(async move {
+                        for i in 0..500 {
+                            tx.send(p * 1000 + i).await.unwrap();
+                        }
+                    })
+                })
+            })
+            .collect();
+        drop(tx);
+
+        let (count, sum) = block_on(async move {
+            let (mut count, mut sum) = (0u64, 0u64);
+            while let Some(value) = rx.recv().await {
+                count += 1;
+                sum += value;
+            }
+            (count, sum)
+        });
+        producers.into_iter().for_each(|p| p.join().unwrap());
+
+        assert_eq!(count, 2000);
+        assert_eq!(sum, (0..4).map(|p| p * 1000 * 500 + 499 * 500 / 2).sum::<u64>());
+    }
+
+    #[test]
+    fn test_abandoned_send_leaves_no_stale_waiter() {
+        let (tx, mut rx) = bounded::<u8>(2);
+        tx.try_send(1).unwrap();
+        tx.try_send(2).unwrap();
+
+        // Re-polls keep one place in the queue; dropping the send (a timeout) gives it up
+        let mut abandoned = Box::pin(tx.send(3));
+        let mut cx = Context::from_waker(Waker::noop());
+        assert!(abandoned.as_mut().poll(&mut cx).is_pending());
+        assert!(abandoned.as_mut().poll(&mut cx).is_pending());
+        assert_eq!(rx.shared.waiting_senders.load(Ordering::SeqCst), 1);
+        drop(abandoned);
+        assert_eq!(rx.shared.waiting_senders.load(Ordering::SeqCst), 0);
+
+        let blocked_tx = tx.clone();
+        let blocked = thread::spawn(move || block_on(blocked_tx.send(4)));
+        while rx.shared.waiting_senders.load(Ordering::SeqCst) == 0 {
+            thread::yield_now();
+        }
+        assert_eq!(rx.try_recv(), Ok(1));
+        blocked.join().unwrap().unwrap();
+        assert_eq!((rx.try_recv(), rx.try_recv()), (Ok(2), Ok(4)));
+    }
+
+    #[test]
+    fn test_blocking_send_and_recv_between_threads() {
+        let (tx, mut rx) = bounded::<u32>(2);
+        let writer = thread::spawn(move || {
+            let mut received = Vec::new();
+            while let Some(value) = rx.recv_blocking() {
+                received.push(value);
+            }
+            received
+        });
+        // Capacity 2: the sender parks until the other thread makes room
+        for i in 0..100 {
+            tx.send_blocking(i).unwrap();
+        }
+        drop(tx);
+        assert_eq!(writer.join().unwrap(), (0..100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_oneshot_and_broadcast() {
+        let (reply_tx, reply_rx) = oneshot::<&str>();
+        let switch = broadcast::<bool>(2);
+        let mut kill = switch.subscribe();
+
+        let worker = thread::spawn(move || {
+            reply_tx.send("ack").unwrap();
+            for halted in [false, true, true] {
+                switch.send(halted);
+            }
+        });
+        assert_eq!(block_on(reply_rx), Ok("ack"));
+        worker.join().unwrap();
+
+        // Capacity 2: the first message was overwritten
+        assert_eq!(kill.try_recv(), Err(BroadcastRecvError::Lagged(1)));
+        assert_eq!(block_on(kill.recv()), Ok(true));
+        assert_eq!(kill.try_recv(), Ok(Some(true)));
+        assert_eq!(kill.try_recv(), Err(BroadcastRecvError::Closed));
+
+        let (tx, rx) = oneshot::<u8>();
+        drop(rx);
+        assert_eq!(tx.send(1), Err(1));
+    }
+}
@@ -3,7 +3,9 @@
 //! High-performance architecture for CPU core binding to achieve
 //! maximum single-core performance for trading threads.
 
-use tracing::{info, warn};
+use std::io;
+use std::thread::JoinHandle;
+use tracing::{debug, info, warn};
 
 /// Bind current thread to specific CPU core(s)
 /// 
@@ -89,10 +91,10 @@ pub fn set_performance_governor() -> Result<(), String> {
         for i in 0..cpu_count {
             let governor_path = format!("/sys/devices/system/cpu/cpu{i}/cpufreq/scaling_governor");
             
-            if Path::new(&governor_path).exists() {
-                if let Err(e) = fs::write(&governor_path, "performance") {
-                    errors.push(format!("CPU {i}: {e}"));
-                }
+            if Path::new(&governor_path).exists()
+                && let Err(e) = fs::write(&governor_path, "performance")
+            {
+                errors.push(format!("CPU {i}: {e}"));
             }
         }
         
@@ -112,6 +114,148 @@ pub fn set_performance_governor() -> Result<(), String> {
     }
 }
 
+/// CPUs isolated from the general scheduler (`isolcpus=`), if any
+pub fn get_isolated_cpus() -> Vec<usize> {
+    std::fs::read_to_string("/sys/devices/system/cpu/isolated")
+        .map(|s| parse_cpu_list(&s))
+        .unwrap_or_default()
+}
+
+/// Parse a kernel CPU list such as "2-4,7"
+pub fn parse_cpu_list(list: &str) -> Vec<usize> {
+    let mut cpus = Vec::new();
+    for part in list.trim().split(',').filter(|p| !p.is_empty()) {
+        match part.split_once('-') {
+            Some((start, end)) => {
+                if let (Ok(start), Ok(end)) = (start.parse::<usize>(), end.parse::<usize>()) {
+                    cpus.extend(start..=end);
+                }
+            }
+            None => {
+                if let Ok(cpu) = part.parse() {
+                    cpus.push(cpu);
+                }
+            }
+        }
+    }
+    cpus
+}
+
+/// Switch the current thread to SCHED_FIFO realtime scheduling (Linux only)
+/// 
+/// `priority` must be in 1..=99. Usually requires CAP_SYS_NICE or an
+/// appropriate RLIMIT_RTPRIO; callers should treat failure as non-fatal.
+pub fn set_realtime_priority(priority: u8) -> Result<(), String> {
+    if !(1..=99).contains(&priority) {
+        return Err(format!("Realtime priority {priority} out of range (1-99)"));
+    }
+    
+    #[cfg(target_os = "linux")]
+    {
+        let param = libc::sched_param { sched_priority: priority as libc::c_int };
+        // SAFETY: pthread_self() is always a valid handle for the calling thread
+        let rc = unsafe { libc::pthread_setschedparam(libc::pthread_self(), libc::SCHED_FIFO, &param) };
+        if rc == 0 {
+            info!("⚡ Thread running with SCHED_FIFO priority {}", priority);
+            Ok(())
+        } else {
+            Err(format!("SCHED_FIFO priority {priority} not permitted: {}", io::Error::from_raw_os_error(rc)))
+        }
+    }
+    
+    #[cfg(not(target_os = "linux"))]
+    {
+        Err("Realtime scheduling only supported on Linux".to_string())
+    }
+}
+
+/// Builder for latency-sensitive threads
+/// 
+/// Sets the thread name and stack size at spawn time, then pins the thread to
+/// a CPU core and applies realtime priority from inside the new thread before
+/// running the user closure. Affinity and priority failures are logged and the
+/// thread keeps running with default scheduling.
+/// 
+/// # Example
+/// ```rust
+/// use sriquant_core::cpu::ThreadBuilder;
+/// 
+/// let handle = ThreadBuilder::new("md-feed")
+///     .core(0)
+///     .stack_size(4 * 1024 * 1024)
+///     .spawn(|| 42)
+///     .unwrap();
+/// assert_eq!(handle.join().unwrap(), 42);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ThreadBuilder {
+    name: String,
+    core: Option<usize>,
+    realtime_priority: Option<u8>,
+    stack_size: Option<usize>,
+}
+
+impl ThreadBuilder {
+    /// Create a builder for a thread with the given name
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+    
+    /// Pin the thread to a CPU core
+    pub fn core(mut self, core: usize) -> Self {
+        self.core = Some(core);
+        self
+    }
+    
+    /// Request SCHED_FIFO with the given priority (1-99) where permitted
+    pub fn realtime_priority(mut self, priority: u8) -> Self {
+        self.realtime_priority = Some(priority);
+        self
+    }
+    
+    /// Set the thread stack size in bytes
+    pub fn stack_size(mut self, bytes: usize) -> Self {
+        self.stack_size = Some(bytes);
+        self
+    }
+    
+    /// Spawn the thread
+    pub fn spawn<F, T>(self, f: F) -> io::Result<JoinHandle<T>>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let mut builder = std::thread::Builder::new().name(self.name.clone());
+        if let Some(stack_size) = self.stack_size {
+            builder = builder.stack_size(stack_size);
+        }
+        
+        let name = self.name;
+        let core = self.core;
+        let priority = self.realtime_priority;
+        
+        builder.spawn(move || {
+            if let Some(core) = core
+                && let Err(e) = bind_to_cpu_set(core)
+            {
+                warn!("Thread {} could not bind to CPU core {}: {}", name, core, e);
+            }
+            
+            if let Some(priority) = priority
+                && let Err(e) = set_realtime_priority(priority)
+            {
+                warn!("Thread {} falling back to default scheduling: {}", name, e);
+            }
+            
+            debug!("🧵 Thread {} started (core: {:?}, rt priority: {:?})", name, core, priority);
+            f()
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -129,4 +273,27 @@ mod tests {
         let result = bind_to_cpu_set(cpu_count + 10);
         assert!(result.is_err());
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_thread_builder() {
+        let handle = ThreadBuilder::new("sriquant-test")
+            .core(0)
+            .stack_size(512 * 1024)
+            .spawn(|| std::thread::current().name().map(|n| n.to_string()))
+            .unwrap();
+        
+        assert_eq!(handle.join().unwrap().as_deref(), Some("sriquant-test"));
+    }
+    
+    #[test]
+    fn test_parse_cpu_list() {
+        assert_eq!(parse_cpu_list("2-4,7\n"), vec![2, 3, 4, 7]);
+        assert!(parse_cpu_list("\n").is_empty());
+    }
+    
+    #[test]
+    fn test_realtime_priority_range() {
+        assert!(set_realtime_priority(0).is_err());
+        assert!(set_realtime_priority(100).is_err());
+    }
+}
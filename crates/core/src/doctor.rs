@@ -0,0 +1,252 @@
+//! Startup latency self-test and environment report
+//!
+//! `doctor` measures the properties of the host that bound achievable
+//! latency — clock precision, timer overhead, CPU isolation — and collects
+//! them into a `DoctorReport` that compares each measurement against the
+//! crate's performance targets. Exchange adapters append venue checks
+//! (REST RTT, TLS handshake) to the same report.
+
+use crate::cpu::{get_cpu_count, get_isolated_cpus};
+use crate::memory::MemoryDiagnostics;
+use crate::timing::{nanos, PerfTimer};
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use tracing::{info, warn};
+
+/// Clock precision target in nanoseconds
+pub const CLOCK_PRECISION_TARGET_NANOS: u64 = 100;
+/// Timer overhead target in nanoseconds
+pub const TIMER_OVERHEAD_TARGET_NANOS: u64 = 50;
+
+/// Outcome of a single check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+    Skipped,
+}
+
+impl fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            CheckStatus::Pass => "✅ PASS",
+            CheckStatus::Warn => "⚠️  WARN",
+            CheckStatus::Fail => "❌ FAIL",
+            CheckStatus::Skipped => "⏭️  SKIP",
+        };
+        write!(f, "{label}")
+    }
+}
+
+/// A single measured check and its target
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DoctorCheck {
+    pub name: String,
+    pub status: CheckStatus,
+    /// Measured value, in `unit`
+    pub value: Option<f64>,
+    /// Target value, in `unit`
+    pub target: Option<f64>,
+    pub unit: String,
+    pub detail: String,
+}
+
+impl DoctorCheck {
+    /// Check a measurement against an upper bound; above `fail_at` is a failure
+    pub fn measured(name: &str, value: f64, target: f64, fail_at: Option<f64>, unit: &str) -> Self {
+        let status = if value <= target {
+            CheckStatus::Pass
+        } else if fail_at.is_some_and(|limit| value > limit) {
+            CheckStatus::Fail
+        } else {
+            CheckStatus::Warn
+        };
+
+        Self {
+            name: name.to_string(),
+            status,
+            value: Some(value),
+            target: Some(target),
+            unit: unit.to_string(),
+            detail: String::new(),
+        }
+    }
+
+    /// A check without a numeric measurement
+    pub fn status(name: &str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status,
+            value: None,
+            target: None,
+            unit: String::new(),
+            detail: detail.into(),
+        }
+    }
+
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = detail.into();
+        self
+    }
+}
+
+impl fmt::Display for DoctorCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {:<20}", self.status, self.name)?;
+        if let Some(value) = self.value {
+            write!(f, " {:>10.1}{}", value, self.unit)?;
+        }
+        if let Some(target) = self.target {
+            write!(f, " (target ≤ {:.0}{})", target, self.unit)?;
+        }
+        if !self.detail.is_empty() {
+            write!(f, " — {}", self.detail)?;
+        }
+        Ok(())
+    }
+}
+
+/// Structured startup report
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DoctorReport {
+    pub generated_at: u64,
+    pub checks: Vec<DoctorCheck>,
+}
+
+impl DoctorReport {
+    pub fn new() -> Self {
+        Self {
+            generated_at: nanos(),
+            checks: Vec::new(),
+        }
+    }
+
+    /// Run all host-local checks
+    pub fn run_local() -> Self {
+        let mut report = Self::new();
+        report.add(check_clock_precision());
+        report.add(check_timer_overhead());
+        report.add(check_cpu_isolation());
+        report.add(check_memory_locking());
+        report
+    }
+
+    pub fn add(&mut self, check: DoctorCheck) {
+        self.checks.push(check);
+    }
+
+    pub fn check(&self, name: &str) -> Option<&DoctorCheck> {
+        self.checks.iter().find(|c| c.name == name)
+    }
+
+    /// Checks that did not pass
+    pub fn warnings(&self) -> impl Iterator<Item = &DoctorCheck> {
+        self.checks.iter().filter(|c| matches!(c.status, CheckStatus::Warn | CheckStatus::Fail))
+    }
+
+    pub fn has_failures(&self) -> bool {
+        self.checks.iter().any(|c| c.status == CheckStatus::Fail)
+    }
+
+    /// Log the report, one line per check
+    pub fn log(&self) {
+        info!("🩺 SriQuant doctor report");
+        for check in &self.checks {
+            match check.status {
+                CheckStatus::Warn | CheckStatus::Fail => warn!("   {}", check),
+                _ => info!("   {}", check),
+            }
+        }
+    }
+}
+
+impl fmt::Display for DoctorReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "SriQuant doctor report")?;
+        for check in &self.checks {
+            writeln!(f, "  {check}")?;
+        }
+        let issues = self.warnings().count();
+        write!(f, "  {} checks, {} issues", self.checks.len(), issues)
+    }
+}
+
+/// Smallest observable non-zero step of `nanos()`
+pub fn measure_clock_precision(samples: usize) -> u64 {
+    let mut best = u64::MAX;
+    for _ in 0..samples {
+        let start = nanos();
+        let mut next = nanos();
+        while next == start {
+            next = nanos();
+        }
+        best = best.min(next - start);
+    }
+    best
+}
+
+/// Average cost of one `nanos()` call
+pub fn measure_timer_overhead(iterations: u64) -> f64 {
+    let start = nanos();
+    let mut sink = 0u64;
+    for _ in 0..iterations {
+        sink = sink.wrapping_add(std::hint::black_box(nanos()));
+    }
+    std::hint::black_box(sink);
+    (nanos() - start) as f64 / iterations.max(1) as f64
+}
+
+fn check_clock_precision() -> DoctorCheck {
+    let _timer = PerfTimer::start("doctor_clock_precision");
+    let precision = measure_clock_precision(1_000) as f64;
+    DoctorCheck::measured("clock_precision", precision, CLOCK_PRECISION_TARGET_NANOS as f64, Some(1_000.0), "ns")
+}
+
+fn check_timer_overhead() -> DoctorCheck {
+    let _timer = PerfTimer::start("doctor_timer_overhead");
+    let overhead = measure_timer_overhead(100_000);
+    DoctorCheck::measured("timer_overhead", overhead, TIMER_OVERHEAD_TARGET_NANOS as f64, Some(1_000.0), "ns")
+}
+
+fn check_cpu_isolation() -> DoctorCheck {
+    let isolated = get_isolated_cpus();
+    if isolated.is_empty() {
+        DoctorCheck::status(
+            "cpu_isolation",
+            CheckStatus::Warn,
+            format!("no isolated cores of {}; consider isolcpus= for trading threads", get_cpu_count()),
+        )
+    } else {
+        DoctorCheck::status("cpu_isolation", CheckStatus::Pass, format!("isolated cores: {isolated:?}"))
+    }
+}
+
+fn check_memory_locking() -> DoctorCheck {
+    let diag = MemoryDiagnostics::collect();
+    let status = if diag.memlock_unlimited { CheckStatus::Pass } else { CheckStatus::Warn };
+    DoctorCheck::status("memory_locking", status, diag.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_measured_check_status() {
+        assert_eq!(DoctorCheck::measured("x", 5.0, 10.0, Some(20.0), "ns").status, CheckStatus::Pass);
+        assert_eq!(DoctorCheck::measured("x", 15.0, 10.0, Some(20.0), "ns").status, CheckStatus::Warn);
+        assert_eq!(DoctorCheck::measured("x", 25.0, 10.0, Some(20.0), "ns").status, CheckStatus::Fail);
+        assert_eq!(DoctorCheck::measured("x", 25.0, 10.0, None, "ns").status, CheckStatus::Warn);
+    }
+
+    #[test]
+    fn test_local_report() {
+        let report = DoctorReport::run_local();
+        assert!(report.check("clock_precision").unwrap().value.unwrap() > 0.0);
+        assert!(report.check("timer_overhead").is_some());
+        assert!(report.check("cpu_isolation").is_some());
+        assert!(report.to_string().contains("checks"));
+    }
+}
@@ -2,6 +2,15 @@
 //! 
 //! Provides a Fixed structure that supports numerical values up to 
 //! 999999.999999999999, catering to the precision needs of financial calculations.
+//!
+//! The arithmetic operators follow `Decimal`: results are not clamped to the
+//! Fixed range, and they panic on division by zero or when the result
+//! exceeds `Decimal`'s own range (about 7.9e28). Use `checked_*` to get a
+//! `FixedError` for any result outside the Fixed range, or `saturating_*` to
+//! clamp to `Fixed::min()`/`Fixed::max()`.
+//!
+//! Parsing is range-checked except in `from_str_unbounded` and `serde_str`,
+//! which keep volumes and other amounts beyond the range exact.
 
 use rust_decimal::{Decimal, prelude::*};
 use serde::{Deserialize, Serialize};
@@ -66,6 +75,38 @@ impl Fixed {
         Self::from_decimal(decimal)
     }
     
+    /// Create a Fixed from a float at the given scale, rejecting precision loss
+    /// 
+    /// The float is rounded to `scale` decimal places and the conversion fails with
+    /// `FixedError::PrecisionLoss` if the rounding moved the value by more than
+    /// `tolerance`. Pass `Fixed::ZERO` to require an exact representation.
+    /// 
+    /// # Example
+    /// ```rust
+    /// use sriquant_core::fixed::Fixed;
+    /// 
+    /// let price = Fixed::try_from_f64(50000.12, 2, Fixed::ZERO).unwrap();
+    /// assert_eq!(price.to_string(), "50000.12");
+    /// 
+    /// // 0.123456789 cannot be stored at 8 decimal places without rounding
+    /// assert!(Fixed::try_from_f64(0.123456789, 8, Fixed::ZERO).is_err());
+    /// ```
+    pub fn try_from_f64(value: f64, scale: u32, tolerance: Fixed) -> Result<Self, FixedError> {
+        if !value.is_finite() {
+            return Err(FixedError::InvalidValue);
+        }
+        
+        let decimal = Decimal::from_f64(value)
+            .ok_or(FixedError::InvalidValue)?;
+        let rounded = decimal.round_dp(scale);
+        
+        if (decimal - rounded).abs() > tolerance.abs().value {
+            return Err(FixedError::PrecisionLoss);
+        }
+        
+        Self::from_decimal(rounded)
+    }
+    
     /// Create a Fixed from a string
     pub fn from_str_exact(s: &str) -> Result<Self, FixedError> {
         let decimal = Decimal::from_str(s)
@@ -73,6 +114,83 @@ impl Fixed {
         Self::from_decimal(decimal)
     }
     
+    /// Create a Fixed from a string, keeping values beyond the Fixed range
+    ///
+    /// For volumes, notionals and filter maxima, which routinely exceed
+    /// 999999 on low-priced assets. The value is kept exact, as `serde_str`
+    /// does, rather than rejected or clamped; only malformed input fails.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sriquant_core::fixed::Fixed;
+    ///
+    /// assert_eq!(Fixed::from_str_unbounded("1830452109.53").unwrap().to_string(), "1830452109.53");
+    /// assert!(Fixed::from_str_exact("1830452109.53").is_err());
+    /// ```
+    pub fn from_str_unbounded(s: &str) -> Result<Self, FixedError> {
+        Decimal::from_str(s)
+            .map(Fixed::from)
+            .map_err(|_| FixedError::InvalidValue)
+    }
+    
+    /// Parse an ASCII decimal such as `b"-0.00100000"` without going through `&str`
+    ///
+    /// Hand-rolled for market data, where every depth level carries a price and
+    /// a quantity: an optional sign, digits and at most one `.`. No exponents or
+    /// whitespace. Inputs with more than 28 digits take the `from_str_exact` path.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sriquant_core::fixed::Fixed;
+    ///
+    /// assert_eq!(Fixed::from_ascii(b"50000.10").unwrap(), Fixed::from_str_exact("50000.10").unwrap());
+    /// assert!(Fixed::from_ascii(b"1e5").is_err());
+    /// ```
+    pub fn from_ascii(bytes: &[u8]) -> Result<Self, FixedError> {
+        const MAX_DIGITS: usize = 28;
+        
+        let (negative, body) = match bytes.split_first() {
+            Some((b'-', rest)) => (true, rest),
+            Some((b'+', rest)) => (false, rest),
+            _ => (false, bytes),
+        };
+        
+        let mut mantissa: u128 = 0;
+        let mut digits = 0usize;
+        let mut scale = 0u32;
+        let mut seen_dot = false;
+        for &byte in body {
+            match byte {
+                b'0'..=b'9' => {
+                    digits += 1;
+                    if digits > MAX_DIGITS {
+                        let s = std::str::from_utf8(bytes).map_err(|_| FixedError::InvalidValue)?;
+                        return Self::from_str_exact(s);
+                    }
+                    mantissa = mantissa * 10 + (byte - b'0') as u128;
+                    if seen_dot {
+                        scale += 1;
+                    }
+                }
+                b'.' if !seen_dot => seen_dot = true,
+                _ => return Err(FixedError::InvalidValue),
+            }
+        }
+        if digits == 0 {
+            return Err(FixedError::InvalidValue);
+        }
+        
+        let signed = if negative { -(mantissa as i128) } else { mantissa as i128 };
+        Self::from_decimal(Decimal::from_i128_with_scale(signed, scale))
+    }
+    
+    /// Parse a batch of decimal strings, e.g. a column of kline closes
+    ///
+    /// Each value goes through `from_ascii`; the first invalid one fails the batch.
+    pub fn parse_many(values: &[&str]) -> Result<Vec<Self>, FixedError> {
+        values.iter().map(|s| Self::from_ascii(s.as_bytes())).collect()
+    }
+    
     /// Get the underlying Decimal value
     pub fn to_decimal(&self) -> Decimal {
         self.value
@@ -80,6 +198,14 @@ impl Fixed {
     
     /// Convert to f64 (may lose precision)
     pub fn to_f64(&self) -> f64 {
+        self.to_f64_lossy()
+    }
+    
+    /// Convert to f64, explicitly accepting binary floating-point rounding
+    /// 
+    /// Prefer this over `to_f64` at analytics boundaries so precision loss is
+    /// visible at the call site.
+    pub fn to_f64_lossy(&self) -> f64 {
         self.value.to_f64().unwrap_or(0.0)
     }
     
@@ -129,6 +255,82 @@ impl Fixed {
         }
     }
     
+    /// Map an unchecked `Decimal` result into the Fixed range
+    fn checked(value: Option<Decimal>, negative: bool) -> Result<Self, FixedError> {
+        match value {
+            Some(value) if value > Self::max().value => Err(FixedError::Overflow),
+            Some(value) if value < Self::min().value => Err(FixedError::Underflow),
+            Some(value) => Ok(Fixed { value }),
+            None if negative => Err(FixedError::Underflow),
+            None => Err(FixedError::Overflow),
+        }
+    }
+    
+    /// Add, failing with `Overflow`/`Underflow` outside the Fixed range
+    pub fn checked_add(self, rhs: Self) -> Result<Self, FixedError> {
+        Self::checked(self.value.checked_add(rhs.value), rhs.is_negative())
+    }
+    
+    /// Subtract, failing with `Overflow`/`Underflow` outside the Fixed range
+    pub fn checked_sub(self, rhs: Self) -> Result<Self, FixedError> {
+        Self::checked(self.value.checked_sub(rhs.value), rhs.is_positive())
+    }
+    
+    /// Multiply, failing with `Overflow`/`Underflow` outside the Fixed range
+    ///
+    /// # Example
+    /// ```rust
+    /// use sriquant_core::fixed::{Fixed, FixedError};
+    ///
+    /// let notional = Fixed::from_i64(1_000).unwrap().checked_mul(Fixed::from_i64(60_000).unwrap());
+    /// assert_eq!(notional, Err(FixedError::Overflow));
+    /// ```
+    pub fn checked_mul(self, rhs: Self) -> Result<Self, FixedError> {
+        Self::checked(self.value.checked_mul(rhs.value), self.is_negative() != rhs.is_negative())
+    }
+    
+    /// Divide, failing with `DivisionByZero`, or `Overflow`/`Underflow` outside the Fixed range
+    pub fn checked_div(self, rhs: Self) -> Result<Self, FixedError> {
+        if rhs.is_zero() {
+            return Err(FixedError::DivisionByZero);
+        }
+        Self::checked(self.value.checked_div(rhs.value), self.is_negative() != rhs.is_negative())
+    }
+    
+    fn saturate(result: Result<Self, FixedError>) -> Self {
+        match result {
+            Ok(value) => value,
+            Err(FixedError::Underflow) => Self::min(),
+            Err(_) => Self::max(),
+        }
+    }
+    
+    /// Add, clamping to `Fixed::min()`/`Fixed::max()`
+    pub fn saturating_add(self, rhs: Self) -> Self {
+        Self::saturate(self.checked_add(rhs))
+    }
+    
+    /// Subtract, clamping to `Fixed::min()`/`Fixed::max()`
+    pub fn saturating_sub(self, rhs: Self) -> Self {
+        Self::saturate(self.checked_sub(rhs))
+    }
+    
+    /// Multiply, clamping to `Fixed::min()`/`Fixed::max()`
+    pub fn saturating_mul(self, rhs: Self) -> Self {
+        Self::saturate(self.checked_mul(rhs))
+    }
+    
+    /// Divide, clamping to `Fixed::min()`/`Fixed::max()`
+    ///
+    /// Division by zero saturates towards the dividend's sign; `0 / 0` is zero.
+    pub fn saturating_div(self, rhs: Self) -> Self {
+        match self.checked_div(rhs) {
+            Err(FixedError::DivisionByZero) if self.is_zero() => Self::ZERO,
+            Err(FixedError::DivisionByZero) if self.is_negative() => Self::min(),
+            result => Self::saturate(result),
+        }
+    }
+    
     /// Calculate percentage of another Fixed value
     pub fn percent_of(&self, other: Fixed) -> Result<Fixed, FixedError> {
         if other.is_zero() {
@@ -157,9 +359,19 @@ pub enum FixedError {
     DivisionByZero,
     #[error("Overflow in arithmetic operation")]
     Overflow,
+    #[error("Underflow in arithmetic operation (min: -999999.999999999999)")]
+    Underflow,
+    #[error("Precision loss exceeds tolerance")]
+    PrecisionLoss,
 }
 
 // Arithmetic implementations
+//
+// Operators don't enforce the Fixed range and panic where `Decimal` does
+// (division by zero, results beyond ~7.9e28). See `checked_*` and
+// `saturating_*` for deterministic failure modes.
+
+/// Unchecked: may leave the Fixed range, panics on `Decimal` overflow
 impl Add for Fixed {
     type Output = Fixed;
     
@@ -170,6 +382,7 @@ impl Add for Fixed {
     }
 }
 
+/// Unchecked: may leave the Fixed range, panics on `Decimal` overflow
 impl Sub for Fixed {
     type Output = Fixed;
     
@@ -180,6 +393,7 @@ impl Sub for Fixed {
     }
 }
 
+/// Unchecked: may leave the Fixed range, panics on `Decimal` overflow
 impl Mul for Fixed {
     type Output = Fixed;
     
@@ -190,6 +404,7 @@ impl Mul for Fixed {
     }
 }
 
+/// Unchecked: may leave the Fixed range, panics on division by zero
 impl Div for Fixed {
     type Output = Fixed;
     
@@ -251,6 +466,203 @@ impl From<Fixed> for Decimal {
     }
 }
 
+/// Serde helpers writing `Fixed` as a decimal string, as exchange APIs send it
+/// 
+/// For `#[serde(with = "sriquant_core::fixed::serde_str")]` on `Fixed` fields.
+/// Deserialization also takes JSON numbers. Values outside the Fixed range are
+/// kept as sent, like `From<Decimal>`: 24h quote volumes and meme coin
+/// quantities routinely exceed it, and rejecting them would fail the whole
+/// response.
+/// 
+/// # Example
+/// ```rust
+/// use sriquant_core::fixed::Fixed;
+/// use serde::{Deserialize, Serialize};
+/// 
+/// #[derive(Serialize, Deserialize)]
+/// struct Ticker {
+///     #[serde(with = "sriquant_core::fixed::serde_str")]
+///     price: Fixed,
+/// }
+/// 
+/// let ticker: Ticker = serde_json::from_str(r#"{"price":"50000.10"}"#).unwrap();
+/// assert_eq!(ticker.price, Fixed::from_str_exact("50000.10").unwrap());
+/// assert_eq!(serde_json::to_string(&ticker).unwrap(), r#"{"price":"50000.10"}"#);
+/// ```
+pub mod serde_str {
+    use super::Fixed;
+    use rust_decimal::Decimal;
+    use serde::de::{self, Visitor};
+    use serde::{Deserializer, Serializer};
+    use std::fmt;
+    use std::str::FromStr;
+    
+    pub fn serialize<S: Serializer>(value: &Fixed, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&value.value)
+    }
+    
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Fixed, D::Error> {
+        deserializer.deserialize_any(FixedVisitor)
+    }
+    
+    pub(super) struct FixedVisitor;
+    
+    impl Visitor<'_> for FixedVisitor {
+        type Value = Fixed;
+        
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a decimal string or number")
+        }
+        
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Fixed, E> {
+            Decimal::from_str(v)
+                .or_else(|_| Decimal::from_scientific(v))
+                .map(Fixed::from)
+                .map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+        }
+        
+        fn visit_i64<E: de::Error>(self, v: i64) -> Result<Fixed, E> {
+            Ok(Fixed::from(Decimal::from(v)))
+        }
+        
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<Fixed, E> {
+            Ok(Fixed::from(Decimal::from(v)))
+        }
+        
+        fn visit_f64<E: de::Error>(self, v: f64) -> Result<Fixed, E> {
+            Decimal::try_from(v)
+                .map(Fixed::from)
+                .map_err(|_| E::invalid_value(de::Unexpected::Float(v), &self))
+        }
+    }
+}
+
+/// Like `serde_str` for `Option<Fixed>`; `null` is `None`
+/// 
+/// Pair with `#[serde(default)]` for fields the API may omit.
+pub mod serde_str_option {
+    use super::Fixed;
+    use serde::de::{self, Visitor};
+    use serde::{Deserializer, Serializer};
+    use std::fmt;
+    
+    pub fn serialize<S: Serializer>(value: &Option<Fixed>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => super::serde_str::serialize(value, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+    
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Fixed>, D::Error> {
+        deserializer.deserialize_option(OptionVisitor)
+    }
+    
+    struct OptionVisitor;
+    
+    impl<'de> Visitor<'de> for OptionVisitor {
+        type Value = Option<Fixed>;
+        
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a decimal string, number or null")
+        }
+        
+        fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+        
+        fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+        
+        fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+            deserializer.deserialize_any(super::serde_str::FixedVisitor).map(Some)
+        }
+    }
+}
+
+/// Sum, min, max and VWAP over slices of `Fixed`
+///
+/// Exchange feeds pad every price and quantity of a symbol to the same number
+/// of decimals. When a slice shares one scale the helpers work on the raw
+/// `i128` mantissas in plain integer loops the compiler can unroll and
+/// vectorize, instead of rescaling and normalizing a `Decimal` per element.
+/// Mixed scales, or an integer result `Decimal` can't hold, take the naive
+/// path over the operators, so results always equal the naive fold.
+///
+/// # Example
+/// ```rust
+/// use sriquant_core::fixed::{bulk, Fixed};
+///
+/// let prices = Fixed::parse_many(&["100.50", "101.00", "99.75"]).unwrap();
+/// let quantities = Fixed::parse_many(&["2", "1", "1"]).unwrap();
+/// assert_eq!(bulk::sum(&prices), Fixed::from_str_exact("301.25").unwrap());
+/// assert_eq!(bulk::min(&prices), Fixed::from_str_exact("99.75").ok());
+/// assert_eq!(bulk::vwap(&prices, &quantities), Fixed::from_str_exact("100.4375").ok());
+/// ```
+pub mod bulk {
+    use super::Fixed;
+    use rust_decimal::Decimal;
+    
+    /// Scale shared by every value, `None` for an empty or mixed slice
+    fn uniform_scale(values: &[Fixed]) -> Option<u32> {
+        let scale = values.first()?.value.scale();
+        values.iter().all(|v| v.value.scale() == scale).then_some(scale)
+    }
+    
+    /// Sum of all values; zero for an empty slice
+    pub fn sum(values: &[Fixed]) -> Fixed {
+        // Mantissas are below 2^96, so fewer than 2^31 of them can't overflow i128
+        if let Some(scale) = uniform_scale(values).filter(|_| values.len() < 1 << 31) {
+            let total: i128 = values.iter().map(|v| v.value.mantissa()).sum();
+            if let Ok(value) = Decimal::try_from_i128_with_scale(total, scale) {
+                return Fixed { value };
+            }
+        }
+        values.iter().fold(Fixed::ZERO, |acc, v| acc + *v)
+    }
+    
+    pub fn min(values: &[Fixed]) -> Option<Fixed> {
+        match uniform_scale(values) {
+            Some(scale) => values.iter().map(|v| v.value.mantissa()).min()
+                .map(|m| Fixed { value: Decimal::from_i128_with_scale(m, scale) }),
+            None => values.iter().min().copied(),
+        }
+    }
+    
+    pub fn max(values: &[Fixed]) -> Option<Fixed> {
+        match uniform_scale(values) {
+            Some(scale) => values.iter().map(|v| v.value.mantissa()).max()
+                .map(|m| Fixed { value: Decimal::from_i128_with_scale(m, scale) }),
+            None => values.iter().max().copied(),
+        }
+    }
+    
+    /// Quantity-weighted average price
+    ///
+    /// `None` when the slices differ in length or the total quantity is zero.
+    pub fn vwap(prices: &[Fixed], quantities: &[Fixed]) -> Option<Fixed> {
+        if prices.len() != quantities.len() {
+            return None;
+        }
+        let quantity = sum(quantities);
+        if quantity.is_zero() {
+            return None;
+        }
+        let notional = notional(prices, quantities)
+            .unwrap_or_else(|| prices.iter().zip(quantities).fold(Fixed::ZERO, |acc, (p, q)| acc + *p * *q));
+        Some(notional / quantity)
+    }
+    
+    /// Σ price × quantity on mantissas, `None` when the scales are mixed or it overflows
+    fn notional(prices: &[Fixed], quantities: &[Fixed]) -> Option<Fixed> {
+        let scale = uniform_scale(prices)? + uniform_scale(quantities)?;
+        let total = prices.iter().zip(quantities).try_fold(0i128, |acc, (p, q)| {
+            acc.checked_add(p.value.mantissa().checked_mul(q.value.mantissa())?)
+        })?;
+        Decimal::try_from_i128_with_scale(total, scale).ok().map(|value| Fixed { value })
+    }
+}
+
 /// Convenience macro for creating Fixed values
 #[macro_export]
 macro_rules! fixed {
@@ -301,6 +713,116 @@ mod tests {
         assert_eq!(result.to_string(), "110.00");
     }
     
+    #[test]
+    fn test_fixed_f64_conversion() {
+        let exact = Fixed::try_from_f64(50000.12, 2, Fixed::ZERO).unwrap();
+        assert_eq!(exact, Fixed::from_str_exact("50000.12").unwrap());
+        assert_eq!(exact.to_f64_lossy(), 50000.12);
+        
+        let lossy = Fixed::try_from_f64(0.123456789, 8, Fixed::ZERO);
+        assert_eq!(lossy, Err(FixedError::PrecisionLoss));
+        
+        let tolerated = Fixed::try_from_f64(0.123456789, 8, Fixed::from_str_exact("0.00000001").unwrap());
+        assert_eq!(tolerated.unwrap().to_string(), "0.12345679");
+        
+        assert_eq!(Fixed::try_from_f64(f64::NAN, 8, Fixed::ZERO), Err(FixedError::InvalidValue));
+        assert_eq!(Fixed::try_from_f64(f64::INFINITY, 8, Fixed::ZERO), Err(FixedError::InvalidValue));
+        assert_eq!(Fixed::try_from_f64(2_000_000.0, 2, Fixed::ZERO), Err(FixedError::OutOfRange));
+    }
+    
+    #[test]
+    fn test_fixed_checked_and_saturating() {
+        let big = Fixed::from_i64(999_999).unwrap();
+        let price = Fixed::from_i64(60_000).unwrap();
+        assert_eq!(big.checked_add(Fixed::ONE), Err(FixedError::Overflow));
+        assert_eq!(Fixed::min().checked_sub(Fixed::ONE), Err(FixedError::Underflow));
+        assert_eq!(big.checked_mul(price), Err(FixedError::Overflow));
+        assert_eq!((Fixed::ZERO - big).checked_mul(price), Err(FixedError::Underflow));
+        assert_eq!(price.checked_div(Fixed::ZERO), Err(FixedError::DivisionByZero));
+        assert_eq!(price.checked_div(Fixed::from_i64(4).unwrap()).unwrap(), Fixed::from_i64(15_000).unwrap());
+        
+        // Beyond Decimal's own range, where the operators would panic
+        let huge = Fixed::from(Decimal::MAX);
+        assert_eq!(huge.checked_mul(huge), Err(FixedError::Overflow));
+        assert_eq!(huge.saturating_add(huge), Fixed::max());
+        
+        assert_eq!(big.saturating_mul(price), Fixed::max());
+        assert_eq!(Fixed::min().saturating_sub(price), Fixed::min());
+        assert_eq!((Fixed::ZERO - price).saturating_div(Fixed::ZERO), Fixed::min());
+        assert_eq!(Fixed::ZERO.saturating_div(Fixed::ZERO), Fixed::ZERO);
+        assert_eq!(price.saturating_sub(Fixed::ONE), Fixed::from_i64(59_999).unwrap());
+    }
+    
+    #[test]
+    fn test_fixed_from_ascii() {
+        for input in ["0", "-0.00100000", "50000.10", "+7", "12.", ".5", "999999.999999999999", "0.0000000000000000000000000001"] {
+            assert_eq!(Fixed::from_ascii(input.as_bytes()), Fixed::from_str_exact(input), "{input}");
+        }
+        // More digits than the fast path holds
+        assert_eq!(Fixed::from_ascii(b"1.00000000000000000000000000000").unwrap(), Fixed::ONE);
+        
+        for input in ["", "-", ".", "1.2.3", "1e5", " 1", "0x10", "1,5"] {
+            assert_eq!(Fixed::from_ascii(input.as_bytes()), Err(FixedError::InvalidValue), "{input:?}");
+        }
+        assert_eq!(Fixed::from_ascii(b"1000000"), Err(FixedError::OutOfRange));
+    }
+    
+    #[test]
+    fn test_fixed_from_str_unbounded() {
+        assert_eq!(Fixed::from_str_unbounded("0.5"), Fixed::from_str_exact("0.5"));
+        let volume = Fixed::from_str_unbounded("98765432109.12345678").unwrap();
+        assert_eq!(volume.to_string(), "98765432109.12345678");
+        assert!(volume > Fixed::max());
+        assert_eq!(Fixed::from_str_unbounded("1e5"), Err(FixedError::InvalidValue));
+    }
+    
+    #[test]
+    fn test_fixed_serde_str() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Level {
+            #[serde(with = "serde_str")]
+            price: Fixed,
+            #[serde(with = "serde_str_option", default)]
+            stop: Option<Fixed>,
+        }
+        
+        let level: Level = serde_json::from_str(r#"{"price":"0.00100000","stop":12.5}"#).unwrap();
+        assert_eq!(level, Level { price: Fixed::from_str_exact("0.001").unwrap(), stop: Some(Fixed::from_str_exact("12.5").unwrap()) });
+        assert_eq!(serde_json::to_string(&level).unwrap(), r#"{"price":"0.00100000","stop":"12.5"}"#);
+        
+        // Quote volumes beyond the Fixed range survive; garbage doesn't
+        let volume: Level = serde_json::from_str(r#"{"price":"1830452109.53","stop":null}"#).unwrap();
+        assert_eq!((volume.price.to_string().as_str(), volume.stop), ("1830452109.53", None));
+        assert!(serde_json::from_str::<Level>(r#"{"price":"abc"}"#).is_err());
+        assert_eq!(serde_json::from_str::<Level>(r#"{"price":1}"#).unwrap().stop, None);
+    }
+    
+    #[test]
+    fn test_fixed_bulk_matches_naive() {
+        let uniform = Fixed::parse_many(&["43127.45000000", "43127.46000000", "-0.01000000", "43126.99000000"]).unwrap();
+        let mixed = Fixed::parse_many(&["1.5", "0.25", "-3", "2.125"]).unwrap();
+        assert!(Fixed::parse_many(&["1", "x"]).is_err());
+        
+        for values in [&uniform, &mixed] {
+            assert_eq!(bulk::sum(values), values.iter().fold(Fixed::ZERO, |acc, v| acc + *v));
+            assert_eq!(bulk::min(values), values.iter().min().copied());
+            assert_eq!(bulk::max(values), values.iter().max().copied());
+        }
+        assert_eq!(bulk::sum(&[]), Fixed::ZERO);
+        assert_eq!(bulk::max(&[]), None);
+        
+        let quantities = Fixed::parse_many(&["0.5", "1.5", "2", "1"]).unwrap();
+        let naive = uniform.iter().zip(&quantities).fold(Fixed::ZERO, |acc, (p, q)| acc + *p * *q) / bulk::sum(&quantities);
+        assert_eq!(bulk::vwap(&uniform, &quantities), Some(naive));
+        assert_eq!(bulk::vwap(&uniform, &quantities[..2]), None);
+        assert_eq!(bulk::vwap(&uniform, &[Fixed::ZERO; 4]), None);
+        
+        // Scales adding past 28 fall back to Decimal arithmetic
+        let prices = Fixed::parse_many(&["2.00000000000000000000", "4.00000000000000000000"]).unwrap();
+        let quantities = Fixed::parse_many(&["1.0000000000", "3.0000000000"]).unwrap();
+        assert_eq!(bulk::vwap(&prices, &quantities), Fixed::from_str_exact("3.5").ok());
+    }
+    
     #[test]
     fn test_fixed_macro() {
         let f = fixed!(123.456);
@@ -8,9 +8,12 @@
 //! 2. **CPU binding** - Dedicated CPU cores for trading threads
 //! 3. **Nanosecond precision timing** - 7ns latency, 0.3ns precision
 //! 4. **Fixed-point arithmetic** - Exact decimal calculations
-//! 5. **Lock-free communication** - Ringbuf for inter-thread messaging
+//! 5. **Lock-free communication** - Ringbuf and cross-runtime channels for inter-thread messaging
 //! 6. **Unified logging** - ftlog for consistent logging
 //! 7. **Efficient ID generation** - nanoid for unique identifiers
+//! 8. **Fault-free hot memory** - mlockall and hugepage-backed buffer arenas
+//! 9. **Offline metrics** - Timestamped counter/histogram snapshots on disk
+//! 10. **No false sharing** - Cross-core hot fields padded to their own cache line
 
 pub mod runtime;
 pub mod timing;
@@ -18,13 +21,19 @@ pub mod fixed;
 pub mod logging;
 pub mod id_gen;
 pub mod cpu;
+pub mod memory;
+pub mod doctor;
+pub mod metrics;
+pub mod channel;
+pub mod cache;
 
 // Re-export commonly used items
 pub use runtime::SriQuantRuntime;
-pub use timing::{nanos, PerfTimer, Timestamp};
+pub use timing::{nanos, ClockJump, ClockWatch, PerfTimer, Timestamp};
 pub use fixed::Fixed;
 pub use logging::init_logging;
 pub use id_gen::{generate_id, OrderId, TradeId};
+pub use cache::{CachePadded, CACHE_LINE_SIZE};
 
 /// Prelude module for convenient imports
 pub mod prelude {
@@ -33,7 +42,7 @@ pub mod prelude {
     pub use crate::fixed::Fixed;
     pub use crate::id_gen::{generate_id, OrderId, TradeId, generate_id_with_prefix, idgen_next_id};
     pub use crate::logging::init_logging;
-    pub use crate::cpu::{bind_to_cpu_set, get_cpu_count};
+    pub use crate::cpu::{bind_to_cpu_set, get_cpu_count, ThreadBuilder};
     
     // Common external types
     pub use monoio;
@@ -0,0 +1,513 @@
+//! Memory locking and hugepage-backed arenas for hot buffers
+//!
+//! Page faults in the middle of a trading session cost tens of microseconds.
+//! This module lets hot-path components lock the process address space into
+//! RAM (`mlockall`) and pre-fault their ring buffers and buffer pools in an
+//! arena backed by hugepages where the host allows it. Every step degrades
+//! gracefully: missing permissions or hugepages fall back to regular pages
+//! with a warning and a diagnostic explaining what to change.
+
+use std::fmt;
+use tracing::{info, warn};
+
+/// Page backing actually obtained for an arena
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArenaBacking {
+    /// Explicit hugepages (MAP_HUGETLB)
+    HugePages,
+    /// Regular mapping with transparent hugepages requested via madvise
+    TransparentHugePages,
+    /// Regular pages
+    Regular,
+}
+
+/// Options for hot-buffer arenas
+#[derive(Debug, Clone)]
+pub struct MemoryOptions {
+    /// Try explicit hugepages before falling back
+    pub use_hugepages: bool,
+    /// Lock the arena into RAM
+    pub lock: bool,
+    /// Touch every page up front so no faults happen later
+    pub prefault: bool,
+}
+
+impl Default for MemoryOptions {
+    fn default() -> Self {
+        Self {
+            use_hugepages: true,
+            lock: true,
+            prefault: true,
+        }
+    }
+}
+
+impl MemoryOptions {
+    /// Plain heap-like memory with no locking or hugepages
+    pub fn regular() -> Self {
+        Self {
+            use_hugepages: false,
+            lock: false,
+            prefault: false,
+        }
+    }
+
+    pub fn with_hugepages(mut self, enabled: bool) -> Self {
+        self.use_hugepages = enabled;
+        self
+    }
+
+    pub fn with_lock(mut self, enabled: bool) -> Self {
+        self.lock = enabled;
+        self
+    }
+
+    pub fn with_prefault(mut self, enabled: bool) -> Self {
+        self.prefault = enabled;
+        self
+    }
+}
+
+/// Lock all current and future pages of the process into RAM
+///
+/// Requires CAP_IPC_LOCK or a sufficient RLIMIT_MEMLOCK. Callers should treat
+/// failure as non-fatal and surface `MemoryDiagnostics` to the operator.
+pub fn lock_all_memory() -> Result<(), String> {
+    #[cfg(target_os = "linux")]
+    {
+        // SAFETY: mlockall has no memory-safety preconditions
+        let rc = unsafe { libc::mlockall(libc::MCL_CURRENT | libc::MCL_FUTURE) };
+        if rc == 0 {
+            info!("🔒 Process memory locked into RAM");
+            Ok(())
+        } else {
+            Err(format!("mlockall failed: {} (raise RLIMIT_MEMLOCK or grant CAP_IPC_LOCK)", std::io::Error::last_os_error()))
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        Err("Memory locking only supported on Linux".to_string())
+    }
+}
+
+/// Undo `lock_all_memory`
+pub fn unlock_all_memory() -> Result<(), String> {
+    #[cfg(target_os = "linux")]
+    {
+        // SAFETY: munlockall has no memory-safety preconditions
+        let rc = unsafe { libc::munlockall() };
+        if rc == 0 {
+            Ok(())
+        } else {
+            Err(format!("munlockall failed: {}", std::io::Error::last_os_error()))
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        Err("Memory locking only supported on Linux".to_string())
+    }
+}
+
+/// Host capabilities relevant to memory locking and hugepages
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryDiagnostics {
+    /// Soft RLIMIT_MEMLOCK in bytes (`None` if unlimited or unknown)
+    pub memlock_limit_bytes: Option<u64>,
+    pub memlock_unlimited: bool,
+    pub hugepages_total: u64,
+    pub hugepages_free: u64,
+    pub hugepage_size_bytes: u64,
+    /// Transparent hugepage mode (e.g. "madvise")
+    pub thp_mode: Option<String>,
+}
+
+impl MemoryDiagnostics {
+    /// Inspect the current host
+    pub fn collect() -> Self {
+        let mut diag = Self::default();
+
+        #[cfg(target_os = "linux")]
+        {
+            let mut limit = libc::rlimit { rlim_cur: 0, rlim_max: 0 };
+            // SAFETY: `limit` is a valid out pointer for the duration of the call
+            if unsafe { libc::getrlimit(libc::RLIMIT_MEMLOCK, &mut limit) } == 0 {
+                if limit.rlim_cur == libc::RLIM_INFINITY {
+                    diag.memlock_unlimited = true;
+                } else {
+                    diag.memlock_limit_bytes = Some(limit.rlim_cur);
+                }
+            }
+
+            if let Ok(meminfo) = std::fs::read_to_string("/proc/meminfo") {
+                diag.apply_meminfo(&meminfo);
+            }
+
+            diag.thp_mode = std::fs::read_to_string("/sys/kernel/mm/transparent_hugepage/enabled")
+                .ok()
+                .and_then(|s| parse_thp_mode(&s));
+        }
+
+        diag
+    }
+
+    fn apply_meminfo(&mut self, meminfo: &str) {
+        for line in meminfo.lines() {
+            let mut parts = line.split_whitespace();
+            let (Some(key), Some(value)) = (parts.next(), parts.next()) else { continue };
+            let Ok(value) = value.parse::<u64>() else { continue };
+            match key {
+                "HugePages_Total:" => self.hugepages_total = value,
+                "HugePages_Free:" => self.hugepages_free = value,
+                "Hugepagesize:" => self.hugepage_size_bytes = value * 1024,
+                _ => {}
+            }
+        }
+    }
+
+    /// Whether `bytes` could be locked under the current limit
+    pub fn can_lock(&self, bytes: u64) -> bool {
+        self.memlock_unlimited || self.memlock_limit_bytes.is_some_and(|limit| bytes <= limit)
+    }
+
+    /// Whether `bytes` of explicit hugepages are available
+    pub fn hugepages_available(&self, bytes: u64) -> bool {
+        self.hugepage_size_bytes > 0 && self.hugepages_free * self.hugepage_size_bytes >= bytes
+    }
+
+    /// Actionable hints for missing capabilities
+    pub fn recommendations(&self) -> Vec<String> {
+        let mut hints = Vec::new();
+        if !self.memlock_unlimited {
+            hints.push("Set `ulimit -l unlimited` (or LimitMEMLOCK=infinity) to allow mlockall".to_string());
+        }
+        if self.hugepages_free == 0 {
+            hints.push("Reserve hugepages via /proc/sys/vm/nr_hugepages for hot buffers".to_string());
+        }
+        if self.thp_mode.as_deref() == Some("never") {
+            hints.push("Transparent hugepages are disabled; set THP to `madvise`".to_string());
+        }
+        hints
+    }
+}
+
+impl fmt::Display for MemoryDiagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let memlock = if self.memlock_unlimited {
+            "unlimited".to_string()
+        } else {
+            self.memlock_limit_bytes.map_or("unknown".to_string(), |b| format!("{} KiB", b / 1024))
+        };
+        write!(
+            f,
+            "memlock: {}, hugepages: {}/{} free ({} KiB), THP: {}",
+            memlock,
+            self.hugepages_free,
+            self.hugepages_total,
+            self.hugepage_size_bytes / 1024,
+            self.thp_mode.as_deref().unwrap_or("unknown"),
+        )
+    }
+}
+
+/// Extract the selected mode from e.g. "always [madvise] never"
+fn parse_thp_mode(contents: &str) -> Option<String> {
+    let start = contents.find('[')?;
+    let end = contents[start..].find(']')? + start;
+    Some(contents[start + 1..end].to_string())
+}
+
+/// A contiguous, page-aligned memory region for hot buffers
+///
+/// The region is zero-initialized and stays mapped for the arena's lifetime.
+pub struct HotArena {
+    ptr: *mut u8,
+    len: usize,
+    mapped_len: usize,
+    backing: ArenaBacking,
+    locked: bool,
+}
+
+// SAFETY: the arena exclusively owns its mapping; access goes through &/&mut self
+unsafe impl Send for HotArena {}
+unsafe impl Sync for HotArena {}
+
+impl HotArena {
+    /// Map an arena of at least `len` bytes, falling back as permissions allow
+    pub fn new(len: usize, options: &MemoryOptions) -> Result<Self, String> {
+        if len == 0 {
+            return Err("Arena length must be non-zero".to_string());
+        }
+
+        let mut arena = Self::map(len, options)?;
+
+        if options.lock {
+            arena.locked = arena.lock();
+        }
+        if options.prefault {
+            arena.prefault();
+        }
+
+        info!("🧠 Hot arena ready: {} bytes, {:?}, locked: {}", arena.len, arena.backing, arena.locked);
+        Ok(arena)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn map(len: usize, options: &MemoryOptions) -> Result<Self, String> {
+        const HUGEPAGE_SIZE: usize = 2 * 1024 * 1024;
+
+        if options.use_hugepages {
+            let mapped_len = len.div_ceil(HUGEPAGE_SIZE) * HUGEPAGE_SIZE;
+            if let Some(ptr) = mmap_anonymous(mapped_len, libc::MAP_HUGETLB) {
+                return Ok(Self { ptr, len, mapped_len, backing: ArenaBacking::HugePages, locked: false });
+            }
+            warn!("Hugepages unavailable for {} byte arena, falling back to regular pages", len);
+        }
+
+        let page = page_size();
+        let mapped_len = len.div_ceil(page) * page;
+        let ptr = mmap_anonymous(mapped_len, 0)
+            .ok_or_else(|| format!("mmap of {} bytes failed: {}", mapped_len, std::io::Error::last_os_error()))?;
+
+        let mut backing = ArenaBacking::Regular;
+        // SAFETY: ptr/mapped_len describe the mapping created above
+        if options.use_hugepages && unsafe { libc::madvise(ptr.cast(), mapped_len, libc::MADV_HUGEPAGE) } == 0 {
+            backing = ArenaBacking::TransparentHugePages;
+        }
+
+        Ok(Self { ptr, len, mapped_len, backing, locked: false })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn map(len: usize, _options: &MemoryOptions) -> Result<Self, String> {
+        let layout = std::alloc::Layout::from_size_align(len, 4096).map_err(|e| e.to_string())?;
+        // SAFETY: layout has non-zero size
+        let ptr = unsafe { std::alloc::alloc_zeroed(layout) };
+        if ptr.is_null() {
+            return Err(format!("Allocation of {} bytes failed", len));
+        }
+        Ok(Self { ptr, len, mapped_len: len, backing: ArenaBacking::Regular, locked: false })
+    }
+
+    fn lock(&self) -> bool {
+        #[cfg(target_os = "linux")]
+        {
+            // SAFETY: ptr/mapped_len describe our own mapping
+            if unsafe { libc::mlock(self.ptr.cast(), self.mapped_len) } == 0 {
+                return true;
+            }
+            warn!(
+                "Could not lock {} byte arena: {} ({})",
+                self.mapped_len,
+                std::io::Error::last_os_error(),
+                MemoryDiagnostics::collect()
+            );
+        }
+        false
+    }
+
+    fn prefault(&mut self) {
+        #[cfg(target_os = "linux")]
+        let step = page_size();
+        #[cfg(not(target_os = "linux"))]
+        let step = 4096;
+
+        for offset in (0..self.mapped_len).step_by(step) {
+            // SAFETY: offset is within the mapping; volatile keeps the touch from being elided
+            unsafe { std::ptr::write_volatile(self.ptr.add(offset), 0) };
+        }
+    }
+
+    /// Usable length in bytes
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Page backing obtained
+    pub fn backing(&self) -> ArenaBacking {
+        self.backing
+    }
+
+    /// Whether the arena is locked into RAM
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        // SAFETY: ptr is valid for len initialized (zeroed) bytes while self lives
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        // SAFETY: ptr is valid for len bytes and &mut self guarantees exclusivity
+        unsafe { std::slice::from_raw_parts_mut(self.ptr, self.len) }
+    }
+}
+
+impl fmt::Debug for HotArena {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HotArena")
+            .field("len", &self.len)
+            .field("backing", &self.backing)
+            .field("locked", &self.locked)
+            .finish()
+    }
+}
+
+impl Drop for HotArena {
+    fn drop(&mut self) {
+        #[cfg(target_os = "linux")]
+        {
+            // SAFETY: ptr/mapped_len came from mmap and are unmapped exactly once
+            unsafe { libc::munmap(self.ptr.cast(), self.mapped_len) };
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            let layout = std::alloc::Layout::from_size_align(self.mapped_len, 4096).expect("valid layout");
+            // SAFETY: allocated in `map` with the same layout
+            unsafe { std::alloc::dealloc(self.ptr, layout) };
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn page_size() -> usize {
+    // SAFETY: sysconf has no memory-safety preconditions
+    let size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    if size > 0 { size as usize } else { 4096 }
+}
+
+#[cfg(target_os = "linux")]
+fn mmap_anonymous(len: usize, extra_flags: libc::c_int) -> Option<*mut u8> {
+    // SAFETY: anonymous private mapping with no address hint
+    let ptr = unsafe {
+        libc::mmap(
+            std::ptr::null_mut(),
+            len,
+            libc::PROT_READ | libc::PROT_WRITE,
+            libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | extra_flags,
+            -1,
+            0,
+        )
+    };
+    if ptr == libc::MAP_FAILED { None } else { Some(ptr.cast()) }
+}
+
+/// Fixed-size buffer pool carved from a `HotArena`
+///
+/// Buffers are addressed by index so the pool can hand them across
+/// components without allocation.
+#[derive(Debug)]
+pub struct BufferPool {
+    arena: HotArena,
+    buffer_size: usize,
+    free: Vec<usize>,
+}
+
+impl BufferPool {
+    /// Create a pool of `count` buffers of `buffer_size` bytes each
+    pub fn new(buffer_size: usize, count: usize, options: &MemoryOptions) -> Result<Self, String> {
+        let total = buffer_size
+            .checked_mul(count)
+            .ok_or_else(|| "Buffer pool size overflows".to_string())?;
+        let arena = HotArena::new(total, options)?;
+
+        Ok(Self {
+            arena,
+            buffer_size,
+            free: (0..count).rev().collect(),
+        })
+    }
+
+    /// Take a free buffer index
+    pub fn acquire(&mut self) -> Option<usize> {
+        self.free.pop()
+    }
+
+    /// Return a buffer to the pool
+    pub fn release(&mut self, index: usize) {
+        debug_assert!(index < self.capacity() && !self.free.contains(&index));
+        self.free.push(index);
+    }
+
+    pub fn buffer(&self, index: usize) -> &[u8] {
+        let start = index * self.buffer_size;
+        &self.arena.as_slice()[start..start + self.buffer_size]
+    }
+
+    pub fn buffer_mut(&mut self, index: usize) -> &mut [u8] {
+        let start = index * self.buffer_size;
+        &mut self.arena.as_mut_slice()[start..start + self.buffer_size]
+    }
+
+    pub fn buffer_size(&self) -> usize {
+        self.buffer_size
+    }
+
+    /// Total number of buffers
+    pub fn capacity(&self) -> usize {
+        self.arena.len() / self.buffer_size
+    }
+
+    /// Buffers currently free
+    pub fn available(&self) -> usize {
+        self.free.len()
+    }
+
+    pub fn arena(&self) -> &HotArena {
+        &self.arena
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arena_falls_back_and_is_usable() {
+        // Hugepages and locking may be unavailable in CI; the arena must still work
+        let mut arena = HotArena::new(10_000, &MemoryOptions::default()).unwrap();
+        assert_eq!(arena.len(), 10_000);
+        assert!(arena.as_slice().iter().all(|&b| b == 0));
+
+        arena.as_mut_slice()[9_999] = 7;
+        assert_eq!(arena.as_slice()[9_999], 7);
+
+        let regular = HotArena::new(4096, &MemoryOptions::regular()).unwrap();
+        assert_eq!(regular.backing(), ArenaBacking::Regular);
+        assert!(!regular.is_locked());
+    }
+
+    #[test]
+    fn test_buffer_pool() {
+        let mut pool = BufferPool::new(256, 4, &MemoryOptions::regular()).unwrap();
+        assert_eq!(pool.capacity(), 4);
+
+        let a = pool.acquire().unwrap();
+        let b = pool.acquire().unwrap();
+        pool.buffer_mut(a).fill(1);
+        assert!(pool.buffer(b).iter().all(|&x| x == 0));
+        assert_eq!(pool.available(), 2);
+
+        pool.release(a);
+        assert_eq!(pool.available(), 3);
+    }
+
+    #[test]
+    fn test_diagnostics_parsing() {
+        let mut diag = MemoryDiagnostics::default();
+        diag.apply_meminfo("HugePages_Total:      16\nHugePages_Free:        8\nHugepagesize:       2048 kB\n");
+        assert_eq!(diag.hugepages_free, 8);
+        assert_eq!(diag.hugepage_size_bytes, 2 * 1024 * 1024);
+        assert!(diag.hugepages_available(16 * 1024 * 1024));
+        assert!(!diag.hugepages_available(17 * 1024 * 1024));
+
+        assert_eq!(parse_thp_mode("always [madvise] never\n").as_deref(), Some("madvise"));
+    }
+}
@@ -0,0 +1,277 @@
+//! Persistent metrics snapshots
+//!
+//! Counters and latency histograms live in a shared `MetricsRegistry`. A
+//! `MetricsSnapshotWriter` periodically appends timestamped snapshots to a
+//! JSON-lines file (histograms stored as sparse log2 buckets), and
+//! `read_snapshots` loads them back for post-trade latency analysis without a
+//! live Prometheus stack.
+
+use crate::timing::nanos;
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tracing::debug;
+
+const BUCKETS: usize = 64;
+
+/// Latency histogram with power-of-two nanosecond buckets
+#[derive(Debug, Clone)]
+pub struct LatencyHistogram {
+    buckets: [u64; BUCKETS],
+    count: u64,
+    sum_nanos: u64,
+    min_nanos: u64,
+    max_nanos: u64,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self {
+            buckets: [0; BUCKETS],
+            count: 0,
+            sum_nanos: 0,
+            min_nanos: u64::MAX,
+            max_nanos: 0,
+        }
+    }
+}
+
+impl LatencyHistogram {
+    /// Bucket `i` holds values in `[2^i, 2^(i+1))`; zero goes in bucket 0
+    fn bucket_index(value: u64) -> usize {
+        (63 - value.max(1).leading_zeros()) as usize
+    }
+
+    pub fn record(&mut self, latency_nanos: u64) {
+        self.buckets[Self::bucket_index(latency_nanos)] += 1;
+        self.count += 1;
+        self.sum_nanos = self.sum_nanos.saturating_add(latency_nanos);
+        self.min_nanos = self.min_nanos.min(latency_nanos);
+        self.max_nanos = self.max_nanos.max(latency_nanos);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Compact form for persistence
+    pub fn snapshot(&self) -> HistogramSnapshot {
+        HistogramSnapshot {
+            count: self.count,
+            sum_nanos: self.sum_nanos,
+            min_nanos: if self.count == 0 { 0 } else { self.min_nanos },
+            max_nanos: self.max_nanos,
+            buckets: self
+                .buckets
+                .iter()
+                .enumerate()
+                .filter(|(_, n)| **n > 0)
+                .map(|(i, n)| (i as u8, *n))
+                .collect(),
+        }
+    }
+}
+
+/// Persisted histogram: sparse `(log2 bucket, count)` pairs plus summary stats
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistogramSnapshot {
+    pub count: u64,
+    pub sum_nanos: u64,
+    pub min_nanos: u64,
+    pub max_nanos: u64,
+    pub buckets: Vec<(u8, u64)>,
+}
+
+impl HistogramSnapshot {
+    pub fn mean_nanos(&self) -> f64 {
+        if self.count == 0 { 0.0 } else { self.sum_nanos as f64 / self.count as f64 }
+    }
+
+    /// Approximate quantile (`0.0..=1.0`), reported as the bucket's upper bound
+    /// clamped to the observed max
+    pub fn quantile_nanos(&self, q: f64) -> u64 {
+        if self.count == 0 {
+            return 0;
+        }
+        let target = ((q.clamp(0.0, 1.0) * self.count as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (bucket, n) in &self.buckets {
+            seen += n;
+            if seen >= target {
+                let upper = 1u64.checked_shl(*bucket as u32 + 1).map_or(u64::MAX, |v| v - 1);
+                return upper.min(self.max_nanos);
+            }
+        }
+        self.max_nanos
+    }
+}
+
+/// One timestamped metrics snapshot
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetricsSnapshot {
+    /// Snapshot time, nanos since epoch
+    pub ts: u64,
+    /// Cumulative counters
+    pub counters: BTreeMap<String, u64>,
+    /// Latency observed since the previous snapshot
+    pub histograms: BTreeMap<String, HistogramSnapshot>,
+}
+
+#[derive(Debug, Default)]
+struct RegistryState {
+    counters: BTreeMap<String, u64>,
+    histograms: BTreeMap<String, LatencyHistogram>,
+}
+
+/// Shared counters and latency histograms
+#[derive(Debug, Clone, Default)]
+pub struct MetricsRegistry {
+    state: Arc<Mutex<RegistryState>>,
+}
+
+impl MetricsRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, RegistryState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn increment(&self, name: &str, by: u64) {
+        *self.lock().counters.entry(name.to_string()).or_default() += by;
+    }
+
+    pub fn counter(&self, name: &str) -> u64 {
+        self.lock().counters.get(name).copied().unwrap_or(0)
+    }
+
+    pub fn record_latency(&self, name: &str, latency_nanos: u64) {
+        self.lock().histograms.entry(name.to_string()).or_default().record(latency_nanos);
+    }
+
+    /// Snapshot all metrics; histograms are reset so each snapshot covers one interval
+    pub fn take_snapshot(&self) -> MetricsSnapshot {
+        let mut state = self.lock();
+        let histograms = std::mem::take(&mut state.histograms)
+            .into_iter()
+            .map(|(name, h)| (name, h.snapshot()))
+            .collect();
+        MetricsSnapshot {
+            ts: nanos(),
+            counters: state.counters.clone(),
+            histograms,
+        }
+    }
+}
+
+/// Appends registry snapshots to a JSON-lines file on an interval
+pub struct MetricsSnapshotWriter {
+    registry: MetricsRegistry,
+    path: PathBuf,
+    writer: BufWriter<File>,
+    interval_nanos: u64,
+    last_flush: u64,
+}
+
+impl MetricsSnapshotWriter {
+    pub fn open(registry: MetricsRegistry, path: impl AsRef<Path>, interval_nanos: u64) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok(Self {
+            registry,
+            path,
+            writer: BufWriter::new(file),
+            interval_nanos,
+            last_flush: nanos(),
+        })
+    }
+
+    /// Write a snapshot if the interval has elapsed; returns whether one was written
+    pub fn flush_if_due(&mut self, now: u64) -> io::Result<bool> {
+        if now.saturating_sub(self.last_flush) < self.interval_nanos {
+            return Ok(false);
+        }
+        self.flush()?;
+        Ok(true)
+    }
+
+    /// Write a snapshot now
+    pub fn flush(&mut self) -> io::Result<()> {
+        let snapshot = self.registry.take_snapshot();
+        serde_json::to_writer(&mut self.writer, &snapshot)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()?;
+        self.last_flush = snapshot.ts;
+        debug!("📈 Metrics snapshot written to {}", self.path.display());
+        Ok(())
+    }
+}
+
+impl Drop for MetricsSnapshotWriter {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+/// Load every snapshot from a metrics file, in write order
+pub fn read_snapshots(path: impl AsRef<Path>) -> io::Result<Vec<MetricsSnapshot>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut snapshots = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        snapshots.push(serde_json::from_str(&line)?);
+    }
+    Ok(snapshots)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_quantiles() {
+        let mut hist = LatencyHistogram::default();
+        for latency in [100, 200, 300, 5_000] {
+            hist.record(latency);
+        }
+        let snap = hist.snapshot();
+        assert_eq!(snap.count, 4);
+        assert_eq!(snap.min_nanos, 100);
+        assert_eq!(snap.quantile_nanos(0.5), 255);
+        assert_eq!(snap.quantile_nanos(0.99), 5_000);
+        assert_eq!(snap.mean_nanos(), 1_400.0);
+    }
+
+    #[test]
+    fn test_snapshots_round_trip_through_file() {
+        let path = std::env::temp_dir().join(format!("sriquant_metrics_{}.jsonl", nanos()));
+        let registry = MetricsRegistry::new();
+        {
+            let mut writer = MetricsSnapshotWriter::open(registry.clone(), &path, u64::MAX).unwrap();
+            registry.increment("orders_sent", 2);
+            registry.record_latency("order_ack", 250_000);
+            assert!(!writer.flush_if_due(nanos()).unwrap());
+            writer.flush().unwrap();
+            registry.increment("orders_sent", 1);
+        }
+
+        let snapshots = read_snapshots(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(snapshots.len(), 2);
+        assert_eq!(snapshots[0].counters["orders_sent"], 2);
+        assert_eq!(snapshots[0].histograms["order_ack"].count, 1);
+        // Counters are cumulative, histograms cover a single interval
+        assert_eq!(snapshots[1].counters["orders_sent"], 3);
+        assert!(snapshots[1].histograms.is_empty());
+        assert!(snapshots[1].ts >= snapshots[0].ts);
+    }
+}
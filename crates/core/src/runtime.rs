@@ -7,7 +7,7 @@
 
 use monoio::{RuntimeBuilder, IoUringDriver};
 use tracing::{info, warn};
-use crate::cpu::bind_to_cpu_set;
+use crate::cpu::{bind_to_cpu_set, ThreadBuilder};
 
 /// High-performance trading runtime configuration
 #[derive(Debug, Clone)]
@@ -20,6 +20,8 @@ pub struct RuntimeConfig {
     pub enable_timing: bool,
     /// Runtime thread stack size
     pub stack_size: Option<usize>,
+    /// SCHED_FIFO priority (1-99) for spawned runtime threads, where permitted
+    pub realtime_priority: Option<u8>,
 }
 
 impl Default for RuntimeConfig {
@@ -29,6 +31,7 @@ impl Default for RuntimeConfig {
             thread_name: "sriquant-main".to_string(),
             enable_timing: true,
             stack_size: Some(2 * 1024 * 1024), // 2MB stack
+            realtime_priority: None,
         }
     }
 }
@@ -71,7 +74,10 @@ impl SriQuantRuntime {
     where
         F: std::future::Future,
     {
-        let mut runtime = RuntimeBuilder::<IoUringDriver>::new().build().expect("Failed to create runtime");
+        let mut runtime = RuntimeBuilder::<IoUringDriver>::new()
+            .enable_timer()
+            .build()
+            .expect("Failed to create runtime");
         runtime.block_on(future)
     }
     
@@ -82,12 +88,46 @@ impl SriQuantRuntime {
         Fut: std::future::Future,
     {
         info!("▶️  Starting SriQuant runtime");
-        let mut runtime = RuntimeBuilder::<IoUringDriver>::new().build().expect("Failed to create runtime");
+        let mut runtime = RuntimeBuilder::<IoUringDriver>::new()
+            .enable_timer()
+            .build()
+            .expect("Failed to create runtime");
         let result = runtime.block_on(f());
         info!("⏹️  SriQuant runtime stopped");
         result
     }
     
+    /// Run a runtime on a dedicated thread configured from `config`
+    /// 
+    /// The thread is named, pinned and prioritized via `ThreadBuilder`, so each
+    /// component of the topology gets deterministic placement.
+    pub fn spawn<F, Fut>(config: RuntimeConfig, f: F) -> std::io::Result<std::thread::JoinHandle<Fut::Output>>
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: std::future::Future,
+        Fut::Output: Send + 'static,
+    {
+        let mut builder = ThreadBuilder::new(config.thread_name.clone());
+        if let Some(core) = config.cpu_core {
+            builder = builder.core(core);
+        }
+        if let Some(priority) = config.realtime_priority {
+            builder = builder.realtime_priority(priority);
+        }
+        if let Some(stack_size) = config.stack_size {
+            builder = builder.stack_size(stack_size);
+        }
+        
+        builder.spawn(move || {
+            info!("🚀 SriQuant runtime thread {} started", config.thread_name);
+            let mut runtime = RuntimeBuilder::<IoUringDriver>::new()
+                .enable_timer()
+                .build()
+                .expect("Failed to create runtime");
+            runtime.block_on(f())
+        })
+    }
+    
     /// Get runtime configuration
     pub fn config(&self) -> &RuntimeConfig {
         &self.config
@@ -142,6 +182,7 @@ mod tests {
             thread_name: "test-runtime".to_string(),
             enable_timing: false,
             stack_size: None,
+            realtime_priority: None,
         };
         
         let runtime = SriQuantRuntime::with_config(config);
@@ -149,4 +190,19 @@ mod tests {
         assert_eq!(runtime.config().thread_name, "test-runtime");
         assert!(!runtime.config().enable_timing);
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_runtime_spawn_thread() {
+        let config = RuntimeConfig {
+            cpu_core: Some(0),
+            thread_name: "sriquant-spawned".to_string(),
+            ..Default::default()
+        };
+        
+        let handle = SriQuantRuntime::spawn(config, || async {
+            std::thread::current().name().map(|n| n.to_string())
+        }).unwrap();
+        
+        assert_eq!(handle.join().unwrap().as_deref(), Some("sriquant-spawned"));
+    }
+}
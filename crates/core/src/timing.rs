@@ -3,7 +3,7 @@
 //! Provides nanosecond-precision timestamps with 7ns latency and 0.3ns precision,
 //! essential for high-frequency trading strategies.
 
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use tracing;
@@ -84,6 +84,144 @@ pub fn system_nanos() -> u64 {
         .as_nanos() as u64
 }
 
+/// Nanoseconds since boot including time spent suspended, where the OS exposes it
+#[cfg(target_os = "linux")]
+pub fn boot_nanos() -> Option<u64> {
+    let mut ts = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+    let rc = unsafe { libc::clock_gettime(libc::CLOCK_BOOTTIME, &mut ts) };
+    (rc == 0).then(|| ts.tv_sec as u64 * 1_000_000_000 + ts.tv_nsec as u64)
+}
+
+/// Nanoseconds since boot including time spent suspended, where the OS exposes it
+#[cfg(not(target_os = "linux"))]
+pub fn boot_nanos() -> Option<u64> {
+    None
+}
+
+/// A discontinuity between the wall clock and the monotonic clock
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClockJump {
+    /// The wall clock was stepped (NTP step correction, manual change) by
+    /// `delta_ns`, positive forwards
+    WallStep { delta_ns: i64 },
+    /// The machine was suspended for `duration_ns`; the monotonic clock
+    /// stopped while the wall clock kept going. Detected through the boot
+    /// clock on Linux; elsewhere a suspend shows up as a forward `WallStep`
+    Suspend { duration_ns: u64 },
+    /// `gap_ns` passed on the monotonic clock between two checks, far more
+    /// than expected: a VM pause or migration, or a starved process
+    Stall { gap_ns: u64 },
+}
+
+impl ClockJump {
+    /// Whether timestamps taken before the jump can't be compared with those after
+    pub fn breaks_wall_time(&self) -> bool {
+        !matches!(self, ClockJump::Stall { .. })
+    }
+}
+
+impl std::fmt::Display for ClockJump {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClockJump::WallStep { delta_ns } => write!(f, "wall clock stepped {:+.3}ms", *delta_ns as f64 / 1e6),
+            ClockJump::Suspend { duration_ns } => write!(f, "suspended for {:.3}s", *duration_ns as f64 / 1e9),
+            ClockJump::Stall { gap_ns } => write!(f, "stalled for {:.3}s", *gap_ns as f64 / 1e9),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ClockSample {
+    mono: u64,
+    wall: u64,
+    boot: Option<u64>,
+}
+
+/// Detects clock jumps by comparing the wall, monotonic and boot clocks
+/// between periodic checks
+///
+/// Latency math and signed request timestamps assume the wall clock moves
+/// with the monotonic clock. Call `check` regularly (e.g. once a second from
+/// the event loop); it reports when that stopped holding since the last call.
+///
+/// ```rust
+/// use sriquant_core::timing::ClockWatch;
+///
+/// let mut watch = ClockWatch::new();
+/// assert_eq!(watch.check(), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ClockWatch {
+    anchor: Instant,
+    last: ClockSample,
+    step_threshold_ns: u64,
+    stall_threshold_ns: u64,
+}
+
+impl Default for ClockWatch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClockWatch {
+    pub fn new() -> Self {
+        let anchor = Instant::now();
+        Self {
+            anchor,
+            last: ClockSample { mono: 0, wall: system_nanos(), boot: boot_nanos() },
+            step_threshold_ns: 250_000_000,
+            stall_threshold_ns: 10_000_000_000,
+        }
+    }
+
+    /// Smallest wall clock step or suspend reported (default 250ms)
+    pub fn with_step_threshold(mut self, threshold: Duration) -> Self {
+        self.step_threshold_ns = threshold.as_nanos() as u64;
+        self
+    }
+
+    /// Longest expected monotonic gap between checks (default 10s)
+    pub fn with_stall_threshold(mut self, threshold: Duration) -> Self {
+        self.stall_threshold_ns = threshold.as_nanos() as u64;
+        self
+    }
+
+    /// Sample the clocks and report a jump since the previous check
+    pub fn check(&mut self) -> Option<ClockJump> {
+        let mono = self.anchor.elapsed().as_nanos() as u64;
+        self.observe(mono, system_nanos(), boot_nanos())
+    }
+
+    /// Compare a sample (monotonic, wall and optional boot nanos) with the previous one
+    pub fn observe(&mut self, mono: u64, wall: u64, boot: Option<u64>) -> Option<ClockJump> {
+        let last = std::mem::replace(&mut self.last, ClockSample { mono, wall, boot });
+        let mono_elapsed = mono.saturating_sub(last.mono);
+        let wall_elapsed = wall as i128 - last.wall as i128;
+
+        // Boot time keeps counting through a suspend, monotonic time doesn't
+        let real_elapsed = match (last.boot, boot) {
+            (Some(before), Some(after)) => after.saturating_sub(before).max(mono_elapsed),
+            _ => mono_elapsed,
+        };
+        let suspended = real_elapsed - mono_elapsed;
+        let step = wall_elapsed - real_elapsed as i128;
+
+        let jump = if step.unsigned_abs() > self.step_threshold_ns as u128 {
+            ClockJump::WallStep { delta_ns: step.clamp(i64::MIN as i128, i64::MAX as i128) as i64 }
+        } else if suspended > self.step_threshold_ns {
+            ClockJump::Suspend { duration_ns: suspended }
+        } else if mono_elapsed > self.stall_threshold_ns {
+            ClockJump::Stall { gap_ns: mono_elapsed }
+        } else {
+            return None;
+        };
+        tracing::warn!("⏰ Clock jump detected: {}", jump);
+        Some(jump)
+    }
+}
+
 /// Performance measurement utilities
 pub struct PerfTimer {
     start: Timestamp,
@@ -197,6 +335,25 @@ mod tests {
         assert!(elapsed.as_millis() < 1);
     }
     
+    #[test]
+    fn test_clock_watch_classifies_jumps() {
+        let sec = 1_000_000_000u64;
+        let mut watch = ClockWatch::new();
+        watch.observe(0, 1_000 * sec, Some(50 * sec));
+
+        assert_eq!(watch.observe(sec, 1_001 * sec, Some(51 * sec)), None);
+        // NTP stepped the wall clock back 2s
+        assert_eq!(watch.observe(2 * sec, 1_000 * sec, Some(52 * sec)), Some(ClockJump::WallStep { delta_ns: -2 * sec as i64 }));
+        // Laptop lid closed for an hour: wall and boot moved on, monotonic didn't
+        assert_eq!(watch.observe(3 * sec, 4_601 * sec, Some(3_653 * sec)), Some(ClockJump::Suspend { duration_ns: 3_600 * sec }));
+        assert_eq!(watch.observe(33 * sec, 4_631 * sec, Some(3_683 * sec)), Some(ClockJump::Stall { gap_ns: 30 * sec }));
+
+        // Without a boot clock the suspend reads as a forward step
+        let mut watch = ClockWatch::new();
+        watch.observe(0, 1_000 * sec, None);
+        assert_eq!(watch.observe(sec, 4_601 * sec, None), Some(ClockJump::WallStep { delta_ns: 3_600 * sec as i64 }));
+    }
+    
     #[test]
     fn test_perf_timer() {
         let timer = PerfTimer::start("test");
@@ -0,0 +1,293 @@
+//! Cost-basis and tax-lot accounting for spot holdings
+//!
+//! Trades from the journal are replayed into a `CostBasisLedger`. Buys open
+//! lots; sells close them under the selected `CostBasisMethod` and produce a
+//! `RealizedLot` per lot touched, so realized PnL can be reported the way a
+//! jurisdiction requires (pooled average cost, FIFO or LIFO).
+//!
+//! Commissions paid in the quote asset are added to cost on buys and taken
+//! from proceeds on sells; commissions paid in the base asset reduce the
+//! quantity received. Commissions in a third asset (e.g. BNB) are left out
+//! and should be expensed separately.
+
+use crate::journal::{Journal, TradeRecord};
+use crate::types::OrderSide;
+use sriquant_core::prelude::*;
+
+use std::collections::{BTreeMap, VecDeque};
+use tracing::{debug, warn};
+
+/// How sells are matched against open lots
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CostBasisMethod {
+    /// All holdings pooled at a weighted average cost
+    AverageCost,
+    /// Oldest lots are disposed first
+    Fifo,
+    /// Newest lots are disposed first
+    Lifo,
+}
+
+/// Quantity acquired in one buy (or the pool, under average cost)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaxLot {
+    pub symbol: String,
+    /// Opening trade; `None` for an average-cost pool
+    pub trade_id: Option<u64>,
+    pub acquired_at: u64,
+    /// Quantity still held
+    pub quantity: Fixed,
+    /// Cost per unit including quote-asset fees
+    pub unit_cost: Fixed,
+}
+
+/// Realized result of disposing (part of) one lot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RealizedLot {
+    pub symbol: String,
+    pub open_trade_id: Option<u64>,
+    pub close_trade_id: u64,
+    pub acquired_at: u64,
+    pub disposed_at: u64,
+    pub quantity: Fixed,
+    pub cost_basis: Fixed,
+    pub proceeds: Fixed,
+    pub pnl: Fixed,
+}
+
+impl RealizedLot {
+    /// Holding period in milliseconds, for short/long-term classification
+    pub fn holding_period_ms(&self) -> u64 {
+        self.disposed_at.saturating_sub(self.acquired_at)
+    }
+}
+
+/// Open lots and realized results per symbol
+#[derive(Debug, Clone)]
+pub struct CostBasisLedger {
+    method: CostBasisMethod,
+    lots: BTreeMap<String, VecDeque<TaxLot>>,
+    realized: Vec<RealizedLot>,
+    /// Sold quantity with no open lot to match (history before the backfill)
+    unmatched: BTreeMap<String, Fixed>,
+}
+
+impl CostBasisLedger {
+    pub fn new(method: CostBasisMethod) -> Self {
+        Self {
+            method,
+            lots: BTreeMap::new(),
+            realized: Vec::new(),
+            unmatched: BTreeMap::new(),
+        }
+    }
+
+    /// Replay every journaled trade in exchange time order
+    pub fn from_journal(method: CostBasisMethod, journal: &Journal) -> Self {
+        let mut trades: Vec<&TradeRecord> = journal.trades().collect();
+        trades.sort_by_key(|t| (t.time, t.trade_id));
+
+        let mut ledger = Self::new(method);
+        for trade in trades {
+            ledger.apply_trade(trade);
+        }
+        ledger
+    }
+
+    pub fn method(&self) -> CostBasisMethod {
+        self.method
+    }
+
+    /// Apply one trade; returns the lots it realized (empty for buys)
+    pub fn apply_trade(&mut self, trade: &TradeRecord) -> Vec<RealizedLot> {
+        let fee_in_quote = trade.symbol.ends_with(&trade.commission_asset);
+        let fee_in_base = !fee_in_quote && trade.symbol.starts_with(&trade.commission_asset);
+
+        match trade.side {
+            OrderSide::Buy => {
+                let quantity = if fee_in_base { trade.quantity - trade.commission } else { trade.quantity };
+                let cost = if fee_in_quote { trade.quote_quantity + trade.commission } else { trade.quote_quantity };
+                if quantity > Fixed::ZERO {
+                    self.open_lot(trade, quantity, cost / quantity);
+                }
+                Vec::new()
+            }
+            OrderSide::Sell => {
+                let proceeds = if fee_in_quote { trade.quote_quantity - trade.commission } else { trade.quote_quantity };
+                let realized = self.close_lots(trade, proceeds);
+                self.realized.extend(realized.iter().cloned());
+                realized
+            }
+        }
+    }
+
+    fn open_lot(&mut self, trade: &TradeRecord, quantity: Fixed, unit_cost: Fixed) {
+        let lots = self.lots.entry(trade.symbol.clone()).or_default();
+        match (self.method, lots.front_mut()) {
+            (CostBasisMethod::AverageCost, Some(pool)) => {
+                let total = pool.quantity + quantity;
+                pool.unit_cost = (pool.quantity * pool.unit_cost + quantity * unit_cost) / total;
+                pool.quantity = total;
+            }
+            (method, _) => lots.push_back(TaxLot {
+                symbol: trade.symbol.clone(),
+                trade_id: (method != CostBasisMethod::AverageCost).then_some(trade.trade_id),
+                acquired_at: trade.time,
+                quantity,
+                unit_cost,
+            }),
+        }
+    }
+
+    fn close_lots(&mut self, trade: &TradeRecord, proceeds: Fixed) -> Vec<RealizedLot> {
+        let mut realized = Vec::new();
+        if trade.quantity <= Fixed::ZERO {
+            return realized;
+        }
+        let unit_proceeds = proceeds / trade.quantity;
+        let mut remaining = trade.quantity;
+        let lots = self.lots.entry(trade.symbol.clone()).or_default();
+
+        while remaining > Fixed::ZERO {
+            let lot = match self.method {
+                CostBasisMethod::AverageCost | CostBasisMethod::Fifo => lots.front_mut(),
+                CostBasisMethod::Lifo => lots.back_mut(),
+            };
+            let Some(lot) = lot else { break };
+
+            let quantity = remaining.min(lot.quantity);
+            let cost_basis = quantity * lot.unit_cost;
+            let lot_proceeds = quantity * unit_proceeds;
+            realized.push(RealizedLot {
+                symbol: trade.symbol.clone(),
+                open_trade_id: lot.trade_id,
+                close_trade_id: trade.trade_id,
+                acquired_at: lot.acquired_at,
+                disposed_at: trade.time,
+                quantity,
+                cost_basis,
+                proceeds: lot_proceeds,
+                pnl: lot_proceeds - cost_basis,
+            });
+
+            lot.quantity -= quantity;
+            remaining -= quantity;
+            if lot.quantity.is_zero() {
+                match self.method {
+                    CostBasisMethod::Lifo => lots.pop_back(),
+                    _ => lots.pop_front(),
+                };
+            }
+        }
+
+        if remaining > Fixed::ZERO {
+            warn!("📒 {} sell {} exceeds open lots by {}", trade.symbol, trade.trade_id, remaining);
+            *self.unmatched.entry(trade.symbol.clone()).or_insert(Fixed::ZERO) += remaining;
+        }
+        debug!("📒 {} sell {} closed {} lots", trade.symbol, trade.trade_id, realized.len());
+        realized
+    }
+
+    /// Open lots for a symbol, oldest first
+    pub fn open_lots(&self, symbol: &str) -> impl Iterator<Item = &TaxLot> {
+        self.lots.get(symbol).into_iter().flatten()
+    }
+
+    /// Quantity held according to the ledger
+    pub fn position(&self, symbol: &str) -> Fixed {
+        self.open_lots(symbol).fold(Fixed::ZERO, |acc, lot| acc + lot.quantity)
+    }
+
+    /// Weighted average cost of the open lots
+    pub fn average_cost(&self, symbol: &str) -> Option<Fixed> {
+        let quantity = self.position(symbol);
+        if quantity <= Fixed::ZERO {
+            return None;
+        }
+        let cost = self.open_lots(symbol).fold(Fixed::ZERO, |acc, lot| acc + lot.quantity * lot.unit_cost);
+        Some(cost / quantity)
+    }
+
+    /// Every realized lot in disposal order
+    pub fn realized(&self) -> &[RealizedLot] {
+        &self.realized
+    }
+
+    pub fn realized_pnl(&self, symbol: &str) -> Fixed {
+        self.realized.iter().filter(|r| r.symbol == symbol).fold(Fixed::ZERO, |acc, r| acc + r.pnl)
+    }
+
+    /// Sold quantity that had no open lot to match
+    pub fn unmatched_quantity(&self, symbol: &str) -> Fixed {
+        self.unmatched.get(symbol).copied().unwrap_or(Fixed::ZERO)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testkit::fx;
+    use crate::journal::JournalEvent;
+
+    fn trade(id: u64, side: OrderSide, price: &str, qty: &str, fee: &str) -> TradeRecord {
+        TradeRecord {
+            trade_id: id,
+            order_id: id,
+            symbol: "BTCUSDT".to_string(),
+            side,
+            price: fx(price),
+            quantity: fx(qty),
+            quote_quantity: fx(price) * fx(qty),
+            commission: fx(fee),
+            commission_asset: "USDT".to_string(),
+            is_maker: true,
+            time: id * 1000,
+        }
+    }
+
+    fn journal() -> Journal {
+        let mut journal = Journal::new();
+        for t in [
+            trade(1, OrderSide::Buy, "100", "1", "0"),
+            trade(2, OrderSide::Buy, "200", "1", "0"),
+            trade(3, OrderSide::Sell, "300", "1.5", "0"),
+        ] {
+            journal.record("binance", JournalEvent::Trade(t));
+        }
+        journal
+    }
+
+    #[test]
+    fn test_methods_realize_different_lots() {
+        let fifo = CostBasisLedger::from_journal(CostBasisMethod::Fifo, &journal());
+        // 1 @ 100 + 0.5 @ 200 against 450 proceeds
+        assert_eq!(fifo.realized().len(), 2);
+        assert_eq!(fifo.realized_pnl("BTCUSDT"), fx("250"));
+        assert_eq!(fifo.open_lots("BTCUSDT").next().unwrap().trade_id, Some(2));
+
+        let lifo = CostBasisLedger::from_journal(CostBasisMethod::Lifo, &journal());
+        // 1 @ 200 + 0.5 @ 100
+        assert_eq!(lifo.realized_pnl("BTCUSDT"), fx("200"));
+        assert_eq!(lifo.average_cost("BTCUSDT"), Some(fx("100")));
+
+        let average = CostBasisLedger::from_journal(CostBasisMethod::AverageCost, &journal());
+        // 1.5 @ 150
+        assert_eq!(average.realized_pnl("BTCUSDT"), fx("225"));
+        assert_eq!(average.position("BTCUSDT"), fx("0.5"));
+        assert_eq!(average.realized()[0].open_trade_id, None);
+    }
+
+    #[test]
+    fn test_quote_fees_and_oversell() {
+        let mut ledger = CostBasisLedger::new(CostBasisMethod::Fifo);
+        ledger.apply_trade(&trade(1, OrderSide::Buy, "100", "1", "0.1"));
+        let realized = ledger.apply_trade(&trade(2, OrderSide::Sell, "110", "2", "0.2"));
+
+        assert_eq!(realized.len(), 1);
+        assert_eq!(realized[0].cost_basis, fx("100.1"));
+        assert_eq!(realized[0].proceeds, fx("109.9"));
+        assert_eq!(realized[0].holding_period_ms(), 1000);
+        assert_eq!(ledger.unmatched_quantity("BTCUSDT"), fx("1"));
+        assert_eq!(ledger.position("BTCUSDT"), Fixed::ZERO);
+    }
+}
@@ -0,0 +1,329 @@
+//! Admin HTTP endpoint with liveness and readiness probes
+//!
+//! `AdminServer` answers plain HTTP on a local port so Kubernetes or systemd
+//! can gate traffic and restarts: `GET /healthz` is 200 while the process is
+//! alive and its trading loop keeps calling `HealthChecks::heartbeat`, and
+//! `GET /readyz` is 200 only once every required readiness check has passed
+//! (connected, time-synced, books warm, reconciliation done), 503 with the
+//! failing checks otherwise. Subsystems report through a cheap cloneable
+//! `HealthChecks` handle shared with the server.
+//!
+//! ```rust,ignore
+//! let health = HealthChecks::new();
+//! let server = AdminServer::bind("0.0.0.0:8081", health.clone())?;
+//! monoio::spawn(async move { server.run().await });
+//!
+//! health.set(ReadinessCheck::Connected, true, "user stream open");
+//! health.heartbeat();
+//! ```
+
+use crate::errors::{ExchangeError, Result};
+use sriquant_core::prelude::*;
+
+use monoio::io::{AsyncReadRent, AsyncWriteRentExt};
+use monoio::net::{TcpListener, TcpStream};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// Largest request head the admin server reads
+const MAX_REQUEST_BYTES: usize = 4096;
+
+/// A condition that must hold before the process takes traffic
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadinessCheck {
+    /// Market data and user streams are connected
+    Connected,
+    /// Local clock offset to the exchange is measured and within tolerance
+    TimeSynced,
+    /// Order books are synced and warm-up gates have passed
+    BooksWarm,
+    /// Open orders and balances have been reconciled with the exchange
+    Reconciled,
+}
+
+impl ReadinessCheck {
+    pub const ALL: [ReadinessCheck; 4] = [Self::Connected, Self::TimeSynced, Self::BooksWarm, Self::Reconciled];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Connected => "connected",
+            Self::TimeSynced => "time_synced",
+            Self::BooksWarm => "books_warm",
+            Self::Reconciled => "reconciled",
+        }
+    }
+}
+
+/// Last reported state of one readiness check
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CheckStatus {
+    pub check: ReadinessCheck,
+    pub ready: bool,
+    pub detail: String,
+    /// When the check was last reported; 0 if it never was
+    pub updated_at: u64,
+}
+
+/// Result of evaluating every required check
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Readiness {
+    pub ready: bool,
+    pub checks: Vec<CheckStatus>,
+}
+
+#[derive(Debug, Default)]
+struct HealthState {
+    checks: HashMap<ReadinessCheck, CheckStatus>,
+    last_heartbeat: Option<u64>,
+}
+
+/// Shared liveness and readiness state
+#[derive(Debug, Clone)]
+pub struct HealthChecks {
+    state: Arc<RwLock<HealthState>>,
+    required: Vec<ReadinessCheck>,
+    stall_timeout: Duration,
+}
+
+impl Default for HealthChecks {
+    fn default() -> Self {
+        Self {
+            state: Arc::default(),
+            required: ReadinessCheck::ALL.to_vec(),
+            stall_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+impl HealthChecks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only these checks gate readiness, e.g. no `Reconciled` for a data recorder
+    pub fn with_required(mut self, checks: &[ReadinessCheck]) -> Self {
+        self.required = checks.to_vec();
+        self
+    }
+
+    /// Report the process dead when no heartbeat arrives for this long (default 30s)
+    pub fn with_stall_timeout(mut self, timeout: Duration) -> Self {
+        self.stall_timeout = timeout;
+        self
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, HealthState> {
+        self.state.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, HealthState> {
+        self.state.write().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Report the current state of a readiness check
+    pub fn set(&self, check: ReadinessCheck, ready: bool, detail: impl Into<String>) {
+        let detail = detail.into();
+        let previous = self.write().checks.insert(check, CheckStatus {
+            check,
+            ready,
+            detail: detail.clone(),
+            updated_at: nanos(),
+        });
+        match previous {
+            Some(previous) if previous.ready && !ready => warn!("🩺 Readiness check {} failing: {}", check.name(), detail),
+            Some(previous) if previous.ready => {}
+            _ if ready => info!("🩺 Readiness check {} passed: {}", check.name(), detail),
+            _ => debug!("🩺 Readiness check {} not ready: {}", check.name(), detail),
+        }
+    }
+
+    /// Record that the trading loop is making progress
+    pub fn heartbeat(&self) {
+        self.write().last_heartbeat = Some(nanos());
+    }
+
+    /// Alive until a heartbeat has been seen and then goes stale
+    pub fn is_alive(&self, now: u64) -> bool {
+        match self.read().last_heartbeat {
+            Some(last) => now.saturating_sub(last) <= self.stall_timeout.as_nanos() as u64,
+            None => true,
+        }
+    }
+
+    /// Evaluate the required checks; unreported checks count as not ready
+    pub fn readiness(&self) -> Readiness {
+        let state = self.read();
+        let checks: Vec<CheckStatus> = self.required.iter()
+            .map(|check| state.checks.get(check).cloned().unwrap_or_else(|| CheckStatus {
+                check: *check,
+                ready: false,
+                detail: "not reported".to_string(),
+                updated_at: 0,
+            }))
+            .collect();
+        Readiness { ready: checks.iter().all(|c| c.ready), checks }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.readiness().ready
+    }
+
+    /// Status code and JSON body for an admin request
+    pub fn respond(&self, method: &str, path: &str) -> (u16, String) {
+        let path = path.split('?').next().unwrap_or(path);
+        match (method, path) {
+            ("GET", "/healthz") => {
+                let now = nanos();
+                let last_heartbeat = self.read().last_heartbeat;
+                let alive = self.is_alive(now);
+                let body = serde_json::json!({
+                    "alive": alive,
+                    "heartbeat_age_ms": last_heartbeat.map(|last| now.saturating_sub(last) / 1_000_000),
+                });
+                (if alive { 200 } else { 503 }, body.to_string())
+            }
+            ("GET", "/readyz") => {
+                let readiness = self.readiness();
+                let body = serde_json::to_string(&readiness).unwrap_or_default();
+                (if readiness.ready { 200 } else { 503 }, body)
+            }
+            (_, "/healthz" | "/readyz") => (405, r#"{"error":"method not allowed"}"#.to_string()),
+            _ => (404, r#"{"error":"not found"}"#.to_string()),
+        }
+    }
+}
+
+/// Minimal HTTP server for the probe endpoints
+pub struct AdminServer {
+    listener: TcpListener,
+    health: HealthChecks,
+}
+
+impl AdminServer {
+    /// Listen on `addr`, e.g. `0.0.0.0:8081`
+    pub fn bind(addr: &str, health: HealthChecks) -> Result<Self> {
+        let listener = TcpListener::bind(addr)
+            .map_err(|e| ExchangeError::NetworkError(format!("Failed to bind admin server on {addr}: {e}")))?;
+        info!("🩺 Admin server listening on {}", addr);
+        Ok(Self { listener, health })
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        self.listener.local_addr()
+            .map_err(|e| ExchangeError::NetworkError(format!("Admin server address: {e}")))
+    }
+
+    pub fn health(&self) -> &HealthChecks {
+        &self.health
+    }
+
+    /// Serve probes until the listener fails
+    pub async fn run(&self) -> Result<()> {
+        loop {
+            if let Err(e) = self.serve_one().await {
+                debug!("🩺 Admin request failed: {}", e);
+            }
+        }
+    }
+
+    /// Accept one connection and answer its request
+    pub async fn serve_one(&self) -> Result<()> {
+        let (stream, peer) = self.listener.accept().await
+            .map_err(|e| ExchangeError::NetworkError(format!("Admin accept failed: {e}")))?;
+        debug!("🩺 Admin connection from {}", peer);
+        self.serve_connection(stream).await
+    }
+
+    async fn serve_connection(&self, mut stream: TcpStream) -> Result<()> {
+        let mut request = Vec::new();
+        while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+            if request.len() >= MAX_REQUEST_BYTES {
+                return Err(ExchangeError::InvalidResponse("Admin request head too large".to_string()));
+            }
+            let (read, buf) = stream.read(Vec::with_capacity(1024)).await;
+            let read = read.map_err(|e| ExchangeError::NetworkError(format!("Admin read failed: {e}")))?;
+            if read == 0 {
+                break;
+            }
+            request.extend_from_slice(&buf[..read]);
+        }
+
+        let head = String::from_utf8_lossy(&request);
+        let mut request_line = head.lines().next().unwrap_or_default().split_whitespace();
+        let (status, body) = match (request_line.next(), request_line.next()) {
+            (Some(method), Some(path)) => self.health.respond(method, path),
+            _ => (400, r#"{"error":"bad request"}"#.to_string()),
+        };
+
+        let reason = match status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            _ => "Service Unavailable",
+        };
+        let response = format!(
+            "HTTP/1.1 {status} {reason}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        let (written, _) = stream.write_all(response.into_bytes()).await;
+        written.map_err(|e| ExchangeError::NetworkError(format!("Admin write failed: {e}")))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_readiness_requires_every_check() {
+        let health = HealthChecks::new().with_required(&[ReadinessCheck::Connected, ReadinessCheck::BooksWarm]);
+        assert_eq!(health.respond("GET", "/readyz").0, 503);
+
+        health.set(ReadinessCheck::Connected, true, "streams open");
+        health.set(ReadinessCheck::BooksWarm, false, "BTCUSDT resyncing");
+        let (status, body) = health.respond("GET", "/readyz");
+        assert_eq!(status, 503);
+        assert!(body.contains("BTCUSDT resyncing"), "{body}");
+
+        health.set(ReadinessCheck::BooksWarm, true, "2 books synced");
+        // Not required, so it doesn't hold readiness back
+        health.set(ReadinessCheck::Reconciled, false, "skipped");
+        assert_eq!(health.respond("GET", "/readyz?verbose").0, 200);
+        assert_eq!(health.respond("POST", "/readyz").0, 405);
+        assert_eq!(health.respond("GET", "/metrics").0, 404);
+    }
+
+    #[test]
+    fn test_liveness_follows_heartbeat() {
+        let health = HealthChecks::new().with_stall_timeout(Duration::from_secs(5));
+        assert_eq!(health.respond("GET", "/healthz").0, 200);
+
+        health.heartbeat();
+        let now = nanos();
+        assert!(health.is_alive(now));
+        assert!(!health.is_alive(now + 6_000_000_000));
+    }
+
+    #[monoio::test]
+    async fn test_serves_probes_over_http() {
+        let health = HealthChecks::new().with_required(&[ReadinessCheck::Connected]);
+        health.set(ReadinessCheck::Connected, true, "ok");
+        let server = AdminServer::bind("127.0.0.1:0", health).unwrap();
+
+        let mut client = TcpStream::connect(server.local_addr().unwrap()).await.unwrap();
+        let (written, _) = client.write_all(b"GET /readyz HTTP/1.1\r\nHost: localhost\r\n\r\n".to_vec()).await;
+        written.unwrap();
+        server.serve_one().await.unwrap();
+
+        let (read, buf) = client.read(Vec::with_capacity(1024)).await;
+        let response = String::from_utf8_lossy(&buf[..read.unwrap()]).to_string();
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{response}");
+        assert!(response.contains("\r\n\r\n{\"ready\":true,"), "{response}");
+    }
+}
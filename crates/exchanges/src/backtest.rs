@@ -0,0 +1,451 @@
+//! Backtesting on historical klines and trades
+//!
+//! `Backtester` replays `MarketData` events (klines from `get_klines`, or
+//! recorded trades and books) through a `Strategy` and fills its orders with
+//! a slippage and fee model. Orders computed on an event execute on the next
+//! event for the same symbol, never on the one that produced them, so a
+//! strategy reacting to a bar close fills at the following bar's open.
+//!
+//! Cash, positions and fills are `Fixed`; the report carries the equity curve
+//! and drawdown statistics.
+
+use crate::fees::FeeTier;
+use crate::paper::PaperFill;
+use crate::runner::Strategy;
+use crate::types::*;
+use sriquant_core::prelude::*;
+
+use std::collections::HashMap;
+use tracing::{debug, info};
+
+/// Price adjustment against the order on every fill
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SlippageModel {
+    None,
+    /// Fixed basis points: buys fill higher, sells lower
+    Bps(Fixed),
+}
+
+/// Backtest settings
+#[derive(Debug, Clone)]
+pub struct BacktestConfig {
+    /// Starting cash in the quote asset
+    pub initial_cash: Fixed,
+    pub fee_tier: FeeTier,
+    pub slippage: SlippageModel,
+    /// Allow selling more than is held
+    pub allow_short: bool,
+}
+
+impl Default for BacktestConfig {
+    fn default() -> Self {
+        Self {
+            initial_cash: Fixed::from_i64(10_000).unwrap_or(Fixed::ONE),
+            fee_tier: FeeTier::new("VIP0", 0.0, 10.0, 10.0),
+            slippage: SlippageModel::None,
+            allow_short: false,
+        }
+    }
+}
+
+impl BacktestConfig {
+    pub fn with_initial_cash(mut self, cash: Fixed) -> Self {
+        self.initial_cash = cash;
+        self
+    }
+
+    pub fn with_fee_tier(mut self, fee_tier: FeeTier) -> Self {
+        self.fee_tier = fee_tier;
+        self
+    }
+
+    pub fn with_slippage(mut self, slippage: SlippageModel) -> Self {
+        self.slippage = slippage;
+        self
+    }
+
+    pub fn with_short_selling(mut self, allow: bool) -> Self {
+        self.allow_short = allow;
+        self
+    }
+}
+
+/// Portfolio value after an event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EquityPoint {
+    pub timestamp: u64,
+    pub equity: Fixed,
+    /// Fraction below the running peak, in `[0, 1]`
+    pub drawdown: Fixed,
+}
+
+/// Result of a backtest run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestReport {
+    pub strategy: String,
+    pub initial_equity: Fixed,
+    pub final_equity: Fixed,
+    /// `final / initial - 1`
+    pub total_return: Fixed,
+    /// Largest peak-to-trough decline as a fraction of the peak
+    pub max_drawdown: Fixed,
+    /// Longest time spent below a previous peak
+    pub max_drawdown_duration_ms: u64,
+    pub fees_paid: Fixed,
+    pub fills: Vec<PaperFill>,
+    /// Orders refused for lack of cash or position
+    pub rejected_orders: usize,
+    pub equity_curve: Vec<EquityPoint>,
+}
+
+/// Order waiting for the next event on its symbol
+#[derive(Debug, Clone)]
+struct PendingOrder {
+    id: u64,
+    request: OrderRequest,
+}
+
+/// Replays market data through a strategy
+#[derive(Debug)]
+pub struct Backtester {
+    config: BacktestConfig,
+    cash: Fixed,
+    positions: HashMap<String, Fixed>,
+    last_prices: HashMap<String, Fixed>,
+    pending: Vec<PendingOrder>,
+    fills: Vec<PaperFill>,
+    fees_paid: Fixed,
+    rejected_orders: usize,
+    equity_curve: Vec<EquityPoint>,
+    peak: Fixed,
+    next_id: u64,
+}
+
+impl Backtester {
+    pub fn new(config: BacktestConfig) -> Self {
+        Self {
+            cash: config.initial_cash,
+            peak: config.initial_cash,
+            config,
+            positions: HashMap::new(),
+            last_prices: HashMap::new(),
+            pending: Vec::new(),
+            fills: Vec::new(),
+            fees_paid: Fixed::ZERO,
+            rejected_orders: 0,
+            equity_curve: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Run a strategy over events in time order
+    pub fn run<S: Strategy>(mut self, strategy: &mut S, events: impl IntoIterator<Item = MarketData>) -> BacktestReport {
+        let timer = PerfTimer::start(format!("backtest_{}", strategy.name()));
+        for event in events {
+            for fill in self.execute_pending(&event) {
+                strategy.on_fill(&fill);
+            }
+            let (symbol, price, timestamp) = mark(&event);
+            if let Some(price) = price {
+                self.last_prices.insert(symbol.to_string(), price);
+            }
+
+            for request in strategy.on_market_data(&event) {
+                self.next_id += 1;
+                self.pending.push(PendingOrder { id: self.next_id, request });
+            }
+            self.record_equity(timestamp);
+        }
+        timer.log_elapsed();
+
+        let report = self.report(strategy.name());
+        info!(
+            "📈 Backtest {}: return {}, max drawdown {}, {} fills",
+            report.strategy, report.total_return, report.max_drawdown, report.fills.len()
+        );
+        report
+    }
+
+    /// Fill orders pending on this event's symbol
+    fn execute_pending(&mut self, event: &MarketData) -> Vec<PaperFill> {
+        let (symbol, _, timestamp) = mark(event);
+        let (due, waiting): (Vec<_>, Vec<_>) = std::mem::take(&mut self.pending)
+            .into_iter()
+            .partition(|o| o.request.symbol == symbol);
+        self.pending = waiting;
+
+        let mut fills = Vec::new();
+        for order in due {
+            match execution_price(&order.request, event) {
+                Some((price, passive)) => {
+                    if let Some(fill) = self.fill(&order, price, passive, timestamp) {
+                        fills.push(fill);
+                    }
+                }
+                // Limit orders not reached keep waiting; market orders always have a price
+                None => self.pending.push(order),
+            }
+        }
+        fills
+    }
+
+    fn fill(&mut self, order: &PendingOrder, price: Fixed, passive: bool, timestamp: u64) -> Option<PaperFill> {
+        let request = &order.request;
+        // Limit orders fill at their price; slippage only applies to takers
+        let price = if passive { price } else { self.slip(price, request.side) };
+        let notional = price * request.quantity;
+        let fee_bps = self.config.fee_tier.fee_bps(passive);
+        let fee = Fixed::from_f64(notional.to_f64_lossy() * fee_bps / 10_000.0).map(|f| f.round_dp(8)).unwrap_or(Fixed::ZERO);
+        let position = self.positions.get(&request.symbol).copied().unwrap_or(Fixed::ZERO);
+
+        let affordable = match request.side {
+            OrderSide::Buy => notional + fee <= self.cash,
+            OrderSide::Sell => self.config.allow_short || request.quantity <= position,
+        };
+        if !affordable {
+            debug!("📈 Rejected backtest order {} {} {}", request.symbol, request.side, request.quantity);
+            self.rejected_orders += 1;
+            return None;
+        }
+
+        let (position_delta, cash_delta) = match request.side {
+            OrderSide::Buy => (request.quantity, Fixed::ZERO - notional - fee),
+            OrderSide::Sell => (Fixed::ZERO - request.quantity, notional - fee),
+        };
+        self.positions.insert(request.symbol.clone(), position + position_delta);
+        self.cash += cash_delta;
+        self.fees_paid += fee;
+
+        let fill = PaperFill {
+            paper_order_id: order.id,
+            symbol: request.symbol.clone(),
+            side: request.side,
+            price,
+            quantity: request.quantity,
+            timestamp,
+            passive,
+        };
+        self.fills.push(fill.clone());
+        Some(fill)
+    }
+
+    fn slip(&self, price: Fixed, side: OrderSide) -> Fixed {
+        let SlippageModel::Bps(bps) = self.config.slippage else { return price };
+        let Ok(ten_thousand) = Fixed::from_i64(10_000) else { return price };
+        let adjustment = price * bps / ten_thousand;
+        match side {
+            OrderSide::Buy => price + adjustment,
+            OrderSide::Sell => price - adjustment,
+        }
+    }
+
+    fn equity(&self) -> Fixed {
+        self.positions.iter().fold(self.cash, |equity, (symbol, quantity)| {
+            equity + self.last_prices.get(symbol).map(|p| *p * *quantity).unwrap_or(Fixed::ZERO)
+        })
+    }
+
+    fn record_equity(&mut self, timestamp: u64) {
+        let equity = self.equity();
+        self.peak = self.peak.max(equity);
+        let drawdown = if self.peak > Fixed::ZERO { (self.peak - equity) / self.peak } else { Fixed::ZERO };
+        self.equity_curve.push(EquityPoint { timestamp, equity, drawdown });
+    }
+
+    fn report(self, strategy: &str) -> BacktestReport {
+        let initial = self.config.initial_cash;
+        let final_equity = self.equity_curve.last().map(|p| p.equity).unwrap_or(initial);
+        let total_return = if initial > Fixed::ZERO { final_equity / initial - Fixed::ONE } else { Fixed::ZERO };
+        let max_drawdown = self.equity_curve.iter().map(|p| p.drawdown).max().unwrap_or(Fixed::ZERO);
+
+        let mut max_drawdown_duration_ms = 0;
+        let mut underwater_since = None;
+        for point in &self.equity_curve {
+            match (point.drawdown > Fixed::ZERO, underwater_since) {
+                (true, None) => underwater_since = Some(point.timestamp),
+                (false, Some(start)) => {
+                    max_drawdown_duration_ms = max_drawdown_duration_ms.max(point.timestamp - start);
+                    underwater_since = None;
+                }
+                _ => {}
+            }
+        }
+        if let (Some(start), Some(last)) = (underwater_since, self.equity_curve.last()) {
+            max_drawdown_duration_ms = max_drawdown_duration_ms.max(last.timestamp - start);
+        }
+
+        BacktestReport {
+            strategy: strategy.to_string(),
+            initial_equity: initial,
+            final_equity,
+            total_return,
+            max_drawdown,
+            max_drawdown_duration_ms,
+            fees_paid: self.fees_paid,
+            fills: self.fills,
+            rejected_orders: self.rejected_orders,
+            equity_curve: self.equity_curve,
+        }
+    }
+}
+
+/// Kline events from historical candles, e.g. the output of `get_klines`
+pub fn candles_to_events(symbol: &str, interval: &str, candles: &[Candle]) -> Vec<MarketData> {
+    candles.iter()
+        .map(|c| MarketData::Kline(Kline {
+            symbol: symbol.to_string(),
+            interval: interval.to_string(),
+            open_time: c.open_time,
+            close_time: c.close_time,
+            open: c.open,
+            high: c.high,
+            low: c.low,
+            close: c.close,
+            volume: c.volume,
+            quote_volume: Fixed::ZERO,
+            number_of_trades: c.number_of_trades,
+            is_closed: true,
+        }))
+        .collect()
+}
+
+/// Symbol, mark price and time of an event
+fn mark(event: &MarketData) -> (&str, Option<Fixed>, u64) {
+    match event {
+        MarketData::Kline(k) => (&k.symbol, Some(k.close), k.close_time),
+        MarketData::Trade(t) => (&t.symbol, Some(t.price), t.timestamp),
+        MarketData::Ticker(t) => (&t.symbol, Some(t.price), t.timestamp),
+        MarketData::OrderBook(b) => (&b.symbol, b.mid_price(), b.timestamp),
+    }
+}
+
+/// Where an order executes on this event, and whether it fills passively
+///
+/// Market orders take the bar open, the trade price or the touch. Limit
+/// orders fill at their price once the bar's range or a trade reaches it, or
+/// at a better opening price if the bar gaps through.
+fn execution_price(request: &OrderRequest, event: &MarketData) -> Option<(Fixed, bool)> {
+    let (open, low, high) = match event {
+        MarketData::Kline(k) => (k.open, k.low, k.high),
+        MarketData::Trade(t) => (t.price, t.price, t.price),
+        MarketData::Ticker(t) => (t.price, t.price, t.price),
+        MarketData::OrderBook(b) => {
+            let touch = match request.side {
+                OrderSide::Buy => b.best_ask()?,
+                OrderSide::Sell => b.best_bid()?,
+            };
+            (touch, touch, touch)
+        }
+    };
+    match (request.order_type, request.price) {
+        (OrderType::Limit, Some(limit)) => match request.side {
+            OrderSide::Buy if open <= limit => Some((open, false)),
+            OrderSide::Buy if low <= limit => Some((limit, true)),
+            OrderSide::Sell if open >= limit => Some((open, false)),
+            OrderSide::Sell if high >= limit => Some((limit, true)),
+            _ => None,
+        },
+        _ => Some((open, false)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testkit::fx;
+
+    fn candle(minute: u64, open: &str, high: &str, low: &str, close: &str) -> Candle {
+        Candle {
+            open_time: minute * 60_000,
+            close_time: minute * 60_000 + 59_999,
+            open: fx(open),
+            high: fx(high),
+            low: fx(low),
+            close: fx(close),
+            volume: fx("10"),
+            number_of_trades: 100,
+        }
+    }
+
+    /// Buys one unit on the first bar, sells on the third
+    struct RoundTrip {
+        bars: usize,
+        fills: usize,
+    }
+
+    impl Strategy for RoundTrip {
+        fn name(&self) -> &str {
+            "round_trip"
+        }
+
+        fn on_market_data(&mut self, event: &MarketData) -> Vec<OrderRequest> {
+            let MarketData::Kline(kline) = event else { return Vec::new() };
+            self.bars += 1;
+            let side = match self.bars {
+                1 => OrderSide::Buy,
+                3 => OrderSide::Sell,
+                _ => return Vec::new(),
+            };
+            vec![OrderRequest {
+                symbol: kline.symbol.clone(),
+                side,
+                order_type: OrderType::Market,
+                quantity: Fixed::ONE,
+                price: None,
+                stop_price: None,
+                time_in_force: None,
+                client_order_id: None,
+            }]
+        }
+
+        fn on_fill(&mut self, _fill: &PaperFill) {
+            self.fills += 1;
+        }
+    }
+
+    #[test]
+    fn test_fills_at_next_open_with_fees_and_drawdown() {
+        let candles = [
+            candle(0, "100", "101", "99", "100"),
+            candle(1, "100", "100", "80", "80"),
+            candle(2, "80", "95", "80", "90"),
+            candle(3, "110", "110", "105", "105"),
+        ];
+        let config = BacktestConfig::default().with_initial_cash(fx("1000"));
+        let mut strategy = RoundTrip { bars: 0, fills: 0 };
+        let report = Backtester::new(config).run(&mut strategy, candles_to_events("BTCUSDT", "1m", &candles));
+
+        assert_eq!(strategy.fills, 2);
+        assert_eq!(report.fills[0].price, fx("100"));
+        assert_eq!(report.fills[1].price, fx("110"));
+        // +10 minus 10 bps on 100 and 110
+        assert_eq!(report.fees_paid, fx("0.21"));
+        assert_eq!(report.final_equity, fx("1009.79"));
+        // Bought at 100 plus 0.1 fee, marked at 80: 20.1 below the 1000 peak
+        assert_eq!(report.max_drawdown, fx("0.0201"));
+        assert_eq!(report.max_drawdown_duration_ms, 120_000);
+    }
+
+    #[test]
+    fn test_limit_orders_and_slippage() {
+        let buy = |price: Option<&str>| OrderRequest {
+            symbol: "BTCUSDT".to_string(),
+            side: OrderSide::Buy,
+            order_type: if price.is_some() { OrderType::Limit } else { OrderType::Market },
+            quantity: Fixed::ONE,
+            price: price.map(fx),
+            stop_price: None,
+            time_in_force: None,
+            client_order_id: None,
+        };
+        let bar = candles_to_events("BTCUSDT", "1m", &[candle(0, "100", "102", "97", "101")]).remove(0);
+
+        assert_eq!(execution_price(&buy(Some("98")), &bar), Some((fx("98"), true)));
+        assert_eq!(execution_price(&buy(Some("101")), &bar), Some((fx("100"), false)));
+        assert_eq!(execution_price(&buy(Some("96")), &bar), None);
+
+        let backtester = Backtester::new(BacktestConfig::default().with_slippage(SlippageModel::Bps(fx("5"))));
+        assert_eq!(backtester.slip(fx("100"), OrderSide::Buy), fx("100.05"));
+        assert_eq!(backtester.slip(fx("100"), OrderSide::Sell), fx("99.95"));
+    }
+}
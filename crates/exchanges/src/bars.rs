@@ -0,0 +1,392 @@
+//! Bar close timing on the exchange clock
+//!
+//! Bar strategies want to act the moment a bar closes, not when the closing
+//! kline arrives, which can lag by hundreds of milliseconds, or never come on
+//! a symbol that didn't trade. `BarClock` computes bar boundaries for each
+//! interval on the exchange's clock (the local clock corrected by the offset
+//! `ExchangeClock` measured against server time) and reports each close
+//! exactly once: from the timer (`poll`, `wait`) or from a closed kline that
+//! beats it, whichever comes first.
+//!
+//! Boundaries follow Binance: multiples of the interval since the Unix
+//! epoch, with weekly bars opening Monday 00:00 UTC.
+
+use crate::errors::{ExchangeError, Result};
+use crate::types::Kline;
+use sriquant_core::fixed::bulk;
+use sriquant_core::prelude::*;
+
+use std::io::Write;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// 1970-01-05, the first Monday after the epoch, in milliseconds
+const FIRST_MONDAY_MS: u64 = 4 * 86_400_000;
+
+/// Length of a kline interval ("1s" through "1w") in milliseconds
+pub fn interval_ms(interval: &str) -> Result<u64> {
+    let split = interval.find(|c: char| !c.is_ascii_digit()).unwrap_or(interval.len());
+    let (count, unit) = interval.split_at(split);
+    let count: u64 = count.parse()
+        .map_err(|_| ExchangeError::ConfigurationError(format!("Bad interval {interval:?}")))?;
+    let unit_ms = match unit {
+        "s" => 1_000,
+        "m" => 60_000,
+        "h" => 3_600_000,
+        "d" => 86_400_000,
+        "w" => 7 * 86_400_000,
+        _ => return Err(ExchangeError::ConfigurationError(format!("Unsupported interval {interval:?}"))),
+    };
+    if count == 0 {
+        return Err(ExchangeError::ConfigurationError(format!("Bad interval {interval:?}")));
+    }
+    Ok(count * unit_ms)
+}
+
+/// Local clock corrected to exchange time
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExchangeClock {
+    offset_ms: i64,
+}
+
+impl ExchangeClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_offset_ms(offset_ms: i64) -> Self {
+        Self { offset_ms }
+    }
+
+    /// Exchange time minus local time
+    pub fn offset_ms(&self) -> i64 {
+        self.offset_ms
+    }
+
+    pub fn set_offset_ms(&mut self, offset_ms: i64) {
+        self.offset_ms = offset_ms;
+    }
+
+    /// Take the offset from a server time fetched between two local readings
+    ///
+    /// The server is assumed to have stamped the response halfway through the
+    /// round trip. Returns the new offset.
+    pub fn observe(&mut self, local_before_ms: u64, server_time_ms: u64, local_after_ms: u64) -> i64 {
+        let midpoint = local_before_ms / 2 + local_after_ms / 2 + (local_before_ms % 2 + local_after_ms % 2) / 2;
+        self.offset_ms = server_time_ms as i64 - midpoint as i64;
+        self.offset_ms
+    }
+
+    pub fn to_exchange(&self, local_ms: u64) -> u64 {
+        local_ms.saturating_add_signed(self.offset_ms)
+    }
+
+    pub fn to_local(&self, exchange_ms: u64) -> u64 {
+        exchange_ms.saturating_add_signed(-self.offset_ms)
+    }
+
+    /// Current exchange time in milliseconds
+    pub fn now_ms(&self) -> u64 {
+        self.to_exchange(nanos() / 1_000_000)
+    }
+}
+
+/// A bar that closed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BarClose {
+    pub interval: String,
+    pub open_time: u64,
+    /// Last millisecond of the bar, as in Binance klines
+    pub close_time: u64,
+}
+
+#[derive(Debug, Clone)]
+struct Schedule {
+    interval: String,
+    length_ms: u64,
+    /// Boundary ending the bar in progress; set on first use
+    next_boundary: Option<u64>,
+}
+
+impl Schedule {
+    /// First boundary strictly after `now`
+    fn boundary_after(&self, now: u64) -> u64 {
+        let anchor = if self.interval.ends_with('w') { FIRST_MONDAY_MS } else { 0 };
+        if now < anchor {
+            return anchor;
+        }
+        anchor + ((now - anchor) / self.length_ms + 1) * self.length_ms
+    }
+
+    fn close(&self, boundary: u64) -> BarClose {
+        BarClose { interval: self.interval.clone(), open_time: boundary.saturating_sub(self.length_ms), close_time: boundary - 1 }
+    }
+}
+
+/// Fires bar closes at exchange-time boundaries
+///
+/// ```rust,ignore
+/// let mut bars = BarClock::new(clock).with_interval("1m")?.with_interval("5m")?;
+/// loop {
+///     for close in bars.wait().await {
+///         strategy.on_bar_close(&close);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct BarClock {
+    clock: ExchangeClock,
+    schedules: Vec<Schedule>,
+}
+
+impl BarClock {
+    pub fn new(clock: ExchangeClock) -> Self {
+        Self { clock, schedules: Vec::new() }
+    }
+
+    /// Track another interval; repeats are ignored
+    pub fn with_interval(mut self, interval: &str) -> Result<Self> {
+        if !self.schedules.iter().any(|s| s.interval == interval) {
+            let length_ms = interval_ms(interval)?;
+            self.schedules.push(Schedule { interval: interval.to_string(), length_ms, next_boundary: None });
+        }
+        Ok(self)
+    }
+
+    pub fn clock(&self) -> ExchangeClock {
+        self.clock
+    }
+
+    /// Apply a fresh offset measurement; bars already due still fire once
+    pub fn set_offset_ms(&mut self, offset_ms: i64) {
+        self.clock.set_offset_ms(offset_ms);
+    }
+
+    pub fn intervals(&self) -> impl Iterator<Item = &str> {
+        self.schedules.iter().map(|s| s.interval.as_str())
+    }
+
+    /// Bars closed as of now
+    pub fn poll(&mut self) -> Vec<BarClose> {
+        self.poll_at(self.clock.now_ms())
+    }
+
+    /// Bars closed as of exchange time `now_ms`
+    ///
+    /// The first poll only starts the schedules. When several boundaries of
+    /// one interval passed since the last poll (a stalled loop), only the
+    /// latest bar is reported.
+    pub fn poll_at(&mut self, now_ms: u64) -> Vec<BarClose> {
+        let mut closes = Vec::new();
+        for schedule in &mut self.schedules {
+            let Some(boundary) = schedule.next_boundary else {
+                schedule.next_boundary = Some(schedule.boundary_after(now_ms));
+                continue;
+            };
+            if now_ms < boundary {
+                continue;
+            }
+            let next = schedule.boundary_after(now_ms);
+            let latest = next - schedule.length_ms;
+            let skipped = (latest - boundary) / schedule.length_ms;
+            if skipped > 0 {
+                warn!("⏰ Skipped {} {} bar closes; the event loop stalled", skipped, schedule.interval);
+            }
+            closes.push(schedule.close(latest));
+            schedule.next_boundary = Some(next);
+        }
+        closes
+    }
+
+    /// Report a closed kline's bar now if the timer hasn't yet
+    pub fn on_kline(&mut self, kline: &Kline) -> Option<BarClose> {
+        if !kline.is_closed {
+            return None;
+        }
+        let schedule = self.schedules.iter_mut().find(|s| s.interval == kline.interval)?;
+        let boundary = kline.close_time + 1;
+        if schedule.next_boundary.is_some_and(|next| boundary < next) {
+            return None;
+        }
+        debug!("🕯️  {} bar closed by kline ahead of the timer", schedule.interval);
+        schedule.next_boundary = Some(boundary + schedule.length_ms);
+        Some(schedule.close(boundary))
+    }
+
+    /// Exchange time of the next boundary across all intervals
+    pub fn next_boundary(&self) -> Option<u64> {
+        let now = self.clock.now_ms();
+        self.schedules.iter()
+            .map(|s| s.next_boundary.unwrap_or_else(|| s.boundary_after(now)))
+            .min()
+    }
+
+    /// Local time left until the next boundary
+    pub fn until_next_close(&self) -> Option<Duration> {
+        let boundary = self.next_boundary()?;
+        Some(Duration::from_millis(boundary.saturating_sub(self.clock.now_ms())))
+    }
+
+    /// Sleep until at least one bar closes and return the closes
+    ///
+    /// Returns immediately, empty, with no intervals configured.
+    pub async fn wait(&mut self) -> Vec<BarClose> {
+        if self.schedules.iter().any(|s| s.next_boundary.is_none()) {
+            self.poll();
+        }
+        loop {
+            let Some(delay) = self.until_next_close() else {
+                return Vec::new();
+            };
+            if !delay.is_zero() {
+                monoio::time::sleep(delay).await;
+            }
+            let closes = self.poll();
+            if !closes.is_empty() {
+                return closes;
+            }
+        }
+    }
+}
+
+/// OHLCV over a run of klines, e.g. an hour of 1m bars
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BarSummary {
+    pub open_time: u64,
+    pub close_time: u64,
+    pub open: Fixed,
+    pub high: Fixed,
+    pub low: Fixed,
+    pub close: Fixed,
+    pub volume: Fixed,
+    pub quote_volume: Fixed,
+    /// Quote volume over base volume; `None` without any volume
+    pub vwap: Option<Fixed>,
+    pub number_of_trades: u64,
+}
+
+impl BarSummary {
+    /// Summarize klines in time order; `None` for an empty slice
+    pub fn from_klines(klines: &[Kline]) -> Option<Self> {
+        let (first, last) = (klines.first()?, klines.last()?);
+        let column = |field: fn(&Kline) -> Fixed| klines.iter().map(field).collect::<Vec<_>>();
+        let volume = bulk::sum(&column(|k| k.volume));
+        let quote_volume = bulk::sum(&column(|k| k.quote_volume));
+
+        Some(Self {
+            open_time: first.open_time,
+            close_time: last.close_time,
+            open: first.open,
+            high: bulk::max(&column(|k| k.high))?,
+            low: bulk::min(&column(|k| k.low))?,
+            close: last.close,
+            volume,
+            quote_volume,
+            vwap: (!volume.is_zero()).then(|| quote_volume / volume),
+            number_of_trades: klines.iter().map(|k| k.number_of_trades as u64).sum(),
+        })
+    }
+}
+
+/// Write klines as CSV with a header row, returning the rows written
+///
+/// Prices and volumes keep their exact decimal strings, so research tools
+/// read back the values Binance sent.
+pub fn write_klines_csv<W: Write>(mut out: W, klines: &[Kline]) -> Result<usize> {
+    writeln!(out, "open_time,close_time,symbol,interval,open,high,low,close,volume,quote_volume,number_of_trades")?;
+    for k in klines {
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{},{},{},{},{}",
+            k.open_time, k.close_time, k.symbol, k.interval, k.open, k.high, k.low, k.close,
+            k.volume, k.quote_volume, k.number_of_trades
+        )?;
+    }
+    out.flush()?;
+    Ok(klines.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testkit::fx;
+
+    fn kline(interval: &str, close_time: u64) -> Kline {
+        Kline {
+            symbol: "BTCUSDT".to_string(),
+            interval: interval.to_string(),
+            open_time: close_time + 1 - interval_ms(interval).unwrap(),
+            close_time,
+            open: Fixed::ZERO,
+            high: Fixed::ZERO,
+            low: Fixed::ZERO,
+            close: Fixed::ZERO,
+            volume: Fixed::ZERO,
+            quote_volume: Fixed::ZERO,
+            number_of_trades: 0,
+            is_closed: true,
+        }
+    }
+
+    #[test]
+    fn test_boundaries_fire_once_from_timer_or_kline() {
+        let mut bars = BarClock::new(ExchangeClock::new()).with_interval("1m").unwrap().with_interval("1w").unwrap();
+        assert!(bars.poll_at(90_000).is_empty());
+        assert!(bars.poll_at(119_999).is_empty());
+
+        let closes = bars.poll_at(120_000);
+        assert_eq!(closes, vec![BarClose { interval: "1m".to_string(), open_time: 60_000, close_time: 119_999 }]);
+        assert!(bars.poll_at(120_500).is_empty());
+
+        // The 2-3 minute kline arrives first: the timer doesn't fire it again
+        assert_eq!(bars.on_kline(&kline("1m", 179_999)).unwrap().close_time, 179_999);
+        assert!(bars.poll_at(180_010).is_empty());
+        assert!(bars.on_kline(&kline("1m", 179_999)).is_none());
+
+        // A stalled loop reports only the latest bar
+        let closes = bars.poll_at(400_000);
+        assert_eq!(closes[0].open_time, 300_000);
+
+        // Weekly bars close on Monday 00:00 UTC (2024-01-08 here)
+        let closes = bars.poll_at(1_704_672_000_000);
+        assert_eq!(closes.iter().find(|c| c.interval == "1w").unwrap().close_time, 1_704_671_999_999);
+    }
+
+    #[test]
+    fn test_exchange_clock_offset() {
+        let mut clock = ExchangeClock::new();
+        assert_eq!(clock.observe(1_000, 1_350, 1_100), 300);
+        assert_eq!(clock.to_exchange(2_000), 2_300);
+        assert_eq!(clock.to_local(2_300), 2_000);
+        assert!(interval_ms("1M").is_err());
+        assert_eq!(interval_ms("15m").unwrap(), 900_000);
+    }
+
+    #[test]
+    fn test_bar_summary() {
+        let bar = |close_time, high: &str, low: &str, volume: &str, quote_volume: &str| Kline {
+            high: fx(high),
+            low: fx(low),
+            volume: fx(volume),
+            quote_volume: fx(quote_volume),
+            number_of_trades: 10,
+            ..kline("1m", close_time)
+        };
+        let summary = BarSummary::from_klines(&[
+            bar(59_999, "101.00", "99.00", "2.000", "200.000"),
+            bar(119_999, "103.00", "100.00", "1.000", "102.000"),
+        ]).unwrap();
+
+        assert_eq!((summary.open_time, summary.close_time), (0, 119_999));
+        assert_eq!((summary.high, summary.low), (fx("103"), fx("99")));
+        assert_eq!(summary.volume, fx("3"));
+        assert_eq!(summary.vwap, Some(fx("100.666666666666666666666666667")));
+        assert_eq!(summary.number_of_trades, 20);
+        assert!(BarSummary::from_klines(&[]).is_none());
+
+        let mut csv = Vec::new();
+        assert_eq!(write_klines_csv(&mut csv, &[bar(59_999, "101.00", "99.00", "2.000", "200.000")]).unwrap(), 1);
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(csv.lines().nth(1), Some("0,59999,BTCUSDT,1m,0,101.00,99.00,0,2.000,200.000,10"));
+    }
+}
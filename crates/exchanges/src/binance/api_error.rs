@@ -0,0 +1,164 @@
+//! Typed Binance API error codes
+//!
+//! Binance rejects requests with a JSON body such as
+//! `{"code":-1021,"msg":"Timestamp for this request is outside of the recvWindow."}`.
+//! `BinanceApiError::from_response` maps that payload (plus the HTTP status
+//! and `Retry-After` header for 429/418) to a variant callers can match on to
+//! retry, back off or re-sync the clock.
+
+use serde::Deserialize;
+
+/// A rejected Binance request
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum BinanceApiError {
+    /// -1000 / -1001: internal error or disconnect on Binance's side
+    #[error("Binance internal error ({0})")]
+    Internal(i64),
+    /// -1003 or HTTP 429: too many requests
+    #[error("rate limited, retry after {retry_after:?}s")]
+    RateLimited { retry_after: Option<u64> },
+    /// HTTP 418: IP banned for repeatedly ignoring 429s
+    #[error("IP banned, retry after {retry_after:?}s")]
+    IpBanned { retry_after: Option<u64> },
+    /// -1015: too many new orders
+    #[error("order rate limit exceeded")]
+    TooManyOrders,
+    /// -1021: local clock is outside the recvWindow
+    #[error("timestamp outside recvWindow")]
+    TimestampOutOfRecvWindow,
+    /// -1022: signature rejected
+    #[error("invalid signature")]
+    InvalidSignature,
+    /// -2014 / -2015: API key rejected, or IP/permission not allowed
+    #[error("invalid API key, IP or permissions")]
+    InvalidApiKey,
+    /// -1121
+    #[error("invalid symbol")]
+    InvalidSymbol,
+    /// -1013 "Filter failure: LOT_SIZE"
+    #[error("LOT_SIZE filter failure")]
+    LotSizeFilterFailure,
+    /// -1013 "Filter failure: PRICE_FILTER"
+    #[error("PRICE_FILTER filter failure")]
+    PriceFilterFailure,
+    /// -1013 "Filter failure: NOTIONAL" or "MIN_NOTIONAL"
+    #[error("notional filter failure")]
+    NotionalFilterFailure,
+    /// -1013 with any other filter
+    #[error("filter failure: {0}")]
+    FilterFailure(String),
+    /// -2010 "Account has insufficient balance for requested action."
+    #[error("insufficient balance")]
+    InsufficientBalance,
+    /// -2010 for any other reason
+    #[error("new order rejected: {0}")]
+    NewOrderRejected(String),
+    /// -2011 cancel rejected / -2013 order does not exist
+    #[error("unknown order")]
+    UnknownOrder,
+    /// Any other code
+    #[error("code {code}: {msg}")]
+    Other { code: i64, msg: String },
+}
+
+#[derive(Deserialize)]
+struct ErrorPayload {
+    code: i64,
+    msg: String,
+}
+
+impl BinanceApiError {
+    /// Parse a non-200 response; `None` when the body isn't a Binance error payload
+    /// and the status doesn't identify the error on its own
+    pub fn from_response(status: u16, body: &str, headers: &[(String, String)]) -> Option<Self> {
+        let retry_after = headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("retry-after"))
+            .and_then(|(_, value)| value.trim().parse().ok());
+        match status {
+            418 => return Some(Self::IpBanned { retry_after }),
+            429 => return Some(Self::RateLimited { retry_after }),
+            _ => {}
+        }
+
+        let payload: ErrorPayload = serde_json::from_str(body).ok()?;
+        Some(Self::from_code(payload.code, &payload.msg))
+    }
+
+    /// Map an error code and message
+    pub fn from_code(code: i64, msg: &str) -> Self {
+        match code {
+            -1000 | -1001 => Self::Internal(code),
+            -1003 => Self::RateLimited { retry_after: None },
+            -1015 => Self::TooManyOrders,
+            -1021 => Self::TimestampOutOfRecvWindow,
+            -1022 => Self::InvalidSignature,
+            -2014 | -2015 => Self::InvalidApiKey,
+            -1121 => Self::InvalidSymbol,
+            -1013 => match msg.strip_prefix("Filter failure: ").map(str::trim) {
+                Some("LOT_SIZE") | Some("MARKET_LOT_SIZE") => Self::LotSizeFilterFailure,
+                Some("PRICE_FILTER") => Self::PriceFilterFailure,
+                Some("NOTIONAL") | Some("MIN_NOTIONAL") => Self::NotionalFilterFailure,
+                Some(filter) => Self::FilterFailure(filter.to_string()),
+                None => Self::Other { code, msg: msg.to_string() },
+            },
+            -2010 if msg.contains("insufficient balance") => Self::InsufficientBalance,
+            -2010 => Self::NewOrderRejected(msg.to_string()),
+            -2011 | -2013 => Self::UnknownOrder,
+            _ => Self::Other { code, msg: msg.to_string() },
+        }
+    }
+
+    /// Safe to retry the same request after a pause
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::Internal(_) | Self::RateLimited { .. } | Self::TooManyOrders | Self::TimestampOutOfRecvWindow)
+    }
+
+    /// -1013: the order broke a symbol filter, possibly one that just changed
+    pub fn is_filter_failure(&self) -> bool {
+        matches!(self, Self::LotSizeFilterFailure | Self::PriceFilterFailure | Self::NotionalFilterFailure | Self::FilterFailure(_))
+    }
+
+    /// The local clock drifted; re-sync with `server_time()` before retrying
+    pub fn needs_clock_resync(&self) -> bool {
+        matches!(self, Self::TimestampOutOfRecvWindow)
+    }
+
+    /// Seconds the exchange asked us to wait, if any
+    pub fn retry_after(&self) -> Option<u64> {
+        match self {
+            Self::RateLimited { retry_after } | Self::IpBanned { retry_after } => *retry_after,
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_payloads_map_to_variants() {
+        let parse = |status, body: &str| BinanceApiError::from_response(status, body, &[]);
+
+        let clock = parse(400, r#"{"code":-1021,"msg":"Timestamp for this request is outside of the recvWindow."}"#).unwrap();
+        assert_eq!(clock, BinanceApiError::TimestampOutOfRecvWindow);
+        assert!(clock.needs_clock_resync() && clock.is_retryable());
+
+        assert_eq!(parse(400, r#"{"code":-1013,"msg":"Filter failure: LOT_SIZE"}"#), Some(BinanceApiError::LotSizeFilterFailure));
+        assert_eq!(
+            parse(400, r#"{"code":-1013,"msg":"Filter failure: PERCENT_PRICE_BY_SIDE"}"#),
+            Some(BinanceApiError::FilterFailure("PERCENT_PRICE_BY_SIDE".to_string()))
+        );
+        assert_eq!(
+            parse(400, r#"{"code":-2010,"msg":"Account has insufficient balance for requested action."}"#),
+            Some(BinanceApiError::InsufficientBalance)
+        );
+        assert_eq!(parse(400, r#"{"code":-9999,"msg":"new"}"#), Some(BinanceApiError::Other { code: -9999, msg: "new".to_string() }));
+        assert_eq!(parse(502, "<html>Bad Gateway</html>"), None);
+
+        let headers = vec![("Retry-After".to_string(), "30".to_string())];
+        let limited = BinanceApiError::from_response(429, r#"{"code":-1003,"msg":"Too many requests"}"#, &headers).unwrap();
+        assert_eq!(limited.retry_after(), Some(30));
+    }
+}
@@ -5,6 +5,7 @@
 //! - Nanosecond precision timestamps
 //! - Secure credential handling
 
+use crate::client_id::ClientIdPolicy;
 use crate::errors::{ExchangeError, Result};
 use sriquant_core::prelude::*;
 
@@ -332,10 +333,9 @@ impl BinanceSecurity {
         key.len() >= 64 && key.chars().all(|c| c.is_ascii_alphanumeric())
     }
     
-    /// Generate client order ID
+    /// Generate client order ID under Binance's rules
     pub fn generate_client_order_id() -> String {
-        let id = generate_id_with_prefix("SRI");
-        id.replace("-", "").chars().take(36).collect() // Binance limit
+        ClientIdPolicy::binance().generate()
     }
     
     /// Check if timestamp is within acceptable range
@@ -14,7 +14,12 @@ use std::sync::Arc;
 use std::time::Duration;
 use tracing::{info, warn, error, debug};
 use url::Url;
-use flume::{unbounded, Sender, Receiver};
+use sriquant_core::channel::{bounded, Receiver, Sender, TakeOnce, TrySendError};
+
+/// Inbound messages buffered for the consumer; beyond this the reader drops
+/// them rather than stall pings and commands
+const MESSAGE_QUEUE_CAPACITY: usize = 8192;
+const COMMAND_QUEUE_CAPACITY: usize = 64;
 
 /// WebSocket connection state
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -35,6 +40,8 @@ pub struct ConnectionHealth {
     pub ping_latency_micros: u64,
     pub reconnect_count: u32,
     pub message_count: u64,
+    /// Messages dropped because the consumer's queue was full
+    pub dropped_messages: u64,
     pub error_count: u64,
     pub uptime_seconds: u64,
     pub connected_at: u64,
@@ -55,6 +62,7 @@ impl ConnectionHealth {
             ping_latency_micros: 0,
             reconnect_count: 0,
             message_count: 0,
+            dropped_messages: 0,
             error_count: 0,
             uptime_seconds: 0,
             connected_at: 0,
@@ -89,15 +97,28 @@ impl Default for ReconnectConfig {
     }
 }
 
+impl ReconnectConfig {
+    /// Exponential backoff for the given 1-based attempt, capped, plus jitter
+    pub fn backoff_delay_ms(&self, attempt: u32) -> u64 {
+        let delay = self.initial_delay_ms as f64 *
+            self.backoff_multiplier.powi(attempt.saturating_sub(1) as i32);
+        let delay = delay.min(self.max_delay_ms as f64) as u64;
+        
+        // Add jitter
+        let jitter = (rand::random::<f64>() * self.jitter_ms as f64) as u64;
+        delay + jitter
+    }
+}
+
 /// WebSocket connection manager
 pub struct ConnectionManager {
     url: Url,
     health: Arc<std::sync::Mutex<ConnectionHealth>>,
     reconnect_config: ReconnectConfig,
     message_tx: Sender<String>,
-    message_rx: Arc<std::sync::Mutex<Option<Receiver<String>>>>,
+    message_rx: TakeOnce<Receiver<String>>,
     command_tx: Sender<ConnectionCommand>,
-    command_rx: Arc<std::sync::Mutex<Option<Receiver<ConnectionCommand>>>>,
+    command_rx: TakeOnce<Receiver<ConnectionCommand>>,
 }
 
 /// Connection management commands
@@ -114,17 +135,17 @@ pub enum ConnectionCommand {
 impl ConnectionManager {
     /// Create a new connection manager
     pub fn new(url: Url) -> Self {
-        let (message_tx, message_rx) = unbounded();
-        let (command_tx, command_rx) = unbounded();
+        let (message_tx, message_rx) = bounded(MESSAGE_QUEUE_CAPACITY);
+        let (command_tx, command_rx) = bounded(COMMAND_QUEUE_CAPACITY);
         
         Self {
             url,
             health: Arc::new(std::sync::Mutex::new(ConnectionHealth::new())),
             reconnect_config: ReconnectConfig::default(),
             message_tx,
-            message_rx: Arc::new(std::sync::Mutex::new(Some(message_rx))),
+            message_rx: TakeOnce::new(message_rx),
             command_tx,
-            command_rx: Arc::new(std::sync::Mutex::new(Some(command_rx))),
+            command_rx: TakeOnce::new(command_rx),
         }
     }
     
@@ -139,12 +160,9 @@ impl ConnectionManager {
         let message_tx = self.message_tx.clone();
         
         // Take ownership of receivers
-        let command_rx = {
-            let mut rx_guard = self.command_rx.lock().unwrap();
-            rx_guard.take().ok_or_else(|| {
-                ExchangeError::ConnectionFailed("Command receiver already taken".to_string())
-            })?
-        };
+        let mut command_rx = self.command_rx.take().ok_or_else(|| {
+            ExchangeError::ConnectionFailed("Command receiver already taken".to_string())
+        })?;
         
         // Clone necessary data for the async task
         let command_tx = self.command_tx.clone();
@@ -263,16 +281,12 @@ impl ConnectionManager {
                     match monoio::time::timeout(Duration::from_millis(10), websocket.receive_text()).await {
                         Ok(Ok(message)) => {
                             debug!("Received WebSocket message: {}", message);
-                            if let Err(e) = message_tx.send(message) {
-                                warn!("Failed to forward message: {}", e);
-                            } else {
-                                Self::increment_message_count(&health);
-                            }
+                            Self::forward_message(&message_tx, &health, message);
                         }
                         Ok(Err(e)) => {
                             warn!("WebSocket receive error: {}", e);
                             // Trigger reconnect on error
-                            if let Err(e) = command_tx.send(ConnectionCommand::Reconnect) {
+                            if let Err(e) = command_tx.try_send(ConnectionCommand::Reconnect) {
                                 error!("Failed to send reconnect command: {}", e);
                             }
                         }
@@ -289,7 +303,7 @@ impl ConnectionManager {
                         drop(health_guard);
                         warn!("⚠️ Connection unhealthy, triggering reconnect");
                         // Trigger reconnect
-                        if let Err(e) = command_tx.send(ConnectionCommand::Reconnect) {
+                        if let Err(e) = command_tx.try_send(ConnectionCommand::Reconnect) {
                             error!("Failed to send reconnect command: {}", e);
                         }
                     }
@@ -310,14 +324,13 @@ impl ConnectionManager {
     
     /// Send a command to the connection manager
     pub async fn send_command(&self, command: ConnectionCommand) -> Result<()> {
-        self.command_tx.send(command)
+        self.command_tx.send(command).await
             .map_err(|e| ExchangeError::ConnectionFailed(format!("Failed to send command: {e}")))
     }
     
     /// Get message receiver
     pub fn take_message_receiver(&self) -> Result<Receiver<String>> {
-        let mut rx_guard = self.message_rx.lock().unwrap();
-        rx_guard.take().ok_or_else(|| {
+        self.message_rx.take().ok_or_else(|| {
             ExchangeError::ConnectionFailed("Message receiver already taken".to_string())
         })
     }
@@ -378,24 +391,31 @@ impl ConnectionManager {
         health_guard.connected_at = nanos() / 1_000_000;
     }
     
-    fn increment_message_count(health: &Arc<std::sync::Mutex<ConnectionHealth>>) {
-        let mut health_guard = health.lock().unwrap();
-        health_guard.message_count += 1;
+    /// Queue a message for the consumer without waiting; a full queue drops it
+    fn forward_message(message_tx: &Sender<String>, health: &Arc<std::sync::Mutex<ConnectionHealth>>, message: String) {
+        match message_tx.try_send(message) {
+            Ok(()) => health.lock().unwrap().message_count += 1,
+            Err(TrySendError::Full(_)) => {
+                let dropped = {
+                    let mut health_guard = health.lock().unwrap();
+                    health_guard.dropped_messages += 1;
+                    health_guard.dropped_messages
+                };
+                if dropped == 1 || dropped.is_multiple_of(1000) {
+                    warn!("⚠️ Message consumer is falling behind, {} messages dropped", dropped);
+                }
+            }
+            Err(e) => warn!("Failed to forward message: {}", e),
+        }
     }
     
     fn calculate_backoff_delay(attempt: u32, config: &ReconnectConfig) -> u64 {
-        let delay = config.initial_delay_ms as f64 * 
-            config.backoff_multiplier.powi((attempt - 1) as i32);
-        let delay = delay.min(config.max_delay_ms as f64) as u64;
-        
-        // Add jitter
-        let jitter = (rand::random::<f64>() * config.jitter_ms as f64) as u64;
-        delay + jitter
+        config.backoff_delay_ms(attempt)
     }
 }
 
 // Add a simple random number generator for demonstration
-mod rand {
+pub(crate) mod rand {
     use std::sync::atomic::{AtomicU64, Ordering};
     
     static SEED: AtomicU64 = AtomicU64::new(1);
@@ -443,4 +463,18 @@ mod tests {
         let health = manager.health();
         assert_eq!(health.state, ConnectionState::Disconnected);
     }
-}
\ No newline at end of file
+    
+    #[monoio::test]
+    async fn test_full_queue_drops_instead_of_stalling() {
+        let health = Arc::new(std::sync::Mutex::new(ConnectionHealth::new()));
+        let (message_tx, mut message_rx) = bounded(2);
+        
+        for i in 0..3 {
+            ConnectionManager::forward_message(&message_tx, &health, format!("msg{i}"));
+        }
+        let snapshot = health.lock().unwrap().clone();
+        assert_eq!((snapshot.message_count, snapshot.dropped_messages), (2, 1));
+        assert_eq!(message_rx.try_recv().as_deref(), Ok("msg0"));
+        assert_eq!(message_rx.try_recv().as_deref(), Ok("msg1"));
+    }
+}
@@ -0,0 +1,692 @@
+//! Unified `Exchange`, `TradingExchange` and `StreamingExchange` traits for Binance
+//!
+//! Converts Binance's string-typed REST and WebSocket payloads into the
+//! generic types in `crate::types`, so strategies can be written against the
+//! traits and run on any venue.
+
+use crate::binance::rest::{BinanceRestClient, CancelOrderResponse, MyTradeResponse, NewOrderResponse, QueryOrderResponse, SymbolInfo, TestOrderParams, Ticker24hr, TradeResponse};
+use crate::binance::filters::{render_price, render_quantity};
+use crate::binance::websocket::BinanceWebSocketClient;
+use crate::binance::BinanceExchange;
+use crate::errors::{ExchangeError, Result};
+use crate::traits::{Exchange, StreamingExchange, TradingExchange};
+use crate::types::*;
+use sriquant_core::prelude::*;
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use tracing::{debug, info, warn};
+
+impl BinanceExchange {
+    fn rest(&self) -> Result<&BinanceRestClient> {
+        self.rest_client.as_ref()
+            .ok_or_else(|| ExchangeError::ClientNotInitialized("REST client not initialized".to_string()))
+    }
+
+    fn websocket(&mut self) -> Result<&mut BinanceWebSocketClient> {
+        self.websocket_client.as_mut()
+            .ok_or_else(|| ExchangeError::ClientNotInitialized("WebSocket client not initialized".to_string()))
+    }
+}
+
+#[async_trait(?Send)]
+impl Exchange for BinanceExchange {
+    fn name(&self) -> &str {
+        "binance"
+    }
+
+    async fn ping(&self) -> Result<u64> {
+        BinanceExchange::ping(self).await
+    }
+
+    async fn server_time(&self) -> Result<u64> {
+        self.rest()?.server_time().await
+    }
+
+    async fn exchange_info(&self) -> Result<HashMap<String, Symbol>> {
+        let info = BinanceExchange::exchange_info(self).await?;
+        if !self.config.lenient_exchange_info {
+            return info.symbols
+                .iter()
+                .map(|s| Ok((s.symbol.clone(), symbol_from_info(s)?)))
+                .collect();
+        }
+        Ok(info.symbols
+            .iter()
+            .filter_map(|s| match symbol_from_info(s) {
+                Ok(symbol) => Some((s.symbol.clone(), symbol)),
+                Err(e) => {
+                    warn!("⚠️  Skipping symbol {}: {}", s.symbol, e);
+                    None
+                }
+            })
+            .collect())
+    }
+
+    async fn account_info(&self) -> Result<AccountInfo> {
+        let account = self.rest()?.get_account_info().await?;
+        Ok(AccountInfo {
+            account_type: account.account_type,
+            can_trade: account.can_trade,
+            can_withdraw: account.can_withdraw,
+            can_deposit: account.can_deposit,
+            balances: account.balances.iter()
+                .map(|b| Balance { asset: b.asset.clone(), free: b.free, locked: b.locked })
+                .collect(),
+            update_time: account.update_time,
+        })
+    }
+
+    async fn balances(&self) -> Result<Vec<Balance>> {
+        if self.config.portfolio_margin {
+            // The spot wallet is empty under portfolio margin; report the cross margin wallet
+            let balances = self.rest()?.get_portfolio_margin_balances().await?;
+            return balances.iter()
+                .map(|b| b.to_balance())
+                .filter(|b| !b.as_ref().is_ok_and(|b| b.total().is_zero()))
+                .collect();
+        }
+        let account = Exchange::account_info(self).await?;
+        Ok(account.balances.into_iter().filter(|b| !b.total().is_zero()).collect())
+    }
+
+    async fn ticker(&self, symbol: &str) -> Result<Ticker> {
+        ticker_from_24hr(&self.rest()?.ticker_24hr(symbol).await?)
+    }
+
+    async fn order_book(&self, symbol: &str, limit: Option<u32>) -> Result<OrderBook> {
+        let book = self.rest()?.order_book(symbol, limit).await?;
+        let levels = |side: &[[String; 2]]| {
+            side.iter()
+                .map(|[price, qty]| Ok(OrderBookLevel { price: Fixed::from_str_exact(price)?, quantity: unbounded(qty)? }))
+                .collect::<Result<Vec<_>>>()
+        };
+        Ok(OrderBook {
+            symbol: symbol.to_string(),
+            bids: levels(&book.bids)?,
+            asks: levels(&book.asks)?,
+            timestamp: nanos() / 1_000_000,
+            update_id: book.last_update_id,
+        })
+    }
+
+    async fn recent_trades(&self, symbol: &str, limit: Option<u32>) -> Result<Vec<Trade>> {
+        self.rest()?.recent_trades(symbol, limit).await?
+            .iter()
+            .map(|t| trade_from_public(symbol, t))
+            .collect()
+    }
+
+    async fn klines(
+        &self,
+        symbol: &str,
+        interval: &str,
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<Vec<Kline>> {
+        let now = nanos() / 1_000_000;
+        self.rest()?.get_klines_raw(symbol, interval, start_time, end_time, limit).await?
+            .iter()
+            .map(|k| k.to_kline(symbol, interval, now))
+            .collect()
+    }
+}
+
+#[async_trait(?Send)]
+impl TradingExchange for BinanceExchange {
+    async fn place_order(&self, request: OrderRequest) -> Result<OrderResponse> {
+        let rest = self.rest()?;
+        let mut rules = rest.order_rules(&request.symbol).await?;
+        let side = request.side.to_string();
+        let order_type = request.order_type.to_string();
+        // Binance requires a time in force on limit orders
+        let time_in_force = match (request.time_in_force, request.order_type) {
+            (Some(tif), _) => Some(tif.to_string()),
+            (None, OrderType::Limit | OrderType::StopLossLimit) => Some(TimeInForce::GoodTillCanceled.to_string()),
+            (None, _) => None,
+        };
+
+        let mut refreshed = false;
+        let (response, quantity) = loop {
+            let quantity = render_quantity(rules.as_ref(), request.quantity, request.order_type == OrderType::Market);
+            let price = request.price.map(|p| render_price(rules.as_ref(), p));
+            let stop_price = request.stop_price.map(|p| render_price(rules.as_ref(), p));
+            let params = TestOrderParams {
+                symbol: &request.symbol,
+                side: &side,
+                order_type: &order_type,
+                quantity: Some(&quantity),
+                price: price.as_deref(),
+                time_in_force: time_in_force.as_deref(),
+                stop_price: stop_price.as_deref(),
+                iceberg_qty: None,
+                new_client_order_id: request.client_order_id.as_deref(),
+            };
+            match rest.new_order(&params).await {
+                // A filter failure with cached rules may mean the tick or step
+                // just changed: refetch once and re-quote on the new grid
+                Err(e) if rules.is_some() && !refreshed && is_filter_failure(&e) => {
+                    refreshed = true;
+                    match rest.refresh_rules_for(&request.symbol).await? {
+                        Some(change) => {
+                            info!("📐 Re-quoting {} order under new trading rules", request.symbol);
+                            rules = Some(change.current);
+                        }
+                        None => return Err(e),
+                    }
+                }
+                result => break (result?, quantity),
+            }
+        };
+        info!("📝 {} {} {} placed as {}", request.symbol, side, quantity, response.order_id);
+        let mut order = order_from_new(&response)?;
+        order.stop_price = request.stop_price;
+        Ok(order)
+    }
+
+    async fn cancel_order(&self, symbol: &str, order_id: &str) -> Result<OrderResponse> {
+        order_from_cancel(&self.rest()?.cancel_order(symbol, parse_order_id(order_id)?).await?)
+    }
+
+    async fn cancel_all_orders(&self, symbol: &str) -> Result<Vec<OrderResponse>> {
+        self.rest()?.cancel_all_orders(symbol).await?
+            .iter()
+            .map(order_from_cancel)
+            .collect()
+    }
+
+    async fn get_order(&self, symbol: &str, order_id: &str) -> Result<OrderResponse> {
+        order_from_query(&self.rest()?.query_order(symbol, parse_order_id(order_id)?).await?)
+    }
+
+    async fn open_orders(&self, symbol: Option<&str>) -> Result<Vec<OrderResponse>> {
+        self.rest()?.open_orders(symbol).await?
+            .iter()
+            .map(order_from_query)
+            .collect()
+    }
+
+    async fn order_history(
+        &self,
+        symbol: &str,
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<Vec<OrderResponse>> {
+        self.rest()?.get_all_orders(symbol, limit, start_time, end_time).await?
+            .iter()
+            .map(order_from_query)
+            .collect()
+    }
+
+    async fn trade_history(
+        &self,
+        symbol: &str,
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<Vec<Trade>> {
+        self.rest()?.my_trades_in_range(symbol, start_time, end_time, limit).await?
+            .iter()
+            .map(trade_from_own)
+            .collect()
+    }
+}
+
+#[async_trait(?Send)]
+impl StreamingExchange for BinanceExchange {
+    async fn connect(&mut self) -> Result<()> {
+        match self.websocket_client.as_mut() {
+            Some(ws) if ws.is_connected() => Ok(()),
+            Some(ws) => ws.connect().await,
+            None => self.init_websocket().await,
+        }
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        self.pending_events.clear();
+        match self.websocket_client.as_mut() {
+            Some(ws) => ws.close().await,
+            None => Ok(()),
+        }
+    }
+
+    async fn subscribe_ticker(&mut self, symbol: &str) -> Result<()> {
+        self.websocket()?.subscribe_ticker(symbol).await
+    }
+
+    async fn subscribe_trades(&mut self, symbol: &str) -> Result<()> {
+        self.websocket()?.subscribe_trades(symbol).await
+    }
+
+    async fn subscribe_order_book(&mut self, symbol: &str, levels: Option<u32>) -> Result<()> {
+        self.websocket()?.subscribe_depth(symbol, levels).await
+    }
+
+    async fn subscribe_klines(&mut self, symbol: &str, interval: &str) -> Result<()> {
+        self.websocket()?.subscribe_klines(symbol, interval).await
+    }
+
+    async fn unsubscribe(&mut self, stream: &str) -> Result<()> {
+        self.websocket()?.unsubscribe(stream).await
+    }
+
+    async fn next_event(&mut self) -> Result<Option<MarketData>> {
+        loop {
+            if let Some(event) = self.pending_events.pop_front() {
+                return Ok(Some(event));
+            }
+            let ws = self.websocket()?;
+            if !ws.is_connected() {
+                return Ok(None);
+            }
+            let event = ws.receive_message().await?;
+            self.pending_events.extend(market_data_from_event(event)?);
+        }
+    }
+
+    fn connection_status(&self) -> ConnectionStatus {
+        match &self.websocket_client {
+            Some(ws) if ws.is_connected() => ConnectionStatus::Connected,
+            _ => ConnectionStatus::Disconnected,
+        }
+    }
+
+    fn subscriptions(&self) -> Vec<Subscription> {
+        let Some(ws) = &self.websocket_client else {
+            return Vec::new();
+        };
+        ws.get_subscriptions()
+            .into_iter()
+            .map(|stream| Subscription {
+                symbol: stream.split('@').next().unwrap_or_default().to_uppercase(),
+                stream,
+                status: SubscriptionStatus::Subscribed,
+                last_update: 0,
+            })
+            .collect()
+    }
+}
+
+/// Parse a quantity, volume or filter bound, keeping values beyond the `Fixed` range exact
+///
+/// Volumes, balances of low-priced assets and filter maxima routinely exceed
+/// ±999999. They are kept as `Fixed::from_str_unbounded` reads them, the same
+/// as `serde_str` fields, rather than clamped or rejected.
+pub(crate) fn unbounded(value: &str) -> Result<Fixed> {
+    Fixed::from_str_unbounded(value).map_err(|_| ExchangeError::InvalidResponse(format!("Invalid decimal: {value}")))
+}
+
+/// Decimal places in a step such as "0.00100000"
+fn step_precision(step: &str) -> u32 {
+    step.split_once('.')
+        .map(|(_, fraction)| fraction.trim_end_matches('0').len() as u32)
+        .unwrap_or(0)
+}
+
+fn parse_order_id(order_id: &str) -> Result<u64> {
+    order_id.parse()
+        .map_err(|_| ExchangeError::InvalidOrder(format!("Invalid Binance order id: {order_id}")))
+}
+
+fn symbol_from_info(info: &SymbolInfo) -> Result<Symbol> {
+    let filter = |name: &str| info.filters.iter().find(|f| f["filterType"] == name);
+    let field = |filter: Option<&serde_json::Value>, key: &str| -> Result<Fixed> {
+        filter.and_then(|f| f[key].as_str()).map(unbounded).unwrap_or(Ok(Fixed::ZERO))
+    };
+
+    let lot_size = filter("LOT_SIZE");
+    let price_filter = filter("PRICE_FILTER");
+    let notional = filter("NOTIONAL").or_else(|| filter("MIN_NOTIONAL"));
+    let precision = |filter: Option<&serde_json::Value>, key: &str| {
+        filter.and_then(|f| f[key].as_str()).map(step_precision).unwrap_or(8)
+    };
+
+    Ok(Symbol {
+        symbol: info.symbol.clone(),
+        base_asset: info.base_asset.clone(),
+        quote_asset: info.quote_asset.clone(),
+        status: info.status.clone(),
+        min_quantity: field(lot_size, "minQty")?,
+        max_quantity: field(lot_size, "maxQty")?,
+        quantity_precision: precision(lot_size, "stepSize"),
+        min_price: field(price_filter, "minPrice")?,
+        max_price: field(price_filter, "maxPrice")?,
+        price_precision: precision(price_filter, "tickSize"),
+        min_notional: field(notional, "minNotional")?,
+    })
+}
+
+fn ticker_from_24hr(ticker: &Ticker24hr) -> Result<Ticker> {
+    Ok(Ticker {
+        symbol: ticker.symbol.clone(),
+        price: ticker.last_price,
+        price_change: ticker.price_change,
+        price_change_percent: ticker.price_change_percent,
+        high: ticker.high_price,
+        low: ticker.low_price,
+        volume: ticker.volume,
+        quote_volume: ticker.quote_volume,
+        timestamp: ticker.close_time,
+    })
+}
+
+fn trade_from_public(symbol: &str, trade: &TradeResponse) -> Result<Trade> {
+    Ok(Trade {
+        id: trade.id.to_string(),
+        symbol: symbol.to_string(),
+        price: Fixed::from_str_exact(&trade.price)?,
+        quantity: unbounded(&trade.qty)?,
+        // A maker buyer means the aggressor sold
+        side: if trade.is_buyer_maker { OrderSide::Sell } else { OrderSide::Buy },
+        timestamp: trade.time,
+        is_buyer_maker: trade.is_buyer_maker,
+    })
+}
+
+fn trade_from_own(trade: &MyTradeResponse) -> Result<Trade> {
+    Ok(Trade {
+        id: trade.id.to_string(),
+        symbol: trade.symbol.clone(),
+        price: trade.price,
+        quantity: trade.qty,
+        side: if trade.is_buyer { OrderSide::Buy } else { OrderSide::Sell },
+        timestamp: trade.time,
+        is_buyer_maker: trade.is_buyer == trade.is_maker,
+    })
+}
+
+pub(crate) fn parse_side(side: &str) -> Result<OrderSide> {
+    match side {
+        "BUY" => Ok(OrderSide::Buy),
+        "SELL" => Ok(OrderSide::Sell),
+        other => Err(ExchangeError::InvalidResponse(format!("Unknown order side: {other}"))),
+    }
+}
+
+pub(crate) fn parse_order_type(order_type: &str) -> Result<OrderType> {
+    match order_type {
+        "MARKET" => Ok(OrderType::Market),
+        "LIMIT" | "LIMIT_MAKER" => Ok(OrderType::Limit),
+        "STOP_LOSS" | "TAKE_PROFIT" => Ok(OrderType::StopLoss),
+        "STOP_LOSS_LIMIT" | "TAKE_PROFIT_LIMIT" => Ok(OrderType::StopLossLimit),
+        other => Err(ExchangeError::InvalidResponse(format!("Unknown order type: {other}"))),
+    }
+}
+
+pub(crate) fn parse_status(status: &str) -> Result<OrderStatus> {
+    match status {
+        "NEW" | "PENDING_NEW" => Ok(OrderStatus::New),
+        "PARTIALLY_FILLED" => Ok(OrderStatus::PartiallyFilled),
+        "FILLED" => Ok(OrderStatus::Filled),
+        "CANCELED" | "PENDING_CANCEL" => Ok(OrderStatus::Canceled),
+        "REJECTED" => Ok(OrderStatus::Rejected),
+        "EXPIRED" | "EXPIRED_IN_MATCH" => Ok(OrderStatus::Expired),
+        other => Err(ExchangeError::InvalidResponse(format!("Unknown order status: {other}"))),
+    }
+}
+
+/// The venue rejected an order for breaking a symbol filter (-1013)
+fn is_filter_failure(error: &ExchangeError) -> bool {
+    matches!(error.root(), ExchangeError::BinanceApi(_, api) if api.is_filter_failure())
+}
+
+fn parse_time_in_force(tif: &str) -> Option<TimeInForce> {
+    match tif {
+        "GTC" => Some(TimeInForce::GoodTillCanceled),
+        "IOC" => Some(TimeInForce::ImmediateOrCancel),
+        "FOK" => Some(TimeInForce::FillOrKill),
+        _ => None,
+    }
+}
+
+/// Zero prices mean "not set" (market orders, no stop)
+fn optional_price(price: Fixed) -> Option<Fixed> {
+    (price > Fixed::ZERO).then_some(price)
+}
+
+/// Average fill price from cumulative quote and executed quantity
+///
+/// Computed in `f64` because the cumulative quote can exceed the `Fixed` range.
+fn average_price(cumulative_quote: Fixed, executed: Fixed) -> Option<Fixed> {
+    if executed <= Fixed::ZERO {
+        return None;
+    }
+    Fixed::from_f64(cumulative_quote.to_f64_lossy() / executed.to_f64_lossy()).ok().map(|p| p.round_dp(8))
+}
+
+/// Fields shared by Binance's new, cancel and query order responses
+struct OrderFields<'a> {
+    symbol: &'a str,
+    order_id: u64,
+    client_order_id: &'a str,
+    side: &'a str,
+    order_type: &'a str,
+    status: &'a str,
+    time_in_force: &'a str,
+    price: Fixed,
+    orig_qty: Fixed,
+    executed_qty: Fixed,
+    cumulative_quote_qty: Fixed,
+}
+
+impl OrderFields<'_> {
+    fn into_order(self, stop_price: Option<Fixed>, timestamp: u64, update_time: u64) -> Result<OrderResponse> {
+        let filled_quantity = self.executed_qty;
+        Ok(OrderResponse {
+            order_id: self.order_id.to_string(),
+            client_order_id: self.client_order_id.to_string(),
+            symbol: self.symbol.to_string(),
+            side: parse_side(self.side)?,
+            order_type: parse_order_type(self.order_type)?,
+            quantity: self.orig_qty,
+            price: optional_price(self.price),
+            stop_price,
+            status: parse_status(self.status)?,
+            filled_quantity,
+            average_price: average_price(self.cumulative_quote_qty, filled_quantity),
+            time_in_force: parse_time_in_force(self.time_in_force),
+            timestamp,
+            update_time,
+        })
+    }
+}
+
+fn order_from_new(r: &NewOrderResponse) -> Result<OrderResponse> {
+    OrderFields {
+        symbol: &r.symbol,
+        order_id: r.order_id,
+        client_order_id: &r.client_order_id,
+        side: &r.side,
+        order_type: &r.order_type,
+        status: &r.status,
+        time_in_force: &r.time_in_force,
+        price: r.price,
+        orig_qty: r.orig_qty,
+        executed_qty: r.executed_qty,
+        cumulative_quote_qty: r.cumulative_quote_qty,
+    }
+    .into_order(None, r.transact_time, r.transact_time)
+}
+
+fn order_from_cancel(r: &CancelOrderResponse) -> Result<OrderResponse> {
+    let now = nanos() / 1_000_000;
+    OrderFields {
+        symbol: &r.symbol,
+        order_id: r.order_id,
+        client_order_id: &r.orig_client_order_id,
+        side: &r.side,
+        order_type: &r.order_type,
+        status: &r.status,
+        time_in_force: &r.time_in_force,
+        price: r.price,
+        orig_qty: r.orig_qty,
+        executed_qty: r.executed_qty,
+        cumulative_quote_qty: r.cumulative_quote_qty,
+    }
+    .into_order(None, now, now)
+}
+
+fn order_from_query(r: &QueryOrderResponse) -> Result<OrderResponse> {
+    OrderFields {
+        symbol: &r.symbol,
+        order_id: r.order_id,
+        client_order_id: &r.client_order_id,
+        side: &r.side,
+        order_type: &r.order_type,
+        status: &r.status,
+        time_in_force: &r.time_in_force,
+        price: r.price,
+        orig_qty: r.orig_qty,
+        executed_qty: r.executed_qty,
+        cumulative_quote_qty: r.cumulative_quote_qty,
+    }
+    .into_order(optional_price(r.stop_price), r.time, r.update_time)
+}
+
+fn ticker_from_mini(t: &MiniTickerUpdate) -> Result<Ticker> {
+    let price_change = t.close - t.open;
+    let price_change_percent = if t.open > Fixed::ZERO {
+        Fixed::from_f64(price_change.to_f64_lossy() / t.open.to_f64_lossy() * 100.0)?.round_dp(3)
+    } else {
+        Fixed::ZERO
+    };
+    Ok(Ticker {
+        symbol: t.symbol.clone(),
+        price: t.close,
+        price_change,
+        price_change_percent,
+        high: t.high,
+        low: t.low,
+        volume: t.base_volume,
+        quote_volume: t.quote_volume,
+        timestamp: t.timestamp,
+    })
+}
+
+/// Map a stream event to unified market data
+///
+/// Batches are split into one event per symbol; book tickers have no unified
+/// counterpart and are dropped. An aggregated trade becomes one trade with
+/// its combined quantity. Fields a stream doesn't carry are zero.
+fn market_data_from_event(event: MarketDataEvent) -> Result<Vec<MarketData>> {
+    Ok(match event {
+        MarketDataEvent::Ticker(t) => vec![MarketData::Ticker(Ticker {
+            symbol: t.symbol,
+            price: t.price,
+            price_change: t.price_change,
+            price_change_percent: Fixed::ZERO,
+            high: Fixed::ZERO,
+            low: Fixed::ZERO,
+            volume: t.volume,
+            quote_volume: Fixed::ZERO,
+            timestamp: t.timestamp,
+        })],
+        MarketDataEvent::Depth(d) => vec![MarketData::OrderBook(OrderBook {
+            symbol: d.symbol,
+            bids: d.bids,
+            asks: d.asks,
+            timestamp: d.timestamp,
+            update_id: d.update_id,
+        })],
+        MarketDataEvent::Trade(t) => vec![MarketData::Trade(Trade {
+            id: t.trade_id.to_string(),
+            symbol: t.symbol,
+            price: t.price,
+            quantity: t.quantity,
+            side: match t.side {
+                TradeSide::Buy => OrderSide::Buy,
+                TradeSide::Sell => OrderSide::Sell,
+            },
+            timestamp: t.timestamp,
+            is_buyer_maker: matches!(t.side, TradeSide::Sell),
+        })],
+        MarketDataEvent::AggTrade(t) => vec![MarketData::Trade(Trade {
+            id: t.agg_trade_id.to_string(),
+            symbol: t.symbol,
+            price: t.price,
+            quantity: t.quantity,
+            side: match t.side {
+                TradeSide::Buy => OrderSide::Buy,
+                TradeSide::Sell => OrderSide::Sell,
+            },
+            timestamp: t.timestamp,
+            is_buyer_maker: matches!(t.side, TradeSide::Sell),
+        })],
+        MarketDataEvent::Kline(k) => vec![MarketData::Kline(Kline {
+            symbol: k.symbol,
+            interval: k.interval,
+            open_time: k.open_time,
+            close_time: k.close_time,
+            open: k.open,
+            high: k.high,
+            low: k.low,
+            close: k.close,
+            volume: k.volume,
+            quote_volume: Fixed::ZERO,
+            number_of_trades: 0,
+            is_closed: k.is_closed,
+        })],
+        MarketDataEvent::MiniTicker(t) => vec![MarketData::Ticker(ticker_from_mini(&t)?)],
+        MarketDataEvent::MiniTickerBatch(batch) => batch.iter()
+            .map(|t| ticker_from_mini(t).map(MarketData::Ticker))
+            .collect::<Result<_>>()?,
+        MarketDataEvent::BookTicker(_) | MarketDataEvent::BookTickerBatch(_) => {
+            debug!("Skipping book ticker event with no unified counterpart");
+            Vec::new()
+        }
+        MarketDataEvent::Reconnected(info) => {
+            debug!("WebSocket reconnected after {} attempts", info.attempts);
+            Vec::new()
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binance::BinanceConfig;
+
+    #[test]
+    fn test_symbol_and_order_conversion() {
+        let info: SymbolInfo = serde_json::from_str(r#"{
+            "symbol": "BTCUSDT", "status": "TRADING", "baseAsset": "BTC", "quoteAsset": "USDT",
+            "filters": [
+                {"filterType": "PRICE_FILTER", "minPrice": "0.01000000", "maxPrice": "1000000.00000000", "tickSize": "0.01000000"},
+                {"filterType": "LOT_SIZE", "minQty": "0.00001000", "maxQty": "9000.00000000", "stepSize": "0.00001000"},
+                {"filterType": "NOTIONAL", "minNotional": "5.00000000"}
+            ]
+        }"#).unwrap();
+        let symbol = symbol_from_info(&info).unwrap();
+        assert_eq!(symbol.price_precision, 2);
+        assert_eq!(symbol.quantity_precision, 5);
+        assert_eq!(symbol.max_price.to_string(), "1000000.00000000");
+        assert_eq!(symbol.min_notional, Fixed::from_str_exact("5").unwrap());
+
+        let query: QueryOrderResponse = serde_json::from_str(r#"{
+            "symbol": "BTCUSDT", "orderId": 28, "orderListId": -1, "clientOrderId": "abc",
+            "price": "50000.00", "origQty": "0.002", "executedQty": "0.001", "cummulativeQuoteQty": "49.99",
+            "status": "PARTIALLY_FILLED", "timeInForce": "GTC", "type": "LIMIT_MAKER", "side": "BUY",
+            "stopPrice": "0.00", "icebergQty": "0.0", "time": 1, "updateTime": 2, "isWorking": true,
+            "origQuoteOrderQty": "0.0"
+        }"#).unwrap();
+        let order = order_from_query(&query).unwrap();
+        assert_eq!(order.order_id, "28");
+        assert_eq!(order.order_type, OrderType::Limit);
+        assert_eq!(order.status, OrderStatus::PartiallyFilled);
+        assert_eq!(order.stop_price, None);
+        assert_eq!(order.average_price, Some(Fixed::from_str_exact("49990").unwrap()));
+    }
+
+    #[monoio::test]
+    async fn test_uninitialized_clients() {
+        let mut exchange = BinanceExchange::new(BinanceConfig::testnet()).await.unwrap();
+        assert_eq!(Exchange::name(&exchange), "binance");
+        assert!(matches!(Exchange::server_time(&exchange).await, Err(ExchangeError::ClientNotInitialized(_))));
+        assert!(matches!(exchange.get_order("BTCUSDT", "1").await, Err(ExchangeError::ClientNotInitialized(_))));
+        assert!(matches!(exchange.subscribe_trades("BTCUSDT").await, Err(ExchangeError::ClientNotInitialized(_))));
+        assert_eq!(exchange.connection_status(), ConnectionStatus::Disconnected);
+        assert!(exchange.subscriptions().is_empty());
+    }
+}
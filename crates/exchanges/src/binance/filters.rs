@@ -0,0 +1,389 @@
+//! Typed exchangeInfo symbol filters
+//!
+//! `SymbolRules` parses the PRICE_FILTER, LOT_SIZE, MARKET_LOT_SIZE,
+//! MIN_NOTIONAL/NOTIONAL and ICEBERG_PARTS entries of `SymbolInfo.filters`
+//! and snaps orders onto the tick and step grid before they are sent, so a
+//! price like 50000.123 becomes 50000.12 instead of a -1013 rejection.
+//! Other filter types are ignored.
+//!
+//! Binance occasionally changes a symbol's tick or step size. Comparing
+//! cached rules with a fresh exchangeInfo gives an `InstrumentChanged`, whose
+//! `requote` says what a working order has to become under the new rules.
+//!
+//! `format_price` and `format_quantity` render the snapped values with
+//! exactly the decimals the tick and step allow. Binance rejects numbers
+//! carrying more precision than the filter (-1111), even as trailing zeros.
+
+use crate::binance::exchange::unbounded;
+use crate::binance::rest::SymbolInfo;
+use crate::errors::{ExchangeError, Result};
+use sriquant_core::prelude::*;
+
+use serde::Deserialize;
+
+/// PRICE_FILTER; zero bounds or tick mean the check is disabled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PriceFilter {
+    pub min_price: Fixed,
+    pub max_price: Fixed,
+    pub tick_size: Fixed,
+}
+
+/// LOT_SIZE or MARKET_LOT_SIZE
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LotSizeFilter {
+    pub min_qty: Fixed,
+    pub max_qty: Fixed,
+    pub step_size: Fixed,
+}
+
+/// MIN_NOTIONAL, or the newer NOTIONAL which also has a maximum
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotionalFilter {
+    pub min_notional: Fixed,
+    pub max_notional: Option<Fixed>,
+    pub apply_to_market: bool,
+}
+
+/// One entry of `SymbolInfo.filters`, numbers still as sent
+#[derive(Deserialize)]
+#[serde(tag = "filterType")]
+enum FilterWire {
+    #[serde(rename = "PRICE_FILTER", rename_all = "camelCase")]
+    Price { min_price: String, max_price: String, tick_size: String },
+    #[serde(rename = "LOT_SIZE", rename_all = "camelCase")]
+    LotSize { min_qty: String, max_qty: String, step_size: String },
+    #[serde(rename = "MARKET_LOT_SIZE", rename_all = "camelCase")]
+    MarketLotSize { min_qty: String, max_qty: String, step_size: String },
+    #[serde(rename = "MIN_NOTIONAL", rename_all = "camelCase")]
+    MinNotional { min_notional: String, #[serde(default)] apply_to_market: bool },
+    #[serde(rename = "NOTIONAL", rename_all = "camelCase")]
+    Notional { min_notional: String, max_notional: Option<String>, #[serde(default)] apply_min_to_market: bool },
+    #[serde(rename = "ICEBERG_PARTS")]
+    IcebergParts { limit: u32 },
+    #[serde(other)]
+    Other,
+}
+
+impl LotSizeFilter {
+    fn parse(min_qty: &str, max_qty: &str, step_size: &str) -> Result<Self> {
+        Ok(Self { min_qty: unbounded(min_qty)?, max_qty: unbounded(max_qty)?, step_size: unbounded(step_size)? })
+    }
+}
+
+/// Largest `base + n * step` not above `value`
+fn floor_to_step(value: Fixed, base: Fixed, step: Fixed) -> Fixed {
+    if step <= Fixed::ZERO || value <= base {
+        return value;
+    }
+    base + ((value - base) / step).trunc_with_scale(0) * step
+}
+
+/// `base + n * step` nearest to `value`
+fn round_to_step(value: Fixed, base: Fixed, step: Fixed) -> Fixed {
+    if step <= Fixed::ZERO || value <= base {
+        return value;
+    }
+    base + ((value - base) / step).round_dp(0) * step
+}
+
+/// Decimal places a tick or step size allows, e.g. 0.01000000 gives 2
+pub fn step_decimals(step: Fixed) -> u32 {
+    let text = step.to_string_exact();
+    match text.split_once('.') {
+        Some((_, fraction)) => fraction.trim_end_matches('0').len() as u32,
+        None => 0,
+    }
+}
+
+/// Render `value` with exactly `decimals` places, or with trailing zeros
+/// trimmed when `None`; never in exponent form
+pub fn render_decimal(value: Fixed, decimals: Option<u32>) -> String {
+    match decimals {
+        Some(decimals) => value.round_dp(decimals).to_string_with_scale(decimals),
+        None => {
+            let text = value.to_string_exact();
+            if text.contains('.') {
+                text.trim_end_matches('0').trim_end_matches('.').to_string()
+            } else {
+                text
+            }
+        }
+    }
+}
+
+/// `SymbolRules::format_price`, or the trimmed price when there are no rules
+pub fn render_price(rules: Option<&SymbolRules>, price: Fixed) -> String {
+    rules.map_or_else(|| render_decimal(price, None), |r| r.format_price(price))
+}
+
+/// `SymbolRules::format_quantity`, or the trimmed quantity when there are no rules
+pub fn render_quantity(rules: Option<&SymbolRules>, quantity: Fixed, market: bool) -> String {
+    rules.map_or_else(|| render_decimal(quantity, None), |r| r.format_quantity(quantity, market))
+}
+
+/// Trading rules for one symbol
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SymbolRules {
+    pub symbol: String,
+    pub price: Option<PriceFilter>,
+    pub lot_size: Option<LotSizeFilter>,
+    pub market_lot_size: Option<LotSizeFilter>,
+    pub notional: Option<NotionalFilter>,
+    /// Most parts an iceberg order may be split into
+    pub iceberg_parts: Option<u32>,
+}
+
+impl SymbolRules {
+    pub fn from_info(info: &SymbolInfo) -> Result<Self> {
+        let mut rules = Self { symbol: info.symbol.clone(), ..Self::default() };
+        for value in &info.filters {
+            let filter = FilterWire::deserialize(value)
+                .map_err(|e| ExchangeError::from(e).with_symbol(&info.symbol).with_payload(&value.to_string()))?;
+            match filter {
+                FilterWire::Price { min_price, max_price, tick_size } => {
+                    rules.price = Some(PriceFilter {
+                        min_price: unbounded(&min_price)?,
+                        max_price: unbounded(&max_price)?,
+                        tick_size: unbounded(&tick_size)?,
+                    });
+                }
+                FilterWire::LotSize { min_qty, max_qty, step_size } => {
+                    rules.lot_size = Some(LotSizeFilter::parse(&min_qty, &max_qty, &step_size)?);
+                }
+                FilterWire::MarketLotSize { min_qty, max_qty, step_size } => {
+                    rules.market_lot_size = Some(LotSizeFilter::parse(&min_qty, &max_qty, &step_size)?);
+                }
+                FilterWire::MinNotional { min_notional, apply_to_market } => {
+                    // NOTIONAL supersedes MIN_NOTIONAL when both are listed
+                    if rules.notional.is_none() {
+                        rules.notional = Some(NotionalFilter { min_notional: unbounded(&min_notional)?, max_notional: None, apply_to_market });
+                    }
+                }
+                FilterWire::Notional { min_notional, max_notional, apply_min_to_market } => {
+                    rules.notional = Some(NotionalFilter {
+                        min_notional: unbounded(&min_notional)?,
+                        max_notional: max_notional.as_deref().map(unbounded).transpose()?,
+                        apply_to_market: apply_min_to_market,
+                    });
+                }
+                FilterWire::IcebergParts { limit } => rules.iceberg_parts = Some(limit),
+                FilterWire::Other => {}
+            }
+        }
+        Ok(rules)
+    }
+
+    /// Snap a limit price to the nearest tick
+    pub fn round_price(&self, price: Fixed) -> Fixed {
+        match self.price {
+            Some(filter) => round_to_step(price, filter.min_price, filter.tick_size),
+            None => price,
+        }
+    }
+
+    /// Round a quantity down to the step, so an order never grows
+    ///
+    /// Market orders use the MARKET_LOT_SIZE step where it sets one, LOT_SIZE's otherwise.
+    pub fn round_quantity(&self, quantity: Fixed, market: bool) -> Fixed {
+        match self.lot_filter(market) {
+            Some(filter) => floor_to_step(quantity, filter.min_qty, filter.step_size),
+            None => quantity,
+        }
+    }
+
+    /// Decimals the tick size allows, when the symbol has one
+    pub fn price_decimals(&self) -> Option<u32> {
+        self.price.filter(|f| f.tick_size > Fixed::ZERO).map(|f| step_decimals(f.tick_size))
+    }
+
+    /// Decimals the lot step allows, when the symbol has one
+    pub fn quantity_decimals(&self, market: bool) -> Option<u32> {
+        self.lot_filter(market).filter(|f| f.step_size > Fixed::ZERO).map(|f| step_decimals(f.step_size))
+    }
+
+    /// Price snapped to the tick and rendered at the tick's precision
+    pub fn format_price(&self, price: Fixed) -> String {
+        render_decimal(self.round_price(price), self.price_decimals())
+    }
+
+    /// Quantity floored to the step and rendered at the step's precision
+    pub fn format_quantity(&self, quantity: Fixed, market: bool) -> String {
+        render_decimal(self.round_quantity(quantity, market), self.quantity_decimals(market))
+    }
+
+    fn lot_filter(&self, market: bool) -> Option<LotSizeFilter> {
+        match self.market_lot_size {
+            Some(filter) if market && filter.step_size > Fixed::ZERO => Some(filter),
+            _ => self.lot_size,
+        }
+    }
+
+    /// Snap an order to the tick/step grid and check it passes every filter
+    ///
+    /// `price` is `None` for market orders, which skip the price filter and
+    /// the notional check. Returns the rounded price and quantity.
+    pub fn validate_and_round(&self, price: Option<Fixed>, quantity: Fixed) -> Result<(Option<Fixed>, Fixed)> {
+        let reject = |reason: String| Err(ExchangeError::InvalidOrder(format!("{}: {}", self.symbol, reason)));
+        let price = price.map(|p| self.round_price(p));
+        let quantity = self.round_quantity(quantity, price.is_none());
+
+        if let (Some(filter), Some(price)) = (self.price, price) {
+            if filter.min_price > Fixed::ZERO && price < filter.min_price {
+                return reject(format!("price {} below min {}", price, filter.min_price));
+            }
+            if filter.max_price > Fixed::ZERO && price > filter.max_price {
+                return reject(format!("price {} above max {}", price, filter.max_price));
+            }
+        }
+        if quantity <= Fixed::ZERO {
+            return reject(format!("quantity {quantity} rounds to nothing"));
+        }
+        let market_lot = if price.is_none() { self.market_lot_size } else { None };
+        for filter in self.lot_size.iter().chain(market_lot.iter()) {
+            if quantity < filter.min_qty {
+                return reject(format!("quantity {} below min {}", quantity, filter.min_qty));
+            }
+            if filter.max_qty > Fixed::ZERO && quantity > filter.max_qty {
+                return reject(format!("quantity {} above max {}", quantity, filter.max_qty));
+            }
+        }
+        if let (Some(filter), Some(price)) = (self.notional, price) {
+            let notional = price * quantity;
+            if notional < filter.min_notional {
+                return reject(format!("notional {} below min {}", notional, filter.min_notional));
+            }
+            if let Some(max) = filter.max_notional
+                && max > Fixed::ZERO
+                && notional > max
+            {
+                return reject(format!("notional {notional} above max {max}"));
+            }
+        }
+        Ok((price, quantity))
+    }
+}
+
+impl SymbolInfo {
+    /// Typed trading rules from `filters`
+    pub fn rules(&self) -> Result<SymbolRules> {
+        SymbolRules::from_info(self)
+    }
+}
+
+/// A symbol's trading rules differ between two exchangeInfo fetches
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstrumentChanged {
+    pub symbol: String,
+    pub previous: SymbolRules,
+    pub current: SymbolRules,
+}
+
+impl InstrumentChanged {
+    /// `None` when the rules are the same
+    pub fn detect(previous: &SymbolRules, current: &SymbolRules) -> Option<Self> {
+        (previous != current).then(|| Self {
+            symbol: current.symbol.clone(),
+            previous: previous.clone(),
+            current: current.clone(),
+        })
+    }
+
+    pub fn tick_changed(&self) -> bool {
+        self.previous.price.map(|f| f.tick_size) != self.current.price.map(|f| f.tick_size)
+    }
+
+    pub fn step_changed(&self) -> bool {
+        self.previous.lot_size.map(|f| f.step_size) != self.current.lot_size.map(|f| f.step_size)
+            || self.previous.market_lot_size.map(|f| f.step_size) != self.current.market_lot_size.map(|f| f.step_size)
+    }
+
+    /// What a working order at `price` for `quantity` has to become
+    ///
+    /// `Ok(None)` when it still passes unchanged, the snapped price and
+    /// quantity when it only needs moving onto the new grid, and an error when
+    /// no order close to it is valid any more.
+    pub fn requote(&self, price: Option<Fixed>, quantity: Fixed) -> Result<Option<(Option<Fixed>, Fixed)>> {
+        let (new_price, new_quantity) = self.current.validate_and_round(price, quantity)?;
+        Ok((new_price != price || new_quantity != quantity).then_some((new_price, new_quantity)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testkit::fx;
+
+    fn btcusdt() -> SymbolInfo {
+        serde_json::from_str(r#"{
+            "symbol": "BTCUSDT", "status": "TRADING", "baseAsset": "BTC", "quoteAsset": "USDT",
+            "filters": [
+                {"filterType": "PRICE_FILTER", "minPrice": "0.01000000", "maxPrice": "1000000.00000000", "tickSize": "0.01000000"},
+                {"filterType": "LOT_SIZE", "minQty": "0.00001000", "maxQty": "9000.00000000", "stepSize": "0.00001000"},
+                {"filterType": "ICEBERG_PARTS", "limit": 10},
+                {"filterType": "MARKET_LOT_SIZE", "minQty": "0.00000000", "maxQty": "83.05695167", "stepSize": "0.00000000"},
+                {"filterType": "TRAILING_DELTA", "minTrailingAboveDelta": 10, "maxTrailingAboveDelta": 2000},
+                {"filterType": "NOTIONAL", "minNotional": "5.00000000", "applyMinToMarket": true, "maxNotional": "9000000.00000000", "applyMaxToMarket": false, "avgPriceMins": 5}
+            ]
+        }"#).unwrap()
+    }
+
+    #[test]
+    fn test_parses_typed_filters() {
+        let rules = btcusdt().rules().unwrap();
+        assert_eq!(rules.price.unwrap().tick_size, fx("0.01"));
+        assert_eq!(rules.lot_size.unwrap().step_size, fx("0.00001"));
+        assert_eq!(rules.market_lot_size.unwrap().max_qty, fx("83.05695167"));
+        assert_eq!(rules.notional.unwrap().min_notional, fx("5"));
+        // Beyond the Fixed range, kept exact
+        assert_eq!(rules.notional.unwrap().max_notional.map(|max| max.to_string()).as_deref(), Some("9000000.00000000"));
+        assert_eq!(rules.iceberg_parts, Some(10));
+    }
+
+    #[test]
+    fn test_validate_and_round_snaps_to_grid() {
+        let rules = btcusdt().rules().unwrap();
+        let (price, quantity) = rules.validate_and_round(Some(fx("50000.126")), fx("0.0012345")).unwrap();
+        assert_eq!(price, Some(fx("50000.13")));
+        assert_eq!(quantity, fx("0.00123"));
+
+        // Market orders fall back to LOT_SIZE since MARKET_LOT_SIZE has no step
+        assert_eq!(rules.validate_and_round(None, fx("0.0012345")).unwrap(), (None, fx("0.00123")));
+
+        assert!(rules.validate_and_round(Some(fx("50000")), fx("0.000009")).is_err());
+        assert!(rules.validate_and_round(Some(fx("50000")), fx("0.00009")).is_err());
+        assert!(rules.validate_and_round(None, fx("100")).is_err());
+    }
+
+    #[test]
+    fn test_renders_at_filter_precision() {
+        let rules = btcusdt().rules().unwrap();
+        assert_eq!(rules.format_price(fx("50000.1")), "50000.10");
+        assert_eq!(rules.format_price(fx("50000.126")), "50000.13");
+        assert_eq!(rules.format_quantity(fx("0.0012345"), false), "0.00123");
+        assert_eq!(rules.format_quantity(fx("2"), true), "2.00000");
+
+        assert_eq!(step_decimals(fx("1.00000000")), 0);
+        assert_eq!(render_decimal(fx("1.50000000"), None), "1.5");
+        assert_eq!(render_decimal(fx("0.0000001"), None), "0.0000001");
+        assert_eq!(render_decimal(fx("3.000"), None), "3");
+    }
+
+    #[test]
+    fn test_tick_change_requotes_off_grid_orders() {
+        let previous = btcusdt().rules().unwrap();
+        let mut current = previous.clone();
+        current.price = Some(PriceFilter { min_price: fx("0.1"), max_price: Fixed::ZERO, tick_size: fx("0.1") });
+        assert!(InstrumentChanged::detect(&previous, &previous).is_none());
+
+        let change = InstrumentChanged::detect(&previous, &current).unwrap();
+        assert!(change.tick_changed() && !change.step_changed());
+        assert_eq!(change.requote(Some(fx("50000.1")), fx("0.001")).unwrap(), None);
+        assert_eq!(change.requote(Some(fx("50000.12")), fx("0.001")).unwrap(), Some((Some(fx("50000.1")), fx("0.001"))));
+
+        // A larger minimum quantity leaves no valid order
+        current.lot_size.as_mut().unwrap().min_qty = fx("0.01");
+        let change = InstrumentChanged::detect(&previous, &current).unwrap();
+        assert!(change.requote(Some(fx("50000.1")), fx("0.001")).is_err());
+    }
+}
@@ -9,18 +9,36 @@ pub mod types;
 pub mod websocket;
 pub mod user_stream;
 pub mod connection;
+pub mod subscriptions;
+pub mod stream_stats;
+pub mod rate_limiter;
+pub mod api_error;
+pub mod filters;
+pub mod portfolio_margin;
+pub mod futures;
+pub mod retry;
+pub(crate) mod exchange;
 
 use crate::errors::{ExchangeError, Result};
 use sriquant_core::{PerfTimer, nanos};
+use std::collections::VecDeque;
 use tracing::info;
 
 // Re-export types from submodules
-pub use rest::{BinanceConfig, ExchangeInfo, SymbolInfo, BinanceRestClient};
+pub use rest::{BinanceConfig, BinanceEnvironment, BnbBurnStatus, EndpointClass, EndpointSettings, EndpointTimeouts, ExchangeInfo, AggTradeResponse, AggTradesQuery, ExchangeInfoParams, OrderRateLimit, PreventedMatchQuery, PreventedMatchResponse, SymbolInfo, BinanceRestClient};
 pub use auth::{BinanceCredentials, BinanceSigner};
 pub use types::*;
-pub use websocket::BinanceWebSocketClient;
-pub use user_stream::{BinanceUserStreamClient, UserDataEvent, AccountUpdateEvent, BalanceUpdateEvent, OrderUpdateEvent, BalanceInfo, TradeSide};
-pub use connection::ConnectionManager;
+pub use websocket::{BinanceWebSocketClient, DepthSpeed, DepthStream, ReconnectInfo};
+pub use subscriptions::{EventFilter, SubscriptionManager};
+pub use stream_stats::{StreamStats, StreamStatsRegistry};
+pub use user_stream::{BinanceUserStreamClient, UserStreamManager, UserDataEvent, AccountUpdateEvent, BalanceUpdateEvent, OrderUpdateEvent, BalanceInfo, TradeSide};
+pub use connection::{ConnectionManager, ReconnectConfig};
+pub use api_error::BinanceApiError;
+pub use filters::{render_decimal, InstrumentChanged, LotSizeFilter, NotionalFilter, PriceFilter, SymbolRules};
+pub use portfolio_margin::{PortfolioMarginAccount, PortfolioMarginBalance};
+pub use futures::{FuturesIncome, IncomeQuery, IncomeType};
+pub use retry::{RetryOn, RetryPolicy};
+pub use rate_limiter::{RateLimitDecision, RateLimitKind, RateLimitStatus, RateLimitUsage, RateLimiter, RateLimiterConfig};
 
 
 /// High-performance Binance exchange client
@@ -36,6 +54,8 @@ pub struct BinanceExchange {
     #[allow(dead_code)] // Will be used when authenticated endpoints are implemented
     signer: Option<BinanceSigner>,
     websocket_client: Option<BinanceWebSocketClient>,
+    /// Unified events split from stream batches, not yet returned by `next_event`
+    pending_events: VecDeque<crate::types::MarketData>,
 }
 
 impl BinanceExchange {
@@ -61,6 +81,7 @@ impl BinanceExchange {
             rest_client: None,
             signer,
             websocket_client: None,
+            pending_events: VecDeque::new(),
         })
     }
     
@@ -75,7 +96,8 @@ impl BinanceExchange {
     /// Initialize WebSocket streaming
     pub async fn init_websocket(&mut self) -> Result<()> {
         info!("🌐 Initializing Binance WebSocket");
-        let mut ws_client = BinanceWebSocketClient::new(self.config.clone());
+        let mut ws_client = BinanceWebSocketClient::new(self.config.clone())
+            .with_reconnect(connection::ReconnectConfig::default());
         ws_client.connect().await?;
         self.websocket_client = Some(ws_client);
         info!("✅ Binance WebSocket client initialized and connected");
@@ -0,0 +1,125 @@
+//! Binance portfolio margin (PAPI) account types
+//!
+//! Portfolio margin accounts pool spot, cross margin and futures collateral
+//! into one unified account. Spot free balances don't describe what such an
+//! account can trade: any asset backs any order, and the limit is the
+//! account's available margin. These are the `/papi/v1` responses that
+//! describe it.
+
+use crate::binance::exchange::unbounded;
+use crate::errors::{ExchangeError, Result};
+use crate::risk::BuyingPower;
+use crate::types::Balance;
+
+use serde::{Deserialize, Serialize};
+
+/// Unified account summary (`GET /papi/v1/account`), values in USD
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortfolioMarginAccount {
+    /// Unified maintenance margin ratio; liquidation starts near 1.05
+    #[serde(rename = "uniMMR")]
+    pub uni_mmr: String,
+    pub account_equity: String,
+    /// Equity without collateral rate haircuts
+    pub actual_equity: String,
+    pub account_initial_margin: String,
+    pub account_maint_margin: String,
+    /// NORMAL, MARGIN_CALL, SUPPLY_MARGIN, REDUCE_ONLY, ACTIVE_LIQUIDATION,
+    /// FORCE_LIQUIDATION or BANKRUPTED
+    pub account_status: String,
+    pub virtual_max_withdraw_amount: String,
+    /// Left empty by some accounts
+    #[serde(default)]
+    pub total_available_balance: String,
+    pub update_time: u64,
+}
+
+impl PortfolioMarginAccount {
+    /// New positions can be opened
+    pub fn is_normal(&self) -> bool {
+        matches!(self.account_status.as_str(), "NORMAL" | "MARGIN_CALL" | "SUPPLY_MARGIN")
+    }
+
+    /// Margin available for new orders, in USD
+    ///
+    /// `totalAvailableBalance` when reported, else equity less initial margin.
+    pub fn available_usd(&self) -> Result<f64> {
+        if let Ok(available) = self.total_available_balance.parse::<f64>() {
+            return Ok(available.max(0.0));
+        }
+        let equity = parse_usd("accountEquity", &self.account_equity)?;
+        let initial = parse_usd("accountInitialMargin", &self.account_initial_margin)?;
+        Ok((equity - initial).max(0.0))
+    }
+
+    /// Buying power of the unified account; none while it is reduce-only
+    /// or being liquidated
+    pub fn buying_power(&self) -> Result<BuyingPower> {
+        let available_usd = if self.is_normal() { self.available_usd()? } else { 0.0 };
+        Ok(BuyingPower::UnifiedMargin { available_usd })
+    }
+}
+
+/// One asset of a portfolio margin account (`GET /papi/v1/balance`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortfolioMarginBalance {
+    pub asset: String,
+    pub total_wallet_balance: String,
+    pub cross_margin_asset: String,
+    pub cross_margin_borrowed: String,
+    pub cross_margin_free: String,
+    pub cross_margin_interest: String,
+    pub cross_margin_locked: String,
+    pub um_wallet_balance: String,
+    #[serde(rename = "umUnrealizedPNL")]
+    pub um_unrealized_pnl: String,
+    pub cm_wallet_balance: String,
+    #[serde(rename = "cmUnrealizedPNL")]
+    pub cm_unrealized_pnl: String,
+    #[serde(default)]
+    pub negative_balance: String,
+    pub update_time: u64,
+}
+
+impl PortfolioMarginBalance {
+    /// Cross margin wallet as a generic balance
+    pub fn to_balance(&self) -> Result<Balance> {
+        Ok(Balance {
+            asset: self.asset.clone(),
+            free: unbounded(&self.cross_margin_free)?,
+            locked: unbounded(&self.cross_margin_locked)?,
+        })
+    }
+}
+
+fn parse_usd(field: &str, value: &str) -> Result<f64> {
+    value.parse().map_err(|_| ExchangeError::InvalidResponse(format!("Invalid portfolio margin {field}: {value:?}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sriquant_core::prelude::*;
+
+    #[test]
+    fn test_account_buying_power() {
+        let json = r#"{"uniMMR":"5167.92171923","accountEquity":"122607.35137903","actualEquity":"73.47428058",
+            "accountInitialMargin":"23.72469206","accountMaintMargin":"23.72469206","accountStatus":"NORMAL",
+            "virtualMaxWithdrawAmount":"1627523.32459208","totalAvailableBalance":"","totalMarginOpenLoss":"",
+            "updateTime":1657707212154}"#;
+        let mut account: PortfolioMarginAccount = serde_json::from_str(json).unwrap();
+        assert!((account.available_usd().unwrap() - 122_583.626_686_97).abs() < 1e-6);
+
+        account.account_status = "REDUCE_ONLY".to_string();
+        assert_eq!(account.buying_power().unwrap(), BuyingPower::UnifiedMargin { available_usd: 0.0 });
+
+        let balance: PortfolioMarginBalance = serde_json::from_str(r#"{"asset":"USDT","totalWalletBalance":"122607.35137903",
+            "crossMarginAsset":"92.27530794","crossMarginBorrowed":"10.00000000","crossMarginFree":"100.00000000",
+            "crossMarginInterest":"0.72469206","crossMarginLocked":"3.00000000","umWalletBalance":"0.00000000",
+            "umUnrealizedPNL":"23.72469206","cmWalletBalance":"23.72469206","cmUnrealizedPNL":"","updateTime":1617939110373,
+            "negativeBalance":"0"}"#).unwrap();
+        assert_eq!(balance.to_balance().unwrap().total(), Fixed::from_str_exact("103").unwrap());
+    }
+}
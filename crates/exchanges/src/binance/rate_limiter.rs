@@ -0,0 +1,475 @@
+//! Request-weight and order-count limiting for the Binance REST API
+//!
+//! Every response carries `X-MBX-USED-WEIGHT-<interval>` and, for order
+//! placement, `X-MBX-ORDER-COUNT-<interval>` headers with the exchange's
+//! authoritative usage. The limiter adopts those counts, adds the known
+//! weight of each request sent in between, and queues or rejects a request
+//! locally when it would push a window over its limit, before Binance
+//! answers with 429 (and eventually a 418 IP ban).
+//!
+//! Order-count windows are also paced: once one is `slow_down_at_percent`
+//! used, orders are spread evenly over what is left of it instead of
+//! running into the cap. `/api/v3/rateLimit/order` re-syncs the counts,
+//! including orders other sessions on the account have sent.
+
+use crate::binance::rest::OrderRateLimit;
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+const USED_WEIGHT_HEADER: &str = "x-mbx-used-weight-";
+const ORDER_COUNT_HEADER: &str = "x-mbx-order-count-";
+
+/// Which budget a window meters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RateLimitKind {
+    RequestWeight,
+    Orders,
+}
+
+/// Limiter thresholds
+#[derive(Debug, Clone)]
+pub struct RateLimiterConfig {
+    /// Longest a request may be queued waiting for a window to reset;
+    /// anything longer is rejected with `RateLimitExceeded`
+    pub max_queue_wait: Duration,
+    /// Weight kept in reserve per request-weight window (e.g. for cancels)
+    pub reserve_weight: u32,
+    /// Weight of endpoints missing from the weight table
+    pub default_weight: u32,
+    /// Start spreading orders out once an order-count window is this full
+    pub slow_down_at_percent: u32,
+    /// Orders kept in reserve per order-count window (e.g. for cancel-and-replace)
+    pub reserve_orders: u32,
+}
+
+impl Default for RateLimiterConfig {
+    fn default() -> Self {
+        Self {
+            max_queue_wait: Duration::from_secs(2),
+            reserve_weight: 0,
+            default_weight: 1,
+            slow_down_at_percent: 80,
+            reserve_orders: 0,
+        }
+    }
+}
+
+impl RateLimiterConfig {
+    pub fn with_max_queue_wait(mut self, wait: Duration) -> Self {
+        self.max_queue_wait = wait;
+        self
+    }
+
+    pub fn with_reserve_weight(mut self, weight: u32) -> Self {
+        self.reserve_weight = weight;
+        self
+    }
+
+    /// 100 or more turns pacing off
+    pub fn with_slow_down_at_percent(mut self, percent: u32) -> Self {
+        self.slow_down_at_percent = percent;
+        self
+    }
+
+    pub fn with_reserve_orders(mut self, orders: u32) -> Self {
+        self.reserve_orders = orders;
+        self
+    }
+}
+
+/// One fixed window, aligned to the epoch like Binance's
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimitUsage {
+    pub kind: RateLimitKind,
+    /// Header suffix, e.g. "1M" or "10S"
+    pub interval: String,
+    pub interval_ms: u64,
+    pub limit: u32,
+    pub used: u32,
+    pub window_start_ms: u64,
+}
+
+impl RateLimitUsage {
+    /// `None` for an unknown unit or a zero-length window
+    fn new(kind: RateLimitKind, interval: &str, limit: u32) -> Option<Self> {
+        Some(Self {
+            kind,
+            interval: interval.to_ascii_uppercase(),
+            interval_ms: interval_ms(interval).filter(|ms| *ms > 0)?,
+            limit,
+            used: 0,
+            window_start_ms: 0,
+        })
+    }
+
+    fn roll(&mut self, now_ms: u64) {
+        let start = now_ms - now_ms % self.interval_ms;
+        if start != self.window_start_ms {
+            self.window_start_ms = start;
+            self.used = 0;
+        }
+    }
+
+    /// Milliseconds until the current window resets
+    pub fn remaining_ms(&self, now_ms: u64) -> u64 {
+        (self.window_start_ms + self.interval_ms).saturating_sub(now_ms)
+    }
+
+    pub fn usage_percent(&self) -> u32 {
+        if self.limit == 0 { 100 } else { (self.used as u64 * 100 / self.limit as u64) as u32 }
+    }
+}
+
+/// Usage snapshot for proactive throttling
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimitStatus {
+    pub windows: Vec<RateLimitUsage>,
+    /// Set after a 418/429 with `Retry-After`; all requests are held until then
+    pub blocked_until_ms: Option<u64>,
+}
+
+impl RateLimitStatus {
+    /// Highest usage across windows of `kind`
+    pub fn max_usage_percent(&self, kind: RateLimitKind) -> u32 {
+        self.windows.iter().filter(|w| w.kind == kind).map(|w| w.usage_percent()).max().unwrap_or(0)
+    }
+}
+
+/// What to do with a request about to be sent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RateLimitDecision {
+    /// Budget reserved, send now
+    Proceed,
+    /// Queue for this long, then ask again
+    Wait(Duration),
+    /// Would exceed a limit for longer than the queue allows
+    Reject(String),
+}
+
+/// Tracks Binance REST budgets from response headers
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    config: RateLimiterConfig,
+    windows: Vec<RateLimitUsage>,
+    weights: HashMap<String, u32>,
+    blocked_until_ms: Option<u64>,
+    /// Earliest time the next order may go out while pacing
+    next_order_at_ms: u64,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new(RateLimiterConfig::default())
+    }
+}
+
+impl RateLimiter {
+    /// Limiter with Binance spot's published default limits and weights
+    pub fn new(config: RateLimiterConfig) -> Self {
+        let windows = [
+            (RateLimitKind::RequestWeight, "1M", 6000),
+            (RateLimitKind::Orders, "10S", 100),
+            (RateLimitKind::Orders, "1D", 200_000),
+        ]
+        .into_iter()
+        .filter_map(|(kind, interval, limit)| RateLimitUsage::new(kind, interval, limit))
+        .collect();
+
+        let weights = [
+            ("/api/v3/ping", 1),
+            ("/api/v3/time", 1),
+            ("/api/v3/exchangeInfo", 20),
+            ("/api/v3/depth", 5),
+            ("/api/v3/trades", 25),
+            ("/api/v3/klines", 2),
+            ("/api/v3/ticker/24hr", 2),
+            ("/api/v3/ticker/price", 2),
+            ("/api/v3/order", 1),
+            ("/api/v3/order/test", 1),
+            ("/api/v3/openOrders", 6),
+            ("/api/v3/allOrders", 20),
+            ("/api/v3/account", 20),
+            ("/api/v3/myTrades", 20),
+            ("/api/v3/myPreventedMatches", 20),
+            ("/api/v3/rateLimit/order", 40),
+            ("/api/v3/userDataStream", 2),
+        ]
+        .into_iter()
+        .map(|(path, weight)| (path.to_string(), weight))
+        .collect();
+
+        Self {
+            config,
+            windows,
+            weights,
+            blocked_until_ms: None,
+            next_order_at_ms: 0,
+        }
+    }
+
+    /// Override the weight of one endpoint path
+    pub fn with_endpoint_weight(mut self, path: &str, weight: u32) -> Self {
+        self.weights.insert(path.to_string(), weight);
+        self
+    }
+
+    /// Replace limits with those reported by the exchange (`rateLimits`),
+    /// adopting the current counts where they are included
+    pub fn sync_limits(&mut self, limits: &[OrderRateLimit], now_ms: u64) {
+        for limit in limits {
+            let kind = match limit.rate_limit_type.as_str() {
+                "REQUEST_WEIGHT" => RateLimitKind::RequestWeight,
+                "ORDERS" => RateLimitKind::Orders,
+                _ => continue,
+            };
+            let Some(interval_ms) = limit.interval_millis() else { continue };
+            // "MINUTE" -> "1M", matching the header suffix
+            let interval = format!("{}{}", limit.interval_num, &limit.interval[..1]);
+            let index = match self.windows.iter().position(|w| w.kind == kind && w.interval_ms == interval_ms) {
+                Some(index) => index,
+                None => {
+                    let Some(window) = RateLimitUsage::new(kind, &interval, limit.limit) else { continue };
+                    self.windows.push(window);
+                    self.windows.len() - 1
+                }
+            };
+            let window = &mut self.windows[index];
+            window.limit = limit.limit;
+            if let Some(count) = limit.count {
+                window.roll(now_ms);
+                window.used = count;
+            }
+        }
+        debug!("🚦 Rate limiter synced with {} windows", self.windows.len());
+    }
+
+    /// Request weight of `path`
+    pub fn endpoint_weight(&self, path: &str) -> u32 {
+        self.weights.get(path).copied().unwrap_or(self.config.default_weight)
+    }
+
+    /// Reserve budget for a request, or say how long to hold it
+    pub fn acquire(&mut self, method: &str, path: &str, now_ms: u64) -> RateLimitDecision {
+        if let Some(until) = self.blocked_until_ms {
+            if now_ms < until {
+                return self.queue_or_reject(until - now_ms, format!("blocked by Binance until {until}"));
+            }
+            self.blocked_until_ms = None;
+        }
+
+        let weight = self.endpoint_weight(path);
+        let is_order = is_order_endpoint(method, path);
+        let mut longest_wait = 0;
+        let mut exhausted = None;
+        for window in &mut self.windows {
+            window.roll(now_ms);
+            let (cost, capacity) = match window.kind {
+                RateLimitKind::RequestWeight => (weight, window.limit.saturating_sub(self.config.reserve_weight)),
+                RateLimitKind::Orders if is_order => (1, window.limit.saturating_sub(self.config.reserve_orders)),
+                RateLimitKind::Orders => continue,
+            };
+            if window.used + cost > capacity {
+                let wait = window.remaining_ms(now_ms);
+                if wait >= longest_wait {
+                    longest_wait = wait;
+                    exhausted = Some(format!("{:?} {}/{} per {}", window.kind, window.used, window.limit, window.interval));
+                }
+            }
+        }
+
+        if let Some(reason) = exhausted {
+            return self.queue_or_reject(longest_wait, reason);
+        }
+        if is_order && now_ms < self.next_order_at_ms {
+            return self.queue_or_reject(self.next_order_at_ms - now_ms, "pacing orders".to_string());
+        }
+
+        for window in &mut self.windows {
+            match window.kind {
+                RateLimitKind::RequestWeight => window.used += weight,
+                RateLimitKind::Orders if is_order => window.used += 1,
+                RateLimitKind::Orders => {}
+            }
+        }
+        if is_order {
+            self.next_order_at_ms = now_ms + self.order_gap_ms(now_ms);
+        }
+        RateLimitDecision::Proceed
+    }
+
+    /// Spacing that spreads the orders left in the fullest window over its remaining time
+    fn order_gap_ms(&self, now_ms: u64) -> u64 {
+        self.windows.iter()
+            .filter(|w| w.kind == RateLimitKind::Orders && w.usage_percent() >= self.config.slow_down_at_percent)
+            .map(|w| {
+                let left = w.limit.saturating_sub(self.config.reserve_orders).saturating_sub(w.used).max(1);
+                w.remaining_ms(now_ms) / left as u64
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn queue_or_reject(&self, wait_ms: u64, reason: String) -> RateLimitDecision {
+        let wait = Duration::from_millis(wait_ms);
+        if wait <= self.config.max_queue_wait {
+            debug!("🚦 Queuing request {}ms: {}", wait_ms, reason);
+            RateLimitDecision::Wait(wait)
+        } else {
+            warn!("🚦 Rejecting request locally: {}", reason);
+            RateLimitDecision::Reject(reason)
+        }
+    }
+
+    /// Adopt the exchange's counts from a response
+    pub fn update_from_response(&mut self, status: u16, headers: &[(String, String)], now_ms: u64) {
+        for (name, value) in headers {
+            let name = name.to_ascii_lowercase();
+            let (kind, interval) = if let Some(interval) = name.strip_prefix(USED_WEIGHT_HEADER) {
+                (RateLimitKind::RequestWeight, interval)
+            } else if let Some(interval) = name.strip_prefix(ORDER_COUNT_HEADER) {
+                (RateLimitKind::Orders, interval)
+            } else {
+                continue;
+            };
+            let Ok(used) = value.trim().parse::<u32>() else { continue };
+            let interval = interval.to_ascii_uppercase();
+
+            let index = match self.windows.iter().position(|w| w.kind == kind && w.interval == interval) {
+                Some(index) => index,
+                None => {
+                    // Unknown window: track it, with the limit learned later via `sync_limits`
+                    let Some(window) = RateLimitUsage::new(kind, &interval, u32::MAX) else { continue };
+                    self.windows.push(window);
+                    self.windows.len() - 1
+                }
+            };
+            let window = &mut self.windows[index];
+            window.roll(now_ms);
+            window.used = used;
+        }
+
+        if matches!(status, 418 | 429) {
+            let retry_after_secs = headers
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case("retry-after"))
+                .and_then(|(_, value)| value.trim().parse::<u64>().ok())
+                .unwrap_or(60);
+            let until = now_ms + retry_after_secs * 1000;
+            warn!("🚫 Binance returned {}, holding requests for {}s", status, retry_after_secs);
+            self.blocked_until_ms = Some(self.blocked_until_ms.map_or(until, |u| u.max(until)));
+        }
+    }
+
+    /// Current usage of every window
+    pub fn status(&mut self, now_ms: u64) -> RateLimitStatus {
+        for window in &mut self.windows {
+            window.roll(now_ms);
+        }
+        RateLimitStatus {
+            windows: self.windows.clone(),
+            blocked_until_ms: self.blocked_until_ms.filter(|until| *until > now_ms),
+        }
+    }
+}
+
+/// Requests that count against the ORDERS limits
+pub(crate) fn is_order_endpoint(method: &str, path: &str) -> bool {
+    method.eq_ignore_ascii_case("POST")
+        && (path.starts_with("/api/v3/order") || path.starts_with("/api/v3/sor/order"))
+        && !path.ends_with("/test")
+}
+
+/// Header interval suffix ("10S", "1M", "1H", "1D") in milliseconds
+pub(crate) fn interval_ms(interval: &str) -> Option<u64> {
+    let interval = interval.trim();
+    let (num, unit) = interval.split_at(interval.len().checked_sub(1)?);
+    let unit_ms = match unit.to_ascii_uppercase().as_str() {
+        "S" => 1_000,
+        "M" => 60_000,
+        "H" => 3_600_000,
+        "D" => 86_400_000,
+        _ => return None,
+    };
+    Some(num.parse::<u64>().ok()? * unit_ms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_headers_drive_weight_and_order_windows() {
+        let mut limiter = RateLimiter::new(RateLimiterConfig::default().with_max_queue_wait(Duration::from_secs(60)));
+        let now = 120_000;
+
+        limiter.update_from_response(200, &headers(&[("X-MBX-USED-WEIGHT-1M", "5990"), ("x-mbx-order-count-10s", "99")]), now);
+        assert_eq!(limiter.acquire("GET", "/api/v3/depth", now), RateLimitDecision::Proceed);
+        // 5995 used: a 20-weight call must wait for the minute to roll
+        assert_eq!(limiter.acquire("GET", "/api/v3/account", now + 1_000), RateLimitDecision::Wait(Duration::from_millis(59_000)));
+
+        let status = limiter.status(now);
+        assert_eq!(status.max_usage_percent(RateLimitKind::RequestWeight), 99);
+        assert_eq!(status.max_usage_percent(RateLimitKind::Orders), 99);
+
+        // Both windows have rolled; test orders don't count against ORDERS
+        let later = 180_000;
+        assert_eq!(limiter.acquire("POST", "/api/v3/order", later), RateLimitDecision::Proceed);
+        assert_eq!(limiter.status(later).windows[1].used, 1);
+        assert_eq!(limiter.acquire("POST", "/api/v3/order/test", later), RateLimitDecision::Proceed);
+        assert_eq!(limiter.status(later).windows[1].used, 1);
+    }
+
+    #[test]
+    fn test_ban_blocks_until_retry_after() {
+        let mut limiter = RateLimiter::default();
+        limiter.update_from_response(429, &headers(&[("Retry-After", "1")]), 10_000);
+        assert_eq!(limiter.acquire("GET", "/api/v3/ping", 10_500), RateLimitDecision::Wait(Duration::from_millis(500)));
+
+        limiter.update_from_response(418, &headers(&[("Retry-After", "120")]), 10_500);
+        assert!(matches!(limiter.acquire("GET", "/api/v3/ping", 11_000), RateLimitDecision::Reject(_)));
+        assert_eq!(limiter.status(11_000).blocked_until_ms, Some(130_500));
+        assert_eq!(limiter.acquire("GET", "/api/v3/ping", 130_500), RateLimitDecision::Proceed);
+    }
+
+    #[test]
+    fn test_zero_length_windows_are_ignored() {
+        let mut limiter = RateLimiter::default();
+        let limits: Vec<OrderRateLimit> = serde_json::from_str(r#"[
+            {"rateLimitType": "ORDERS", "interval": "SECOND", "intervalNum": 0, "limit": 50}
+        ]"#).unwrap();
+        limiter.sync_limits(&limits, 1_000);
+        limiter.update_from_response(200, &headers(&[("x-mbx-order-count-0s", "3")]), 1_000);
+
+        assert_eq!(limiter.status(1_000).windows.len(), 3);
+        assert_eq!(limiter.acquire("POST", "/api/v3/order", 1_000), RateLimitDecision::Proceed);
+    }
+
+    #[test]
+    fn test_orders_are_paced_near_the_cap() {
+        let mut limiter = RateLimiter::new(RateLimiterConfig::default().with_max_queue_wait(Duration::from_secs(60)));
+        let limits: Vec<OrderRateLimit> = serde_json::from_str(r#"[
+            {"rateLimitType": "ORDERS", "interval": "SECOND", "intervalNum": 10, "limit": 50, "count": 39}
+        ]"#).unwrap();
+        let now = 1_000_000;
+        limiter.sync_limits(&limits, now);
+
+        // 40 of 50: the remaining 10 orders share the 10s window
+        assert_eq!(limiter.acquire("POST", "/api/v3/order", now), RateLimitDecision::Proceed);
+        assert_eq!(limiter.acquire("POST", "/api/v3/order", now), RateLimitDecision::Wait(Duration::from_millis(1_000)));
+        // Pacing holds orders only
+        assert_eq!(limiter.acquire("GET", "/api/v3/depth", now), RateLimitDecision::Proceed);
+        assert_eq!(limiter.acquire("POST", "/api/v3/order", now + 1_000), RateLimitDecision::Proceed);
+        assert_eq!(limiter.status(now).windows.iter().find(|w| w.interval == "10S").map(|w| w.used), Some(41));
+
+        // A fresh window starts unpaced
+        assert_eq!(limiter.acquire("POST", "/api/v3/order", now + 10_000), RateLimitDecision::Proceed);
+        assert_eq!(limiter.acquire("POST", "/api/v3/order", now + 10_000), RateLimitDecision::Proceed);
+    }
+}
@@ -7,16 +7,44 @@
 //! - Fixed-point arithmetic for price calculations
 
 use crate::errors::{ExchangeError, Result};
-use crate::http::MonoioHttpsClient;
+use crate::cassette::CassetteHandle;
+use crate::chaos::FaultInjector;
+use crate::testkit::MockBinance;
+use crate::http::{with_deadline, MonoioHttpsClient};
+use crate::incidents::{Incident, IncidentBus, IncidentKind, Severity};
 use crate::binance::auth::BinanceAuth;
+use crate::binance::api_error::BinanceApiError;
+use crate::binance::filters::{render_price, render_quantity, InstrumentChanged, SymbolRules};
+use crate::binance::portfolio_margin::{PortfolioMarginAccount, PortfolioMarginBalance};
+use crate::binance::futures::{next_income_page, FuturesIncome, IncomeQuery, INCOME_PAGE_LIMIT};
+use crate::risk::BuyingPower;
+use crate::client_id::ClientIdPolicy;
+use crate::pagination::{paginate, PageWindow, Paged, Paginator};
+use crate::binance::rate_limiter::{RateLimitDecision, RateLimitKind, RateLimitStatus, RateLimiter, RateLimiterConfig};
+use crate::binance::retry::RetryPolicy;
 use sriquant_core::prelude::*;
+use sriquant_core::doctor::{CheckStatus, DoctorCheck, DoctorReport};
 
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 use serde_json::Value;
 use url::Url;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
 use std::collections::HashMap;
 
+/// Decode a response body, naming the endpoint and quoting the body on failure
+fn decode<T: DeserializeOwned>(endpoint: &str, body: &str) -> Result<T> {
+    serde_json::from_str(body)
+        .map_err(|e| ExchangeError::from(e).with_endpoint(endpoint).with_payload(body))
+}
+
+/// `decode` for an already-parsed response
+fn decode_value<T: DeserializeOwned>(endpoint: &str, value: Value) -> Result<T> {
+    T::deserialize(&value)
+        .map_err(|e| ExchangeError::from(e).with_endpoint(endpoint).with_payload(&value.to_string()))
+}
+
 /// Parameters for test order request
 #[derive(Debug, Clone)]
 pub struct TestOrderParams<'a> {
@@ -28,6 +56,162 @@ pub struct TestOrderParams<'a> {
     pub time_in_force: Option<&'a str>,
     pub stop_price: Option<&'a str>,
     pub iceberg_qty: Option<&'a str>,
+    pub new_client_order_id: Option<&'a str>,
+}
+
+/// Selector for the myPreventedMatches query (Binance requires exactly one)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreventedMatchQuery {
+    /// A single prevented match by id
+    ById(u64),
+    /// All prevented matches involving an order
+    ByOrderId(u64),
+    /// Prevented matches from an id onwards, for pagination
+    FromId { from_prevented_match_id: u64, limit: Option<u32> },
+}
+
+/// Optional filters for the exchangeInfo request
+#[derive(Debug, Clone, Default)]
+pub struct ExchangeInfoParams<'a> {
+    /// Only return symbols with this status (e.g. "TRADING"); cannot be combined with a symbol filter
+    pub symbol_status: Option<&'a str>,
+    /// Only return symbols with any of these permissions (e.g. "SPOT", "MARGIN")
+    pub permissions: &'a [&'a str],
+}
+
+impl ExchangeInfoParams<'_> {
+    /// Build query parameters; multiple permissions use Binance's JSON array form
+    pub fn query_params(&self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+        if let Some(status) = self.symbol_status {
+            params.push(("symbolStatus", status.to_string()));
+        }
+        match self.permissions {
+            [] => {}
+            [single] => params.push(("permissions", single.to_string())),
+            many => {
+                let list = many.iter().map(|p| format!("\"{p}\"")).collect::<Vec<_>>().join(",");
+                params.push(("permissions", format!("[{list}]")));
+            }
+        }
+        params
+    }
+}
+
+/// Which aggregated trades `get_agg_trades` returns
+///
+/// With nothing set Binance returns the most recent trades. `start_time` and
+/// `end_time` may be at most one hour apart.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AggTradesQuery {
+    /// First aggregate trade id to return
+    pub from_id: Option<u64>,
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+    /// Default 500, max 1000
+    pub limit: Option<u32>,
+}
+
+impl AggTradesQuery {
+    pub fn from_id(from_id: u64) -> Self {
+        Self { from_id: Some(from_id), ..Self::default() }
+    }
+
+    pub fn between(start_time: u64, end_time: u64) -> Self {
+        Self { start_time: Some(start_time), end_time: Some(end_time), ..Self::default() }
+    }
+
+    pub fn with_limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn query_params(&self) -> Vec<(&'static str, String)> {
+        [
+            ("fromId", self.from_id),
+            ("startTime", self.start_time),
+            ("endTime", self.end_time),
+            ("limit", self.limit.map(u64::from)),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| value.map(|v| (name, v.to_string())))
+        .collect()
+    }
+}
+
+/// Binance deployment a config talks to
+///
+/// Each environment has its own host for spot and USDⓈ-M futures, REST,
+/// market streams and the WebSocket API; mixing them up fails with
+/// confusing key or symbol errors, so they are only ever set together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BinanceEnvironment {
+    Mainnet,
+    Testnet,
+}
+
+impl BinanceEnvironment {
+    /// Spot REST API
+    pub fn rest_url(&self) -> &'static str {
+        match self {
+            Self::Mainnet => "https://api.binance.com",
+            Self::Testnet => "https://testnet.binance.vision",
+        }
+    }
+
+    /// Spot market data and user data streams
+    pub fn stream_url(&self) -> &'static str {
+        match self {
+            Self::Mainnet => "wss://stream.binance.com:9443",
+            Self::Testnet => "wss://stream.testnet.binance.vision",
+        }
+    }
+
+    /// Spot WebSocket API
+    pub fn ws_api_url(&self) -> &'static str {
+        match self {
+            Self::Mainnet => "wss://ws-api.binance.com:443/ws-api/v3",
+            Self::Testnet => "wss://ws-api.testnet.binance.vision/ws-api/v3",
+        }
+    }
+
+    /// USDⓈ-M futures REST API
+    pub fn futures_rest_url(&self) -> &'static str {
+        match self {
+            Self::Mainnet => "https://fapi.binance.com",
+            Self::Testnet => "https://testnet.binancefuture.com",
+        }
+    }
+
+    /// USDⓈ-M futures market data streams
+    pub fn futures_stream_url(&self) -> &'static str {
+        match self {
+            Self::Mainnet => "wss://fstream.binance.com",
+            Self::Testnet => "wss://fstream.binancefuture.com",
+        }
+    }
+
+    /// USDⓈ-M futures WebSocket API
+    pub fn futures_ws_api_url(&self) -> &'static str {
+        match self {
+            Self::Mainnet => "wss://ws-fapi.binance.com/ws-fapi/v1",
+            Self::Testnet => "wss://testnet.binancefuture.com/ws-fapi/v1",
+        }
+    }
+}
+
+impl std::str::FromStr for BinanceEnvironment {
+    type Err = crate::errors::ExchangeError;
+
+    /// `mainnet`/`production`/`live` or `testnet`/`test`, as in the YAML configs
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "mainnet" | "production" | "live" => Ok(Self::Mainnet),
+            "testnet" | "test" => Ok(Self::Testnet),
+            other => Err(crate::errors::ExchangeError::ConfigurationError(format!("unknown Binance environment {other:?}"))),
+        }
+    }
 }
 
 /// Binance exchange configuration
@@ -36,11 +220,149 @@ pub struct BinanceConfig {
     pub api_key: String,
     pub api_secret: String,
     pub base_url: String,
+    /// Spot market data and user data streams
     pub ws_url: String,
     pub testnet: bool,
     pub timeout_ms: u64,
     pub enable_timing: bool,
     pub cpu_core: Option<usize>,
+    #[serde(default)]
+    pub endpoints: EndpointTimeouts,
+    /// Skip exchangeInfo symbols that fail to parse instead of failing the request
+    #[serde(default)]
+    pub lenient_exchange_info: bool,
+    /// Render order prices and quantities at the symbol's tick/step precision
+    #[serde(default = "default_true")]
+    pub filter_precision: bool,
+    /// The account uses portfolio margin: balances and buying power come
+    /// from the unified account on `papi_url` instead of spot
+    #[serde(default)]
+    pub portfolio_margin: bool,
+    #[serde(default = "default_papi_url")]
+    pub papi_url: String,
+    /// USDⓈ-M futures API, for income history
+    #[serde(default = "default_fapi_url")]
+    pub fapi_url: String,
+    /// USDⓈ-M futures market data streams
+    #[serde(default = "default_fapi_ws_url")]
+    pub fapi_ws_url: String,
+    /// Spot WebSocket API
+    #[serde(default = "default_ws_api_url")]
+    pub ws_api_url: String,
+    /// USDⓈ-M futures WebSocket API
+    #[serde(default = "default_fapi_ws_api_url")]
+    pub fapi_ws_api_url: String,
+    /// Offer permessage-deflate on market data streams
+    #[serde(default = "default_true")]
+    pub ws_compression: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_papi_url() -> String {
+    "https://papi.binance.com".to_string()
+}
+
+fn default_fapi_url() -> String {
+    BinanceEnvironment::Mainnet.futures_rest_url().to_string()
+}
+
+fn default_fapi_ws_url() -> String {
+    BinanceEnvironment::Mainnet.futures_stream_url().to_string()
+}
+
+fn default_ws_api_url() -> String {
+    BinanceEnvironment::Mainnet.ws_api_url().to_string()
+}
+
+fn default_fapi_ws_api_url() -> String {
+    BinanceEnvironment::Mainnet.futures_ws_api_url().to_string()
+}
+
+/// Endpoint class used to pick timeouts and tag latency metrics
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EndpointClass {
+    Order,
+    Cancel,
+    MarketData,
+    Account,
+}
+
+impl EndpointClass {
+    /// Metric label for this endpoint class
+    pub fn label(&self) -> &'static str {
+        match self {
+            EndpointClass::Order => "order",
+            EndpointClass::Cancel => "cancel",
+            EndpointClass::MarketData => "market_data",
+            EndpointClass::Account => "account",
+        }
+    }
+}
+
+impl std::fmt::Display for EndpointClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+/// Timeout and latency SLO for one endpoint class
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EndpointSettings {
+    /// Deadline for each attempt at a request
+    pub timeout_ms: u64,
+    /// Latency above which a completed request is reported as slow
+    pub slo_us: u64,
+}
+
+impl EndpointSettings {
+    pub fn new(timeout_ms: u64, slo_us: u64) -> Self {
+        Self { timeout_ms, slo_us }
+    }
+}
+
+/// Per-class endpoint settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EndpointTimeouts {
+    pub order: EndpointSettings,
+    pub cancel: EndpointSettings,
+    pub market_data: EndpointSettings,
+    pub account: EndpointSettings,
+}
+
+impl Default for EndpointTimeouts {
+    fn default() -> Self {
+        Self {
+            order: EndpointSettings::new(2000, 50_000),
+            cancel: EndpointSettings::new(2000, 50_000),
+            market_data: EndpointSettings::new(5000, 200_000),
+            account: EndpointSettings::new(5000, 500_000),
+        }
+    }
+}
+
+impl EndpointTimeouts {
+    /// Get settings for an endpoint class
+    pub fn get(&self, class: EndpointClass) -> EndpointSettings {
+        match class {
+            EndpointClass::Order => self.order,
+            EndpointClass::Cancel => self.cancel,
+            EndpointClass::MarketData => self.market_data,
+            EndpointClass::Account => self.account,
+        }
+    }
+    
+    /// Get mutable settings for an endpoint class
+    pub fn get_mut(&mut self, class: EndpointClass) -> &mut EndpointSettings {
+        match class {
+            EndpointClass::Order => &mut self.order,
+            EndpointClass::Cancel => &mut self.cancel,
+            EndpointClass::MarketData => &mut self.market_data,
+            EndpointClass::Account => &mut self.account,
+        }
+    }
 }
 
 impl Default for BinanceConfig {
@@ -48,24 +370,48 @@ impl Default for BinanceConfig {
         Self {
             api_key: String::new(),
             api_secret: String::new(),
-            base_url: "https://api.binance.com".to_string(),
-            ws_url: "wss://stream.binance.com:9443".to_string(),
+            base_url: BinanceEnvironment::Mainnet.rest_url().to_string(),
+            ws_url: BinanceEnvironment::Mainnet.stream_url().to_string(),
             testnet: false,
             timeout_ms: 5000,
             enable_timing: true,
             cpu_core: Some(0),
+            endpoints: EndpointTimeouts::default(),
+            lenient_exchange_info: false,
+            filter_precision: true,
+            portfolio_margin: false,
+            papi_url: default_papi_url(),
+            fapi_url: default_fapi_url(),
+            fapi_ws_url: default_fapi_ws_url(),
+            ws_api_url: default_ws_api_url(),
+            fapi_ws_api_url: default_fapi_ws_api_url(),
+            ws_compression: true,
         }
     }
 }
 
 impl BinanceConfig {
+    /// Defaults with every URL pointing at `environment`
+    pub fn environment(environment: BinanceEnvironment) -> Self {
+        Self::default().with_environment(environment)
+    }
+    
     pub fn testnet() -> Self {
-        Self {
-            base_url: "https://testnet.binance.vision".to_string(),
-            ws_url: "wss://testnet.binance.vision".to_string(),
-            testnet: true,
-            ..Default::default()
-        }
+        Self::environment(BinanceEnvironment::Testnet)
+    }
+    
+    /// Point spot, futures and WebSocket API URLs at `environment` together
+    ///
+    /// Portfolio margin has no testnet, so `papi_url` is left as is.
+    pub fn with_environment(mut self, environment: BinanceEnvironment) -> Self {
+        self.base_url = environment.rest_url().to_string();
+        self.ws_url = environment.stream_url().to_string();
+        self.ws_api_url = environment.ws_api_url().to_string();
+        self.fapi_url = environment.futures_rest_url().to_string();
+        self.fapi_ws_url = environment.futures_stream_url().to_string();
+        self.fapi_ws_api_url = environment.futures_ws_api_url().to_string();
+        self.testnet = environment == BinanceEnvironment::Testnet;
+        self
     }
     
     pub fn with_credentials(mut self, api_key: String, api_secret: String) -> Self {
@@ -84,6 +430,55 @@ impl BinanceConfig {
         self
     }
     
+    /// Tolerate malformed symbols in exchangeInfo, see `ExchangeInfo::from_json_lenient`
+    pub fn with_lenient_exchange_info(mut self, lenient: bool) -> Self {
+        self.lenient_exchange_info = lenient;
+        self
+    }
+
+    /// Format `place_order` fields from the symbol's filters (on by default);
+    /// when off, numbers are sent as given with trailing zeros trimmed
+    pub fn with_filter_precision(mut self, enabled: bool) -> Self {
+        self.filter_precision = enabled;
+        self
+    }
+    
+    /// Treat the account as portfolio margin (see `BinanceRestClient::buying_power`)
+    pub fn with_portfolio_margin(mut self, enabled: bool) -> Self {
+        self.portfolio_margin = enabled;
+        self
+    }
+    
+    /// Compress market data streams (on by default); turn off when inflate
+    /// time matters more than bandwidth
+    pub fn with_ws_compression(mut self, enabled: bool) -> Self {
+        self.ws_compression = enabled;
+        self
+    }
+    
+    /// Override timeout and SLO for one endpoint class
+    pub fn with_endpoint_settings(mut self, class: EndpointClass, settings: EndpointSettings) -> Self {
+        *self.endpoints.get_mut(class) = settings;
+        self
+    }
+    
+    /// Deadline applied to connects, reads and writes; `timeout_ms: 0` disables it
+    pub fn request_timeout(&self) -> Option<std::time::Duration> {
+        (self.timeout_ms > 0).then(|| std::time::Duration::from_millis(self.timeout_ms))
+    }
+    
+    /// Get timeout and SLO for an endpoint class
+    pub fn endpoint_settings(&self, class: EndpointClass) -> EndpointSettings {
+        self.endpoints.get(class)
+    }
+    
+    /// Deadline for one attempt at a request of this class, retries excluded;
+    /// `timeout_ms: 0` disables it
+    pub fn endpoint_timeout(&self, class: EndpointClass) -> Option<std::time::Duration> {
+        let timeout_ms = self.endpoints.get(class).timeout_ms;
+        (timeout_ms > 0).then(|| std::time::Duration::from_millis(timeout_ms))
+    }
+    
     pub fn with_env_credentials(mut self) -> crate::errors::Result<Self> {
         use crate::errors::ExchangeError;
         
@@ -102,8 +497,45 @@ impl BinanceConfig {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExchangeInfo {
     pub timezone: String,
+    #[serde(rename = "serverTime")]
     pub server_time: u64,
     pub symbols: Vec<SymbolInfo>,
+    /// Symbols skipped by a lenient parse, with the reason
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parse_warnings: Vec<String>,
+}
+
+/// Top level of exchangeInfo with symbols left unparsed
+#[derive(Deserialize)]
+struct RawExchangeInfo {
+    #[serde(default)]
+    timezone: String,
+    #[serde(rename = "serverTime", default)]
+    server_time: u64,
+    symbols: Vec<Value>,
+}
+
+impl ExchangeInfo {
+    /// Parse exchangeInfo, skipping symbols that don't match `SymbolInfo`
+    ///
+    /// One changed field on one symbol shouldn't hide the rest of the
+    /// universe. Each skipped symbol is logged and listed in `parse_warnings`.
+    pub fn from_json_lenient(body: &str) -> Result<Self> {
+        let raw: RawExchangeInfo = decode("/api/v3/exchangeInfo", body)?;
+        let mut symbols = Vec::with_capacity(raw.symbols.len());
+        let mut parse_warnings = Vec::new();
+        for value in raw.symbols {
+            match SymbolInfo::deserialize(&value) {
+                Ok(symbol) => symbols.push(symbol),
+                Err(e) => {
+                    let name = value.get("symbol").and_then(Value::as_str).unwrap_or("<unnamed>");
+                    warn!("⚠️  Skipping exchangeInfo symbol {}: {}", name, e);
+                    parse_warnings.push(format!("{name}: {e}"));
+                }
+            }
+        }
+        Ok(Self { timezone: raw.timezone, server_time: raw.server_time, symbols, parse_warnings })
+    }
 }
 
 /// Symbol information
@@ -118,12 +550,28 @@ pub struct SymbolInfo {
     pub filters: Vec<serde_json::Value>,
 }
 
+/// Whether a request that failed transiently may be sent again
+#[derive(Debug, Clone, Copy)]
+enum Resend<'a> {
+    /// Repeating it changes nothing
+    Freely,
+    /// It may have placed an order that can't be told apart from a new one
+    Never,
+    /// It may have placed `client_order_id`; resent only once a lookup shows it didn't
+    IfOrderMissing { base_url: &'a Url, symbol: &'a str, client_order_id: &'a str },
+}
+
 /// High-performance Binance REST client using monoio
 pub struct BinanceRestClient {
     #[allow(dead_code)] // Will be used for authenticated requests
     config: BinanceConfig,
     base_url: Url,
     https_client: MonoioHttpsClient,
+    incidents: Option<IncidentBus>,
+    rate_limiter: RefCell<RateLimiter>,
+    retry: RetryPolicy,
+    /// Trading rules fetched for `place_order` formatting
+    symbol_rules: RefCell<HashMap<String, SymbolRules>>,
     // Connection pool for reuse (simplified for now)
     // In production, you'd want a proper connection pool
 }
@@ -137,26 +585,148 @@ impl BinanceRestClient {
         info!("🔗 Binance REST client created");
         info!("   Base URL: {}", base_url);
         
-        let https_client = MonoioHttpsClient::new()?;
+        let mut https_client = MonoioHttpsClient::new()?;
+        if let Some(timeout) = config.request_timeout() {
+            https_client = https_client.with_timeout(timeout);
+        }
         
         Ok(Self {
             config,
             base_url,
             https_client,
+            incidents: None,
+            rate_limiter: RefCell::new(RateLimiter::default()),
+            retry: RetryPolicy::default(),
+            symbol_rules: RefCell::new(HashMap::new()),
         })
     }
     
     /// Test connectivity (ping endpoint)
     pub async fn ping(&self) -> Result<()> {
         let endpoint = "/api/v3/ping";
-        let _response = self.get_request(endpoint, None).await?;
+        let _response = self.get_request(endpoint, EndpointClass::MarketData, None).await?;
         Ok(())
     }
     
+    /// Record traffic into, or replay it from, a cassette
+    /// 
+    /// # Example
+    /// ```rust,ignore
+    /// let cassette = CassetteHandle::replay_file("cassettes/binance_rest.json")?;
+    /// let client = BinanceRestClient::new(config).await?.with_cassette(cassette);
+    /// ```
+    pub fn with_cassette(mut self, cassette: CassetteHandle) -> Self {
+        self.https_client = self.https_client.with_cassette(cassette);
+        self
+    }
+    
+    /// Answer requests from a `MockBinance` instead of the network
+    pub fn with_mock(mut self, mock: MockBinance) -> Self {
+        self.https_client = self.https_client.with_mock(mock);
+        self
+    }
+    
+    /// Inject network faults into every request, see `crate::chaos`
+    pub fn with_faults(mut self, faults: FaultInjector) -> Self {
+        self.https_client = self.https_client.with_faults(faults);
+        self
+    }
+    
+    /// Publish SLO breaches and bans on an incident bus
+    pub fn with_incidents(mut self, bus: IncidentBus) -> Self {
+        self.incidents = Some(bus);
+        self
+    }
+    
+    /// Replace the default request-weight/order-count limiter
+    pub fn with_rate_limiter(mut self, config: RateLimiterConfig) -> Self {
+        self.rate_limiter = RefCell::new(RateLimiter::new(config));
+        self
+    }
+    
+    /// Replace the retry policy for transient failures (see `RetryPolicy`)
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+    
+    /// Current weight and order-count usage, as last reported by Binance
+    /// plus requests sent since
+    pub fn rate_limit_status(&self) -> RateLimitStatus {
+        self.rate_limiter.borrow_mut().status(nanos() / 1_000_000)
+    }
+    
+    /// Adopt limits reported by `/api/v3/rateLimit/order` or `exchangeInfo`,
+    /// and the counts where they are included
+    pub fn sync_rate_limits(&self, limits: &[OrderRateLimit]) {
+        self.rate_limiter.borrow_mut().sync_limits(limits, nanos() / 1_000_000);
+    }
+    
+    fn publish_incident(&self, incident: Incident) {
+        if let Some(bus) = &self.incidents {
+            bus.publish(incident);
+        }
+    }
+    
+    /// Run the startup self-test against this venue
+    /// 
+    /// Extends the host-local `DoctorReport` with REST round-trip time (median
+    /// of `samples` pings) and TLS handshake time, judged against the
+    /// market-data SLO and timeout budget.
+    /// 
+    /// # Example
+    /// ```rust,ignore
+    /// let report = client.doctor(5).await;
+    /// report.log();
+    /// ```
+    pub async fn doctor(&self, samples: usize) -> DoctorReport {
+        let mut report = DoctorReport::run_local();
+        let settings = self.config.endpoint_settings(EndpointClass::MarketData);
+        let slo_ms = settings.slo_us as f64 / 1000.0;
+        let timeout_ms = settings.timeout_ms as f64;
+        
+        let mut rtts = Vec::with_capacity(samples);
+        let mut last_error = None;
+        for _ in 0..samples.max(1) {
+            let timer = PerfTimer::start("doctor_rest_rtt");
+            match self.ping().await {
+                Ok(()) => rtts.push(timer.elapsed_micros()),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        
+        if rtts.is_empty() {
+            let detail = last_error.map(|e| e.to_string()).unwrap_or_default();
+            report.add(DoctorCheck::status("rest_rtt", CheckStatus::Fail, detail));
+        } else {
+            rtts.sort_unstable();
+            let median_ms = rtts[rtts.len() / 2] as f64 / 1000.0;
+            report.add(
+                DoctorCheck::measured("rest_rtt", median_ms, slo_ms, Some(timeout_ms), "ms")
+                    .with_detail(format!("{} samples to {}", rtts.len(), self.base_url)),
+            );
+        }
+        
+        match self.https_client.measure_handshake(self.base_url.as_str()).await {
+            Ok(timing) => {
+                report.add(DoctorCheck::measured(
+                    "tls_handshake",
+                    timing.tls_handshake_micros as f64 / 1000.0,
+                    slo_ms,
+                    Some(timeout_ms),
+                    "ms",
+                ).with_detail(format!("tcp connect {:.1}ms", timing.tcp_connect_micros as f64 / 1000.0)));
+            }
+            Err(e) => report.add(DoctorCheck::status("tls_handshake", CheckStatus::Fail, e.to_string())),
+        }
+        
+        report
+    }
+    
     /// Get server time
     pub async fn server_time(&self) -> Result<u64> {
         let endpoint = "/api/v3/time";
-        let response = self.get_request(endpoint, None).await?;
+        let response = self.get_request(endpoint, EndpointClass::MarketData, None).await?;
         
         let server_time: u64 = response["serverTime"]
             .as_u64()
@@ -172,21 +742,109 @@ impl BinanceRestClient {
     
     /// Get exchange information
     pub async fn exchange_info(&self) -> Result<ExchangeInfo> {
+        self.exchange_info_filtered(&ExchangeInfoParams::default()).await
+    }
+    
+    /// Get exchange information restricted by symbol status and/or permissions
+    /// 
+    /// # Example
+    /// ```rust,ignore
+    /// // Only spot symbols that are currently trading
+    /// let info = client.exchange_info_filtered(&ExchangeInfoParams {
+    ///     symbol_status: Some("TRADING"),
+    ///     permissions: &["SPOT"],
+    /// }).await?;
+    /// ```
+    pub async fn exchange_info_filtered(&self, filter: &ExchangeInfoParams<'_>) -> Result<ExchangeInfo> {
         let endpoint = "/api/v3/exchangeInfo";
-        let response = self.get_request(endpoint, None).await?;
         
-        serde_json::from_value(response)
-            .map_err(|e| ExchangeError::SerializationError(e.to_string()))
+        let params = filter.query_params();
+        let params = params.iter().map(|(k, v)| (*k, v.as_str())).collect::<Vec<_>>();
+        let params = if params.is_empty() { None } else { Some(params) };
+        let body = self.get_request_text(endpoint, EndpointClass::MarketData, params).await?;
+        
+        if self.config.lenient_exchange_info {
+            ExchangeInfo::from_json_lenient(&body)
+        } else {
+            decode(endpoint, &body)
+        }
     }
     
+    /// Trading rules for `symbol`, fetched once and cached
+    pub async fn symbol_rules(&self, symbol: &str) -> Result<SymbolRules> {
+        if let Some(rules) = self.symbol_rules.borrow().get(symbol) {
+            return Ok(rules.clone());
+        }
+        let rules = self.fetch_symbol_rules(symbol).await?;
+        self.cache_symbol_rules(rules.clone());
+        Ok(rules)
+    }
+
+    async fn fetch_symbol_rules(&self, symbol: &str) -> Result<SymbolRules> {
+        let endpoint = "/api/v3/exchangeInfo";
+        let body = self.get_request_text(endpoint, EndpointClass::MarketData, Some(vec![("symbol", symbol)])).await?;
+        let info: ExchangeInfo = decode(endpoint, &body)?;
+        info.symbols.iter()
+            .find(|s| s.symbol == symbol)
+            .ok_or_else(|| ExchangeError::InvalidSymbol(symbol.to_string()))?
+            .rules()
+    }
+
+    /// Refetch the rules of every cached symbol and report the ones that changed
+    pub async fn refresh_symbol_rules(&self) -> Result<Vec<InstrumentChanged>> {
+        let mut symbols: Vec<String> = self.symbol_rules.borrow().keys().cloned().collect();
+        if symbols.is_empty() {
+            return Ok(Vec::new());
+        }
+        symbols.sort_unstable();
+        let endpoint = "/api/v3/exchangeInfo";
+        let list = format!("[{}]", symbols.iter().map(|s| format!("\"{s}\"")).collect::<Vec<_>>().join(","));
+        let body = self.get_request_text(endpoint, EndpointClass::MarketData, Some(vec![("symbols", list.as_str())])).await?;
+        let info: ExchangeInfo = decode(endpoint, &body)?;
+        let mut changes = Vec::new();
+        for symbol in &info.symbols {
+            changes.extend(self.update_symbol_rules(symbol.rules()?));
+        }
+        Ok(changes)
+    }
+
+    /// Refetch one symbol's rules, e.g. after a -1013 filter failure
+    pub async fn refresh_rules_for(&self, symbol: &str) -> Result<Option<InstrumentChanged>> {
+        let rules = self.fetch_symbol_rules(symbol).await?;
+        Ok(self.update_symbol_rules(rules))
+    }
+
+    /// Cache `rules`, reporting how they differ from the ones they replace
+    pub fn update_symbol_rules(&self, rules: SymbolRules) -> Option<InstrumentChanged> {
+        let previous = self.symbol_rules.borrow_mut().insert(rules.symbol.clone(), rules.clone())?;
+        let change = InstrumentChanged::detect(&previous, &rules)?;
+        warn!(
+            "📐 {} trading rules changed (tick changed: {}, step changed: {})",
+            change.symbol, change.tick_changed(), change.step_changed()
+        );
+        Some(change)
+    }
+
+    /// Rules to format orders on `symbol` with; `None` when `filter_precision` is off
+    pub async fn order_rules(&self, symbol: &str) -> Result<Option<SymbolRules>> {
+        if !self.config.filter_precision {
+            return Ok(None);
+        }
+        self.symbol_rules(symbol).await.map(Some)
+    }
+
+    /// Use `rules` for formatting orders on its symbol without fetching them
+    pub fn cache_symbol_rules(&self, rules: SymbolRules) {
+        self.symbol_rules.borrow_mut().insert(rules.symbol.clone(), rules);
+    }
+
     /// Get ticker information for a symbol
     pub async fn ticker_24hr(&self, symbol: &str) -> Result<Ticker24hr> {
         let endpoint = "/api/v3/ticker/24hr";
         let params = vec![("symbol", symbol)];
-        let response = self.get_request(endpoint, Some(params)).await?;
+        let response = self.get_request(endpoint, EndpointClass::MarketData, Some(params)).await?;
         
-        serde_json::from_value(response)
-            .map_err(|e| ExchangeError::SerializationError(e.to_string()))
+        decode_value(endpoint, response)
     }
     
     /// Alias for ticker_24hr() - Get 24hr ticker statistics
@@ -200,7 +858,7 @@ impl BinanceRestClient {
     /// - Number of trades
     /// 
     /// # Example
-    /// ```rust
+    /// ```rust,ignore
     /// let ticker = client.get_24hr_ticker("BTCUSDT").await?;
     /// println!("24hr change: {}% Volume: {} BTC",
     ///     ticker.price_change_percent, ticker.volume);
@@ -209,6 +867,70 @@ impl BinanceRestClient {
         self.ticker_24hr(symbol).await
     }
     
+    /// Get 24hr ticker statistics for every symbol in one request
+    /// 
+    /// This is a heavy call (request weight 80); prefer it over per-symbol
+    /// polling when refreshing a whole universe, e.g. for market scanners.
+    pub async fn ticker_24hr_all(&self) -> Result<Vec<Ticker24hr>> {
+        let endpoint = "/api/v3/ticker/24hr";
+        let body = self.get_request_text(endpoint, EndpointClass::MarketData, None).await?;
+        
+        decode(endpoint, &body)
+    }
+    
+    /// Get 24hr ticker statistics for a set of symbols in one request
+    pub async fn ticker_24hr_many(&self, symbols: &[&str]) -> Result<Vec<Ticker24hr>> {
+        let endpoint = "/api/v3/ticker/24hr";
+        let symbols_param = symbols_json_array(symbols);
+        let params = vec![("symbols", symbols_param.as_str())];
+        let body = self.get_request_text(endpoint, EndpointClass::MarketData, Some(params)).await?;
+        
+        decode(endpoint, &body)
+    }
+    
+    /// Refresh a market scanner from the full-market 24hr ticker and run a scan
+    /// 
+    /// Symbols whose statistics can't be represented (e.g. prices beyond the
+    /// `Fixed` range) are skipped rather than failing the whole refresh.
+    pub async fn refresh_scanner(&self, scanner: &mut crate::scanner::MarketScanner) -> Result<crate::scanner::ScanResult> {
+        let tickers = self.ticker_24hr_all().await?;
+        let total = tickers.len();
+        
+        let converted: Vec<_> = tickers.iter().filter_map(|t| t.to_scan_ticker().ok()).collect();
+        if converted.len() < total {
+            debug!("🔎 Scanner skipped {} unparseable tickers", total - converted.len());
+        }
+        
+        scanner.update_all(converted);
+        Ok(scanner.scan())
+    }
+    
+    /// Periodically refresh a scanner, passing each result to `on_result`
+    /// 
+    /// Runs until `on_result` returns `false`. Refresh errors are logged and
+    /// retried on the next tick. Requires a runtime with the timer enabled.
+    pub async fn run_scanner<F>(
+        &self,
+        scanner: &mut crate::scanner::MarketScanner,
+        interval: std::time::Duration,
+        mut on_result: F,
+    ) -> Result<()>
+    where
+        F: FnMut(&crate::scanner::ScanResult) -> bool,
+    {
+        loop {
+            match self.refresh_scanner(scanner).await {
+                Ok(result) => {
+                    if !on_result(&result) {
+                        return Ok(());
+                    }
+                }
+                Err(e) => warn!("🔎 Scanner refresh failed: {}", e),
+            }
+            monoio::time::sleep(interval).await;
+        }
+    }
+    
     /// Get order book for a symbol
     pub async fn order_book(&self, symbol: &str, limit: Option<u32>) -> Result<OrderBookResponse> {
         let endpoint = "/api/v3/depth";
@@ -220,10 +942,9 @@ impl BinanceRestClient {
             params.push(("limit", &limit_str));
         }
         
-        let response = self.get_request(endpoint, Some(params)).await?;
+        let response = self.get_request(endpoint, EndpointClass::MarketData, Some(params)).await?;
         
-        serde_json::from_value(response)
-            .map_err(|e| ExchangeError::SerializationError(e.to_string()))
+        decode_value(endpoint, response)
     }
     
     /// Get recent trades for a symbol
@@ -237,29 +958,238 @@ impl BinanceRestClient {
             params.push(("limit", &limit_str));
         }
         
-        let response = self.get_request(endpoint, Some(params)).await?;
+        let response = self.get_request(endpoint, EndpointClass::MarketData, Some(params)).await?;
+        
+        decode_value(endpoint, response)
+    }
+    
+    /// Get older market trades (requires API key, no signature)
+    /// 
+    /// # Arguments
+    /// * `symbol` - Trading pair (e.g., "BTCUSDT")
+    /// * `limit` - Number of trades to return (default 500, max 1000)
+    /// * `from_id` - Trade id to fetch from; defaults to the most recent trades
+    /// 
+    /// # Example
+    /// ```rust,ignore
+    /// // Page forward through the archive
+    /// let page = client.historical_trades("BTCUSDT", Some(1000), Some(last_id + 1)).await?;
+    /// ```
+    pub async fn historical_trades(
+        &self,
+        symbol: &str,
+        limit: Option<u32>,
+        from_id: Option<u64>,
+    ) -> Result<Vec<TradeResponse>> {
+        let endpoint = "/api/v3/historicalTrades";
+        let mut params = vec![("symbol", symbol)];
+        
+        let limit_str = limit.map(|l| l.to_string());
+        let from_id_str = from_id.map(|id| id.to_string());
+        if let Some(ref l) = limit_str {
+            params.push(("limit", l));
+        }
+        if let Some(ref id) = from_id_str {
+            params.push(("fromId", id));
+        }
+        
+        let response = self.api_key_request(endpoint, EndpointClass::MarketData, params).await?;
+        
+        decode(endpoint, &response)
+    }
+    
+    /// Fetch up to `count` consecutive trades starting at `from_id`, paging by `fromId`
+    pub async fn historical_trades_from(
+        &self,
+        symbol: &str,
+        from_id: u64,
+        count: usize,
+    ) -> Result<Vec<TradeResponse>> {
+        const PAGE_SIZE: usize = 1000;
+        
+        let mut trades = Vec::with_capacity(count);
+        let mut next_id = from_id;
+        
+        while trades.len() < count {
+            let limit = (count - trades.len()).min(PAGE_SIZE) as u32;
+            let page = self.historical_trades(symbol, Some(limit), Some(next_id)).await?;
+            let page_len = page.len();
+            
+            if let Some(last) = page.last() {
+                next_id = last.id + 1;
+            }
+            trades.extend(page);
+            
+            // A short page means we've caught up with the live tape
+            if page_len < limit as usize {
+                break;
+            }
+        }
+        
+        debug!("📜 Fetched {} historical trades for {} from id {}", trades.len(), symbol, from_id);
+        Ok(trades)
+    }
+    
+    /// Aggregated trades for a symbol (`/api/v3/aggTrades`)
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let page = client.get_agg_trades("BTCUSDT", &AggTradesQuery::from_id(last_id + 1).with_limit(1000)).await?;
+    /// ```
+    pub async fn get_agg_trades(&self, symbol: &str, query: &AggTradesQuery) -> Result<Vec<AggTradeResponse>> {
+        let endpoint = "/api/v3/aggTrades";
+        let query_params = query.query_params();
+        let mut params = vec![("symbol", symbol)];
+        params.extend(query_params.iter().map(|(k, v)| (*k, v.as_str())));
+        let body = self.get_request_text(endpoint, EndpointClass::MarketData, Some(params)).await?;
         
-        serde_json::from_value(response)
-            .map_err(|e| ExchangeError::SerializationError(e.to_string()))
+        decode(endpoint, &body)
+    }
+    
+    /// Every aggregated trade between two times, in order
+    ///
+    /// Paged by time one hour window at a time, the most Binance accepts.
+    pub async fn agg_trades_between(&self, symbol: &str, start_time: u64, end_time: u64) -> Result<Vec<AggTradeResponse>> {
+        let trades = Paginator::new(HISTORY_PAGE_LIMIT)
+            .with_max_window_ms(HOUR_MS)
+            .run(|w| async move {
+                self.get_agg_trades(symbol, &AggTradesQuery::between(w.start, w.end).with_limit(w.limit)).await
+            }, start_time, end_time)
+            .await?;
+        debug!("📜 Fetched {} aggregated trades for {} between {} and {}", trades.len(), symbol, start_time, end_time);
+        Ok(trades)
     }
     
     /// Get account information (requires authentication)
+    /// 
+    /// The body is deserialized straight into `AccountInfo` without an
+    /// intermediate `Value` tree, which matters for accounts with many assets.
     pub async fn get_account_info(&self) -> Result<AccountInfo> {
+        self.get_account_info_with(false).await
+    }
+    
+    /// Get account information, optionally asking Binance to omit zero balances
+    pub async fn get_account_info_with(&self, omit_zero_balances: bool) -> Result<AccountInfo> {
         let endpoint = "/api/v3/account";
-        let response = self.signed_request(endpoint, "GET", None).await?;
+        let params = omit_zero_balances.then(|| HashMap::from([("omitZeroBalances", "true")]));
+        let body = self.signed_request_text(endpoint, EndpointClass::Account, "GET", params).await?;
+        
+        decode(endpoint, &body)
+    }
+    
+    /// Get account information keeping only non-zero balances
+    /// 
+    /// Sends `omitZeroBalances=true` and drops any zero balances the server
+    /// returns anyway. Useful for accounts holding hundreds of dust assets.
+    pub async fn get_account_info_non_zero(&self) -> Result<AccountInfo> {
+        let endpoint = "/api/v3/account";
+        let params = HashMap::from([("omitZeroBalances", "true")]);
+        let body = self.signed_request_text(endpoint, EndpointClass::Account, "GET", Some(params)).await?;
+        
+        AccountInfo::from_json_non_zero(&body)
+    }
+    
+    /// Unified account summary of a portfolio margin account
+    pub async fn get_portfolio_margin_account(&self) -> Result<PortfolioMarginAccount> {
+        self.signed_papi_request("/papi/v1/account", None).await
+    }
+    
+    /// Per-asset balances of a portfolio margin account, across cross margin
+    /// and USDⓈ-M / COIN-M futures wallets
+    pub async fn get_portfolio_margin_balances(&self) -> Result<Vec<PortfolioMarginBalance>> {
+        self.signed_papi_request("/papi/v1/balance", None).await
+    }
+    
+    /// USDⓈ-M futures income entries: funding fees, realized PnL, commissions...
+    /// 
+    /// Reads `/papi/v1/um/income` for portfolio margin accounts, otherwise
+    /// `/fapi/v1/income`.
+    pub async fn get_futures_income(&self, query: &IncomeQuery) -> Result<Vec<FuturesIncome>> {
+        let params = query.query_params();
+        let params = params.iter().map(|(name, value)| (*name, value.as_str())).collect();
+        if self.config.portfolio_margin {
+            return self.signed_papi_request("/papi/v1/um/income", Some(params)).await;
+        }
         
-        serde_json::from_value(response)
-            .map_err(|e| ExchangeError::SerializationError(e.to_string()))
+        let endpoint = "/fapi/v1/income";
+        let base_url = Url::parse(&self.config.fapi_url)
+            .map_err(|e| ExchangeError::ConfigurationError(format!("Invalid futures URL: {e}")))?;
+        let response = self.signed_request_to(&base_url, endpoint, EndpointClass::Account, "GET", Some(params)).await?;
+        
+        decode(endpoint, &response)
+    }
+    
+    /// Journal every futures income entry since the last journaled one
+    /// 
+    /// A fresh journal starts at `since_ms`; Binance keeps three months of
+    /// income history. Returns the number of newly journaled entries.
+    pub async fn sync_futures_income(&self, since_ms: u64, journal: &mut crate::journal::Journal) -> Result<usize> {
+        let timer = PerfTimer::start("binance_sync_futures_income".to_string());
+        let mut next = Some(journal.last_income_time("binance").unwrap_or(since_ms));
+        let mut added = 0;
+        
+        while let Some(start) = next {
+            let page = self.get_futures_income(&IncomeQuery::since(start).with_limit(INCOME_PAGE_LIMIT)).await?;
+            next = next_income_page(&page, start, INCOME_PAGE_LIMIT);
+            for income in &page {
+                if journal.record("binance", crate::journal::JournalEvent::Income(income.to_record()?)) {
+                    added += 1;
+                }
+            }
+        }
+        
+        info!("💸 Journaled {} new futures income entries", added);
+        timer.log_elapsed();
+        Ok(added)
+    }
+    
+    /// What the account can put up for new orders, for `RiskEngine::set_buying_power`
+    /// 
+    /// Spot accounts are limited by each asset's free balance. Under portfolio
+    /// margin all collateral backs every order, so the check is against the
+    /// unified account's available balance instead; checking spot free
+    /// balances there blocks orders the exchange would accept.
+    pub async fn buying_power(&self) -> Result<BuyingPower> {
+        if self.config.portfolio_margin {
+            let account = self.get_portfolio_margin_account().await?;
+            if !account.is_normal() {
+                warn!("⚠️ Portfolio margin account status {} (uniMMR {})", account.account_status, account.uni_mmr);
+            }
+            return account.buying_power();
+        }
+        let account = self.get_account_info_non_zero().await?;
+        Ok(BuyingPower::Spot {
+            free: account.balances.iter()
+                .map(|b| (b.asset.clone(), b.free.to_f64_lossy()))
+                .collect(),
+        })
     }
     
     /// Get symbol price ticker
     pub async fn get_symbol_price_ticker(&self, symbol: &str) -> Result<PriceTicker> {
         let endpoint = "/api/v3/ticker/price";
         let params = vec![("symbol", symbol)];
-        let response = self.get_request(endpoint, Some(params)).await?;
+        let response = self.get_request(endpoint, EndpointClass::MarketData, Some(params)).await?;
+        
+        decode_value(endpoint, response)
+    }
+    
+    /// Get latest prices for every symbol in one request
+    pub async fn get_all_price_tickers(&self) -> Result<Vec<PriceTicker>> {
+        let endpoint = "/api/v3/ticker/price";
+        let body = self.get_request_text(endpoint, EndpointClass::MarketData, None).await?;
+        
+        decode(endpoint, &body)
+    }
+    
+    /// Get latest prices for a set of symbols in one request
+    pub async fn get_price_tickers(&self, symbols: &[&str]) -> Result<Vec<PriceTicker>> {
+        let endpoint = "/api/v3/ticker/price";
+        let symbols_param = symbols_json_array(symbols);
+        let params = vec![("symbols", symbols_param.as_str())];
+        let body = self.get_request_text(endpoint, EndpointClass::MarketData, Some(params)).await?;
         
-        serde_json::from_value(response)
-            .map_err(|e| ExchangeError::SerializationError(e.to_string()))
+        decode(endpoint, &body)
     }
     
     /// Test new order (validates order without placing)
@@ -286,8 +1216,12 @@ impl BinanceRestClient {
         if let Some(iq) = order_params.iceberg_qty {
             params.insert("icebergQty", iq);
         }
+        if let Some(id) = order_params.new_client_order_id {
+            ClientIdPolicy::binance().validate(id)?;
+            params.insert("newClientOrderId", id);
+        }
         
-        let _response = self.signed_request(endpoint, "POST", Some(params)).await?;
+        let _response = self.signed_request(endpoint, EndpointClass::Order, "POST", Some(params)).await?;
         Ok(())
     }
 
@@ -315,11 +1249,14 @@ impl BinanceRestClient {
         if let Some(iq) = order_params.iceberg_qty {
             params.insert("icebergQty", iq);
         }
+        if let Some(id) = order_params.new_client_order_id {
+            ClientIdPolicy::binance().validate(id)?;
+            params.insert("newClientOrderId", id);
+        }
         
-        let response = self.signed_request(endpoint, "POST", Some(params)).await?;
+        let response = self.signed_request(endpoint, EndpointClass::Order, "POST", Some(params)).await?;
         
-        serde_json::from_value(response)
-            .map_err(|e| ExchangeError::SerializationError(e.to_string()))
+        decode_value(endpoint, response)
     }
 
     /// Simplified order placement using Fixed types
@@ -332,7 +1269,7 @@ impl BinanceRestClient {
     /// * `price` - Order price as Fixed (required for limit orders)
     /// 
     /// # Example
-    /// ```rust
+    /// ```rust,ignore
     /// let order = client.place_order(
     ///     "BTCUSDT",
     ///     OrderSide::Buy,
@@ -362,9 +1299,10 @@ impl BinanceRestClient {
             crate::types::OrderType::StopLossLimit => "STOP_LOSS_LIMIT",
         };
         
-        // Convert Fixed to string
-        let qty_str = quantity.to_string();
-        let price_str = price.map(|p| p.to_string());
+        // Render at the symbol's precision; extra decimals are rejected with -1111
+        let rules = self.order_rules(symbol).await?;
+        let qty_str = render_quantity(rules.as_ref(), quantity, order_type == crate::types::OrderType::Market);
+        let price_str = price.map(|p| render_price(rules.as_ref(), p));
         
         // Determine time in force for limit orders
         let time_in_force = match order_type {
@@ -382,6 +1320,7 @@ impl BinanceRestClient {
             time_in_force,
             stop_price: None,
             iceberg_qty: None,
+            new_client_order_id: None,
         };
         
         self.new_order(&order_params).await
@@ -396,10 +1335,28 @@ impl BinanceRestClient {
         params.insert("symbol", symbol);
         params.insert("orderId", &order_id_str);
         
-        let response = self.signed_request(endpoint, "DELETE", Some(params)).await?;
+        let response = self.signed_request(endpoint, EndpointClass::Cancel, "DELETE", Some(params)).await?;
+        
+        decode_value(endpoint, response)
+    }
+
+    /// Cancel every open order on a symbol
+    /// 
+    /// Order lists (OCO) in the response are skipped; only individual orders are returned.
+    pub async fn cancel_all_orders(&self, symbol: &str) -> Result<Vec<CancelOrderResponse>> {
+        let endpoint = "/api/v3/openOrders";
+        
+        let mut params = HashMap::new();
+        params.insert("symbol", symbol);
         
-        serde_json::from_value(response)
-            .map_err(|e| ExchangeError::SerializationError(e.to_string()))
+        let response = self.signed_request(endpoint, EndpointClass::Cancel, "DELETE", Some(params)).await?;
+        let entries: Vec<Value> = decode_value(endpoint, response)?;
+        
+        entries
+            .into_iter()
+            .filter(|entry| entry.get("orderId").is_some())
+            .map(|entry| decode_value(endpoint, entry))
+            .collect()
     }
 
     /// Query order status
@@ -411,10 +1368,9 @@ impl BinanceRestClient {
         params.insert("symbol", symbol);
         params.insert("orderId", &order_id_str);
         
-        let response = self.signed_request(endpoint, "GET", Some(params)).await?;
+        let response = self.signed_request(endpoint, EndpointClass::Account, "GET", Some(params)).await?;
         
-        serde_json::from_value(response)
-            .map_err(|e| ExchangeError::SerializationError(e.to_string()))
+        decode_value(endpoint, response)
     }
 
     /// Get all open orders for a symbol
@@ -426,28 +1382,243 @@ impl BinanceRestClient {
             params.insert("symbol", s);
         }
         
-        let response = self.signed_request(endpoint, "GET", Some(params)).await?;
+        let response = self.signed_request(endpoint, EndpointClass::Account, "GET", Some(params)).await?;
+        
+        decode_value(endpoint, response)
+    }
+
+    /// Get orders that expired due to self-trade prevention
+    /// 
+    /// # Example
+    /// ```rust,ignore
+    /// let prevented = client
+    ///     .get_prevented_matches("BTCUSDT", PreventedMatchQuery::ByOrderId(12345678))
+    ///     .await?;
+    /// ```
+    pub async fn get_prevented_matches(
+        &self,
+        symbol: &str,
+        query: PreventedMatchQuery,
+    ) -> Result<Vec<PreventedMatchResponse>> {
+        let endpoint = "/api/v3/myPreventedMatches";
+        
+        let mut params = HashMap::new();
+        params.insert("symbol", symbol);
+        
+        let id_str;
+        let limit_str;
+        match query {
+            PreventedMatchQuery::ById(id) => {
+                id_str = id.to_string();
+                params.insert("preventedMatchId", &id_str);
+            }
+            PreventedMatchQuery::ByOrderId(order_id) => {
+                id_str = order_id.to_string();
+                params.insert("orderId", &id_str);
+            }
+            PreventedMatchQuery::FromId { from_prevented_match_id, limit } => {
+                id_str = from_prevented_match_id.to_string();
+                params.insert("fromPreventedMatchId", &id_str);
+                if let Some(limit) = limit {
+                    limit_str = limit.to_string();
+                    params.insert("limit", &limit_str);
+                }
+            }
+        }
+        
+        let response = self.signed_request(endpoint, EndpointClass::Account, "GET", Some(params)).await?;
+        
+        decode_value(endpoint, response)
+    }
+    
+    /// Pull prevented matches from `from_id` onwards into a journal
+    /// 
+    /// Returns the number of newly journaled entries; already-known matches are skipped.
+    pub async fn sync_prevented_matches(
+        &self,
+        symbol: &str,
+        from_id: u64,
+        journal: &mut crate::journal::Journal,
+    ) -> Result<usize> {
+        let query = PreventedMatchQuery::FromId { from_prevented_match_id: from_id, limit: Some(1000) };
+        let matches = self.get_prevented_matches(symbol, query).await?;
+        
+        let mut added = 0;
+        for m in matches {
+            let record = m.to_record()?;
+            if journal.record("binance", crate::journal::JournalEvent::PreventedMatch(record)) {
+                added += 1;
+            }
+        }
+        
+        info!("🛡️ Journaled {} new prevented matches for {}", added, symbol);
+        Ok(added)
+    }
+    
+    /// Get current order-count usage for all order rate limit windows
+    pub async fn get_order_rate_limit(&self) -> Result<Vec<OrderRateLimit>> {
+        let endpoint = "/api/v3/rateLimit/order";
+        let response = self.signed_request(endpoint, EndpointClass::Account, "GET", None).await?;
+        
+        decode_value(endpoint, response)
+    }
+    
+    /// Whether BNB is burned to pay spot trading fees and margin interest
+    pub async fn get_bnb_burn(&self) -> Result<BnbBurnStatus> {
+        let endpoint = "/sapi/v1/bnbBurn";
+        let response = self.signed_request(endpoint, EndpointClass::Account, "GET", None).await?;
+        
+        decode_value(endpoint, response)
+    }
+    
+    /// Toggle BNB burn; `None` leaves that setting unchanged
+    pub async fn set_bnb_burn(&self, spot: Option<bool>, interest: Option<bool>) -> Result<BnbBurnStatus> {
+        let endpoint = "/sapi/v1/bnbBurn";
+        
+        let mut params = HashMap::new();
+        if let Some(spot) = spot {
+            params.insert("spotBNBBurn", if spot { "true" } else { "false" });
+        }
+        if let Some(interest) = interest {
+            params.insert("interestBNBBurn", if interest { "true" } else { "false" });
+        }
+        if params.is_empty() {
+            return Err(ExchangeError::ConfigurationError("set_bnb_burn needs at least one setting".to_string()));
+        }
+        
+        let response = self.signed_request(endpoint, EndpointClass::Account, "POST", Some(params)).await?;
+        let status: BnbBurnStatus = decode_value(endpoint, response)?;
+        info!("🔥 BNB burn: spot={} interest={}", status.spot_bnb_burn, status.interest_bnb_burn);
+        Ok(status)
+    }
+    
+    /// Re-sync the rate limiter with the account's current order counts,
+    /// which include orders sent by other sessions
+    pub async fn sync_order_limits(&self) -> Result<()> {
+        let limits = self.get_order_rate_limit().await?;
+        self.sync_rate_limits(&limits);
+        Ok(())
+    }
+
+    /// Get trade history for a symbol
+    pub async fn my_trades(&self, symbol: &str, limit: Option<u32>) -> Result<Vec<MyTradeResponse>> {
+        let endpoint = "/api/v3/myTrades";
+        
+        let mut params = HashMap::new();
+        params.insert("symbol", symbol);
+        
+        let limit_str = limit.map(|l| l.to_string());
+        if let Some(ref l) = limit_str {
+            params.insert("limit", l);
+        }
+        
+        let response = self.signed_request(endpoint, EndpointClass::Account, "GET", Some(params)).await?;
+        
+        decode_value(endpoint, response)
+    }
+
+    /// Get trade history for a symbol within a time range
+    /// 
+    /// Binance rejects ranges longer than 24 hours when both bounds are set.
+    pub async fn my_trades_in_range(
+        &self,
+        symbol: &str,
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<Vec<MyTradeResponse>> {
+        let endpoint = "/api/v3/myTrades";
+        
+        let mut params = HashMap::new();
+        params.insert("symbol", symbol);
+        
+        let start_time_str = start_time.map(|t| t.to_string());
+        let end_time_str = end_time.map(|t| t.to_string());
+        let limit_str = limit.map(|l| l.to_string());
+        if let Some(ref st) = start_time_str {
+            params.insert("startTime", st);
+        }
+        if let Some(ref et) = end_time_str {
+            params.insert("endTime", et);
+        }
+        if let Some(ref l) = limit_str {
+            params.insert("limit", l);
+        }
+        
+        let response = self.signed_request(endpoint, EndpointClass::Account, "GET", Some(params)).await?;
+        
+        decode_value(endpoint, response)
+    }
+
+    /// Full trade history from `from_id` onwards, paging through `fromId`
+    /// 
+    /// Binance returns at most 1000 trades per call; pages are requested until
+    /// a short page comes back.
+    /// 
+    /// # Example
+    /// ```rust,ignore
+    /// // Everything since the account's first trade on BTCUSDT
+    /// let trades = client.my_trades_since("BTCUSDT", 0).await?;
+    /// ```
+    pub async fn my_trades_since(&self, symbol: &str, from_id: u64) -> Result<Vec<MyTradeResponse>> {
+        let timer = PerfTimer::start("binance_my_trades_since".to_string());
+        let mut trades = Vec::new();
+        let mut next = Some(from_id);
+        
+        while let Some(from) = next {
+            let page = self.my_trades_page(symbol, from, MY_TRADES_PAGE_LIMIT).await?;
+            next = next_trade_page(&page, MY_TRADES_PAGE_LIMIT);
+            trades.extend(page);
+        }
+        
+        debug!("📜 Fetched {} trades for {} from id {}", trades.len(), symbol, from_id);
+        timer.log_elapsed();
+        Ok(trades)
+    }
+    
+    /// Every trade for a symbol between two times, in order
+    ///
+    /// Paged one day window at a time; use `my_trades_since` to follow trade ids instead.
+    pub async fn my_trades_between(&self, symbol: &str, start_time: u64, end_time: u64) -> Result<Vec<MyTradeResponse>> {
+        Paginator::new(HISTORY_PAGE_LIMIT)
+            .with_max_window_ms(DAY_MS)
+            .run(|w| self.my_trades_in_range(symbol, Some(w.start), Some(w.end), Some(w.limit)), start_time, end_time)
+            .await
+    }
+    
+    /// Backfill the journal with every trade not yet journaled for `symbol`
+    /// 
+    /// Resumes after the last journaled trade id, so onboarding an existing
+    /// account and periodic re-syncs use the same call. Returns the number of
+    /// newly journaled trades.
+    pub async fn sync_my_trades(&self, symbol: &str, journal: &mut crate::journal::Journal) -> Result<usize> {
+        let from_id = journal.last_trade_id(symbol).map_or(0, |id| id + 1);
+        let trades = self.my_trades_since(symbol, from_id).await?;
+        
+        let mut added = 0;
+        for trade in trades {
+            if journal.record("binance", crate::journal::JournalEvent::Trade(trade.to_record()?)) {
+                added += 1;
+            }
+        }
         
-        serde_json::from_value(response)
-            .map_err(|e| ExchangeError::SerializationError(e.to_string()))
+        info!("📜 Journaled {} new trades for {}", added, symbol);
+        Ok(added)
     }
-
-    /// Get trade history for a symbol
-    pub async fn my_trades(&self, symbol: &str, limit: Option<u32>) -> Result<Vec<MyTradeResponse>> {
+    
+    async fn my_trades_page(&self, symbol: &str, from_id: u64, limit: u32) -> Result<Vec<MyTradeResponse>> {
         let endpoint = "/api/v3/myTrades";
         
+        let from_id_str = from_id.to_string();
+        let limit_str = limit.to_string();
         let mut params = HashMap::new();
         params.insert("symbol", symbol);
+        params.insert("fromId", from_id_str.as_str());
+        params.insert("limit", limit_str.as_str());
         
-        let limit_str = limit.map(|l| l.to_string());
-        if let Some(ref l) = limit_str {
-            params.insert("limit", l);
-        }
-        
-        let response = self.signed_request(endpoint, "GET", Some(params)).await?;
+        let response = self.signed_request(endpoint, EndpointClass::Account, "GET", Some(params)).await?;
         
-        serde_json::from_value(response)
-            .map_err(|e| ExchangeError::SerializationError(e.to_string()))
+        decode_value(endpoint, response)
     }
 
     /// Get all orders (active, canceled, or filled) for a symbol
@@ -459,7 +1630,7 @@ impl BinanceRestClient {
     /// * `end_time` - Most recent order timestamp to fetch (optional)
     /// 
     /// # Example
-    /// ```rust
+    /// ```rust,ignore
     /// // Get last 100 orders
     /// let orders = client.get_all_orders("BTCUSDT", Some(100), None, None).await?;
     /// 
@@ -495,12 +1666,21 @@ impl BinanceRestClient {
             params.insert("endTime", et);
         }
         
-        let response = self.signed_request(endpoint, "GET", Some(params)).await?;
+        let response = self.signed_request(endpoint, EndpointClass::Account, "GET", Some(params)).await?;
         
         timer.log_elapsed();
         
-        serde_json::from_value(response)
-            .map_err(|e| ExchangeError::SerializationError(e.to_string()))
+        decode_value(endpoint, response)
+    }
+
+    /// Every order for a symbol created between two times, in order
+    ///
+    /// Paged through `allOrders` one day window at a time, the widest range it accepts.
+    pub async fn all_orders_between(&self, symbol: &str, start_time: u64, end_time: u64) -> Result<Vec<QueryOrderResponse>> {
+        Paginator::new(HISTORY_PAGE_LIMIT)
+            .with_max_window_ms(DAY_MS)
+            .run(|w| self.get_all_orders(symbol, Some(w.limit), Some(w.start), Some(w.end)), start_time, end_time)
+            .await
     }
 
     /// Get trades for a specific order
@@ -510,7 +1690,7 @@ impl BinanceRestClient {
     /// * `order_id` - The order ID to get trades for
     /// 
     /// # Example
-    /// ```rust
+    /// ```rust,ignore
     /// let trades = client.get_order_trades("BTCUSDT", 12345678).await?;
     /// for trade in trades {
     ///     println!("Trade {} - Price: {} Qty: {} Fee: {} {}",
@@ -527,15 +1707,14 @@ impl BinanceRestClient {
         params.insert("symbol", symbol);
         params.insert("orderId", &order_id_str);
         
-        let response = self.signed_request(endpoint, "GET", Some(params)).await?;
+        let response = self.signed_request(endpoint, EndpointClass::Account, "GET", Some(params)).await?;
         
         timer.log_elapsed();
         
-        serde_json::from_value(response)
-            .map_err(|e| ExchangeError::SerializationError(e.to_string()))
+        decode_value(endpoint, response)
     }
 
-    /// Get historical klines/candlestick data
+    /// Get historical klines/candlestick data as normalized candles
     /// 
     /// # Arguments
     /// * `symbol` - Trading pair (e.g., "BTCUSDT")
@@ -545,14 +1724,14 @@ impl BinanceRestClient {
     /// * `limit` - Number of klines to return (default 500, max 1000)
     /// 
     /// # Example
-    /// ```rust
+    /// ```rust,ignore
     /// // Get last 100 1-hour candles
-    /// let klines = client.get_klines("BTCUSDT", "1h", None, None, Some(100)).await?;
+    /// let candles = client.get_klines("BTCUSDT", "1h", None, None, Some(100)).await?;
     /// 
     /// // Get candles for specific time range
     /// let start = nanos() / 1_000_000 - 24 * 60 * 60 * 1000; // 24 hours ago
     /// let end = nanos() / 1_000_000;
-    /// let klines = client.get_klines("BTCUSDT", "5m", Some(start), Some(end), None).await?;
+    /// let candles = client.get_klines("BTCUSDT", "5m", Some(start), Some(end), None).await?;
     /// ```
     pub async fn get_klines(
         &self,
@@ -561,6 +1740,25 @@ impl BinanceRestClient {
         start_time: Option<u64>,
         end_time: Option<u64>,
         limit: Option<u32>,
+    ) -> Result<Vec<crate::types::Candle>> {
+        self.get_klines_raw(symbol, interval, start_time, end_time, limit)
+            .await?
+            .into_iter()
+            .map(crate::types::Candle::try_from)
+            .collect()
+    }
+
+    /// Get historical klines in Binance's raw string representation
+    /// 
+    /// Use this when you need fields not carried by `Candle` (quote volume,
+    /// taker buy volumes) or want to defer decimal parsing.
+    pub async fn get_klines_raw(
+        &self,
+        symbol: &str,
+        interval: &str,
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+        limit: Option<u32>,
     ) -> Result<Vec<crate::binance::types::BinanceKline>> {
         let endpoint = "/api/v3/klines";
         let timer = PerfTimer::start("binance_get_klines".to_string());
@@ -586,39 +1784,97 @@ impl BinanceRestClient {
             params.push(("limit", l));
         }
         
-        let response = self.get_request(endpoint, Some(params)).await?;
+        let response = self.get_request(endpoint, EndpointClass::MarketData, Some(params)).await?;
         
         timer.log_elapsed();
         
         // The response is an array of arrays, need to deserialize as Vec<Vec<Value>> first
-        let raw_klines: Vec<Vec<serde_json::Value>> = serde_json::from_value(response)
-            .map_err(|e| ExchangeError::SerializationError(e.to_string()))?;
-        
-        // Convert to BinanceKline structs
-        let mut klines = Vec::with_capacity(raw_klines.len());
-        for raw_kline in raw_klines {
-            if raw_kline.len() >= 12 {
-                let kline = crate::binance::types::BinanceKline {
-                    open_time: raw_kline[0].as_u64().unwrap_or(0),
-                    open: raw_kline[1].as_str().unwrap_or("0").to_string(),
-                    high: raw_kline[2].as_str().unwrap_or("0").to_string(),
-                    low: raw_kline[3].as_str().unwrap_or("0").to_string(),
-                    close: raw_kline[4].as_str().unwrap_or("0").to_string(),
-                    volume: raw_kline[5].as_str().unwrap_or("0").to_string(),
-                    close_time: raw_kline[6].as_u64().unwrap_or(0),
-                    quote_asset_volume: raw_kline[7].as_str().unwrap_or("0").to_string(),
-                    number_of_trades: raw_kline[8].as_u64().unwrap_or(0) as u32,
-                    taker_buy_base_asset_volume: raw_kline[9].as_str().unwrap_or("0").to_string(),
-                    taker_buy_quote_asset_volume: raw_kline[10].as_str().unwrap_or("0").to_string(),
-                    ignore: raw_kline[11].as_str().unwrap_or("0").to_string(),
-                };
-                klines.push(kline);
-            }
+        let raw_klines: Vec<Vec<serde_json::Value>> = decode_value(endpoint, response)?;
+        
+        // A malformed kline fails the call rather than leaving a hole in the series
+        raw_klines
+            .iter()
+            .map(|raw| crate::binance::types::BinanceKline::from_json_array(raw)
+                .ok_or_else(|| ExchangeError::InvalidResponse(format!("Invalid kline: {raw:?}"))))
+            .collect()
+    }
+
+    /// Every kline of an interval opening between two times, in order
+    pub async fn klines_between(
+        &self,
+        symbol: &str,
+        interval: &str,
+        start_time: u64,
+        end_time: u64,
+    ) -> Result<Vec<crate::binance::types::BinanceKline>> {
+        paginate(
+            |w| self.get_klines_raw(symbol, interval, Some(w.start), Some(w.end), Some(w.limit)),
+            start_time,
+            end_time,
+            HISTORY_PAGE_LIMIT,
+        ).await
+    }
+
+    /// Every kline of an interval opening in `range` (milliseconds, end exclusive)
+    ///
+    /// For research downloads spanning months: 1000-bar pages are fetched in
+    /// time order and bars repeated across pages are dropped by open time.
+    /// Before each page the download waits out a 418/429 hold, or the reset
+    /// of any request-weight window over `KLINE_DOWNLOAD_MAX_WEIGHT_PERCENT`
+    /// used, leaving headroom for trading; a page rate limited anyway is
+    /// retried after that wait instead of failing the whole download.
+    ///
+    /// ```rust,ignore
+    /// let klines = client.download_klines("BTCUSDT", "1m", start_ms..end_ms).await?;
+    /// write_klines_csv(std::fs::File::create("btcusdt-1m.csv")?, &klines)?;
+    /// ```
+    pub async fn download_klines(&self, symbol: &str, interval: &str, range: std::ops::Range<u64>) -> Result<Vec<crate::types::Kline>> {
+        if range.is_empty() {
+            return Ok(Vec::new());
         }
-        
+        let timer = PerfTimer::start("binance_download_klines".to_string());
+        let fetch = |w: PageWindow| async move {
+            let mut attempt = 0;
+            loop {
+                if let Some(pause) = self.history_pause(KLINE_DOWNLOAD_MAX_WEIGHT_PERCENT) {
+                    debug!("📜 Pausing kline download for {}ms to stay under rate limits", pause.as_millis());
+                    monoio::time::sleep(pause).await;
+                }
+                match self.get_klines_raw(symbol, interval, Some(w.start), Some(w.end), Some(w.limit)).await {
+                    Err(e) if attempt < KLINE_DOWNLOAD_RETRIES && is_rate_limited(&e) => {
+                        attempt += 1;
+                        warn!("📜 Kline page from {} rate limited, retrying ({}/{})", w.start, attempt, KLINE_DOWNLOAD_RETRIES);
+                        // Local rejections leave no hold behind, so wait for the next window at least
+                        monoio::time::sleep(self.history_pause(0).unwrap_or(std::time::Duration::from_secs(1))).await;
+                    }
+                    result => return result,
+                }
+            }
+        };
+        let raw = Paginator::new(HISTORY_PAGE_LIMIT).run(fetch, range.start, range.end - 1).await?;
+
+        let now = nanos() / 1_000_000;
+        let klines = raw.iter().map(|k| k.to_kline(symbol, interval, now)).collect::<Result<Vec<_>>>()?;
+        timer.log_elapsed();
+        info!("📜 Downloaded {} {} {} klines", klines.len(), symbol, interval);
         Ok(klines)
     }
 
+    /// How long history downloads should wait before the next request
+    ///
+    /// Until a 418/429 hold lifts, or until the busiest request-weight window
+    /// over `max_percent` used resets; `None` to go ahead.
+    fn history_pause(&self, max_percent: u32) -> Option<std::time::Duration> {
+        let now = nanos() / 1_000_000;
+        let status = self.rate_limit_status();
+        let blocked = status.blocked_until_ms.map(|until| until.saturating_sub(now));
+        let saturated = status.windows.iter()
+            .filter(|w| w.kind == RateLimitKind::RequestWeight && w.usage_percent() >= max_percent && w.used > 0)
+            .map(|w| w.remaining_ms(now))
+            .max();
+        blocked.max(saturated).filter(|ms| *ms > 0).map(std::time::Duration::from_millis)
+    }
+
     /// Create a listen key for user data stream
     pub async fn create_listen_key(&self) -> Result<String> {
         let timer = PerfTimer::start("binance_create_listen_key".to_string());
@@ -630,8 +1886,7 @@ impl BinanceRestClient {
         let url = format!("{}/api/v3/userDataStream", self.config.base_url);
         let response_text = self.make_http_request_with_headers(&url, "POST", None, headers).await?;
         
-        let response: serde_json::Value = serde_json::from_str(&response_text)
-            .map_err(|e| ExchangeError::SerializationError(e.to_string()))?;
+        let response: serde_json::Value = decode("/api/v3/userDataStream", &response_text)?;
         
         let listen_key = response["listenKey"]
             .as_str()
@@ -680,9 +1935,24 @@ impl BinanceRestClient {
     async fn get_request(
         &self,
         endpoint: &str,
+        class: EndpointClass,
         params: Option<Vec<(&str, &str)>>,
     ) -> Result<Value> {
-        let timer = PerfTimer::start(format!("binance_get_{endpoint}"));
+        let response = self.get_request_text(endpoint, class, params).await?;
+        
+        debug!("Response: {}", response);
+        
+        decode(endpoint, &response)
+    }
+    
+    /// Make a GET request and return the raw response body
+    async fn get_request_text(
+        &self,
+        endpoint: &str,
+        class: EndpointClass,
+        params: Option<Vec<(&str, &str)>>,
+    ) -> Result<String> {
+        let timer = PerfTimer::start(format!("binance_{class}_get_{endpoint}"));
         
         // Build URL
         let mut url = self.base_url.clone();
@@ -697,72 +1967,218 @@ impl BinanceRestClient {
         
         debug!("📡 GET {}", url);
         
-        // For now, use a simplified HTTP client
-        // In production, you'd want a proper monoio-based HTTP client
-        let response = self.make_http_request(url.as_str(), "GET", None).await?;
+        let response = self.with_retries("GET", endpoint, class, Resend::Freely, || self.make_http_request(url.as_str(), "GET", None)).await?;
         
-        timer.log_elapsed();
+        self.check_slo(&timer, class, endpoint);
         
-        debug!("Response: {}", response);
+        Ok(response)
+    }
+    
+    /// Make a GET request that needs the API key header but no signature
+    async fn api_key_request(
+        &self,
+        endpoint: &str,
+        class: EndpointClass,
+        params: Vec<(&str, &str)>,
+    ) -> Result<String> {
+        if self.config.api_key.is_empty() {
+            return Err(ExchangeError::MissingCredentials(format!("API key required for {endpoint}")));
+        }
         
-        serde_json::from_str(&response)
-            .map_err(|e| ExchangeError::SerializationError(format!("{e}: {response}")))
+        let timer = PerfTimer::start(format!("binance_{class}_apikey_{endpoint}"));
+        
+        let mut url = self.base_url.clone();
+        url.set_path(endpoint);
+        url.query_pairs_mut().extend_pairs(params);
+        
+        debug!("📡 GET {} (api key)", url);
+        
+        let mut headers = HashMap::new();
+        headers.insert("X-MBX-APIKEY", self.config.api_key.as_str());
+        
+        let response = self.with_retries("GET", endpoint, class, Resend::Freely, || {
+            self.make_http_request_with_headers(url.as_str(), "GET", None, headers.clone())
+        }).await?;
+        
+        self.check_slo(&timer, class, endpoint);
+        
+        Ok(response)
     }
     
     /// Make a signed request (for authenticated endpoints)
     async fn signed_request(
         &self,
         endpoint: &str,
+        class: EndpointClass,
         method: &str,
         params: Option<HashMap<&str, &str>>,
     ) -> Result<Value> {
-        let timer = PerfTimer::start(format!("binance_signed_{endpoint}"));
+        let response = self.signed_request_text(endpoint, class, method, params).await?;
         
-        // Create auth helper
-        let auth = BinanceAuth::new(&self.config.api_key, &self.config.api_secret);
+        decode(endpoint, &response)
+    }
+    
+    /// Make a signed request and return the raw response body
+    async fn signed_request_text(
+        &self,
+        endpoint: &str,
+        class: EndpointClass,
+        method: &str,
+        params: Option<HashMap<&str, &str>>,
+    ) -> Result<String> {
+        self.signed_request_to(&self.base_url, endpoint, class, method, params).await
+    }
+    
+    /// Make a signed request to the portfolio margin API (`papi_url`)
+    async fn signed_papi_request<T: DeserializeOwned>(&self, endpoint: &str, params: Option<HashMap<&str, &str>>) -> Result<T> {
+        let base_url = Url::parse(&self.config.papi_url)
+            .map_err(|e| ExchangeError::ConfigurationError(format!("Invalid portfolio margin URL: {e}")))?;
+        let response = self.signed_request_to(&base_url, endpoint, EndpointClass::Account, "GET", params).await?;
         
-        // Build URL with signature
-        let mut url = self.base_url.clone();
-        url.set_path(endpoint);
+        decode(endpoint, &response)
+    }
+    
+    /// Make a signed request against `base_url`
+    async fn signed_request_to(
+        &self,
+        base_url: &Url,
+        endpoint: &str,
+        class: EndpointClass,
+        method: &str,
+        params: Option<HashMap<&str, &str>>,
+    ) -> Result<String> {
+        let timer = PerfTimer::start(format!("binance_{class}_signed_{endpoint}"));
         
-        // Prepare query parameters
-        let mut query_params = HashMap::new();
-        if let Some(p) = params {
-            query_params.extend(p);
-        }
+        let params = params.unwrap_or_default();
+        let resend = match (params.get("symbol"), params.get("newClientOrderId")) {
+            _ if RetryPolicy::is_idempotent(method) => Resend::Freely,
+            (Some(symbol), Some(client_order_id)) => Resend::IfOrderMissing { base_url, symbol, client_order_id },
+            _ => Resend::Never,
+        };
+        
+        // Signed afresh on each attempt so a retry carries a current timestamp
+        let response = self.with_retries(method, endpoint, class, resend, || {
+            let url = self.signed_url(base_url, endpoint, &params);
+            debug!("📡 {} {} (signed)", method, url);
+            
+            let mut headers = HashMap::new();
+            headers.insert("X-MBX-APIKEY", self.config.api_key.as_str());
+            async move { self.make_http_request_with_headers(url.as_str(), method, None, headers).await }
+        }).await?;
+        
+        self.check_slo(&timer, class, endpoint);
+        
+        Ok(response)
+    }
+    
+    /// `base_url` + `endpoint` with `params`, a timestamp and the signature
+    fn signed_url(&self, base_url: &Url, endpoint: &str, params: &HashMap<&str, &str>) -> Url {
+        let auth = BinanceAuth::new(&self.config.api_key, &self.config.api_secret);
         
-        // Add timestamp and recvWindow
-        let timestamp = nanos() / 1_000_000; // Convert to milliseconds
-        let timestamp_str = timestamp.to_string();
-        let recv_window = "5000".to_string();
+        let mut query_params = params.clone();
+        let timestamp_str = (nanos() / 1_000_000).to_string();
         query_params.insert("timestamp", &timestamp_str);
-        query_params.insert("recvWindow", &recv_window);
+        query_params.insert("recvWindow", "5000");
         
-        // Create signature
         let query_string = auth.build_query_string(&query_params);
         let signature = auth.sign(&query_string);
         
-        
-        // Add signature to URL
+        let mut url = base_url.clone();
+        url.set_path(endpoint);
         url.set_query(Some(&format!("{query_string}&signature={signature}")));
-        
-        debug!("📡 {} {} (signed)", method, url);
-        
-        // Make request with API key header
-        let mut headers = HashMap::new();
-        headers.insert("X-MBX-APIKEY", self.config.api_key.as_str());
-        
-        let response = self.make_http_request_with_headers(
-            url.as_str(),
-            method,
-            None,
-            headers
-        ).await?;
-        
+        url
+    }
+    
+    /// Run `send`, repeating it under the retry policy while it fails transiently
+    /// 
+    /// Each attempt is cut off at the class timeout, which fails it with
+    /// `ExchangeError::Timeout`. How a failed request may be repeated is up to `resend`.
+    async fn with_retries<F, Fut>(&self, method: &str, endpoint: &str, class: EndpointClass, resend: Resend<'_>, mut send: F) -> Result<String>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<String>>,
+    {
+        let deadline = self.config.endpoint_timeout(class);
+        let what = format!("{method} {endpoint}");
+        let mut attempt = 1;
+        loop {
+            match with_deadline(deadline, &what, send()).await {
+                Err(e) if self.retry.should_retry(attempt, &e) => {
+                    match resend {
+                        Resend::Freely => {}
+                        Resend::Never => {
+                            debug!("🔁 Not retrying {} {} without a client order id: {}", method, endpoint, e);
+                            return Err(e);
+                        }
+                        Resend::IfOrderMissing { base_url, symbol, client_order_id } => {
+                            match with_deadline(deadline, "order lookup", self.find_placed_order(base_url, symbol, client_order_id)).await {
+                                Ok(Some(placed)) => {
+                                    info!("🔁 {} {} failed ({}) but {} was placed, not resending", method, endpoint, e, client_order_id);
+                                    return Ok(placed);
+                                }
+                                Ok(None) => {}
+                                Err(lookup) => {
+                                    warn!("🔁 Not retrying {} {}: lookup of {} failed ({})", method, endpoint, client_order_id, lookup);
+                                    return Err(e);
+                                }
+                            }
+                        }
+                    }
+                    let delay_ms = self.retry.backoff_delay_ms(attempt);
+                    warn!("🔁 {} {} failed ({}), retry {}/{} in {}ms",
+                          method, endpoint, e, attempt, self.retry.max_attempts - 1, delay_ms);
+                    monoio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+    
+    /// The order a failed POST may have placed, as a new-order acknowledgement
+    /// 
+    /// Binance only rejects a repeated `newClientOrderId` while the first
+    /// order is still open, so a resent MARKET or IOC order that already
+    /// filled would fill again. One `origClientOrderId` lookup, not retried,
+    /// decides: `None` means the order doesn't exist and may be sent again.
+    async fn find_placed_order(&self, base_url: &Url, symbol: &str, client_order_id: &str) -> Result<Option<String>> {
+        let params = HashMap::from([("symbol", symbol), ("origClientOrderId", client_order_id)]);
+        let url = self.signed_url(base_url, "/api/v3/order", &params);
+        let headers = HashMap::from([("X-MBX-APIKEY", self.config.api_key.as_str())]);
+        let body = match self.make_http_request_with_headers(url.as_str(), "GET", None, headers).await {
+            Ok(body) => body,
+            Err(e) if matches!(e.root(), ExchangeError::BinanceApi(_, BinanceApiError::UnknownOrder)) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let mut order: Value = decode("/api/v3/order", &body)?;
+        // The acknowledgement's transactTime is when the order last changed
+        if let Some(update_time) = order.get("updateTime").cloned() {
+            order["transactTime"] = update_time;
+        }
+        Ok(Some(order.to_string()))
+    }
+    
+    /// Log request latency and flag SLO breaches for the endpoint class
+    fn check_slo(&self, timer: &PerfTimer, class: EndpointClass, endpoint: &str) {
         timer.log_elapsed();
         
-        serde_json::from_str(&response)
-            .map_err(|e| ExchangeError::SerializationError(e.to_string()))
+        let elapsed_us = timer.elapsed_micros();
+        let settings = self.config.endpoint_settings(class);
+        let (severity, target_us) = if elapsed_us > settings.timeout_ms * 1000 {
+            warn!("⏰ {} {} took {}μs, exceeding {}ms timeout budget", class, endpoint, elapsed_us, settings.timeout_ms);
+            (Severity::Critical, settings.timeout_ms * 1000)
+        } else if elapsed_us > settings.slo_us {
+            warn!("🐢 {} {} took {}μs, exceeding {}μs SLO", class, endpoint, elapsed_us, settings.slo_us);
+            (Severity::Warning, settings.slo_us)
+        } else {
+            return;
+        };
+        self.publish_incident(
+            Incident::new(severity, "binance_rest", IncidentKind::SloBreach, format!("{class} request over budget"))
+                .with_context("endpoint", endpoint)
+                .with_context("elapsed_us", elapsed_us)
+                .with_context("target_us", target_us),
+        );
     }
     
     /// Make HTTP request using monoio-native HTTPS client
@@ -783,9 +2199,26 @@ impl BinanceRestClient {
         body: Option<&str>,
         headers: HashMap<&str, &str>,
     ) -> Result<String> {
+        let path = Url::parse(url).map(|u| u.path().to_string()).unwrap_or_default();
+        self.throttle(method, &path).await?;
+        
         let response = self.https_client.request_with_headers(method, url, body, &headers).await?;
+        self.rate_limiter.borrow_mut().update_from_response(response.status, &response.headers, nanos() / 1_000_000);
+        
+        if matches!(response.status, 418 | 429) {
+            let severity = if response.status == 418 { Severity::Critical } else { Severity::Warning };
+            self.publish_incident(
+                Incident::new(severity, "binance_rest", IncidentKind::Ban, format!("HTTP {} from Binance", response.status))
+                    .with_context("url", crate::cassette::scrub_url(url))
+                    .with_context("body", &response.body),
+            );
+        }
         
         if response.status != 200 {
+            if let Some(error) = BinanceApiError::from_response(response.status, &response.body, &response.headers) {
+                debug!("❗ Binance rejected {} {}: {}", method, path, error);
+                return Err(ExchangeError::BinanceApi(response.status, error));
+            }
             return Err(ExchangeError::HttpError(
                 response.status,
                 format!("HTTP {}: {}", response.status, response.body),
@@ -795,6 +2228,95 @@ impl BinanceRestClient {
         Ok(response.body)
     }
     
+    /// Hold or reject a request that would exceed a Binance rate limit
+    async fn throttle(&self, method: &str, path: &str) -> Result<()> {
+        loop {
+            let decision = self.rate_limiter.borrow_mut().acquire(method, path, nanos() / 1_000_000);
+            match decision {
+                RateLimitDecision::Proceed => return Ok(()),
+                RateLimitDecision::Wait(delay) => monoio::time::sleep(delay).await,
+                RateLimitDecision::Reject(reason) => {
+                    debug!("🚦 {} {} rejected locally: {}", method, path, reason);
+                    return Err(ExchangeError::RateLimitExceeded);
+                }
+            }
+        }
+    }
+    
+}
+
+/// Largest page `/api/v3/myTrades` returns
+const MY_TRADES_PAGE_LIMIT: u32 = 1000;
+
+/// Largest page the time-ranged history endpoints return
+const HISTORY_PAGE_LIMIT: u32 = 1000;
+
+/// Request-weight usage above which `download_klines` waits for the window to reset
+const KLINE_DOWNLOAD_MAX_WEIGHT_PERCENT: u32 = 80;
+
+/// Times a rate-limited kline page is retried
+const KLINE_DOWNLOAD_RETRIES: u32 = 3;
+
+/// A local rate-limit rejection or a 429 from Binance
+fn is_rate_limited(error: &ExchangeError) -> bool {
+    matches!(error.root(), ExchangeError::RateLimitExceeded | ExchangeError::BinanceApi(429, _))
+}
+
+const HOUR_MS: u64 = 60 * 60 * 1000;
+const DAY_MS: u64 = 24 * HOUR_MS;
+
+impl Paged for AggTradeResponse {
+    fn timestamp_ms(&self) -> u64 {
+        self.time
+    }
+
+    fn page_key(&self) -> u64 {
+        self.agg_trade_id
+    }
+}
+
+impl Paged for QueryOrderResponse {
+    fn timestamp_ms(&self) -> u64 {
+        self.time
+    }
+
+    fn page_key(&self) -> u64 {
+        self.order_id
+    }
+}
+
+impl Paged for MyTradeResponse {
+    fn timestamp_ms(&self) -> u64 {
+        self.time
+    }
+
+    fn page_key(&self) -> u64 {
+        self.id
+    }
+}
+
+impl Paged for crate::binance::types::BinanceKline {
+    fn timestamp_ms(&self) -> u64 {
+        self.open_time
+    }
+
+    fn page_key(&self) -> u64 {
+        self.open_time
+    }
+}
+
+/// `fromId` of the page after `page`, or `None` if `page` was the last
+fn next_trade_page(page: &[MyTradeResponse], limit: u32) -> Option<u64> {
+    if page.len() < limit as usize {
+        return None;
+    }
+    page.iter().map(|t| t.id).max().map(|id| id + 1)
+}
+
+/// Encode symbols as the JSON array Binance expects for `symbols=`
+fn symbols_json_array(symbols: &[&str]) -> String {
+    let quoted = symbols.iter().map(|s| format!("\"{s}\"")).collect::<Vec<_>>();
+    format!("[{}]", quoted.join(","))
 }
 
 /// 24-hour ticker statistics
@@ -802,45 +2324,83 @@ impl BinanceRestClient {
 pub struct Ticker24hr {
     pub symbol: String,
     #[serde(rename = "priceChange")]
-    pub price_change: String,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub price_change: Fixed,
     #[serde(rename = "priceChangePercent")]
-    pub price_change_percent: String,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub price_change_percent: Fixed,
     #[serde(rename = "weightedAvgPrice")]
-    pub weighted_avg_price: String,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub weighted_avg_price: Fixed,
     #[serde(rename = "prevClosePrice")]
-    pub prev_close_price: String,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub prev_close_price: Fixed,
     #[serde(rename = "lastPrice")]
-    pub last_price: String,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub last_price: Fixed,
     #[serde(rename = "lastQty")]
-    pub last_qty: String,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub last_qty: Fixed,
     #[serde(rename = "bidPrice")]
-    pub bid_price: String,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub bid_price: Fixed,
     #[serde(rename = "bidQty")]
-    pub bid_qty: String,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub bid_qty: Fixed,
     #[serde(rename = "askPrice")]
-    pub ask_price: String,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub ask_price: Fixed,
     #[serde(rename = "askQty")]
-    pub ask_qty: String,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub ask_qty: Fixed,
     #[serde(rename = "openPrice")]
-    pub open_price: String,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub open_price: Fixed,
     #[serde(rename = "highPrice")]
-    pub high_price: String,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub high_price: Fixed,
     #[serde(rename = "lowPrice")]
-    pub low_price: String,
-    pub volume: String,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub low_price: Fixed,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub volume: Fixed,
     #[serde(rename = "quoteVolume")]
-    pub quote_volume: String,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub quote_volume: Fixed,
     #[serde(rename = "openTime")]
     pub open_time: u64,
     #[serde(rename = "closeTime")]
     pub close_time: u64,
+    /// -1 for symbols with no trades in the window
     #[serde(rename = "firstId")]
-    pub first_id: u64,
+    pub first_id: i64,
+    /// -1 for symbols with no trades in the window
     #[serde(rename = "lastId")]
-    pub last_id: u64,
+    pub last_id: i64,
     pub count: u64,
 }
 
+impl Ticker24hr {
+    /// Convert into scanner statistics
+    pub fn to_scan_ticker(&self) -> Result<crate::scanner::ScanTicker> {
+        let optional_price = |price: Fixed| (price > Fixed::ZERO).then_some(price);
+        
+        Ok(crate::scanner::ScanTicker {
+            symbol: self.symbol.clone(),
+            last_price: self.last_price,
+            open_price: self.open_price,
+            high_price: self.high_price,
+            low_price: self.low_price,
+            bid_price: optional_price(self.bid_price),
+            ask_price: optional_price(self.ask_price),
+            volume: self.volume.to_f64_lossy(),
+            quote_volume: self.quote_volume.to_f64_lossy(),
+            trade_count: self.count,
+            timestamp: self.close_time,
+        })
+    }
+}
+
 /// Order book response from Binance
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct OrderBookResponse {
@@ -865,6 +2425,44 @@ pub struct TradeResponse {
     pub is_best_match: bool,
 }
 
+/// Aggregated trade from `/api/v3/aggTrades`
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct AggTradeResponse {
+    #[serde(rename = "a")]
+    pub agg_trade_id: u64,
+    #[serde(rename = "p")]
+    pub price: String,
+    #[serde(rename = "q")]
+    pub qty: String,
+    #[serde(rename = "f")]
+    pub first_trade_id: u64,
+    #[serde(rename = "l")]
+    pub last_trade_id: u64,
+    #[serde(rename = "T")]
+    pub time: u64,
+    #[serde(rename = "m")]
+    pub is_buyer_maker: bool,
+    #[serde(rename = "M")]
+    pub is_best_match: bool,
+}
+
+impl AggTradeResponse {
+    /// As the event the `@aggTrade` stream delivers
+    pub fn to_update(&self, symbol: &str) -> Result<crate::types::AggTradeUpdate> {
+        Ok(crate::types::AggTradeUpdate {
+            exchange: crate::types::ExchangeId::Binance,
+            symbol: symbol.to_string(),
+            agg_trade_id: self.agg_trade_id,
+            price: Fixed::from_str_exact(&self.price)?,
+            quantity: Fixed::from_str_exact(&self.qty)?,
+            first_trade_id: self.first_trade_id,
+            last_trade_id: self.last_trade_id,
+            side: if self.is_buyer_maker { crate::types::TradeSide::Sell } else { crate::types::TradeSide::Buy },
+            timestamp: self.time,
+        })
+    }
+}
+
 /// Account information response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AccountInfo {
@@ -890,19 +2488,141 @@ pub struct AccountInfo {
     pub permissions: Vec<String>,
 }
 
+impl AccountInfo {
+    /// Parse an account response and drop zero balances
+    /// 
+    /// Every balance is parsed first, so a malformed amount is an error
+    /// rather than a balance that silently disappears.
+    pub fn from_json_non_zero(body: &str) -> Result<Self> {
+        let mut account: AccountInfo = decode("/api/v3/account", body)?;
+        account.retain_non_zero_balances();
+        Ok(account)
+    }
+    
+    /// Drop balances with nothing free or locked
+    pub fn retain_non_zero_balances(&mut self) {
+        self.balances.retain(|b| !b.free.is_zero() || !b.locked.is_zero());
+    }
+}
+
 /// Balance information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Balance {
     pub asset: String,
-    pub free: String,
-    pub locked: String,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub free: Fixed,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub locked: Fixed,
 }
 
 /// Price ticker
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PriceTicker {
     pub symbol: String,
-    pub price: String,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub price: Fixed,
+}
+
+/// Prevented match (self-trade prevention) response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreventedMatchResponse {
+    pub symbol: String,
+    #[serde(rename = "preventedMatchId")]
+    pub prevented_match_id: u64,
+    #[serde(rename = "takerOrderId")]
+    pub taker_order_id: u64,
+    #[serde(rename = "makerOrderId")]
+    pub maker_order_id: u64,
+    #[serde(rename = "tradeGroupId")]
+    pub trade_group_id: u64,
+    #[serde(rename = "selfTradePreventionMode")]
+    pub self_trade_prevention_mode: String,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub price: Fixed,
+    #[serde(rename = "makerPreventedQuantity")]
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub maker_prevented_quantity: Fixed,
+    #[serde(rename = "transactTime")]
+    pub transact_time: u64,
+}
+
+impl PreventedMatchResponse {
+    /// Convert into an exchange-agnostic journal record
+    pub fn to_record(&self) -> Result<crate::journal::PreventedMatchRecord> {
+        Ok(crate::journal::PreventedMatchRecord {
+            prevented_match_id: self.prevented_match_id,
+            symbol: self.symbol.clone(),
+            taker_order_id: self.taker_order_id,
+            maker_order_id: self.maker_order_id,
+            trade_group_id: self.trade_group_id,
+            stp_mode: self.self_trade_prevention_mode.clone(),
+            price: self.price,
+            maker_prevented_quantity: self.maker_prevented_quantity,
+            transact_time: self.transact_time,
+        })
+    }
+}
+
+impl MyTradeResponse {
+    /// Convert into an exchange-agnostic journal record
+    pub fn to_record(&self) -> Result<crate::journal::TradeRecord> {
+        Ok(crate::journal::TradeRecord {
+            trade_id: self.id,
+            order_id: self.order_id,
+            symbol: self.symbol.clone(),
+            side: if self.is_buyer { crate::types::OrderSide::Buy } else { crate::types::OrderSide::Sell },
+            price: self.price,
+            quantity: self.qty,
+            quote_quantity: self.quote_qty,
+            commission: self.commission,
+            commission_asset: self.commission_asset.clone(),
+            is_maker: self.is_maker,
+            time: self.time,
+        })
+    }
+}
+
+/// BNB burn settings for fee and interest payment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BnbBurnStatus {
+    #[serde(rename = "spotBNBBurn")]
+    pub spot_bnb_burn: bool,
+    #[serde(rename = "interestBNBBurn")]
+    pub interest_bnb_burn: bool,
+}
+
+impl BnbBurnStatus {
+    /// Fee preference implied by the spot setting
+    pub fn fee_preference(&self) -> crate::fees::FeePreference {
+        crate::fees::FeePreference::binance_spot(self.spot_bnb_burn)
+    }
+}
+
+/// Order rate limit window usage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderRateLimit {
+    #[serde(rename = "rateLimitType")]
+    pub rate_limit_type: String,
+    pub interval: String,
+    #[serde(rename = "intervalNum")]
+    pub interval_num: u32,
+    pub limit: u32,
+    #[serde(default)]
+    pub count: Option<u32>,
+}
+
+impl OrderRateLimit {
+    /// Window length in milliseconds, or `None` for an unknown interval unit
+    pub fn interval_millis(&self) -> Option<u64> {
+        let unit = match self.interval.as_str() {
+            "SECOND" => 1_000,
+            "MINUTE" => 60_000,
+            "HOUR" => 3_600_000,
+            "DAY" => 86_400_000,
+            _ => return None,
+        };
+        Some(unit * self.interval_num as u64)
+    }
 }
 
 /// New order response
@@ -917,13 +2637,17 @@ pub struct NewOrderResponse {
     pub client_order_id: String,
     #[serde(rename = "transactTime")]
     pub transact_time: u64,
-    pub price: String,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub price: Fixed,
     #[serde(rename = "origQty")]
-    pub orig_qty: String,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub orig_qty: Fixed,
     #[serde(rename = "executedQty")]
-    pub executed_qty: String,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub executed_qty: Fixed,
     #[serde(rename = "cummulativeQuoteQty")]
-    pub cumulative_quote_qty: String,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub cumulative_quote_qty: Fixed,
     pub status: String,
     #[serde(rename = "timeInForce")]
     pub time_in_force: String,
@@ -944,13 +2668,17 @@ pub struct CancelOrderResponse {
     pub order_list_id: i32,
     #[serde(rename = "clientOrderId")]
     pub client_order_id: String,
-    pub price: String,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub price: Fixed,
     #[serde(rename = "origQty")]
-    pub orig_qty: String,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub orig_qty: Fixed,
     #[serde(rename = "executedQty")]
-    pub executed_qty: String,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub executed_qty: Fixed,
     #[serde(rename = "cummulativeQuoteQty")]
-    pub cumulative_quote_qty: String,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub cumulative_quote_qty: Fixed,
     pub status: String,
     #[serde(rename = "timeInForce")]
     pub time_in_force: String,
@@ -969,13 +2697,17 @@ pub struct QueryOrderResponse {
     pub order_list_id: i32,
     #[serde(rename = "clientOrderId")]
     pub client_order_id: String,
-    pub price: String,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub price: Fixed,
     #[serde(rename = "origQty")]
-    pub orig_qty: String,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub orig_qty: Fixed,
     #[serde(rename = "executedQty")]
-    pub executed_qty: String,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub executed_qty: Fixed,
     #[serde(rename = "cummulativeQuoteQty")]
-    pub cumulative_quote_qty: String,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub cumulative_quote_qty: Fixed,
     pub status: String,
     #[serde(rename = "timeInForce")]
     pub time_in_force: String,
@@ -983,16 +2715,19 @@ pub struct QueryOrderResponse {
     pub order_type: String,
     pub side: String,
     #[serde(rename = "stopPrice")]
-    pub stop_price: String,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub stop_price: Fixed,
     #[serde(rename = "icebergQty")]
-    pub iceberg_qty: String,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub iceberg_qty: Fixed,
     pub time: u64,
     #[serde(rename = "updateTime")]
     pub update_time: u64,
     #[serde(rename = "isWorking")]
     pub is_working: bool,
     #[serde(rename = "origQuoteOrderQty")]
-    pub orig_quote_order_qty: String,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub orig_quote_order_qty: Fixed,
 }
 
 /// My trades response
@@ -1004,11 +2739,15 @@ pub struct MyTradeResponse {
     pub order_id: u64,
     #[serde(rename = "orderListId")]
     pub order_list_id: i32,
-    pub price: String,
-    pub qty: String,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub price: Fixed,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub qty: Fixed,
     #[serde(rename = "quoteQty")]
-    pub quote_qty: String,
-    pub commission: String,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub quote_qty: Fixed,
+    #[serde(with = "sriquant_core::fixed::serde_str")]
+    pub commission: Fixed,
     #[serde(rename = "commissionAsset")]
     pub commission_asset: String,
     pub time: u64,
@@ -1030,4 +2769,326 @@ mod tests {
         let client = BinanceRestClient::new(config).await;
         assert!(client.is_ok());
     }
+
+    #[test]
+    fn test_environment_sets_every_url() {
+        let testnet = BinanceConfig::environment("testnet".parse().unwrap());
+        assert!(testnet.testnet);
+        assert_eq!(testnet.base_url, "https://testnet.binance.vision");
+        assert_eq!(testnet.ws_url, "wss://stream.testnet.binance.vision");
+        assert_eq!(testnet.ws_api_url, "wss://ws-api.testnet.binance.vision/ws-api/v3");
+        assert_eq!(testnet.fapi_url, "https://testnet.binancefuture.com");
+        assert_eq!(testnet.fapi_ws_url, "wss://fstream.binancefuture.com");
+        assert_eq!(testnet.fapi_ws_api_url, "wss://testnet.binancefuture.com/ws-fapi/v1");
+
+        // Switching back restores the production hosts as a set
+        let mainnet = testnet.with_environment(BinanceEnvironment::Mainnet);
+        let default = BinanceConfig::default();
+        assert!(!mainnet.testnet);
+        assert_eq!(
+            (mainnet.base_url, mainnet.ws_url, mainnet.ws_api_url, mainnet.fapi_url, mainnet.fapi_ws_url, mainnet.fapi_ws_api_url),
+            (default.base_url, default.ws_url, default.ws_api_url, default.fapi_url, default.fapi_ws_url, default.fapi_ws_api_url)
+        );
+        assert_eq!("production".parse::<BinanceEnvironment>().unwrap(), BinanceEnvironment::Mainnet);
+        assert!("staging".parse::<BinanceEnvironment>().is_err());
+    }
+
+    #[monoio::test]
+    async fn test_historical_trades_requires_api_key() {
+        let client = BinanceRestClient::new(BinanceConfig::testnet()).await.unwrap();
+        let result = client.historical_trades("BTCUSDT", Some(10), None).await;
+        assert!(matches!(result, Err(ExchangeError::MissingCredentials(_))));
+    }
+    
+    #[test]
+    fn test_account_info_non_zero_parse() {
+        let body = r#"{
+            "makerCommission": 10, "takerCommission": 10, "buyerCommission": 0, "sellerCommission": 0,
+            "canTrade": true, "canWithdraw": true, "canDeposit": true, "updateTime": 123,
+            "accountType": "SPOT",
+            "balances": [
+                {"asset": "BTC", "free": "0.50000000", "locked": "0.00000000"},
+                {"asset": "DUST", "free": "0.00000000", "locked": "0.00000000"},
+                {"asset": "USDT", "free": "0.00000000", "locked": "25.00000000"}
+            ],
+            "permissions": ["SPOT"]
+        }"#;
+        
+        let full: AccountInfo = serde_json::from_str(body).unwrap();
+        assert_eq!(full.balances.len(), 3);
+        
+        let compact = AccountInfo::from_json_non_zero(body).unwrap();
+        let assets: Vec<&str> = compact.balances.iter().map(|b| b.asset.as_str()).collect();
+        assert_eq!(assets, vec!["BTC", "USDT"]);
+        assert_eq!(compact.account_type, "SPOT");
+        
+        // An amount that doesn't parse fails the response instead of reading as zero
+        let malformed = body.replace(r#""free": "0.00000000", "locked": "0.00000000""#, r#""free": "", "locked": "0.00000000""#);
+        assert!(AccountInfo::from_json_non_zero(&malformed).is_err());
+    }
+    
+    #[test]
+    fn test_prevented_match_record() {
+        let body = r#"[{
+            "symbol": "BTCUSDT", "preventedMatchId": 1, "takerOrderId": 5, "makerOrderId": 3,
+            "tradeGroupId": 1, "selfTradePreventionMode": "EXPIRE_MAKER", "price": "1.100000",
+            "makerPreventedQuantity": "1.300000", "transactTime": 1669101687094
+        }]"#;
+        
+        let matches: Vec<PreventedMatchResponse> = serde_json::from_str(body).unwrap();
+        let record = matches[0].to_record().unwrap();
+        
+        assert_eq!(record.maker_order_id, 3);
+        assert_eq!(record.stp_mode, "EXPIRE_MAKER");
+        assert_eq!(record.maker_prevented_quantity.to_string(), "1.300000");
+    }
+    
+    #[test]
+    fn test_my_trades_paging_and_record() {
+        let body = r#"[
+            {"symbol": "BNBBTC", "id": 28457, "orderId": 100234, "orderListId": -1, "price": "4.00000100",
+             "qty": "12.00000000", "quoteQty": "48.000012", "commission": "10.10000000",
+             "commissionAsset": "BNB", "time": 1499865549590, "isBuyer": true, "isMaker": false,
+             "isBestMatch": true},
+            {"symbol": "BNBBTC", "id": 28460, "orderId": 100240, "orderListId": -1, "price": "4.10000000",
+             "qty": "1.00000000", "quoteQty": "4.1", "commission": "0.001",
+             "commissionAsset": "BTC", "time": 1499865549600, "isBuyer": false, "isMaker": true,
+             "isBestMatch": true}
+        ]"#;
+        let trades: Vec<MyTradeResponse> = serde_json::from_str(body).unwrap();
+        
+        assert_eq!(next_trade_page(&trades, 2), Some(28461));
+        assert_eq!(next_trade_page(&trades, 1000), None);
+        assert_eq!(next_trade_page(&[], 1000), None);
+        
+        let record = trades[1].to_record().unwrap();
+        assert_eq!(record.side, crate::types::OrderSide::Sell);
+        assert!(record.is_maker);
+        assert_eq!(record.quote_quantity.to_string(), "4.1");
+        
+        let mut journal = crate::journal::Journal::new();
+        for trade in &trades {
+            journal.record("binance", crate::journal::JournalEvent::Trade(trade.to_record().unwrap()));
+        }
+        assert_eq!(journal.last_trade_id("BNBBTC"), Some(28460));
+        assert_eq!(journal.last_trade_id("ETHBTC"), None);
+    }
+    
+    #[test]
+    fn test_full_market_ticker_parse() {
+        assert_eq!(symbols_json_array(&["BTCUSDT", "ETHUSDT"]), r#"["BTCUSDT","ETHUSDT"]"#);
+        
+        // Inactive symbols report -1 trade ids in the full-market response
+        let body = r#"[{
+            "symbol": "OLDCOIN", "priceChange": "0", "priceChangePercent": "0", "weightedAvgPrice": "0",
+            "prevClosePrice": "0", "lastPrice": "0", "lastQty": "0", "bidPrice": "0", "bidQty": "0",
+            "askPrice": "0", "askQty": "0", "openPrice": "0", "highPrice": "0", "lowPrice": "0",
+            "volume": "0", "quoteVolume": "0", "openTime": 1, "closeTime": 2,
+            "firstId": -1, "lastId": -1, "count": 0
+        }]"#;
+        let tickers: Vec<Ticker24hr> = serde_json::from_str(body).unwrap();
+        assert_eq!(tickers[0].first_id, -1);
+        
+        let scan = tickers[0].to_scan_ticker().unwrap();
+        assert_eq!(scan.bid_price, None);
+        assert_eq!(scan.quote_volume, 0.0);
+        
+        let prices: Vec<PriceTicker> =
+            serde_json::from_str(r#"[{"symbol":"BTCUSDT","price":"50000.00"},{"symbol":"ETHUSDT","price":"3000.00"}]"#).unwrap();
+        assert_eq!(prices.len(), 2);
+    }
+    
+    #[test]
+    fn test_lenient_exchange_info_skips_bad_symbols() {
+        let body = r#"{"timezone":"UTC","serverTime":1700000000000,"rateLimits":[],"symbols":[
+            {"symbol":"BTCUSDT","status":"TRADING","baseAsset":"BTC","quoteAsset":"USDT","filters":[],"newField":1},
+            {"symbol":"ODDUSDT","status":"TRADING","baseAsset":"ODD","quoteAsset":{"changed":true},"filters":[]},
+            {"symbol":"ETHUSDT","status":"BREAK","baseAsset":"ETH","quoteAsset":"USDT","filters":[]}
+        ]}"#;
+        
+        let err = decode::<ExchangeInfo>("/api/v3/exchangeInfo", body).unwrap_err();
+        assert!(err.to_string().contains("/api/v3/exchangeInfo"));
+        
+        let info = ExchangeInfo::from_json_lenient(body).unwrap();
+        assert_eq!(info.server_time, 1_700_000_000_000);
+        assert_eq!(info.symbols.iter().map(|s| s.symbol.as_str()).collect::<Vec<_>>(), vec!["BTCUSDT", "ETHUSDT"]);
+        assert_eq!(info.parse_warnings.len(), 1);
+        assert!(info.parse_warnings[0].starts_with("ODDUSDT: "));
+    }
+    
+    #[test]
+    fn test_exchange_info_params() {
+        assert!(ExchangeInfoParams::default().query_params().is_empty());
+        
+        let params = ExchangeInfoParams { symbol_status: Some("TRADING"), permissions: &["SPOT"] };
+        assert_eq!(params.query_params(), vec![
+            ("symbolStatus", "TRADING".to_string()),
+            ("permissions", "SPOT".to_string()),
+        ]);
+        
+        let params = ExchangeInfoParams { symbol_status: None, permissions: &["MARGIN", "LEVERAGED"] };
+        assert_eq!(params.query_params(), vec![("permissions", r#"["MARGIN","LEVERAGED"]"#.to_string())]);
+    }
+    
+    #[test]
+    fn test_agg_trades_query_and_response() {
+        assert!(AggTradesQuery::default().query_params().is_empty());
+        assert_eq!(AggTradesQuery::from_id(26129).with_limit(1000).query_params(), vec![
+            ("fromId", "26129".to_string()),
+            ("limit", "1000".to_string()),
+        ]);
+        assert_eq!(AggTradesQuery::between(1, 2).query_params().len(), 2);
+
+        let body = r#"[{"a":26129,"p":"0.01633102","q":"4.70443515","f":27781,"l":27783,"T":1498793709153,"m":true,"M":true}]"#;
+        let trades: Vec<AggTradeResponse> = serde_json::from_str(body).unwrap();
+        let update = trades[0].to_update("LTCBTC").unwrap();
+        assert_eq!(update.trade_count(), 3);
+        assert!(matches!(update.side, crate::types::TradeSide::Sell));
+        assert_eq!(update.price, Fixed::from_str_exact("0.01633102").unwrap());
+    }
+    
+    #[monoio::test(timer_enabled = true)]
+    async fn test_sync_futures_income_into_journal() {
+        let body = r#"[
+            {"symbol":"BTCUSDT","incomeType":"FUNDING_FEE","income":"-0.375","asset":"USDT","info":"","time":1000,"tranId":7,"tradeId":""},
+            {"symbol":"BTCUSDT","incomeType":"REALIZED_PNL","income":"12.5","asset":"USDT","info":"","time":2000,"tranId":8,"tradeId":"31"},
+            {"symbol":"BTCUSDT","incomeType":"COMMISSION","income":"-0.25","asset":"USDT","info":"","time":2000,"tranId":8,"tradeId":"31"},
+            {"symbol":"ETHUSDT","incomeType":"FUNDING_FEE","income":"0.125","asset":"USDT","info":"","time":3000,"tranId":9,"tradeId":""}
+        ]"#;
+        let mock = crate::testkit::MockBinance::new().with_response("GET", "/fapi/v1/income", body);
+        let config = BinanceConfig::default().with_credentials("key".to_string(), "secret".to_string());
+        let client = BinanceRestClient::new(config).await.unwrap().with_mock(mock);
+        
+        let mut journal = crate::journal::Journal::new();
+        assert_eq!(client.sync_futures_income(0, &mut journal).await.unwrap(), 4);
+        // Resumes from the last entry; the overlap is dropped
+        assert_eq!(client.sync_futures_income(0, &mut journal).await.unwrap(), 0);
+        assert_eq!(journal.last_income_time("binance"), Some(3000));
+        
+        let totals = journal.income_totals(0, 3000);
+        let funding = &totals[&("USDT".to_string(), "FUNDING_FEE".to_string())];
+        assert_eq!(*funding, Fixed::from_str_exact("-0.375").unwrap());
+        assert_eq!(totals.len(), 3);
+    }
+    
+    #[monoio::test(timer_enabled = true)]
+    async fn test_download_klines_dedups_pages() {
+        // The mock answers every page with the same two bars
+        let body = r#"[
+            [60000,"100.0","101.0","99.0","100.5","2.0",119999,"201.0",7,"1.0","100.5","0"],
+            [120000,"100.5","102.0","100.0","101.5","1.0",179999,"101.5",3,"0.5","50.7","0"]
+        ]"#;
+        let mock = crate::testkit::MockBinance::new().with_response("GET", "/api/v3/klines", body);
+        let client = BinanceRestClient::new(BinanceConfig::default()).await.unwrap().with_mock(mock.clone());
+
+        let klines = client.download_klines("BTCUSDT", "1m", 60_000..180_000).await.unwrap();
+        assert_eq!(klines.iter().map(|k| k.open_time).collect::<Vec<_>>(), vec![60_000, 120_000]);
+        assert_eq!(klines[1].close, Fixed::from_str_exact("101.5").unwrap());
+        assert!(klines.iter().all(|k| k.symbol == "BTCUSDT" && k.is_closed));
+
+        let requests = mock.stats().requests;
+        assert!(client.download_klines("BTCUSDT", "1m", 60_000..60_000).await.unwrap().is_empty());
+        assert_eq!(mock.stats().requests, requests);
+    }
+    
+    #[monoio::test(timer_enabled = true)]
+    async fn test_retries_skip_orders_without_client_id() {
+        use crate::chaos::{Fault, FaultConfig, FaultInjector};
+        
+        let mock = crate::testkit::MockBinance::new();
+        let faults = FaultInjector::new(FaultConfig::new(1));
+        let config = BinanceConfig::testnet()
+            .with_credentials("key".to_string(), "secret".to_string())
+            .with_filter_precision(false);
+        let client = BinanceRestClient::new(config).await.unwrap()
+            .with_mock(mock.clone())
+            .with_faults(faults.clone())
+            .with_retry_policy(RetryPolicy::default().with_backoff(1, 1).with_jitter_ms(0));
+        
+        // The lost response is retried and the ping goes through
+        faults.schedule(Fault::Drop);
+        client.ping().await.unwrap();
+        assert_eq!(mock.stats().requests, 2);
+        
+        // The order may exist, so it isn't sent again
+        faults.schedule(Fault::Drop);
+        let qty = Fixed::from_str_exact("0.001").unwrap();
+        let price = Some(Fixed::from_str_exact("50000").unwrap());
+        let result = client.place_order("BTCUSDT", crate::types::OrderSide::Buy, crate::types::OrderType::Limit, qty, price).await;
+        assert!(matches!(result, Err(ExchangeError::NetworkError(_))));
+        assert_eq!(mock.stats().requests, 3);
+    }
+    
+    #[monoio::test(timer_enabled = true)]
+    async fn test_order_retries_look_up_before_resending() {
+        use crate::chaos::{Fault, FaultConfig, FaultInjector};
+        
+        let mock = crate::testkit::MockBinance::new();
+        let faults = FaultInjector::new(FaultConfig::new(1));
+        let config = BinanceConfig::testnet()
+            .with_credentials("key".to_string(), "secret".to_string())
+            .with_filter_precision(false);
+        let client = BinanceRestClient::new(config).await.unwrap()
+            .with_mock(mock.clone())
+            .with_faults(faults.clone())
+            .with_retry_policy(RetryPolicy::default().with_backoff(1, 1).with_jitter_ms(0));
+        let order = |id| TestOrderParams {
+            symbol: "BTCUSDT",
+            side: "BUY",
+            order_type: "MARKET",
+            quantity: Some("0.001"),
+            price: None,
+            time_in_force: None,
+            stop_price: None,
+            iceberg_qty: None,
+            new_client_order_id: Some(id),
+        };
+        
+        // The order landed but its response was lost: the lookup finds it, nothing is resent
+        faults.schedule(Fault::Drop);
+        let placed = client.new_order(&order("mm-landed")).await.unwrap();
+        assert_eq!(placed.client_order_id, "mm-landed");
+        assert_eq!(placed.order_id, 1);
+        assert_eq!(mock.stats().requests, 2);
+        
+        // The request never got out: the lookup finds nothing, so it is sent again
+        faults.schedule(Fault::PartialWrite);
+        let placed = client.new_order(&order("mm-lost")).await.unwrap();
+        assert_eq!(placed.order_id, 2);
+        assert_eq!(mock.stats().requests, 4);
+    }
+    
+    #[monoio::test(timer_enabled = true)]
+    async fn test_endpoint_class_timeout_cuts_off_each_attempt() {
+        use crate::chaos::{Fault, FaultConfig, FaultInjector};
+        
+        let mock = crate::testkit::MockBinance::new();
+        let faults = FaultInjector::new(FaultConfig::new(1));
+        let config = BinanceConfig::testnet()
+            .with_endpoint_settings(EndpointClass::MarketData, EndpointSettings::new(20, 10_000));
+        let client = BinanceRestClient::new(config).await.unwrap()
+            .with_mock(mock.clone())
+            .with_faults(faults.clone())
+            .with_retry_policy(RetryPolicy::none());
+        
+        faults.schedule(Fault::Delay(std::time::Duration::from_millis(500)));
+        let started = std::time::Instant::now();
+        let result = client.ping().await;
+        assert!(matches!(result, Err(ExchangeError::Timeout(ref what)) if what == "GET /api/v3/ping after 20ms"), "{result:?}");
+        assert!(started.elapsed() < std::time::Duration::from_millis(500));
+        assert_eq!(mock.stats().requests, 0);
+        
+        client.ping().await.unwrap();
+        assert_eq!(mock.stats().requests, 1);
+    }
+    
+    #[test]
+    fn test_endpoint_settings() {
+        let config = BinanceConfig::default()
+            .with_endpoint_settings(EndpointClass::Order, EndpointSettings::new(1000, 20_000));
+        
+        assert_eq!(config.endpoint_settings(EndpointClass::Order), EndpointSettings::new(1000, 20_000));
+        assert_eq!(config.endpoint_settings(EndpointClass::Cancel), EndpointTimeouts::default().cancel);
+        assert_eq!(EndpointClass::MarketData.to_string(), "market_data");
+    }
 }
\ No newline at end of file
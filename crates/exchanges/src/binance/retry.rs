@@ -0,0 +1,143 @@
+//! Retry policy for transient REST failures
+//!
+//! `BinanceRestClient` retries requests that failed for reasons likely to
+//! clear up on their own: network errors, timeouts, 5xx responses and
+//! Binance's -1000/-1001 internal errors. Rate limit rejections are left to
+//! the rate limiter. A retried POST could place a second order, and Binance
+//! only rejects a repeated `newClientOrderId` while the first order is still
+//! open: a MARKET or IOC order that filled before the timeout would fill
+//! again. So a POST is resent only when it carries a `newClientOrderId` and
+//! an `origClientOrderId` lookup shows the first attempt placed nothing; if
+//! it did, that order is returned instead.
+
+use crate::binance::api_error::BinanceApiError;
+use crate::binance::connection::rand;
+use crate::errors::ExchangeError;
+
+/// Failure kinds a request may be retried on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RetryOn {
+    /// Connection refused, reset or closed before a response
+    Network,
+    /// No response within the client timeout
+    Timeout,
+    /// HTTP 5xx without a more specific Binance error
+    ServerError,
+    /// -1000 / -1001: internal error or disconnect on Binance's side
+    Internal,
+}
+
+impl RetryOn {
+    pub const ALL: [RetryOn; 4] = [Self::Network, Self::Timeout, Self::ServerError, Self::Internal];
+
+    /// The retryable kind of `error`, if it is one
+    pub fn classify(error: &ExchangeError) -> Option<Self> {
+        match error.root() {
+            ExchangeError::NetworkError(_) | ExchangeError::ConnectionFailed(_) => Some(Self::Network),
+            ExchangeError::Timeout(_) => Some(Self::Timeout),
+            ExchangeError::BinanceApi(_, BinanceApiError::Internal(_)) => Some(Self::Internal),
+            ExchangeError::HttpError(status, _) if (500..600).contains(status) => Some(Self::ServerError),
+            _ => None,
+        }
+    }
+}
+
+/// How often and how patiently to retry
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Total attempts including the first; 1 disables retries
+    pub max_attempts: u32,
+    pub initial_delay_ms: u64,
+    pub max_delay_ms: u64,
+    pub backoff_multiplier: f64,
+    pub jitter_ms: u64,
+    pub retry_on: Vec<RetryOn>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_delay_ms: 100,
+            max_delay_ms: 2000,
+            backoff_multiplier: 2.0,
+            jitter_ms: 50,
+            retry_on: RetryOn::ALL.to_vec(),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Never retry
+    pub fn none() -> Self {
+        Self { max_attempts: 1, ..Self::default() }
+    }
+
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    pub fn with_backoff(mut self, initial_delay_ms: u64, max_delay_ms: u64) -> Self {
+        self.initial_delay_ms = initial_delay_ms;
+        self.max_delay_ms = max_delay_ms;
+        self
+    }
+
+    pub fn with_jitter_ms(mut self, jitter_ms: u64) -> Self {
+        self.jitter_ms = jitter_ms;
+        self
+    }
+
+    pub fn with_retry_on(mut self, retry_on: &[RetryOn]) -> Self {
+        self.retry_on = retry_on.to_vec();
+        self
+    }
+
+    /// Whether a request can safely be sent again without checking its effect
+    ///
+    /// GET, PUT and DELETE don't compound; a POST might have created an
+    /// order, which has to be looked up first.
+    pub fn is_idempotent(method: &str) -> bool {
+        !method.eq_ignore_ascii_case("POST")
+    }
+
+    /// Whether attempt number `attempt` (1-based), which failed with `error`, is followed by another
+    pub fn should_retry(&self, attempt: u32, error: &ExchangeError) -> bool {
+        attempt < self.max_attempts && RetryOn::classify(error).is_some_and(|kind| self.retry_on.contains(&kind))
+    }
+
+    /// Exponential backoff after the given 1-based attempt, capped, plus jitter
+    pub fn backoff_delay_ms(&self, attempt: u32) -> u64 {
+        let delay = self.initial_delay_ms as f64 *
+            self.backoff_multiplier.powi(attempt.saturating_sub(1) as i32);
+        let delay = delay.min(self.max_delay_ms as f64) as u64;
+
+        let jitter = (rand::random::<f64>() * self.jitter_ms as f64) as u64;
+        delay + jitter
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_classification_and_backoff() {
+        let policy = RetryPolicy::default().with_jitter_ms(0);
+        let internal = ExchangeError::BinanceApi(500, BinanceApiError::Internal(-1001));
+        assert!(policy.should_retry(1, &internal));
+        assert!(policy.should_retry(2, &ExchangeError::HttpError(503, "unavailable".to_string())));
+        assert!(!policy.should_retry(3, &ExchangeError::Timeout("response".to_string())));
+        assert!(!policy.should_retry(1, &ExchangeError::HttpError(404, "missing".to_string())));
+        assert!(!policy.should_retry(1, &ExchangeError::BinanceApi(429, BinanceApiError::RateLimited { retry_after: None })));
+
+        let network_only = policy.clone().with_retry_on(&[RetryOn::Network]);
+        assert!(!network_only.should_retry(1, &internal));
+        assert!(!RetryPolicy::none().should_retry(1, &internal));
+
+        assert_eq!((1..=6).map(|a| policy.backoff_delay_ms(a)).collect::<Vec<_>>(), [100, 200, 400, 800, 1600, 2000]);
+        assert!(RetryPolicy::is_idempotent("DELETE"));
+        assert!(!RetryPolicy::is_idempotent("POST"));
+    }
+}
@@ -0,0 +1,132 @@
+//! Per-connection and per-stream WebSocket message statistics
+//!
+//! Counters are updated on every received message so operators can see at
+//! runtime which subscription is noisy, silent, or failing to parse.
+
+use std::collections::HashMap;
+
+/// Key used for messages that couldn't be attributed to a stream
+pub const UNATTRIBUTED_STREAM: &str = "<unattributed>";
+
+/// Message counters for one stream (or a whole connection)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StreamStats {
+    pub messages: u64,
+    pub bytes: u64,
+    pub parse_errors: u64,
+    /// Receive time of the first message in nanoseconds
+    pub first_message_at: u64,
+    /// Receive time of the latest message in nanoseconds
+    pub last_message_at: u64,
+    /// Largest gap between consecutive messages in nanoseconds
+    pub max_gap_nanos: u64,
+}
+
+impl StreamStats {
+    fn record(&mut self, bytes: usize, parse_error: bool, now: u64) {
+        if self.messages == 0 {
+            self.first_message_at = now;
+        } else {
+            self.max_gap_nanos = self.max_gap_nanos.max(now.saturating_sub(self.last_message_at));
+        }
+        self.messages += 1;
+        self.bytes += bytes as u64;
+        self.last_message_at = now;
+        if parse_error {
+            self.parse_errors += 1;
+        }
+    }
+
+    /// Time since the last message, in nanoseconds
+    pub fn silence_nanos(&self, now: u64) -> u64 {
+        now.saturating_sub(self.last_message_at)
+    }
+
+    /// Average message rate over the observed interval
+    pub fn messages_per_second(&self) -> f64 {
+        let span = self.last_message_at.saturating_sub(self.first_message_at);
+        if span == 0 {
+            return 0.0;
+        }
+        self.messages.saturating_sub(1) as f64 / (span as f64 / 1_000_000_000.0)
+    }
+}
+
+/// Statistics for a connection and each of its streams
+#[derive(Debug, Clone, Default)]
+pub struct StreamStatsRegistry {
+    connection: StreamStats,
+    streams: HashMap<String, StreamStats>,
+}
+
+impl StreamStatsRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a received message against a stream and the connection
+    pub fn record(&mut self, stream: Option<&str>, bytes: usize, parse_error: bool, now: u64) {
+        self.connection.record(bytes, parse_error, now);
+
+        let key = stream.unwrap_or(UNATTRIBUTED_STREAM);
+        match self.streams.get_mut(key) {
+            Some(stats) => stats.record(bytes, parse_error, now),
+            None => {
+                let mut stats = StreamStats::default();
+                stats.record(bytes, parse_error, now);
+                self.streams.insert(key.to_string(), stats);
+            }
+        }
+    }
+
+    /// Totals across all streams on the connection
+    pub fn connection(&self) -> &StreamStats {
+        &self.connection
+    }
+
+    /// Statistics for one stream
+    pub fn stream(&self, stream: &str) -> Option<&StreamStats> {
+        self.streams.get(stream)
+    }
+
+    /// Snapshot of all streams, sorted by name
+    pub fn streams(&self) -> Vec<(String, StreamStats)> {
+        let mut all: Vec<_> = self.streams.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+        all.sort_by(|a, b| a.0.cmp(&b.0));
+        all
+    }
+
+    /// Reset all counters
+    pub fn reset(&mut self) {
+        self.connection = StreamStats::default();
+        self.streams.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stream_stats_tracking() {
+        let mut registry = StreamStatsRegistry::new();
+
+        registry.record(Some("btcusdt@trade"), 100, false, 1_000_000_000);
+        registry.record(Some("btcusdt@trade"), 120, false, 1_500_000_000);
+        registry.record(Some("btcusdt@trade"), 80, true, 3_500_000_000);
+        registry.record(Some("ethusdt@trade"), 50, false, 3_600_000_000);
+        registry.record(None, 10, true, 3_700_000_000);
+
+        let btc = registry.stream("btcusdt@trade").unwrap();
+        assert_eq!(btc.messages, 3);
+        assert_eq!(btc.bytes, 300);
+        assert_eq!(btc.parse_errors, 1);
+        assert_eq!(btc.max_gap_nanos, 2_000_000_000);
+        assert!((btc.messages_per_second() - 0.8).abs() < 1e-9);
+
+        assert_eq!(registry.connection().messages, 5);
+        assert_eq!(registry.connection().parse_errors, 2);
+        assert_eq!(registry.stream(UNATTRIBUTED_STREAM).unwrap().messages, 1);
+        assert_eq!(registry.streams().len(), 3);
+    }
+}
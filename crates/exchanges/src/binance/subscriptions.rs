@@ -0,0 +1,423 @@
+//! Stream subscription bookkeeping and per-symbol event fan-out
+//!
+//! The manager tracks which WebSocket streams are active on a connection and
+//! lets consumers register interest in individual symbols. Streams are
+//! reference-counted: several strategies can share one stream, and it is only
+//! unsubscribed when the last of them releases it. Incoming events are
+//! fanned out to the interested consumers; whole-market batches (e.g.
+//! `!miniTicker@arr`) are split so each consumer only sees its own symbols.
+//!
+//! A consumer can attach an `EventFilter` that runs on the reader before the
+//! event is sent, so trades below a size or depth updates deep in the book
+//! never reach the strategy's channel.
+//!
+//! Each consumer's channel is bounded. The reader never waits on a slow
+//! consumer: events that don't fit are dropped and counted, see `dropped_count`.
+
+use crate::types::{MarketDataEvent, OrderBookLevel};
+use sriquant_core::prelude::*;
+
+use sriquant_core::channel::{bounded, Receiver, Sender, TrySendError};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use tracing::{debug, warn};
+
+/// Events queued per consumer before new ones are dropped
+pub const SYMBOL_QUEUE_CAPACITY: usize = 4096;
+
+/// Predicate run on the reader before an event is delivered
+pub struct EventFilter(Box<dyn FnMut(&MarketDataEvent) -> bool>);
+
+impl EventFilter {
+    pub fn new(predicate: impl FnMut(&MarketDataEvent) -> bool + 'static) -> Self {
+        Self(Box::new(predicate))
+    }
+
+    /// Drop trades smaller than `quantity`; other events pass
+    pub fn min_trade_quantity(quantity: Fixed) -> Self {
+        Self::new(move |event| match event {
+            MarketDataEvent::Trade(trade) => trade.quantity >= quantity,
+            _ => true,
+        })
+    }
+
+    /// Drop depth updates that can't touch the top `levels` of either side
+    ///
+    /// Keeps a shadow of the levels seen in updates. The real book holds at
+    /// least those levels, so its Nth price is never worse than the shadow's
+    /// and an update inside the real top N is never dropped.
+    pub fn depth_top_levels(levels: usize) -> Self {
+        let mut shadow = ShadowBook::new(levels.max(1));
+        Self::new(move |event| match event {
+            MarketDataEvent::Depth(update) => shadow.touches_top(&update.bids, &update.asks),
+            _ => true,
+        })
+    }
+
+    fn allows(&mut self, event: &MarketDataEvent) -> bool {
+        (self.0)(event)
+    }
+}
+
+impl fmt::Debug for EventFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("EventFilter")
+    }
+}
+
+/// Price levels seen in diffs, bounded per side
+struct ShadowBook {
+    levels: usize,
+    bids: BTreeMap<Fixed, Fixed>,
+    asks: BTreeMap<Fixed, Fixed>,
+}
+
+impl ShadowBook {
+    /// Levels kept per side beyond the watched depth
+    const RETAIN_FACTOR: usize = 32;
+
+    fn new(levels: usize) -> Self {
+        Self { levels, bids: BTreeMap::new(), asks: BTreeMap::new() }
+    }
+
+    /// Whether the update changes a price at or better than the Nth level, then apply it
+    fn touches_top(&mut self, bids: &[OrderBookLevel], asks: &[OrderBookLevel]) -> bool {
+        let nth_bid = (self.bids.len() >= self.levels).then(|| *self.bids.keys().rev().nth(self.levels - 1).unwrap_or(&Fixed::ZERO));
+        let nth_ask = (self.asks.len() >= self.levels).then(|| *self.asks.keys().nth(self.levels - 1).unwrap_or(&Fixed::ZERO));
+        let touches = bids.iter().any(|l| nth_bid.is_none_or(|nth| l.price >= nth))
+            || asks.iter().any(|l| nth_ask.is_none_or(|nth| l.price <= nth));
+
+        let retain = self.levels * Self::RETAIN_FACTOR;
+        for (side, updates) in [(&mut self.bids, bids), (&mut self.asks, asks)] {
+            for level in updates {
+                if level.quantity.is_zero() {
+                    side.remove(&level.price);
+                } else {
+                    side.insert(level.price, level.quantity);
+                }
+            }
+        }
+        while self.bids.len() > retain {
+            self.bids.pop_first();
+        }
+        while self.asks.len() > retain {
+            self.asks.pop_last();
+        }
+        touches
+    }
+}
+
+/// A consumer's channel and optional filter
+#[derive(Debug)]
+struct Sink {
+    tx: Sender<MarketDataEvent>,
+    filter: Option<EventFilter>,
+}
+
+/// Subscription state for one stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct StreamEntry {
+    id: u64,
+    ref_count: u32,
+}
+
+/// Active streams and per-symbol consumers for one WebSocket connection
+#[derive(Debug, Default)]
+pub struct SubscriptionManager {
+    streams: HashMap<String, StreamEntry>,
+    next_id: u64,
+    symbol_sinks: HashMap<String, Vec<Sink>>,
+    filtered: u64,
+    dropped: u64,
+}
+
+impl SubscriptionManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Take a reference to a stream
+    ///
+    /// Returns the request id to SUBSCRIBE with when this is the first
+    /// reference, or `None` if the stream is already active.
+    pub fn acquire(&mut self, stream: &str) -> Option<u64> {
+        if let Some(entry) = self.streams.get_mut(stream) {
+            entry.ref_count += 1;
+            return None;
+        }
+
+        self.next_id += 1;
+        let id = self.next_id;
+        self.streams.insert(stream.to_string(), StreamEntry { id, ref_count: 1 });
+        Some(id)
+    }
+
+    /// Drop a reference to a stream; returns `true` when the last reference
+    /// is gone and the stream should be unsubscribed
+    pub fn release(&mut self, stream: &str) -> bool {
+        let Some(entry) = self.streams.get_mut(stream) else {
+            return false;
+        };
+
+        entry.ref_count = entry.ref_count.saturating_sub(1);
+        if entry.ref_count == 0 {
+            self.streams.remove(stream);
+            debug!("🔕 Last reference to {} released", stream);
+            return true;
+        }
+        false
+    }
+
+    /// Forget a stream regardless of its reference count; returns `true` if it was subscribed
+    pub fn remove_stream(&mut self, stream: &str) -> bool {
+        self.streams.remove(stream).is_some()
+    }
+
+    /// Number of holders of a stream (0 if not subscribed)
+    pub fn ref_count(&self, stream: &str) -> u32 {
+        self.streams.get(stream).map_or(0, |e| e.ref_count)
+    }
+
+    /// Request id the stream was subscribed with
+    pub fn request_id(&self, stream: &str) -> Option<u64> {
+        self.streams.get(stream).map(|e| e.id)
+    }
+
+    /// Allocate a request id for a control message that isn't tied to a new stream
+    pub fn next_request_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    pub fn contains(&self, stream: &str) -> bool {
+        self.streams.contains_key(stream)
+    }
+
+    /// Names of all subscribed streams
+    pub fn streams(&self) -> Vec<String> {
+        self.streams.keys().cloned().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.streams.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.streams.is_empty()
+    }
+
+    /// Drop all streams (e.g. on disconnect); symbol consumers are kept
+    pub fn clear_streams(&mut self) {
+        self.streams.clear();
+    }
+
+    /// Register interest in a symbol's events
+    pub fn subscribe_symbol(&mut self, symbol: &str) -> Receiver<MarketDataEvent> {
+        let (tx, rx) = bounded(SYMBOL_QUEUE_CAPACITY);
+        self.symbol_sinks.entry(symbol.to_uppercase()).or_default().push(Sink { tx, filter: None });
+        rx
+    }
+
+    /// Register interest in a symbol's events that pass `filter`
+    ///
+    /// ```rust,ignore
+    /// let big_trades = manager.subscribe_symbol_filtered("BTCUSDT", EventFilter::min_trade_quantity(qty));
+    /// ```
+    pub fn subscribe_symbol_filtered(&mut self, symbol: &str, filter: EventFilter) -> Receiver<MarketDataEvent> {
+        let (tx, rx) = bounded(SYMBOL_QUEUE_CAPACITY);
+        self.symbol_sinks.entry(symbol.to_uppercase()).or_default().push(Sink { tx, filter: Some(filter) });
+        rx
+    }
+
+    /// Events withheld from consumers by their filters
+    pub fn filtered_count(&self) -> u64 {
+        self.filtered
+    }
+
+    /// Events dropped because a consumer's queue was full
+    pub fn dropped_count(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Symbols with at least one registered consumer
+    pub fn interested_symbols(&self) -> Vec<String> {
+        self.symbol_sinks.keys().cloned().collect()
+    }
+
+    /// Deliver an event to interested consumers, splitting batches per symbol
+    ///
+    /// Returns the number of deliveries. Consumers whose receiver was dropped
+    /// are pruned; a consumer whose queue is full misses the event.
+    pub fn fan_out(&mut self, event: &MarketDataEvent) -> usize {
+        if self.symbol_sinks.is_empty() {
+            return 0;
+        }
+
+        let mut delivered = 0;
+        for single in event.split_by_symbol() {
+            let Some(symbol) = single.symbol() else { continue };
+            let Some(sinks) = self.symbol_sinks.get_mut(symbol) else { continue };
+
+            sinks.retain_mut(|sink| {
+                if let Some(filter) = &mut sink.filter
+                    && !filter.allows(&single)
+                {
+                    self.filtered += 1;
+                    return !sink.tx.is_closed();
+                }
+                match sink.tx.try_send(single.clone()) {
+                    Ok(()) => {
+                        delivered += 1;
+                        true
+                    }
+                    Err(TrySendError::Full(_)) => {
+                        self.dropped += 1;
+                        if self.dropped == 1 || self.dropped.is_multiple_of(1000) {
+                            warn!("⚠️ Consumer of {} is falling behind, {} events dropped", symbol, self.dropped);
+                        }
+                        true
+                    }
+                    Err(TrySendError::Disconnected(_)) => false,
+                }
+            });
+
+            if sinks.is_empty() {
+                debug!("🔕 No consumers left for {}", symbol);
+                let symbol = symbol.to_string();
+                self.symbol_sinks.remove(&symbol);
+            }
+        }
+        delivered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testkit::fx;
+    use crate::types::{ExchangeId, MiniTickerUpdate};
+
+    fn mini(symbol: &str) -> MiniTickerUpdate {
+        MiniTickerUpdate {
+            exchange: ExchangeId::Binance,
+            symbol: symbol.to_string(),
+            close: Fixed::ONE,
+            open: Fixed::ONE,
+            high: Fixed::ONE,
+            low: Fixed::ONE,
+            base_volume: Fixed::ONE,
+            quote_volume: Fixed::ONE,
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn test_stream_tracking() {
+        let mut manager = SubscriptionManager::new();
+        assert_eq!(manager.acquire("btcusdt@trade"), Some(1));
+        assert_eq!(manager.acquire("!miniTicker@arr"), Some(2));
+        assert!(manager.contains("btcusdt@trade"));
+        assert!(manager.remove_stream("btcusdt@trade"));
+        assert!(!manager.remove_stream("btcusdt@trade"));
+        assert_eq!(manager.len(), 1);
+    }
+
+    #[test]
+    fn test_reference_counting() {
+        let mut manager = SubscriptionManager::new();
+
+        // Two strategies share one stream; only the first triggers a SUBSCRIBE
+        assert!(manager.acquire("ethusdt@depth@100ms").is_some());
+        assert!(manager.acquire("ethusdt@depth@100ms").is_none());
+        assert_eq!(manager.ref_count("ethusdt@depth@100ms"), 2);
+
+        assert!(!manager.release("ethusdt@depth@100ms"));
+        assert!(manager.contains("ethusdt@depth@100ms"));
+
+        // Last holder leaves: stream must be unsubscribed
+        assert!(manager.release("ethusdt@depth@100ms"));
+        assert!(!manager.contains("ethusdt@depth@100ms"));
+        assert!(!manager.release("ethusdt@depth@100ms"));
+    }
+
+    #[test]
+    fn test_batch_fan_out() {
+        let mut manager = SubscriptionManager::new();
+        let mut btc = manager.subscribe_symbol("btcusdt");
+        let mut eth = manager.subscribe_symbol("ETHUSDT");
+
+        let batch = MarketDataEvent::MiniTickerBatch(vec![mini("BTCUSDT"), mini("ETHUSDT"), mini("BNBUSDT")]);
+        assert_eq!(manager.fan_out(&batch), 2);
+
+        assert!(matches!(btc.try_recv(), Ok(MarketDataEvent::MiniTicker(t)) if t.symbol == "BTCUSDT"));
+        assert!(matches!(eth.try_recv(), Ok(MarketDataEvent::MiniTicker(t)) if t.symbol == "ETHUSDT"));
+        assert!(btc.try_recv().is_err());
+
+        // Dropped consumers are pruned
+        drop(eth);
+        assert_eq!(manager.fan_out(&batch), 1);
+        assert_eq!(manager.interested_symbols(), vec!["BTCUSDT".to_string()]);
+    }
+
+    #[test]
+    fn test_full_consumer_drops_instead_of_blocking() {
+        let mut manager = SubscriptionManager::new();
+        let mut slow = manager.subscribe_symbol("BTCUSDT");
+        let event = MarketDataEvent::MiniTicker(mini("BTCUSDT"));
+
+        for _ in 0..SYMBOL_QUEUE_CAPACITY {
+            assert_eq!(manager.fan_out(&event), 1);
+        }
+        assert_eq!(manager.fan_out(&event), 0);
+        assert_eq!(manager.dropped_count(), 1);
+
+        // Still subscribed: once it catches up, events flow again
+        assert!(slow.try_recv().is_ok());
+        assert_eq!(manager.fan_out(&event), 1);
+        assert_eq!(slow.len(), SYMBOL_QUEUE_CAPACITY);
+    }
+
+    #[test]
+    fn test_filters_run_before_delivery() {
+        use crate::types::{DepthUpdate, TradeSide, TradeUpdate};
+
+        let level = |price: &str| OrderBookLevel { price: fx(price), quantity: Fixed::ONE };
+        let depth = |bids: &[&str], asks: &[&str]| MarketDataEvent::Depth(DepthUpdate {
+            exchange: ExchangeId::Binance,
+            symbol: "BTCUSDT".to_string(),
+            bids: bids.iter().map(|p| level(p)).collect(),
+            asks: asks.iter().map(|p| level(p)).collect(),
+            timestamp: 0,
+            first_update_id: 1,
+            update_id: 1,
+            kind: crate::types::DepthKind::Diff,
+        });
+        let trade = |quantity: &str| MarketDataEvent::Trade(TradeUpdate {
+            exchange: ExchangeId::Binance,
+            symbol: "BTCUSDT".to_string(),
+            price: Fixed::ONE,
+            quantity: fx(quantity),
+            side: TradeSide::Buy,
+            timestamp: 0,
+            trade_id: 1,
+        });
+
+        let mut manager = SubscriptionManager::new();
+        let all = manager.subscribe_symbol("BTCUSDT");
+        let big = manager.subscribe_symbol_filtered("BTCUSDT", EventFilter::min_trade_quantity(fx("1")));
+        let top = manager.subscribe_symbol_filtered("BTCUSDT", EventFilter::depth_top_levels(2));
+
+        assert_eq!(manager.fan_out(&trade("0.5")), 2);
+        assert_eq!(manager.fan_out(&trade("2")), 3);
+        assert_eq!(big.len(), 1);
+
+        // Builds the shadow: bids 100, 99, 98 and asks 101, 102, 103
+        manager.fan_out(&depth(&["100", "99", "98"], &["101", "102", "103"]));
+        // Third level on each side only: outside the top 2
+        manager.fan_out(&depth(&["97"], &["104"]));
+        manager.fan_out(&depth(&["99"], &[]));
+        assert_eq!(top.len(), 4);
+        assert_eq!(all.len(), 5);
+        assert_eq!(manager.filtered_count(), 2);
+    }
+}
@@ -246,6 +246,32 @@ pub struct BinanceKline {
 }
 
 impl BinanceKline {
+    /// Decode a kline from the positional JSON array returned by `/api/v3/klines`
+    /// 
+    /// Returns `None` if the array has fewer than the 12 documented fields or
+    /// any of them has the wrong type, rather than defaulting it to zero.
+    pub fn from_json_array(raw: &[serde_json::Value]) -> Option<Self> {
+        if raw.len() < 12 {
+            return None;
+        }
+        let text = |i: usize| raw[i].as_str().map(str::to_string);
+        
+        Some(Self {
+            open_time: raw[0].as_u64()?,
+            open: text(1)?,
+            high: text(2)?,
+            low: text(3)?,
+            close: text(4)?,
+            volume: text(5)?,
+            close_time: raw[6].as_u64()?,
+            quote_asset_volume: text(7)?,
+            number_of_trades: u32::try_from(raw[8].as_u64()?).ok()?,
+            taker_buy_base_asset_volume: text(9)?,
+            taker_buy_quote_asset_volume: text(10)?,
+            ignore: text(11)?,
+        })
+    }
+    
     /// Get OHLCV as Fixed values
     pub fn ohlcv(&self) -> Result<(Fixed, Fixed, Fixed, Fixed, Fixed), crate::errors::ExchangeError> {
         let open = Fixed::from_str_exact(&self.open)
@@ -256,11 +282,52 @@ impl BinanceKline {
             .map_err(|_| crate::errors::ExchangeError::InvalidResponse("Invalid low price".to_string()))?;
         let close = Fixed::from_str_exact(&self.close)
             .map_err(|_| crate::errors::ExchangeError::InvalidResponse("Invalid close price".to_string()))?;
-        let volume = Fixed::from_str_exact(&self.volume)
+        // Volume isn't bounded by a price: DOGE and SHIB bars trade billions
+        let volume = Fixed::from_str_unbounded(&self.volume)
             .map_err(|_| crate::errors::ExchangeError::InvalidResponse("Invalid volume".to_string()))?;
         
         Ok((open, high, low, close, volume))
     }
+    
+    /// Convert to the exchange-agnostic kline; closed once `close_time` is before `now_ms`
+    pub fn to_kline(&self, symbol: &str, interval: &str, now_ms: u64) -> Result<crate::types::Kline, crate::errors::ExchangeError> {
+        use crate::binance::exchange::unbounded;
+        
+        Ok(crate::types::Kline {
+            symbol: symbol.to_string(),
+            interval: interval.to_string(),
+            open_time: self.open_time,
+            close_time: self.close_time,
+            open: Fixed::from_str_exact(&self.open)?,
+            high: Fixed::from_str_exact(&self.high)?,
+            low: Fixed::from_str_exact(&self.low)?,
+            close: Fixed::from_str_exact(&self.close)?,
+            volume: unbounded(&self.volume)?,
+            quote_volume: unbounded(&self.quote_asset_volume)?,
+            number_of_trades: self.number_of_trades,
+            is_closed: self.close_time < now_ms,
+        })
+    }
+}
+
+/// Convert a raw Binance kline into the exchange-agnostic candle
+impl TryFrom<BinanceKline> for crate::types::Candle {
+    type Error = crate::errors::ExchangeError;
+    
+    fn try_from(kline: BinanceKline) -> Result<Self, Self::Error> {
+        let (open, high, low, close, volume) = kline.ohlcv()?;
+        
+        Ok(crate::types::Candle {
+            open_time: kline.open_time,
+            close_time: kline.close_time,
+            open,
+            high,
+            low,
+            close,
+            volume,
+            number_of_trades: kline.number_of_trades,
+        })
+    }
 }
 
 /// Binance error response
@@ -356,6 +423,35 @@ mod tests {
         assert_eq!(commission.to_string(), "0.001");
     }
     
+    #[test]
+    fn test_kline_to_candle() {
+        let raw: Vec<serde_json::Value> = serde_json::from_str(
+            r#"[1499040000000,"0.01634790","0.80000000","0.01575800","0.01577100","148976.11427815",1499644799999,"2434.19055334",308,"1756.87402397","28.46694368","0"]"#
+        ).unwrap();
+        
+        let kline = BinanceKline::from_json_array(&raw).unwrap();
+        let candle = crate::types::Candle::try_from(kline).unwrap();
+        
+        assert_eq!(candle.open_time, 1499040000000);
+        assert_eq!(candle.close_time, 1499644799999);
+        assert_eq!(candle.open.to_string(), "0.01634790");
+        assert_eq!(candle.high.to_string(), "0.80000000");
+        assert_eq!(candle.volume.to_string(), "148976.11427815");
+        assert_eq!(candle.number_of_trades, 308);
+        
+        let mut heavy = raw.clone();
+        heavy[5] = "5312690411.00000000".into();
+        let candle = crate::types::Candle::try_from(BinanceKline::from_json_array(&heavy).unwrap()).unwrap();
+        assert_eq!(candle.volume.to_string(), "5312690411.00000000");
+        let kline = BinanceKline::from_json_array(&heavy).unwrap().to_kline("DOGEUSDT", "1m", 0).unwrap();
+        assert_eq!(kline.volume, candle.volume);
+        
+        assert!(BinanceKline::from_json_array(&raw[..6]).is_none());
+        let mut missing_close = raw.clone();
+        missing_close[4] = serde_json::Value::Null;
+        assert!(BinanceKline::from_json_array(&missing_close).is_none());
+    }
+    
     #[test]
     fn test_side_conversions() {
         let binance_buy = BinanceOrderSide::Buy;
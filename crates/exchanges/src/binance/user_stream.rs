@@ -5,16 +5,29 @@
 //! - Balance updates  
 //! - Order updates
 //! - Trade executions
+//!
+//! `UserStreamManager` owns the listen key lifecycle on top of the client:
+//! it creates the key, keeps it alive every 30 minutes, and renews it and
+//! reconnects whenever the stream drops or the key expires.
 
 use crate::errors::{ExchangeError, Result};
-use crate::websocket::MonoioWebSocket;
+use crate::cassette::CassetteHandle;
+use crate::chaos::FaultInjector;
+use crate::websocket::{MonoioWebSocket, WebSocketOptions};
 use sriquant_core::prelude::*;
-use super::rest::BinanceConfig;
+use super::connection::ReconnectConfig;
+use super::rest::{BinanceConfig, BinanceRestClient};
 
-use tracing::{info, debug};
-use serde_json::Value;
+use tracing::{info, debug, error, warn};
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::Duration;
 use url::Url;
 
+/// Binance expires listen keys not kept alive for 60 minutes
+pub const LISTEN_KEY_KEEPALIVE: Duration = Duration::from_secs(30 * 60);
+
 /// Binance User Stream WebSocket client
 pub struct BinanceUserStreamClient {
     #[allow(dead_code)]
@@ -22,16 +35,14 @@ pub struct BinanceUserStreamClient {
     base_url: String,
     websocket: Option<MonoioWebSocket>,
     listen_key: String,
+    cassette: Option<CassetteHandle>,
+    faults: Option<FaultInjector>,
 }
 
 impl BinanceUserStreamClient {
     /// Create a new user stream client
     pub fn new(config: BinanceConfig) -> Self {
-        let base_url = if config.testnet {
-            "wss://stream.testnet.binance.vision".to_string()
-        } else {
-            "wss://stream.binance.com:9443".to_string()
-        };
+        let base_url = config.ws_url.trim_end_matches('/').to_string();
         
         info!("🔗 Binance User Stream client created");
         info!("   Base URL: {}", base_url);
@@ -41,9 +52,23 @@ impl BinanceUserStreamClient {
             base_url,
             websocket: None,
             listen_key: String::new(),
+            cassette: None,
+            faults: None,
         }
     }
     
+    /// Record sessions into, or replay them from, a cassette (listen keys are scrubbed)
+    pub fn with_cassette(mut self, cassette: CassetteHandle) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+    
+    /// Inject network faults into the connection, see `crate::chaos`
+    pub fn with_faults(mut self, faults: FaultInjector) -> Self {
+        self.faults = Some(faults);
+        self
+    }
+    
     /// Connect to user data stream
     pub async fn connect(&mut self, listen_key: &str) -> Result<()> {
         let timer = PerfTimer::start("binance_user_stream_connect".to_string());
@@ -59,7 +84,11 @@ impl BinanceUserStreamClient {
         info!("🔗 Connecting to Binance user data stream: {}", url);
         
         // Establish WebSocket connection
-        let websocket = MonoioWebSocket::connect(url).await?;
+        let options = WebSocketOptions { timeout: self.config.request_timeout(), ..WebSocketOptions::default() };
+        let mut websocket = MonoioWebSocket::connect_with_options(url, self.cassette.as_ref(), options).await?;
+        if let Some(faults) = &self.faults {
+            websocket = websocket.with_faults(faults.clone());
+        }
         self.websocket = Some(websocket);
         
         timer.log_elapsed();
@@ -84,6 +113,7 @@ impl BinanceUserStreamClient {
             
             match self.process_message(&message) {
                 Ok(event) => return Ok(event),
+                Err(e @ ExchangeError::ConnectionFailed(_)) => return Err(e),
                 Err(e) => {
                     debug!("Error processing message: {}", e);
                     continue;
@@ -93,119 +123,26 @@ impl BinanceUserStreamClient {
     }
     
     /// Process incoming user data message
+    ///
+    /// Each event type is deserialized into its own wire struct, so a missing
+    /// or mistyped field is an error instead of a silent zero.
     fn process_message(&self, message: &str) -> Result<UserDataEvent> {
         let timer = PerfTimer::start("binance_user_stream_process".to_string());
         
-        let json: Value = serde_json::from_str(message)
-            .map_err(|e| ExchangeError::SerializationError(e.to_string()))?;
-        
-        let event = if let Some(event_type) = json["e"].as_str() {
-            match event_type {
-                "outboundAccountPosition" => self.parse_account_update(&json)?,
-                "balanceUpdate" => self.parse_balance_update(&json)?,
-                "executionReport" => self.parse_order_update(&json)?,
-                _ => return Err(ExchangeError::UnsupportedStream(format!("Unknown user event type: {}", event_type)))
-            }
-        } else {
-            return Err(ExchangeError::InvalidResponse("No event type in user data message".to_string()));
+        let header: EventHeader = serde_json::from_str(message)?;
+        let event = match header.event_type.as_ref() {
+            // Nothing more arrives on this connection until a new key is used
+            "listenKeyExpired" => return Err(ExchangeError::ConnectionFailed("listen key expired".to_string())),
+            "outboundAccountPosition" => serde_json::from_str::<AccountPositionWire>(message)?.into_event()?,
+            "balanceUpdate" => serde_json::from_str::<BalanceUpdateWire>(message)?.into_event()?,
+            "executionReport" => serde_json::from_str::<ExecutionReportWire>(message)?.into_event()?,
+            event_type => return Err(ExchangeError::UnsupportedStream(format!("Unknown user event type: {}", event_type))),
         };
         
         timer.log_elapsed();
         Ok(event)
     }
     
-    /// Parse account update event
-    fn parse_account_update(&self, data: &Value) -> Result<UserDataEvent> {
-        let mut balances = Vec::new();
-        
-        if let Some(balance_array) = data["B"].as_array() {
-            for balance in balance_array {
-                let asset = balance["a"].as_str().unwrap_or("").to_string();
-                let free = Fixed::from_str_exact(balance["f"].as_str().unwrap_or("0"))
-                    .map_err(|_| ExchangeError::InvalidResponse("Invalid free balance".to_string()))?;
-                let locked = Fixed::from_str_exact(balance["l"].as_str().unwrap_or("0"))
-                    .map_err(|_| ExchangeError::InvalidResponse("Invalid locked balance".to_string()))?;
-                
-                balances.push(BalanceInfo { asset, free, locked });
-            }
-        }
-        
-        let account_update = AccountUpdateEvent {
-            event_time: data["E"].as_u64().unwrap_or(0),
-            last_account_update: data["u"].as_u64().unwrap_or(0),
-            balances,
-        };
-        
-        Ok(UserDataEvent::AccountUpdate(account_update))
-    }
-
-    /// Parse balance update event
-    fn parse_balance_update(&self, data: &Value) -> Result<UserDataEvent> {
-        let balance_update = BalanceUpdateEvent {
-            event_time: data["E"].as_u64().unwrap_or(0),
-            asset: data["a"].as_str().unwrap_or("").to_string(),
-            balance_delta: Fixed::from_str_exact(data["d"].as_str().unwrap_or("0"))
-                .map_err(|_| ExchangeError::InvalidResponse("Invalid balance delta".to_string()))?,
-            clear_time: data["T"].as_u64().unwrap_or(0),
-        };
-        
-        Ok(UserDataEvent::BalanceUpdate(balance_update))
-    }
-
-    /// Parse order update event (execution report)
-    fn parse_order_update(&self, data: &Value) -> Result<UserDataEvent> {
-        let side = match data["S"].as_str().unwrap_or("") {
-            "BUY" => TradeSide::Buy,
-            "SELL" => TradeSide::Sell,
-            _ => TradeSide::Buy, // default
-        };
-        
-        let order_update = OrderUpdateEvent {
-            event_time: data["E"].as_u64().unwrap_or(0),
-            symbol: data["s"].as_str().unwrap_or("").to_string(),
-            client_order_id: data["c"].as_str().unwrap_or("").to_string(),
-            side,
-            order_type: data["o"].as_str().unwrap_or("").to_string(),
-            time_in_force: data["f"].as_str().unwrap_or("").to_string(),
-            order_quantity: Fixed::from_str_exact(data["q"].as_str().unwrap_or("0"))
-                .map_err(|_| ExchangeError::InvalidResponse("Invalid order quantity".to_string()))?,
-            order_price: Fixed::from_str_exact(data["p"].as_str().unwrap_or("0"))
-                .map_err(|_| ExchangeError::InvalidResponse("Invalid order price".to_string()))?,
-            stop_price: Fixed::from_str_exact(data["P"].as_str().unwrap_or("0"))
-                .map_err(|_| ExchangeError::InvalidResponse("Invalid stop price".to_string()))?,
-            iceberg_quantity: Fixed::from_str_exact(data["F"].as_str().unwrap_or("0"))
-                .map_err(|_| ExchangeError::InvalidResponse("Invalid iceberg quantity".to_string()))?,
-            order_list_id: data["g"].as_i64().unwrap_or(-1),
-            original_client_order_id: data["C"].as_str().unwrap_or("").to_string(),
-            execution_type: data["x"].as_str().unwrap_or("").to_string(),
-            order_status: data["X"].as_str().unwrap_or("").to_string(),
-            order_reject_reason: data["r"].as_str().unwrap_or("").to_string(),
-            order_id: data["i"].as_u64().unwrap_or(0),
-            last_executed_quantity: Fixed::from_str_exact(data["l"].as_str().unwrap_or("0"))
-                .map_err(|_| ExchangeError::InvalidResponse("Invalid last executed quantity".to_string()))?,
-            cumulative_filled_quantity: Fixed::from_str_exact(data["z"].as_str().unwrap_or("0"))
-                .map_err(|_| ExchangeError::InvalidResponse("Invalid cumulative filled quantity".to_string()))?,
-            last_executed_price: Fixed::from_str_exact(data["L"].as_str().unwrap_or("0"))
-                .map_err(|_| ExchangeError::InvalidResponse("Invalid last executed price".to_string()))?,
-            commission_amount: Fixed::from_str_exact(data["n"].as_str().unwrap_or("0"))
-                .map_err(|_| ExchangeError::InvalidResponse("Invalid commission amount".to_string()))?,
-            commission_asset: data["N"].as_str().unwrap_or("").to_string(),
-            transaction_time: data["T"].as_u64().unwrap_or(0),
-            trade_id: data["t"].as_u64().unwrap_or(0),
-            is_order_on_book: data["w"].as_bool().unwrap_or(false),
-            is_trade_maker_side: data["m"].as_bool().unwrap_or(false),
-            order_creation_time: data["O"].as_u64().unwrap_or(0),
-            cumulative_quote_asset_transacted_quantity: Fixed::from_str_exact(data["Z"].as_str().unwrap_or("0"))
-                .map_err(|_| ExchangeError::InvalidResponse("Invalid cumulative quote quantity".to_string()))?,
-            last_quote_asset_transacted_quantity: Fixed::from_str_exact(data["Y"].as_str().unwrap_or("0"))
-                .map_err(|_| ExchangeError::InvalidResponse("Invalid last quote quantity".to_string()))?,
-            quote_order_quantity: Fixed::from_str_exact(data["Q"].as_str().unwrap_or("0"))
-                .map_err(|_| ExchangeError::InvalidResponse("Invalid quote order quantity".to_string()))?,
-        };
-        
-        Ok(UserDataEvent::OrderUpdate(order_update))
-    }
-    
     /// Check if connected
     pub fn is_connected(&self) -> bool {
         self.websocket.as_ref().is_some_and(|ws| ws.is_connected())
@@ -235,8 +172,380 @@ impl BinanceUserStreamClient {
     }
 }
 
+/// Listen key shared with the keepalive task
+struct KeepaliveState {
+    listen_key: RefCell<String>,
+    stopped: Cell<bool>,
+}
+
+/// User data stream with the listen key managed for you
+///
+/// ```rust,ignore
+/// let mut stream = UserStreamManager::new(BinanceConfig::testnet().with_env_credentials()?).await?;
+/// loop {
+///     match stream.next_event().await? {
+///         UserDataEvent::OrderUpdate(order) => oms.apply_update(&order),
+///         _ => {}
+///     }
+/// }
+/// ```
+pub struct UserStreamManager {
+    config: BinanceConfig,
+    rest: Rc<BinanceRestClient>,
+    client: Option<BinanceUserStreamClient>,
+    keepalive: Rc<KeepaliveState>,
+    keepalive_interval: Duration,
+    reconnect: ReconnectConfig,
+    cassette: Option<CassetteHandle>,
+    faults: Option<FaultInjector>,
+    reconnects: u64,
+}
+
+impl UserStreamManager {
+    /// Manager with its own REST client; call `next_event` to connect
+    pub async fn new(config: BinanceConfig) -> Result<Self> {
+        let rest = Rc::new(BinanceRestClient::new(config.clone()).await?);
+        Ok(Self::with_rest_client(config, rest))
+    }
+
+    /// Manager sharing a REST client with the rest of the strategy
+    pub fn with_rest_client(config: BinanceConfig, rest: Rc<BinanceRestClient>) -> Self {
+        Self {
+            config,
+            rest,
+            client: None,
+            keepalive: Rc::new(KeepaliveState { listen_key: RefCell::new(String::new()), stopped: Cell::new(false) }),
+            keepalive_interval: LISTEN_KEY_KEEPALIVE,
+            reconnect: ReconnectConfig::default(),
+            cassette: None,
+            faults: None,
+            reconnects: 0,
+        }
+    }
+
+    pub fn with_keepalive_interval(mut self, interval: Duration) -> Self {
+        self.keepalive_interval = interval;
+        self
+    }
+
+    pub fn with_reconnect(mut self, reconnect: ReconnectConfig) -> Self {
+        self.reconnect = reconnect;
+        self
+    }
+
+    /// Record or replay the WebSocket side in a cassette
+    pub fn with_cassette(mut self, cassette: CassetteHandle) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+
+    /// Inject network faults into each stream connection
+    pub fn with_faults(mut self, faults: FaultInjector) -> Self {
+        self.faults = Some(faults);
+        self
+    }
+
+    pub fn listen_key(&self) -> String {
+        self.keepalive.listen_key.borrow().clone()
+    }
+
+    /// Times the stream was re-established after dropping
+    pub fn reconnects(&self) -> u64 {
+        self.reconnects
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.client.as_ref().is_some_and(|c| c.is_connected())
+    }
+
+    /// Create (or refresh) the listen key and connect, starting the keepalive on first use
+    pub async fn connect(&mut self) -> Result<()> {
+        let timer = PerfTimer::start("binance_user_stream_manager_connect".to_string());
+        // Binance hands back the current key while it is still valid
+        let listen_key = self.rest.create_listen_key().await?;
+        let mut client = BinanceUserStreamClient::new(self.config.clone());
+        if let Some(cassette) = &self.cassette {
+            client = client.with_cassette(cassette.clone());
+        }
+        if let Some(faults) = &self.faults {
+            client = client.with_faults(faults.clone());
+        }
+        client.connect(&listen_key).await?;
+
+        let first = self.keepalive.listen_key.replace(listen_key).is_empty();
+        self.client = Some(client);
+        if first {
+            self.spawn_keepalive();
+        }
+        timer.log_elapsed();
+        Ok(())
+    }
+
+    fn spawn_keepalive(&self) {
+        let rest = self.rest.clone();
+        let state = Rc::downgrade(&self.keepalive);
+        let interval = self.keepalive_interval;
+        monoio::spawn(async move {
+            loop {
+                monoio::time::sleep(interval).await;
+                // Stop once the manager is gone or shut down
+                let Some(state) = state.upgrade() else { break };
+                if state.stopped.get() {
+                    break;
+                }
+                let listen_key = state.listen_key.borrow().clone();
+                if let Err(e) = rest.keepalive_listen_key(&listen_key).await {
+                    // The stream reports the expiry; next_event renews the key then
+                    warn!("⚠️  Listen key keepalive failed: {}", e);
+                }
+            }
+            debug!("Listen key keepalive stopped");
+        });
+        info!("🔄 Listen key keepalive every {}s", interval.as_secs());
+    }
+
+    /// Next user data event, connecting first and reconnecting with a renewed
+    /// key whenever the stream drops
+    ///
+    /// Fails only when `ReconnectConfig::max_attempts` reconnects in a row fail.
+    pub async fn next_event(&mut self) -> Result<UserDataEvent> {
+        if self.client.is_none() {
+            self.connect().await?;
+        }
+        loop {
+            let Some(client) = self.client.as_mut() else { unreachable!("connected above") };
+            match client.receive_event().await {
+                Ok(event) => return Ok(event),
+                Err(e) => {
+                    warn!("🔌 User stream dropped: {}", e);
+                    self.client = None;
+                    self.reconnect_after(e).await?;
+                }
+            }
+        }
+    }
+
+    async fn reconnect_after(&mut self, cause: ExchangeError) -> Result<()> {
+        let mut last_error = cause;
+        for attempt in 1..=self.reconnect.max_attempts {
+            let delay = self.reconnect.backoff_delay_ms(attempt);
+            warn!("🔄 Renewing listen key and reconnecting in {}ms (attempt {}/{})", delay, attempt, self.reconnect.max_attempts);
+            monoio::time::sleep(Duration::from_millis(delay)).await;
+            match self.connect().await {
+                Ok(()) => {
+                    self.reconnects += 1;
+                    info!("✅ User stream reconnected after {} attempts", attempt);
+                    return Ok(());
+                }
+                Err(e) => {
+                    warn!("⚠️  User stream reconnect attempt {} failed: {}", attempt, e);
+                    last_error = e;
+                }
+            }
+        }
+        error!("❌ User stream gave up after {} reconnect attempts", self.reconnect.max_attempts);
+        Err(last_error)
+    }
+
+    /// Stop the keepalive, close the socket and delete the listen key
+    pub async fn shutdown(&mut self) -> Result<()> {
+        self.keepalive.stopped.set(true);
+        if let Some(mut client) = self.client.take() {
+            client.close().await?;
+        }
+        let listen_key = self.keepalive.listen_key.take();
+        if !listen_key.is_empty() {
+            self.rest.close_listen_key(&listen_key).await?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for UserStreamManager {
+    fn drop(&mut self) {
+        self.keepalive.stopped.set(true);
+    }
+}
+
+/// Parse a decimal field, naming it in the error
+fn decimal(field: &str, value: &str) -> Result<Fixed> {
+    Fixed::from_str_exact(value)
+        .map_err(|_| ExchangeError::InvalidResponse(format!("Invalid {}: {}", field, value)))
+}
+
+/// Just the event type, to pick the wire struct
+#[derive(Deserialize)]
+struct EventHeader<'a> {
+    #[serde(rename = "e", borrow)]
+    event_type: Cow<'a, str>,
+}
+
+/// `outboundAccountPosition` as sent by Binance
+#[derive(Deserialize)]
+struct AccountPositionWire<'a> {
+    #[serde(rename = "E")]
+    event_time: u64,
+    #[serde(rename = "u")]
+    last_account_update: u64,
+    #[serde(rename = "B", borrow)]
+    balances: Vec<BalanceWire<'a>>,
+}
+
+#[derive(Deserialize)]
+struct BalanceWire<'a> {
+    #[serde(rename = "a")]
+    asset: String,
+    #[serde(rename = "f", borrow)]
+    free: Cow<'a, str>,
+    #[serde(rename = "l", borrow)]
+    locked: Cow<'a, str>,
+}
+
+impl AccountPositionWire<'_> {
+    fn into_event(self) -> Result<UserDataEvent> {
+        let balances = self.balances.into_iter()
+            .map(|b| Ok(BalanceInfo {
+                free: decimal("free balance", &b.free)?,
+                locked: decimal("locked balance", &b.locked)?,
+                asset: b.asset,
+            }))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(UserDataEvent::AccountUpdate(AccountUpdateEvent {
+            event_time: self.event_time,
+            last_account_update: self.last_account_update,
+            balances,
+        }))
+    }
+}
+
+/// `balanceUpdate` as sent by Binance
+#[derive(Deserialize)]
+struct BalanceUpdateWire<'a> {
+    #[serde(rename = "E")]
+    event_time: u64,
+    #[serde(rename = "a")]
+    asset: String,
+    #[serde(rename = "d", borrow)]
+    balance_delta: Cow<'a, str>,
+    #[serde(rename = "T")]
+    clear_time: u64,
+}
+
+impl BalanceUpdateWire<'_> {
+    fn into_event(self) -> Result<UserDataEvent> {
+        Ok(UserDataEvent::BalanceUpdate(BalanceUpdateEvent {
+            event_time: self.event_time,
+            balance_delta: decimal("balance delta", &self.balance_delta)?,
+            asset: self.asset,
+            clear_time: self.clear_time,
+        }))
+    }
+}
+
+/// `executionReport` as sent by Binance
+#[derive(Deserialize)]
+struct ExecutionReportWire<'a> {
+    #[serde(rename = "E")]
+    event_time: u64,
+    #[serde(rename = "s")]
+    symbol: String,
+    #[serde(rename = "c")]
+    client_order_id: String,
+    #[serde(rename = "S")]
+    side: TradeSide,
+    #[serde(rename = "o")]
+    order_type: String,
+    #[serde(rename = "f")]
+    time_in_force: String,
+    #[serde(rename = "q", borrow)]
+    order_quantity: Cow<'a, str>,
+    #[serde(rename = "p", borrow)]
+    order_price: Cow<'a, str>,
+    #[serde(rename = "P", borrow)]
+    stop_price: Cow<'a, str>,
+    #[serde(rename = "F", borrow)]
+    iceberg_quantity: Cow<'a, str>,
+    #[serde(rename = "g")]
+    order_list_id: i64,
+    #[serde(rename = "C")]
+    original_client_order_id: String,
+    #[serde(rename = "x")]
+    execution_type: String,
+    #[serde(rename = "X")]
+    order_status: String,
+    #[serde(rename = "r")]
+    order_reject_reason: String,
+    #[serde(rename = "i")]
+    order_id: u64,
+    #[serde(rename = "l", borrow)]
+    last_executed_quantity: Cow<'a, str>,
+    #[serde(rename = "z", borrow)]
+    cumulative_filled_quantity: Cow<'a, str>,
+    #[serde(rename = "L", borrow)]
+    last_executed_price: Cow<'a, str>,
+    #[serde(rename = "n", borrow)]
+    commission_amount: Cow<'a, str>,
+    /// `null` until the order trades
+    #[serde(rename = "N")]
+    commission_asset: Option<String>,
+    #[serde(rename = "T")]
+    transaction_time: u64,
+    /// -1 for events without a trade
+    #[serde(rename = "t")]
+    trade_id: i64,
+    #[serde(rename = "w")]
+    is_order_on_book: bool,
+    #[serde(rename = "m")]
+    is_trade_maker_side: bool,
+    #[serde(rename = "O")]
+    order_creation_time: u64,
+    #[serde(rename = "Z", borrow)]
+    cumulative_quote_asset_transacted_quantity: Cow<'a, str>,
+    #[serde(rename = "Y", borrow)]
+    last_quote_asset_transacted_quantity: Cow<'a, str>,
+    #[serde(rename = "Q", borrow)]
+    quote_order_quantity: Cow<'a, str>,
+}
+
+impl ExecutionReportWire<'_> {
+    fn into_event(self) -> Result<UserDataEvent> {
+        Ok(UserDataEvent::OrderUpdate(OrderUpdateEvent {
+            event_time: self.event_time,
+            symbol: self.symbol,
+            client_order_id: self.client_order_id,
+            side: self.side,
+            order_type: self.order_type,
+            time_in_force: self.time_in_force,
+            order_quantity: decimal("order quantity", &self.order_quantity)?,
+            order_price: decimal("order price", &self.order_price)?,
+            stop_price: decimal("stop price", &self.stop_price)?,
+            iceberg_quantity: decimal("iceberg quantity", &self.iceberg_quantity)?,
+            order_list_id: self.order_list_id,
+            original_client_order_id: self.original_client_order_id,
+            execution_type: self.execution_type,
+            order_status: self.order_status,
+            order_reject_reason: self.order_reject_reason,
+            order_id: self.order_id,
+            last_executed_quantity: decimal("last executed quantity", &self.last_executed_quantity)?,
+            cumulative_filled_quantity: decimal("cumulative filled quantity", &self.cumulative_filled_quantity)?,
+            last_executed_price: decimal("last executed price", &self.last_executed_price)?,
+            commission_amount: decimal("commission amount", &self.commission_amount)?,
+            commission_asset: self.commission_asset,
+            transaction_time: self.transaction_time,
+            trade_id: u64::try_from(self.trade_id).ok(),
+            is_order_on_book: self.is_order_on_book,
+            is_trade_maker_side: self.is_trade_maker_side,
+            order_creation_time: self.order_creation_time,
+            cumulative_quote_asset_transacted_quantity: decimal("cumulative quote quantity", &self.cumulative_quote_asset_transacted_quantity)?,
+            last_quote_asset_transacted_quantity: decimal("last quote quantity", &self.last_quote_asset_transacted_quantity)?,
+            quote_order_quantity: decimal("quote order quantity", &self.quote_order_quantity)?,
+        }))
+    }
+}
+
 /// User data events
 #[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)] // Boxing would add an allocation per execution report
 pub enum UserDataEvent {
     AccountUpdate(AccountUpdateEvent),
     BalanceUpdate(BalanceUpdateEvent),
@@ -291,9 +600,11 @@ pub struct OrderUpdateEvent {
     pub cumulative_filled_quantity: Fixed,
     pub last_executed_price: Fixed,
     pub commission_amount: Fixed,
-    pub commission_asset: String,
+    /// `None` until the order trades
+    pub commission_asset: Option<String>,
     pub transaction_time: u64,
-    pub trade_id: u64,
+    /// `None` for events without a trade
+    pub trade_id: Option<u64>,
     pub is_order_on_book: bool,
     pub is_trade_maker_side: bool,
     pub order_creation_time: u64,
@@ -303,7 +614,8 @@ pub struct OrderUpdateEvent {
 }
 
 /// Trade side
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
 pub enum TradeSide {
     Buy,
     Sell,
@@ -320,4 +632,47 @@ mod tests {
         assert_eq!(client.base_url, "wss://stream.testnet.binance.vision");
         assert!(!client.is_connected());
     }
-}
\ No newline at end of file
+
+    /// Payloads as captured from the spot user data stream
+    const EXECUTION_REPORT: &str = r#"{"e":"executionReport","E":1499405658658,"s":"ETHBTC","c":"mUvoqJxFIILMdfAW5iGSOW","S":"BUY","o":"LIMIT","f":"GTC","q":"1.00000000","p":"0.10264410","P":"0.00000000","F":"0.00000000","g":-1,"C":"","x":"NEW","X":"NEW","r":"NONE","i":4293153,"l":"0.00000000","z":"0.00000000","L":"0.00000000","n":"0","N":null,"T":1499405658657,"t":-1,"I":8641984,"w":true,"m":false,"M":false,"O":1499405658657,"Z":"0.00000000","Y":"0.00000000","Q":"0.00000000","W":1499405658657,"V":"NONE"}"#;
+    const ACCOUNT_POSITION: &str = r#"{"e":"outboundAccountPosition","E":1564034571105,"u":1564034571073,"B":[{"a":"ETH","f":"10000.000000","l":"0.000000"},{"a":"BTC","f":"0.51820000","l":"0.10000000"}]}"#;
+    const BALANCE_UPDATE: &str = r#"{"e":"balanceUpdate","E":1573200697110,"a":"BTC","d":"100.00000000","T":1573200697068}"#;
+
+    #[test]
+    fn test_parses_captured_user_events() {
+        let client = BinanceUserStreamClient::new(BinanceConfig::testnet());
+
+        let UserDataEvent::OrderUpdate(order) = client.process_message(EXECUTION_REPORT).unwrap() else { panic!("expected order update") };
+        assert_eq!(order.symbol, "ETHBTC");
+        assert!(matches!(order.side, TradeSide::Buy));
+        assert_eq!(order.order_price, Fixed::from_str_exact("0.1026441").unwrap());
+        assert_eq!(order.order_id, 4293153);
+        assert_eq!(order.order_list_id, -1);
+        assert_eq!(order.trade_id, None);
+        assert_eq!(order.commission_asset, None);
+
+        let UserDataEvent::AccountUpdate(account) = client.process_message(ACCOUNT_POSITION).unwrap() else { panic!("expected account update") };
+        assert_eq!(account.balances.len(), 2);
+        assert_eq!(account.balances[1].locked, Fixed::from_str_exact("0.1").unwrap());
+
+        let UserDataEvent::BalanceUpdate(balance) = client.process_message(BALANCE_UPDATE).unwrap() else { panic!("expected balance update") };
+        assert_eq!(balance.balance_delta, Fixed::from_i64(100).unwrap());
+        assert_eq!(balance.clear_time, 1573200697068);
+    }
+
+    #[test]
+    fn test_missing_or_malformed_fields_are_errors() {
+        let client = BinanceUserStreamClient::new(BinanceConfig::testnet());
+
+        let missing = EXECUTION_REPORT.replace(r#""z":"0.00000000","#, "");
+        let error = client.process_message(&missing).unwrap_err().to_string();
+        assert!(error.contains("missing field `z`"), "{error}");
+
+        let malformed = BALANCE_UPDATE.replace("100.00000000", "1e2");
+        assert!(matches!(client.process_message(&malformed), Err(ExchangeError::InvalidResponse(_))));
+        assert!(matches!(client.process_message(r#"{"e":"listStatus"}"#), Err(ExchangeError::UnsupportedStream(_))));
+        // Surfaced so the manager renews the key instead of waiting forever
+        let expired = r#"{"e":"listenKeyExpired","E":1576653824250,"listenKey":"OfYGbUzi3PraNagEkdKuFwUHn48brFsItTdsuiIXrucEvD0rhRXZ7I6URWfE8YE8"}"#;
+        assert!(matches!(client.process_message(expired), Err(ExchangeError::ConnectionFailed(_))));
+    }
+}
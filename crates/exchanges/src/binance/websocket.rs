@@ -6,34 +6,106 @@
 //! - Efficient WebSocket handling
 //! - Real-time market data streaming
 
+use crate::cassette::CassetteHandle;
+use crate::chaos::FaultInjector;
 use crate::errors::{ExchangeError, Result};
-use crate::websocket::MonoioWebSocket;
+use crate::websocket::{MonoioWebSocket, WebSocketOptions};
 use sriquant_core::prelude::*;
+use sriquant_core::fixed::FixedError;
 use sriquant_core::timing::nanos;
+use sriquant_core::channel::Receiver;
 use super::rest::BinanceConfig;
+use super::subscriptions::{EventFilter, SubscriptionManager};
+use super::stream_stats::{StreamStats, StreamStatsRegistry};
+use super::connection::ReconnectConfig;
 
-use std::collections::HashMap;
-use tracing::{info, debug};
+pub use crate::types::{
+    AggTradeUpdate, BookTickerUpdate, DepthKind, DepthUpdate, ExchangeId, KlineUpdate, MarketDataEvent, MiniTickerUpdate, OrderBookLevel,
+    ReconnectInfo, TickerUpdate, TradeSide, TradeUpdate,
+};
+
+use tracing::{info, debug, warn, error};
 use serde_json::Value;
+use std::time::Duration;
 use url::Url;
 
+/// How often Binance pushes a depth stream
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DepthSpeed {
+    #[default]
+    Ms100,
+    Ms1000,
+}
+
+impl DepthSpeed {
+    /// Stream name suffix; 1000ms is the unsuffixed default
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            DepthSpeed::Ms100 => "@100ms",
+            DepthSpeed::Ms1000 => "",
+        }
+    }
+}
+
+/// Which depth stream to subscribe to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepthStream {
+    /// Top 5, 10 or 20 levels, each event a snapshot (`@depth<levels>`)
+    Partial(u32),
+    /// Changed levels for a local book (`@depth`)
+    Diff,
+}
+
+impl DepthStream {
+    pub fn kind(&self) -> DepthKind {
+        match self {
+            DepthStream::Partial(_) => DepthKind::Snapshot,
+            DepthStream::Diff => DepthKind::Diff,
+        }
+    }
+
+    /// Stream name for `symbol`, e.g. `btcusdt@depth10@100ms`
+    pub fn stream_name(&self, symbol: &str, speed: DepthSpeed) -> Result<String> {
+        let symbol = symbol.to_lowercase();
+        match self {
+            DepthStream::Partial(levels @ (5 | 10 | 20)) => Ok(format!("{symbol}@depth{levels}{}", speed.suffix())),
+            DepthStream::Partial(levels) => Err(ExchangeError::ConfigurationError(format!(
+                "partial depth supports 5, 10 or 20 levels, not {levels}"
+            ))),
+            DepthStream::Diff => Ok(format!("{symbol}@depth{}", speed.suffix())),
+        }
+    }
+}
+
+/// Streams per SUBSCRIBE request when several are sent at once
+const MAX_STREAMS_PER_REQUEST: usize = 200;
+
 /// High-performance Binance WebSocket client using monoio
 pub struct BinanceWebSocketClient {
     #[allow(dead_code)] // Stored for future authenticated WebSocket operations
     config: BinanceConfig,
     base_url: String,
-    subscriptions: HashMap<String, bool>,
+    subscriptions: SubscriptionManager,
+    stats: StreamStatsRegistry,
     websocket: Option<MonoioWebSocket>,
+    cassette: Option<CassetteHandle>,
+    faults: Option<FaultInjector>,
+    /// Reconnect when a receive fails; `None` surfaces the error instead
+    reconnect: Option<ReconnectConfig>,
+    /// Where the last connection went, for reconnecting
+    connect_url: Option<Url>,
+    /// Connected straight to one stream, so there is nothing to resubscribe
+    single_stream: bool,
+    /// Hold new subscriptions until `flush_subscriptions`
+    batch_subscriptions: bool,
+    /// Streams acquired but not yet sent in a SUBSCRIBE
+    pending_subscriptions: Vec<String>,
 }
 
 impl BinanceWebSocketClient {
     /// Create a new Binance WebSocket client
     pub fn new(config: BinanceConfig) -> Self {
-        let base_url = if config.testnet {
-            "wss://stream.testnet.binance.vision".to_string()
-        } else {
-            "wss://stream.binance.com:9443".to_string()
-        };
+        let base_url = config.ws_url.trim_end_matches('/').to_string();
         
         info!("🔗 Binance WebSocket client created");
         info!("   Base URL: {}", base_url);
@@ -41,11 +113,62 @@ impl BinanceWebSocketClient {
         Self {
             config,
             base_url,
-            subscriptions: HashMap::new(),
+            subscriptions: SubscriptionManager::new(),
+            stats: StreamStatsRegistry::new(),
             websocket: None,
+            cassette: None,
+            faults: None,
+            reconnect: None,
+            connect_url: None,
+            single_stream: false,
+            batch_subscriptions: false,
+            pending_subscriptions: Vec::new(),
         }
     }
     
+    /// Reconnect with backoff when the socket drops, replaying subscriptions
+    /// 
+    /// After a successful reconnect `receive_message` returns
+    /// `MarketDataEvent::Reconnected`; consumers holding books should resync.
+    pub fn with_reconnect(mut self, config: ReconnectConfig) -> Self {
+        self.reconnect = Some(config);
+        self
+    }
+    
+    /// Record sessions into, or replay them from, a cassette
+    pub fn with_cassette(mut self, cassette: CassetteHandle) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+    
+    /// Coalesce subscriptions made in quick succession
+    ///
+    /// `subscribe_*` calls only record the stream; the batch goes out as one
+    /// SUBSCRIBE per 200 streams, in a single write, on `flush_subscriptions`
+    /// or before the next `receive_message`. Binance accepts 5 messages per
+    /// second per connection, so one message per stream makes starting a
+    /// large universe slow.
+    pub fn with_subscription_batching(mut self, enabled: bool) -> Self {
+        self.batch_subscriptions = enabled;
+        self
+    }
+    
+    /// Inject network faults into every connection this client opens
+    pub fn with_faults(mut self, faults: FaultInjector) -> Self {
+        self.faults = Some(faults);
+        self
+    }
+    
+    /// Open a WebSocket to `url` with the client's cassette and faults
+    async fn open(&self, url: Url) -> Result<MonoioWebSocket> {
+        let options = WebSocketOptions { compression: self.config.ws_compression, timeout: self.config.request_timeout() };
+        let websocket = MonoioWebSocket::connect_with_options(url, self.cassette.as_ref(), options).await?;
+        Ok(match &self.faults {
+            Some(faults) => websocket.with_faults(faults.clone()),
+            None => websocket,
+        })
+    }
+    
     /// Connect to WebSocket stream (multi-stream endpoint)
     pub async fn connect(&mut self) -> Result<()> {
         let timer = PerfTimer::start("binance_ws_connect".to_string());
@@ -58,8 +181,10 @@ impl BinanceWebSocketClient {
         info!("🔗 Connecting to Binance WebSocket: {}", url);
         
         // Establish WebSocket connection
-        let websocket = MonoioWebSocket::connect(url).await?;
+        let websocket = self.open(url.clone()).await?;
         self.websocket = Some(websocket);
+        self.connect_url = Some(url);
+        self.single_stream = false;
         
         timer.log_elapsed();
         info!("✅ Connected to Binance WebSocket successfully");
@@ -79,11 +204,13 @@ impl BinanceWebSocketClient {
         info!("🔗 Connecting to single Binance WebSocket stream: {}", url);
         
         // Establish WebSocket connection
-        let websocket = MonoioWebSocket::connect(url).await?;
+        let websocket = self.open(url.clone()).await?;
         self.websocket = Some(websocket);
+        self.connect_url = Some(url);
+        self.single_stream = true;
         
         // Mark this stream as subscribed (no subscription message needed)
-        self.subscriptions.insert(stream.to_string(), true);
+        self.subscriptions.acquire(stream);
         
         timer.log_elapsed();
         info!("✅ Connected to single stream: {}", stream);
@@ -98,10 +225,12 @@ impl BinanceWebSocketClient {
         
         info!("📊 Subscribing to {} streams...", streams.len());
         
-        // Subscribe to each stream
         for stream in streams {
-            self.subscribe_stream(stream).await?;
+            if self.subscriptions.acquire(stream).is_some() {
+                self.pending_subscriptions.push(stream.to_string());
+            }
         }
+        self.flush_subscriptions().await?;
         
         info!("✅ All {} streams subscribed successfully", self.subscriptions.len());
         Ok(())
@@ -114,13 +243,17 @@ impl BinanceWebSocketClient {
         self.subscribe_stream(&stream_name).await
     }
     
-    /// Subscribe to order book updates for a symbol
+    /// Subscribe to order book updates for a symbol at 100ms
+    ///
+    /// `Some(levels)` selects the partial book stream, `None` the diff stream.
     pub async fn subscribe_depth(&mut self, symbol: &str, levels: Option<u32>) -> Result<()> {
-        let stream_name = if let Some(levels) = levels {
-            format!("{}@depth{}@100ms", symbol.to_lowercase(), levels)
-        } else {
-            format!("{}@depth@100ms", symbol.to_lowercase())
-        };
+        let stream = levels.map_or(DepthStream::Diff, DepthStream::Partial);
+        self.subscribe_depth_stream(symbol, stream, DepthSpeed::Ms100).await
+    }
+
+    /// Subscribe to a partial or diff depth stream at the given speed
+    pub async fn subscribe_depth_stream(&mut self, symbol: &str, stream: DepthStream, speed: DepthSpeed) -> Result<()> {
+        let stream_name = stream.stream_name(symbol, speed)?;
         self.subscribe_stream(&stream_name).await
     }
     
@@ -130,6 +263,44 @@ impl BinanceWebSocketClient {
         self.subscribe_stream(&stream_name).await
     }
     
+    /// Subscribe to aggregated trades for a symbol (`@aggTrade`)
+    ///
+    /// One event per taker order and price rather than per fill, so far fewer
+    /// messages than `subscribe_trades` for the same volume.
+    pub async fn subscribe_agg_trades(&mut self, symbol: &str) -> Result<()> {
+        let stream_name = format!("{}@aggTrade", symbol.to_lowercase());
+        self.subscribe_stream(&stream_name).await
+    }
+    
+    /// Subscribe to best bid/ask updates for a symbol (`@bookTicker`)
+    ///
+    /// Pushed on every top-of-book change, with no depth behind it: much
+    /// lighter than a depth stream when quoting only needs the touch.
+    pub async fn subscribe_book_ticker(&mut self, symbol: &str) -> Result<()> {
+        let stream_name = format!("{}@bookTicker", symbol.to_lowercase());
+        self.subscribe_stream(&stream_name).await
+    }
+    
+    /// Subscribe to mini tickers for all symbols (`!miniTicker@arr`, batched once per second)
+    pub async fn subscribe_all_mini_tickers(&mut self) -> Result<()> {
+        self.subscribe_stream("!miniTicker@arr").await
+    }
+    
+    /// Subscribe to best bid/ask updates for all symbols (`!bookTicker`)
+    pub async fn subscribe_all_book_tickers(&mut self) -> Result<()> {
+        self.subscribe_stream("!bookTicker").await
+    }
+    
+    /// Receive events for a single symbol, including its share of whole-market batches
+    pub fn subscribe_symbol_events(&mut self, symbol: &str) -> Receiver<MarketDataEvent> {
+        self.subscriptions.subscribe_symbol(symbol)
+    }
+    
+    /// Receive a symbol's events that pass `filter`, evaluated on the reader before delivery
+    pub fn subscribe_symbol_events_filtered(&mut self, symbol: &str, filter: EventFilter) -> Receiver<MarketDataEvent> {
+        self.subscriptions.subscribe_symbol_filtered(symbol, filter)
+    }
+    
     /// Subscribe to kline/candlestick updates
     pub async fn subscribe_klines(&mut self, symbol: &str, interval: &str) -> Result<()> {
         let stream_name = format!("{}@kline_{}", symbol.to_lowercase(), interval);
@@ -137,13 +308,24 @@ impl BinanceWebSocketClient {
     }
     
     /// Generic stream subscription
-    async fn subscribe_stream(&mut self, stream: &str) -> Result<()> {
+    /// 
+    /// Subscriptions are reference-counted: subscribing to an active stream only
+    /// adds a reference, and each call should be paired with `release_stream`.
+    pub async fn subscribe_stream(&mut self, stream: &str) -> Result<()> {
         if self.websocket.is_none() {
             return Err(ExchangeError::NetworkError("WebSocket not connected".to_string()));
         }
 
-        // Generate unique ID for this subscription
-        let sub_id = self.subscriptions.len() + 1;
+        // Generate unique ID for this subscription, or share the existing one
+        let Some(sub_id) = self.subscriptions.acquire(stream) else {
+            debug!("📊 Stream {} already active ({} holders)", stream, self.subscriptions.ref_count(stream));
+            return Ok(());
+        };
+        if self.batch_subscriptions {
+            self.pending_subscriptions.push(stream.to_string());
+            debug!("📊 Queued subscription to {} ({} pending)", stream, self.pending_subscriptions.len());
+            return Ok(());
+        }
 
         // Create subscription message
         let subscription_msg = serde_json::json!({
@@ -155,31 +337,89 @@ impl BinanceWebSocketClient {
         info!("📨 Sending subscription message: {}", subscription_msg);
 
         // Send subscription message
-        if let Some(ref mut ws) = self.websocket {
-            ws.send_text(subscription_msg.to_string()).await?;
+        if let Some(ref mut ws) = self.websocket
+            && let Err(e) = ws.send_text(subscription_msg.to_string()).await
+        {
+            self.subscriptions.remove_stream(stream);
+            return Err(e);
         }
 
-        self.subscriptions.insert(stream.to_string(), true);
         info!("📊 Subscribed to stream: {}", stream);
         Ok(())
     }
     
+    /// Send queued subscriptions; returns how many streams were subscribed
+    pub async fn flush_subscriptions(&mut self) -> Result<usize> {
+        if self.pending_subscriptions.is_empty() {
+            return Ok(0);
+        }
+        let streams = std::mem::take(&mut self.pending_subscriptions);
+        let requests = subscribe_requests("SUBSCRIBE", &streams, || self.subscriptions.next_request_id());
+        let sent = match self.websocket.as_mut() {
+            Some(ws) => ws.send_texts(requests.iter().cloned()).await,
+            None => Err(ExchangeError::NetworkError("WebSocket not connected".to_string())),
+        };
+        if let Err(e) = sent {
+            for stream in &streams {
+                self.subscriptions.remove_stream(stream);
+            }
+            return Err(e);
+        }
+        info!("📊 Subscribed to {} streams in {} requests", streams.len(), requests.len());
+        Ok(streams.len())
+    }
+    
+    /// Streams waiting for `flush_subscriptions`
+    pub fn pending_subscriptions(&self) -> &[String] {
+        &self.pending_subscriptions
+    }
+    
     /// Receive and process next WebSocket message
     pub async fn receive_message(&mut self) -> Result<MarketDataEvent> {
+        self.flush_subscriptions().await?;
         loop {
-            let message = if let Some(ref mut ws) = self.websocket {
+            let received = if let Some(ref mut ws) = self.websocket {
                 let timer = PerfTimer::start("binance_ws_receive".to_string());
-                let msg = ws.receive_text().await?;
+                let received = ws.receive_text().await;
                 timer.log_elapsed();
-                msg
+                received
             } else {
                 return Err(ExchangeError::NetworkError("WebSocket not connected".to_string()));
             };
+            let message = match received {
+                Ok(message) => message,
+                Err(e) if self.reconnect.is_some() && self.connect_url.is_some() => return self.reconnect_after(e).await,
+                Err(e) => return Err(e),
+            };
             
             debug!("Received WebSocket message: {}", message);
             
-            match self.process_message_content(&message) {
-                Ok(event) => return Ok(event),
+            let result = match serde_json::from_str::<Value>(&message) {
+                Ok(json) => {
+                    let stream = self.stream_key(&json);
+                    let result = self.process_json(&json, &message);
+                    let parse_error = match &result {
+                        Ok(_) => false,
+                        Err(e) => match e.root() {
+                            ExchangeError::SerializationError(_) | ExchangeError::Json(_) | ExchangeError::UnsupportedStream(_) => true,
+                            ExchangeError::InvalidResponse(msg) => !msg.contains("Subscription confirmation"),
+                            _ => false,
+                        },
+                    };
+                    self.stats.record(stream.as_deref(), message.len(), parse_error, nanos());
+                    result
+                }
+                Err(e) => {
+                    self.stats.record(None, message.len(), true, nanos());
+                    Err(ExchangeError::from(e).with_payload(&message))
+                }
+            };
+            
+            match result {
+                Ok(event) => {
+                    self.subscriptions.fan_out(&event);
+                    return Ok(event);
+                }
                 Err(ExchangeError::InvalidResponse(msg)) if msg.contains("Subscription confirmation") => {
                     // Skip subscription confirmations and continue reading
                     continue;
@@ -189,22 +429,84 @@ impl BinanceWebSocketClient {
         }
     }
 
-    /// Process incoming WebSocket message content
-    fn process_message_content(&self, message: &str) -> Result<MarketDataEvent> {
-        let timer = PerfTimer::start("binance_ws_process".to_string());
+    /// Reconnect with backoff after `cause` and replay the subscription list
+    async fn reconnect_after(&mut self, cause: ExchangeError) -> Result<MarketDataEvent> {
+        let config = self.reconnect.clone().unwrap_or_default();
+        warn!("🔌 Binance WebSocket dropped: {}", cause);
+        self.websocket = None;
+        let dropped_at = nanos();
+        
+        let mut last_error = cause;
+        for attempt in 1..=config.max_attempts {
+            let delay = config.backoff_delay_ms(attempt);
+            warn!("🔄 Reconnecting in {}ms (attempt {}/{})", delay, attempt, config.max_attempts);
+            monoio::time::sleep(Duration::from_millis(delay)).await;
+            
+            match self.resume().await {
+                Ok(streams) => {
+                    let downtime_ms = (nanos() - dropped_at) / 1_000_000;
+                    info!("✅ Reconnected after {} attempts, {} streams resubscribed", attempt, streams.len());
+                    return Ok(MarketDataEvent::Reconnected(ReconnectInfo { exchange: ExchangeId::Binance, attempts: attempt, streams, downtime_ms }));
+                }
+                Err(e) => {
+                    warn!("⚠️  Reconnect attempt {} failed: {}", attempt, e);
+                    self.websocket = None;
+                    last_error = e;
+                }
+            }
+        }
         
+        error!("❌ Max reconnection attempts reached");
+        Err(last_error)
+    }
+    
+    /// Open the last connection again and resubscribe every stream in one request
+    async fn resume(&mut self) -> Result<Vec<String>> {
+        let url = self.connect_url.clone()
+            .ok_or_else(|| ExchangeError::NetworkError("WebSocket was never connected".to_string()))?;
+        let mut websocket = self.open(url).await?;
+        
+        let mut streams = self.subscriptions.streams();
+        streams.sort_unstable();
+        if !self.single_stream && !streams.is_empty() {
+            let requests = subscribe_requests("SUBSCRIBE", &streams, || self.subscriptions.next_request_id());
+            websocket.send_texts(requests).await?;
+        }
+        // Queued streams were part of the replayed list
+        self.pending_subscriptions.clear();
+        self.websocket = Some(websocket);
+        Ok(streams)
+    }
+
+    /// Parse a raw WebSocket message without touching statistics or fan-out
+    /// 
+    /// Useful for replaying recorded messages.
+    pub fn process_message_content(&self, message: &str) -> Result<MarketDataEvent> {
         let json: Value = serde_json::from_str(message)
-            .map_err(|e| ExchangeError::SerializationError(e.to_string()))?;
+            .map_err(|e| ExchangeError::from(e).with_payload(message))?;
         
-        let event = if let Some(stream) = json["stream"].as_str() {
+        self.process_json(&json, message)
+    }
+    
+    /// Process an already-parsed WebSocket message
+    fn process_json(&self, json: &Value, message: &str) -> Result<MarketDataEvent> {
+        let timer = PerfTimer::start("binance_ws_process".to_string());
+        
+        let event = if let Some(items) = json.as_array() {
+            // Whole-market array on a raw stream: [{"e":"24hrMiniTicker",...},...]
+            self.parse_array_data(items)?
+        } else if let Some(stream) = json["stream"].as_str() {
             // Combined stream format: {"stream":"btcusdt@ticker","data":{...}}
             self.parse_stream_data(stream, &json["data"])?
         } else if let Some(event_type) = json["e"].as_str() {
             // Single stream format: {"e":"24hrTicker","s":"BTCUSDT",...}
-            self.parse_single_stream_data(event_type, &json)?
+            self.parse_single_stream_data(event_type, json)?
+        } else if json["u"].is_number() && json["b"].is_string() && json["a"].is_string() {
+            // Book ticker has no event type: {"u":400900217,"s":"BNBUSDT","b":"25.35",...}
+            self.parse_book_ticker_data(json)?
         } else if json["lastUpdateId"].is_number() && (json["bids"].is_array() || json["asks"].is_array()) {
             // Order book snapshot format: {"lastUpdateId":123,"bids":[...],"asks":[...]}
-            self.parse_order_book_snapshot(&json)?
+            self.parse_order_book_snapshot(json, None)?
         } else if let Some(_result) = json["result"].as_null() {
             // Handle subscription confirmation messages ({"result":null,"id":1})
             if let Some(id) = json["id"].as_u64() {
@@ -223,14 +525,86 @@ impl BinanceWebSocketClient {
         Ok(event)
     }
     
+    /// Work out which subscribed stream a message belongs to, for statistics
+    fn stream_key(&self, json: &Value) -> Option<String> {
+        // Combined streams name themselves
+        if let Some(stream) = json["stream"].as_str() {
+            return Some(stream.to_string());
+        }
+        
+        // Whole-market arrays on a raw connection
+        if let Some(items) = json.as_array() {
+            return match items.first() {
+                Some(first) if first["e"].as_str() == Some("24hrMiniTicker") => Some("!miniTicker@arr".to_string()),
+                Some(first) if first["u"].is_number() => Some("!bookTicker".to_string()),
+                _ => None,
+            };
+        }
+        
+        // A single-stream connection carries exactly one stream
+        let streams = self.subscriptions.streams();
+        if streams.len() == 1 && json["result"].is_null() && json["id"].is_null() {
+            return streams.into_iter().next();
+        }
+        
+        let symbol = json["s"].as_str()?.to_lowercase();
+        let prefix = match json["e"].as_str() {
+            Some("24hrTicker") => format!("{symbol}@ticker"),
+            Some("24hrMiniTicker") => format!("{symbol}@miniTicker"),
+            Some("depthUpdate") => format!("{symbol}@depth"),
+            Some("trade") => format!("{symbol}@trade"),
+            Some("aggTrade") => format!("{symbol}@aggTrade"),
+            Some("kline") => format!("{symbol}@kline_{}", json["k"]["i"].as_str().unwrap_or("")),
+            None if json["u"].is_number() => {
+                if self.subscriptions.contains("!bookTicker") {
+                    return Some("!bookTicker".to_string());
+                }
+                format!("{symbol}@bookTicker")
+            }
+            _ => return None,
+        };
+        
+        streams
+            .into_iter()
+            .find(|s| s.starts_with(&prefix))
+            .or(Some(prefix))
+    }
+    
+    /// Message statistics for the whole connection
+    pub fn connection_stats(&self) -> &StreamStats {
+        self.stats.connection()
+    }
+    
+    /// Message statistics for one stream
+    pub fn stream_stats(&self, stream: &str) -> Option<&StreamStats> {
+        self.stats.stream(stream)
+    }
+    
+    /// Message statistics for every stream seen on this connection
+    pub fn all_stream_stats(&self) -> Vec<(String, StreamStats)> {
+        self.stats.streams()
+    }
+    
     /// Parse stream data based on stream type
     fn parse_stream_data(&self, stream: &str, data: &Value) -> Result<MarketDataEvent> {
-        if stream.contains("@ticker") {
+        if let Some(items) = data.as_array() {
+            self.parse_array_data(items)
+        } else if stream.contains("miniTicker") {
+            Ok(MarketDataEvent::MiniTicker(parse_mini_ticker(data)?))
+        } else if stream.contains("bookTicker") {
+            self.parse_book_ticker_data(data)
+        } else if stream.contains("@ticker") {
             self.parse_ticker_data(data)
+        } else if stream.contains("@depth") && data["lastUpdateId"].is_number() {
+            // Partial book streams carry no symbol; take it from the stream name
+            let symbol = stream.split('@').next().unwrap_or("").to_uppercase();
+            self.parse_order_book_snapshot(data, Some(&symbol))
         } else if stream.contains("@depth") {
             self.parse_depth_data(data)
         } else if stream.contains("@trade") {
             self.parse_trade_data(data)
+        } else if stream.contains("@aggTrade") {
+            Ok(MarketDataEvent::AggTrade(parse_agg_trade(data)?))
         } else if stream.contains("@kline") {
             self.parse_kline_data(data)
         } else {
@@ -244,50 +618,76 @@ impl BinanceWebSocketClient {
             "24hrTicker" => self.parse_ticker_data(data),
             "depthUpdate" => self.parse_depth_data(data),
             "trade" => self.parse_trade_data(data),
+            "aggTrade" => Ok(MarketDataEvent::AggTrade(parse_agg_trade(data)?)),
             "kline" => self.parse_kline_data(data),
+            "24hrMiniTicker" => Ok(MarketDataEvent::MiniTicker(parse_mini_ticker(data)?)),
             _ => Err(ExchangeError::UnsupportedStream(format!("Unsupported event type: {}", event_type)))
         }
     }
 
-    /// Parse order book snapshot (initial depth data)
-    fn parse_order_book_snapshot(&self, data: &Value) -> Result<MarketDataEvent> {
-        let mut bids = Vec::new();
-        let mut asks = Vec::new();
-        
-        if let Some(bids_array) = data["bids"].as_array() {
-            for bid in bids_array {
-                if let Some(bid_array) = bid.as_array() {
-                    if bid_array.len() >= 2 {
-                        let price = Fixed::from_str_exact(bid_array[0].as_str().unwrap_or("0"))
-                            .map_err(|_| ExchangeError::InvalidResponse("Invalid bid price".to_string()))?;
-                        let quantity = Fixed::from_str_exact(bid_array[1].as_str().unwrap_or("0"))
-                            .map_err(|_| ExchangeError::InvalidResponse("Invalid bid quantity".to_string()))?;
-                        bids.push(OrderBookLevel { price, quantity });
+    /// Parse a whole-market array into a batched event
+    /// 
+    /// Like every other payload, a malformed entry fails the whole batch
+    /// (quoting the entry) rather than silently dropping its symbol.
+    fn parse_array_data(&self, items: &[Value]) -> Result<MarketDataEvent> {
+        // A schema change fails the batch; one symbol's out-of-range value only drops that symbol
+        fn parse_all<T>(items: &[Value], parse: fn(&Value) -> Result<T>) -> Result<Vec<T>> {
+            let mut parsed = Vec::with_capacity(items.len());
+            for item in items {
+                match parse(item) {
+                    Ok(value) => parsed.push(value),
+                    Err(e @ ExchangeError::FixedPointError(_)) => {
+                        warn!("⚠️  Skipping {} in whole-market batch: {}", item["s"].as_str().unwrap_or("<unnamed>"), e);
                     }
+                    Err(e) => return Err(e.with_payload(&item.to_string())),
                 }
             }
+            Ok(parsed)
         }
-        
-        if let Some(asks_array) = data["asks"].as_array() {
-            for ask in asks_array {
-                if let Some(ask_array) = ask.as_array() {
-                    if ask_array.len() >= 2 {
-                        let price = Fixed::from_str_exact(ask_array[0].as_str().unwrap_or("0"))
-                            .map_err(|_| ExchangeError::InvalidResponse("Invalid ask price".to_string()))?;
-                        let quantity = Fixed::from_str_exact(ask_array[1].as_str().unwrap_or("0"))
-                            .map_err(|_| ExchangeError::InvalidResponse("Invalid ask quantity".to_string()))?;
-                        asks.push(OrderBookLevel { price, quantity });
-                    }
-                }
+        match items.first().and_then(|v| v["e"].as_str()) {
+            Some("24hrMiniTicker") => Ok(MarketDataEvent::MiniTickerBatch(parse_all(items, parse_mini_ticker)?)),
+            None if items.first().is_some_and(|v| v["u"].is_number()) => {
+                Ok(MarketDataEvent::BookTickerBatch(parse_all(items, parse_book_ticker)?))
             }
+            None if items.is_empty() => Ok(MarketDataEvent::MiniTickerBatch(Vec::new())),
+            other => Err(ExchangeError::UnsupportedStream(format!("Unsupported array event: {other:?}"))),
         }
-        
+    }
+    
+    /// Parse book ticker data
+    fn parse_book_ticker_data(&self, data: &Value) -> Result<MarketDataEvent> {
+        Ok(MarketDataEvent::BookTicker(parse_book_ticker(data)?))
+    }
+
+    /// Parse a partial book snapshot
+    ///
+    /// Without a stream name the symbol is taken from the one subscribed
+    /// partial depth stream, if there is exactly one.
+    fn parse_order_book_snapshot(&self, data: &Value, symbol: Option<&str>) -> Result<MarketDataEvent> {
+        let symbol = match symbol {
+            Some(symbol) => symbol.to_string(),
+            None => {
+                let partial: Vec<String> = self.subscriptions.streams().into_iter()
+                    .filter(|s| s.split('@').nth(1).is_some_and(|d| d.len() > "depth".len() && d.starts_with("depth")))
+                    .collect();
+                match partial.as_slice() {
+                    [stream] => stream.split('@').next().unwrap_or("").to_uppercase(),
+                    _ => return Err(ExchangeError::InvalidResponse(format!(
+                        "partial book without a stream name matches {} depth streams", partial.len()
+                    ))),
+                }
+            }
+        };
+        let update_id = u64_field(data, "lastUpdateId")?;
         let depth = DepthUpdate {
-            symbol: "BTCUSDT".to_string(), // For depth snapshots, we know this is BTCUSDT from our subscription
-            bids,
-            asks,
+            exchange: ExchangeId::Binance,
+            symbol,
+            bids: parse_levels(data, "bids")?,
+            asks: parse_levels(data, "asks")?,
             timestamp: nanos() / 1_000_000, // Current timestamp in milliseconds
-            update_id: data["lastUpdateId"].as_u64().unwrap_or(0),
+            first_update_id: update_id,
+            update_id,
+            kind: DepthKind::Snapshot,
         };
         
         Ok(MarketDataEvent::Depth(depth))
@@ -296,14 +696,12 @@ impl BinanceWebSocketClient {
     /// Parse ticker data
     fn parse_ticker_data(&self, data: &Value) -> Result<MarketDataEvent> {
         let ticker = TickerUpdate {
-            symbol: data["s"].as_str().unwrap_or("").to_string(),
-            price: Fixed::from_str_exact(data["c"].as_str().unwrap_or("0"))
-                .map_err(|_| ExchangeError::InvalidResponse("Invalid price".to_string()))?,
-            price_change: Fixed::from_str_exact(data["P"].as_str().unwrap_or("0"))
-                .map_err(|_| ExchangeError::InvalidResponse("Invalid price change".to_string()))?,
-            volume: Fixed::from_str_exact(data["v"].as_str().unwrap_or("0"))
-                .map_err(|_| ExchangeError::InvalidResponse("Invalid volume".to_string()))?,
-            timestamp: data["E"].as_u64().unwrap_or(0),
+            exchange: ExchangeId::Binance,
+            symbol: str_field(data, "s")?.to_string(),
+            price: fixed_field(data, "c", "price")?,
+            price_change: fixed_field(data, "P", "price change")?,
+            volume: fixed_field(data, "v", "volume")?,
+            timestamp: u64_field(data, "E")?,
         };
         
         Ok(MarketDataEvent::Ticker(ticker))
@@ -311,43 +709,15 @@ impl BinanceWebSocketClient {
     
     /// Parse depth/order book data
     fn parse_depth_data(&self, data: &Value) -> Result<MarketDataEvent> {
-        let mut bids = Vec::new();
-        let mut asks = Vec::new();
-        
-        if let Some(bids_array) = data["b"].as_array() {
-            for bid in bids_array {
-                if let Some(bid_array) = bid.as_array() {
-                    if bid_array.len() >= 2 {
-                        let price = Fixed::from_str_exact(bid_array[0].as_str().unwrap_or("0"))
-                            .map_err(|_| ExchangeError::InvalidResponse("Invalid bid price".to_string()))?;
-                        let quantity = Fixed::from_str_exact(bid_array[1].as_str().unwrap_or("0"))
-                            .map_err(|_| ExchangeError::InvalidResponse("Invalid bid quantity".to_string()))?;
-                        bids.push(OrderBookLevel { price, quantity });
-                    }
-                }
-            }
-        }
-        
-        if let Some(asks_array) = data["a"].as_array() {
-            for ask in asks_array {
-                if let Some(ask_array) = ask.as_array() {
-                    if ask_array.len() >= 2 {
-                        let price = Fixed::from_str_exact(ask_array[0].as_str().unwrap_or("0"))
-                            .map_err(|_| ExchangeError::InvalidResponse("Invalid ask price".to_string()))?;
-                        let quantity = Fixed::from_str_exact(ask_array[1].as_str().unwrap_or("0"))
-                            .map_err(|_| ExchangeError::InvalidResponse("Invalid ask quantity".to_string()))?;
-                        asks.push(OrderBookLevel { price, quantity });
-                    }
-                }
-            }
-        }
-        
         let depth = DepthUpdate {
-            symbol: data["s"].as_str().unwrap_or("").to_string(),
-            bids,
-            asks,
-            timestamp: data["E"].as_u64().unwrap_or(0),
-            update_id: data["u"].as_u64().unwrap_or(0),
+            exchange: ExchangeId::Binance,
+            symbol: str_field(data, "s")?.to_string(),
+            bids: parse_levels(data, "b")?,
+            asks: parse_levels(data, "a")?,
+            timestamp: u64_field(data, "E")?,
+            first_update_id: u64_field(data, "U")?,
+            update_id: u64_field(data, "u")?,
+            kind: DepthKind::Diff,
         };
         
         Ok(MarketDataEvent::Depth(depth))
@@ -356,14 +726,13 @@ impl BinanceWebSocketClient {
     /// Parse trade data
     fn parse_trade_data(&self, data: &Value) -> Result<MarketDataEvent> {
         let trade = TradeUpdate {
-            symbol: data["s"].as_str().unwrap_or("").to_string(),
-            price: Fixed::from_str_exact(data["p"].as_str().unwrap_or("0"))
-                .map_err(|_| ExchangeError::InvalidResponse("Invalid trade price".to_string()))?,
-            quantity: Fixed::from_str_exact(data["q"].as_str().unwrap_or("0"))
-                .map_err(|_| ExchangeError::InvalidResponse("Invalid trade quantity".to_string()))?,
-            side: if data["m"].as_bool().unwrap_or(false) { TradeSide::Sell } else { TradeSide::Buy },
-            timestamp: data["T"].as_u64().unwrap_or(0),
-            trade_id: data["t"].as_u64().unwrap_or(0),
+            exchange: ExchangeId::Binance,
+            symbol: str_field(data, "s")?.to_string(),
+            price: fixed_field(data, "p", "trade price")?,
+            quantity: fixed_field(data, "q", "trade quantity")?,
+            side: if bool_field(data, "m")? { TradeSide::Sell } else { TradeSide::Buy },
+            timestamp: u64_field(data, "T")?,
+            trade_id: u64_field(data, "t")?,
         };
         
         Ok(MarketDataEvent::Trade(trade))
@@ -374,21 +743,17 @@ impl BinanceWebSocketClient {
         let k = &data["k"];
         
         let kline = KlineUpdate {
-            symbol: k["s"].as_str().unwrap_or("").to_string(),
-            interval: k["i"].as_str().unwrap_or("").to_string(),
-            open_time: k["t"].as_u64().unwrap_or(0),
-            close_time: k["T"].as_u64().unwrap_or(0),
-            open: Fixed::from_str_exact(k["o"].as_str().unwrap_or("0"))
-                .map_err(|_| ExchangeError::InvalidResponse("Invalid open price".to_string()))?,
-            high: Fixed::from_str_exact(k["h"].as_str().unwrap_or("0"))
-                .map_err(|_| ExchangeError::InvalidResponse("Invalid high price".to_string()))?,
-            low: Fixed::from_str_exact(k["l"].as_str().unwrap_or("0"))
-                .map_err(|_| ExchangeError::InvalidResponse("Invalid low price".to_string()))?,
-            close: Fixed::from_str_exact(k["c"].as_str().unwrap_or("0"))
-                .map_err(|_| ExchangeError::InvalidResponse("Invalid close price".to_string()))?,
-            volume: Fixed::from_str_exact(k["v"].as_str().unwrap_or("0"))
-                .map_err(|_| ExchangeError::InvalidResponse("Invalid volume".to_string()))?,
-            is_closed: k["x"].as_bool().unwrap_or(false),
+            exchange: ExchangeId::Binance,
+            symbol: str_field(k, "s")?.to_string(),
+            interval: str_field(k, "i")?.to_string(),
+            open_time: u64_field(k, "t")?,
+            close_time: u64_field(k, "T")?,
+            open: fixed_field(k, "o", "open price")?,
+            high: fixed_field(k, "h", "high price")?,
+            low: fixed_field(k, "l", "low price")?,
+            close: fixed_field(k, "c", "close price")?,
+            volume: fixed_field(k, "v", "volume")?,
+            is_closed: bool_field(k, "x")?,
         };
         
         Ok(MarketDataEvent::Kline(kline))
@@ -396,23 +761,57 @@ impl BinanceWebSocketClient {
     
     /// Get active subscriptions
     pub fn get_subscriptions(&self) -> Vec<String> {
-        self.subscriptions.keys().cloned().collect()
+        self.subscriptions.streams()
+    }
+    
+    /// Release one reference to a stream, unsubscribing when no holders remain
+    /// 
+    /// Returns `true` if the stream was removed from the connection.
+    pub async fn release_stream(&mut self, stream: &str) -> Result<bool> {
+        if !self.subscriptions.release(stream) {
+            return Ok(false);
+        }
+        
+        if !self.drop_pending(stream) {
+            self.send_unsubscribe(stream).await?;
+        }
+        info!("❌ Last holder released stream: {}", stream);
+        Ok(true)
     }
     
-    /// Unsubscribe from a stream
+    /// Number of holders currently sharing a stream
+    pub fn stream_ref_count(&self, stream: &str) -> u32 {
+        self.subscriptions.ref_count(stream)
+    }
+    
+    /// Unsubscribe from a stream regardless of how many holders share it
     pub async fn unsubscribe(&mut self, stream: &str) -> Result<()> {
+        if !self.drop_pending(stream) {
+            self.send_unsubscribe(stream).await?;
+        }
+        self.subscriptions.remove_stream(stream);
+        info!("❌ Unsubscribed from stream: {}", stream);
+        Ok(())
+    }
+    
+    /// Forget a stream that was never sent; true if it was pending
+    fn drop_pending(&mut self, stream: &str) -> bool {
+        let before = self.pending_subscriptions.len();
+        self.pending_subscriptions.retain(|pending| pending != stream);
+        self.pending_subscriptions.len() != before
+    }
+    
+    /// Send an UNSUBSCRIBE request for a stream
+    async fn send_unsubscribe(&mut self, stream: &str) -> Result<()> {
         if let Some(ref mut ws) = self.websocket {
             let unsubscription_msg = serde_json::json!({
                 "method": "UNSUBSCRIBE",
                 "params": [stream],
-                "id": 2
+                "id": self.subscriptions.next_request_id()
             });
             
             ws.send_text(unsubscription_msg.to_string()).await?;
         }
-        
-        self.subscriptions.remove(stream);
-        info!("❌ Unsubscribed from stream: {}", stream);
         Ok(())
     }
     
@@ -422,7 +821,8 @@ impl BinanceWebSocketClient {
             info!("🔌 Closing Binance WebSocket connection");
             ws.close(1000, "Normal closure".to_string()).await?;
         }
-        self.subscriptions.clear();
+        self.pending_subscriptions.clear();
+        self.subscriptions.clear_streams();
         Ok(())
     }
     
@@ -441,73 +841,140 @@ impl BinanceWebSocketClient {
     }
 }
 
-/// Market data events from WebSocket
-#[derive(Debug, Clone)]
-pub enum MarketDataEvent {
-    Ticker(TickerUpdate),
-    Depth(DepthUpdate),
-    Trade(TradeUpdate),
-    Kline(KlineUpdate),
+/// SUBSCRIBE/UNSUBSCRIBE request bodies for `streams`, each with up to
+/// `MAX_STREAMS_PER_REQUEST` params and an id from `next_id`
+fn subscribe_requests(method: &str, streams: &[String], mut next_id: impl FnMut() -> u64) -> Vec<String> {
+    streams
+        .chunks(MAX_STREAMS_PER_REQUEST)
+        .map(|chunk| serde_json::json!({ "method": method, "params": chunk, "id": next_id() }).to_string())
+        .collect()
 }
 
-/// Ticker update data
-#[derive(Debug, Clone)]
-pub struct TickerUpdate {
-    pub symbol: String,
-    pub price: Fixed,
-    pub price_change: Fixed,
-    pub volume: Fixed,
-    pub timestamp: u64,
+/// A required string field
+///
+/// Missing fields are errors rather than `""`/`"0"` defaults: a price that
+/// silently reads as zero is worse than a dropped message.
+fn str_field<'a>(data: &'a Value, key: &str) -> Result<&'a str> {
+    match &data[key] {
+        Value::String(value) => Ok(value),
+        Value::Null => Err(ExchangeError::MissingField(key.to_string())),
+        other => Err(ExchangeError::InvalidResponse(format!("Invalid field {key}: {other}"))),
+    }
 }
 
-/// Depth/order book update data
-#[derive(Debug, Clone)]
-pub struct DepthUpdate {
-    pub symbol: String,
-    pub bids: Vec<OrderBookLevel>,
-    pub asks: Vec<OrderBookLevel>,
-    pub timestamp: u64,
-    pub update_id: u64,
+fn u64_field(data: &Value, key: &str) -> Result<u64> {
+    match &data[key] {
+        Value::Null => Err(ExchangeError::MissingField(key.to_string())),
+        other => other.as_u64().ok_or_else(|| ExchangeError::InvalidResponse(format!("Invalid field {key}: {other}"))),
+    }
 }
 
-/// Trade update data
-#[derive(Debug, Clone)]
-pub struct TradeUpdate {
-    pub symbol: String,
-    pub price: Fixed,
-    pub quantity: Fixed,
-    pub side: TradeSide,
-    pub timestamp: u64,
-    pub trade_id: u64,
+fn bool_field(data: &Value, key: &str) -> Result<bool> {
+    match &data[key] {
+        Value::Null => Err(ExchangeError::MissingField(key.to_string())),
+        other => other.as_bool().ok_or_else(|| ExchangeError::InvalidResponse(format!("Invalid field {key}: {other}"))),
+    }
 }
 
-/// Kline/candlestick update data
-#[derive(Debug, Clone)]
-pub struct KlineUpdate {
-    pub symbol: String,
-    pub interval: String,
-    pub open_time: u64,
-    pub close_time: u64,
-    pub open: Fixed,
-    pub high: Fixed,
-    pub low: Fixed,
-    pub close: Fixed,
-    pub volume: Fixed,
-    pub is_closed: bool,
+/// A required decimal string field, `what` naming it in the error
+fn fixed_field(data: &Value, key: &str, what: &str) -> Result<Fixed> {
+    let value = str_field(data, key)?;
+    Fixed::from_str_exact(value).map_err(|e| match e {
+        FixedError::OutOfRange => ExchangeError::FixedPointError(format!("{what} out of range: {value}")),
+        _ => ExchangeError::InvalidResponse(format!("Invalid {what}: {value}")),
+    })
 }
 
-/// Order book level
-#[derive(Debug, Clone)]
-pub struct OrderBookLevel {
-    pub price: Fixed,
-    pub quantity: Fixed,
+/// A required quantity or volume field, kept exact beyond the `Fixed` range (see `Fixed::from_str_unbounded`)
+fn unbounded_field(data: &Value, key: &str, what: &str) -> Result<Fixed> {
+    let value = str_field(data, key)?;
+    Fixed::from_str_unbounded(value).map_err(|_| ExchangeError::InvalidResponse(format!("Invalid {what}: {value}")))
 }
 
-/// Trade side
-#[derive(Debug, Clone)]
-pub enum TradeSide {
-    Buy,
-    Sell,
+/// Parse `[price, quantity]` pairs; a malformed level fails the whole update
+/// rather than leaving a gap in the book
+fn parse_levels(data: &Value, key: &str) -> Result<Vec<OrderBookLevel>> {
+    let levels = match &data[key] {
+        Value::Array(levels) => levels,
+        Value::Null => return Err(ExchangeError::MissingField(key.to_string())),
+        other => return Err(ExchangeError::InvalidResponse(format!("Invalid levels {key}: {other}"))),
+    };
+    levels.iter()
+        .map(|level| {
+            let invalid = || ExchangeError::InvalidResponse(format!("Invalid level in {key}: {level}"));
+            let [price, quantity, ..] = level.as_array().map(Vec::as_slice).unwrap_or_default() else {
+                return Err(invalid());
+            };
+            let decimal = |v: &Value| v.as_str().and_then(|v| Fixed::from_ascii(v.as_bytes()).ok()).ok_or_else(invalid);
+            Ok(OrderBookLevel { price: decimal(price)?, quantity: decimal(quantity)? })
+        })
+        .collect()
+}
+
+/// Parse a `24hrMiniTicker` payload
+fn parse_mini_ticker(data: &Value) -> Result<MiniTickerUpdate> {
+    Ok(MiniTickerUpdate {
+        exchange: ExchangeId::Binance,
+        symbol: str_field(data, "s")?.to_string(),
+        close: fixed_field(data, "c", "mini ticker close")?,
+        open: fixed_field(data, "o", "mini ticker open")?,
+        high: fixed_field(data, "h", "mini ticker high")?,
+        low: fixed_field(data, "l", "mini ticker low")?,
+        base_volume: unbounded_field(data, "v", "mini ticker volume")?,
+        quote_volume: unbounded_field(data, "q", "mini ticker quote volume")?,
+        timestamp: u64_field(data, "E")?,
+    })
+}
+
+/// Parse an `aggTrade` payload
+fn parse_agg_trade(data: &Value) -> Result<AggTradeUpdate> {
+    Ok(AggTradeUpdate {
+        exchange: ExchangeId::Binance,
+        symbol: str_field(data, "s")?.to_string(),
+        agg_trade_id: u64_field(data, "a")?,
+        price: fixed_field(data, "p", "aggTrade price")?,
+        quantity: fixed_field(data, "q", "aggTrade quantity")?,
+        first_trade_id: u64_field(data, "f")?,
+        last_trade_id: u64_field(data, "l")?,
+        side: if bool_field(data, "m")? { TradeSide::Sell } else { TradeSide::Buy },
+        timestamp: u64_field(data, "T")?,
+    })
+}
+
+/// Parse a book ticker payload
+fn parse_book_ticker(data: &Value) -> Result<BookTickerUpdate> {
+    Ok(BookTickerUpdate {
+        exchange: ExchangeId::Binance,
+        symbol: str_field(data, "s")?.to_string(),
+        update_id: u64_field(data, "u")?,
+        bid_price: fixed_field(data, "b", "book ticker bid price")?,
+        bid_qty: unbounded_field(data, "B", "book ticker bid quantity")?,
+        ask_price: fixed_field(data, "a", "book ticker ask price")?,
+        ask_qty: unbounded_field(data, "A", "book ticker ask quantity")?,
+    })
+}
+
+/// Convert a full 24hrTicker stream payload (e.g. an element of `!ticker@arr`) into scanner statistics
+pub fn scan_ticker_from_stream(data: &Value) -> Result<crate::scanner::ScanTicker> {
+    // An absent or zero touch means an empty book side, not a zero price
+    let touch = |key: &str, what: &str| match &data[key] {
+        Value::Null => Ok(None),
+        _ => fixed_field(data, key, what).map(|price| (price > Fixed::ZERO).then_some(price)),
+    };
+    
+    Ok(crate::scanner::ScanTicker {
+        symbol: str_field(data, "s")?.to_string(),
+        last_price: fixed_field(data, "c", "ticker last price")?,
+        open_price: fixed_field(data, "o", "ticker open price")?,
+        high_price: fixed_field(data, "h", "ticker high price")?,
+        low_price: fixed_field(data, "l", "ticker low price")?,
+        bid_price: touch("b", "ticker bid price")?,
+        ask_price: touch("a", "ticker ask price")?,
+        volume: unbounded_field(data, "v", "ticker volume")?.to_f64_lossy(),
+        quote_volume: unbounded_field(data, "q", "ticker quote volume")?.to_f64_lossy(),
+        trade_count: u64_field(data, "n")?,
+        timestamp: u64_field(data, "E")?,
+    })
 }
 
 #[cfg(test)]
@@ -561,4 +1028,274 @@ mod tests {
             panic!("Expected ticker event");
         }
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_depth_streams_carry_kind() {
+        assert_eq!(DepthStream::Partial(10).stream_name("BTCUSDT", DepthSpeed::Ms100).unwrap(), "btcusdt@depth10@100ms");
+        assert_eq!(DepthStream::Diff.stream_name("BTCUSDT", DepthSpeed::Ms1000).unwrap(), "btcusdt@depth");
+        assert!(DepthStream::Partial(7).stream_name("BTCUSDT", DepthSpeed::Ms100).is_err());
+
+        let client = BinanceWebSocketClient::new(BinanceConfig::testnet());
+        let partial = r#"{"stream":"ethusdt@depth5@100ms","data":{"lastUpdateId":160,
+            "bids":[["0.0024","10"]],"asks":[["0.0026","100"]]}}"#;
+        let Ok(MarketDataEvent::Depth(depth)) = client.process_message_content(partial) else { panic!("expected depth") };
+        assert!(depth.is_snapshot());
+        assert_eq!((depth.symbol.as_str(), depth.update_id, depth.bids.len()), ("ETHUSDT", 160, 1));
+
+        let diff = r#"{"stream":"ethusdt@depth@100ms","data":{"e":"depthUpdate","E":1,"s":"ETHUSDT","U":157,"u":160,
+            "b":[["0.0024","10"]],"a":[]}}"#;
+        let Ok(MarketDataEvent::Depth(depth)) = client.process_message_content(diff) else { panic!("expected depth") };
+        assert_eq!((depth.kind, depth.first_update_id), (DepthKind::Diff, 157));
+    }
+
+    #[test]
+    fn test_malformed_payloads_are_errors() {
+        let client = BinanceWebSocketClient::new(BinanceConfig::testnet());
+        let trade = r#"{"e":"trade","E":1,"s":"BTCUSDT","t":7,"p":"50000.00","q":"0.1","T":1,"m":false}"#;
+        assert!(matches!(client.process_message_content(trade), Ok(MarketDataEvent::Trade(t)) if t.trade_id == 7));
+
+        // A missing price must never read as zero
+        let missing_price = trade.replace(r#""p":"50000.00","#, "");
+        assert!(matches!(client.process_message_content(&missing_price), Err(ExchangeError::MissingField(f)) if f == "p"));
+        let numeric_price = trade.replace(r#""50000.00""#, "50000.00");
+        assert!(matches!(client.process_message_content(&numeric_price), Err(ExchangeError::InvalidResponse(_))));
+        let missing_side = trade.replace(r#","m":false"#, "");
+        assert!(matches!(client.process_message_content(&missing_side), Err(ExchangeError::MissingField(_))));
+
+        let diff = r#"{"e":"depthUpdate","E":1,"s":"ETHUSDT","U":157,"u":160,"b":[["0.0024","10"]],"a":[]}"#;
+        assert!(client.process_message_content(diff).is_ok());
+        let no_update_id = diff.replace(r#""u":160,"#, "");
+        assert!(matches!(client.process_message_content(&no_update_id), Err(ExchangeError::MissingField(f)) if f == "u"));
+        let short_level = diff.replace(r#"["0.0024","10"]"#, r#"["0.0024"]"#);
+        assert!(matches!(client.process_message_content(&short_level), Err(ExchangeError::InvalidResponse(_))));
+        let no_asks = diff.replace(r#","a":[]"#, "");
+        assert!(matches!(client.process_message_content(&no_asks), Err(ExchangeError::MissingField(_))));
+
+        let kline = r#"{"e":"kline","E":1,"s":"BTCUSDT","k":{"t":0,"T":59999,"s":"BTCUSDT","i":"1m","o":"1","h":"2","l":"0.5","v":"10","x":true}}"#;
+        assert!(matches!(client.process_message_content(kline), Err(ExchangeError::MissingField(f)) if f == "c"));
+        let book_ticker = r#"{"u":1,"s":"BTCUSDT","b":"1","B":"1","a":"2"}"#;
+        assert!(matches!(client.process_message_content(book_ticker), Err(ExchangeError::MissingField(f)) if f == "A"));
+
+        // Partial books need an unambiguous symbol
+        let partial = r#"{"lastUpdateId":160,"bids":[],"asks":[]}"#;
+        assert!(client.process_message_content(partial).is_err());
+    }
+
+    #[test]
+    fn test_scan_ticker_from_stream() {
+        let data: Value = serde_json::from_str(r#"{
+            "e": "24hrTicker", "E": 123456789, "s": "BNBBTC", "p": "0.0015", "P": "250.00",
+            "c": "0.0025", "o": "0.0010", "h": "0.0025", "l": "0.0010", "v": "10000",
+            "q": "18", "b": "0.0024", "a": "0.0026", "n": 18151
+        }"#).unwrap();
+        
+        let ticker = scan_ticker_from_stream(&data).unwrap();
+        assert_eq!(ticker.symbol, "BNBBTC");
+        assert_eq!(ticker.trade_count, 18151);
+        assert!((ticker.change_percent() - 150.0).abs() < 1e-9);
+    }
+    
+    #[test]
+    fn test_whole_market_arrays() {
+        let config = BinanceConfig::testnet();
+        let client = BinanceWebSocketClient::new(config);
+        
+        let raw = r#"[
+            {"e":"24hrMiniTicker","E":1,"s":"BTCUSDT","c":"50000","o":"49000","h":"51000","l":"48000","v":"1000","q":"50000000"},
+            {"e":"24hrMiniTicker","E":1,"s":"ETHUSDT","c":"3000","o":"2900","h":"3100","l":"2800","v":"20000","q":"60000000"}
+        ]"#;
+        match client.process_message_content(raw) {
+            Ok(MarketDataEvent::MiniTickerBatch(batch)) => {
+                assert_eq!(batch.len(), 2);
+                assert_eq!(batch[1].symbol, "ETHUSDT");
+                assert_eq!(batch[0].quote_volume.to_string(), "50000000");
+            }
+            other => panic!("Expected mini ticker batch, got {other:?}"),
+        }
+
+        // A schema change fails the batch instead of dropping the symbol from fan-out
+        let renamed = raw.replace(r#""c":"3000""#, r#""close":"3000""#);
+        let err = client.process_message_content(&renamed).unwrap_err();
+        assert!(matches!(err.root(), ExchangeError::MissingField(_)));
+        assert!(err.context().unwrap().payload.as_ref().unwrap().contains("ETHUSDT"));
+        
+        // Meme-coin touch sizes exceed the Fixed range and are kept; an out-of-range price only drops its symbol
+        let books = r#"[
+            {"u":1,"s":"PEPEUSDT","b":"0.00001234","B":"987654321098","a":"0.00001235","A":"123456789012"},
+            {"u":2,"s":"ODDUSDT","b":"1000000000","B":"1","a":"1000000001","A":"1"},
+            {"u":3,"s":"BNBUSDT","b":"25.35","B":"31.21","a":"25.36","A":"40.66"}
+        ]"#;
+        match client.process_message_content(books) {
+            Ok(MarketDataEvent::BookTickerBatch(batch)) => {
+                assert_eq!(batch.iter().map(|t| t.symbol.as_str()).collect::<Vec<_>>(), ["PEPEUSDT", "BNBUSDT"]);
+                assert_eq!(batch[0].bid_qty.to_string(), "987654321098");
+            }
+            other => panic!("Expected book ticker batch, got {other:?}"),
+        }
+        
+        let combined = r#"{"stream":"!bookTicker","data":{"u":400900217,"s":"BNBUSDT","b":"25.35","B":"31.21","a":"25.36","A":"40.66"}}"#;
+        match client.process_message_content(combined) {
+            Ok(MarketDataEvent::BookTicker(t)) => {
+                assert_eq!(t.symbol, "BNBUSDT");
+                assert_eq!(t.ask_qty.to_string(), "40.66");
+            }
+            other => panic!("Expected book ticker, got {other:?}"),
+        }
+    }
+    
+    #[test]
+    fn test_parses_agg_trades() {
+        let client = BinanceWebSocketClient::new(BinanceConfig::testnet());
+        let combined = r#"{"stream":"btcusdt@aggTrade","data":{"e":"aggTrade","E":1672515782136,"s":"BTCUSDT","a":12345,"p":"0.001","q":"100","f":100,"l":105,"T":1672515782136,"m":true,"M":true}}"#;
+        match client.process_message_content(combined) {
+            Ok(MarketDataEvent::AggTrade(t)) => {
+                assert_eq!((t.agg_trade_id, t.trade_count()), (12345, 6));
+                assert!(matches!(t.side, TradeSide::Sell));
+            }
+            other => panic!("Expected agg trade, got {other:?}"),
+        }
+        let raw: Value = serde_json::from_str(r#"{"e":"aggTrade","s":"BTCUSDT"}"#).unwrap();
+        assert_eq!(client.stream_key(&raw).as_deref(), Some("btcusdt@aggTrade"));
+    }
+    
+    #[test]
+    fn test_parses_symbol_book_ticker() {
+        let client = BinanceWebSocketClient::new(BinanceConfig::testnet());
+        let raw = r#"{"u":400900217,"s":"BTCUSDT","b":"50000.10","B":"1.5","a":"50000.30","A":"0.25"}"#;
+        match client.process_message_content(raw) {
+            Ok(MarketDataEvent::BookTicker(t)) => {
+                assert_eq!((t.symbol.as_str(), t.update_id), ("BTCUSDT", 400900217));
+                assert_eq!(t.spread(), Fixed::from_str_exact("0.2").unwrap());
+                assert_eq!(t.mid_price(), Fixed::from_str_exact("50000.2").unwrap());
+            }
+            other => panic!("Expected book ticker, got {other:?}"),
+        }
+        let combined = r#"{"stream":"btcusdt@bookTicker","data":{"u":1,"s":"BTCUSDT","b":"1","B":"1","a":"2","A":"1"}}"#;
+        assert!(matches!(client.process_message_content(combined), Ok(MarketDataEvent::BookTicker(_))));
+        let json: Value = serde_json::from_str(raw).unwrap();
+        assert_eq!(client.stream_key(&json).as_deref(), Some("btcusdt@bookTicker"));
+    }
+    
+    #[test]
+    fn test_stream_key_attribution() {
+        let config = BinanceConfig::testnet();
+        let client = BinanceWebSocketClient::new(config);
+        
+        let combined: Value = serde_json::from_str(r#"{"stream":"btcusdt@trade","data":{}}"#).unwrap();
+        assert_eq!(client.stream_key(&combined).as_deref(), Some("btcusdt@trade"));
+        
+        let raw: Value = serde_json::from_str(r#"{"e":"kline","s":"ETHUSDT","k":{"i":"1m"}}"#).unwrap();
+        assert_eq!(client.stream_key(&raw).as_deref(), Some("ethusdt@kline_1m"));
+        
+        let arr: Value = serde_json::from_str(r#"[{"e":"24hrMiniTicker","s":"BTCUSDT"}]"#).unwrap();
+        assert_eq!(client.stream_key(&arr).as_deref(), Some("!miniTicker@arr"));
+        
+        let confirmation: Value = serde_json::from_str(r#"{"result":null,"id":1}"#).unwrap();
+        assert_eq!(client.stream_key(&confirmation), None);
+        assert_eq!(client.connection_stats().messages, 0);
+    }
+    
+    #[monoio::test(timer_enabled = true)]
+    async fn test_reconnect_replays_subscriptions() {
+        use crate::cassette::{Cassette, WsEvent, WsSession};
+        
+        let url = "wss://stream.binance.com:9443/ws";
+        let trade = r#"{"e":"trade","E":1,"s":"BTCUSDT","t":7,"p":"50000.00","q":"0.1","T":1,"m":false}"#;
+        let session = |sent: &str| WsSession {
+            url: url.to_string(),
+            events: vec![WsEvent::Sent(sent.to_string()), WsEvent::Received(trade.to_string())],
+        };
+        let cassette = CassetteHandle::replay(Cassette {
+            http: Vec::new(),
+            websocket: vec![session("subscribe"), session("resubscribe")],
+        });
+        
+        let config = ReconnectConfig { max_attempts: 2, initial_delay_ms: 1, jitter_ms: 0, ..ReconnectConfig::default() };
+        let mut client = BinanceWebSocketClient::new(BinanceConfig::default())
+            .with_cassette(cassette)
+            .with_reconnect(config);
+        client.connect().await.unwrap();
+        client.subscribe_trades("BTCUSDT").await.unwrap();
+        assert!(matches!(client.receive_message().await, Ok(MarketDataEvent::Trade(_))));
+        
+        // First session ends: the second is opened and the trade stream replayed
+        match client.receive_message().await {
+            Ok(MarketDataEvent::Reconnected(info)) => {
+                assert_eq!(info.attempts, 1);
+                assert_eq!(info.streams, vec!["btcusdt@trade".to_string()]);
+            }
+            other => panic!("expected Reconnected, got {other:?}"),
+        }
+        assert!(matches!(client.receive_message().await, Ok(MarketDataEvent::Trade(_))));
+        assert_eq!(client.get_subscriptions(), vec!["btcusdt@trade".to_string()]);
+        
+        // No sessions left: gives up after max_attempts
+        assert!(client.receive_message().await.is_err());
+    }
+    
+    #[monoio::test]
+    async fn test_batched_subscriptions_share_one_request() {
+        use crate::cassette::{Cassette, WsEvent, WsSession};
+        
+        let trade = r#"{"e":"trade","E":1,"s":"BTCUSDT","t":7,"p":"50000.00","q":"0.1","T":1,"m":false}"#;
+        let cassette = CassetteHandle::replay(Cassette {
+            http: Vec::new(),
+            websocket: vec![WsSession {
+                url: "wss://stream.binance.com:9443/ws".to_string(),
+                events: vec![WsEvent::Sent("subscribe".to_string()), WsEvent::Received(trade.to_string())],
+            }],
+        });
+        let mut client = BinanceWebSocketClient::new(BinanceConfig::default())
+            .with_cassette(cassette)
+            .with_subscription_batching(true);
+        client.connect().await.unwrap();
+        for symbol in ["BTCUSDT", "ETHUSDT", "BNBUSDT"] {
+            client.subscribe_trades(symbol).await.unwrap();
+        }
+        // Never sent, so dropped without an UNSUBSCRIBE
+        assert!(client.release_stream("bnbusdt@trade").await.unwrap());
+        assert_eq!(client.pending_subscriptions(), ["btcusdt@trade", "ethusdt@trade"]);
+        
+        assert!(matches!(client.receive_message().await, Ok(MarketDataEvent::Trade(_))));
+        assert!(client.pending_subscriptions().is_empty());
+        
+        let streams: Vec<String> = (0..450).map(|i| format!("s{i}@trade")).collect();
+        let mut id = 0;
+        let requests = subscribe_requests("SUBSCRIBE", &streams, || { id += 1; id });
+        assert_eq!(requests.len(), 3);
+        let last: Value = serde_json::from_str(&requests[2]).unwrap();
+        assert_eq!((last["params"].as_array().unwrap().len(), last["id"].as_u64()), (50, Some(3)));
+    }
+    
+    #[monoio::test(timer_enabled = true)]
+    async fn test_connection_cut_mid_frame_reconnects() {
+        use crate::cassette::{Cassette, WsEvent, WsSession};
+        use crate::chaos::{Fault, FaultConfig};
+        
+        let url = "wss://stream.binance.com:9443/ws";
+        let trade = |id: u64| WsEvent::Received(format!(r#"{{"e":"trade","E":1,"s":"BTCUSDT","t":{id},"p":"50000.00","q":"0.1","T":1,"m":false}}"#));
+        let cassette = CassetteHandle::replay(Cassette {
+            http: Vec::new(),
+            websocket: vec![
+                WsSession { url: url.to_string(), events: vec![WsEvent::Sent("subscribe".to_string()), trade(1), trade(2)] },
+                WsSession { url: url.to_string(), events: vec![WsEvent::Sent("resubscribe".to_string()), trade(3)] },
+            ],
+        });
+        
+        let faults = FaultInjector::new(FaultConfig::new(1));
+        let config = ReconnectConfig { max_attempts: 2, initial_delay_ms: 1, jitter_ms: 0, ..ReconnectConfig::default() };
+        let mut client = BinanceWebSocketClient::new(BinanceConfig::default())
+            .with_cassette(cassette)
+            .with_faults(faults.clone())
+            .with_reconnect(config);
+        client.connect().await.unwrap();
+        client.subscribe_trades("BTCUSDT").await.unwrap();
+        assert!(matches!(client.receive_message().await, Ok(MarketDataEvent::Trade(t)) if t.trade_id == 1));
+        
+        // Trade 2 is lost with the connection; the stream resumes on a new one
+        faults.schedule(Fault::PartialWrite);
+        assert!(matches!(client.receive_message().await, Ok(MarketDataEvent::Reconnected(_))));
+        assert!(matches!(client.receive_message().await, Ok(MarketDataEvent::Trade(t)) if t.trade_id == 3));
+        assert_eq!(faults.stats().partial_writes, 1);
+    }
+}
@@ -0,0 +1,319 @@
+//! Persisted order book snapshots for warm starts
+//!
+//! Fetching a REST snapshot for every symbol at startup costs time and
+//! request weight that grows with the universe. `BookSnapshotStore`
+//! periodically writes each synced book as a deflated snapshot and logs the
+//! diffs applied since, so a restarted engine rebuilds its books from disk
+//! and replays only that short diff window.
+//!
+//! Per symbol the directory holds `<SYMBOL>.book` (deflated JSON, replaced
+//! atomically) and `<SYMBOL>.diffs` (JSON lines, truncated at each
+//! snapshot). Disk I/O runs on a background thread, as in `KvStore`, and
+//! snapshots are replaced with the same synced rename.
+//!
+//! A restored book is as fresh as the last logged diff. When the live
+//! stream continues from there the book carries on without a REST call;
+//! otherwise its first live diff reports a gap and the book resyncs from a
+//! REST snapshot as usual.
+
+use crate::disk_writer::{replace_atomic, BackgroundWriter};
+use crate::errors::{ExchangeError, Result};
+use crate::orderbook::OrderBook;
+use crate::types::{DepthKind, DepthUpdate, ExchangeId, OrderBookLevel};
+use sriquant_core::prelude::*;
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// Deflate level for snapshots; books are written in the background, so
+/// size matters more than speed
+const COMPRESSION_LEVEL: u8 = 6;
+
+/// A snapshot as written to `<SYMBOL>.book`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PersistedBook {
+    /// Local time of the write, in milliseconds
+    saved_at: u64,
+    book: crate::types::OrderBook,
+}
+
+/// One line of `<SYMBOL>.diffs`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LoggedDiff {
+    exchange: ExchangeId,
+    #[serde(rename = "U")]
+    first_update_id: u64,
+    #[serde(rename = "u")]
+    update_id: u64,
+    #[serde(rename = "E")]
+    timestamp: u64,
+    #[serde(rename = "b")]
+    bids: Vec<OrderBookLevel>,
+    #[serde(rename = "a")]
+    asks: Vec<OrderBookLevel>,
+}
+
+impl LoggedDiff {
+    fn into_update(self, symbol: &str) -> DepthUpdate {
+        DepthUpdate {
+            exchange: self.exchange,
+            symbol: symbol.to_string(),
+            bids: self.bids,
+            asks: self.asks,
+            timestamp: self.timestamp,
+            first_update_id: self.first_update_id,
+            update_id: self.update_id,
+            kind: DepthKind::Diff,
+        }
+    }
+}
+
+enum WriteOp {
+    Snapshot { symbol: String, contents: Vec<u8> },
+    Diff { symbol: String, line: String },
+}
+
+/// Directory of persisted books, one snapshot and diff log per symbol
+///
+/// ```rust,ignore
+/// let mut store = BookSnapshotStore::open("books")?.with_interval(Duration::from_secs(30));
+/// let mut book = store.restore("BTCUSDT")?.unwrap_or_else(|| OrderBook::new("BTCUSDT"));
+/// // on every depth diff
+/// if book.apply_depth(&update)? == DepthApplied::Applied {
+///     store.record(&book, &update)?;
+/// }
+/// ```
+pub struct BookSnapshotStore {
+    dir: PathBuf,
+    interval: Duration,
+    max_age: Duration,
+    /// Local time of the last snapshot per symbol, in milliseconds
+    last_saved: HashMap<String, u64>,
+    writer: BackgroundWriter<WriteOp>,
+}
+
+impl BookSnapshotStore {
+    /// Open (creating if needed) a store in `dir`
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        std::fs::create_dir_all(&dir)?;
+
+        let writer_dir = dir.clone();
+        let mut logs = HashMap::new();
+        let writer = BackgroundWriter::spawn("book store", move |ops| write_ops(&writer_dir, &mut logs, ops))?;
+        info!("📚 Opened book store at {}", dir.display());
+
+        Ok(Self {
+            dir,
+            interval: Duration::from_secs(60),
+            max_age: Duration::from_secs(600),
+            last_saved: HashMap::new(),
+            writer,
+        })
+    }
+
+    /// How often a book is re-snapshotted, bounding the diff window to replay
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Books older than this are not restored
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Persist an applied diff, snapshotting the book when the interval is up
+    ///
+    /// Call with the book after `apply_depth` returned `Applied`.
+    pub fn record(&mut self, book: &OrderBook, update: &DepthUpdate) -> Result<()> {
+        if !book.is_synced() {
+            return Ok(());
+        }
+        let now = nanos() / 1_000_000;
+        let due = self.last_saved.get(book.symbol())
+            .is_none_or(|saved| now.saturating_sub(*saved) >= self.interval.as_millis() as u64);
+        if due {
+            return self.save(book);
+        }
+        let line = serde_json::to_string(&LoggedDiff {
+            exchange: update.exchange,
+            first_update_id: update.first_update_id,
+            update_id: update.update_id,
+            timestamp: update.timestamp,
+            bids: update.bids.clone(),
+            asks: update.asks.clone(),
+        })?;
+        self.writer.send(WriteOp::Diff { symbol: book.symbol().to_string(), line })
+    }
+
+    /// Snapshot `book` now and start a new diff window
+    pub fn save(&mut self, book: &OrderBook) -> Result<()> {
+        let timer = PerfTimer::start("book_store_snapshot".to_string());
+        let saved_at = nanos() / 1_000_000;
+        let json = serde_json::to_vec(&PersistedBook { saved_at, book: book.to_snapshot(usize::MAX) })?;
+        let contents = miniz_oxide::deflate::compress_to_vec(&json, COMPRESSION_LEVEL);
+        debug!("📚 {} snapshot at {}: {} bytes, {} deflated", book.symbol(), book.last_update_id(), json.len(), contents.len());
+        self.last_saved.insert(book.symbol().to_string(), saved_at);
+        self.writer.send(WriteOp::Snapshot { symbol: book.symbol().to_string(), contents })?;
+        timer.log_elapsed();
+        Ok(())
+    }
+
+    /// The persisted book for `symbol` with its diff window replayed
+    ///
+    /// `None` when nothing is stored, the book is older than `max_age`, or
+    /// the logged diffs don't connect to the snapshot.
+    pub fn restore(&self, symbol: &str) -> Result<Option<OrderBook>> {
+        let symbol = symbol.to_uppercase();
+        let path = self.dir.join(format!("{symbol}.book"));
+        if !path.exists() {
+            return Ok(None);
+        }
+        let timer = PerfTimer::start("book_store_restore".to_string());
+        let json = miniz_oxide::inflate::decompress_to_vec(&std::fs::read(&path)?)
+            .map_err(|e| ExchangeError::IoError(format!("{}: corrupt snapshot ({e:?})", path.display())))?;
+        let persisted: PersistedBook = serde_json::from_slice(&json)?;
+        let mut book = OrderBook::restore(&persisted.book);
+
+        let diffs = self.dir.join(format!("{symbol}.diffs"));
+        let mut replayed = 0;
+        if diffs.exists() {
+            for line in BufReader::new(File::open(&diffs)?).lines() {
+                let line = line?;
+                // A torn last line from a crash mid-append
+                let Ok(diff) = serde_json::from_str::<LoggedDiff>(&line) else { break };
+                if book.apply_depth(&diff.into_update(&symbol)).is_err() {
+                    warn!("📚 {} diff log doesn't connect to its snapshot, not restoring", symbol);
+                    return Ok(None);
+                }
+                replayed += 1;
+            }
+        }
+
+        let now = nanos() / 1_000_000;
+        let fresh_at = persisted.saved_at.max(book.timestamp());
+        timer.log_elapsed();
+        if now.saturating_sub(fresh_at) > self.max_age.as_millis() as u64 {
+            info!("📚 {} persisted book is {}s old, not restoring", symbol, now.saturating_sub(fresh_at) / 1000);
+            return Ok(None);
+        }
+        info!("📚 {} restored at update {} ({} diffs replayed)", symbol, book.last_update_id(), replayed);
+        Ok(Some(book))
+    }
+
+    /// Wait until every snapshot and diff so far is on disk
+    pub async fn flush(&self) -> Result<()> {
+        self.writer.flush().await
+    }
+}
+
+/// Apply a burst of ops in order: a diff logged after a snapshot belongs in the new window
+fn write_ops(dir: &Path, logs: &mut HashMap<String, BufWriter<File>>, ops: Vec<WriteOp>) -> Option<String> {
+    let mut last_error = None;
+    for op in ops {
+        let result = match op {
+            WriteOp::Snapshot { symbol, contents } => {
+                logs.remove(&symbol);
+                replace_atomic(&dir.join(format!("{symbol}.book")), &contents)
+                    .and_then(|()| File::create(dir.join(format!("{symbol}.diffs"))))
+                    .map(|file| {
+                        logs.insert(symbol.clone(), BufWriter::new(file));
+                    })
+                    .map_err(|e| format!("{symbol}: {e}"))
+            }
+            WriteOp::Diff { symbol, line } => append_diff(dir, logs, &symbol, &line).map_err(|e| format!("{symbol}: {e}")),
+        };
+        if let Err(e) = result {
+            warn!("❗ Failed to persist book {}", e);
+            last_error = Some(e);
+        }
+    }
+    for (symbol, log) in logs.iter_mut() {
+        if let Err(e) = log.flush() {
+            warn!("❗ Failed to flush {} diff log: {}", symbol, e);
+            last_error = Some(format!("{symbol}: {e}"));
+        }
+    }
+    last_error
+}
+
+fn append_diff(dir: &Path, logs: &mut HashMap<String, BufWriter<File>>, symbol: &str, line: &str) -> std::io::Result<()> {
+    let log = match logs.entry(symbol.to_string()) {
+        std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
+        std::collections::hash_map::Entry::Vacant(entry) => {
+            let file = File::options().create(true).append(true).open(dir.join(format!("{symbol}.diffs")))?;
+            entry.insert(BufWriter::new(file))
+        }
+    };
+    writeln!(log, "{line}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binance::rest::OrderBookResponse;
+    use crate::orderbook::DepthApplied;
+
+    fn diff(first: u64, last: u64, bid: (&str, &str)) -> DepthUpdate {
+        DepthUpdate {
+            exchange: ExchangeId::Binance,
+            symbol: "BTCUSDT".to_string(),
+            bids: vec![OrderBookLevel { price: Fixed::from_str_exact(bid.0).unwrap(), quantity: Fixed::from_str_exact(bid.1).unwrap() }],
+            asks: vec![],
+            timestamp: nanos() / 1_000_000,
+            first_update_id: first,
+            update_id: last,
+            kind: DepthKind::Diff,
+        }
+    }
+
+    #[monoio::test]
+    async fn test_restores_snapshot_and_diff_window() {
+        let dir = std::env::temp_dir().join(format!("sriquant-books-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let mut book = OrderBook::new("BTCUSDT");
+        book.apply_snapshot(&OrderBookResponse {
+            last_update_id: 10,
+            bids: (0..500).map(|i| [format!("{}", 50_000 - i), "1.5".into()]).collect(),
+            asks: (0..500).map(|i| [format!("{}", 50_001 + i), "2".into()]).collect(),
+        })
+        .unwrap();
+
+        let mut store = BookSnapshotStore::open(&dir).unwrap().with_interval(Duration::from_secs(3600));
+        // First diff snapshots the book, the rest go to the diff log
+        for (i, update) in [diff(11, 11, ("50000", "3")), diff(12, 13, ("49999", "0")), diff(14, 14, ("50000.5", "1"))].iter().enumerate() {
+            assert_eq!(book.apply_depth(update).unwrap(), DepthApplied::Applied, "diff {i}");
+            store.record(&book, update).unwrap();
+        }
+        store.flush().await.unwrap();
+        let snapshot_bytes = std::fs::metadata(dir.join("BTCUSDT.book")).unwrap().len();
+        assert!(snapshot_bytes < 4_000, "{snapshot_bytes}");
+        drop(store);
+
+        let store = BookSnapshotStore::open(&dir).unwrap();
+        let restored = store.restore("btcusdt").unwrap().unwrap();
+        assert!(restored.is_synced());
+        assert_eq!(restored.last_update_id(), 14);
+        assert_eq!(restored.bids(1000), book.bids(1000));
+        assert_eq!(restored.asks(1000), book.asks(1000));
+        assert!(store.restore("ETHUSDT").unwrap().is_none());
+
+        // Too old to trust
+        let strict = BookSnapshotStore::open(&dir).unwrap().with_max_age(Duration::ZERO);
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(strict.restore("BTCUSDT").unwrap().is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
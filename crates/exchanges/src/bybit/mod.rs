@@ -0,0 +1,434 @@
+//! Bybit v5 unified API integration
+//!
+//! Implements `Exchange`, `TradingExchange` and (through
+//! `BybitPublicStream`) `StreamingExchange` for one product category: spot,
+//! USDT/USDC linear contracts or inverse contracts. Symbols are Bybit's
+//! ("BTCUSDT"). `BybitPrivateStream` carries order, position and execution
+//! updates, and `BybitExchange::positions` reads derivatives positions.
+//!
+//! Differences from Binance a strategy may notice:
+//! - spot stops are sent as `StopOrder` conditional orders
+//! - klines have no trade count
+//! - balances come from the unified trading account
+
+pub mod auth;
+pub mod rest;
+pub mod websocket;
+
+pub use auth::{BybitCredentials, BybitSigner, DEFAULT_RECV_WINDOW_MS};
+pub use rest::{BybitPosition, BybitRestClient, CreateOrderRequest};
+pub use websocket::{BybitPrivateEvent, BybitPrivateStream, BybitPublicStream};
+
+use crate::cassette::CassetteHandle;
+use crate::errors::{ExchangeError, Result};
+use crate::traits::{Exchange, TradingExchange};
+use crate::types::*;
+use rest::*;
+use sriquant_core::prelude::*;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::{info, warn};
+
+/// Bybit product category
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BybitCategory {
+    Spot,
+    Linear,
+    Inverse,
+}
+
+impl BybitCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BybitCategory::Spot => "spot",
+            BybitCategory::Linear => "linear",
+            BybitCategory::Inverse => "inverse",
+        }
+    }
+
+    pub fn is_derivatives(&self) -> bool {
+        *self != BybitCategory::Spot
+    }
+}
+
+/// Bybit connection settings
+#[derive(Debug, Clone)]
+pub struct BybitConfig {
+    pub base_url: String,
+    /// Public streams live at `{public_ws_base}/{category}`
+    pub public_ws_base: String,
+    pub private_ws_url: String,
+    pub category: BybitCategory,
+    pub recv_window_ms: u64,
+    /// Needed for accounts, orders and the private stream only
+    pub credentials: Option<BybitCredentials>,
+}
+
+impl Default for BybitConfig {
+    fn default() -> Self {
+        Self {
+            base_url: "https://api.bybit.com".to_string(),
+            public_ws_base: "wss://stream.bybit.com/v5/public".to_string(),
+            private_ws_url: "wss://stream.bybit.com/v5/private".to_string(),
+            category: BybitCategory::Spot,
+            recv_window_ms: DEFAULT_RECV_WINDOW_MS,
+            credentials: None,
+        }
+    }
+}
+
+impl BybitConfig {
+    /// Bybit's testnet endpoints
+    pub fn testnet() -> Self {
+        Self {
+            base_url: "https://api-testnet.bybit.com".to_string(),
+            public_ws_base: "wss://stream-testnet.bybit.com/v5/public".to_string(),
+            private_ws_url: "wss://stream-testnet.bybit.com/v5/private".to_string(),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_credentials(mut self, credentials: BybitCredentials) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+
+    pub fn with_category(mut self, category: BybitCategory) -> Self {
+        self.category = category;
+        self
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    pub fn with_recv_window_ms(mut self, recv_window_ms: u64) -> Self {
+        self.recv_window_ms = recv_window_ms;
+        self
+    }
+
+    /// Public stream URL for the configured category
+    pub fn public_ws_url(&self) -> String {
+        format!("{}/{}", self.public_ws_base, self.category.as_str())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ServerTime {
+    time_second: String,
+    #[serde(default)]
+    time_nano: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Cancelled {
+    #[serde(default)]
+    list: Vec<OrderIds>,
+}
+
+/// Bybit v5 over REST
+///
+/// ```rust,ignore
+/// let config = BybitConfig::default()
+///     .with_category(BybitCategory::Linear)
+///     .with_credentials(BybitCredentials::from_env()?);
+/// let bybit = BybitExchange::new(config)?;
+/// let positions = bybit.positions(Some("BTCUSDT")).await?;
+/// ```
+pub struct BybitExchange {
+    config: BybitConfig,
+    rest: BybitRestClient,
+}
+
+impl BybitExchange {
+    pub fn new(config: BybitConfig) -> Result<Self> {
+        let rest = BybitRestClient::new(config.clone())?;
+        info!("🟨 Bybit {} exchange created (authenticated: {})", config.category.as_str(), rest.has_credentials());
+        Ok(Self { config, rest })
+    }
+
+    /// Record requests into, or replay them from, a cassette
+    pub fn with_cassette(mut self, cassette: CassetteHandle) -> Self {
+        self.rest = self.rest.with_cassette(cassette);
+        self
+    }
+
+    pub fn rest(&self) -> &BybitRestClient {
+        &self.rest
+    }
+
+    /// A market data stream sharing this exchange's settings
+    pub fn public_stream(&self) -> BybitPublicStream {
+        BybitPublicStream::new(self.config.clone())
+    }
+
+    /// An order, position and execution stream sharing this exchange's settings
+    pub fn private_stream(&self) -> Result<BybitPrivateStream> {
+        BybitPrivateStream::new(self.config.clone())
+    }
+
+    fn category(&self) -> (&'static str, String) {
+        ("category", self.config.category.as_str().to_string())
+    }
+
+    /// Open derivatives positions, for one symbol or all settled in USDT
+    pub async fn positions(&self, symbol: Option<&str>) -> Result<Vec<BybitPosition>> {
+        if !self.config.category.is_derivatives() {
+            return Err(ExchangeError::FeatureNotSupported("Bybit spot has no positions".to_string()));
+        }
+        let mut query = vec![self.category()];
+        match symbol {
+            Some(symbol) => query.push(("symbol", symbol.to_string())),
+            None => query.push(("settleCoin", "USDT".to_string())),
+        }
+        let response: List<BybitPositionWire> = self.rest.get("/v5/position/list", &query, true).await?;
+        response.list.iter()
+            .map(BybitPositionWire::to_position)
+            .filter(|p| p.as_ref().map_or(true, |p| !p.quantity.is_zero()))
+            .collect()
+    }
+
+    async fn orders(&self, path: &str, query: Vec<(&str, String)>) -> Result<Vec<OrderResponse>> {
+        let response: List<BybitOrder> = self.rest.get(path, &query, true).await?;
+        response.list.iter().map(BybitOrder::to_order_response).collect()
+    }
+}
+
+/// Optional time bounds as Bybit query parameters
+fn time_range(start_time: Option<u64>, end_time: Option<u64>) -> Vec<(&'static str, String)> {
+    let mut query = Vec::new();
+    if let Some(start) = start_time {
+        query.push(("startTime", start.to_string()));
+    }
+    if let Some(end) = end_time {
+        query.push(("endTime", end.to_string()));
+    }
+    query
+}
+
+#[async_trait(?Send)]
+impl Exchange for BybitExchange {
+    fn name(&self) -> &str {
+        "bybit"
+    }
+
+    async fn ping(&self) -> Result<u64> {
+        let timer = PerfTimer::start("bybit_ping".to_string());
+        self.server_time().await?;
+        Ok(timer.elapsed_nanos() / 1_000)
+    }
+
+    async fn server_time(&self) -> Result<u64> {
+        let time: ServerTime = self.rest.get("/v5/market/time", &[], false).await?;
+        if let Ok(nanos) = time.time_nano.parse::<u64>() {
+            return Ok(nanos / 1_000_000);
+        }
+        time.time_second.parse::<u64>()
+            .map(|seconds| seconds * 1000)
+            .map_err(|e| ExchangeError::InvalidResponse(format!("Bad timeSecond {:?}: {e}", time.time_second)))
+    }
+
+    async fn exchange_info(&self) -> Result<HashMap<String, Symbol>> {
+        let query = [self.category(), ("limit", "1000".to_string())];
+        let response: List<InstrumentInfo> = self.rest.get("/v5/market/instruments-info", &query, false).await?;
+        let mut symbols = HashMap::new();
+        for instrument in &response.list {
+            match instrument.to_symbol() {
+                Ok(symbol) => {
+                    symbols.insert(symbol.symbol.clone(), symbol);
+                }
+                Err(e) => warn!("⚠️ Skipping Bybit instrument {}: {}", instrument.symbol, e),
+            }
+        }
+        Ok(symbols)
+    }
+
+    async fn account_info(&self) -> Result<AccountInfo> {
+        let balances = self.balances().await?;
+        Ok(AccountInfo {
+            account_type: "UNIFIED".to_string(),
+            can_trade: true,
+            can_withdraw: false,
+            can_deposit: false,
+            balances,
+            update_time: nanos() / 1_000_000,
+        })
+    }
+
+    async fn balances(&self) -> Result<Vec<Balance>> {
+        let query = [("accountType", "UNIFIED".to_string())];
+        let response: List<WalletAccount> = self.rest.get("/v5/account/wallet-balance", &query, true).await?;
+        response.list.iter()
+            .flat_map(|account| account.coin.iter())
+            .map(WalletCoin::to_balance)
+            .collect()
+    }
+
+    async fn ticker(&self, symbol: &str) -> Result<Ticker> {
+        let query = [self.category(), ("symbol", symbol.to_string())];
+        let response: List<TickerInfo> = self.rest.get("/v5/market/tickers", &query, false).await?;
+        response.list.first()
+            .ok_or_else(|| ExchangeError::InvalidSymbol(symbol.to_string()))?
+            .to_ticker(nanos() / 1_000_000)
+    }
+
+    async fn order_book(&self, symbol: &str, limit: Option<u32>) -> Result<OrderBook> {
+        let mut query = vec![self.category(), ("symbol", symbol.to_string())];
+        if let Some(limit) = limit {
+            query.push(("limit", limit.to_string()));
+        }
+        let response: OrderBookResult = self.rest.get("/v5/market/orderbook", &query, false).await?;
+        response.to_order_book()
+    }
+
+    async fn recent_trades(&self, symbol: &str, limit: Option<u32>) -> Result<Vec<Trade>> {
+        let mut query = vec![self.category(), ("symbol", symbol.to_string())];
+        if let Some(limit) = limit {
+            query.push(("limit", limit.to_string()));
+        }
+        let response: List<RecentTrade> = self.rest.get("/v5/market/recent-trade", &query, false).await?;
+        response.list.iter().map(RecentTrade::to_trade).collect()
+    }
+
+    async fn klines(
+        &self,
+        symbol: &str,
+        interval: &str,
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<Vec<Kline>> {
+        let mut query = vec![
+            self.category(),
+            ("symbol", symbol.to_string()),
+            ("interval", kline_interval(interval)?.to_string()),
+        ];
+        if let Some(start) = start_time {
+            query.push(("start", start.to_string()));
+        }
+        if let Some(end) = end_time {
+            query.push(("end", end.to_string()));
+        }
+        if let Some(limit) = limit {
+            query.push(("limit", limit.to_string()));
+        }
+        let response: KlineResult = self.rest.get("/v5/market/kline", &query, false).await?;
+        // Newest first on the wire
+        let mut klines = response.list.iter()
+            .map(|row| kline_from_row(&response.symbol, interval, row))
+            .collect::<Result<Vec<_>>>()?;
+        klines.sort_by_key(|k| k.open_time);
+        Ok(klines)
+    }
+}
+
+#[async_trait(?Send)]
+impl TradingExchange for BybitExchange {
+    async fn place_order(&self, request: OrderRequest) -> Result<OrderResponse> {
+        let timer = PerfTimer::start("bybit_place_order".to_string());
+        let body = CreateOrderRequest::from_request(self.config.category, &request)?;
+        let ids: OrderIds = self.rest.post("/v5/order/create", &body).await?;
+        timer.log_elapsed();
+        info!("✅ Bybit order {} placed: {} {} {}", ids.order_id, request.side, request.quantity, request.symbol);
+
+        let now = nanos() / 1_000_000;
+        Ok(OrderResponse {
+            order_id: ids.order_id,
+            client_order_id: ids.order_link_id,
+            symbol: request.symbol,
+            side: request.side,
+            order_type: request.order_type,
+            quantity: request.quantity,
+            price: request.price,
+            stop_price: request.stop_price,
+            status: OrderStatus::New,
+            filled_quantity: Fixed::ZERO,
+            average_price: None,
+            time_in_force: request.time_in_force,
+            timestamp: now,
+            update_time: now,
+        })
+    }
+
+    async fn cancel_order(&self, symbol: &str, order_id: &str) -> Result<OrderResponse> {
+        let body = serde_json::json!({
+            "category": self.config.category,
+            "symbol": symbol,
+            "orderId": order_id,
+        });
+        let _: OrderIds = self.rest.post("/v5/order/cancel", &body).await?;
+        self.get_order(symbol, order_id).await
+    }
+
+    async fn cancel_all_orders(&self, symbol: &str) -> Result<Vec<OrderResponse>> {
+        let open = self.open_orders(Some(symbol)).await?;
+        if open.is_empty() {
+            return Ok(Vec::new());
+        }
+        let body = serde_json::json!({ "category": self.config.category, "symbol": symbol });
+        let cancelled: Cancelled = self.rest.post("/v5/order/cancel-all", &body).await?;
+        Ok(open.into_iter()
+            .filter(|order| cancelled.list.iter().any(|c| c.order_id == order.order_id))
+            .map(|mut order| {
+                order.status = OrderStatus::Canceled;
+                order
+            })
+            .collect())
+    }
+
+    async fn get_order(&self, symbol: &str, order_id: &str) -> Result<OrderResponse> {
+        let query = vec![self.category(), ("symbol", symbol.to_string()), ("orderId", order_id.to_string())];
+        // Open and recently closed orders are both in realtime; older ones only in history
+        let mut orders = self.orders("/v5/order/realtime", query.clone()).await?;
+        if orders.is_empty() {
+            orders = self.orders("/v5/order/history", query).await?;
+        }
+        orders.into_iter().next().ok_or_else(|| ExchangeError::OrderNotFound(order_id.to_string()))
+    }
+
+    async fn open_orders(&self, symbol: Option<&str>) -> Result<Vec<OrderResponse>> {
+        let mut query = vec![self.category(), ("openOnly", "0".to_string())];
+        match symbol {
+            Some(symbol) => query.push(("symbol", symbol.to_string())),
+            None if self.config.category.is_derivatives() => query.push(("settleCoin", "USDT".to_string())),
+            None => {}
+        }
+        self.orders("/v5/order/realtime", query).await
+    }
+
+    async fn order_history(
+        &self,
+        symbol: &str,
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<Vec<OrderResponse>> {
+        let mut query = vec![self.category(), ("symbol", symbol.to_string())];
+        query.extend(time_range(start_time, end_time));
+        if let Some(limit) = limit {
+            query.push(("limit", limit.to_string()));
+        }
+        self.orders("/v5/order/history", query).await
+    }
+
+    async fn trade_history(
+        &self,
+        symbol: &str,
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<Vec<Trade>> {
+        let mut query = vec![self.category(), ("symbol", symbol.to_string())];
+        query.extend(time_range(start_time, end_time));
+        if let Some(limit) = limit {
+            query.push(("limit", limit.to_string()));
+        }
+        let response: List<BybitExecution> = self.rest.get("/v5/execution/list", &query, true).await?;
+        response.list.iter().map(BybitExecution::to_trade).collect()
+    }
+}
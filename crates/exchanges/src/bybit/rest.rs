@@ -0,0 +1,703 @@
+//! Bybit v5 REST client
+//!
+//! Every v5 response is `{"retCode", "retMsg", "result", "time"}`; a
+//! non-zero `retCode` is mapped onto the closest `ExchangeError`. Numbers
+//! arrive as strings and times as epoch-millisecond strings.
+
+use super::auth::BybitSigner;
+use super::{BybitCategory, BybitConfig};
+use crate::cassette::CassetteHandle;
+use crate::client_id::ClientIdPolicy;
+use crate::errors::{ExchangeError, Result, ResultExt};
+use crate::types::*;
+use crate::http::MonoioHttpsClient;
+use sriquant_core::prelude::*;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::debug;
+
+/// A decimal string as `Fixed`; empty strings, sent for unset fields, are zero
+pub(crate) fn parse_fixed(value: &str) -> Result<Fixed> {
+    if value.is_empty() {
+        return Ok(Fixed::ZERO);
+    }
+    Ok(Fixed::from_str_exact(value)?)
+}
+
+/// An epoch-millisecond string; zero when empty
+pub(crate) fn parse_ms(value: &str) -> Result<u64> {
+    if value.is_empty() {
+        return Ok(0);
+    }
+    value.parse().map_err(|e| ExchangeError::InvalidResponse(format!("Bad timestamp {value:?}: {e}")))
+}
+
+pub(crate) fn parse_side(side: &str) -> Result<OrderSide> {
+    match side {
+        "Buy" => Ok(OrderSide::Buy),
+        "Sell" => Ok(OrderSide::Sell),
+        other => Err(ExchangeError::InvalidResponse(format!("Unknown side {other:?}"))),
+    }
+}
+
+fn side_str(side: OrderSide) -> &'static str {
+    match side {
+        OrderSide::Buy => "Buy",
+        OrderSide::Sell => "Sell",
+    }
+}
+
+/// Kline interval to Bybit's interval code
+pub fn kline_interval(interval: &str) -> Result<&'static str> {
+    Ok(match interval {
+        "1m" => "1",
+        "3m" => "3",
+        "5m" => "5",
+        "15m" => "15",
+        "30m" => "30",
+        "1h" => "60",
+        "2h" => "120",
+        "4h" => "240",
+        "6h" => "360",
+        "12h" => "720",
+        "1d" => "D",
+        "1w" => "W",
+        other => return Err(ExchangeError::FeatureNotSupported(format!("Bybit has no {other} klines"))),
+    })
+}
+
+/// Map a non-zero `retCode` onto an `ExchangeError`
+pub fn api_error(code: i64, message: &str) -> ExchangeError {
+    match code {
+        10003 | 10004 | 10005 | 33004 => ExchangeError::AuthenticationFailed,
+        10006 | 10018 => ExchangeError::RateLimitExceeded,
+        110004 | 110007 | 110012 | 170131 => ExchangeError::InsufficientBalance,
+        110001 | 170213 => ExchangeError::OrderNotFound(message.to_string()),
+        10001 | 110000..=110099 | 170000..=170999 => ExchangeError::InvalidOrder(format!("Bybit {code}: {message}")),
+        _ => ExchangeError::InvalidResponse(format!("Bybit {code}: {message}")),
+    }
+}
+
+/// The v5 response envelope
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Envelope<T> {
+    pub ret_code: i64,
+    #[serde(default)]
+    pub ret_msg: String,
+    pub result: Option<T>,
+    #[serde(default)]
+    pub time: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct List<T> {
+    pub list: Vec<T>,
+    #[serde(default, rename = "nextPageCursor")]
+    pub next_page_cursor: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstrumentInfo {
+    pub symbol: String,
+    pub base_coin: String,
+    pub quote_coin: String,
+    pub status: String,
+    pub lot_size_filter: LotSizeFilter,
+    pub price_filter: PriceFilter,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LotSizeFilter {
+    #[serde(default)]
+    pub min_order_qty: String,
+    #[serde(default)]
+    pub max_order_qty: String,
+    /// Derivatives
+    #[serde(default)]
+    pub qty_step: String,
+    /// Spot
+    #[serde(default)]
+    pub base_precision: String,
+    /// Spot minimum order value
+    #[serde(default)]
+    pub min_order_amt: String,
+    /// Derivatives minimum order value
+    #[serde(default)]
+    pub min_notional_value: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceFilter {
+    pub tick_size: String,
+    #[serde(default)]
+    pub min_price: String,
+    #[serde(default)]
+    pub max_price: String,
+}
+
+/// Decimal places of an increment such as "0.001"
+fn precision(increment: &str) -> u32 {
+    increment.split_once('.').map(|(_, frac)| frac.trim_end_matches('0').len() as u32).unwrap_or(0)
+}
+
+impl InstrumentInfo {
+    pub fn to_symbol(&self) -> Result<Symbol> {
+        let step = if self.lot_size_filter.qty_step.is_empty() { &self.lot_size_filter.base_precision } else { &self.lot_size_filter.qty_step };
+        let min_notional = if self.lot_size_filter.min_order_amt.is_empty() {
+            &self.lot_size_filter.min_notional_value
+        } else {
+            &self.lot_size_filter.min_order_amt
+        };
+        Ok(Symbol {
+            symbol: self.symbol.clone(),
+            base_asset: self.base_coin.clone(),
+            quote_asset: self.quote_coin.clone(),
+            status: if self.status == "Trading" { "TRADING".to_string() } else { self.status.to_uppercase() },
+            min_quantity: parse_fixed(&self.lot_size_filter.min_order_qty)?,
+            max_quantity: parse_fixed(&self.lot_size_filter.max_order_qty)?,
+            quantity_precision: precision(step),
+            min_price: parse_fixed(&self.price_filter.min_price)?,
+            max_price: parse_fixed(&self.price_filter.max_price)?,
+            price_precision: precision(&self.price_filter.tick_size),
+            min_notional: parse_fixed(min_notional)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TickerInfo {
+    pub symbol: String,
+    pub last_price: String,
+    #[serde(default)]
+    pub prev_price24h: String,
+    /// Fraction, e.g. "0.0068" for +0.68%
+    #[serde(default)]
+    pub price24h_pcnt: String,
+    #[serde(default)]
+    pub high_price24h: String,
+    #[serde(default)]
+    pub low_price24h: String,
+    #[serde(default)]
+    pub volume24h: String,
+    #[serde(default)]
+    pub turnover24h: String,
+}
+
+impl TickerInfo {
+    pub fn to_ticker(&self, timestamp: u64) -> Result<Ticker> {
+        let price = parse_fixed(&self.last_price)?;
+        let previous = parse_fixed(&self.prev_price24h)?;
+        Ok(Ticker {
+            symbol: self.symbol.clone(),
+            price,
+            price_change: if previous > Fixed::ZERO { price - previous } else { Fixed::ZERO },
+            price_change_percent: (parse_fixed(&self.price24h_pcnt)? * Fixed::from_i64(100)?).round_dp(2),
+            high: parse_fixed(&self.high_price24h)?,
+            low: parse_fixed(&self.low_price24h)?,
+            volume: parse_fixed(&self.volume24h)?,
+            quote_volume: parse_fixed(&self.turnover24h)?,
+            timestamp,
+        })
+    }
+}
+
+/// `[price, size]` pairs as the order book endpoints send them
+pub(crate) fn levels(entries: &[[String; 2]]) -> Result<Vec<OrderBookLevel>> {
+    entries.iter()
+        .map(|[price, size]| Ok(OrderBookLevel { price: parse_fixed(price)?, quantity: parse_fixed(size)? }))
+        .collect()
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OrderBookResult {
+    pub s: String,
+    pub b: Vec<[String; 2]>,
+    pub a: Vec<[String; 2]>,
+    pub ts: u64,
+    pub u: u64,
+}
+
+impl OrderBookResult {
+    pub fn to_order_book(&self) -> Result<OrderBook> {
+        Ok(OrderBook { symbol: self.s.clone(), bids: levels(&self.b)?, asks: levels(&self.a)?, timestamp: self.ts, update_id: self.u })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentTrade {
+    pub exec_id: String,
+    pub symbol: String,
+    pub price: String,
+    pub size: String,
+    /// Taker side
+    pub side: String,
+    pub time: String,
+}
+
+impl RecentTrade {
+    pub fn to_trade(&self) -> Result<Trade> {
+        let side = parse_side(&self.side)?;
+        Ok(Trade {
+            id: self.exec_id.clone(),
+            symbol: self.symbol.clone(),
+            price: parse_fixed(&self.price)?,
+            quantity: parse_fixed(&self.size)?,
+            side,
+            timestamp: parse_ms(&self.time)?,
+            is_buyer_maker: side == OrderSide::Sell,
+        })
+    }
+}
+
+/// `[startTime, open, high, low, close, volume, turnover]`
+pub type KlineRow = [String; 7];
+
+pub(crate) fn kline_from_row(symbol: &str, interval: &str, row: &KlineRow) -> Result<Kline> {
+    let open_time = parse_ms(&row[0])?;
+    let length = crate::bars::interval_ms(interval)?;
+    Ok(Kline {
+        symbol: symbol.to_string(),
+        interval: interval.to_string(),
+        open_time,
+        close_time: open_time + length - 1,
+        open: parse_fixed(&row[1])?,
+        high: parse_fixed(&row[2])?,
+        low: parse_fixed(&row[3])?,
+        close: parse_fixed(&row[4])?,
+        volume: parse_fixed(&row[5])?,
+        quote_volume: parse_fixed(&row[6])?,
+        number_of_trades: 0,
+        is_closed: open_time + length <= nanos() / 1_000_000,
+    })
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct KlineResult {
+    pub symbol: String,
+    pub list: Vec<KlineRow>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletAccount {
+    pub account_type: String,
+    pub coin: Vec<WalletCoin>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletCoin {
+    pub coin: String,
+    pub wallet_balance: String,
+    #[serde(default)]
+    pub locked: String,
+}
+
+impl WalletCoin {
+    pub fn to_balance(&self) -> Result<Balance> {
+        let total = parse_fixed(&self.wallet_balance)?;
+        let locked = parse_fixed(&self.locked)?.min(total);
+        Ok(Balance { asset: self.coin.clone(), free: total - locked, locked })
+    }
+}
+
+/// Body of `POST /v5/order/create`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateOrderRequest {
+    pub category: BybitCategory,
+    pub symbol: String,
+    pub side: &'static str,
+    pub order_type: &'static str,
+    pub qty: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_in_force: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_link_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trigger_price: Option<String>,
+    /// 1 triggers on a rise to the trigger price, 2 on a fall; derivatives only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trigger_direction: Option<u8>,
+    /// "StopOrder" for spot conditional orders
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_filter: Option<&'static str>,
+    /// Spot market orders size in base coin, not Bybit's default quote coin
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub market_unit: Option<&'static str>,
+}
+
+impl CreateOrderRequest {
+    /// Map a shared order request onto a v5 create-order body
+    ///
+    /// Stops trigger against the market in the direction that makes them
+    /// stop-losses: a sell stop on a fall, a buy stop on a rise.
+    pub fn from_request(category: BybitCategory, request: &OrderRequest) -> Result<Self> {
+        let (order_type, conditional) = match request.order_type {
+            OrderType::Market => ("Market", false),
+            OrderType::Limit => ("Limit", false),
+            OrderType::StopLoss => ("Market", true),
+            OrderType::StopLossLimit => ("Limit", true),
+        };
+        let price = match order_type {
+            "Limit" => Some(request.price
+                .ok_or_else(|| ExchangeError::InvalidOrder(format!("{} order needs a price", request.order_type)))?
+                .to_string()),
+            _ => None,
+        };
+        let trigger_price = if conditional {
+            Some(request.stop_price
+                .ok_or_else(|| ExchangeError::InvalidOrder(format!("{} order needs a stop price", request.order_type)))?
+                .to_string())
+        } else {
+            None
+        };
+        if let Some(id) = &request.client_order_id {
+            ClientIdPolicy::bybit().validate(id)?;
+        }
+        let spot = category == BybitCategory::Spot;
+        Ok(Self {
+            category,
+            symbol: request.symbol.clone(),
+            side: side_str(request.side),
+            order_type,
+            qty: request.quantity.to_string(),
+            price,
+            time_in_force: request.time_in_force.map(|tif| match tif {
+                TimeInForce::GoodTillCanceled => "GTC",
+                TimeInForce::ImmediateOrCancel => "IOC",
+                TimeInForce::FillOrKill => "FOK",
+            }),
+            order_link_id: request.client_order_id.clone(),
+            trigger_direction: (conditional && !spot).then_some(match request.side {
+                OrderSide::Buy => 1,
+                OrderSide::Sell => 2,
+            }),
+            trigger_price,
+            order_filter: (conditional && spot).then_some("StopOrder"),
+            market_unit: (spot && order_type == "Market").then_some("baseCoin"),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderIds {
+    pub order_id: String,
+    #[serde(default)]
+    pub order_link_id: String,
+}
+
+/// An order from `/v5/order/realtime`, `/v5/order/history` or the `order` stream
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BybitOrder {
+    pub order_id: String,
+    #[serde(default)]
+    pub order_link_id: String,
+    pub symbol: String,
+    pub side: String,
+    pub order_type: String,
+    #[serde(default)]
+    pub price: String,
+    pub qty: String,
+    pub order_status: String,
+    #[serde(default)]
+    pub time_in_force: String,
+    #[serde(default)]
+    pub cum_exec_qty: String,
+    #[serde(default)]
+    pub avg_price: String,
+    #[serde(default)]
+    pub trigger_price: String,
+    #[serde(default)]
+    pub created_time: String,
+    #[serde(default)]
+    pub updated_time: String,
+}
+
+impl BybitOrder {
+    pub fn to_order_response(&self) -> Result<OrderResponse> {
+        let trigger_price = parse_fixed(&self.trigger_price)?;
+        let conditional = trigger_price > Fixed::ZERO;
+        let order_type = match (self.order_type.as_str(), conditional) {
+            ("Market", false) => OrderType::Market,
+            ("Limit", false) => OrderType::Limit,
+            ("Market", true) => OrderType::StopLoss,
+            ("Limit", true) => OrderType::StopLossLimit,
+            (other, _) => return Err(ExchangeError::InvalidResponse(format!("Unknown order type {other:?}"))),
+        };
+        let filled_quantity = parse_fixed(&self.cum_exec_qty)?;
+        let status = match self.order_status.as_str() {
+            "New" | "Untriggered" | "Triggered" | "Created" => OrderStatus::New,
+            "PartiallyFilled" => OrderStatus::PartiallyFilled,
+            "Filled" => OrderStatus::Filled,
+            "Cancelled" | "PartiallyFilledCanceled" | "Deactivated" => OrderStatus::Canceled,
+            "Rejected" => OrderStatus::Rejected,
+            other => return Err(ExchangeError::InvalidResponse(format!("Unknown order status {other:?}"))),
+        };
+        let price = parse_fixed(&self.price)?;
+        let average_price = parse_fixed(&self.avg_price)?;
+        Ok(OrderResponse {
+            order_id: self.order_id.clone(),
+            client_order_id: self.order_link_id.clone(),
+            symbol: self.symbol.clone(),
+            side: parse_side(&self.side)?,
+            order_type,
+            quantity: parse_fixed(&self.qty)?,
+            price: (price > Fixed::ZERO).then_some(price),
+            stop_price: conditional.then_some(trigger_price),
+            status,
+            filled_quantity,
+            average_price: (average_price > Fixed::ZERO).then_some(average_price),
+            time_in_force: match self.time_in_force.as_str() {
+                "GTC" | "PostOnly" => Some(TimeInForce::GoodTillCanceled),
+                "IOC" => Some(TimeInForce::ImmediateOrCancel),
+                "FOK" => Some(TimeInForce::FillOrKill),
+                _ => None,
+            },
+            timestamp: parse_ms(&self.created_time)?,
+            update_time: parse_ms(&self.updated_time)?,
+        })
+    }
+}
+
+/// An execution from `/v5/execution/list` or the `execution` stream
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BybitExecution {
+    pub exec_id: String,
+    pub symbol: String,
+    pub order_id: String,
+    #[serde(default)]
+    pub order_link_id: String,
+    pub side: String,
+    pub exec_price: String,
+    pub exec_qty: String,
+    #[serde(default)]
+    pub exec_fee: String,
+    pub exec_time: String,
+    #[serde(default)]
+    pub is_maker: bool,
+}
+
+impl BybitExecution {
+    pub fn to_trade(&self) -> Result<Trade> {
+        let side = parse_side(&self.side)?;
+        Ok(Trade {
+            id: self.exec_id.clone(),
+            symbol: self.symbol.clone(),
+            price: parse_fixed(&self.exec_price)?,
+            quantity: parse_fixed(&self.exec_qty)?,
+            side,
+            timestamp: parse_ms(&self.exec_time)?,
+            // The buyer made the market if we bought as maker or sold as taker
+            is_buyer_maker: (side == OrderSide::Buy) == self.is_maker,
+        })
+    }
+}
+
+/// A derivatives position from `/v5/position/list` or the `position` stream
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BybitPositionWire {
+    pub symbol: String,
+    /// "Buy", "Sell", or empty when flat
+    pub side: String,
+    pub size: String,
+    #[serde(default, alias = "entryPrice")]
+    pub avg_price: String,
+    #[serde(default)]
+    pub mark_price: String,
+    #[serde(default)]
+    pub unrealised_pnl: String,
+    #[serde(default)]
+    pub leverage: String,
+    #[serde(default)]
+    pub liq_price: String,
+    #[serde(default)]
+    pub updated_time: String,
+}
+
+/// A derivatives position
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BybitPosition {
+    pub symbol: String,
+    /// Positive long, negative short
+    pub quantity: Fixed,
+    pub entry_price: Fixed,
+    pub mark_price: Fixed,
+    pub unrealized_pnl: Fixed,
+    pub leverage: Fixed,
+    /// `None` when Bybit reports no liquidation price
+    pub liquidation_price: Option<Fixed>,
+    pub update_time: u64,
+}
+
+impl BybitPositionWire {
+    pub fn to_position(&self) -> Result<BybitPosition> {
+        let size = parse_fixed(&self.size)?;
+        let liquidation_price = parse_fixed(&self.liq_price)?;
+        Ok(BybitPosition {
+            symbol: self.symbol.clone(),
+            quantity: if self.side == "Sell" { Fixed::ZERO - size } else { size },
+            entry_price: parse_fixed(&self.avg_price)?,
+            mark_price: parse_fixed(&self.mark_price)?,
+            unrealized_pnl: parse_fixed(&self.unrealised_pnl)?,
+            leverage: parse_fixed(&self.leverage)?,
+            liquidation_price: (liquidation_price > Fixed::ZERO).then_some(liquidation_price),
+            update_time: parse_ms(&self.updated_time)?,
+        })
+    }
+}
+
+/// Bybit v5 REST client
+pub struct BybitRestClient {
+    config: BybitConfig,
+    https_client: MonoioHttpsClient,
+    signer: Option<BybitSigner>,
+}
+
+impl BybitRestClient {
+    pub fn new(config: BybitConfig) -> Result<Self> {
+        let signer = config.credentials.clone()
+            .map(|credentials| BybitSigner::new(credentials, config.recv_window_ms))
+            .transpose()?;
+        Ok(Self { config, https_client: MonoioHttpsClient::new()?, signer })
+    }
+
+    /// Record requests into, or replay them from, a cassette
+    pub fn with_cassette(mut self, cassette: CassetteHandle) -> Self {
+        self.https_client = self.https_client.with_cassette(cassette);
+        self
+    }
+
+    pub fn has_credentials(&self) -> bool {
+        self.signer.is_some()
+    }
+
+    pub fn category(&self) -> BybitCategory {
+        self.config.category
+    }
+
+    /// GET with query parameters, signed when `signed` is set
+    pub async fn get<T: DeserializeOwned>(&self, path: &str, query: &[(&str, String)], signed: bool) -> Result<T> {
+        let query_string = query.iter()
+            .map(|(k, v)| format!("{}={}", k, urlencoding::encode(v)))
+            .collect::<Vec<_>>()
+            .join("&");
+        let url = if query_string.is_empty() {
+            format!("{}{}", self.config.base_url, path)
+        } else {
+            format!("{}{}?{}", self.config.base_url, path, query_string)
+        };
+        self.send("GET", path, &url, None, signed.then_some(query_string.as_str())).await
+    }
+
+    /// Signed POST with a JSON body
+    pub async fn post<T: DeserializeOwned, B: Serialize>(&self, path: &str, body: &B) -> Result<T> {
+        let body = serde_json::to_string(body)?;
+        let url = format!("{}{}", self.config.base_url, path);
+        self.send("POST", path, &url, Some(&body), Some(&body)).await
+    }
+
+    async fn send<T: DeserializeOwned>(&self, method: &str, path: &str, url: &str, body: Option<&str>, signed_payload: Option<&str>) -> Result<T> {
+        let mut auth = Vec::new();
+        if let Some(payload) = signed_payload {
+            let signer = self.signer.as_ref()
+                .ok_or_else(|| ExchangeError::MissingCredentials("Bybit API key".to_string()))?;
+            auth = signer.auth_headers(payload)?;
+        }
+        let mut headers: HashMap<&str, &str> = auth.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+        if body.is_some() {
+            headers.insert("Content-Type", "application/json");
+        }
+
+        let response = self.https_client.request_with_headers(method, url, body, &headers).await.with_endpoint(path)?;
+        if response.status != 200 {
+            debug!("❗ Bybit rejected {} {}: {}", method, path, response.body);
+            let error = match response.status {
+                401 => ExchangeError::AuthenticationFailed,
+                403 | 429 => ExchangeError::RateLimitExceeded,
+                status => ExchangeError::HttpError(status, format!("HTTP {}: {}", status, response.body)),
+            };
+            return Err(error.with_endpoint(path));
+        }
+
+        let envelope: Envelope<T> = serde_json::from_str(&response.body)
+            .map_err(|e| ExchangeError::from(e).with_endpoint(path).with_payload(&response.body))?;
+        if envelope.ret_code != 0 {
+            debug!("❗ Bybit {} {} returned {}: {}", method, path, envelope.ret_code, envelope.ret_msg);
+            return Err(api_error(envelope.ret_code, &envelope.ret_msg).with_endpoint(path));
+        }
+        envelope.result
+            .ok_or_else(|| ExchangeError::InvalidResponse("Response has no result".to_string()).with_endpoint(path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testkit::fx;
+
+    #[test]
+    fn test_order_request_mapping() {
+        let request = OrderRequest {
+            symbol: "BTCUSDT".to_string(),
+            side: OrderSide::Sell,
+            order_type: OrderType::StopLoss,
+            quantity: fx("0.01"),
+            price: None,
+            stop_price: Some(fx("48000")),
+            time_in_force: None,
+            client_order_id: Some("sq-1".to_string()),
+        };
+        let linear = serde_json::to_value(CreateOrderRequest::from_request(BybitCategory::Linear, &request).unwrap()).unwrap();
+        assert_eq!(linear, serde_json::json!({
+            "category": "linear", "symbol": "BTCUSDT", "side": "Sell", "orderType": "Market",
+            "qty": "0.01", "orderLinkId": "sq-1", "triggerPrice": "48000", "triggerDirection": 2
+        }));
+
+        let spot = CreateOrderRequest::from_request(BybitCategory::Spot, &request).unwrap();
+        assert_eq!((spot.order_filter, spot.trigger_direction, spot.market_unit), (Some("StopOrder"), None, Some("baseCoin")));
+
+        let limit = OrderRequest { order_type: OrderType::Limit, ..request };
+        assert!(CreateOrderRequest::from_request(BybitCategory::Linear, &limit).is_err());
+    }
+
+    #[test]
+    fn test_order_and_position_mapping() {
+        let order: BybitOrder = serde_json::from_str(r#"{
+            "orderId": "fd4300ae", "orderLinkId": "sq-1", "symbol": "ETHUSDT", "side": "Buy", "orderType": "Limit",
+            "price": "1600.00", "qty": "0.10", "orderStatus": "PartiallyFilled", "timeInForce": "GTC",
+            "cumExecQty": "0.04", "avgPrice": "1599.5", "triggerPrice": "", "createdTime": "1672211918471", "updatedTime": "1672211918472"
+        }"#).unwrap();
+        let response = order.to_order_response().unwrap();
+        assert_eq!(response.status, OrderStatus::PartiallyFilled);
+        assert_eq!(response.order_type, OrderType::Limit);
+        assert_eq!(response.filled_quantity, fx("0.04"));
+        assert_eq!(response.update_time, 1_672_211_918_472);
+
+        let position: BybitPositionWire = serde_json::from_str(r#"{
+            "symbol": "BTCUSDT", "side": "Sell", "size": "0.5", "entryPrice": "30000", "markPrice": "29000",
+            "unrealisedPnl": "500", "leverage": "10", "liqPrice": "", "updatedTime": "1672211918471"
+        }"#).unwrap();
+        let position = position.to_position().unwrap();
+        assert_eq!(position.quantity, fx("-0.5"));
+        assert_eq!(position.entry_price, fx("30000"));
+        assert_eq!(position.liquidation_price, None);
+
+        assert!(matches!(api_error(110007, "ab not enough for new order"), ExchangeError::InsufficientBalance));
+        assert!(matches!(api_error(10006, "Too many visits"), ExchangeError::RateLimitExceeded));
+    }
+}
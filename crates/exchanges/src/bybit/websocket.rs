@@ -0,0 +1,522 @@
+//! Bybit v5 WebSocket streams
+//!
+//! `BybitPublicStream` implements `StreamingExchange` over the public
+//! `tickers`, `publicTrade`, `orderbook` and `kline` topics of one category.
+//! Order books arrive as a snapshot followed by deltas with absolute sizes
+//! (zero deletes); a delta with `u == 1` means Bybit restarted the book and
+//! is applied as a snapshot. Derivatives tickers send only changed fields
+//! after the first message, so the last ticker is kept and patched.
+//!
+//! `BybitPrivateStream` authenticates and yields order, position and
+//! execution updates mapped onto the shared types.
+//!
+//! Bybit drops connections that stay silent; call `heartbeat` every 20
+//! seconds.
+
+use super::auth::BybitSigner;
+use super::rest::{BybitExecution, BybitOrder, BybitPosition, BybitPositionWire, RecentTrade, kline_from_row, parse_fixed, parse_ms};
+use super::BybitConfig;
+use crate::cassette::CassetteHandle;
+use crate::errors::{ExchangeError, Result, ResultExt};
+use crate::traits::StreamingExchange;
+use crate::types::*;
+use crate::websocket::MonoioWebSocket;
+use sriquant_core::prelude::*;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use tracing::{debug, info};
+use url::Url;
+
+/// Order book depths Bybit publishes; a request is rounded up to the next one
+const BOOK_DEPTHS: [u32; 4] = [1, 50, 200, 500];
+
+#[derive(Debug, Deserialize)]
+struct Message {
+    #[serde(default)]
+    topic: String,
+    #[serde(default, rename = "type")]
+    kind: String,
+    #[serde(default)]
+    ts: u64,
+    #[serde(default)]
+    data: Value,
+    #[serde(default)]
+    op: String,
+    success: Option<bool>,
+    #[serde(default)]
+    ret_msg: String,
+}
+
+/// Reply to an `op` request; returns whether the message was one
+fn check_op_reply(message: &Message) -> Result<bool> {
+    if message.op.is_empty() {
+        return Ok(false);
+    }
+    if message.success == Some(false) {
+        return Err(ExchangeError::InvalidResponse(format!("Bybit {} failed: {}", message.op, message.ret_msg)));
+    }
+    debug!("Bybit {} acknowledged", message.op);
+    Ok(true)
+}
+
+#[derive(Debug, Deserialize)]
+struct TradeData {
+    #[serde(rename = "T")]
+    time: u64,
+    s: String,
+    #[serde(rename = "S")]
+    side: String,
+    v: String,
+    p: String,
+    i: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BookData {
+    s: String,
+    b: Vec<[String; 2]>,
+    a: Vec<[String; 2]>,
+    u: u64,
+}
+
+/// Ticker fields; every one but the symbol may be missing from a delta
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TickerData {
+    symbol: String,
+    last_price: Option<String>,
+    prev_price24h: Option<String>,
+    price24h_pcnt: Option<String>,
+    high_price24h: Option<String>,
+    low_price24h: Option<String>,
+    volume24h: Option<String>,
+    turnover24h: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KlineData {
+    start: u64,
+    open: String,
+    high: String,
+    low: String,
+    close: String,
+    volume: String,
+    turnover: String,
+    confirm: bool,
+}
+
+/// Local book for one symbol
+#[derive(Debug, Default)]
+struct LocalBook {
+    bids: BTreeMap<Fixed, Fixed>,
+    asks: BTreeMap<Fixed, Fixed>,
+}
+
+impl LocalBook {
+    fn apply(side: &mut BTreeMap<Fixed, Fixed>, levels: &[[String; 2]]) -> Result<()> {
+        for [price, size] in levels {
+            let price = parse_fixed(price)?;
+            let size = parse_fixed(size)?;
+            if size.is_zero() {
+                side.remove(&price);
+            } else {
+                side.insert(price, size);
+            }
+        }
+        Ok(())
+    }
+
+    fn snapshot(&self, symbol: &str, levels: usize, timestamp: u64, update_id: u64) -> OrderBook {
+        let level = |(price, quantity): (&Fixed, &Fixed)| OrderBookLevel { price: *price, quantity: *quantity };
+        OrderBook {
+            symbol: symbol.to_string(),
+            bids: self.bids.iter().rev().take(levels).map(level).collect(),
+            asks: self.asks.iter().take(levels).map(level).collect(),
+            timestamp,
+            update_id,
+        }
+    }
+}
+
+/// Kline interval to the code used in `kline.{code}.{symbol}` topics, and back
+fn interval_code(interval: &str) -> Result<&'static str> {
+    super::rest::kline_interval(interval)
+}
+
+fn interval_from_code(code: &str) -> Option<&'static str> {
+    ["1m", "3m", "5m", "15m", "30m", "1h", "2h", "4h", "6h", "12h", "1d", "1w"].into_iter()
+        .find(|interval| interval_code(interval).is_ok_and(|c| c == code))
+}
+
+/// Bybit public market data for one category
+pub struct BybitPublicStream {
+    config: BybitConfig,
+    websocket: Option<MonoioWebSocket>,
+    cassette: Option<CassetteHandle>,
+    /// Topic to symbol
+    topics: HashMap<String, String>,
+    books: HashMap<String, LocalBook>,
+    book_levels: HashMap<String, usize>,
+    tickers: HashMap<String, Ticker>,
+    pending_events: VecDeque<MarketData>,
+}
+
+impl BybitPublicStream {
+    pub fn new(config: BybitConfig) -> Self {
+        Self {
+            config,
+            websocket: None,
+            cassette: None,
+            topics: HashMap::new(),
+            books: HashMap::new(),
+            book_levels: HashMap::new(),
+            tickers: HashMap::new(),
+            pending_events: VecDeque::new(),
+        }
+    }
+
+    /// Record sessions into, or replay them from, a cassette
+    pub fn with_cassette(mut self, cassette: CassetteHandle) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+
+    /// Keep the connection alive; Bybit expects this every 20 seconds
+    pub async fn heartbeat(&mut self) -> Result<()> {
+        send_json(self.websocket.as_mut(), &serde_json::json!({ "op": "ping" })).await
+    }
+
+    async fn subscribe_topic(&mut self, topic: String, symbol: &str) -> Result<()> {
+        if self.topics.contains_key(&topic) {
+            return Ok(());
+        }
+        send_json(self.websocket.as_mut(), &serde_json::json!({ "op": "subscribe", "args": [topic] })).await?;
+        info!("📊 Subscribed to Bybit topic: {}", topic);
+        self.topics.insert(topic, symbol.to_string());
+        Ok(())
+    }
+
+    fn process_message(&mut self, text: &str) -> Result<()> {
+        let message: Message = serde_json::from_str(text).map_err(|e| ExchangeError::from(e).with_payload(text))?;
+        if check_op_reply(&message)? {
+            return Ok(());
+        }
+        let (kind, rest) = message.topic.split_once('.').unwrap_or((message.topic.as_str(), ""));
+        match kind {
+            "publicTrade" => {
+                let trades = Vec::<TradeData>::deserialize(&message.data).map_err(ExchangeError::from).with_endpoint(&message.topic)?;
+                for trade in trades {
+                    let trade = RecentTrade { exec_id: trade.i, symbol: trade.s, price: trade.p, size: trade.v, side: trade.side, time: trade.time.to_string() };
+                    self.pending_events.push_back(MarketData::Trade(trade.to_trade()?));
+                }
+            }
+            "orderbook" => {
+                let data = BookData::deserialize(&message.data).map_err(ExchangeError::from).with_endpoint(&message.topic)?;
+                let book = self.books.entry(data.s.clone()).or_default();
+                if message.kind == "snapshot" || data.u == 1 {
+                    *book = LocalBook::default();
+                }
+                LocalBook::apply(&mut book.bids, &data.b).with_symbol(&data.s)?;
+                LocalBook::apply(&mut book.asks, &data.a).with_symbol(&data.s)?;
+                let levels = self.book_levels.get(&data.s).copied().unwrap_or(usize::MAX);
+                let snapshot = book.snapshot(&data.s, levels, message.ts, data.u);
+                self.pending_events.push_back(MarketData::OrderBook(snapshot));
+            }
+            "tickers" => {
+                let data = TickerData::deserialize(&message.data).map_err(ExchangeError::from).with_endpoint(&message.topic)?;
+                let ticker = self.patch_ticker(data, message.ts)?;
+                self.pending_events.push_back(MarketData::Ticker(ticker));
+            }
+            "kline" => {
+                let (code, symbol) = rest.split_once('.').unwrap_or_default();
+                let interval = interval_from_code(code)
+                    .ok_or_else(|| ExchangeError::UnsupportedStream(message.topic.clone()))?;
+                let klines = Vec::<KlineData>::deserialize(&message.data).map_err(ExchangeError::from).with_endpoint(&message.topic)?;
+                for data in klines {
+                    let row = [data.start.to_string(), data.open, data.high, data.low, data.close, data.volume, data.turnover];
+                    let mut kline = kline_from_row(symbol, interval, &row)?;
+                    kline.is_closed = data.confirm;
+                    self.pending_events.push_back(MarketData::Kline(kline));
+                }
+            }
+            _ => debug!("Ignoring Bybit message on {:?}", message.topic),
+        }
+        Ok(())
+    }
+
+    /// Merge a ticker snapshot or delta into the last known ticker
+    fn patch_ticker(&mut self, data: TickerData, timestamp: u64) -> Result<Ticker> {
+        let ticker = self.tickers.entry(data.symbol.clone()).or_insert_with(|| Ticker {
+            symbol: data.symbol.clone(),
+            price: Fixed::ZERO,
+            price_change: Fixed::ZERO,
+            price_change_percent: Fixed::ZERO,
+            high: Fixed::ZERO,
+            low: Fixed::ZERO,
+            volume: Fixed::ZERO,
+            quote_volume: Fixed::ZERO,
+            timestamp: 0,
+        });
+        let set = |field: &mut Fixed, value: &Option<String>| -> Result<()> {
+            if let Some(value) = value {
+                *field = parse_fixed(value)?;
+            }
+            Ok(())
+        };
+        set(&mut ticker.price, &data.last_price)?;
+        set(&mut ticker.high, &data.high_price24h)?;
+        set(&mut ticker.low, &data.low_price24h)?;
+        set(&mut ticker.volume, &data.volume24h)?;
+        set(&mut ticker.quote_volume, &data.turnover24h)?;
+        if let Some(pct) = &data.price24h_pcnt {
+            ticker.price_change_percent = (parse_fixed(pct)? * Fixed::from_i64(100)?).round_dp(2);
+        }
+        if let Some(previous) = &data.prev_price24h {
+            let previous = parse_fixed(previous)?;
+            ticker.price_change = if previous > Fixed::ZERO { ticker.price - previous } else { Fixed::ZERO };
+        }
+        ticker.timestamp = timestamp;
+        Ok(ticker.clone())
+    }
+}
+
+async fn send_json(websocket: Option<&mut MonoioWebSocket>, message: &Value) -> Result<()> {
+    let websocket = websocket.ok_or_else(|| ExchangeError::NetworkError("WebSocket not connected".to_string()))?;
+    websocket.send_text(message.to_string()).await
+}
+
+async fn connect(url: &str, cassette: Option<&CassetteHandle>) -> Result<MonoioWebSocket> {
+    let url = Url::parse(url).map_err(|e| ExchangeError::InvalidUrl(e.to_string()))?;
+    MonoioWebSocket::connect_with_cassette(url, cassette).await
+}
+
+#[async_trait(?Send)]
+impl StreamingExchange for BybitPublicStream {
+    async fn connect(&mut self) -> Result<()> {
+        if self.websocket.is_some() {
+            return Ok(());
+        }
+        let timer = PerfTimer::start("bybit_ws_connect".to_string());
+        let url = self.config.public_ws_url();
+        self.websocket = Some(connect(&url, self.cassette.as_ref()).await?);
+        timer.log_elapsed();
+        info!("✅ Connected to Bybit public WebSocket: {}", url);
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        self.topics.clear();
+        self.books.clear();
+        self.tickers.clear();
+        self.pending_events.clear();
+        if let Some(mut websocket) = self.websocket.take() {
+            websocket.close(1000, "Normal closure".to_string()).await?;
+        }
+        Ok(())
+    }
+
+    async fn subscribe_ticker(&mut self, symbol: &str) -> Result<()> {
+        self.subscribe_topic(format!("tickers.{symbol}"), symbol).await
+    }
+
+    async fn subscribe_trades(&mut self, symbol: &str) -> Result<()> {
+        self.subscribe_topic(format!("publicTrade.{symbol}"), symbol).await
+    }
+
+    async fn subscribe_order_book(&mut self, symbol: &str, levels: Option<u32>) -> Result<()> {
+        let requested = levels.unwrap_or(50);
+        let depth = BOOK_DEPTHS.into_iter().find(|&d| d >= requested).unwrap_or(500);
+        self.book_levels.insert(symbol.to_string(), requested as usize);
+        self.subscribe_topic(format!("orderbook.{depth}.{symbol}"), symbol).await
+    }
+
+    async fn subscribe_klines(&mut self, symbol: &str, interval: &str) -> Result<()> {
+        self.subscribe_topic(format!("kline.{}.{}", interval_code(interval)?, symbol), symbol).await
+    }
+
+    async fn unsubscribe(&mut self, stream: &str) -> Result<()> {
+        let Some(symbol) = self.topics.remove(stream) else {
+            return Ok(());
+        };
+        send_json(self.websocket.as_mut(), &serde_json::json!({ "op": "unsubscribe", "args": [stream] })).await?;
+        if stream.starts_with("orderbook.") {
+            self.books.remove(&symbol);
+            self.book_levels.remove(&symbol);
+        }
+        info!("❌ Unsubscribed from stream: {}", stream);
+        Ok(())
+    }
+
+    async fn next_event(&mut self) -> Result<Option<MarketData>> {
+        loop {
+            if let Some(event) = self.pending_events.pop_front() {
+                return Ok(Some(event));
+            }
+            let websocket = self.websocket.as_mut()
+                .ok_or_else(|| ExchangeError::NetworkError("WebSocket not connected".to_string()))?;
+            let message = websocket.receive_text().await?;
+            self.process_message(&message)?;
+        }
+    }
+
+    fn connection_status(&self) -> ConnectionStatus {
+        match &self.websocket {
+            Some(websocket) if websocket.is_connected() => ConnectionStatus::Connected,
+            _ => ConnectionStatus::Disconnected,
+        }
+    }
+
+    fn subscriptions(&self) -> Vec<Subscription> {
+        self.topics.iter()
+            .map(|(topic, symbol)| Subscription {
+                stream: topic.clone(),
+                symbol: symbol.clone(),
+                status: SubscriptionStatus::Subscribed,
+                last_update: 0,
+            })
+            .collect()
+    }
+}
+
+/// An update from the private stream
+#[derive(Debug, Clone)]
+pub enum BybitPrivateEvent {
+    Order(OrderResponse),
+    Position(BybitPosition),
+    /// One fill of one of our orders
+    Execution(Trade),
+}
+
+/// Bybit private order, position and execution stream
+pub struct BybitPrivateStream {
+    config: BybitConfig,
+    signer: BybitSigner,
+    websocket: Option<MonoioWebSocket>,
+    cassette: Option<CassetteHandle>,
+    pending_events: VecDeque<BybitPrivateEvent>,
+}
+
+impl BybitPrivateStream {
+    pub fn new(config: BybitConfig) -> Result<Self> {
+        let credentials = config.credentials.clone()
+            .ok_or_else(|| ExchangeError::MissingCredentials("Bybit API key".to_string()))?;
+        let signer = BybitSigner::new(credentials, config.recv_window_ms)?;
+        Ok(Self { config, signer, websocket: None, cassette: None, pending_events: VecDeque::new() })
+    }
+
+    /// Record sessions into, or replay them from, a cassette
+    pub fn with_cassette(mut self, cassette: CassetteHandle) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+
+    /// Connect, authenticate and subscribe to orders, positions and executions
+    pub async fn connect(&mut self) -> Result<()> {
+        let timer = PerfTimer::start("bybit_private_connect".to_string());
+        let mut websocket = connect(&self.config.private_ws_url, self.cassette.as_ref()).await?;
+        websocket.send_text(self.signer.websocket_auth()?.to_string()).await?;
+        let reply: Message = serde_json::from_str(&websocket.receive_text().await?)?;
+        if reply.op != "auth" || reply.success != Some(true) {
+            return Err(ExchangeError::AuthenticationFailed);
+        }
+        websocket.send_text(serde_json::json!({ "op": "subscribe", "args": ["order", "position", "execution"] }).to_string()).await?;
+        self.websocket = Some(websocket);
+        timer.log_elapsed();
+        info!("✅ Connected to Bybit private WebSocket");
+        Ok(())
+    }
+
+    pub async fn heartbeat(&mut self) -> Result<()> {
+        send_json(self.websocket.as_mut(), &serde_json::json!({ "op": "ping" })).await
+    }
+
+    pub async fn next_event(&mut self) -> Result<BybitPrivateEvent> {
+        loop {
+            if let Some(event) = self.pending_events.pop_front() {
+                return Ok(event);
+            }
+            let websocket = self.websocket.as_mut()
+                .ok_or_else(|| ExchangeError::NetworkError("WebSocket not connected".to_string()))?;
+            let text = websocket.receive_text().await?;
+            self.process_message(&text)?;
+        }
+    }
+
+    fn process_message(&mut self, text: &str) -> Result<()> {
+        let message: Message = serde_json::from_str(text).map_err(|e| ExchangeError::from(e).with_payload(text))?;
+        if check_op_reply(&message)? {
+            return Ok(());
+        }
+        // Topics are "order" or, per category, "order.linear" and so on
+        let topic = message.topic.split('.').next().unwrap_or_default();
+        match topic {
+            "order" => {
+                for order in Vec::<BybitOrder>::deserialize(&message.data).map_err(ExchangeError::from).with_endpoint("order")? {
+                    self.pending_events.push_back(BybitPrivateEvent::Order(order.to_order_response()?));
+                }
+            }
+            "position" => {
+                for position in Vec::<BybitPositionWire>::deserialize(&message.data).map_err(ExchangeError::from).with_endpoint("position")? {
+                    self.pending_events.push_back(BybitPrivateEvent::Position(position.to_position()?));
+                }
+            }
+            "execution" => {
+                for execution in Vec::<BybitExecution>::deserialize(&message.data).map_err(ExchangeError::from).with_endpoint("execution")? {
+                    self.pending_events.push_back(BybitPrivateEvent::Execution(execution.to_trade()?));
+                }
+            }
+            _ => debug!("Ignoring Bybit private message on {:?} at {}", message.topic, parse_ms(&message.ts.to_string())?),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testkit::fx;
+    use crate::bybit::BybitCategory;
+    use crate::cassette::{Cassette, WsEvent, WsSession};
+
+    #[monoio::test]
+    async fn test_public_book_and_ticker_deltas() {
+        let received = |text: &str| WsEvent::Received(text.to_string());
+        let cassette = CassetteHandle::replay(Cassette {
+            http: Vec::new(),
+            websocket: vec![WsSession {
+                url: "wss://stream.bybit.com/v5/public/linear".to_string(),
+                events: vec![
+                    WsEvent::Sent("subscribe orderbook".to_string()),
+                    WsEvent::Sent("subscribe tickers".to_string()),
+                    received(r#"{"success":true,"ret_msg":"","conn_id":"c1","op":"subscribe"}"#),
+                    received(r#"{"topic":"orderbook.1.BTCUSDT","type":"snapshot","ts":1000,"data":{"s":"BTCUSDT","b":[["30000.0","1.5"]],"a":[["30000.5","2"]],"u":100,"seq":1}}"#),
+                    received(r#"{"topic":"orderbook.1.BTCUSDT","type":"delta","ts":1001,"data":{"s":"BTCUSDT","b":[["30000.0","0"],["29999.5","3"]],"a":[],"u":101,"seq":2}}"#),
+                    received(r#"{"topic":"tickers.BTCUSDT","type":"snapshot","ts":1002,"data":{"symbol":"BTCUSDT","lastPrice":"30000.5","prevPrice24h":"29000","price24hPcnt":"0.034500","highPrice24h":"30100","lowPrice24h":"28900","volume24h":"30","turnover24h":"900000"}}"#),
+                    received(r#"{"topic":"tickers.BTCUSDT","type":"delta","ts":1003,"data":{"symbol":"BTCUSDT","lastPrice":"30001.0"}}"#),
+                ],
+            }],
+        });
+
+        let mut stream = BybitPublicStream::new(BybitConfig::default().with_category(BybitCategory::Linear)).with_cassette(cassette);
+        stream.connect().await.unwrap();
+        stream.subscribe_order_book("BTCUSDT", Some(1)).await.unwrap();
+        stream.subscribe_ticker("BTCUSDT").await.unwrap();
+
+        let Some(MarketData::OrderBook(book)) = stream.next_event().await.unwrap() else { panic!("expected book") };
+        assert_eq!(book.best_bid(), Some(fx("30000")));
+        let Some(MarketData::OrderBook(book)) = stream.next_event().await.unwrap() else { panic!("expected book") };
+        assert_eq!(book.bids, vec![OrderBookLevel { price: fx("29999.5"), quantity: fx("3") }]);
+        assert_eq!(book.update_id, 101);
+
+        let Some(MarketData::Ticker(ticker)) = stream.next_event().await.unwrap() else { panic!("expected ticker") };
+        assert_eq!(ticker.price_change_percent, fx("3.45"));
+        // The delta only moves the price; the 24h fields carry over
+        let Some(MarketData::Ticker(ticker)) = stream.next_event().await.unwrap() else { panic!("expected ticker") };
+        assert_eq!(ticker.price, fx("30001"));
+        assert_eq!(ticker.high, fx("30100"));
+        assert_eq!(ticker.timestamp, 1003);
+    }
+}
@@ -0,0 +1,391 @@
+//! Cassette-style record/replay of HTTPS and WSS traffic
+//!
+//! In `Record` mode the HTTPS client and WebSocket capture every request,
+//! response and text frame into a `Cassette`, which can be saved as JSON and
+//! committed alongside the tests. In `Replay` mode no sockets are opened:
+//! requests are answered from the cassette and WebSocket sessions yield the
+//! recorded frames, so integration suites run deterministically in CI.
+//!
+//! Secrets never reach the cassette: signatures and timestamps are stripped
+//! from URLs (which also makes signed requests match on replay), API keys are
+//! not recorded, and listen keys are replaced with a placeholder everywhere.
+
+use crate::errors::{ExchangeError, Result};
+use crate::http::HttpResponse;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tracing::{debug, info};
+use url::Url;
+
+/// Placeholder for scrubbed secrets
+pub const SCRUBBED: &str = "SCRUBBED";
+
+/// Query parameters dropped because they change on every request
+const VOLATILE_PARAMS: &[&str] = &["signature", "timestamp"];
+
+/// Query parameters and JSON keys whose values are secrets
+const SECRET_KEYS: &[&str] = &["apiKey", "secretKey", "listenKey", "signature"];
+
+/// Whether traffic is captured or served from the cassette
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    Record,
+    Replay,
+}
+
+/// One recorded HTTPS request/response pair
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HttpInteraction {
+    pub method: String,
+    /// Scrubbed, normalized URL used for matching
+    pub url: String,
+    pub request_body: Option<String>,
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub response_body: String,
+}
+
+/// A text frame sent or received on a WebSocket session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "direction", content = "text")]
+pub enum WsEvent {
+    Sent(String),
+    Received(String),
+}
+
+/// One recorded WebSocket connection
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WsSession {
+    /// Scrubbed handshake URL
+    pub url: String,
+    pub events: Vec<WsEvent>,
+}
+
+/// Recorded traffic, serializable as JSON
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cassette {
+    pub http: Vec<HttpInteraction>,
+    pub websocket: Vec<WsSession>,
+}
+
+impl Cassette {
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        std::fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+}
+
+/// Cassette plus replay cursors
+#[derive(Debug, Default)]
+struct CassetteState {
+    cassette: Cassette,
+    http_used: Vec<bool>,
+    ws_used: Vec<bool>,
+    ws_cursors: Vec<usize>,
+}
+
+/// Shared handle passed to the HTTPS client and WebSockets
+#[derive(Debug, Clone)]
+pub struct CassetteHandle {
+    mode: CassetteMode,
+    state: Arc<Mutex<CassetteState>>,
+}
+
+impl CassetteHandle {
+    /// Start an empty cassette for recording
+    pub fn record() -> Self {
+        Self::with_mode(CassetteMode::Record, Cassette::default())
+    }
+
+    /// Serve traffic from an existing cassette
+    pub fn replay(cassette: Cassette) -> Self {
+        Self::with_mode(CassetteMode::Replay, cassette)
+    }
+
+    /// Load a cassette file for replay
+    pub fn replay_file(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self::replay(Cassette::load(path)?))
+    }
+
+    fn with_mode(mode: CassetteMode, cassette: Cassette) -> Self {
+        let state = CassetteState {
+            http_used: vec![false; cassette.http.len()],
+            ws_used: vec![false; cassette.websocket.len()],
+            ws_cursors: vec![0; cassette.websocket.len()],
+            cassette,
+        };
+        Self {
+            mode,
+            state: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    pub fn is_replay(&self) -> bool {
+        self.mode == CassetteMode::Replay
+    }
+
+    /// Snapshot of the recorded traffic
+    pub fn cassette(&self) -> Cassette {
+        self.lock().cassette.clone()
+    }
+
+    /// Write the recorded traffic to a file
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let cassette = self.cassette();
+        cassette.save(&path)?;
+        info!("📼 Saved cassette with {} HTTP and {} WS interactions", cassette.http.len(), cassette.websocket.len());
+        Ok(())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CassetteState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Capture an HTTPS exchange
+    pub fn record_http(&self, method: &str, url: &str, body: Option<&str>, response: &HttpResponse) {
+        let interaction = HttpInteraction {
+            method: method.to_string(),
+            url: scrub_url(url),
+            request_body: body.map(scrub_form),
+            status: response.status,
+            headers: response.headers.clone(),
+            response_body: scrub_body(&response.body),
+        };
+
+        let mut state = self.lock();
+        state.cassette.http.push(interaction);
+        state.http_used.push(true);
+    }
+
+    /// Answer a request from the first unused matching interaction
+    pub fn replay_http(&self, method: &str, url: &str, body: Option<&str>) -> Result<HttpResponse> {
+        let url = scrub_url(url);
+        let body = body.map(scrub_form);
+
+        let mut state = self.lock();
+        let index = (0..state.cassette.http.len())
+            .find(|&i| {
+                let recorded = &state.cassette.http[i];
+                !state.http_used[i] && recorded.method == method && recorded.url == url && recorded.request_body == body
+            })
+            .ok_or_else(|| ExchangeError::ConfigurationError(format!("No recorded interaction for {method} {url}")))?;
+
+        state.http_used[index] = true;
+        let recorded = &state.cassette.http[index];
+        debug!("📼 Replaying {} {}", method, url);
+
+        Ok(HttpResponse {
+            status: recorded.status,
+            headers: recorded.headers.clone(),
+            body: recorded.response_body.clone(),
+        })
+    }
+
+    /// Begin a WebSocket session, returning its index
+    ///
+    /// Records a new session, or claims the first unused recorded session
+    /// with a matching handshake URL.
+    pub fn open_ws_session(&self, url: &str) -> Result<usize> {
+        let url = scrub_url(url);
+        let mut state = self.lock();
+
+        match self.mode {
+            CassetteMode::Record => {
+                state.cassette.websocket.push(WsSession { url, events: Vec::new() });
+                state.ws_used.push(true);
+                state.ws_cursors.push(0);
+                Ok(state.cassette.websocket.len() - 1)
+            }
+            CassetteMode::Replay => {
+                let index = (0..state.cassette.websocket.len())
+                    .find(|&i| !state.ws_used[i] && state.cassette.websocket[i].url == url)
+                    .ok_or_else(|| ExchangeError::ConnectionFailed(format!("No recorded WebSocket session for {url}")))?;
+                state.ws_used[index] = true;
+                debug!("📼 Replaying WebSocket session {}", url);
+                Ok(index)
+            }
+        }
+    }
+
+    /// Capture a text frame on a recording session
+    pub fn record_ws(&self, session: usize, event: WsEvent) {
+        let event = match event {
+            WsEvent::Sent(text) => WsEvent::Sent(scrub_body(&text)),
+            WsEvent::Received(text) => WsEvent::Received(scrub_body(&text)),
+        };
+        if let Some(session) = self.lock().cassette.websocket.get_mut(session) {
+            session.events.push(event);
+        }
+    }
+
+    /// Consume a sent frame on a replay session
+    ///
+    /// Sent frames are not required to match byte-for-byte (request ids may
+    /// differ); a mismatch is only logged.
+    pub fn replay_ws_sent(&self, session: usize, text: &str) {
+        let mut state = self.lock();
+        let cursor = state.ws_cursors[session];
+        if let Some(WsEvent::Sent(recorded)) = state.cassette.websocket[session].events.get(cursor) {
+            if recorded != &scrub_body(text) {
+                debug!("📼 Sent frame differs from recording: {} vs {}", text, recorded);
+            }
+            state.ws_cursors[session] += 1;
+        }
+    }
+
+    /// Next received frame on a replay session, skipping recorded sends
+    pub fn replay_ws_received(&self, session: usize) -> Option<String> {
+        let mut state = self.lock();
+        loop {
+            let cursor = state.ws_cursors[session];
+            let event = state.cassette.websocket[session].events.get(cursor)?.clone();
+            state.ws_cursors[session] += 1;
+            if let WsEvent::Received(text) = event {
+                return Some(text);
+            }
+        }
+    }
+}
+
+/// Normalize a URL for recording and matching: drop volatile parameters and
+/// scrub secrets in the query and in `/ws/<listenKey>` paths
+pub fn scrub_url(url: &str) -> String {
+    let Ok(mut parsed) = Url::parse(url) else {
+        return url.to_string();
+    };
+
+    if let Some(query) = parsed.query().map(scrub_form) {
+        parsed.set_query(if query.is_empty() { None } else { Some(&query) });
+    }
+
+    // User data streams connect to /ws/<listenKey>; market streams contain '@'
+    let path = parsed.path().to_string();
+    if let Some(last) = path.strip_prefix("/ws/")
+        && !last.is_empty()
+        && !last.contains('@')
+        && !last.starts_with('!')
+    {
+        parsed.set_path(&format!("/ws/{SCRUBBED}"));
+    }
+
+    parsed.to_string()
+}
+
+/// Scrub a form-encoded string (query or body)
+pub fn scrub_form(form: &str) -> String {
+    form.split('&')
+        .filter(|pair| !pair.is_empty())
+        .filter_map(|pair| {
+            let key = pair.split_once('=').map_or(pair, |(k, _)| k);
+            if VOLATILE_PARAMS.contains(&key) {
+                None
+            } else if SECRET_KEYS.contains(&key) {
+                Some(format!("{key}={SCRUBBED}"))
+            } else {
+                Some(pair.to_string())
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Scrub secret values in a JSON body; non-JSON bodies are kept as-is
+pub fn scrub_body(body: &str) -> String {
+    match serde_json::from_str::<Value>(body) {
+        Ok(mut value) => {
+            if scrub_json(&mut value) {
+                value.to_string()
+            } else {
+                body.to_string()
+            }
+        }
+        Err(_) => body.to_string(),
+    }
+}
+
+/// Replace secret values in place; returns whether anything changed
+fn scrub_json(value: &mut Value) -> bool {
+    match value {
+        Value::Object(map) => {
+            let mut changed = false;
+            for (key, v) in map.iter_mut() {
+                if SECRET_KEYS.contains(&key.as_str()) && v.is_string() {
+                    *v = Value::String(SCRUBBED.to_string());
+                    changed = true;
+                } else {
+                    changed |= scrub_json(v);
+                }
+            }
+            changed
+        }
+        Value::Array(items) => items.iter_mut().fold(false, |changed, v| scrub_json(v) | changed),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scrubbing() {
+        assert_eq!(
+            scrub_url("https://api.binance.com/api/v3/order?symbol=BTCUSDT&timestamp=1&signature=abc"),
+            "https://api.binance.com/api/v3/order?symbol=BTCUSDT"
+        );
+        assert_eq!(
+            scrub_url("wss://stream.binance.com:9443/ws/pqia91ma19a5s61cv6a81va65sdf19v8a65a1a5s61cv6a81va65sdf19v8a65a1"),
+            format!("wss://stream.binance.com:9443/ws/{SCRUBBED}")
+        );
+        assert_eq!(scrub_url("wss://stream.binance.com:9443/ws/btcusdt@trade"), "wss://stream.binance.com:9443/ws/btcusdt@trade");
+        assert_eq!(scrub_body(r#"{"listenKey":"secret"}"#), format!(r#"{{"listenKey":"{SCRUBBED}"}}"#));
+        assert_eq!(scrub_form("apiKey=k&symbol=ETHUSDT"), format!("apiKey={SCRUBBED}&symbol=ETHUSDT"));
+    }
+
+    #[test]
+    fn test_record_then_replay() {
+        let recorder = CassetteHandle::record();
+        let response = HttpResponse {
+            status: 200,
+            headers: vec![("x-mbx-used-weight-1m".to_string(), "2".to_string())],
+            body: r#"{"serverTime":1700000000000}"#.to_string(),
+        };
+        recorder.record_http("GET", "https://api.binance.com/api/v3/time?timestamp=5", None, &response);
+
+        let session = recorder.open_ws_session("wss://stream.binance.com:9443/ws").unwrap();
+        recorder.record_ws(session, WsEvent::Sent("sub".to_string()));
+        recorder.record_ws(session, WsEvent::Received("a".to_string()));
+
+        let json = recorder.cassette().to_json().unwrap();
+        let replay = CassetteHandle::replay(Cassette::from_json(&json).unwrap());
+
+        // Different timestamp still matches; each interaction is used once
+        let replayed = replay.replay_http("GET", "https://api.binance.com/api/v3/time?timestamp=9", None).unwrap();
+        assert_eq!(replayed.body, response.body);
+        assert!(replay.replay_http("GET", "https://api.binance.com/api/v3/time", None).is_err());
+
+        let session = replay.open_ws_session("wss://stream.binance.com:9443/ws").unwrap();
+        replay.replay_ws_sent(session, "sub");
+        assert_eq!(replay.replay_ws_received(session).as_deref(), Some("a"));
+        assert_eq!(replay.replay_ws_received(session), None);
+    }
+}
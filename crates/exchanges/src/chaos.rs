@@ -0,0 +1,230 @@
+//! Fault injection for HTTPS and WebSocket traffic
+//!
+//! Reconnect, resync and order-retry paths only run when the network
+//! misbehaves, which it rarely does in CI. A `FaultInjector` attached to
+//! `MonoioHttpsClient` or `MonoioWebSocket` makes it misbehave on purpose,
+//! per message and with configured probabilities:
+//!
+//! - delay: the message is held back for a random time
+//! - drop: a request is sent but its response lost, an outgoing frame is
+//!   never written, an incoming frame is skipped
+//! - partial write: the connection breaks part way through a message
+//! - malformed: a response body or incoming text frame is truncated
+//!
+//! Draws come from a seeded generator, so combined with cassette replay a
+//! failure run is repeatable.
+
+use crate::errors::ExchangeError;
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::debug;
+
+/// Fault probabilities, each per message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FaultConfig {
+    pub seed: u64,
+    pub delay_probability: f64,
+    pub min_delay_ms: u64,
+    pub max_delay_ms: u64,
+    pub drop_probability: f64,
+    pub partial_write_probability: f64,
+    pub malformed_probability: f64,
+}
+
+impl FaultConfig {
+    /// No faults until enabled with the `with_*` methods
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            delay_probability: 0.0,
+            min_delay_ms: 0,
+            max_delay_ms: 0,
+            drop_probability: 0.0,
+            partial_write_probability: 0.0,
+            malformed_probability: 0.0,
+        }
+    }
+
+    pub fn with_delays(mut self, probability: f64, min_ms: u64, max_ms: u64) -> Self {
+        self.delay_probability = probability;
+        self.min_delay_ms = min_ms;
+        self.max_delay_ms = max_ms.max(min_ms);
+        self
+    }
+
+    pub fn with_drops(mut self, probability: f64) -> Self {
+        self.drop_probability = probability;
+        self
+    }
+
+    pub fn with_partial_writes(mut self, probability: f64) -> Self {
+        self.partial_write_probability = probability;
+        self
+    }
+
+    pub fn with_malformed(mut self, probability: f64) -> Self {
+        self.malformed_probability = probability;
+        self
+    }
+}
+
+/// A fault chosen for one message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    Delay(Duration),
+    Drop,
+    PartialWrite,
+    Malformed,
+}
+
+/// How many faults of each kind have been injected
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FaultStats {
+    pub messages: u64,
+    pub delays: u64,
+    pub drops: u64,
+    pub partial_writes: u64,
+    pub malformed: u64,
+}
+
+#[derive(Debug)]
+struct InjectorState {
+    config: FaultConfig,
+    rng: u64,
+    stats: FaultStats,
+    /// Faults forced on the next messages, ahead of random draws
+    scheduled: VecDeque<Fault>,
+}
+
+/// Shared fault source; clones draw from the same generator
+///
+/// ```rust,ignore
+/// let faults = FaultInjector::new(FaultConfig::new(7).with_drops(0.05).with_partial_writes(0.01));
+/// let client = BinanceRestClient::new(config).await?.with_faults(faults.clone());
+/// let ws = BinanceWebSocketClient::new(config).with_faults(faults.clone());
+/// // ... run the scenario ...
+/// assert!(faults.stats().drops > 0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct FaultInjector {
+    state: Arc<Mutex<InjectorState>>,
+}
+
+impl FaultInjector {
+    pub fn new(config: FaultConfig) -> Self {
+        let rng = config.seed.max(1);
+        Self { state: Arc::new(Mutex::new(InjectorState { config, rng, stats: FaultStats::default(), scheduled: VecDeque::new() })) }
+    }
+
+    pub fn config(&self) -> FaultConfig {
+        self.lock().config.clone()
+    }
+
+    /// Replace the probabilities, e.g. to start failing mid-scenario
+    pub fn set_config(&self, config: FaultConfig) {
+        self.lock().config = config;
+    }
+
+    /// Force `fault` on the next message, for scripted scenarios
+    pub fn schedule(&self, fault: Fault) {
+        self.lock().scheduled.push_back(fault);
+    }
+
+    pub fn stats(&self) -> FaultStats {
+        self.lock().stats
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, InjectorState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Pick the fault, if any, for the next message
+    ///
+    /// Scheduled faults come first. Otherwise kinds are tried in a fixed
+    /// order (partial write, drop, malformed, delay) so one message gets at
+    /// most one fault.
+    pub fn next_fault(&self) -> Option<Fault> {
+        let mut state = self.lock();
+        state.stats.messages += 1;
+        let config = state.config.clone();
+        let fault = if let Some(fault) = state.scheduled.pop_front() {
+            fault
+        } else if state.draw() < config.partial_write_probability {
+            Fault::PartialWrite
+        } else if state.draw() < config.drop_probability {
+            Fault::Drop
+        } else if state.draw() < config.malformed_probability {
+            Fault::Malformed
+        } else if state.draw() < config.delay_probability {
+            let span = config.max_delay_ms - config.min_delay_ms;
+            let ms = config.min_delay_ms + (state.next_u64() % (span + 1));
+            Fault::Delay(Duration::from_millis(ms))
+        } else {
+            return None;
+        };
+        match fault {
+            Fault::Delay(_) => state.stats.delays += 1,
+            Fault::Drop => state.stats.drops += 1,
+            Fault::PartialWrite => state.stats.partial_writes += 1,
+            Fault::Malformed => state.stats.malformed += 1,
+        }
+        debug!("💥 Injecting {:?}", fault);
+        Some(fault)
+    }
+}
+
+impl InjectorState {
+    fn next_u64(&mut self) -> u64 {
+        // xorshift64
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        self.rng
+    }
+
+    /// Uniform in [0, 1)
+    fn draw(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// First half of `payload`, cut so that it no longer parses
+pub fn truncate(payload: &[u8]) -> Vec<u8> {
+    payload[..payload.len() / 2].to_vec()
+}
+
+/// Error reported for an injected fault that breaks a request
+pub fn injected(fault: Fault, what: &str) -> ExchangeError {
+    ExchangeError::NetworkError(format!("injected {fault:?} on {what}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_faults_follow_probabilities_and_seed() {
+        let config = FaultConfig::new(42).with_drops(0.2).with_delays(0.5, 5, 10);
+        let faults = FaultInjector::new(config.clone());
+        let draws: Vec<Option<Fault>> = (0..1_000).map(|_| faults.next_fault()).collect();
+
+        let stats = faults.stats();
+        assert_eq!(stats.messages, 1_000);
+        assert_eq!(stats.partial_writes + stats.malformed, 0);
+        assert!((150..250).contains(&stats.drops), "{stats:?}");
+        // Delays are drawn from what drops leave: ~0.8 * 0.5
+        assert!((320..480).contains(&stats.delays), "{stats:?}");
+        assert!(draws.iter().flatten().all(|f| match f {
+            Fault::Delay(d) => (5..=10).contains(&d.as_millis()),
+            _ => *f == Fault::Drop,
+        }));
+
+        // Same seed, same faults
+        let again = FaultInjector::new(config);
+        assert_eq!((0..1_000).map(|_| again.next_fault()).collect::<Vec<_>>(), draws);
+        assert_eq!(FaultInjector::new(FaultConfig::new(1)).next_fault(), None);
+    }
+}
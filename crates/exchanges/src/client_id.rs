@@ -0,0 +1,194 @@
+//! Venue rules for client order ids
+//!
+//! Every venue constrains the id a client attaches to its orders: Binance
+//! and Bybit take up to 36 of `[A-Za-z0-9_-]`, Coinbase any string but
+//! deduplicates on it, and each enforces uniqueness over a different
+//! horizon. `ClientIdPolicy` holds one venue's rules, generates ids from
+//! `id_gen` that satisfy them and validates ids supplied by strategies, so
+//! `OrderManager` and the venue clients don't bake in Binance's assumptions.
+//!
+//! Generated ids are the prefix followed by the tail of a timestamped
+//! sequential id (nanoseconds plus a process-wide counter, in hex); when the
+//! venue's length limit cuts it, the counter end is what survives.
+
+use crate::errors::{ExchangeError, Result};
+use sriquant_core::id_gen::generate_timestamped_id;
+
+/// Characters a venue accepts in a client order id
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdCharset {
+    /// `[A-Za-z0-9]`
+    Alphanumeric,
+    /// `[A-Za-z0-9_-]`
+    AlphanumericDashUnderscore,
+    /// Printable ASCII without spaces
+    Printable,
+}
+
+impl IdCharset {
+    pub fn allows(&self, c: char) -> bool {
+        match self {
+            Self::Alphanumeric => c.is_ascii_alphanumeric(),
+            Self::AlphanumericDashUnderscore => c.is_ascii_alphanumeric() || c == '-' || c == '_',
+            Self::Printable => c.is_ascii_graphic(),
+        }
+    }
+}
+
+/// How long a venue remembers a client order id
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UniquenessHorizon {
+    /// Unique among working orders; reusable once the order is closed
+    OpenOrders,
+    /// Never reusable: a repeat is rejected or returns the original order
+    Lifetime,
+}
+
+/// One venue's client order id rules
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientIdPolicy {
+    venue: &'static str,
+    max_len: usize,
+    charset: IdCharset,
+    uniqueness: UniquenessHorizon,
+    prefix: String,
+}
+
+impl Default for ClientIdPolicy {
+    fn default() -> Self {
+        Self::binance()
+    }
+}
+
+impl ClientIdPolicy {
+    /// `newClientOrderId`: 1-36 of `[A-Za-z0-9_-]`, unique among open orders
+    pub fn binance() -> Self {
+        Self::new("binance", 36, IdCharset::AlphanumericDashUnderscore, UniquenessHorizon::OpenOrders)
+    }
+
+    /// `orderLinkId`: up to 36 of `[A-Za-z0-9_-]`, never reused
+    pub fn bybit() -> Self {
+        Self::new("bybit", 36, IdCharset::AlphanumericDashUnderscore, UniquenessHorizon::Lifetime)
+    }
+
+    /// `client_order_id`: a repeat returns the existing order instead of placing one
+    pub fn coinbase() -> Self {
+        Self::new("coinbase", 36, IdCharset::Printable, UniquenessHorizon::Lifetime)
+    }
+
+    /// Rules for an exchange by `Exchange::name`
+    ///
+    /// Other venues (simulators, backtests) get Binance's rules, which every
+    /// venue here accepts.
+    pub fn for_venue(name: &str) -> Self {
+        match name {
+            "bybit" => Self::bybit(),
+            "coinbase" => Self::coinbase(),
+            _ => Self::binance(),
+        }
+    }
+
+    fn new(venue: &'static str, max_len: usize, charset: IdCharset, uniqueness: UniquenessHorizon) -> Self {
+        Self { venue, max_len, charset, uniqueness, prefix: "SRI".to_string() }
+    }
+
+    /// Start generated ids with `prefix`, dropping characters the venue rejects
+    ///
+    /// At most half the length limit is kept, leaving the rest for the
+    /// unique part.
+    pub fn with_prefix(mut self, prefix: &str) -> Self {
+        self.prefix = prefix.chars().filter(|c| self.charset.allows(*c)).take(self.max_len / 2).collect();
+        self
+    }
+
+    /// Cap ids at `max_len` characters (at least 1), shortening the prefix to fit
+    pub fn with_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len.max(1);
+        self.prefix.truncate(self.max_len / 2);
+        self
+    }
+
+    pub fn venue(&self) -> &'static str {
+        self.venue
+    }
+
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    pub fn charset(&self) -> IdCharset {
+        self.charset
+    }
+
+    pub fn uniqueness(&self) -> UniquenessHorizon {
+        self.uniqueness
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// A fresh id within the venue's length and charset
+    pub fn generate(&self) -> String {
+        let unique = generate_timestamped_id();
+        let room = self.max_len.saturating_sub(self.prefix.len());
+        format!("{}{}", self.prefix, &unique[unique.len().saturating_sub(room)..])
+    }
+
+    /// Check an id supplied by the caller against the venue's rules
+    pub fn validate(&self, id: &str) -> Result<()> {
+        if id.is_empty() || id.len() > self.max_len {
+            return Err(ExchangeError::InvalidOrder(format!(
+                "client order id `{id}` must be 1-{} characters on {}", self.max_len, self.venue
+            )));
+        }
+        if let Some(c) = id.chars().find(|c| !self.charset.allows(*c)) {
+            return Err(ExchangeError::InvalidOrder(format!("client order id `{id}` has `{c}`, not allowed on {}", self.venue)));
+        }
+        Ok(())
+    }
+
+    /// Whether an id last used by an order in this state may be used again
+    pub fn reusable(&self, still_open: bool) -> bool {
+        match self.uniqueness {
+            UniquenessHorizon::OpenOrders => !still_open,
+            UniquenessHorizon::Lifetime => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policies_generate_and_validate() {
+        let binance = ClientIdPolicy::binance();
+        let id = binance.generate();
+        assert!(id.starts_with("SRI") && id.len() <= 36);
+        assert!(binance.validate(&id).is_ok());
+        assert_ne!(binance.generate(), id);
+
+        assert!(binance.validate(&"x".repeat(37)).is_err());
+        assert!(binance.validate("order#1").is_err());
+        assert!(binance.validate("").is_err());
+        assert!(ClientIdPolicy::coinbase().validate("order#1").is_ok());
+
+        // A short limit keeps the counter end of the id
+        let short = ClientIdPolicy::bybit().with_max_len(12).with_prefix("mm bot/");
+        assert_eq!(short.prefix(), "mmbot");
+        let (a, b) = (short.generate(), short.generate());
+        assert_eq!(a.len(), 12);
+        assert_ne!(a, b);
+
+        // Shrinking the limit below the default prefix still leaves room for the unique part
+        let tiny = ClientIdPolicy::binance().with_max_len(2);
+        assert_eq!(tiny.prefix(), "S");
+        assert_eq!(tiny.generate().len(), 2);
+        assert_eq!(ClientIdPolicy::binance().with_max_len(0).max_len(), 1);
+
+        assert!(binance.reusable(false) && !binance.reusable(true));
+        assert!(!ClientIdPolicy::for_venue("bybit").reusable(false));
+        assert_eq!(ClientIdPolicy::for_venue("simulated"), binance);
+    }
+}
@@ -0,0 +1,156 @@
+//! Clock jump handling
+//!
+//! A laptop sleep, VM migration or NTP step moves the wall clock away from
+//! the monotonic clock: latency figures computed across the jump are
+//! garbage and the measured exchange time offset no longer holds, so signed
+//! requests start failing with -1021 or, worse, carry wrong timestamps.
+//! `ClockGuard` runs a `ClockWatch` from the event loop, publishes each jump
+//! as an incident, marks the `TimeSynced` readiness check failing and holds
+//! a pending re-sync until the offset is measured again.
+//!
+//! ```rust,ignore
+//! let mut guard = ClockGuard::new().with_incidents(bus.clone()).with_health(health.clone());
+//! loop {
+//!     if guard.check().is_some() || guard.needs_resync() {
+//!         let offset = guard.resync_binance(&rest).await?;
+//!         runner.set_clock_offset_ms(offset);
+//!     }
+//!     // ...
+//! }
+//! ```
+
+use crate::admin::{HealthChecks, ReadinessCheck};
+use crate::bars::ExchangeClock;
+use crate::binance::BinanceRestClient;
+use crate::errors::Result;
+use crate::incidents::{Incident, IncidentBus, IncidentKind, Severity};
+use sriquant_core::prelude::*;
+use sriquant_core::timing::{ClockJump, ClockWatch};
+
+use tracing::info;
+
+/// Watches for clock jumps and tracks the exchange offset re-sync they require
+#[derive(Debug, Clone)]
+pub struct ClockGuard {
+    watch: ClockWatch,
+    clock: ExchangeClock,
+    incidents: Option<IncidentBus>,
+    health: Option<HealthChecks>,
+    resync_pending: bool,
+    last_jump: Option<ClockJump>,
+}
+
+impl Default for ClockGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClockGuard {
+    pub fn new() -> Self {
+        Self::with_watch(ClockWatch::new())
+    }
+
+    /// Use a watch with custom thresholds
+    pub fn with_watch(watch: ClockWatch) -> Self {
+        Self { watch, clock: ExchangeClock::new(), incidents: None, health: None, resync_pending: false, last_jump: None }
+    }
+
+    /// Publish clock jumps as incidents
+    pub fn with_incidents(mut self, bus: IncidentBus) -> Self {
+        self.incidents = Some(bus);
+        self
+    }
+
+    /// Fail `ReadinessCheck::TimeSynced` from a jump until the re-sync
+    pub fn with_health(mut self, health: HealthChecks) -> Self {
+        self.health = Some(health);
+        self
+    }
+
+    /// Exchange clock as of the last re-sync
+    pub fn clock(&self) -> ExchangeClock {
+        self.clock
+    }
+
+    /// Whether the exchange offset must be measured again before trusting timestamps
+    pub fn needs_resync(&self) -> bool {
+        self.resync_pending
+    }
+
+    pub fn last_jump(&self) -> Option<ClockJump> {
+        self.last_jump
+    }
+
+    /// Sample the clocks; on a jump report it and require a re-sync
+    pub fn check(&mut self) -> Option<ClockJump> {
+        let jump = self.watch.check()?;
+        self.on_jump(jump);
+        Some(jump)
+    }
+
+    /// Handle a jump detected elsewhere
+    pub fn on_jump(&mut self, jump: ClockJump) {
+        self.last_jump = Some(jump);
+        self.resync_pending = true;
+
+        if let Some(bus) = &self.incidents {
+            let (severity, context) = match jump {
+                ClockJump::WallStep { delta_ns } => (Severity::Critical, ("delta_ms", delta_ns / 1_000_000)),
+                ClockJump::Suspend { duration_ns } => (Severity::Critical, ("duration_ms", (duration_ns / 1_000_000) as i64)),
+                ClockJump::Stall { gap_ns } => (Severity::Warning, ("gap_ms", (gap_ns / 1_000_000) as i64)),
+            };
+            bus.publish(
+                Incident::new(severity, "clock", IncidentKind::ClockJump, format!("{jump}; exchange time re-sync required"))
+                    .with_context(context.0, context.1),
+            );
+        }
+        if let Some(health) = &self.health {
+            health.set(ReadinessCheck::TimeSynced, false, format!("{jump}, awaiting re-sync"));
+        }
+    }
+
+    /// Apply a fresh server time measurement taken between two local readings
+    pub fn resynced(&mut self, local_before_ms: u64, server_time_ms: u64, local_after_ms: u64) -> i64 {
+        let offset = self.clock.observe(local_before_ms, server_time_ms, local_after_ms);
+        self.resync_pending = false;
+        info!("⏰ Exchange clock re-synced, offset {}ms", offset);
+        if let Some(health) = &self.health {
+            health.set(ReadinessCheck::TimeSynced, true, format!("offset {offset}ms"));
+        }
+        offset
+    }
+
+    /// Measure the Binance server time offset again
+    pub async fn resync_binance(&mut self, rest: &BinanceRestClient) -> Result<i64> {
+        let before = nanos() / 1_000_000;
+        let server_time = rest.server_time().await?;
+        let after = nanos() / 1_000_000;
+        Ok(self.resynced(before, server_time, after))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jump_requires_resync() {
+        let bus = IncidentBus::new();
+        let health = HealthChecks::new().with_required(&[ReadinessCheck::TimeSynced]);
+        let mut guard = ClockGuard::new().with_incidents(bus.clone()).with_health(health.clone());
+        guard.resynced(1_000, 1_105, 1_010);
+        assert!(health.is_ready() && !guard.needs_resync());
+
+        guard.on_jump(ClockJump::Suspend { duration_ns: 3_600_000_000_000 });
+        assert!(guard.needs_resync());
+        assert!(!health.is_ready());
+        let incident = &bus.recent(1)[0];
+        assert_eq!((incident.kind.clone(), incident.severity), (IncidentKind::ClockJump, Severity::Critical));
+        assert_eq!(incident.context["duration_ms"], "3600000");
+
+        assert_eq!(guard.resynced(2_000, 2_050, 2_000), 50);
+        assert!(health.is_ready() && !guard.needs_resync());
+        assert_eq!(guard.clock().offset_ms(), 50);
+    }
+}
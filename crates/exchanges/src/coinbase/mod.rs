@@ -0,0 +1,373 @@
+//! Coinbase Advanced Trade integration
+//!
+//! Implements `Exchange`, `TradingExchange` and (through
+//! `CoinbaseWebSocketClient`) `StreamingExchange`, so a strategy written
+//! against the shared `types` model runs on Coinbase unchanged. Symbols are
+//! Coinbase product ids ("BTC-USD").
+//!
+//! Differences from Binance a strategy may notice:
+//! - there is no stop-market order; `StopLoss` is rejected
+//! - IOC limits go through the smart order router (`sor_limit_ioc`)
+//! - streamed candles are five-minute only
+
+pub mod auth;
+pub mod rest;
+pub mod websocket;
+
+pub use auth::{CoinbaseCredentials, CoinbaseSigner};
+pub use rest::{CoinbaseRestClient, CreateOrderRequest, OrderConfiguration};
+pub use websocket::CoinbaseWebSocketClient;
+
+use crate::cassette::CassetteHandle;
+use crate::errors::{ExchangeError, Result};
+use crate::traits::{Exchange, TradingExchange};
+use crate::types::*;
+use rest::*;
+use sriquant_core::prelude::*;
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use tracing::{info, warn};
+
+/// Coinbase connection settings
+#[derive(Debug, Clone)]
+pub struct CoinbaseConfig {
+    pub base_url: String,
+    pub ws_url: String,
+    /// Needed for accounts and orders only; market data is public
+    pub credentials: Option<CoinbaseCredentials>,
+}
+
+impl Default for CoinbaseConfig {
+    fn default() -> Self {
+        Self {
+            base_url: "https://api.coinbase.com".to_string(),
+            ws_url: "wss://advanced-trade-ws.coinbase.com".to_string(),
+            credentials: None,
+        }
+    }
+}
+
+impl CoinbaseConfig {
+    pub fn with_credentials(mut self, credentials: CoinbaseCredentials) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    pub fn with_ws_url(mut self, ws_url: impl Into<String>) -> Self {
+        self.ws_url = ws_url.into();
+        self
+    }
+}
+
+/// Coinbase Advanced Trade over REST
+///
+/// ```rust,ignore
+/// let config = CoinbaseConfig::default().with_credentials(CoinbaseCredentials::from_env()?);
+/// let coinbase = CoinbaseExchange::new(config)?;
+/// let order = coinbase.place_order(OrderRequest {
+///     symbol: "BTC-USD".to_string(),
+///     side: OrderSide::Buy,
+///     order_type: OrderType::Limit,
+///     quantity: Fixed::from_str_exact("0.001")?,
+///     price: Some(Fixed::from_str_exact("50000")?),
+///     stop_price: None,
+///     time_in_force: None,
+///     client_order_id: None,
+/// }).await?;
+/// ```
+pub struct CoinbaseExchange {
+    config: CoinbaseConfig,
+    rest: CoinbaseRestClient,
+}
+
+impl CoinbaseExchange {
+    pub fn new(config: CoinbaseConfig) -> Result<Self> {
+        let rest = CoinbaseRestClient::new(config.clone())?;
+        info!("🟦 Coinbase exchange created (authenticated: {})", rest.has_credentials());
+        Ok(Self { config, rest })
+    }
+
+    /// Record requests into, or replay them from, a cassette
+    pub fn with_cassette(mut self, cassette: CassetteHandle) -> Self {
+        self.rest = self.rest.with_cassette(cassette);
+        self
+    }
+
+    pub fn rest(&self) -> &CoinbaseRestClient {
+        &self.rest
+    }
+
+    /// A market data stream sharing this exchange's settings
+    pub fn websocket(&self) -> Result<CoinbaseWebSocketClient> {
+        CoinbaseWebSocketClient::new(self.config.clone())
+    }
+
+    async fn last_price(&self, product_id: &str) -> Result<Fixed> {
+        let product: Product = self.rest.request("GET", &format!("/api/v3/brokerage/market/products/{product_id}"), &[], None, false).await?;
+        parse_fixed(&product.price)
+    }
+
+    async fn historical_orders(&self, query: &[(&str, String)]) -> Result<Vec<OrderResponse>> {
+        let response: OrdersResponse = self.rest.request("GET", "/api/v3/brokerage/orders/historical/batch", query, None, true).await?;
+        response.orders.iter().map(CoinbaseOrder::to_order_response).collect()
+    }
+
+    async fn cancel_orders(&self, order_ids: &[String]) -> Result<Vec<CancelResult>> {
+        let body = serde_json::json!({ "order_ids": order_ids }).to_string();
+        let response: CancelOrdersResponse = self.rest.request("POST", "/api/v3/brokerage/orders/batch_cancel", &[], Some(&body), true).await?;
+        Ok(response.results)
+    }
+}
+
+/// Optional time bounds as the RFC 3339 query parameters Coinbase expects
+fn time_range(start_time: Option<u64>, end_time: Option<u64>, start_key: &'static str, end_key: &'static str) -> Vec<(&'static str, String)> {
+    let rfc3339 = |ms: u64| chrono::DateTime::from_timestamp_millis(ms as i64).map(|t| t.to_rfc3339()).unwrap_or_default();
+    let mut query = Vec::new();
+    if let Some(start) = start_time {
+        query.push((start_key, rfc3339(start)));
+    }
+    if let Some(end) = end_time {
+        query.push((end_key, rfc3339(end)));
+    }
+    query
+}
+
+#[async_trait(?Send)]
+impl Exchange for CoinbaseExchange {
+    fn name(&self) -> &str {
+        "coinbase"
+    }
+
+    async fn ping(&self) -> Result<u64> {
+        let timer = PerfTimer::start("coinbase_ping".to_string());
+        self.server_time().await?;
+        Ok(timer.elapsed_nanos() / 1_000)
+    }
+
+    async fn server_time(&self) -> Result<u64> {
+        let time: ServerTime = self.rest.request("GET", "/api/v3/brokerage/time", &[], None, false).await?;
+        time.epoch_millis.parse()
+            .map_err(|e| ExchangeError::InvalidResponse(format!("Bad epochMillis {:?}: {e}", time.epoch_millis)))
+    }
+
+    async fn exchange_info(&self) -> Result<HashMap<String, Symbol>> {
+        let response: ProductsResponse = self.rest.request("GET", "/api/v3/brokerage/market/products", &[("product_type", "SPOT".to_string())], None, false).await?;
+        let mut symbols = HashMap::new();
+        for product in &response.products {
+            match product.to_symbol() {
+                Ok(symbol) => {
+                    symbols.insert(symbol.symbol.clone(), symbol);
+                }
+                Err(e) => warn!("⚠️ Skipping Coinbase product {}: {}", product.product_id, e),
+            }
+        }
+        Ok(symbols)
+    }
+
+    async fn account_info(&self) -> Result<AccountInfo> {
+        let balances = self.balances().await?;
+        Ok(AccountInfo {
+            account_type: "SPOT".to_string(),
+            can_trade: true,
+            can_withdraw: false,
+            can_deposit: false,
+            balances,
+            update_time: nanos() / 1_000_000,
+        })
+    }
+
+    async fn balances(&self) -> Result<Vec<Balance>> {
+        let response: AccountsResponse = self.rest.request("GET", "/api/v3/brokerage/accounts", &[("limit", "250".to_string())], None, true).await?;
+        response.accounts.iter().map(CoinbaseAccount::to_balance).collect()
+    }
+
+    async fn ticker(&self, symbol: &str) -> Result<Ticker> {
+        let product: Product = self.rest.request("GET", &format!("/api/v3/brokerage/market/products/{symbol}"), &[], None, false).await?;
+        let price = parse_fixed(&product.price)?;
+        let price_change_percent = parse_fixed(&product.price_percentage_change_24h)?.round_dp(2);
+        let pct = price_change_percent.to_f64_lossy();
+        Ok(Ticker {
+            symbol: product.product_id.clone(),
+            price,
+            price_change: Fixed::from_f64(price.to_f64_lossy() * pct / (100.0 + pct)).map(|c| c.round_dp(8)).unwrap_or(Fixed::ZERO),
+            price_change_percent,
+            // The product endpoint has no 24h range
+            high: Fixed::ZERO,
+            low: Fixed::ZERO,
+            volume: parse_fixed(&product.volume_24h)?,
+            quote_volume: Fixed::ZERO,
+            timestamp: nanos() / 1_000_000,
+        })
+    }
+
+    async fn order_book(&self, symbol: &str, limit: Option<u32>) -> Result<OrderBook> {
+        let mut query = vec![("product_id", symbol.to_string())];
+        if let Some(limit) = limit {
+            query.push(("limit", limit.to_string()));
+        }
+        let response: ProductBookResponse = self.rest.request("GET", "/api/v3/brokerage/market/product_book", &query, None, false).await?;
+        response.pricebook.to_order_book()
+    }
+
+    async fn recent_trades(&self, symbol: &str, limit: Option<u32>) -> Result<Vec<Trade>> {
+        let query = [("limit", limit.unwrap_or(100).to_string())];
+        let response: MarketTradesResponse = self.rest.request("GET", &format!("/api/v3/brokerage/market/products/{symbol}/ticker"), &query, None, false).await?;
+        response.trades.iter().map(MarketTrade::to_trade).collect()
+    }
+
+    async fn klines(
+        &self,
+        symbol: &str,
+        interval: &str,
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<Vec<Kline>> {
+        // Candles take epoch seconds, and both bounds are required
+        let end = end_time.unwrap_or_else(|| nanos() / 1_000_000) / 1000;
+        let count = u64::from(limit.unwrap_or(300).min(300));
+        let step = match granularity(interval)? {
+            "ONE_MINUTE" => 60,
+            "FIVE_MINUTE" => 300,
+            "FIFTEEN_MINUTE" => 900,
+            "THIRTY_MINUTE" => 1800,
+            "ONE_HOUR" => 3600,
+            "TWO_HOUR" => 7200,
+            "SIX_HOUR" => 21600,
+            _ => 86400,
+        };
+        let start = start_time.map(|s| s / 1000).unwrap_or_else(|| end.saturating_sub(step * count));
+        let query = [
+            ("start", start.to_string()),
+            ("end", end.to_string()),
+            ("granularity", granularity(interval)?.to_string()),
+        ];
+        let response: CandlesResponse = self.rest.request("GET", &format!("/api/v3/brokerage/market/products/{symbol}/candles"), &query, None, false).await?;
+        // Newest first on the wire
+        let mut klines = response.candles.iter()
+            .map(|c| c.to_kline(symbol, interval))
+            .collect::<Result<Vec<_>>>()?;
+        klines.sort_by_key(|k| k.open_time);
+        Ok(klines)
+    }
+}
+
+#[async_trait(?Send)]
+impl TradingExchange for CoinbaseExchange {
+    async fn place_order(&self, request: OrderRequest) -> Result<OrderResponse> {
+        let timer = PerfTimer::start("coinbase_place_order".to_string());
+        let last_price = match request.order_type {
+            OrderType::StopLossLimit => Some(self.last_price(&request.symbol).await?),
+            _ => None,
+        };
+        let body = CreateOrderRequest::from_request(&request, last_price)?;
+        let response: CreateOrderResponse = self.rest
+            .request("POST", "/api/v3/brokerage/orders", &[], Some(&serde_json::to_string(&body)?), true)
+            .await?;
+        timer.log_elapsed();
+
+        match (response.success, response.success_response, response.error_response) {
+            (true, Some(success), _) => {
+                info!("✅ Coinbase order {} placed: {} {} {}", success.order_id, request.side, request.quantity, request.symbol);
+                let now = nanos() / 1_000_000;
+                Ok(OrderResponse {
+                    order_id: success.order_id,
+                    client_order_id: body.client_order_id,
+                    symbol: request.symbol,
+                    side: request.side,
+                    order_type: request.order_type,
+                    quantity: request.quantity,
+                    price: request.price,
+                    stop_price: request.stop_price,
+                    status: OrderStatus::New,
+                    filled_quantity: Fixed::ZERO,
+                    average_price: None,
+                    time_in_force: request.time_in_force,
+                    timestamp: now,
+                    update_time: now,
+                })
+            }
+            (_, _, Some(error)) => Err(ExchangeError::InvalidOrder(format!(
+                "Coinbase rejected order: {} {} {}", error.error, error.preview_failure_reason, error.message
+            ).trim_end().to_string())),
+            _ => Err(ExchangeError::InvalidResponse("Order response has neither success nor error".to_string())),
+        }
+    }
+
+    async fn cancel_order(&self, symbol: &str, order_id: &str) -> Result<OrderResponse> {
+        let results = self.cancel_orders(&[order_id.to_string()]).await?;
+        if let Some(failed) = results.iter().find(|r| !r.success) {
+            return Err(match failed.failure_reason.as_str() {
+                "UNKNOWN_CANCEL_ORDER" => ExchangeError::OrderNotFound(order_id.to_string()),
+                reason => ExchangeError::InvalidOrder(format!("Cancel of {order_id} failed: {reason}")),
+            });
+        }
+        self.get_order(symbol, order_id).await
+    }
+
+    async fn cancel_all_orders(&self, symbol: &str) -> Result<Vec<OrderResponse>> {
+        let open = self.open_orders(Some(symbol)).await?;
+        if open.is_empty() {
+            return Ok(Vec::new());
+        }
+        let ids: Vec<String> = open.iter().map(|o| o.order_id.clone()).collect();
+        let results = self.cancel_orders(&ids).await?;
+        Ok(open.into_iter()
+            .filter(|order| results.iter().any(|r| r.success && r.order_id == order.order_id))
+            .map(|mut order| {
+                order.status = OrderStatus::Canceled;
+                order
+            })
+            .collect())
+    }
+
+    async fn get_order(&self, _symbol: &str, order_id: &str) -> Result<OrderResponse> {
+        let response: OrderResponseWrapper = self.rest.request("GET", &format!("/api/v3/brokerage/orders/historical/{order_id}"), &[], None, true).await?;
+        response.order.to_order_response()
+    }
+
+    async fn open_orders(&self, symbol: Option<&str>) -> Result<Vec<OrderResponse>> {
+        let mut query = vec![("order_status", "OPEN".to_string())];
+        if let Some(symbol) = symbol {
+            query.push(("product_ids", symbol.to_string()));
+        }
+        self.historical_orders(&query).await
+    }
+
+    async fn order_history(
+        &self,
+        symbol: &str,
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<Vec<OrderResponse>> {
+        let mut query = vec![("product_ids", symbol.to_string())];
+        query.extend(time_range(start_time, end_time, "start_date", "end_date"));
+        if let Some(limit) = limit {
+            query.push(("limit", limit.to_string()));
+        }
+        self.historical_orders(&query).await
+    }
+
+    async fn trade_history(
+        &self,
+        symbol: &str,
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<Vec<Trade>> {
+        let mut query = vec![("product_ids", symbol.to_string())];
+        query.extend(time_range(start_time, end_time, "start_sequence_timestamp", "end_sequence_timestamp"));
+        if let Some(limit) = limit {
+            query.push(("limit", limit.to_string()));
+        }
+        let response: FillsResponse = self.rest.request("GET", "/api/v3/brokerage/orders/historical/fills", &query, None, true).await?;
+        response.fills.iter().map(CoinbaseFill::to_trade).collect()
+    }
+}
@@ -0,0 +1,484 @@
+//! Coinbase Advanced Trade WebSocket market data
+//!
+//! Subscribes to the `ticker`, `market_trades`, `level2` and `candles`
+//! channels and maps them to unified `MarketData`. `level2` sends a full
+//! snapshot and then absolute quantity updates per price; the client keeps
+//! a local book per product and emits the top levels after every message.
+//!
+//! `sequence_num` counts every message on the connection. A gap means a
+//! `level2` update may have been lost, so the books are dropped and the
+//! channel resubscribed for a fresh snapshot.
+
+use super::CoinbaseConfig;
+use super::auth::CoinbaseSigner;
+use super::rest::{Candle, MarketTrade, parse_fixed, parse_time_ms};
+use crate::cassette::CassetteHandle;
+use crate::errors::{ExchangeError, Result, ResultExt};
+use crate::traits::StreamingExchange;
+use crate::types::*;
+use crate::websocket::MonoioWebSocket;
+use sriquant_core::prelude::*;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use tracing::{debug, info, warn};
+use url::Url;
+
+/// Book depth emitted when `subscribe_order_book` gets no level count
+const DEFAULT_BOOK_LEVELS: usize = 20;
+
+/// A Coinbase channel and the stream suffix it is tracked under
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Channel {
+    Ticker,
+    Trades,
+    Level2,
+    Candles,
+}
+
+impl Channel {
+    fn name(self) -> &'static str {
+        match self {
+            Channel::Ticker => "ticker",
+            Channel::Trades => "market_trades",
+            Channel::Level2 => "level2",
+            Channel::Candles => "candles",
+        }
+    }
+
+    fn suffix(self) -> &'static str {
+        match self {
+            Channel::Ticker => "ticker",
+            Channel::Trades => "trade",
+            Channel::Level2 => "depth",
+            Channel::Candles => "kline_5m",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct StreamSubscription {
+    product_id: String,
+    channel: Channel,
+    levels: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct Envelope {
+    #[serde(default)]
+    channel: String,
+    #[serde(default, rename = "type")]
+    kind: String,
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    timestamp: String,
+    sequence_num: Option<u64>,
+    #[serde(default)]
+    events: Vec<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TickerEvent {
+    tickers: Vec<WsTicker>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WsTicker {
+    product_id: String,
+    price: String,
+    #[serde(default)]
+    volume_24_h: String,
+    #[serde(default)]
+    low_24_h: String,
+    #[serde(default)]
+    high_24_h: String,
+    #[serde(default)]
+    price_percent_chg_24_h: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TradesEvent {
+    trades: Vec<MarketTrade>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Level2Event {
+    #[serde(rename = "type")]
+    kind: String,
+    product_id: String,
+    updates: Vec<Level2Update>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Level2Update {
+    side: String,
+    price_level: String,
+    new_quantity: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CandlesEvent {
+    candles: Vec<WsCandle>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WsCandle {
+    product_id: String,
+    #[serde(flatten)]
+    candle: Candle,
+}
+
+/// Local `level2` book for one product
+#[derive(Debug, Default)]
+struct LocalBook {
+    bids: BTreeMap<Fixed, Fixed>,
+    asks: BTreeMap<Fixed, Fixed>,
+}
+
+impl LocalBook {
+    fn apply(&mut self, update: &Level2Update) -> Result<()> {
+        let side = match update.side.as_str() {
+            "bid" => &mut self.bids,
+            "offer" | "ask" => &mut self.asks,
+            other => return Err(ExchangeError::InvalidResponse(format!("Unknown level2 side {other:?}"))),
+        };
+        let price = parse_fixed(&update.price_level)?;
+        let quantity = parse_fixed(&update.new_quantity)?;
+        if quantity.is_zero() {
+            side.remove(&price);
+        } else {
+            side.insert(price, quantity);
+        }
+        Ok(())
+    }
+
+    fn snapshot(&self, symbol: &str, levels: usize, timestamp: u64, update_id: u64) -> OrderBook {
+        let level = |(price, quantity): (&Fixed, &Fixed)| OrderBookLevel { price: *price, quantity: *quantity };
+        OrderBook {
+            symbol: symbol.to_string(),
+            bids: self.bids.iter().rev().take(levels).map(level).collect(),
+            asks: self.asks.iter().take(levels).map(level).collect(),
+            timestamp,
+            update_id,
+        }
+    }
+}
+
+/// Coinbase market data stream
+pub struct CoinbaseWebSocketClient {
+    config: CoinbaseConfig,
+    signer: Option<CoinbaseSigner>,
+    websocket: Option<MonoioWebSocket>,
+    cassette: Option<CassetteHandle>,
+    subscriptions: HashMap<String, StreamSubscription>,
+    books: HashMap<String, LocalBook>,
+    pending_events: VecDeque<MarketData>,
+    last_sequence: Option<u64>,
+}
+
+impl CoinbaseWebSocketClient {
+    pub fn new(config: CoinbaseConfig) -> Result<Self> {
+        let signer = config.credentials.clone().map(CoinbaseSigner::new).transpose()?;
+        Ok(Self {
+            config,
+            signer,
+            websocket: None,
+            cassette: None,
+            subscriptions: HashMap::new(),
+            books: HashMap::new(),
+            pending_events: VecDeque::new(),
+            last_sequence: None,
+        })
+    }
+
+    /// Record sessions into, or replay them from, a cassette
+    pub fn with_cassette(mut self, cassette: CassetteHandle) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+
+    /// Send a subscribe or unsubscribe message for one channel
+    async fn send_channel_message(&mut self, kind: &str, channel: Channel, product_ids: Vec<String>) -> Result<()> {
+        let mut message = serde_json::Map::new();
+        message.insert("type".to_string(), kind.into());
+        message.insert("channel".to_string(), channel.name().into());
+        if let Some(signer) = &self.signer {
+            message.extend(signer.websocket_auth(channel.name(), &product_ids)?);
+        }
+        message.insert("product_ids".to_string(), product_ids.into());
+
+        let websocket = self.websocket.as_mut()
+            .ok_or_else(|| ExchangeError::NetworkError("WebSocket not connected".to_string()))?;
+        websocket.send_text(Value::Object(message).to_string()).await
+    }
+
+    async fn subscribe(&mut self, product_id: &str, channel: Channel, levels: usize) -> Result<()> {
+        let stream = format!("{}@{}", product_id, channel.suffix());
+        if self.subscriptions.contains_key(&stream) {
+            return Ok(());
+        }
+        self.send_channel_message("subscribe", channel, vec![product_id.to_string()]).await?;
+        self.subscriptions.insert(stream.clone(), StreamSubscription { product_id: product_id.to_string(), channel, levels });
+        info!("📊 Subscribed to Coinbase stream: {}", stream);
+        Ok(())
+    }
+
+    /// Drop every book and ask for fresh `level2` snapshots
+    async fn resync_level2(&mut self) -> Result<()> {
+        self.books.clear();
+        let mut products: Vec<String> = self.subscriptions.values()
+            .filter(|s| s.channel == Channel::Level2)
+            .map(|s| s.product_id.clone())
+            .collect();
+        if products.is_empty() {
+            return Ok(());
+        }
+        products.sort_unstable();
+        self.send_channel_message("unsubscribe", Channel::Level2, products.clone()).await?;
+        self.send_channel_message("subscribe", Channel::Level2, products).await
+    }
+
+    fn book_levels(&self, product_id: &str) -> usize {
+        self.subscriptions.values()
+            .filter(|s| s.channel == Channel::Level2 && s.product_id == product_id)
+            .map(|s| s.levels)
+            .max()
+            .unwrap_or(DEFAULT_BOOK_LEVELS)
+    }
+
+    /// Parse one message into unified events; returns whether the sequence had a gap
+    fn process_message(&mut self, text: &str) -> Result<bool> {
+        let envelope: Envelope = serde_json::from_str(text)
+            .map_err(|e| ExchangeError::from(e).with_payload(text))?;
+        if envelope.kind == "error" {
+            return Err(ExchangeError::InvalidResponse(format!("Coinbase WebSocket error: {}", envelope.message)));
+        }
+
+        let gap = match (self.last_sequence, envelope.sequence_num) {
+            (Some(last), Some(sequence)) => sequence > last + 1,
+            _ => false,
+        };
+        if let Some(sequence) = envelope.sequence_num {
+            self.last_sequence = Some(sequence);
+        }
+        let timestamp = parse_time_ms(&envelope.timestamp)?;
+        let sequence = envelope.sequence_num.unwrap_or(0);
+
+        for event in envelope.events {
+            match envelope.channel.as_str() {
+                "ticker" | "ticker_batch" => {
+                    let event = TickerEvent::deserialize(&event).map_err(ExchangeError::from).with_endpoint("ticker")?;
+                    for ticker in event.tickers {
+                        self.pending_events.push_back(MarketData::Ticker(ticker_from_ws(&ticker, timestamp)?));
+                    }
+                }
+                "market_trades" => {
+                    let event = TradesEvent::deserialize(&event).map_err(ExchangeError::from).with_endpoint("market_trades")?;
+                    for trade in event.trades {
+                        self.pending_events.push_back(MarketData::Trade(trade.to_trade()?));
+                    }
+                }
+                "l2_data" => {
+                    let event = Level2Event::deserialize(&event).map_err(ExchangeError::from).with_endpoint("l2_data")?;
+                    if gap {
+                        // The book is resynced once this message is handled
+                        continue;
+                    }
+                    let book = self.books.entry(event.product_id.clone()).or_default();
+                    if event.kind == "snapshot" {
+                        *book = LocalBook::default();
+                    }
+                    for update in &event.updates {
+                        book.apply(update).with_symbol(&event.product_id)?;
+                    }
+                    let levels = self.book_levels(&event.product_id);
+                    let snapshot = self.books[&event.product_id].snapshot(&event.product_id, levels, timestamp, sequence);
+                    self.pending_events.push_back(MarketData::OrderBook(snapshot));
+                }
+                "candles" => {
+                    let event = CandlesEvent::deserialize(&event).map_err(ExchangeError::from).with_endpoint("candles")?;
+                    for candle in event.candles {
+                        self.pending_events.push_back(MarketData::Kline(candle.candle.to_kline(&candle.product_id, "5m")?));
+                    }
+                }
+                "subscriptions" | "heartbeats" => {}
+                other => debug!("Ignoring Coinbase channel {}", other),
+            }
+        }
+        Ok(gap)
+    }
+}
+
+fn ticker_from_ws(ticker: &WsTicker, timestamp: u64) -> Result<Ticker> {
+    let price = parse_fixed(&ticker.price)?;
+    let price_change_percent = parse_fixed(&ticker.price_percent_chg_24_h)?.round_dp(2);
+    // Only the percentage is sent; back out the absolute change from it
+    let pct = price_change_percent.to_f64_lossy();
+    let price_change = Fixed::from_f64(price.to_f64_lossy() * pct / (100.0 + pct))
+        .map(|c| c.round_dp(8))
+        .unwrap_or(Fixed::ZERO);
+    Ok(Ticker {
+        symbol: ticker.product_id.clone(),
+        price,
+        price_change,
+        price_change_percent,
+        high: parse_fixed(&ticker.high_24_h)?,
+        low: parse_fixed(&ticker.low_24_h)?,
+        volume: parse_fixed(&ticker.volume_24_h)?,
+        quote_volume: Fixed::ZERO,
+        timestamp,
+    })
+}
+
+#[async_trait(?Send)]
+impl StreamingExchange for CoinbaseWebSocketClient {
+    async fn connect(&mut self) -> Result<()> {
+        if self.websocket.is_some() {
+            return Ok(());
+        }
+        let timer = PerfTimer::start("coinbase_ws_connect".to_string());
+        let url = Url::parse(&self.config.ws_url).map_err(|e| ExchangeError::InvalidUrl(e.to_string()))?;
+        self.websocket = Some(MonoioWebSocket::connect_with_cassette(url, self.cassette.as_ref()).await?);
+        self.last_sequence = None;
+        timer.log_elapsed();
+        info!("✅ Connected to Coinbase WebSocket");
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        self.pending_events.clear();
+        self.books.clear();
+        self.subscriptions.clear();
+        if let Some(mut websocket) = self.websocket.take() {
+            websocket.close(1000, "Normal closure".to_string()).await?;
+        }
+        Ok(())
+    }
+
+    async fn subscribe_ticker(&mut self, symbol: &str) -> Result<()> {
+        self.subscribe(symbol, Channel::Ticker, 0).await
+    }
+
+    async fn subscribe_trades(&mut self, symbol: &str) -> Result<()> {
+        self.subscribe(symbol, Channel::Trades, 0).await
+    }
+
+    async fn subscribe_order_book(&mut self, symbol: &str, levels: Option<u32>) -> Result<()> {
+        let levels = levels.map(|l| l as usize).unwrap_or(DEFAULT_BOOK_LEVELS);
+        self.subscribe(symbol, Channel::Level2, levels).await
+    }
+
+    /// The `candles` channel only streams five-minute bars
+    async fn subscribe_klines(&mut self, symbol: &str, interval: &str) -> Result<()> {
+        if interval != "5m" {
+            return Err(ExchangeError::FeatureNotSupported(format!("Coinbase streams 5m candles only, not {interval}")));
+        }
+        self.subscribe(symbol, Channel::Candles, 0).await
+    }
+
+    async fn unsubscribe(&mut self, stream: &str) -> Result<()> {
+        let Some(subscription) = self.subscriptions.remove(stream) else {
+            return Ok(());
+        };
+        self.send_channel_message("unsubscribe", subscription.channel, vec![subscription.product_id.clone()]).await?;
+        if subscription.channel == Channel::Level2 {
+            self.books.remove(&subscription.product_id);
+        }
+        info!("❌ Unsubscribed from stream: {}", stream);
+        Ok(())
+    }
+
+    async fn next_event(&mut self) -> Result<Option<MarketData>> {
+        loop {
+            if let Some(event) = self.pending_events.pop_front() {
+                return Ok(Some(event));
+            }
+            let websocket = self.websocket.as_mut()
+                .ok_or_else(|| ExchangeError::NetworkError("WebSocket not connected".to_string()))?;
+            let message = websocket.receive_text().await?;
+            if self.process_message(&message)? {
+                warn!("⚠️ Coinbase sequence gap; resubscribing level2 for fresh snapshots");
+                self.resync_level2().await?;
+            }
+        }
+    }
+
+    fn connection_status(&self) -> ConnectionStatus {
+        match &self.websocket {
+            Some(websocket) if websocket.is_connected() => ConnectionStatus::Connected,
+            _ => ConnectionStatus::Disconnected,
+        }
+    }
+
+    fn subscriptions(&self) -> Vec<Subscription> {
+        self.subscriptions.iter()
+            .map(|(stream, subscription)| Subscription {
+                stream: stream.clone(),
+                symbol: subscription.product_id.clone(),
+                status: SubscriptionStatus::Subscribed,
+                last_update: 0,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testkit::fx;
+    use crate::cassette::{Cassette, WsEvent, WsSession};
+
+    #[monoio::test]
+    async fn test_ticker_and_level2_stream() {
+        let received = |text: &str| WsEvent::Received(text.to_string());
+        let cassette = CassetteHandle::replay(Cassette {
+            http: Vec::new(),
+            websocket: vec![WsSession {
+                url: "wss://advanced-trade-ws.coinbase.com/".to_string(),
+                events: vec![
+                    WsEvent::Sent("subscribe ticker".to_string()),
+                    WsEvent::Sent("subscribe level2".to_string()),
+                    received(r#"{"channel":"subscriptions","timestamp":"2024-01-02T03:04:05Z","sequence_num":0,"events":[{"subscriptions":{"ticker":["BTC-USD"]}}]}"#),
+                    received(r#"{"channel":"ticker","timestamp":"2024-01-02T03:04:05.5Z","sequence_num":1,"events":[{"type":"snapshot","tickers":[
+                        {"type":"ticker","product_id":"BTC-USD","price":"51000","volume_24_h":"1200.5","low_24_h":"49000","high_24_h":"52000","price_percent_chg_24_h":"2"}]}]}"#),
+                    received(r#"{"channel":"l2_data","timestamp":"2024-01-02T03:04:06Z","sequence_num":2,"events":[{"type":"snapshot","product_id":"BTC-USD","updates":[
+                        {"side":"bid","event_time":"2024-01-02T03:04:06Z","price_level":"50999","new_quantity":"1.5"},
+                        {"side":"bid","event_time":"2024-01-02T03:04:06Z","price_level":"50998","new_quantity":"2"},
+                        {"side":"offer","event_time":"2024-01-02T03:04:06Z","price_level":"51001","new_quantity":"0.7"}]}]}"#),
+                    received(r#"{"channel":"l2_data","timestamp":"2024-01-02T03:04:07Z","sequence_num":3,"events":[{"type":"update","product_id":"BTC-USD","updates":[
+                        {"side":"bid","event_time":"2024-01-02T03:04:07Z","price_level":"50999","new_quantity":"0"}]}]}"#),
+                    // Sequence 4 was lost: the book is dropped and resubscribed
+                    received(r#"{"channel":"l2_data","timestamp":"2024-01-02T03:04:08Z","sequence_num":5,"events":[{"type":"update","product_id":"BTC-USD","updates":[]}]}"#),
+                    WsEvent::Sent("unsubscribe level2".to_string()),
+                    WsEvent::Sent("subscribe level2".to_string()),
+                ],
+            }],
+        });
+
+        let mut client = CoinbaseWebSocketClient::new(CoinbaseConfig::default()).unwrap().with_cassette(cassette);
+        client.connect().await.unwrap();
+        client.subscribe_ticker("BTC-USD").await.unwrap();
+        client.subscribe_order_book("BTC-USD", Some(1)).await.unwrap();
+        assert_eq!(client.subscriptions().len(), 2);
+
+        let Some(MarketData::Ticker(ticker)) = client.next_event().await.unwrap() else { panic!("expected ticker") };
+        assert_eq!(ticker.price, fx("51000"));
+        assert_eq!(ticker.price_change, fx("1000"));
+        assert_eq!(ticker.timestamp, 1_704_164_645_500);
+
+        let Some(MarketData::OrderBook(book)) = client.next_event().await.unwrap() else { panic!("expected book") };
+        assert_eq!(book.bids, vec![OrderBookLevel { price: fx("50999"), quantity: fx("1.5") }]);
+        assert_eq!(book.best_ask(), Some(fx("51001")));
+
+        let Some(MarketData::OrderBook(book)) = client.next_event().await.unwrap() else { panic!("expected book") };
+        assert_eq!(book.best_bid(), Some(fx("50998")));
+        assert_eq!(book.update_id, 3);
+
+        // The gapped message yields nothing, and the cassette is then exhausted
+        assert!(client.next_event().await.is_err());
+        assert!(client.books.is_empty());
+    }
+}
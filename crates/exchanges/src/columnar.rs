@@ -0,0 +1,221 @@
+//! Arrow and Parquet export of market data and order history
+//!
+//! Research happens in pandas/polars, which load Parquet directly. The
+//! `*_batch` functions turn downloaded klines, recorded trades and Binance
+//! order/fill history into Arrow `RecordBatch`es; `write_parquet` stores one.
+//!
+//! Prices and quantities become `Decimal128` columns at the largest scale in
+//! the column, so values stay exact rather than passing through f64;
+//! timestamps are millisecond UTC timestamps. Built with the `arrow` feature.
+//!
+//! ```rust,ignore
+//! let klines = client.download_klines("BTCUSDT", "1m", start_ms..end_ms).await?;
+//! write_parquet(std::fs::File::create("btcusdt-1m.parquet")?, &klines_batch(&klines)?)?;
+//! ```
+
+use crate::binance::rest::{MyTradeResponse, QueryOrderResponse};
+use crate::binance::BinanceWebSocketClient;
+use crate::errors::{ExchangeError, Result};
+use crate::recorder::Recording;
+use crate::types::{Kline, MarketDataEvent, TradeSide, TradeUpdate};
+use sriquant_core::prelude::*;
+
+use arrow_array::{ArrayRef, BooleanArray, Decimal128Array, RecordBatch, StringArray, TimestampMillisecondArray, UInt64Array};
+use arrow_schema::{Field, Schema};
+use parquet::arrow::ArrowWriter;
+use std::io::Write;
+use std::sync::Arc;
+
+/// Widest decimal Arrow holds in 128 bits
+const DECIMAL_PRECISION: u8 = 38;
+
+/// Klines, one row per bar
+pub fn klines_batch(klines: &[Kline]) -> Result<RecordBatch> {
+    let mut columns = Columns::default();
+    columns.timestamp("open_time", klines.iter().map(|k| k.open_time));
+    columns.timestamp("close_time", klines.iter().map(|k| k.close_time));
+    columns.string("symbol", klines.iter().map(|k| k.symbol.as_str()));
+    columns.string("interval", klines.iter().map(|k| k.interval.as_str()));
+    columns.decimal("open", klines.iter().map(|k| k.open))?;
+    columns.decimal("high", klines.iter().map(|k| k.high))?;
+    columns.decimal("low", klines.iter().map(|k| k.low))?;
+    columns.decimal("close", klines.iter().map(|k| k.close))?;
+    columns.decimal("volume", klines.iter().map(|k| k.volume))?;
+    columns.decimal("quote_volume", klines.iter().map(|k| k.quote_volume))?;
+    columns.uint("number_of_trades", klines.iter().map(|k| k.number_of_trades as u64));
+    columns.boolean("is_closed", klines.iter().map(|k| k.is_closed));
+    columns.finish()
+}
+
+/// Public trades, one row per trade; `side` is the taker side
+pub fn trades_batch(trades: &[TradeUpdate]) -> Result<RecordBatch> {
+    let mut columns = Columns::default();
+    columns.timestamp("timestamp", trades.iter().map(|t| t.timestamp));
+    columns.string("symbol", trades.iter().map(|t| t.symbol.as_str()));
+    columns.uint("trade_id", trades.iter().map(|t| t.trade_id));
+    columns.decimal("price", trades.iter().map(|t| t.price))?;
+    columns.decimal("quantity", trades.iter().map(|t| t.quantity))?;
+    columns.string("side", trades.iter().map(|t| match t.side {
+        TradeSide::Buy => "BUY",
+        TradeSide::Sell => "SELL",
+    }));
+    columns.finish()
+}
+
+/// Trades in a recording, in the order they were received
+///
+/// Frames that aren't trades (other streams, subscription replies) are skipped.
+pub fn recorded_trades(recording: &Recording, client: &BinanceWebSocketClient) -> Vec<TradeUpdate> {
+    recording.frames()
+        .filter_map(|frame| match client.process_message_content(frame) {
+            Ok(MarketDataEvent::Trade(trade)) => Some(trade),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Order history from `get_all_orders`, one row per order
+pub fn orders_batch(orders: &[QueryOrderResponse]) -> Result<RecordBatch> {
+    let mut columns = Columns::default();
+    columns.timestamp("time", orders.iter().map(|o| o.time));
+    columns.timestamp("update_time", orders.iter().map(|o| o.update_time));
+    columns.string("symbol", orders.iter().map(|o| o.symbol.as_str()));
+    columns.uint("order_id", orders.iter().map(|o| o.order_id));
+    columns.string("client_order_id", orders.iter().map(|o| o.client_order_id.as_str()));
+    columns.string("side", orders.iter().map(|o| o.side.as_str()));
+    columns.string("type", orders.iter().map(|o| o.order_type.as_str()));
+    columns.string("time_in_force", orders.iter().map(|o| o.time_in_force.as_str()));
+    columns.string("status", orders.iter().map(|o| o.status.as_str()));
+    columns.decimal("price", orders.iter().map(|o| o.price))?;
+    columns.decimal("orig_qty", orders.iter().map(|o| o.orig_qty))?;
+    columns.decimal("executed_qty", orders.iter().map(|o| o.executed_qty))?;
+    columns.decimal("cumulative_quote_qty", orders.iter().map(|o| o.cumulative_quote_qty))?;
+    columns.decimal("stop_price", orders.iter().map(|o| o.stop_price))?;
+    columns.finish()
+}
+
+/// Account fills from `my_trades`, one row per fill
+pub fn my_trades_batch(trades: &[MyTradeResponse]) -> Result<RecordBatch> {
+    let mut columns = Columns::default();
+    columns.timestamp("time", trades.iter().map(|t| t.time));
+    columns.string("symbol", trades.iter().map(|t| t.symbol.as_str()));
+    columns.uint("trade_id", trades.iter().map(|t| t.id));
+    columns.uint("order_id", trades.iter().map(|t| t.order_id));
+    columns.decimal("price", trades.iter().map(|t| t.price))?;
+    columns.decimal("qty", trades.iter().map(|t| t.qty))?;
+    columns.decimal("quote_qty", trades.iter().map(|t| t.quote_qty))?;
+    columns.decimal("commission", trades.iter().map(|t| t.commission))?;
+    columns.string("commission_asset", trades.iter().map(|t| t.commission_asset.as_str()));
+    columns.boolean("is_buyer", trades.iter().map(|t| t.is_buyer));
+    columns.boolean("is_maker", trades.iter().map(|t| t.is_maker));
+    columns.finish()
+}
+
+/// Write a batch as a Parquet file, returning the rows written
+pub fn write_parquet<W: Write + Send>(out: W, batch: &RecordBatch) -> Result<usize> {
+    let mut writer = ArrowWriter::try_new(out, batch.schema(), None).map_err(parquet_error)?;
+    writer.write(batch).map_err(parquet_error)?;
+    writer.close().map_err(parquet_error)?;
+    Ok(batch.num_rows())
+}
+
+fn parquet_error(e: parquet::errors::ParquetError) -> ExchangeError {
+    ExchangeError::IoError(format!("Parquet: {e}"))
+}
+
+fn arrow_error(e: arrow_schema::ArrowError) -> ExchangeError {
+    ExchangeError::SerializationError(format!("Arrow: {e}"))
+}
+
+/// Columns collected in schema order
+#[derive(Default)]
+struct Columns {
+    fields: Vec<Field>,
+    arrays: Vec<ArrayRef>,
+}
+
+impl Columns {
+    fn push(&mut self, name: &str, array: ArrayRef) {
+        self.fields.push(Field::new(name, array.data_type().clone(), false));
+        self.arrays.push(array);
+    }
+
+    fn timestamp(&mut self, name: &str, values: impl Iterator<Item = u64>) {
+        let millis = TimestampMillisecondArray::from_iter_values(values.map(|ms| ms as i64)).with_timezone("UTC");
+        self.push(name, Arc::new(millis));
+    }
+
+    fn string<'a>(&mut self, name: &str, values: impl Iterator<Item = &'a str>) {
+        self.push(name, Arc::new(StringArray::from_iter_values(values)));
+    }
+
+    fn uint(&mut self, name: &str, values: impl Iterator<Item = u64>) {
+        self.push(name, Arc::new(UInt64Array::from_iter_values(values)));
+    }
+
+    fn boolean(&mut self, name: &str, values: impl Iterator<Item = bool>) {
+        self.push(name, Arc::new(values.map(Some).collect::<BooleanArray>()));
+    }
+
+    /// Exact decimals, rescaled to the largest scale in the column
+    fn decimal(&mut self, name: &str, values: impl Iterator<Item = Fixed>) -> Result<()> {
+        let values: Vec<_> = values.map(|v| v.to_decimal()).collect();
+        let scale = values.iter().map(|d| d.scale()).max().unwrap_or(0);
+        let mantissas = values.iter()
+            .map(|d| {
+                10i128.checked_pow(scale - d.scale())
+                    .and_then(|factor| d.mantissa().checked_mul(factor))
+                    .ok_or_else(|| ExchangeError::SerializationError(format!("{name}: {d} overflows Decimal128 at scale {scale}")))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let array = Decimal128Array::from(mantissas)
+            .with_precision_and_scale(DECIMAL_PRECISION, scale as i8)
+            .map_err(arrow_error)?;
+        self.push(name, Arc::new(array));
+        Ok(())
+    }
+
+    fn finish(self) -> Result<RecordBatch> {
+        RecordBatch::try_new(Arc::new(Schema::new(self.fields)), self.arrays).map_err(arrow_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testkit::fx;
+    use crate::types::ExchangeId;
+    use arrow_array::Array;
+    use arrow_schema::{DataType, TimeUnit};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    #[test]
+    fn test_batches_keep_exact_decimals_through_parquet() {
+        let trade = |trade_id, price: &str, quantity: &str| TradeUpdate {
+            exchange: ExchangeId::Binance,
+            symbol: "BTCUSDT".to_string(),
+            price: fx(price),
+            quantity: fx(quantity),
+            side: TradeSide::Sell,
+            timestamp: 1_700_000_000_000 + trade_id,
+            trade_id,
+        };
+        let batch = trades_batch(&[trade(1, "50000.1", "0.00125"), trade(2, "50000.25", "3")]).unwrap();
+
+        let price = batch.column_by_name("price").unwrap().as_any().downcast_ref::<Decimal128Array>().unwrap();
+        assert_eq!(price.data_type(), &DataType::Decimal128(DECIMAL_PRECISION, 2));
+        assert_eq!(price.value_as_string(0), "50000.10");
+        assert_eq!(batch.schema().field_with_name("timestamp").unwrap().data_type(),
+            &DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())));
+
+        let path = std::env::temp_dir().join(format!("sriquant-columnar-{}.parquet", std::process::id()));
+        assert_eq!(write_parquet(std::fs::File::create(&path).unwrap(), &batch).unwrap(), 2);
+        let read: Vec<RecordBatch> = ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&path).unwrap()).unwrap()
+            .build().unwrap()
+            .collect::<std::result::Result<_, _>>().unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(read, vec![batch]);
+
+        assert_eq!(klines_batch(&[]).unwrap().num_rows(), 0);
+    }
+}
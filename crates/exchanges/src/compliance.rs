@@ -0,0 +1,406 @@
+//! Compliance export of journaled orders and fills
+//!
+//! Exchanges and auditors each expect activity in their own layout.
+//! `ComplianceExporter` picks an `AuditFormat` per venue and renders that
+//! venue's journal entries with it: `BinanceOrderCsv` follows the order
+//! history CSV downloaded from the Binance UI, `FixDropCopy` emits FIX 4.4
+//! execution reports as a drop copy session would have delivered them.
+//!
+//! ```rust,ignore
+//! let exporter = ComplianceExporter::new()
+//!     .with_format("binance", BinanceOrderCsv)
+//!     .with_default(FixDropCopy::new("SRIQUANT").with_delimiter(b'|'));
+//! let file = std::fs::File::create("binance-orders.csv")?;
+//! exporter.export(&journal, "binance", file)?;
+//! ```
+
+use crate::errors::{ExchangeError, Result};
+use crate::journal::{Journal, JournalEntry, JournalEvent, OrderRecord, TradeRecord};
+use crate::types::{OrderSide, OrderStatus, OrderType};
+use sriquant_core::prelude::*;
+
+use std::collections::HashMap;
+use std::io::Write;
+use tracing::info;
+
+const SOH: u8 = 0x01;
+
+/// A venue-specific rendering of journal entries
+pub trait AuditFormat {
+    fn name(&self) -> &'static str;
+
+    /// Render entries (all from one venue, in journal order); returns the number of records written
+    fn write(&self, entries: &[&JournalEntry], out: &mut dyn Write) -> Result<usize>;
+}
+
+/// Binance order history CSV: one row per order in its final journaled state
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BinanceOrderCsv;
+
+impl AuditFormat for BinanceOrderCsv {
+    fn name(&self) -> &'static str {
+        "binance_order_csv"
+    }
+
+    fn write(&self, entries: &[&JournalEntry], out: &mut dyn Write) -> Result<usize> {
+        // First sighting dates the order, the latest record holds its final state
+        let mut orders: Vec<(u64, &OrderRecord)> = Vec::new();
+        let mut index: HashMap<&str, usize> = HashMap::new();
+        for entry in entries {
+            if let JournalEvent::Order(order) = &entry.event {
+                match index.get(order.client_order_id.as_str()) {
+                    Some(&i) => orders[i].1 = order,
+                    None => {
+                        index.insert(&order.client_order_id, orders.len());
+                        orders.push((order.update_time, order));
+                    }
+                }
+            }
+        }
+
+        writeln!(out, "Date(UTC),OrderNo,Pair,Type,Side,Order Price,Order Amount,Executed,Average Price,Trading total,Status")?;
+        for (created, order) in &orders {
+            let total = order.average_price.map(|avg| avg * order.filled_quantity);
+            writeln!(
+                out,
+                "{},{},{},{},{},{},{},{},{},{},{}",
+                utc(*created, "%Y-%m-%d %H:%M:%S")?,
+                order.order_id.as_deref().unwrap_or(&order.client_order_id),
+                order.symbol,
+                order.order_type.map(|t| t.to_string()).unwrap_or_default(),
+                order.side,
+                order.price.map(|p| p.to_string()).unwrap_or_default(),
+                order.quantity,
+                order.filled_quantity,
+                order.average_price.map(|p| p.to_string()).unwrap_or_default(),
+                total.map(|t| t.to_string()).unwrap_or_default(),
+                order.status,
+            )?;
+        }
+        Ok(orders.len())
+    }
+}
+
+/// FIX 4.4 execution reports (35=8), one per order state change and fill
+#[derive(Debug, Clone)]
+pub struct FixDropCopy {
+    sender_comp_id: String,
+    target_comp_id: Option<String>,
+    delimiter: u8,
+}
+
+impl FixDropCopy {
+    pub fn new(sender_comp_id: &str) -> Self {
+        Self { sender_comp_id: sender_comp_id.to_string(), target_comp_id: None, delimiter: SOH }
+    }
+
+    /// TargetCompID of every message; defaults to the upper-cased venue name
+    pub fn with_target_comp_id(mut self, target_comp_id: &str) -> Self {
+        self.target_comp_id = Some(target_comp_id.to_string());
+        self
+    }
+
+    /// Field separator instead of SOH, e.g. `b'|'` for human review
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Frame a body with BeginString, BodyLength and CheckSum
+    fn frame(&self, fields: &[(u32, String)]) -> Vec<u8> {
+        let mut body = Vec::new();
+        for (tag, value) in fields {
+            write!(body, "{tag}={value}").ok();
+            body.push(self.delimiter);
+        }
+
+        let mut message = Vec::with_capacity(body.len() + 32);
+        write!(message, "8=FIX.4.4").ok();
+        message.push(self.delimiter);
+        write!(message, "9={}", body.len()).ok();
+        message.push(self.delimiter);
+        message.extend_from_slice(&body);
+        // The checksum is defined over SOH-delimited bytes whatever the display separator
+        let checksum = message.iter()
+            .map(|&b| if b == self.delimiter { SOH } else { b } as u32)
+            .sum::<u32>() % 256;
+        write!(message, "10={checksum:03}").ok();
+        message.push(self.delimiter);
+        message
+    }
+
+    fn order_fields(order: &OrderRecord) -> Vec<(u32, String)> {
+        let exec_type = match order.status {
+            OrderStatus::New => "0",
+            // Fills are reported by their own trade records
+            OrderStatus::PartiallyFilled | OrderStatus::Filled => "I",
+            OrderStatus::Canceled => "4",
+            OrderStatus::Rejected => "8",
+            OrderStatus::Expired => "C",
+        };
+        let mut fields = vec![
+            (37, order.order_id.clone().unwrap_or_else(|| "NONE".to_string())),
+            (11, order.client_order_id.clone()),
+            (17, format!("{}-{}", order.client_order_id, order.update_time)),
+            (150, exec_type.to_string()),
+            (39, ord_status(order.status).to_string()),
+            (55, order.symbol.clone()),
+            (54, side(order.side).to_string()),
+        ];
+        if let Some(order_type) = order.order_type {
+            fields.push((40, ord_type(order_type).to_string()));
+        }
+        fields.push((38, order.quantity.to_string()));
+        if let Some(price) = order.price {
+            fields.push((44, price.to_string()));
+        }
+        let leaves = if matches!(order.status, OrderStatus::New | OrderStatus::PartiallyFilled) {
+            order.quantity - order.filled_quantity
+        } else {
+            Fixed::ZERO
+        };
+        fields.push((151, leaves.to_string()));
+        fields.push((14, order.filled_quantity.to_string()));
+        fields.push((6, order.average_price.unwrap_or(Fixed::ZERO).to_string()));
+        fields
+    }
+
+    fn trade_fields(trade: &TradeRecord, order: Option<&OrderRecord>, cum_qty: Fixed) -> Vec<(u32, String)> {
+        let filled = order.is_some_and(|o| cum_qty >= o.quantity);
+        let mut fields = vec![(37, trade.order_id.to_string())];
+        if let Some(order) = order {
+            fields.push((11, order.client_order_id.clone()));
+        }
+        fields.extend([
+            (17, trade.trade_id.to_string()),
+            (150, "F".to_string()),
+            (39, if filled { "2" } else { "1" }.to_string()),
+            (55, trade.symbol.clone()),
+            (54, side(trade.side).to_string()),
+        ]);
+        if let Some(order) = order {
+            fields.push((38, order.quantity.to_string()));
+            fields.push((151, (order.quantity - cum_qty).max(Fixed::ZERO).to_string()));
+        }
+        fields.extend([
+            (32, trade.quantity.to_string()),
+            (31, trade.price.to_string()),
+            (14, cum_qty.to_string()),
+            (12, trade.commission.to_string()),
+            (13, "3".to_string()),
+            (479, trade.commission_asset.clone()),
+            (851, if trade.is_maker { "1" } else { "2" }.to_string()),
+        ]);
+        fields
+    }
+}
+
+impl AuditFormat for FixDropCopy {
+    fn name(&self) -> &'static str {
+        "fix44_drop_copy"
+    }
+
+    fn write(&self, entries: &[&JournalEntry], out: &mut dyn Write) -> Result<usize> {
+        let orders: HashMap<&str, &OrderRecord> = entries.iter()
+            .filter_map(|e| match &e.event {
+                JournalEvent::Order(o) => o.order_id.as_deref().map(|id| (id, o)),
+                _ => None,
+            })
+            .collect();
+        let mut cum_qty: HashMap<u64, Fixed> = HashMap::new();
+        let mut sequence = 0;
+
+        for entry in entries {
+            let (mut fields, time) = match &entry.event {
+                JournalEvent::Order(order) => (Self::order_fields(order), order.update_time),
+                JournalEvent::Trade(trade) => {
+                    let cum = cum_qty.entry(trade.order_id).or_insert(Fixed::ZERO);
+                    *cum += trade.quantity;
+                    let order = orders.get(trade.order_id.to_string().as_str()).copied();
+                    (Self::trade_fields(trade, order, *cum), trade.time)
+                }
+                _ => continue,
+            };
+            sequence += 1;
+
+            let target = self.target_comp_id.clone().unwrap_or_else(|| entry.exchange.to_uppercase());
+            let mut message = vec![
+                (35, "8".to_string()),
+                (49, self.sender_comp_id.clone()),
+                (56, target),
+                (34, sequence.to_string()),
+                (52, utc(entry.recorded_at, "%Y%m%d-%H:%M:%S%.3f")?),
+            ];
+            message.append(&mut fields);
+            message.push((60, utc(time, "%Y%m%d-%H:%M:%S%.3f")?));
+            out.write_all(&self.frame(&message))?;
+            out.write_all(b"\n")?;
+        }
+        Ok(sequence)
+    }
+}
+
+/// Renders each venue's journal entries in the format its counterparties expect
+#[derive(Default)]
+pub struct ComplianceExporter {
+    formats: HashMap<String, Box<dyn AuditFormat>>,
+    default: Option<Box<dyn AuditFormat>>,
+}
+
+impl ComplianceExporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_format(mut self, venue: &str, format: impl AuditFormat + 'static) -> Self {
+        self.formats.insert(venue.to_string(), Box::new(format));
+        self
+    }
+
+    /// Format for venues without one of their own
+    pub fn with_default(mut self, format: impl AuditFormat + 'static) -> Self {
+        self.default = Some(Box::new(format));
+        self
+    }
+
+    pub fn format_for(&self, venue: &str) -> Option<&dyn AuditFormat> {
+        self.formats.get(venue).or(self.default.as_ref()).map(|f| f.as_ref())
+    }
+
+    /// Export every journal entry of a venue; returns the number of records written
+    pub fn export<W: Write>(&self, journal: &Journal, venue: &str, writer: W) -> Result<usize> {
+        self.export_range(journal, venue, 0, u64::MAX, writer)
+    }
+
+    /// Export a venue's entries with event times in `[start_ms, end_ms)`
+    pub fn export_range<W: Write>(&self, journal: &Journal, venue: &str, start_ms: u64, end_ms: u64, mut writer: W) -> Result<usize> {
+        let format = self.format_for(venue)
+            .ok_or_else(|| ExchangeError::ConfigurationError(format!("no audit format for venue {venue}")))?;
+        let entries: Vec<&JournalEntry> = journal.entries().iter()
+            .filter(|e| e.exchange == venue && (start_ms..end_ms).contains(&e.event.event_time()))
+            .collect();
+
+        let written = format.write(&entries, &mut writer)?;
+        writer.flush()?;
+        info!("📑 Exported {} {} records for {} as {}", written, entries.len(), venue, format.name());
+        Ok(written)
+    }
+}
+
+fn utc(time_ms: u64, format: &str) -> Result<String> {
+    chrono::DateTime::from_timestamp_millis(time_ms as i64)
+        .map(|t| t.format(format).to_string())
+        .ok_or_else(|| ExchangeError::InvalidResponse(format!("timestamp out of range: {time_ms}")))
+}
+
+fn side(side: OrderSide) -> &'static str {
+    match side {
+        OrderSide::Buy => "1",
+        OrderSide::Sell => "2",
+    }
+}
+
+fn ord_status(status: OrderStatus) -> &'static str {
+    match status {
+        OrderStatus::New => "0",
+        OrderStatus::PartiallyFilled => "1",
+        OrderStatus::Filled => "2",
+        OrderStatus::Canceled => "4",
+        OrderStatus::Rejected => "8",
+        OrderStatus::Expired => "C",
+    }
+}
+
+fn ord_type(order_type: OrderType) -> &'static str {
+    match order_type {
+        OrderType::Market => "1",
+        OrderType::Limit => "2",
+        OrderType::StopLoss => "3",
+        OrderType::StopLossLimit => "4",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixed(s: &str) -> Fixed {
+        Fixed::from_str_exact(s).unwrap()
+    }
+
+    fn order(status: OrderStatus, filled: &str, time: u64) -> JournalEvent {
+        JournalEvent::Order(OrderRecord {
+            client_order_id: "sq-1".to_string(),
+            order_id: Some("42".to_string()),
+            symbol: "BTCUSDT".to_string(),
+            side: OrderSide::Buy,
+            order_type: Some(OrderType::Limit),
+            status,
+            quantity: fixed("0.002"),
+            price: Some(fixed("50000")),
+            filled_quantity: fixed(filled),
+            average_price: (filled != "0").then(|| fixed("50000")),
+            update_time: time,
+        })
+    }
+
+    fn journal() -> Journal {
+        let mut journal = Journal::new();
+        journal.record("binance", order(OrderStatus::New, "0", 1_700_000_000_000));
+        journal.record("binance", JournalEvent::Trade(TradeRecord {
+            trade_id: 7,
+            order_id: 42,
+            symbol: "BTCUSDT".to_string(),
+            side: OrderSide::Buy,
+            price: fixed("50000"),
+            quantity: fixed("0.002"),
+            quote_quantity: fixed("100"),
+            commission: fixed("0.000002"),
+            commission_asset: "BTC".to_string(),
+            is_maker: true,
+            time: 1_700_000_001_000,
+        }));
+        journal.record("binance", order(OrderStatus::Filled, "0.002", 1_700_000_001_000));
+        journal.record("bybit", order(OrderStatus::New, "0", 1_700_000_002_000));
+        journal
+    }
+
+    #[test]
+    fn test_binance_order_csv() {
+        let exporter = ComplianceExporter::new().with_format("binance", BinanceOrderCsv);
+        let mut out = Vec::new();
+        assert_eq!(exporter.export(&journal(), "binance", &mut out).unwrap(), 1);
+
+        let csv = String::from_utf8(out).unwrap();
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1], "2023-11-14 22:13:20,42,BTCUSDT,LIMIT,BUY,50000,0.002,0.002,50000,100.000,FILLED");
+        assert!(exporter.export(&journal(), "bybit", Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_fix_drop_copy_frames_execution_reports() {
+        let exporter = ComplianceExporter::new().with_default(FixDropCopy::new("SRIQUANT").with_delimiter(b'|'));
+        let mut out = Vec::new();
+        assert_eq!(exporter.export(&journal(), "binance", &mut out).unwrap(), 3);
+
+        let text = String::from_utf8(out).unwrap();
+        let messages: Vec<&str> = text.lines().collect();
+        assert!(messages[0].starts_with("8=FIX.4.4|9="));
+        assert!(messages[0].contains("|35=8|49=SRIQUANT|56=BINANCE|34=1|"));
+        assert!(messages[0].contains("|150=0|39=0|55=BTCUSDT|54=1|40=2|38=0.002|44=50000|151=0.002|"));
+        assert!(messages[1].contains("|37=42|11=sq-1|17=7|150=F|39=2|"));
+        assert!(messages[1].contains("|32=0.002|31=50000|14=0.002|12=0.000002|13=3|479=BTC|851=1|60=20231114-22:13:21.000|"));
+
+        // BodyLength and CheckSum hold for the SOH framing
+        for message in messages {
+            let soh = message.replace('|', "\x01");
+            let (head, trailer) = soh.split_at(soh.len() - 7);
+            let checksum = head.bytes().map(u32::from).sum::<u32>() % 256;
+            assert_eq!(trailer, format!("10={checksum:03}\x01"));
+            let body_start = head.find("35=").unwrap();
+            let length: usize = head[..body_start].trim_end_matches('\x01').rsplit('=').next().unwrap().parse().unwrap();
+            assert_eq!(length, head.len() - body_start);
+        }
+    }
+}
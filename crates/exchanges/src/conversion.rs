@@ -0,0 +1,254 @@
+//! Asset conversion with triangulation
+//!
+//! `CurrencyConverter` answers "how many `to` per one `from`" from the latest
+//! pair prices: a direct pair (either direction) first, then paths through
+//! bridge assets (USDT and BTC by default), e.g. ADA→BTC→ETH when ADAETH
+//! isn't listed. Used for portfolio valuation, risk limits in a common
+//! currency and notional checks on pairs quoted in something else.
+//!
+//! Rates are `f64`: cross rates between low-priced assets fall below `Fixed`
+//! precision, and portfolio values exceed its range.
+
+use crate::binance::rest::PriceTicker;
+use crate::types::{Balance, Symbol};
+use sriquant_core::prelude::*;
+
+use std::collections::HashMap;
+use tracing::debug;
+
+/// A resolved rate and the assets it went through
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversionRate {
+    /// Units of the target asset per unit of the source asset
+    pub rate: f64,
+    /// Source, any bridges, target
+    pub path: Vec<String>,
+    /// Oldest price used, in milliseconds
+    pub as_of: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PairPrice {
+    price: f64,
+    updated_at: u64,
+}
+
+/// Latest pair prices and the bridges used to triangulate between them
+#[derive(Debug, Clone)]
+pub struct CurrencyConverter {
+    bridges: Vec<String>,
+    max_age_ms: Option<u64>,
+    /// Symbol -> (base, quote)
+    pairs: HashMap<String, (String, String)>,
+    /// (base, quote) -> price of one base in quote
+    prices: HashMap<(String, String), PairPrice>,
+}
+
+impl Default for CurrencyConverter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CurrencyConverter {
+    pub fn new() -> Self {
+        Self {
+            bridges: vec!["USDT".to_string(), "BTC".to_string()],
+            max_age_ms: None,
+            pairs: HashMap::new(),
+            prices: HashMap::new(),
+        }
+    }
+
+    /// Assets to route through when no direct pair exists, in preference order
+    pub fn with_bridges(mut self, bridges: &[&str]) -> Self {
+        self.bridges = bridges.iter().map(|b| b.to_string()).collect();
+        self
+    }
+
+    /// Ignore prices older than this when resolving
+    pub fn with_max_age_ms(mut self, max_age_ms: u64) -> Self {
+        self.max_age_ms = Some(max_age_ms);
+        self
+    }
+
+    /// Learn which assets each symbol trades
+    pub fn register_symbols<'a>(&mut self, symbols: impl IntoIterator<Item = &'a Symbol>) {
+        for symbol in symbols {
+            self.pairs.insert(symbol.symbol.clone(), (symbol.base_asset.clone(), symbol.quote_asset.clone()));
+        }
+    }
+
+    /// Set the price of one `base` in `quote`
+    pub fn set_price(&mut self, base: &str, quote: &str, price: f64, now_ms: u64) {
+        if !(price.is_finite() && price > 0.0) {
+            return;
+        }
+        self.prices.insert((base.to_string(), quote.to_string()), PairPrice { price, updated_at: now_ms });
+    }
+
+    /// Set a price by symbol; `false` if the symbol wasn't registered
+    pub fn update_symbol(&mut self, symbol: &str, price: f64, now_ms: u64) -> bool {
+        let Some((base, quote)) = self.pairs.get(symbol).cloned() else {
+            return false;
+        };
+        self.set_price(&base, &quote, price, now_ms);
+        true
+    }
+
+    /// Ingest `/api/v3/ticker/price` results for registered symbols
+    pub fn update_from_tickers(&mut self, tickers: &[PriceTicker], now_ms: u64) -> usize {
+        tickers.iter()
+            .filter(|t| self.update_symbol(&t.symbol, t.price.to_f64_lossy(), now_ms))
+            .count()
+    }
+
+    /// Rate of one direct hop, using the inverse pair if needed
+    fn hop(&self, from: &str, to: &str, now_ms: u64) -> Option<(f64, u64)> {
+        let fresh = |p: &PairPrice| self.max_age_ms.is_none_or(|max| now_ms.saturating_sub(p.updated_at) <= max);
+        if let Some(p) = self.prices.get(&(from.to_string(), to.to_string())).filter(|p| fresh(p)) {
+            return Some((p.price, p.updated_at));
+        }
+        self.prices.get(&(to.to_string(), from.to_string()))
+            .filter(|p| fresh(p))
+            .map(|p| (1.0 / p.price, p.updated_at))
+    }
+
+    /// Resolve a rate from `from` to `to`
+    ///
+    /// Tries the direct pair, then each bridge, then each ordered pair of
+    /// bridges (e.g. XYZ→BTC→USDT→ABC).
+    pub fn rate(&self, from: &str, to: &str, now_ms: u64) -> Option<ConversionRate> {
+        if from == to {
+            return Some(ConversionRate { rate: 1.0, path: vec![from.to_string()], as_of: now_ms });
+        }
+        if let Some((rate, as_of)) = self.hop(from, to, now_ms) {
+            return Some(ConversionRate { rate, path: vec![from.to_string(), to.to_string()], as_of });
+        }
+
+        let chain = |assets: &[&str]| -> Option<ConversionRate> {
+            let mut rate = 1.0;
+            let mut as_of = u64::MAX;
+            for window in assets.windows(2) {
+                let (hop_rate, updated_at) = self.hop(window[0], window[1], now_ms)?;
+                rate *= hop_rate;
+                as_of = as_of.min(updated_at);
+            }
+            Some(ConversionRate { rate, path: assets.iter().map(|a| a.to_string()).collect(), as_of })
+        };
+
+        let bridges = self.bridges.iter().map(String::as_str).filter(|b| *b != from && *b != to);
+        for bridge in bridges.clone() {
+            if let Some(rate) = chain(&[from, bridge, to]) {
+                return Some(rate);
+            }
+        }
+        for first in bridges.clone() {
+            for second in bridges.clone().filter(|b| *b != first) {
+                if let Some(rate) = chain(&[from, first, second, to]) {
+                    return Some(rate);
+                }
+            }
+        }
+        debug!("💱 No conversion path from {} to {}", from, to);
+        None
+    }
+
+    /// Convert an amount of `from` into `to`
+    pub fn convert(&self, amount: f64, from: &str, to: &str, now_ms: u64) -> Option<f64> {
+        self.rate(from, to, now_ms).map(|r| amount * r.rate)
+    }
+
+    /// Convert a `Fixed` amount; `None` if no path exists or the result leaves the `Fixed` range
+    pub fn convert_fixed(&self, amount: Fixed, from: &str, to: &str, now_ms: u64) -> Option<Fixed> {
+        let value = self.convert(amount.to_f64_lossy(), from, to, now_ms)?;
+        Fixed::from_f64(value).ok().map(|v| v.round_dp(8))
+    }
+
+    /// Notional of `quantity` at `price` on `symbol`, expressed in `asset`
+    ///
+    /// Lets a notional cap in USDT apply to e.g. ETHBTC orders.
+    pub fn notional_in(&self, symbol: &str, quantity: Fixed, price: Fixed, asset: &str, now_ms: u64) -> Option<f64> {
+        let (_, quote) = self.pairs.get(symbol)?;
+        self.convert(quantity.to_f64_lossy() * price.to_f64_lossy(), quote, asset, now_ms)
+    }
+
+    /// Total value of balances in `asset`, plus the assets that couldn't be priced
+    pub fn value_balances(&self, balances: &[Balance], asset: &str, now_ms: u64) -> (f64, Vec<String>) {
+        let mut total = 0.0;
+        let mut unpriced = Vec::new();
+        for balance in balances.iter().filter(|b| !b.total().is_zero()) {
+            match self.convert(balance.total().to_f64_lossy(), &balance.asset, asset, now_ms) {
+                Some(value) => total += value,
+                None => unpriced.push(balance.asset.clone()),
+            }
+        }
+        (total, unpriced)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_direct_inverse_and_triangulated_rates() {
+        let mut converter = CurrencyConverter::new().with_max_age_ms(60_000);
+        converter.set_price("BTC", "USDT", 60_000.0, 1_000);
+        converter.set_price("ETH", "BTC", 0.05, 1_000);
+        converter.set_price("ADA", "ETH", 0.0002, 1_000);
+        converter.set_price("USDT", "INR", 85.0, 1_000);
+
+        assert_eq!(converter.rate("USDT", "BTC", 1_000).unwrap().path, vec!["USDT", "BTC"]);
+        let eth = converter.rate("ETH", "USDT", 1_000).unwrap();
+        assert_eq!(eth.path, vec!["ETH", "BTC", "USDT"]);
+        assert!((eth.rate - 3_000.0).abs() < 1e-9);
+
+        // Two bridges: ETH -> BTC -> USDT -> INR
+        let inr = converter.convert(1.0, "ETH", "INR", 1_000).unwrap();
+        assert!((inr - 255_000.0).abs() < 1e-6);
+
+        // ADA only trades against ETH, which isn't a bridge
+        assert!(converter.rate("ADA", "USDT", 1_000).is_none());
+        // Stale prices are ignored
+        assert!(converter.rate("BTC", "USDT", 120_000).is_none());
+    }
+
+    #[test]
+    fn test_balances_and_notional() {
+        let mut converter = CurrencyConverter::new();
+        let symbol = |s: &str, base: &str, quote: &str| Symbol {
+            symbol: s.to_string(),
+            base_asset: base.to_string(),
+            quote_asset: quote.to_string(),
+            status: "TRADING".to_string(),
+            min_quantity: Fixed::ZERO,
+            max_quantity: Fixed::ZERO,
+            quantity_precision: 8,
+            min_price: Fixed::ZERO,
+            max_price: Fixed::ZERO,
+            price_precision: 8,
+            min_notional: Fixed::ZERO,
+        };
+        let symbols = [symbol("BTCUSDT", "BTC", "USDT"), symbol("ETHBTC", "ETH", "BTC")];
+        converter.register_symbols(&symbols);
+        let tickers = [
+            PriceTicker { symbol: "BTCUSDT".to_string(), price: Fixed::from_str_exact("60000").unwrap() },
+            PriceTicker { symbol: "ETHBTC".to_string(), price: Fixed::from_str_exact("0.05").unwrap() },
+            PriceTicker { symbol: "XYZUSDT".to_string(), price: Fixed::from_str_exact("1").unwrap() },
+        ];
+        assert_eq!(converter.update_from_tickers(&tickers, 0), 2);
+
+        let qty = Fixed::from_str_exact("2").unwrap();
+        let price = Fixed::from_str_exact("0.05").unwrap();
+        assert!((converter.notional_in("ETHBTC", qty, price, "USDT", 0).unwrap() - 6_000.0).abs() < 1e-6);
+
+        let balances = [
+            Balance { asset: "ETH".to_string(), free: Fixed::ONE, locked: Fixed::ZERO },
+            Balance { asset: "DOGE".to_string(), free: Fixed::ONE, locked: Fixed::ZERO },
+        ];
+        let (total, unpriced) = converter.value_balances(&balances, "USDT", 0);
+        assert!((total - 3_000.0).abs() < 1e-6);
+        assert_eq!(unpriced, vec!["DOGE"]);
+    }
+}
@@ -0,0 +1,166 @@
+//! Background disk writer shared by the on-disk stores
+//!
+//! `KvStore` and `BookSnapshotStore` keep their hot paths free of disk I/O
+//! by handing writes to a dedicated thread. `BackgroundWriter` is that
+//! thread: it wakes on the first queued item, takes the rest of the burst
+//! with it, and passes the batch to the store's write function in order.
+//! `flush().await` replies once everything queued before it is written,
+//! with the last write error since the previous flush.
+//!
+//! `replace_atomic` is the crash-safe file replace both stores use.
+
+use crate::errors::{ExchangeError, Result};
+use sriquant_core::channel::{bounded, oneshot, OneshotSender, Sender};
+
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::thread::JoinHandle;
+use tracing::warn;
+
+/// Items queued before `send` waits for the writer thread to catch up
+const WRITE_QUEUE_CAPACITY: usize = 4096;
+
+enum WriteOp<T> {
+    Item(T),
+    /// Reply once every earlier item is written, with the last write error
+    Flush(OneshotSender<std::result::Result<(), String>>),
+}
+
+/// Owns the writer thread; dropping it drains the queue and joins the thread
+pub(crate) struct BackgroundWriter<T> {
+    label: &'static str,
+    tx: Option<Sender<WriteOp<T>>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl<T: Send + 'static> BackgroundWriter<T> {
+    /// Start a thread that hands each burst of items to `write`, which
+    /// returns the last error it hit, if any
+    ///
+    /// `label` names the store in errors and logs, e.g. "KV store".
+    pub(crate) fn spawn(label: &'static str, mut write: impl FnMut(Vec<T>) -> Option<String> + Send + 'static) -> Result<Self> {
+        let (tx, mut rx) = bounded::<WriteOp<T>>(WRITE_QUEUE_CAPACITY);
+        let handle = std::thread::Builder::new()
+            .name(format!("{}-writer", label.to_ascii_lowercase().replace(' ', "-")))
+            .spawn(move || {
+                let mut last_error: Option<String> = None;
+                while let Some(op) = rx.recv_blocking() {
+                    let mut items = Vec::new();
+                    let mut waiters = Vec::new();
+                    for op in std::iter::once(op).chain(std::iter::from_fn(|| rx.try_recv().ok())) {
+                        match op {
+                            WriteOp::Item(item) => items.push(item),
+                            WriteOp::Flush(reply) => waiters.push(reply),
+                        }
+                    }
+                    if let Some(error) = write(items) {
+                        last_error = Some(error);
+                    }
+                    for reply in waiters {
+                        let _ = reply.send(last_error.take().map_or(Ok(()), Err));
+                    }
+                }
+            })?;
+        Ok(Self { label, tx: Some(tx), handle: Some(handle) })
+    }
+
+    /// Queue an item for writing
+    pub(crate) fn send(&self, item: T) -> Result<()> {
+        self.enqueue(WriteOp::Item(item))
+    }
+
+    /// Wait until every item queued so far is written
+    pub(crate) async fn flush(&self) -> Result<()> {
+        let (reply_tx, reply_rx) = oneshot();
+        self.enqueue(WriteOp::Flush(reply_tx))?;
+        reply_rx.await
+            .map_err(|_| self.stopped())?
+            .map_err(ExchangeError::IoError)
+    }
+
+    fn enqueue(&self, op: WriteOp<T>) -> Result<()> {
+        self.tx.as_ref()
+            .and_then(|tx| tx.send_blocking(op).ok())
+            .ok_or_else(|| self.stopped())
+    }
+
+    fn stopped(&self) -> ExchangeError {
+        ExchangeError::IoError(format!("{} writer stopped", self.label))
+    }
+}
+
+impl<T> Drop for BackgroundWriter<T> {
+    fn drop(&mut self) {
+        self.tx.take();
+        if let Some(handle) = self.handle.take()
+            && handle.join().is_err()
+        {
+            warn!("❗ {} writer thread panicked", self.label);
+        }
+    }
+}
+
+/// Replace `path` with `contents` so a crash leaves the old or the new file
+///
+/// The contents go to `<path>.tmp` and are synced before the rename, and
+/// the directory is synced after it, so the rename can't reach the disk
+/// ahead of the data or be lost itself.
+pub(crate) fn replace_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let mut file = File::create(&tmp)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    drop(file);
+    std::fs::rename(&tmp, path)?;
+    sync_parent(path)
+}
+
+#[cfg(unix)]
+fn sync_parent(path: &Path) -> std::io::Result<()> {
+    match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => File::open(dir)?.sync_all(),
+        _ => File::open(".")?.sync_all(),
+    }
+}
+
+/// Directories can't be opened for syncing here; the rename is as durable as the OS makes it
+#[cfg(not(unix))]
+fn sync_parent(_path: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[monoio::test]
+    async fn test_flush_reports_the_last_error_once() {
+        let written = Arc::new(Mutex::new(Vec::<u32>::new()));
+        let sink = Arc::clone(&written);
+        let writer = BackgroundWriter::spawn("test store", move |items: Vec<u32>| {
+            sink.lock().unwrap().extend(&items);
+            items.contains(&13).then(|| "unlucky".to_string())
+        }).unwrap();
+
+        for i in [1, 13, 2] {
+            writer.send(i).unwrap();
+        }
+        assert!(matches!(writer.flush().await, Err(ExchangeError::IoError(e)) if e == "unlucky"));
+        writer.send(3).unwrap();
+        writer.flush().await.unwrap();
+        drop(writer);
+        assert_eq!(*written.lock().unwrap(), vec![1, 13, 2, 3]);
+
+        let dir = std::env::temp_dir().join(format!("sriquant-disk-writer-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("state.json");
+        replace_atomic(&path, b"old").unwrap();
+        replace_atomic(&path, b"new").unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"new");
+        assert!(!dir.join("state.json.tmp").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -2,9 +2,18 @@
 //!
 //! High-performance architecture with comprehensive error handling
 //! and performance-optimized error propagation.
+//!
+//! Underlying errors are kept as `source()` rather than flattened to strings,
+//! and `with_endpoint`/`with_symbol`/`with_payload` attach where a failure
+//! happened, so a decode error names the endpoint and quotes the payload.
 
+use std::fmt;
+use std::sync::Arc;
 use thiserror::Error;
 
+/// Longest payload excerpt kept in an error
+const PAYLOAD_SNIPPET_LEN: usize = 256;
+
 /// Result type for exchange operations
 pub type Result<T> = std::result::Result<T, ExchangeError>;
 
@@ -17,15 +26,26 @@ pub enum ExchangeError {
     #[error("HTTP error {0}: {1}")]
     HttpError(u16, String),
     
+    #[error("Binance API error (HTTP {0}): {1}")]
+    BinanceApi(u16, crate::binance::BinanceApiError),
+    
     #[error("Invalid URL: {0}")]
     InvalidUrl(String),
     
     #[error("Serialization error: {0}")]
     SerializationError(String),
     
+    /// A JSON decode/encode failure, with the serde error as source
+    #[error("Serialization error: {0}")]
+    Json(#[source] Arc<serde_json::Error>),
+    
     #[error("Invalid response: {0}")]
     InvalidResponse(String),
-    
+
+    /// A required payload field was absent, rather than defaulted to zero
+    #[error("Missing field: {0}")]
+    MissingField(String),
+
     #[error("Authentication failed")]
     AuthenticationFailed,
     
@@ -91,6 +111,131 @@ pub enum ExchangeError {
     
     #[error("Fixed point error: {0}")]
     FixedPointError(String),
+    
+    #[error("I/O error: {0}")]
+    IoError(String),
+
+    #[error("Risk limit exceeded: {0}")]
+    RiskLimitExceeded(String),
+    
+    /// Another error plus where it happened
+    #[error("{source} ({context})")]
+    Context {
+        context: ErrorContext,
+        #[source]
+        source: Box<ExchangeError>,
+    },
+}
+
+/// Where an error happened
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ErrorContext {
+    pub endpoint: Option<String>,
+    pub symbol: Option<String>,
+    /// Start of the offending payload
+    pub payload: Option<String>,
+}
+
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(endpoint) = &self.endpoint {
+            parts.push(format!("endpoint {endpoint}"));
+        }
+        if let Some(symbol) = &self.symbol {
+            parts.push(format!("symbol {symbol}"));
+        }
+        if let Some(payload) = &self.payload {
+            parts.push(format!("payload `{payload}`"));
+        }
+        f.write_str(&parts.join(", "))
+    }
+}
+
+/// Cut a payload to `PAYLOAD_SNIPPET_LEN` bytes on a char boundary
+fn snippet(payload: &str) -> String {
+    if payload.len() <= PAYLOAD_SNIPPET_LEN {
+        return payload.to_string();
+    }
+    let mut end = PAYLOAD_SNIPPET_LEN;
+    while !payload.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}…", &payload[..end])
+}
+
+impl ExchangeError {
+    /// The typed Binance rejection, if this error is one
+    pub fn binance_api(&self) -> Option<&crate::binance::BinanceApiError> {
+        match self.root() {
+            Self::BinanceApi(_, error) => Some(error),
+            _ => None,
+        }
+    }
+
+    /// The error with any context stripped, for matching on the kind
+    pub fn root(&self) -> &ExchangeError {
+        match self {
+            Self::Context { source, .. } => source.root(),
+            other => other,
+        }
+    }
+
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            Self::Context { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// Add to the existing context rather than nesting another layer
+    fn update_context(self, update: impl FnOnce(&mut ErrorContext)) -> Self {
+        match self {
+            Self::Context { mut context, source } => {
+                update(&mut context);
+                Self::Context { context, source }
+            }
+            other => {
+                let mut context = ErrorContext::default();
+                update(&mut context);
+                Self::Context { context, source: Box::new(other) }
+            }
+        }
+    }
+
+    pub fn with_endpoint(self, endpoint: &str) -> Self {
+        self.update_context(|c| c.endpoint = Some(endpoint.to_string()))
+    }
+
+    pub fn with_symbol(self, symbol: &str) -> Self {
+        self.update_context(|c| c.symbol = Some(symbol.to_string()))
+    }
+
+    /// Quote the start of the payload that failed
+    pub fn with_payload(self, payload: &str) -> Self {
+        self.update_context(|c| c.payload = Some(snippet(payload)))
+    }
+}
+
+/// Context helpers on `Result`
+pub trait ResultExt<T> {
+    fn with_endpoint(self, endpoint: &str) -> Result<T>;
+    fn with_symbol(self, symbol: &str) -> Result<T>;
+    fn with_payload(self, payload: &str) -> Result<T>;
+}
+
+impl<T> ResultExt<T> for Result<T> {
+    fn with_endpoint(self, endpoint: &str) -> Result<T> {
+        self.map_err(|e| e.with_endpoint(endpoint))
+    }
+
+    fn with_symbol(self, symbol: &str) -> Result<T> {
+        self.map_err(|e| e.with_symbol(symbol))
+    }
+
+    fn with_payload(self, payload: &str) -> Result<T> {
+        self.map_err(|e| e.with_payload(payload))
+    }
 }
 
 impl From<sriquant_core::fixed::FixedError> for ExchangeError {
@@ -101,7 +246,13 @@ impl From<sriquant_core::fixed::FixedError> for ExchangeError {
 
 impl From<serde_json::Error> for ExchangeError {
     fn from(err: serde_json::Error) -> Self {
-        Self::SerializationError(err.to_string())
+        Self::Json(Arc::new(err))
+    }
+}
+
+impl From<std::io::Error> for ExchangeError {
+    fn from(err: std::io::Error) -> Self {
+        Self::IoError(err.to_string())
     }
 }
 
@@ -178,4 +329,30 @@ impl From<u16> for ErrorCode {
             _ => ErrorCode::Unknown,
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as _;
+
+    #[test]
+    fn test_context_names_endpoint_and_keeps_source() {
+        let body = format!(r#"{{"symbol":"BTCUSDT","price":{}}}"#, "1".repeat(400));
+        let parsed: Result<u64> = serde_json::from_str::<u64>(&body)
+            .map_err(ExchangeError::from)
+            .with_endpoint("/api/v3/ticker/price")
+            .with_symbol("BTCUSDT")
+            .with_payload(&body);
+        let err = parsed.unwrap_err();
+
+        let message = err.to_string();
+        assert!(message.contains("endpoint /api/v3/ticker/price"));
+        assert!(message.contains(r#"payload `{"symbol":"BTCUSDT""#));
+        assert!(err.context().unwrap().payload.as_ref().unwrap().len() < body.len());
+
+        // Context merges rather than nesting, and the serde error stays reachable
+        assert!(matches!(err.root(), ExchangeError::Json(_)));
+        let source = err.source().unwrap();
+        assert!(source.source().unwrap().downcast_ref::<Arc<serde_json::Error>>().is_some());
+    }
+}
@@ -0,0 +1,440 @@
+//! Fee tier tracking and fee-aware edge calculation
+//!
+//! Exchanges price fees by the account's rolling 30-day traded volume. The
+//! `FeeTierTracker` derives that volume from journaled trades, reports the
+//! current tier, and projects where the account will be after a horizon at
+//! the recent run rate (accounting for volume rolling out of the window).
+//! The `EdgeCalculator` nets fees out of a strategy's gross edge and is
+//! re-synced from the tracker, so quoting adapts when the account crosses a
+//! tier boundary. A `FeePreference` captures which asset pays fees (e.g.
+//! BNB burn on Binance) so both edge and realized-PnL math use the fee
+//! actually charged.
+//!
+//! Volumes are in quote currency as `f64`; 30-day totals routinely exceed the
+//! range of `Fixed`.
+
+use crate::errors::{ExchangeError, Result};
+use crate::journal::{Journal, TradeRecord};
+
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+/// Length of the fee evaluation window
+pub const FEE_WINDOW_DAYS: u64 = 30;
+
+/// One fee tier
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeeTier {
+    pub name: String,
+    /// Minimum 30-day quote volume to qualify
+    pub min_volume: f64,
+    pub maker_bps: f64,
+    pub taker_bps: f64,
+}
+
+impl FeeTier {
+    pub fn new(name: &str, min_volume: f64, maker_bps: f64, taker_bps: f64) -> Self {
+        Self {
+            name: name.to_string(),
+            min_volume,
+            maker_bps,
+            taker_bps,
+        }
+    }
+
+    /// Fee in basis points for a maker or taker execution
+    pub fn fee_bps(&self, is_maker: bool) -> f64 {
+        if is_maker { self.maker_bps } else { self.taker_bps }
+    }
+}
+
+/// Asset commissions are charged in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FeeAsset {
+    /// Deducted from the asset received in the trade
+    Traded,
+    /// Paid in BNB at a discount (Binance BNB burn)
+    Bnb,
+}
+
+/// How fees are paid on the account
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FeePreference {
+    pub asset: FeeAsset,
+    /// Fractional discount when paying in BNB (0.25 = 25% off)
+    pub bnb_discount: f64,
+}
+
+impl Default for FeePreference {
+    fn default() -> Self {
+        Self { asset: FeeAsset::Traded, bnb_discount: 0.0 }
+    }
+}
+
+impl FeePreference {
+    /// Binance spot: 25% off when fees are paid by burning BNB
+    pub fn binance_spot(bnb_burn: bool) -> Self {
+        if bnb_burn {
+            Self { asset: FeeAsset::Bnb, bnb_discount: 0.25 }
+        } else {
+            Self::default()
+        }
+    }
+
+    /// Multiplier applied to schedule rates
+    pub fn rate_multiplier(&self) -> f64 {
+        match self.asset {
+            FeeAsset::Bnb => 1.0 - self.bnb_discount,
+            FeeAsset::Traded => 1.0,
+        }
+    }
+}
+
+/// Commission of a trade valued in `quote_asset`
+///
+/// Commissions charged in BNB need `bnb_quote_price`; returns `None` if the
+/// commission asset can't be valued.
+pub fn commission_in_quote(trade: &TradeRecord, quote_asset: &str, bnb_quote_price: Option<f64>) -> Option<f64> {
+    let commission = trade.commission.to_f64_lossy();
+    if trade.commission_asset == quote_asset {
+        return Some(commission);
+    }
+    let base_asset = trade.symbol.strip_suffix(quote_asset)?;
+    if trade.commission_asset == base_asset {
+        Some(commission * trade.price.to_f64_lossy())
+    } else if trade.commission_asset == "BNB" {
+        bnb_quote_price.map(|price| commission * price)
+    } else {
+        None
+    }
+}
+
+/// Ordered set of fee tiers for a venue
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "FeeTiers")]
+pub struct FeeSchedule {
+    /// Sorted by ascending `min_volume`, never empty
+    tiers: Vec<FeeTier>,
+}
+
+/// Deserialized tiers, checked by `FeeSchedule::new`
+#[derive(Deserialize)]
+struct FeeTiers {
+    tiers: Vec<FeeTier>,
+}
+
+impl TryFrom<FeeTiers> for FeeSchedule {
+    type Error = ExchangeError;
+
+    fn try_from(raw: FeeTiers) -> Result<Self> {
+        Self::new(raw.tiers)
+    }
+}
+
+impl FeeSchedule {
+    /// Build a schedule; tiers are sorted by volume threshold
+    ///
+    /// Fails without any tier, since every volume has to map to one.
+    pub fn new(mut tiers: Vec<FeeTier>) -> Result<Self> {
+        if tiers.is_empty() {
+            return Err(ExchangeError::ConfigurationError("fee schedule needs at least one tier".to_string()));
+        }
+        tiers.sort_by(|a, b| a.min_volume.total_cmp(&b.min_volume));
+        Ok(Self { tiers })
+    }
+
+    /// Binance spot VIP schedule (volume-only qualification, no BNB discount)
+    pub fn binance_spot() -> Self {
+        Self {
+            tiers: vec![
+                FeeTier::new("VIP0", 0.0, 10.0, 10.0),
+                FeeTier::new("VIP1", 1_000_000.0, 9.0, 10.0),
+                FeeTier::new("VIP2", 5_000_000.0, 8.0, 10.0),
+                FeeTier::new("VIP3", 20_000_000.0, 4.2, 6.0),
+                FeeTier::new("VIP4", 100_000_000.0, 4.2, 5.4),
+                FeeTier::new("VIP5", 150_000_000.0, 3.6, 4.8),
+                FeeTier::new("VIP6", 400_000_000.0, 3.0, 4.2),
+                FeeTier::new("VIP7", 800_000_000.0, 2.4, 3.6),
+                FeeTier::new("VIP8", 2_000_000_000.0, 1.8, 3.0),
+                FeeTier::new("VIP9", 4_000_000_000.0, 1.2, 2.4),
+            ],
+        }
+    }
+
+    /// Tier for a 30-day volume
+    pub fn tier_for(&self, volume: f64) -> &FeeTier {
+        self.tiers
+            .iter()
+            .rev()
+            .find(|t| volume >= t.min_volume)
+            .unwrap_or(&self.tiers[0])
+    }
+
+    /// Next tier above the one for `volume`, if any
+    pub fn next_tier(&self, volume: f64) -> Option<&FeeTier> {
+        self.tiers.iter().find(|t| t.min_volume > volume)
+    }
+
+    pub fn tiers(&self) -> &[FeeTier] {
+        &self.tiers
+    }
+}
+
+/// Rolling 30-day volume and tier projection
+#[derive(Debug, Clone)]
+pub struct FeeTierTracker {
+    schedule: FeeSchedule,
+    /// (time ms, quote volume) per trade within the window
+    trades: Vec<(u64, f64)>,
+    now_ms: u64,
+}
+
+/// Where the account stands and is heading
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeeTierStatus {
+    pub rolling_volume: f64,
+    pub current_tier: FeeTier,
+    pub projected_volume: f64,
+    pub projected_tier: FeeTier,
+    /// Volume still needed for the next tier
+    pub volume_to_next_tier: Option<f64>,
+}
+
+impl FeeTierTracker {
+    pub fn new(schedule: FeeSchedule) -> Self {
+        Self {
+            schedule,
+            trades: Vec::new(),
+            now_ms: 0,
+        }
+    }
+
+    /// Rebuild the window from journaled trades
+    pub fn update_from_journal(&mut self, journal: &Journal, now_ms: u64) {
+        self.trades = journal
+            .trades()
+            .map(|t| (t.time, t.quote_quantity.to_f64_lossy()))
+            .collect();
+        self.advance(now_ms);
+    }
+
+    /// Add a single trade (e.g. from a live execution report)
+    pub fn record_trade(&mut self, time_ms: u64, quote_volume: f64) {
+        self.trades.push((time_ms, quote_volume));
+        self.now_ms = self.now_ms.max(time_ms);
+    }
+
+    /// Move the window forward, dropping expired trades
+    pub fn advance(&mut self, now_ms: u64) {
+        self.now_ms = now_ms;
+        let start = self.window_start(now_ms);
+        self.trades.retain(|(time, _)| *time >= start);
+    }
+
+    fn window_start(&self, now_ms: u64) -> u64 {
+        now_ms.saturating_sub(FEE_WINDOW_DAYS * DAY_MS)
+    }
+
+    fn volume_between(&self, from_ms: u64, to_ms: u64) -> f64 {
+        self.trades
+            .iter()
+            .filter(|(time, _)| *time >= from_ms && *time <= to_ms)
+            .map(|(_, volume)| volume)
+            .sum()
+    }
+
+    /// Traded quote volume over the last 30 days
+    pub fn rolling_volume(&self) -> f64 {
+        self.volume_between(self.window_start(self.now_ms), self.now_ms)
+    }
+
+    /// Average daily volume over the last `days`
+    pub fn daily_run_rate(&self, days: u64) -> f64 {
+        let days = days.clamp(1, FEE_WINDOW_DAYS);
+        self.volume_between(self.now_ms.saturating_sub(days * DAY_MS), self.now_ms) / days as f64
+    }
+
+    /// 30-day volume expected after `horizon_days`, at the 7-day run rate
+    pub fn projected_volume(&self, horizon_days: u64) -> f64 {
+        let horizon_days = horizon_days.min(FEE_WINDOW_DAYS);
+        let future_start = self.window_start(self.now_ms) + horizon_days * DAY_MS;
+        let retained = self.volume_between(future_start, self.now_ms);
+        retained + self.daily_run_rate(7) * horizon_days as f64
+    }
+
+    pub fn current_tier(&self) -> &FeeTier {
+        self.schedule.tier_for(self.rolling_volume())
+    }
+
+    /// Current standing plus projection over `horizon_days`
+    pub fn status(&self, horizon_days: u64) -> FeeTierStatus {
+        let rolling_volume = self.rolling_volume();
+        let projected_volume = self.projected_volume(horizon_days);
+        FeeTierStatus {
+            rolling_volume,
+            current_tier: self.schedule.tier_for(rolling_volume).clone(),
+            projected_volume,
+            projected_tier: self.schedule.tier_for(projected_volume).clone(),
+            volume_to_next_tier: self.schedule.next_tier(rolling_volume).map(|t| t.min_volume - rolling_volume),
+        }
+    }
+
+    pub fn schedule(&self) -> &FeeSchedule {
+        &self.schedule
+    }
+}
+
+/// Nets fees out of gross edge for quoting decisions
+#[derive(Debug, Clone)]
+pub struct EdgeCalculator {
+    tier: FeeTier,
+    preference: FeePreference,
+    /// Minimum edge required after fees
+    min_net_edge_bps: f64,
+}
+
+impl EdgeCalculator {
+    pub fn new(tier: FeeTier, min_net_edge_bps: f64) -> Self {
+        Self { tier, preference: FeePreference::default(), min_net_edge_bps }
+    }
+
+    /// Apply the account's fee asset preference (e.g. from `BnbBurnStatus`)
+    pub fn with_fee_preference(mut self, preference: FeePreference) -> Self {
+        self.preference = preference;
+        self
+    }
+
+    pub fn set_fee_preference(&mut self, preference: FeePreference) {
+        self.preference = preference;
+    }
+
+    /// Fee actually charged, after any fee-asset discount
+    pub fn effective_fee_bps(&self, is_maker: bool) -> f64 {
+        self.tier.fee_bps(is_maker) * self.preference.rate_multiplier()
+    }
+
+    /// Adopt the tracker's current tier; returns `true` if the tier changed
+    pub fn sync(&mut self, tracker: &FeeTierTracker) -> bool {
+        let tier = tracker.current_tier();
+        if tier.name == self.tier.name {
+            return false;
+        }
+        info!(
+            "💸 Fee tier changed {} -> {} (maker {}bps, taker {}bps)",
+            self.tier.name, tier.name, tier.maker_bps, tier.taker_bps
+        );
+        self.tier = tier.clone();
+        true
+    }
+
+    pub fn tier(&self) -> &FeeTier {
+        &self.tier
+    }
+
+    /// Edge remaining after the execution fee
+    pub fn net_edge_bps(&self, gross_edge_bps: f64, is_maker: bool) -> f64 {
+        gross_edge_bps - self.effective_fee_bps(is_maker)
+    }
+
+    /// Whether an opportunity clears the minimum net edge
+    pub fn is_profitable(&self, gross_edge_bps: f64, is_maker: bool) -> bool {
+        self.net_edge_bps(gross_edge_bps, is_maker) >= self.min_net_edge_bps
+    }
+
+    /// Smallest half-spread, in bps of mid, a maker quote needs to clear fees
+    pub fn min_quote_half_spread_bps(&self) -> f64 {
+        self.effective_fee_bps(true) + self.min_net_edge_bps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::journal::{JournalEvent, TradeRecord};
+    use crate::types::OrderSide;
+    use sriquant_core::prelude::*;
+
+    fn trade(id: u64, time: u64, quote: &str) -> JournalEvent {
+        JournalEvent::Trade(TradeRecord {
+            trade_id: id,
+            order_id: id,
+            symbol: "BTCUSDT".to_string(),
+            side: OrderSide::Buy,
+            price: Fixed::from_str_exact("100000").unwrap(),
+            quantity: Fixed::from_str_exact("1").unwrap(),
+            quote_quantity: Fixed::from_str_exact(quote).unwrap(),
+            commission: Fixed::ZERO,
+            commission_asset: "BNB".to_string(),
+            is_maker: true,
+            time,
+        })
+    }
+
+    #[test]
+    fn test_tier_from_journal_and_projection() {
+        let now = 100 * DAY_MS;
+        let mut journal = Journal::new();
+        // Expired trade, one rolling off in 5 days, and recent daily flow
+        journal.record("binance", trade(1, now - 40 * DAY_MS, "900000"));
+        journal.record("binance", trade(2, now - 27 * DAY_MS, "400000"));
+        for day in 0..7 {
+            journal.record("binance", trade(10 + day, now - day * DAY_MS, "100000"));
+        }
+
+        let mut tracker = FeeTierTracker::new(FeeSchedule::binance_spot());
+        tracker.update_from_journal(&journal, now);
+
+        assert_eq!(tracker.rolling_volume(), 1_100_000.0);
+        assert_eq!(tracker.current_tier().name, "VIP1");
+
+        // In 5 days the 400k trade expires but 500k more is traded at the run rate
+        let status = tracker.status(5);
+        assert!((status.projected_volume - 1_200_000.0).abs() < 1e-6);
+        assert_eq!(status.projected_tier.name, "VIP1");
+        assert_eq!(status.volume_to_next_tier, Some(3_900_000.0));
+    }
+
+    #[test]
+    fn test_edge_calculator_adapts_to_tier() {
+        let schedule = FeeSchedule::binance_spot();
+        let mut tracker = FeeTierTracker::new(schedule.clone());
+        let mut edge = EdgeCalculator::new(schedule.tier_for(0.0).clone(), 1.0);
+
+        assert!(!edge.is_profitable(10.5, true));
+        assert!(!edge.sync(&tracker));
+
+        tracker.record_trade(DAY_MS, 25_000_000.0);
+        assert!(edge.sync(&tracker));
+        assert_eq!(edge.tier().name, "VIP3");
+        assert!(edge.is_profitable(10.5, true));
+        assert!((edge.min_quote_half_spread_bps() - 5.2).abs() < 1e-9);
+
+        // Paying in BNB takes 25% off the charged fee
+        let edge = edge.with_fee_preference(FeePreference::binance_spot(true));
+        assert!((edge.effective_fee_bps(false) - 4.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_schedule_needs_a_tier() {
+        assert!(matches!(FeeSchedule::new(vec![]), Err(ExchangeError::ConfigurationError(_))));
+        assert!(serde_json::from_str::<FeeSchedule>(r#"{"tiers":[]}"#).is_err());
+
+        let schedule = FeeSchedule::new(vec![FeeTier::new("VIP1", 1e6, 9.0, 10.0), FeeTier::new("VIP0", 0.0, 10.0, 10.0)]).unwrap();
+        assert_eq!(schedule.tier_for(5e5).name, "VIP0");
+        let json = serde_json::to_string(&schedule).unwrap();
+        assert_eq!(serde_json::from_str::<FeeSchedule>(&json).unwrap(), schedule);
+    }
+
+    #[test]
+    fn test_commission_in_quote() {
+        let JournalEvent::Trade(mut t) = trade(1, 0, "100000") else { unreachable!() };
+        t.commission = Fixed::from_str_exact("0.01").unwrap();
+        assert_eq!(commission_in_quote(&t, "USDT", Some(600.0)), Some(6.0));
+        t.commission_asset = "BTC".to_string();
+        assert_eq!(commission_in_quote(&t, "USDT", None), Some(1000.0));
+        t.commission_asset = "ETH".to_string();
+        assert_eq!(commission_in_quote(&t, "USDT", None), None);
+    }
+}
@@ -0,0 +1,327 @@
+//! Fill-probability models for resting orders in the simulator
+//!
+//! Whether a passive limit order would have filled is the biggest unknown in
+//! a backtest: the recorded data shows trades and book levels, not where our
+//! order would have sat in the queue. `SimulatedExchange` asks a `FillModel`
+//! how much of each resting order fills on every market data event, so the
+//! same run can be repeated under different assumptions to see how much the
+//! result depends on them.
+//!
+//! - `TradeThroughFill` (default): fills when a trade prints through the
+//!   limit or the book crosses it
+//! - `OptimisticFill`: fills as soon as the market touches the limit
+//! - `QueueFill`: joins behind the visible level and fills only once trades at
+//!   the limit have worked through the queue ahead
+//! - `ProbabilisticFill`: fills on a touch with a fixed probability
+
+use crate::queue::QueueEstimator;
+use crate::types::*;
+use sriquant_core::prelude::*;
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+/// What a fill model sees of one resting order on one market data event
+#[derive(Debug, Clone, Copy)]
+pub struct FillContext<'a> {
+    pub order: &'a OrderResponse,
+    /// Latest book snapshot for the symbol, if any
+    pub book: Option<&'a OrderBook>,
+    /// The trade being processed; `None` on a book update
+    pub trade: Option<&'a Trade>,
+    /// Market-clock time since the order reached the engine
+    pub resting_ms: u64,
+    /// Volume traded at or through the limit since the order arrived,
+    /// including this event's trade
+    pub traded_quantity: Fixed,
+}
+
+impl FillContext<'_> {
+    pub fn remaining(&self) -> Fixed {
+        self.order.quantity - self.order.filled_quantity
+    }
+
+    /// Price on the other side of the order for this event: the trade price,
+    /// or the best opposite quote on a book update
+    fn market_price(&self) -> Option<Fixed> {
+        if let Some(trade) = self.trade {
+            return Some(trade.price);
+        }
+        let book = self.book?;
+        match self.order.side {
+            OrderSide::Buy => book.best_ask(),
+            OrderSide::Sell => book.best_bid(),
+        }
+    }
+
+    /// The market reached the limit price
+    pub fn touched(&self) -> bool {
+        match (self.market_price(), self.order.price) {
+            (Some(price), Some(limit)) => match self.order.side {
+                OrderSide::Buy => price <= limit,
+                OrderSide::Sell => price >= limit,
+            },
+            _ => false,
+        }
+    }
+
+    /// The market went beyond the limit price, so every order at it filled
+    pub fn traded_through(&self) -> bool {
+        self.touched() && self.market_price() != self.order.price
+    }
+}
+
+/// Decides how much of a resting limit order fills
+pub trait FillModel: Debug {
+    fn name(&self) -> &str;
+
+    /// Quantity of `ctx.order` filled by this event; capped at the remaining
+    /// quantity by the simulator
+    fn fill_quantity(&mut self, ctx: &FillContext) -> Fixed;
+
+    /// An order started resting, with `book` the snapshot it joined
+    fn on_rest(&mut self, _order: &OrderResponse, _book: Option<&OrderBook>, _at: u64) {}
+
+    /// Every market data event, before `fill_quantity` is asked
+    fn on_market_data(&mut self, _event: &MarketData) {}
+
+    /// An order stopped resting (filled, canceled or expired)
+    fn on_done(&mut self, _order: &OrderResponse) {}
+}
+
+/// Fill when a trade prints through the limit or the book crosses it
+///
+/// A trade at the limit doesn't prove queue priority, but a book quoting at
+/// or through it is taken to mean the order was reached.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TradeThroughFill;
+
+impl FillModel for TradeThroughFill {
+    fn name(&self) -> &str {
+        "trade_through"
+    }
+
+    fn fill_quantity(&mut self, ctx: &FillContext) -> Fixed {
+        let filled = if ctx.trade.is_some() { ctx.traded_through() } else { ctx.touched() };
+        if filled { ctx.remaining() } else { Fixed::ZERO }
+    }
+}
+
+/// Fill as soon as the market touches the limit: an upper bound on passive fills
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OptimisticFill;
+
+impl FillModel for OptimisticFill {
+    fn name(&self) -> &str {
+        "optimistic"
+    }
+
+    fn fill_quantity(&mut self, ctx: &FillContext) -> Fixed {
+        if ctx.touched() { ctx.remaining() } else { Fixed::ZERO }
+    }
+}
+
+/// Fill from trades at the limit once the queue ahead is used up
+///
+/// The order joins behind the visible quantity at its price when it starts
+/// resting; `QueueEstimator` then works the queue down with trades and
+/// cancellations. Trading or quoting through the limit fills the rest.
+#[derive(Debug, Default)]
+pub struct QueueFill {
+    queue: QueueEstimator,
+    /// Estimated fill already handed to the simulator, by order id
+    reported: HashMap<String, Fixed>,
+}
+
+impl QueueFill {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn queue(&self) -> &QueueEstimator {
+        &self.queue
+    }
+}
+
+impl FillModel for QueueFill {
+    fn name(&self) -> &str {
+        "queue"
+    }
+
+    fn fill_quantity(&mut self, ctx: &FillContext) -> Fixed {
+        if ctx.traded_through() {
+            return ctx.remaining();
+        }
+        let Some(position) = self.queue.position(&ctx.order.order_id) else {
+            return Fixed::ZERO;
+        };
+        let reported = self.reported.entry(ctx.order.order_id.clone()).or_insert(Fixed::ZERO);
+        let quantity = position.estimated_filled - *reported;
+        *reported = position.estimated_filled;
+        quantity
+    }
+
+    fn on_rest(&mut self, order: &OrderResponse, book: Option<&OrderBook>, at: u64) {
+        let Some(price) = order.price else { return };
+        let levels = book.map(|b| match order.side {
+            OrderSide::Buy => &b.bids,
+            OrderSide::Sell => &b.asks,
+        });
+        let ahead = levels
+            .and_then(|levels| levels.iter().find(|l| l.price == price))
+            .map(|l| l.quantity)
+            .unwrap_or(Fixed::ZERO);
+        let request = OrderRequest {
+            symbol: order.symbol.clone(),
+            side: order.side,
+            order_type: order.order_type,
+            quantity: order.quantity - order.filled_quantity,
+            price: Some(price),
+            stop_price: None,
+            time_in_force: order.time_in_force,
+            client_order_id: None,
+        };
+        self.queue.track(&order.order_id, &request, ahead, at);
+    }
+
+    fn on_market_data(&mut self, event: &MarketData) {
+        let MarketData::OrderBook(book) = event else {
+            self.queue.on_market_data(event);
+            return;
+        };
+        // Simulated orders aren't on the recorded book; add ours back so the
+        // estimator doesn't read their absence as cancellations
+        let tracked: Vec<(OrderSide, Fixed, Fixed)> = self.queue.positions()
+            .filter(|p| p.symbol == book.symbol)
+            .map(|p| (p.side, p.price, p.remaining))
+            .collect();
+        for (side, price, remaining) in tracked {
+            let levels = match side {
+                OrderSide::Buy => &book.bids,
+                OrderSide::Sell => &book.asks,
+            };
+            if let Some(level) = levels.iter().find(|l| l.price == price) {
+                self.queue.on_level(&book.symbol, side, price, level.quantity + remaining);
+            }
+        }
+    }
+
+    fn on_done(&mut self, order: &OrderResponse) {
+        self.queue.untrack(&order.order_id);
+        self.reported.remove(&order.order_id);
+    }
+}
+
+/// Fill on a touch with probability `probability`; trading through always fills
+///
+/// Draws come from a seeded generator so runs are repeatable.
+#[derive(Debug, Clone)]
+pub struct ProbabilisticFill {
+    probability: f64,
+    rng: u64,
+}
+
+impl ProbabilisticFill {
+    pub fn new(probability: f64, seed: u64) -> Self {
+        Self { probability: probability.clamp(0.0, 1.0), rng: seed.max(1) }
+    }
+
+    pub fn probability(&self) -> f64 {
+        self.probability
+    }
+
+    fn draw(&mut self) -> f64 {
+        // xorshift64
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        (self.rng >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+impl FillModel for ProbabilisticFill {
+    fn name(&self) -> &str {
+        "probabilistic"
+    }
+
+    fn fill_quantity(&mut self, ctx: &FillContext) -> Fixed {
+        if ctx.traded_through() || (ctx.touched() && self.draw() < self.probability) {
+            ctx.remaining()
+        } else {
+            Fixed::ZERO
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testkit::fx;
+
+    fn bid_order() -> OrderResponse {
+        OrderResponse {
+            order_id: "1".to_string(),
+            client_order_id: "sim-1".to_string(),
+            symbol: "BTCUSDT".to_string(),
+            side: OrderSide::Buy,
+            order_type: OrderType::Limit,
+            quantity: fx("1"),
+            price: Some(fx("100")),
+            stop_price: None,
+            status: OrderStatus::New,
+            filled_quantity: Fixed::ZERO,
+            average_price: None,
+            time_in_force: None,
+            timestamp: 0,
+            update_time: 0,
+        }
+    }
+
+    fn sell_at(price: &str, quantity: &str) -> Trade {
+        Trade {
+            id: "1".to_string(),
+            symbol: "BTCUSDT".to_string(),
+            price: fx(price),
+            quantity: fx(quantity),
+            side: OrderSide::Sell,
+            timestamp: 0,
+            is_buyer_maker: true,
+        }
+    }
+
+    fn context<'a>(order: &'a OrderResponse, trade: &'a Trade) -> FillContext<'a> {
+        FillContext { order, book: None, trade: Some(trade), resting_ms: 0, traded_quantity: trade.quantity }
+    }
+
+    #[test]
+    fn test_models_differ_on_a_trade_at_the_limit() {
+        let order = bid_order();
+        let at_limit = sell_at("100", "3");
+        let through = sell_at("99.9", "0.1");
+
+        assert_eq!(TradeThroughFill.fill_quantity(&context(&order, &at_limit)), Fixed::ZERO);
+        assert_eq!(TradeThroughFill.fill_quantity(&context(&order, &through)), fx("1"));
+        assert_eq!(OptimisticFill.fill_quantity(&context(&order, &at_limit)), fx("1"));
+        assert_eq!(ProbabilisticFill::new(0.0, 7).fill_quantity(&context(&order, &at_limit)), Fixed::ZERO);
+        assert_eq!(ProbabilisticFill::new(1.0, 7).fill_quantity(&context(&order, &at_limit)), fx("1"));
+        assert_eq!(ProbabilisticFill::new(0.0, 7).fill_quantity(&context(&order, &through)), fx("1"));
+
+        // 2.5 ahead of us: the first print only reaches us for 0.5
+        let book = OrderBook {
+            symbol: "BTCUSDT".to_string(),
+            bids: vec![OrderBookLevel { price: fx("100"), quantity: fx("2.5") }],
+            asks: vec![OrderBookLevel { price: fx("100.1"), quantity: fx("1") }],
+            timestamp: 0,
+            update_id: 0,
+        };
+        let mut queue = QueueFill::new();
+        queue.on_rest(&order, Some(&book), 0);
+        queue.on_market_data(&MarketData::OrderBook(book));
+        queue.on_market_data(&MarketData::Trade(at_limit.clone()));
+        assert_eq!(queue.fill_quantity(&context(&order, &at_limit)), fx("0.5"));
+        // Nothing new until the next print
+        assert_eq!(queue.fill_quantity(&context(&order, &at_limit)), Fixed::ZERO);
+        queue.on_done(&order);
+        assert_eq!(queue.queue().positions().count(), 0);
+    }
+}
@@ -5,19 +5,118 @@
 //! - Direct TLS integration with rustls
 //! - High-performance HTTP/1.1 implementation
 //! - Zero-copy operations where possible
+//! - Keep-alive connection pool keyed by host:port, so repeated REST calls
+//!   skip the TCP and TLS handshakes
+//! - Optional deadline on connecting and on each request/response exchange,
+//!   failing with `ExchangeError::Timeout` instead of hanging on a stalled read
 
+use crate::cassette::{scrub_url, CassetteHandle};
+use crate::chaos::{self, Fault, FaultInjector};
 use crate::errors::{ExchangeError, Result};
+use crate::testkit::MockBinance;
 use monoio::io::{AsyncReadRent, AsyncWriteRentExt};
 use std::io::{Read, Write};
 use monoio::net::TcpStream;
 use rustls::{ClientConfig, ClientConnection};
 use rustls::pki_types::ServerName;
+use std::collections::HashMap;
+use std::cell::{RefCell, RefMut};
+use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
+use tracing::debug;
 use webpki_roots;
 
+/// Idle connection pool settings
+#[derive(Debug, Clone)]
+pub struct PoolConfig {
+    /// Idle connections kept per host:port
+    pub max_idle_per_host: usize,
+    /// Idle connections older than this are discarded instead of reused;
+    /// keep it below the server's keep-alive timeout
+    pub idle_timeout_nanos: u64,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            max_idle_per_host: 4,
+            idle_timeout_nanos: 30_000_000_000,
+        }
+    }
+}
+
+/// Connection reuse counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    /// Fresh TCP+TLS connections opened
+    pub connects: u64,
+    /// Requests served on a pooled connection
+    pub reuses: u64,
+    /// Pooled connections found dead and replaced by a fresh dial
+    pub stale_redials: u64,
+    /// Connections currently idle in the pool
+    pub idle: usize,
+}
+
+struct IdleConnection<T> {
+    conn: T,
+    idle_since: u64,
+}
+
+/// Idle connections keyed by host:port
+struct ConnectionPool<T> {
+    config: PoolConfig,
+    idle: HashMap<String, Vec<IdleConnection<T>>>,
+    stats: PoolStats,
+}
+
+impl<T> ConnectionPool<T> {
+    fn new(config: PoolConfig) -> Self {
+        Self {
+            config,
+            idle: HashMap::new(),
+            stats: PoolStats::default(),
+        }
+    }
+
+    /// Most recently used live connection for `key`, dropping expired ones
+    fn checkout(&mut self, key: &str, now: u64) -> Option<T> {
+        let conns = self.idle.get_mut(key)?;
+        while let Some(idle) = conns.pop() {
+            self.stats.idle -= 1;
+            if now.saturating_sub(idle.idle_since) < self.config.idle_timeout_nanos {
+                self.stats.reuses += 1;
+                return Some(idle.conn);
+            }
+        }
+        None
+    }
+
+    fn checkin(&mut self, key: &str, conn: T, now: u64) {
+        let timeout = self.config.idle_timeout_nanos;
+        let conns = self.idle.entry(key.to_string()).or_default();
+        let before = conns.len();
+        conns.retain(|c| now.saturating_sub(c.idle_since) < timeout);
+        self.stats.idle -= before - conns.len();
+        if conns.len() < self.config.max_idle_per_host {
+            conns.push(IdleConnection { conn, idle_since: now });
+            self.stats.idle += 1;
+        }
+    }
+}
+
 /// Monoio-native HTTPS client
+///
+/// Pooled connections are bound to the monoio runtime that opened them, so the
+/// client is meant to be used from a single thread (share it with `Rc`).
 pub struct MonoioHttpsClient {
     tls_config: Arc<ClientConfig>,
+    cassette: Option<CassetteHandle>,
+    faults: Option<FaultInjector>,
+    mock: Option<MockBinance>,
+    pool: RefCell<ConnectionPool<TlsStream>>,
+    timeout: Option<Duration>,
 }
 
 /// HTTP response
@@ -27,12 +126,21 @@ pub struct HttpResponse {
     pub body: String,
 }
 
+/// Connection setup timings for a single HTTPS connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandshakeTiming {
+    pub tcp_connect_micros: u64,
+    pub tls_handshake_micros: u64,
+}
+
 /// TLS stream wrapper for monoio
 pub struct TlsStream {
     stream: TcpStream,
     tls_conn: ClientConnection,
     write_buf: Vec<u8>,
     tls_read_buf: Vec<u8>,
+    /// Ciphertext read from TCP by `read`, kept between calls
+    tcp_read_buf: Vec<u8>,
     handshake_complete: bool,
 }
 
@@ -52,9 +160,55 @@ impl MonoioHttpsClient {
 
         Ok(Self {
             tls_config: Arc::new(tls_config),
+            cassette: None,
+            faults: None,
+            mock: None,
+            pool: RefCell::new(ConnectionPool::new(PoolConfig::default())),
+            timeout: None,
         })
     }
 
+    /// Bound connecting, and each request's write and response read, by `timeout`
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Replace the connection pool settings (drops idle connections)
+    pub fn with_pool_config(self, config: PoolConfig) -> Self {
+        Self {
+            pool: RefCell::new(ConnectionPool::new(config)),
+            ..self
+        }
+    }
+
+    /// Connection reuse counters
+    pub fn pool_stats(&self) -> PoolStats {
+        self.pool().stats
+    }
+
+    fn pool(&self) -> RefMut<'_, ConnectionPool<TlsStream>> {
+        self.pool.borrow_mut()
+    }
+
+    /// Record requests into, or replay them from, a cassette
+    pub fn with_cassette(mut self, cassette: CassetteHandle) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+
+    /// Inject delays, lost responses, cut-off requests and truncated bodies
+    pub fn with_faults(mut self, faults: FaultInjector) -> Self {
+        self.faults = Some(faults);
+        self
+    }
+
+    /// Answer every request from an in-process mock instead of the network
+    pub fn with_mock(mut self, mock: MockBinance) -> Self {
+        self.mock = Some(mock);
+        self
+    }
+
     /// Make an HTTPS GET request
     pub async fn get(&self, url: &str) -> Result<HttpResponse> {
         self.request("GET", url, None).await
@@ -78,6 +232,44 @@ impl MonoioHttpsClient {
         url: &str, 
         body: Option<&str>,
         headers: &std::collections::HashMap<&str, &str>
+    ) -> Result<HttpResponse> {
+        let fault = self.faults.as_ref().and_then(|f| f.next_fault());
+        match fault {
+            Some(Fault::Delay(delay)) => monoio::time::sleep(delay).await,
+            // Cut off before the server has the whole request
+            Some(fault @ Fault::PartialWrite) => return Err(chaos::injected(fault, &scrub_url(url))),
+            _ => {}
+        }
+
+        let mut response = match (&self.mock, &self.cassette) {
+            (Some(mock), _) => mock.respond(method, url),
+            (None, Some(cassette)) if cassette.is_replay() => cassette.replay_http(method, url, body)?,
+            (None, Some(cassette)) => {
+                let response = self.send_request(method, url, body, headers).await?;
+                cassette.record_http(method, url, body, &response);
+                response
+            }
+            (None, None) => self.send_request(method, url, body, headers).await?,
+        };
+
+        match fault {
+            // The request took effect but the caller never hears about it
+            Some(fault @ Fault::Drop) => Err(chaos::injected(fault, &scrub_url(url))),
+            Some(Fault::Malformed) => {
+                response.body = String::from_utf8_lossy(&chaos::truncate(response.body.as_bytes())).into_owned();
+                Ok(response)
+            }
+            _ => Ok(response),
+        }
+    }
+
+    /// Send a request, reusing a pooled keep-alive connection when possible
+    async fn send_request(
+        &self,
+        method: &str,
+        url: &str,
+        body: Option<&str>,
+        headers: &std::collections::HashMap<&str, &str>
     ) -> Result<HttpResponse> {
         // Parse URL
         let parsed_url = url::Url::parse(url)
@@ -97,20 +289,6 @@ impl MonoioHttpsClient {
             }
             path_and_query
         };
-        
-        // Connect to server
-        let tcp_stream = TcpStream::connect(&format!("{host}:{port}"))
-            .await
-            .map_err(|e| ExchangeError::NetworkError(format!("TCP connect failed: {e}")))?;
-
-        // Establish TLS connection
-        let server_name = ServerName::try_from(host.to_string())
-            .map_err(|e| ExchangeError::NetworkError(format!("Invalid server name: {e:?}")))?;
-        
-        let tls_conn = ClientConnection::new(self.tls_config.clone(), server_name)
-            .map_err(|e| ExchangeError::NetworkError(format!("TLS setup failed: {e}")))?;
-
-        let mut tls_stream = TlsStream::new(tcp_stream, tls_conn);
 
         // Build HTTP request with custom headers
         let content_length = body.map(|b| b.len()).unwrap_or(0);
@@ -118,7 +296,7 @@ impl MonoioHttpsClient {
             "{method} {path_and_query} HTTP/1.1\r\n\
              Host: {host}\r\n\
              User-Agent: SriQuant.ai/1.0\r\n\
-             Connection: close\r\n\
+             Connection: keep-alive\r\n\
              Content-Length: {content_length}\r\n"
         );
         
@@ -127,64 +305,241 @@ impl MonoioHttpsClient {
             request.push_str(&format!("{key}: {value}\r\n"));
         }
         
-        
         // End headers and add body
         request.push_str("\r\n");
         if let Some(body) = body {
             request.push_str(body);
         }
 
-        // Send request
-        tls_stream.write_all(request.as_bytes()).await
-            .map_err(|e| ExchangeError::NetworkError(format!("Write failed: {e}")))?;
-
-        // Read response
-        let response_data = tls_stream.read_to_end().await
-            .map_err(|e| ExchangeError::NetworkError(format!("Read failed: {e}")))?;
+        let key = format!("{host}:{port}");
+        // A server may close an idle connection just as we reuse it. Only
+        // replay requests that can't have taken effect twice.
+        let replayable = !method.eq_ignore_ascii_case("POST");
+
+        let pooled = self.pool().checkout(&key, sriquant_core::nanos());
+        if let Some(stream) = pooled {
+            match self.timed_exchange(stream, request.as_bytes(), replayable, &key).await {
+                Ok((response, stream)) => {
+                    self.release(&key, stream);
+                    return Ok(response);
+                }
+                Err(Attempt::Stale(e)) => {
+                    debug!("♻️  Pooled connection to {} was stale ({}), re-dialing", key, e);
+                    self.pool().stats.stale_redials += 1;
+                }
+                Err(Attempt::Failed(e)) => return Err(e),
+            }
+        }
 
-        // Parse HTTP response
-        self.parse_http_response(&response_data)
+        let stream = with_deadline(self.timeout, &format!("connect to {key}"), self.connect(host, port)).await?;
+        match self.timed_exchange(stream, request.as_bytes(), false, &key).await {
+            Ok((response, stream)) => {
+                self.release(&key, stream);
+                Ok(response)
+            }
+            Err(Attempt::Stale(e) | Attempt::Failed(e)) => Err(e),
+        }
     }
 
-    /// Parse HTTP response
-    fn parse_http_response(&self, data: &[u8]) -> Result<HttpResponse> {
-        let response_str = String::from_utf8_lossy(data);
-        
-        // Find the end of headers (double CRLF)
-        let header_end = response_str.find("\r\n\r\n")
-            .ok_or_else(|| ExchangeError::NetworkError("Invalid HTTP response: no header terminator".to_string()))?;
-        
-        let header_part = &response_str[..header_end];
-        let body_part = &response_str[header_end + 4..]; // Skip the \r\n\r\n
-        
-        let mut lines = header_part.lines();
-        
-        // Parse status line
-        let status_line = lines.next()
-            .ok_or_else(|| ExchangeError::NetworkError("Empty response".to_string()))?;
-        
-        let status = status_line.split_whitespace()
-            .nth(1)
-            .and_then(|s| s.parse::<u16>().ok())
-            .ok_or_else(|| ExchangeError::NetworkError("Invalid status line".to_string()))?;
+    /// Open a fresh TCP+TLS connection
+    async fn connect(&self, host: &str, port: u16) -> Result<TlsStream> {
+        let tcp_stream = TcpStream::connect(&format!("{host}:{port}"))
+            .await
+            .map_err(|e| ExchangeError::NetworkError(format!("TCP connect failed: {e}")))?;
 
-        // Parse headers
-        let mut headers = Vec::new();
+        let server_name = ServerName::try_from(host.to_string())
+            .map_err(|e| ExchangeError::NetworkError(format!("Invalid server name: {e:?}")))?;
         
-        for line in lines {
-            if let Some((key, value)) = line.split_once(':') {
-                headers.push((key.trim().to_string(), value.trim().to_string()));
+        let tls_conn = ClientConnection::new(self.tls_config.clone(), server_name)
+            .map_err(|e| ExchangeError::NetworkError(format!("TLS setup failed: {e}")))?;
+
+        self.pool().stats.connects += 1;
+        Ok(TlsStream::new(tcp_stream, tls_conn))
+    }
+
+    /// Write one request and read its response
+    ///
+    /// Returns the stream back if the connection can carry another request.
+    /// Failures before any response byte arrives are reported as `Stale` when
+    /// the request is safe to replay on a new connection.
+    async fn exchange(
+        &self,
+        mut stream: TlsStream,
+        request: &[u8],
+        replayable: bool,
+    ) -> std::result::Result<(HttpResponse, Option<TlsStream>), Attempt> {
+        let stale_or_failed = |e: ExchangeError| if replayable { Attempt::Stale(e) } else { Attempt::Failed(e) };
+
+        stream.write_all(request).await
+            .map_err(|e| stale_or_failed(ExchangeError::NetworkError(format!("Write failed: {e}"))))?;
+
+        let raw = match stream.read_response().await {
+            Ok(Some(raw)) => raw,
+            Ok(None) => {
+                return Err(stale_or_failed(ExchangeError::NetworkError("Connection closed before response".to_string())));
             }
-        }
+            Err(e) => return Err(Attempt::Failed(ExchangeError::NetworkError(format!("Read failed: {e}")))),
+        };
 
-        Ok(HttpResponse {
+        let (status, headers) = parse_http_head(&raw.head).map_err(Attempt::Failed)?;
+        let response = HttpResponse {
             status,
             headers,
-            body: body_part.to_string(),
+            body: String::from_utf8_lossy(&raw.body).into_owned(),
+        };
+        Ok((response, raw.keep_alive.then_some(stream)))
+    }
+
+    /// `exchange` bounded by the client timeout; a timed-out request may
+    /// have reached the server, so it is never replayed
+    async fn timed_exchange(
+        &self,
+        stream: TlsStream,
+        request: &[u8],
+        replayable: bool,
+        key: &str,
+    ) -> std::result::Result<(HttpResponse, Option<TlsStream>), Attempt> {
+        match self.timeout {
+            Some(limit) => monoio::time::timeout(limit, self.exchange(stream, request, replayable)).await
+                .unwrap_or_else(|_| Err(Attempt::Failed(timeout_error(&format!("response from {key}"), limit)))),
+            None => self.exchange(stream, request, replayable).await,
+        }
+    }
+
+    fn release(&self, key: &str, stream: Option<TlsStream>) {
+        if let Some(stream) = stream {
+            self.pool().checkin(key, stream, sriquant_core::nanos());
+        }
+    }
+
+    /// Open a connection to `url` and time TCP connect and TLS handshake separately
+    pub async fn measure_handshake(&self, url: &str) -> Result<HandshakeTiming> {
+        let parsed_url = url::Url::parse(url)
+            .map_err(|e| ExchangeError::InvalidUrl(e.to_string()))?;
+        let host = parsed_url.host_str()
+            .ok_or_else(|| ExchangeError::InvalidUrl("No host in URL".to_string()))?;
+        let port = parsed_url.port().unwrap_or(443);
+
+        let start = sriquant_core::nanos();
+        let tcp_stream = TcpStream::connect(&format!("{host}:{port}"))
+            .await
+            .map_err(|e| ExchangeError::NetworkError(format!("TCP connect failed: {e}")))?;
+        let connected = sriquant_core::nanos();
+
+        let server_name = ServerName::try_from(host.to_string())
+            .map_err(|e| ExchangeError::NetworkError(format!("Invalid server name: {e:?}")))?;
+        let tls_conn = ClientConnection::new(self.tls_config.clone(), server_name)
+            .map_err(|e| ExchangeError::NetworkError(format!("TLS setup failed: {e}")))?;
+
+        let mut tls_stream = TlsStream::new(tcp_stream, tls_conn);
+        tls_stream.complete_handshake().await?;
+        let handshaken = sriquant_core::nanos();
+
+        Ok(HandshakeTiming {
+            tcp_connect_micros: (connected - start) / 1_000,
+            tls_handshake_micros: (handshaken - connected) / 1_000,
         })
     }
 }
 
+/// Run `op`, failing with `ExchangeError::Timeout` if it outlasts `limit`
+pub(crate) async fn with_deadline<T>(limit: Option<Duration>, what: &str, op: impl Future<Output = Result<T>>) -> Result<T> {
+    match limit {
+        Some(limit) => monoio::time::timeout(limit, op).await
+            .unwrap_or_else(|_| Err(timeout_error(what, limit))),
+        None => op.await,
+    }
+}
+
+fn timeout_error(what: &str, limit: Duration) -> ExchangeError {
+    debug!("⏰ Timed out waiting for {} after {}ms", what, limit.as_millis());
+    ExchangeError::Timeout(format!("{what} after {}ms", limit.as_millis()))
+}
+
+/// Outcome of a failed request attempt
+enum Attempt {
+    /// The connection was dead before the server answered; safe to retry
+    Stale(ExchangeError),
+    Failed(ExchangeError),
+}
+
+/// Parse the status line and headers of an HTTP response
+fn parse_http_head(head: &str) -> Result<(u16, Vec<(String, String)>)> {
+    let mut lines = head.lines();
+    
+    // Parse status line
+    let status_line = lines.next()
+        .ok_or_else(|| ExchangeError::NetworkError("Empty response".to_string()))?;
+    
+    let status = status_line.split_whitespace()
+        .nth(1)
+        .and_then(|s| s.parse::<u16>().ok())
+        .ok_or_else(|| ExchangeError::NetworkError("Invalid status line".to_string()))?;
+
+    // Parse headers
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect();
+
+    Ok((status, headers))
+}
+
+fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+/// Raw HTTP/1.1 response as framed off a connection
+struct RawResponse {
+    head: String,
+    body: Vec<u8>,
+    /// Connection may carry another request
+    keep_alive: bool,
+}
+
+/// Incremental decoder for `Transfer-Encoding: chunked` bodies
+#[derive(Debug, Default)]
+struct ChunkedDecoder {
+    /// Offset into the raw buffer of the next unparsed byte
+    pos: usize,
+    body: Vec<u8>,
+    in_trailers: bool,
+}
+
+impl ChunkedDecoder {
+    /// Consume as much of `raw` as possible; returns `true` once the body is complete
+    fn feed(&mut self, raw: &[u8]) -> Result<bool> {
+        loop {
+            let Some(line_len) = find_bytes(&raw[self.pos..], b"\r\n") else { return Ok(false) };
+            if self.in_trailers {
+                self.pos += line_len + 2;
+                if line_len == 0 {
+                    return Ok(true);
+                }
+                continue;
+            }
+
+            let line = String::from_utf8_lossy(&raw[self.pos..self.pos + line_len]);
+            let size_field = line.split(';').next().unwrap_or_default().trim();
+            let size = usize::from_str_radix(size_field, 16)
+                .map_err(|_| ExchangeError::InvalidResponse(format!("Invalid chunk size: {line}")))?;
+
+            if size == 0 {
+                self.pos += line_len + 2;
+                self.in_trailers = true;
+                continue;
+            }
+
+            let data_start = self.pos + line_len + 2;
+            if raw.len() < data_start + size + 2 {
+                return Ok(false);
+            }
+            self.body.extend_from_slice(&raw[data_start..data_start + size]);
+            self.pos = data_start + size + 2;
+        }
+    }
+}
+
 impl TlsStream {
     pub fn new(stream: TcpStream, tls_conn: ClientConnection) -> Self {
         Self {
@@ -192,6 +547,7 @@ impl TlsStream {
             tls_conn,
             write_buf: Vec::with_capacity(8192),
             tls_read_buf: Vec::with_capacity(8192),
+            tcp_read_buf: Vec::with_capacity(4096),
             handshake_complete: false,
         }
     }
@@ -215,7 +571,9 @@ impl TlsStream {
                     .map_err(|e| ExchangeError::NetworkError(format!("TLS write failed: {e}")))?;
                 
                 if tls_bytes > 0 {
-                    let (result, _) = self.stream.write_all(self.write_buf.clone()).await;
+                    let buf = std::mem::take(&mut self.write_buf);
+                let (result, buf) = self.stream.write_all(buf).await;
+                self.write_buf = buf;
                     result.map_err(|e| ExchangeError::NetworkError(format!("TCP write failed: {e}")))?;
                 }
             }
@@ -291,9 +649,11 @@ impl TlsStream {
             Err(e) => return Err(ExchangeError::NetworkError(format!("TLS read failed: {e}"))),
         }
 
-        // Need to read more encrypted data from TCP
-        let tcp_buffer = vec![0u8; 4096];
+        // Need to read more encrypted data from TCP, reusing the read buffer
+        let mut tcp_buffer = std::mem::take(&mut self.tcp_read_buf);
+        tcp_buffer.resize(4096, 0);
         let (result, tcp_buf) = self.stream.read(tcp_buffer).await;
+        self.tcp_read_buf = tcp_buf;
         let bytes_read = result.map_err(|e| ExchangeError::NetworkError(format!("TCP read failed: {e}")))?;
 
         if bytes_read == 0 {
@@ -301,7 +661,7 @@ impl TlsStream {
         }
 
         // Process the encrypted data through TLS
-        self.tls_conn.read_tls(&mut std::io::Cursor::new(&tcp_buf[..bytes_read]))
+        self.tls_conn.read_tls(&mut std::io::Cursor::new(&self.tcp_read_buf[..bytes_read]))
             .map_err(|e| ExchangeError::NetworkError(format!("TLS read_tls failed: {e}")))?;
 
         let _tls_state = self.tls_conn.process_new_packets()
@@ -315,56 +675,99 @@ impl TlsStream {
         }
     }
 
-    async fn read_to_end(&mut self) -> Result<Vec<u8>> {
-        // Ensure handshake is complete
+    /// Append the next decrypted bytes to `out`; returns 0 once the peer closed
+    async fn read_some(&mut self, out: &mut Vec<u8>) -> Result<usize> {
         self.complete_handshake().await?;
 
-        let mut response_data = Vec::new();
-        let mut tcp_buffer = vec![0u8; 4096];
-
         loop {
-            // First check if we have decrypted data available
             self.tls_read_buf.clear();
-            self.tls_read_buf.resize(4096, 0);
+            self.tls_read_buf.resize(8192, 0);
             
             match self.tls_conn.reader().read(&mut self.tls_read_buf) {
-                Ok(0) => {
-                    // No more decrypted data available, need to read more from TCP
-                },
                 Ok(n) => {
-                    response_data.extend_from_slice(&self.tls_read_buf[..n]);
-                    continue;
-                },
-                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
-                    // No more decrypted data available right now
-                },
-                Err(e) => {
-                    return Err(ExchangeError::NetworkError(format!("TLS read failed: {e}")));
+                    out.extend_from_slice(&self.tls_read_buf[..n]);
+                    return Ok(n);
                 }
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {}
+                Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(0),
+                Err(e) => return Err(ExchangeError::NetworkError(format!("TLS read failed: {e}"))),
             }
 
             // Read more encrypted data from TCP
-            let (result, buf) = self.stream.read(tcp_buffer).await;
+            let (result, buf) = self.stream.read(vec![0u8; 8192]).await;
             let bytes_read = result.map_err(|e| ExchangeError::NetworkError(format!("TCP read failed: {e}")))?;
-            
             if bytes_read == 0 {
-                break; // Connection closed
+                return Ok(0);
             }
 
-            // Process received TLS data
-            let mut cursor = std::io::Cursor::new(&buf[..bytes_read]);
-            self.tls_conn.read_tls(&mut cursor)
+            self.tls_conn.read_tls(&mut std::io::Cursor::new(&buf[..bytes_read]))
                 .map_err(|e| ExchangeError::NetworkError(format!("TLS read failed: {e}")))?;
-            
-            // Process any TLS messages
             self.tls_conn.process_new_packets()
                 .map_err(|e| ExchangeError::NetworkError(format!("TLS process failed: {e}")))?;
+        }
+    }
 
-            // Prepare buffer for next iteration
-            tcp_buffer = vec![0u8; 4096];
+    async fn read_more(&mut self, buf: &mut Vec<u8>) -> Result<()> {
+        if self.read_some(buf).await? == 0 {
+            return Err(ExchangeError::NetworkError("Connection closed mid-response".to_string()));
         }
+        Ok(())
+    }
 
-        Ok(response_data)
+    /// Read exactly one HTTP response, framed by Content-Length or chunked encoding
+    ///
+    /// Returns `None` if the connection closed or reset before any byte arrived,
+    /// the signature of a keep-alive connection the server already dropped.
+    async fn read_response(&mut self) -> Result<Option<RawResponse>> {
+        let mut buf = Vec::with_capacity(8192);
+        let header_end = loop {
+            if let Some(pos) = find_bytes(&buf, b"\r\n\r\n") {
+                break pos;
+            }
+            match self.read_some(&mut buf).await {
+                Ok(0) | Err(_) if buf.is_empty() => return Ok(None),
+                Ok(0) => return Err(ExchangeError::NetworkError("Connection closed mid-headers".to_string())),
+                Ok(_) => {}
+                Err(e) => return Err(e),
+            }
+        };
+
+        let head = String::from_utf8_lossy(&buf[..header_end]).into_owned();
+        let mut body = buf.split_off(header_end + 4);
+
+        let lower = head.to_ascii_lowercase();
+        let header = |name: &str| {
+            lower.lines().skip(1).find_map(|line| {
+                line.split_once(':')
+                    .filter(|(key, _)| key.trim() == name)
+                    .map(|(_, value)| value.trim().to_string())
+            })
+        };
+        let mut keep_alive = !lower.starts_with("http/1.0") && header("connection").as_deref() != Some("close");
+        let status = lower.split_whitespace().nth(1).and_then(|s| s.parse::<u16>().ok()).unwrap_or(0);
+
+        if status == 204 || status == 304 || (100..200).contains(&status) {
+            body.clear();
+        } else if header("transfer-encoding").is_some_and(|v| v.contains("chunked")) {
+            let mut decoder = ChunkedDecoder::default();
+            while !decoder.feed(&body)? {
+                self.read_more(&mut body).await?;
+            }
+            body = decoder.body;
+        } else if let Some(length) = header("content-length") {
+            let length: usize = length.parse()
+                .map_err(|_| ExchangeError::InvalidResponse(format!("Invalid Content-Length: {length}")))?;
+            while body.len() < length {
+                self.read_more(&mut body).await?;
+            }
+            body.truncate(length);
+        } else {
+            // Body delimited by connection close
+            while self.read_some(&mut body).await? > 0 {}
+            keep_alive = false;
+        }
+
+        Ok(Some(RawResponse { head, body, keep_alive }))
     }
 }
 
@@ -412,6 +815,51 @@ mod tests {
         assert!(client.is_ok());
     }
 
+    #[monoio::test(timer_enabled = true)]
+    async fn test_stalled_server_times_out() {
+        // Accepts the connection but never answers the TLS handshake
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("https://{}/api/v3/ping", listener.local_addr().unwrap());
+
+        let client = MonoioHttpsClient::new().unwrap().with_timeout(Duration::from_millis(50));
+        match client.get(&url).await {
+            Err(ExchangeError::Timeout(what)) => assert!(what.starts_with("response from 127.0.0.1:"), "{what}"),
+            Err(e) => panic!("expected timeout, got {e}"),
+            Ok(_) => panic!("expected timeout"),
+        }
+
+        let url = url::Url::parse(&url.replace("https", "wss")).unwrap();
+        let options = crate::websocket::WebSocketOptions::default().with_timeout(Duration::from_millis(50));
+        let result = crate::websocket::MonoioWebSocket::connect_with_options(url, None, options).await;
+        assert!(matches!(result, Err(ExchangeError::Timeout(_))));
+    }
+
+    #[test]
+    fn test_chunked_decoder_incremental() {
+        let raw = b"4\r\nWiki\r\n6;ext=1\r\npedia \r\n0\r\nX-Trailer: y\r\n\r\n";
+        let mut decoder = ChunkedDecoder::default();
+        assert!(!decoder.feed(&raw[..10]).unwrap());
+        assert!(!decoder.feed(&raw[..25]).unwrap());
+        assert!(decoder.feed(raw).unwrap());
+        assert_eq!(decoder.body, b"Wikipedia ");
+    }
+
+    #[test]
+    fn test_pool_reuse_and_expiry() {
+        let config = PoolConfig { max_idle_per_host: 1, idle_timeout_nanos: 100 };
+        let mut pool = ConnectionPool::new(config);
+
+        pool.checkin("api.binance.com:443", 1u32, 0);
+        pool.checkin("api.binance.com:443", 2u32, 10); // over the per-host cap
+        assert_eq!(pool.stats.idle, 1);
+        assert_eq!(pool.checkout("api.binance.com:443", 50), Some(1));
+        assert_eq!(pool.checkout("api.binance.com:443", 50), None);
+
+        pool.checkin("api.binance.com:443", 3u32, 0);
+        assert_eq!(pool.checkout("api.binance.com:443", 200), None);
+        assert_eq!(pool.stats, PoolStats { connects: 0, reuses: 1, stale_redials: 0, idle: 0 });
+    }
+
     #[monoio::test]
     async fn test_url_parsing() {
         let _client = MonoioHttpsClient::new().unwrap();
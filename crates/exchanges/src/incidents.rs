@@ -0,0 +1,245 @@
+//! Structured incident events
+//!
+//! Subsystems publish notable conditions (book resyncs, IP bans, reconcile
+//! mismatches, SLO breaches) as `Incident`s on a shared `IncidentBus`. Each
+//! consumer — notifier, journal, admin endpoint — holds its own
+//! `IncidentSubscriber` cursor and drains incidents at its own pace, so
+//! every consumer sees the same stream in the same order.
+
+use sriquant_core::prelude::*;
+
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use tracing::{error, info, warn};
+
+/// Incidents retained for late subscribers and the admin endpoint
+pub const DEFAULT_INCIDENT_RETENTION: usize = 1024;
+
+/// How urgent an incident is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Critical,
+}
+
+/// What kind of condition occurred
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IncidentKind {
+    /// Local state was rebuilt from a fresh snapshot
+    Resync,
+    /// The venue banned or throttled us (HTTP 418/429)
+    Ban,
+    /// Local and exchange state disagreed
+    ReconcileMismatch,
+    /// A request or stage exceeded its latency objective
+    SloBreach,
+    /// Trading was paused or resumed by an operator
+    TradingSwitch,
+    /// Orders survived a cancel-all past its deadline
+    CancelUnverified,
+    /// The venue changed a symbol's tick size, step size or other filters
+    InstrumentChanged,
+    /// The local clock stepped, or the machine was suspended or stalled
+    ClockJump,
+    Other(String),
+}
+
+/// A notable condition reported by a subsystem
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Incident {
+    /// Bus-assigned sequence number
+    pub sequence: u64,
+    /// Nanos since epoch
+    pub timestamp: u64,
+    pub severity: Severity,
+    /// Reporting subsystem, e.g. "binance_rest"
+    pub component: String,
+    pub kind: IncidentKind,
+    pub message: String,
+    pub symbol: Option<String>,
+    /// Free-form details (endpoint, latency, status code, ...)
+    pub context: BTreeMap<String, String>,
+}
+
+impl Incident {
+    pub fn new(severity: Severity, component: &str, kind: IncidentKind, message: impl Into<String>) -> Self {
+        Self {
+            sequence: 0,
+            timestamp: nanos(),
+            severity,
+            component: component.to_string(),
+            kind,
+            message: message.into(),
+            symbol: None,
+            context: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_symbol(mut self, symbol: &str) -> Self {
+        self.symbol = Some(symbol.to_string());
+        self
+    }
+
+    pub fn with_context(mut self, key: &str, value: impl ToString) -> Self {
+        self.context.insert(key.to_string(), value.to_string());
+        self
+    }
+}
+
+impl std::fmt::Display for Incident {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{} [{:?}] {} {:?}: {}", self.sequence, self.severity, self.component, self.kind, self.message)?;
+        if let Some(symbol) = &self.symbol {
+            write!(f, " ({symbol})")?;
+        }
+        for (key, value) in &self.context {
+            write!(f, " {key}={value}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct BusState {
+    next_sequence: u64,
+    retained: VecDeque<Incident>,
+    retention: usize,
+}
+
+/// Shared incident stream
+#[derive(Debug, Clone)]
+pub struct IncidentBus {
+    state: Arc<Mutex<BusState>>,
+}
+
+impl Default for IncidentBus {
+    fn default() -> Self {
+        Self::with_retention(DEFAULT_INCIDENT_RETENTION)
+    }
+}
+
+impl IncidentBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_retention(retention: usize) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(BusState {
+                next_sequence: 1,
+                retained: VecDeque::new(),
+                retention: retention.max(1),
+            })),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BusState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Publish an incident; returns its sequence number
+    pub fn publish(&self, mut incident: Incident) -> u64 {
+        let mut state = self.lock();
+        incident.sequence = state.next_sequence;
+        state.next_sequence += 1;
+
+        match incident.severity {
+            Severity::Info => info!("📣 {}", incident),
+            Severity::Warning => warn!("📣 {}", incident),
+            Severity::Critical => error!("🚨 {}", incident),
+        }
+
+        let sequence = incident.sequence;
+        state.retained.push_back(incident);
+        while state.retained.len() > state.retention {
+            state.retained.pop_front();
+        }
+        sequence
+    }
+
+    /// Subscribe from the next published incident onward
+    pub fn subscribe(&self) -> IncidentSubscriber {
+        IncidentSubscriber {
+            bus: self.clone(),
+            next_sequence: self.lock().next_sequence,
+            min_severity: Severity::Info,
+        }
+    }
+
+    /// Most recent incidents, oldest first
+    pub fn recent(&self, limit: usize) -> Vec<Incident> {
+        let state = self.lock();
+        let skip = state.retained.len().saturating_sub(limit);
+        state.retained.iter().skip(skip).cloned().collect()
+    }
+}
+
+/// A consumer's cursor into the bus
+#[derive(Debug, Clone)]
+pub struct IncidentSubscriber {
+    bus: IncidentBus,
+    next_sequence: u64,
+    min_severity: Severity,
+}
+
+impl IncidentSubscriber {
+    /// Only deliver incidents at or above `severity`
+    pub fn with_min_severity(mut self, severity: Severity) -> Self {
+        self.min_severity = severity;
+        self
+    }
+
+    /// Incidents published since the last drain
+    ///
+    /// If the subscriber fell behind the bus retention, the oldest incidents
+    /// are lost and a warning is logged.
+    pub fn drain(&mut self) -> Vec<Incident> {
+        let state = self.bus.lock();
+        if let Some(oldest) = state.retained.front()
+            && oldest.sequence > self.next_sequence
+        {
+            warn!("⚠️ Incident subscriber lagged, {} incidents dropped", oldest.sequence - self.next_sequence);
+        }
+        let incidents: Vec<Incident> = state
+            .retained
+            .iter()
+            .filter(|i| i.sequence >= self.next_sequence && i.severity >= self.min_severity)
+            .cloned()
+            .collect();
+        self.next_sequence = state.next_sequence;
+        incidents
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subscribers_see_same_stream() {
+        let bus = IncidentBus::with_retention(2);
+        let mut journal = bus.subscribe();
+        let mut notifier = bus.subscribe().with_min_severity(Severity::Critical);
+
+        bus.publish(Incident::new(Severity::Warning, "binance_rest", IncidentKind::SloBreach, "slow")
+            .with_context("endpoint", "/api/v3/order"));
+        bus.publish(Incident::new(Severity::Critical, "binance_rest", IncidentKind::Ban, "HTTP 418"));
+
+        let seen = journal.drain();
+        assert_eq!(seen.len(), 2);
+        assert_eq!(seen[0].sequence, 1);
+        assert_eq!(seen[0].context["endpoint"], "/api/v3/order");
+        assert!(journal.drain().is_empty());
+
+        let urgent = notifier.drain();
+        assert_eq!(urgent.len(), 1);
+        assert_eq!(urgent[0].kind, IncidentKind::Ban);
+
+        bus.publish(Incident::new(Severity::Info, "book", IncidentKind::Resync, "rebuilt").with_symbol("BTCUSDT"));
+        assert_eq!(bus.recent(10).len(), 2);
+        assert_eq!(bus.recent(10)[1].symbol.as_deref(), Some("BTCUSDT"));
+    }
+}
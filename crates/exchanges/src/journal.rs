@@ -0,0 +1,323 @@
+//! Append-only account activity journal
+//!
+//! Exchange adapters pull audit-relevant account activity (e.g. matches
+//! prevented by self-trade prevention) into a `Journal`. Entries are
+//! de-duplicated by a stable key so repeated syncs are idempotent, and the
+//! journal can be exported as JSON lines for offline review. Futures wallet
+//! income (funding, realized PnL, commissions) is journaled alongside fills
+//! so PnL reports can be reconciled against exchange statements.
+
+use crate::errors::Result;
+use crate::incidents::{Incident, IncidentSubscriber};
+use crate::oms::ManagedOrder;
+use crate::types::{OrderSide, OrderStatus, OrderType};
+use sriquant_core::prelude::*;
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::io::Write;
+
+/// A match the exchange prevented under self-trade prevention
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PreventedMatchRecord {
+    pub prevented_match_id: u64,
+    pub symbol: String,
+    pub taker_order_id: u64,
+    pub maker_order_id: u64,
+    pub trade_group_id: u64,
+    /// STP mode that triggered the prevention (e.g. "EXPIRE_MAKER")
+    pub stp_mode: String,
+    pub price: Fixed,
+    pub maker_prevented_quantity: Fixed,
+    pub transact_time: u64,
+}
+
+/// An executed trade on the account
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TradeRecord {
+    pub trade_id: u64,
+    pub order_id: u64,
+    pub symbol: String,
+    pub side: OrderSide,
+    pub price: Fixed,
+    pub quantity: Fixed,
+    pub quote_quantity: Fixed,
+    pub commission: Fixed,
+    pub commission_asset: String,
+    pub is_maker: bool,
+    pub time: u64,
+}
+
+/// A futures wallet entry: funding fee, realized PnL, commission, transfer...
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IncomeRecord {
+    pub tran_id: u64,
+    /// Empty for account-wide entries
+    pub symbol: String,
+    /// Exchange name of the entry type, e.g. "FUNDING_FEE"
+    pub income_type: String,
+    /// Signed; negative when paid by the account
+    pub amount: Fixed,
+    pub asset: String,
+    pub trade_id: Option<String>,
+    pub time: u64,
+}
+
+/// An order state change: acknowledgement, fill progress or close
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrderRecord {
+    pub client_order_id: String,
+    pub order_id: Option<String>,
+    pub symbol: String,
+    pub side: OrderSide,
+    /// Missing from entries journaled before the type was recorded
+    #[serde(default)]
+    pub order_type: Option<OrderType>,
+    pub status: OrderStatus,
+    pub quantity: Fixed,
+    pub price: Option<Fixed>,
+    pub filled_quantity: Fixed,
+    pub average_price: Option<Fixed>,
+    pub update_time: u64,
+}
+
+impl From<&ManagedOrder> for OrderRecord {
+    fn from(order: &ManagedOrder) -> Self {
+        Self {
+            client_order_id: order.client_order_id.clone(),
+            order_id: order.order_id.clone(),
+            symbol: order.symbol.clone(),
+            side: order.side,
+            order_type: Some(order.order_type),
+            status: order.status,
+            quantity: order.quantity,
+            price: order.price,
+            filled_quantity: order.filled_quantity,
+            average_price: order.average_price,
+            update_time: order.updated_at,
+        }
+    }
+}
+
+/// Journaled account event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data")]
+pub enum JournalEvent {
+    PreventedMatch(PreventedMatchRecord),
+    Trade(TradeRecord),
+    Incident(Incident),
+    Order(OrderRecord),
+    Income(IncomeRecord),
+}
+
+impl JournalEvent {
+    /// Stable identity used to de-duplicate repeated syncs
+    pub fn dedupe_key(&self) -> String {
+        match self {
+            JournalEvent::PreventedMatch(m) => format!("prevented_match:{}:{}", m.symbol, m.prevented_match_id),
+            JournalEvent::Trade(t) => format!("trade:{}:{}", t.symbol, t.trade_id),
+            JournalEvent::Incident(i) => format!("incident:{}:{}", i.timestamp, i.sequence),
+            JournalEvent::Order(o) => format!("order:{}:{}:{}:{}", o.symbol, o.client_order_id, o.status, o.filled_quantity),
+            // A fill's realized PnL and commission share one tranId
+            JournalEvent::Income(i) => format!("income:{}:{}:{}", i.income_type, i.asset, i.tran_id),
+        }
+    }
+
+    /// Symbol the event relates to
+    pub fn symbol(&self) -> &str {
+        match self {
+            JournalEvent::PreventedMatch(m) => &m.symbol,
+            JournalEvent::Trade(t) => &t.symbol,
+            JournalEvent::Incident(i) => i.symbol.as_deref().unwrap_or(""),
+            JournalEvent::Order(o) => &o.symbol,
+            JournalEvent::Income(i) => &i.symbol,
+        }
+    }
+
+    /// Exchange-side event time in milliseconds
+    pub fn event_time(&self) -> u64 {
+        match self {
+            JournalEvent::PreventedMatch(m) => m.transact_time,
+            JournalEvent::Trade(t) => t.time,
+            JournalEvent::Incident(i) => i.timestamp / 1_000_000,
+            JournalEvent::Order(o) => o.update_time,
+            JournalEvent::Income(i) => i.time,
+        }
+    }
+
+    /// Event name used by external consumers, e.g. "order" or "trade"
+    pub fn kind(&self) -> &'static str {
+        match self {
+            JournalEvent::PreventedMatch(_) => "prevented_match",
+            JournalEvent::Trade(_) => "trade",
+            JournalEvent::Incident(_) => "incident",
+            JournalEvent::Order(_) => "order",
+            JournalEvent::Income(_) => "income",
+        }
+    }
+}
+
+/// A journal entry with local sequencing metadata
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub sequence: u64,
+    pub exchange: String,
+    pub recorded_at: u64,
+    pub event: JournalEvent,
+}
+
+/// In-memory append-only journal
+#[derive(Debug, Default)]
+pub struct Journal {
+    entries: Vec<JournalEntry>,
+    seen: HashSet<(String, String)>,
+}
+
+impl Journal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append an event; returns `false` if it was already journaled
+    pub fn record(&mut self, exchange: &str, event: JournalEvent) -> bool {
+        if !self.seen.insert((exchange.to_string(), event.dedupe_key())) {
+            return false;
+        }
+
+        self.entries.push(JournalEntry {
+            sequence: self.entries.len() as u64,
+            exchange: exchange.to_string(),
+            recorded_at: nanos() / 1_000_000,
+            event,
+        });
+        true
+    }
+
+    /// All entries in recording order
+    pub fn entries(&self) -> &[JournalEntry] {
+        &self.entries
+    }
+
+    /// Entries for a single symbol
+    pub fn entries_for<'a>(&'a self, symbol: &'a str) -> impl Iterator<Item = &'a JournalEntry> + 'a {
+        self.entries.iter().filter(move |e| e.event.symbol() == symbol)
+    }
+
+    /// Prevented matches for a symbol, in recording order
+    pub fn prevented_matches<'a>(&'a self, symbol: &'a str) -> impl Iterator<Item = &'a PreventedMatchRecord> + 'a {
+        self.entries_for(symbol).filter_map(|e| match &e.event {
+            JournalEvent::PreventedMatch(m) => Some(m),
+            _ => None,
+        })
+    }
+
+    /// Trades across all symbols, in recording order
+    pub fn trades(&self) -> impl Iterator<Item = &TradeRecord> {
+        self.entries.iter().filter_map(|e| match &e.event {
+            JournalEvent::Trade(t) => Some(t),
+            _ => None,
+        })
+    }
+
+    /// Highest journaled trade id for a symbol, where a trade backfill resumes
+    pub fn last_trade_id(&self, symbol: &str) -> Option<u64> {
+        self.trades().filter(|t| t.symbol == symbol).map(|t| t.trade_id).max()
+    }
+
+    /// Futures wallet entries, in recording order
+    pub fn incomes(&self) -> impl Iterator<Item = &IncomeRecord> {
+        self.entries.iter().filter_map(|e| match &e.event {
+            JournalEvent::Income(i) => Some(i),
+            _ => None,
+        })
+    }
+
+    /// Latest journaled income time, where an income sync resumes
+    pub fn last_income_time(&self, exchange: &str) -> Option<u64> {
+        self.entries.iter()
+            .filter(|e| e.exchange == exchange)
+            .filter_map(|e| match &e.event {
+                JournalEvent::Income(i) => Some(i.time),
+                _ => None,
+            })
+            .max()
+    }
+
+    /// Net income per (asset, income type) within `[start_ms, end_ms)`, to
+    /// compare against the exchange's statement for the period
+    pub fn income_totals(&self, start_ms: u64, end_ms: u64) -> BTreeMap<(String, String), Fixed> {
+        let mut totals = BTreeMap::new();
+        for income in self.incomes().filter(|i| (start_ms..end_ms).contains(&i.time)) {
+            *totals.entry((income.asset.clone(), income.income_type.clone())).or_insert(Fixed::ZERO) += income.amount;
+        }
+        totals
+    }
+
+    /// Journal an order's current state; repeats of the same status and fill are dropped
+    pub fn record_order(&mut self, exchange: &str, order: &ManagedOrder) -> bool {
+        self.record(exchange, JournalEvent::Order(order.into()))
+    }
+
+    /// Journal every incident the subscriber hasn't seen yet; returns how many were added
+    pub fn record_incidents(&mut self, exchange: &str, subscriber: &mut IncidentSubscriber) -> usize {
+        subscriber
+            .drain()
+            .into_iter()
+            .filter(|incident| self.record(exchange, JournalEvent::Incident(incident.clone())))
+            .count()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Export all entries as JSON lines
+    pub fn write_jsonl<W: Write>(&self, mut writer: W) -> Result<()> {
+        for entry in &self.entries {
+            serde_json::to_writer(&mut writer, entry)?;
+            writer.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prevented(id: u64) -> JournalEvent {
+        JournalEvent::PreventedMatch(PreventedMatchRecord {
+            prevented_match_id: id,
+            symbol: "BTCUSDT".to_string(),
+            taker_order_id: 5,
+            maker_order_id: 3,
+            trade_group_id: 1,
+            stp_mode: "EXPIRE_MAKER".to_string(),
+            price: Fixed::from_str_exact("1.1").unwrap(),
+            maker_prevented_quantity: Fixed::from_str_exact("1.0").unwrap(),
+            transact_time: 1669101687094,
+        })
+    }
+
+    #[test]
+    fn test_journal_dedupes_and_exports() {
+        let mut journal = Journal::new();
+
+        assert!(journal.record("binance", prevented(1)));
+        assert!(!journal.record("binance", prevented(1)));
+        assert!(journal.record("binance", prevented(2)));
+        assert_eq!(journal.len(), 2);
+        assert_eq!(journal.prevented_matches("BTCUSDT").count(), 2);
+        assert_eq!(journal.entries_for("ETHUSDT").count(), 0);
+
+        let mut out = Vec::new();
+        journal.write_jsonl(&mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(text.lines().count(), 2);
+        assert!(text.contains("\"type\":\"PreventedMatch\""));
+    }
+}
@@ -0,0 +1,238 @@
+//! Tick-to-trade latency tracing
+//!
+//! A `LatencyTrace` is started when a market data message is received and
+//! carried with the decision it leads to. Each stage marks the time it was
+//! reached: signal computed, order intent built, REST request sent,
+//! execution report received. `TickToTrade` matches traces to execution
+//! reports by client order id and records each stage's duration, plus the
+//! end-to-end receive-to-execution time, in a `MetricsRegistry`.
+//!
+//! The end-to-end histogram (`tick_to_trade`) is the crate's headline
+//! performance number; the per-stage ones show where a slow trade spent its
+//! time.
+
+use sriquant_core::metrics::{HistogramSnapshot, LatencyHistogram, MetricsRegistry};
+use sriquant_core::prelude::*;
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Registry name of the end-to-end histogram
+pub const TICK_TO_TRADE_METRIC: &str = "tick_to_trade";
+/// Counter of trades over the end-to-end budget
+pub const OVER_BUDGET_METRIC: &str = "tick_to_trade.over_budget";
+
+static NEXT_TRACE_ID: AtomicU64 = AtomicU64::new(1);
+
+/// A point on the way from market data to execution
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stage {
+    Signal,
+    Intent,
+    Send,
+    Execution,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 4] = [Stage::Signal, Stage::Intent, Stage::Send, Stage::Execution];
+
+    /// Registry name of the histogram for the time spent reaching this stage
+    pub fn metric(self) -> &'static str {
+        match self {
+            Stage::Signal => "tick_to_trade.receive_to_signal",
+            Stage::Intent => "tick_to_trade.signal_to_intent",
+            Stage::Send => "tick_to_trade.intent_to_send",
+            Stage::Execution => "tick_to_trade.send_to_execution",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Timestamps of one market data message's path to an order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LatencyTrace {
+    /// Correlation id, unique within the process
+    pub id: u64,
+    /// When the triggering message was received, in nanos
+    pub received_at: u64,
+    marks: [Option<u64>; 4],
+}
+
+impl LatencyTrace {
+    /// Start a trace for a message received at `received_at` nanos
+    pub fn begin(received_at: u64) -> Self {
+        Self { id: NEXT_TRACE_ID.fetch_add(1, Ordering::Relaxed), received_at, marks: [None; 4] }
+    }
+
+    /// Record reaching `stage` now
+    pub fn mark(&mut self, stage: Stage) {
+        self.mark_at(stage, nanos());
+    }
+
+    /// Record reaching `stage` at `at` nanos; a stage is only marked once
+    pub fn mark_at(&mut self, stage: Stage, at: u64) {
+        self.marks[stage.index()].get_or_insert(at);
+    }
+
+    pub fn marked(&self, stage: Stage) -> Option<u64> {
+        self.marks[stage.index()]
+    }
+
+    /// Time spent reaching `stage` from the previous marked stage (or receive)
+    pub fn stage_nanos(&self, stage: Stage) -> Option<u64> {
+        let end = self.marked(stage)?;
+        let start = Stage::ALL[..stage.index()].iter().rev().find_map(|s| self.marked(*s)).unwrap_or(self.received_at);
+        Some(end.saturating_sub(start))
+    }
+
+    /// Receive to execution report, once the execution is marked
+    pub fn total_nanos(&self) -> Option<u64> {
+        self.marked(Stage::Execution).map(|end| end.saturating_sub(self.received_at))
+    }
+}
+
+/// Matches in-flight traces to execution reports and records their latency
+///
+/// ```rust,ignore
+/// let mut trace = LatencyTrace::begin(nanos());   // on the WebSocket message
+/// trace.mark(Stage::Signal);
+/// let request = build_order(...);
+/// trace.mark(Stage::Intent);
+/// oms.place_order_traced(request, trace).await?;  // marks Send
+/// oms.apply_update(&report);                      // marks Execution and records
+/// ```
+#[derive(Debug)]
+pub struct TickToTrade {
+    registry: MetricsRegistry,
+    in_flight: HashMap<String, LatencyTrace>,
+    headline: LatencyHistogram,
+    budget: Option<Duration>,
+}
+
+impl TickToTrade {
+    pub fn new(registry: MetricsRegistry) -> Self {
+        Self { registry, in_flight: HashMap::new(), headline: LatencyHistogram::default(), budget: None }
+    }
+
+    /// Warn about and count trades slower than `budget` end to end
+    pub fn with_budget(mut self, budget: Duration) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    pub fn registry(&self) -> &MetricsRegistry {
+        &self.registry
+    }
+
+    /// Traces waiting for an execution report
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.len()
+    }
+
+    /// The trace waiting for `client_order_id`'s execution report
+    pub fn pending(&self, client_order_id: &str) -> Option<&LatencyTrace> {
+        self.in_flight.get(client_order_id)
+    }
+
+    /// Mark `trace` sent and wait for the execution report of `client_order_id`
+    pub fn submit(&mut self, client_order_id: &str, mut trace: LatencyTrace) {
+        trace.mark(Stage::Send);
+        self.in_flight.insert(client_order_id.to_string(), trace);
+    }
+
+    /// Stop waiting for an order that will never execute (e.g. rejected)
+    pub fn discard(&mut self, client_order_id: &str) -> Option<LatencyTrace> {
+        self.in_flight.remove(client_order_id)
+    }
+
+    /// Finish the trace for `client_order_id` at its first execution report
+    pub fn on_execution(&mut self, client_order_id: &str, at: u64) -> Option<LatencyTrace> {
+        let mut trace = self.in_flight.remove(client_order_id)?;
+        trace.mark_at(Stage::Execution, at);
+        self.record(&trace);
+        Some(trace)
+    }
+
+    /// Record a completed trace's stage and end-to-end durations
+    pub fn record(&mut self, trace: &LatencyTrace) {
+        for stage in Stage::ALL {
+            if let Some(elapsed) = trace.stage_nanos(stage) {
+                self.registry.record_latency(stage.metric(), elapsed);
+            }
+        }
+        let Some(total) = trace.total_nanos() else { return };
+        self.registry.record_latency(TICK_TO_TRADE_METRIC, total);
+        self.headline.record(total);
+
+        if let Some(budget) = self.budget
+            && total > budget.as_nanos() as u64
+        {
+            self.registry.increment(OVER_BUDGET_METRIC, 1);
+            let stages: Vec<String> = Stage::ALL.iter()
+                .filter_map(|s| trace.stage_nanos(*s).map(|n| format!("{}={}us", s.metric().trim_start_matches("tick_to_trade."), n / 1_000)))
+                .collect();
+            warn!("🐢 Trace {} took {}us tick-to-trade, over the {}us budget ({})", trace.id, total / 1_000, budget.as_micros(), stages.join(", "));
+        }
+    }
+
+    /// End-to-end latency of every trace recorded so far
+    pub fn tick_to_trade(&self) -> HistogramSnapshot {
+        self.headline.snapshot()
+    }
+
+    /// Drop traces sent more than `max_age` before `now`; returns how many
+    pub fn expire(&mut self, now: u64, max_age: Duration) -> usize {
+        let before = self.in_flight.len();
+        let max_age = max_age.as_nanos() as u64;
+        self.in_flight.retain(|_, t| now.saturating_sub(t.marked(Stage::Send).unwrap_or(t.received_at)) <= max_age);
+        before - self.in_flight.len()
+    }
+
+    pub fn log_summary(&self) {
+        let h = self.tick_to_trade();
+        info!(
+            "⏱️ Tick-to-trade over {} trades: p50 {}us, p99 {}us, max {}us",
+            h.count,
+            h.quantile_nanos(0.5) / 1_000,
+            h.quantile_nanos(0.99) / 1_000,
+            h.max_nanos / 1_000
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stages_and_end_to_end_are_recorded() {
+        let registry = MetricsRegistry::new();
+        let mut tracer = TickToTrade::new(registry.clone()).with_budget(Duration::from_micros(500));
+
+        let mut trace = LatencyTrace::begin(1_000_000);
+        trace.mark_at(Stage::Signal, 1_020_000);
+        trace.mark_at(Stage::Intent, 1_025_000);
+        trace.mark_at(Stage::Send, 1_100_000);
+        assert_ne!(LatencyTrace::begin(0).id, trace.id);
+        tracer.submit("sq-1", trace);
+        assert_eq!(tracer.in_flight(), 1);
+
+        let done = tracer.on_execution("sq-1", 1_700_000).unwrap();
+        assert_eq!(done.stage_nanos(Stage::Intent), Some(5_000));
+        assert_eq!(done.stage_nanos(Stage::Execution), Some(600_000));
+        assert_eq!(done.total_nanos(), Some(700_000));
+        // Only the first execution report finishes a trace
+        assert!(tracer.on_execution("sq-1", 1_800_000).is_none());
+
+        assert_eq!(tracer.tick_to_trade().count, 1);
+        assert_eq!(registry.counter(OVER_BUDGET_METRIC), 1);
+        let snapshot = registry.take_snapshot();
+        assert_eq!(snapshot.histograms[TICK_TO_TRADE_METRIC].max_nanos, 700_000);
+        assert_eq!(snapshot.histograms[Stage::Signal.metric()].max_nanos, 20_000);
+    }
+}
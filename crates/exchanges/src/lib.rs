@@ -1,7 +1,8 @@
 //! # SriQuant.ai Exchange Integrations
 //! 
 //! High-performance exchange integrations for algorithmic trading.
-//! Currently focuses on Binance with plans to expand to other exchanges.
+//! Binance is the primary venue; Bybit, Coinbase Advanced Trade and NSE/BSE
+//! market data implement the same traits.
 //!
 //! ## Architecture
 //!
@@ -17,19 +18,107 @@ pub mod types;
 pub mod errors;
 pub mod http;
 pub mod websocket;
+pub mod journal;
+pub mod scanner;
+pub mod cassette;
+pub mod paper;
+pub mod runner;
+pub mod switches;
+pub mod fees;
+pub mod routing;
+pub mod incidents;
+pub mod report;
+pub mod queue;
+pub mod orderbook;
+pub mod warmup;
+pub mod bars;
+pub mod mux;
+pub mod testkit;
+pub mod accounting;
+pub mod tax;
+pub mod nse;
+pub mod bybit;
+pub mod coinbase;
+pub mod conversion;
+pub mod simulated;
+pub mod fill_model;
+pub mod backtest;
+pub mod risk;
+pub mod oms;
+pub mod positions;
+pub mod webhook;
+pub mod store;
+pub mod latency;
+pub mod chaos;
+pub mod remote_config;
+pub mod book_store;
+pub mod admin;
+pub mod treasury;
+pub mod clock_guard;
+pub mod pagination;
+pub mod compliance;
+pub mod recorder;
+pub mod quote_guard;
+pub mod client_id;
+mod disk_writer;
+#[cfg(feature = "arrow")]
+pub mod columnar;
 
 // Re-export main types
 pub use binance::BinanceExchange;
-pub use traits::{Exchange, StreamingExchange};
+pub use traits::{Exchange, StreamingExchange, TradingExchange};
 pub use types::*;
-pub use errors::{ExchangeError, Result};
-pub use http::MonoioHttpsClient;
-pub use websocket::MonoioWebSocket;
+pub use errors::{ErrorContext, ExchangeError, Result, ResultExt};
+pub use http::{HandshakeTiming, MonoioHttpsClient, PoolConfig, PoolStats};
+pub use websocket::{MonoioWebSocket, PerMessageDeflate, WebSocketOptions};
+pub use journal::{IncomeRecord, Journal, JournalEntry, JournalEvent, OrderRecord, TradeRecord};
+pub use compliance::{AuditFormat, BinanceOrderCsv, ComplianceExporter, FixDropCopy};
+pub use webhook::{WebhookConfig, WebhookSink};
+pub use store::{KvStore, StrategyStore};
+pub use latency::{LatencyTrace, Stage, TickToTrade};
+pub use accounting::{CostBasisLedger, CostBasisMethod, RealizedLot, TaxLot};
+pub use tax::{IndiaTaxConfig, IndiaTaxReport, IndiaTaxRow, IndiaTaxSummary};
+pub use conversion::{ConversionRate, CurrencyConverter};
+pub use nse::{BrokerFeed, BrokerTick, FeedMode, NseExchange};
+pub use bybit::{BybitCategory, BybitConfig, BybitCredentials, BybitExchange, BybitPrivateStream, BybitPublicStream};
+pub use coinbase::{CoinbaseConfig, CoinbaseCredentials, CoinbaseExchange, CoinbaseWebSocketClient};
+pub use fees::{EdgeCalculator, FeeAsset, FeePreference, FeeSchedule, FeeTier, FeeTierStatus, FeeTierTracker};
+pub use incidents::{Incident, IncidentBus, IncidentKind, IncidentSubscriber, Severity};
+pub use routing::{OrderRouter, OrderTransport, RouterConfig, TransportStats};
+pub use cassette::{Cassette, CassetteHandle, CassetteMode};
+pub use chaos::{Fault, FaultConfig, FaultInjector, FaultStats};
+pub use remote_config::{ParameterBundle, RemoteConfigClient, SignedBundle};
+pub use book_store::BookSnapshotStore;
+pub use recorder::{DataGap, RecordedEntry, Recording, RecordingManifest, StreamRecorder, StreamSummary};
+#[cfg(feature = "arrow")]
+pub use columnar::{klines_batch, my_trades_batch, orders_batch, recorded_trades, trades_batch, write_parquet};
+pub use admin::{AdminServer, CheckStatus, HealthChecks, Readiness, ReadinessCheck};
+pub use treasury::{AssetHoldings, BalanceAggregator, PortfolioView, TransferLeg, TransferPlan, TransferPlanner, TransferStep, VenueBalances, WithdrawalRule};
+pub use testkit::{MockBinance, MockStats};
+pub use paper::{PaperFill, PaperFillSimulator};
+pub use backtest::{BacktestConfig, BacktestReport, Backtester, EquityPoint, SlippageModel};
+pub use simulated::{LatencyModel, SimulatedExchange, SimulatorConfig};
+pub use fill_model::{FillContext, FillModel, OptimisticFill, ProbabilisticFill, QueueFill, TradeThroughFill};
+pub use oms::{CancelAllReport, CancelVerifyConfig, DisconnectPolicy, IntentEvent, IntentQueue, ManagedOrder, OrderManager, ReconcileReport, UpdateOutcome};
+pub use positions::{Position, PositionFill, PositionTracker};
+pub use risk::{BuyingPower, NotionalLimitConfig, OpenNotionalLimit, PriceProtection, RiskEngine, RiskLimits};
+pub use queue::{QueueEstimator, QueuePosition};
+pub use quote_guard::{QuoteGuard, QuoteGuardStats, RequoteThreshold};
+pub use client_id::{ClientIdPolicy, IdCharset, UniquenessHorizon};
+pub use report::{OrderIntent, SignalReport, SignalStats, SlippageReport, SlippageStats, SlippageTracker};
+pub use runner::{Strategy, StrategyRunner, TradingEnvironment, RunnerAction, DivergenceReport, PromotionCriteria, PromotionApproval};
+pub use switches::TradingSwitches;
+pub use bars::{write_klines_csv, BarClock, BarClose, BarSummary, ExchangeClock};
+pub use clock_guard::ClockGuard;
+pub use pagination::{paginate, PageWindow, Paged, Paginator};
+pub use mux::{EventMux, Muxed, SourceId};
+pub use warmup::{warm_up_binance, GateStatus, WarmupGate, WarmupPlan, WarmupTracker};
+pub use scanner::{MarketScanner, ScannerConfig, ScanFilter, RankBy, ScanResult, ScanTicker};
 
 /// Prelude for convenient imports
 pub mod prelude {
     pub use crate::binance::BinanceExchange;
-    pub use crate::traits::{Exchange, StreamingExchange};
+    pub use crate::traits::{Exchange, StreamingExchange, TradingExchange};
     pub use crate::types::*;
     pub use crate::errors::{ExchangeError, Result};
     pub use crate::http::MonoioHttpsClient;
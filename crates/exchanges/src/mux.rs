@@ -0,0 +1,310 @@
+//! One event loop over many sources
+//!
+//! A live strategy listens to several things at once: market data, the user
+//! stream, timers and operator commands. `EventMux` merges any number of
+//! channel receivers and interval timers into a single `next().await`, so the
+//! loop body is one `match` instead of nested loops that each block on their
+//! own source.
+//!
+//! Sources are served in the order they were added: when several have events
+//! ready, the first wins. To keep a busy early source (a depth stream) from
+//! starving the ones after it (the user stream, a heartbeat timer), a ready
+//! source passed over `max_burst` times in a row goes first on the next pick.
+
+use sriquant_core::channel::{Receiver, TryRecvError};
+use std::future::{poll_fn, Future};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// Picks a ready source may be passed over before it is served first
+pub const DEFAULT_MAX_BURST: usize = 32;
+
+/// Handle to a source added to an `EventMux`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SourceId(usize);
+
+/// An event and the source it came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Muxed<T> {
+    pub source: SourceId,
+    pub event: T,
+}
+
+/// A channel whose items are mapped into the loop's event type
+trait ChannelFeed<T> {
+    /// An item is queued, or the channel closed and `try_take` will say so
+    fn has_ready(&self) -> bool;
+    fn try_take(&mut self) -> Result<T, TryRecvError>;
+    /// Wait for an item; `None` once every sender is gone
+    fn poll_take(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>>;
+}
+
+struct MappedChannel<U, F> {
+    receiver: Receiver<U>,
+    map: F,
+}
+
+impl<U, T, F: FnMut(U) -> T> ChannelFeed<T> for MappedChannel<U, F> {
+    fn has_ready(&self) -> bool {
+        !self.receiver.is_empty() || self.receiver.is_disconnected()
+    }
+
+    fn try_take(&mut self) -> Result<T, TryRecvError> {
+        self.receiver.try_recv().map(&mut self.map)
+    }
+
+    fn poll_take(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        // Waiting only registers a wakeup; items stay queued, so an
+        // abandoned wait loses nothing
+        self.receiver.poll_recv(cx).map(|item| item.map(&mut self.map))
+    }
+}
+
+enum Feed<T> {
+    Channel(Box<dyn ChannelFeed<T>>),
+    Interval {
+        every: Duration,
+        next_due: Instant,
+        make: Box<dyn FnMut() -> T>,
+    },
+}
+
+struct Source<T> {
+    name: String,
+    feed: Feed<T>,
+    open: bool,
+    /// Consecutive picks that went elsewhere while this source was ready
+    passed_over: usize,
+    delivered: u64,
+}
+
+impl<T> Source<T> {
+    fn is_ready(&self, now: Instant) -> bool {
+        self.open && match &self.feed {
+            Feed::Channel(channel) => channel.has_ready(),
+            Feed::Interval { next_due, .. } => *next_due <= now,
+        }
+    }
+
+    fn take(&mut self, now: Instant) -> Option<T> {
+        match &mut self.feed {
+            Feed::Channel(channel) => match channel.try_take() {
+                Ok(event) => Some(event),
+                Err(TryRecvError::Empty) => None,
+                Err(TryRecvError::Disconnected) => {
+                    debug!("Event source {} closed", self.name);
+                    self.open = false;
+                    None
+                }
+            },
+            Feed::Interval { every, next_due, make } => {
+                if *next_due > now {
+                    return None;
+                }
+                // A stalled loop gets one tick, not a burst of catch-up ticks
+                *next_due += *every;
+                if *next_due <= now {
+                    *next_due = now + *every;
+                }
+                Some(make())
+            }
+        }
+    }
+}
+
+/// Merges channels and timers into one ordered event loop
+///
+/// ```rust,ignore
+/// enum Event { Market(MarketDataEvent), User(UserDataEvent), Heartbeat }
+///
+/// let mut mux = EventMux::new();
+/// mux.add_channel_map("user", user_rx, Event::User);
+/// mux.add_channel_map("btcusdt", btc_rx, Event::Market);
+/// mux.add_interval("heartbeat", Duration::from_secs(30), || Event::Heartbeat);
+/// while let Some(Muxed { event, .. }) = mux.next().await {
+///     match event { ... }
+/// }
+/// ```
+pub struct EventMux<T> {
+    sources: Vec<Source<T>>,
+    max_burst: usize,
+}
+
+impl<T: 'static> Default for EventMux<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: 'static> EventMux<T> {
+    pub fn new() -> Self {
+        Self { sources: Vec::new(), max_burst: DEFAULT_MAX_BURST }
+    }
+
+    /// Serve a ready source first after it was passed over this many times
+    pub fn with_max_burst(mut self, max_burst: usize) -> Self {
+        self.max_burst = max_burst.max(1);
+        self
+    }
+
+    fn push(&mut self, name: &str, feed: Feed<T>) -> SourceId {
+        self.sources.push(Source { name: name.to_string(), feed, open: true, passed_over: 0, delivered: 0 });
+        SourceId(self.sources.len() - 1)
+    }
+
+    /// Add a receiver of the loop's event type
+    pub fn add_channel(&mut self, name: &str, receiver: Receiver<T>) -> SourceId {
+        self.add_channel_map(name, receiver, |event| event)
+    }
+
+    /// Add a receiver whose items `map` turns into loop events
+    pub fn add_channel_map<U: 'static>(&mut self, name: &str, receiver: Receiver<U>, map: impl FnMut(U) -> T + 'static) -> SourceId {
+        self.push(name, Feed::Channel(Box::new(MappedChannel { receiver, map })))
+    }
+
+    /// Add a timer producing `make()` every `every`, first one period from now
+    pub fn add_interval(&mut self, name: &str, every: Duration, make: impl FnMut() -> T + 'static) -> SourceId {
+        self.push(name, Feed::Interval { every, next_due: Instant::now() + every, make: Box::new(make) })
+    }
+
+    pub fn name(&self, source: SourceId) -> &str {
+        &self.sources[source.0].name
+    }
+
+    /// Events delivered from `source` so far
+    pub fn delivered(&self, source: SourceId) -> u64 {
+        self.sources[source.0].delivered
+    }
+
+    /// False once every sender of a channel source is gone and it is drained
+    pub fn is_open(&self, source: SourceId) -> bool {
+        self.sources[source.0].open
+    }
+
+    /// The next ready event, without waiting
+    pub fn try_next(&mut self) -> Option<Muxed<T>> {
+        let now = Instant::now();
+        loop {
+            let mut first_ready = None;
+            let mut first_starved = None;
+            for (index, source) in self.sources.iter().enumerate() {
+                if source.is_ready(now) {
+                    first_ready.get_or_insert(index);
+                    if source.passed_over >= self.max_burst {
+                        first_starved.get_or_insert(index);
+                    }
+                }
+            }
+            let pick = first_starved.or(first_ready)?;
+            let Some(event) = self.sources[pick].take(now) else {
+                // Raced to empty or closed; look again
+                continue;
+            };
+            for (index, source) in self.sources.iter_mut().enumerate() {
+                if index != pick && source.is_ready(now) {
+                    source.passed_over += 1;
+                }
+            }
+            return Some(self.deliver(pick, event));
+        }
+    }
+
+    fn deliver(&mut self, index: usize, event: T) -> Muxed<T> {
+        let source = &mut self.sources[index];
+        source.passed_over = 0;
+        source.delivered += 1;
+        Muxed { source: SourceId(index), event }
+    }
+
+    /// Wait for the next event; `None` when no channel is open and there are no timers
+    pub async fn next(&mut self) -> Option<Muxed<T>> {
+        loop {
+            if let Some(event) = self.try_next() {
+                return Some(event);
+            }
+            let next_due = self.sources.iter()
+                .filter_map(|s| match &s.feed {
+                    Feed::Interval { next_due, .. } if s.open => Some(*next_due),
+                    _ => None,
+                })
+                .min();
+            if next_due.is_none() && !self.sources.iter().any(|s| s.open) {
+                return None;
+            }
+            let mut sleep = next_due.map(|due| Box::pin(monoio::time::sleep(due.saturating_duration_since(Instant::now()))));
+
+            let woken = poll_fn(|cx| {
+                for (index, source) in self.sources.iter_mut().enumerate() {
+                    let Feed::Channel(channel) = &mut source.feed else { continue };
+                    if !source.open {
+                        continue;
+                    }
+                    if let Poll::Ready(event) = channel.poll_take(cx) {
+                        return Poll::Ready(Some((index, event)));
+                    }
+                }
+                match sleep.as_mut().map(|sleep| sleep.as_mut().poll(cx)) {
+                    Some(Poll::Ready(())) => Poll::Ready(None),
+                    _ => Poll::Pending,
+                }
+            }).await;
+
+            match woken {
+                Some((index, Some(event))) => return Some(self.deliver(index, event)),
+                Some((index, None)) => {
+                    debug!("Event source {} closed", self.sources[index].name);
+                    self.sources[index].open = false;
+                }
+                // A timer is due; the next pass serves it
+                None => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sriquant_core::channel::bounded;
+
+    #[test]
+    fn test_busy_source_does_not_starve_later_ones() {
+        let (market_tx, market_rx) = bounded(16);
+        let (user_tx, user_rx) = bounded(16);
+        let mut mux = EventMux::new().with_max_burst(3);
+        let market = mux.add_channel("market", market_rx);
+        let user = mux.add_channel("user", user_rx);
+
+        for i in 0..10 {
+            market_tx.try_send(i).unwrap();
+        }
+        user_tx.try_send(100).unwrap();
+        user_tx.try_send(101).unwrap();
+
+        let order: Vec<i32> = std::iter::from_fn(|| mux.try_next()).map(|m| m.event).collect();
+        assert_eq!(order, vec![0, 1, 2, 100, 3, 4, 5, 101, 6, 7, 8, 9]);
+        assert_eq!((mux.delivered(market), mux.delivered(user)), (10, 2));
+        assert_eq!(mux.name(user), "user");
+    }
+
+    #[monoio::test(timer_enabled = true)]
+    async fn test_next_waits_on_channels_and_timers() {
+        let (tx, rx) = bounded(4);
+        let mut mux = EventMux::new();
+        let channel = mux.add_channel_map("commands", rx, |text: &str| text.to_string());
+        let timer = mux.add_interval("tick", Duration::from_millis(20), || "tick".to_string());
+
+        monoio::spawn(async move {
+            monoio::time::sleep(Duration::from_millis(5)).await;
+            tx.send("stop").await.unwrap();
+        });
+        let first = mux.next().await.unwrap();
+        assert_eq!((first.source, first.event.as_str()), (channel, "stop"));
+        let second = mux.next().await.unwrap();
+        assert_eq!((second.source, second.event.as_str()), (timer, "tick"));
+        // The sender is gone; the channel closes once drained
+        assert!(mux.try_next().is_none());
+        assert!(!mux.is_open(channel));
+    }
+}
@@ -0,0 +1,74 @@
+//! Pluggable broker feed interface for Indian markets
+//!
+//! NSE and BSE don't offer retail WebSocket feeds directly; ticks arrive via a
+//! broker or data vendor (Kite Ticker, TrueData, ...). A vendor adapter
+//! implements `BrokerFeed` and emits `BrokerTick`s keyed by instrument token.
+
+use crate::errors::Result;
+use sriquant_core::prelude::*;
+
+use async_trait::async_trait;
+
+/// Detail level requested for an instrument, cheapest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum FeedMode {
+    /// Last traded price only
+    Ltp,
+    /// LTP, OHLC, volume and last trade
+    Quote,
+    /// Quote plus market depth
+    Full,
+}
+
+/// One price level of market depth
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DepthLevel {
+    pub price: Fixed,
+    pub quantity: u64,
+    pub orders: u32,
+}
+
+/// A normalized tick from any broker feed
+///
+/// Fields the subscribed `FeedMode` doesn't carry are zero or empty.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BrokerTick {
+    pub instrument_token: u32,
+    pub last_price: Fixed,
+    pub last_quantity: u64,
+    /// Cumulative volume for the session
+    pub volume_traded: u64,
+    pub open: Fixed,
+    pub high: Fixed,
+    pub low: Fixed,
+    /// Previous session close
+    pub close: Fixed,
+    /// Exchange timestamp in milliseconds
+    pub exchange_timestamp: u64,
+    pub last_trade_time: u64,
+    /// Best bids first
+    pub bids: Vec<DepthLevel>,
+    /// Best asks first
+    pub asks: Vec<DepthLevel>,
+}
+
+/// Source of ticks for the Indian market adapter
+#[async_trait(?Send)]
+pub trait BrokerFeed {
+    /// Vendor name used in logs
+    fn name(&self) -> &str;
+
+    async fn connect(&mut self) -> Result<()>;
+
+    async fn disconnect(&mut self) -> Result<()>;
+
+    /// Subscribe tokens at a mode, upgrading tokens already subscribed
+    async fn subscribe(&mut self, tokens: &[u32], mode: FeedMode) -> Result<()>;
+
+    async fn unsubscribe(&mut self, tokens: &[u32]) -> Result<()>;
+
+    /// Next tick; `None` once the feed has closed
+    async fn next_tick(&mut self) -> Result<Option<BrokerTick>>;
+
+    fn is_connected(&self) -> bool;
+}
@@ -0,0 +1,450 @@
+//! NSE/BSE market data adapter
+//!
+//! Implements `StreamingExchange` on top of any `BrokerFeed`, mapping Indian
+//! equity and derivatives ticks to the unified `MarketData` events the
+//! strategy runner consumes. Symbols are whatever keys the instrument
+//! registry was loaded with (e.g. "NSE:RELIANCE", "NFO:NIFTY25OCTFUT"); the
+//! broker only sees instrument tokens.
+//!
+//! This is scaffolding for market data only: order routing goes through the
+//! broker's own API and isn't wrapped here yet.
+
+pub mod feed;
+
+pub use feed::{BrokerFeed, BrokerTick, DepthLevel, FeedMode};
+
+use crate::errors::{ExchangeError, Result};
+use crate::traits::StreamingExchange;
+use crate::types::*;
+use sriquant_core::prelude::*;
+
+use async_trait::async_trait;
+use std::collections::{HashMap, VecDeque};
+use tracing::{debug, info};
+
+/// Indian exchange an instrument is listed on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum IndianExchange {
+    Nse,
+    Bse,
+}
+
+/// Market segment of an instrument
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Segment {
+    Equity,
+    Futures,
+    Options,
+    /// Index values; no trades or depth
+    Index,
+}
+
+/// A tradable instrument as listed by the broker
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Instrument {
+    pub symbol: String,
+    pub instrument_token: u32,
+    pub exchange: IndianExchange,
+    pub segment: Segment,
+    pub tick_size: Fixed,
+    pub lot_size: u32,
+}
+
+/// What a stream subscription asks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum StreamKind {
+    Ticker,
+    Trades,
+    Depth,
+}
+
+impl StreamKind {
+    fn suffix(self) -> &'static str {
+        match self {
+            StreamKind::Ticker => "ticker",
+            StreamKind::Trades => "trade",
+            StreamKind::Depth => "depth",
+        }
+    }
+
+    fn mode(self) -> FeedMode {
+        match self {
+            StreamKind::Ticker | StreamKind::Trades => FeedMode::Quote,
+            StreamKind::Depth => FeedMode::Full,
+        }
+    }
+}
+
+/// Volume seen for a token, to detect new trades between ticks
+#[derive(Debug, Clone, Copy)]
+struct LastTrade {
+    volume_traded: u64,
+    price: Fixed,
+    side: OrderSide,
+}
+
+/// NSE/BSE market data over a pluggable broker feed
+pub struct NseExchange<F: BrokerFeed> {
+    feed: F,
+    instruments: HashMap<String, Instrument>,
+    symbols_by_token: HashMap<u32, String>,
+    subscriptions: HashMap<String, (u32, StreamKind)>,
+    last_trades: HashMap<u32, LastTrade>,
+    pending_events: VecDeque<MarketData>,
+}
+
+impl<F: BrokerFeed> NseExchange<F> {
+    pub fn new(feed: F) -> Self {
+        info!("🇮🇳 NSE/BSE adapter created over {}", feed.name());
+        Self {
+            feed,
+            instruments: HashMap::new(),
+            symbols_by_token: HashMap::new(),
+            subscriptions: HashMap::new(),
+            last_trades: HashMap::new(),
+            pending_events: VecDeque::new(),
+        }
+    }
+
+    /// Register instruments, typically from the broker's instrument dump
+    pub fn with_instruments(mut self, instruments: impl IntoIterator<Item = Instrument>) -> Self {
+        for instrument in instruments {
+            self.symbols_by_token.insert(instrument.instrument_token, instrument.symbol.clone());
+            self.instruments.insert(instrument.symbol.clone(), instrument);
+        }
+        self
+    }
+
+    pub fn instrument(&self, symbol: &str) -> Option<&Instrument> {
+        self.instruments.get(symbol)
+    }
+
+    pub fn feed(&self) -> &F {
+        &self.feed
+    }
+
+    fn token(&self, symbol: &str) -> Result<u32> {
+        self.instruments.get(symbol)
+            .map(|i| i.instrument_token)
+            .ok_or_else(|| ExchangeError::SymbolNotFound(symbol.to_string()))
+    }
+
+    /// Highest mode any remaining subscription needs for a token
+    fn required_mode(&self, token: u32) -> Option<FeedMode> {
+        self.subscriptions.values()
+            .filter(|(t, _)| *t == token)
+            .map(|(_, kind)| kind.mode())
+            .max()
+    }
+
+    async fn subscribe(&mut self, symbol: &str, kind: StreamKind) -> Result<()> {
+        let token = self.token(symbol)?;
+        let stream = format!("{}@{}", symbol, kind.suffix());
+        if self.subscriptions.contains_key(&stream) {
+            return Ok(());
+        }
+
+        let current = self.required_mode(token);
+        if current.is_none_or(|mode| mode < kind.mode()) {
+            self.feed.subscribe(&[token], kind.mode()).await?;
+        }
+        self.subscriptions.insert(stream.clone(), (token, kind));
+        info!("📊 Subscribed to {} via {}", stream, self.feed.name());
+        Ok(())
+    }
+
+    /// Map a tick to unified events for the streams subscribed on its token
+    fn events_from_tick(&mut self, tick: &BrokerTick) -> Vec<MarketData> {
+        let Some(symbol) = self.symbols_by_token.get(&tick.instrument_token).cloned() else {
+            debug!("Tick for unregistered token {}", tick.instrument_token);
+            return Vec::new();
+        };
+        let wants = |kind| self.subscriptions.values().any(|&(t, k)| t == tick.instrument_token && k == kind);
+        let (wants_ticker, wants_trades, wants_depth) = (wants(StreamKind::Ticker), wants(StreamKind::Trades), wants(StreamKind::Depth));
+
+        let mut events = Vec::new();
+        if wants_ticker {
+            events.push(MarketData::Ticker(ticker_from_tick(&symbol, tick)));
+        }
+        if let Some(trade) = self.trade_from_tick(&symbol, tick)
+            && wants_trades
+        {
+            events.push(MarketData::Trade(trade));
+        }
+        if wants_depth && !(tick.bids.is_empty() && tick.asks.is_empty()) {
+            events.push(MarketData::OrderBook(book_from_tick(&symbol, tick)));
+        }
+        events
+    }
+
+    /// Infer the last trade when cumulative volume advanced
+    ///
+    /// Broker feeds don't send individual prints, so the side is guessed with
+    /// the tick rule: an uptick is a buy, a downtick a sell, and an unchanged
+    /// price keeps the previous side.
+    fn trade_from_tick(&mut self, symbol: &str, tick: &BrokerTick) -> Option<Trade> {
+        let previous = self.last_trades.get(&tick.instrument_token).copied();
+        let side = match previous {
+            Some(last) if tick.last_price > last.price => OrderSide::Buy,
+            Some(last) if tick.last_price < last.price => OrderSide::Sell,
+            Some(last) => last.side,
+            None => OrderSide::Buy,
+        };
+        self.last_trades.insert(tick.instrument_token, LastTrade { volume_traded: tick.volume_traded, price: tick.last_price, side });
+
+        // The first tick only establishes the baseline
+        let previous = previous?;
+        if tick.volume_traded <= previous.volume_traded || tick.last_quantity == 0 {
+            return None;
+        }
+        Some(Trade {
+            id: format!("{}-{}", tick.instrument_token, tick.volume_traded),
+            symbol: symbol.to_string(),
+            price: tick.last_price,
+            quantity: quantity(tick.last_quantity),
+            side,
+            timestamp: if tick.last_trade_time > 0 { tick.last_trade_time } else { tick.exchange_timestamp },
+            is_buyer_maker: side == OrderSide::Sell,
+        })
+    }
+}
+
+/// Share and lot counts as `Fixed`, clamped to its range
+fn quantity(value: u64) -> Fixed {
+    i64::try_from(value).ok().and_then(|v| Fixed::from_i64(v).ok()).unwrap_or_else(Fixed::max)
+}
+
+fn ticker_from_tick(symbol: &str, tick: &BrokerTick) -> Ticker {
+    let price_change = if tick.close > Fixed::ZERO { tick.last_price - tick.close } else { Fixed::ZERO };
+    let price_change_percent = if tick.close > Fixed::ZERO {
+        Fixed::from_f64(price_change.to_f64_lossy() / tick.close.to_f64_lossy() * 100.0)
+            .map(|p| p.round_dp(2))
+            .unwrap_or(Fixed::ZERO)
+    } else {
+        Fixed::ZERO
+    };
+    Ticker {
+        symbol: symbol.to_string(),
+        price: tick.last_price,
+        price_change,
+        price_change_percent,
+        high: tick.high,
+        low: tick.low,
+        volume: quantity(tick.volume_traded),
+        quote_volume: Fixed::ZERO,
+        timestamp: tick.exchange_timestamp,
+    }
+}
+
+fn book_from_tick(symbol: &str, tick: &BrokerTick) -> OrderBook {
+    let levels = |side: &[DepthLevel]| {
+        side.iter()
+            .filter(|l| l.quantity > 0)
+            .map(|l| OrderBookLevel { price: l.price, quantity: quantity(l.quantity) })
+            .collect()
+    };
+    OrderBook {
+        symbol: symbol.to_string(),
+        bids: levels(&tick.bids),
+        asks: levels(&tick.asks),
+        timestamp: tick.exchange_timestamp,
+        update_id: tick.exchange_timestamp,
+    }
+}
+
+#[async_trait(?Send)]
+impl<F: BrokerFeed> StreamingExchange for NseExchange<F> {
+    async fn connect(&mut self) -> Result<()> {
+        if !self.feed.is_connected() {
+            self.feed.connect().await?;
+            info!("✅ Connected to {} feed", self.feed.name());
+        }
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        self.pending_events.clear();
+        self.last_trades.clear();
+        self.feed.disconnect().await
+    }
+
+    async fn subscribe_ticker(&mut self, symbol: &str) -> Result<()> {
+        self.subscribe(symbol, StreamKind::Ticker).await
+    }
+
+    async fn subscribe_trades(&mut self, symbol: &str) -> Result<()> {
+        self.subscribe(symbol, StreamKind::Trades).await
+    }
+
+    /// Broker feeds publish a fixed depth (5 levels on Kite), so `levels` is ignored
+    async fn subscribe_order_book(&mut self, symbol: &str, _levels: Option<u32>) -> Result<()> {
+        self.subscribe(symbol, StreamKind::Depth).await
+    }
+
+    async fn subscribe_klines(&mut self, _symbol: &str, _interval: &str) -> Result<()> {
+        Err(ExchangeError::FeatureNotSupported("broker feeds don't stream bars; aggregate trades locally".to_string()))
+    }
+
+    async fn unsubscribe(&mut self, stream: &str) -> Result<()> {
+        let Some((token, kind)) = self.subscriptions.remove(stream) else {
+            return Ok(());
+        };
+        match self.required_mode(token) {
+            None => {
+                self.feed.unsubscribe(&[token]).await?;
+                self.last_trades.remove(&token);
+            }
+            Some(mode) if mode < kind.mode() => self.feed.subscribe(&[token], mode).await?,
+            Some(_) => {}
+        }
+        info!("❌ Unsubscribed from stream: {}", stream);
+        Ok(())
+    }
+
+    async fn next_event(&mut self) -> Result<Option<MarketData>> {
+        loop {
+            if let Some(event) = self.pending_events.pop_front() {
+                return Ok(Some(event));
+            }
+            let Some(tick) = self.feed.next_tick().await? else {
+                return Ok(None);
+            };
+            let events = self.events_from_tick(&tick);
+            self.pending_events.extend(events);
+        }
+    }
+
+    fn connection_status(&self) -> ConnectionStatus {
+        if self.feed.is_connected() { ConnectionStatus::Connected } else { ConnectionStatus::Disconnected }
+    }
+
+    fn subscriptions(&self) -> Vec<Subscription> {
+        self.subscriptions.keys()
+            .map(|stream| Subscription {
+                stream: stream.clone(),
+                symbol: stream.rsplit_once('@').map(|(symbol, _)| symbol).unwrap_or_default().to_string(),
+                status: SubscriptionStatus::Subscribed,
+                last_update: 0,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testkit::fx;
+
+    /// Feed replaying canned ticks and recording mode changes
+    struct MockFeed {
+        ticks: VecDeque<BrokerTick>,
+        modes: HashMap<u32, FeedMode>,
+        connected: bool,
+    }
+
+    #[async_trait(?Send)]
+    impl BrokerFeed for MockFeed {
+        fn name(&self) -> &str {
+            "mock"
+        }
+
+        async fn connect(&mut self) -> Result<()> {
+            self.connected = true;
+            Ok(())
+        }
+
+        async fn disconnect(&mut self) -> Result<()> {
+            self.connected = false;
+            Ok(())
+        }
+
+        async fn subscribe(&mut self, tokens: &[u32], mode: FeedMode) -> Result<()> {
+            for token in tokens {
+                self.modes.insert(*token, mode);
+            }
+            Ok(())
+        }
+
+        async fn unsubscribe(&mut self, tokens: &[u32]) -> Result<()> {
+            for token in tokens {
+                self.modes.remove(token);
+            }
+            Ok(())
+        }
+
+        async fn next_tick(&mut self) -> Result<Option<BrokerTick>> {
+            Ok(self.ticks.pop_front())
+        }
+
+        fn is_connected(&self) -> bool {
+            self.connected
+        }
+    }
+
+    fn tick(price: &str, volume_traded: u64) -> BrokerTick {
+        BrokerTick {
+            instrument_token: 738561,
+            last_price: fx(price),
+            last_quantity: 10,
+            volume_traded,
+            open: fx("2900"),
+            high: fx("2950"),
+            low: fx("2890"),
+            close: fx("2900"),
+            exchange_timestamp: 1_700_000_000_000,
+            last_trade_time: 0,
+            bids: vec![DepthLevel { price: fx("2919.95"), quantity: 40, orders: 2 }],
+            asks: vec![DepthLevel { price: fx("2920.05"), quantity: 25, orders: 1 }],
+        }
+    }
+
+    fn exchange(ticks: Vec<BrokerTick>) -> NseExchange<MockFeed> {
+        let feed = MockFeed { ticks: ticks.into(), modes: HashMap::new(), connected: false };
+        NseExchange::new(feed).with_instruments([Instrument {
+            symbol: "NSE:RELIANCE".to_string(),
+            instrument_token: 738561,
+            exchange: IndianExchange::Nse,
+            segment: Segment::Equity,
+            tick_size: fx("0.05"),
+            lot_size: 1,
+        }])
+    }
+
+    #[monoio::test]
+    async fn test_ticks_map_to_market_data() {
+        let mut nse = exchange(vec![tick("2920", 1000), tick("2919", 1010), tick("2919", 1010)]);
+        nse.connect().await.unwrap();
+        nse.subscribe_ticker("NSE:RELIANCE").await.unwrap();
+        nse.subscribe_trades("NSE:RELIANCE").await.unwrap();
+        assert!(nse.subscribe_ticker("NSE:TCS").await.is_err());
+
+        let Some(MarketData::Ticker(ticker)) = nse.next_event().await.unwrap() else { panic!("expected ticker") };
+        assert_eq!(ticker.price_change, fx("20"));
+        assert_eq!(ticker.price_change_percent, fx("0.69"));
+
+        // Volume advanced on a downtick: a sell print
+        assert!(matches!(nse.next_event().await.unwrap(), Some(MarketData::Ticker(_))));
+        let Some(MarketData::Trade(trade)) = nse.next_event().await.unwrap() else { panic!("expected trade") };
+        assert_eq!(trade.side, OrderSide::Sell);
+        assert_eq!(trade.quantity, fx("10"));
+
+        // Unchanged volume: ticker only, then the feed ends
+        assert!(matches!(nse.next_event().await.unwrap(), Some(MarketData::Ticker(_))));
+        assert!(nse.next_event().await.unwrap().is_none());
+    }
+
+    #[monoio::test]
+    async fn test_feed_mode_follows_subscriptions() {
+        let mut nse = exchange(Vec::new());
+        nse.subscribe_ticker("NSE:RELIANCE").await.unwrap();
+        assert_eq!(nse.feed().modes[&738561], FeedMode::Quote);
+        nse.subscribe_order_book("NSE:RELIANCE", Some(5)).await.unwrap();
+        assert_eq!(nse.feed().modes[&738561], FeedMode::Full);
+
+        nse.unsubscribe("NSE:RELIANCE@depth").await.unwrap();
+        assert_eq!(nse.feed().modes[&738561], FeedMode::Quote);
+        nse.unsubscribe("NSE:RELIANCE@ticker").await.unwrap();
+        assert!(nse.feed().modes.is_empty());
+        assert!(nse.subscriptions().is_empty());
+    }
+}
@@ -0,0 +1,1306 @@
+//! Order management
+//!
+//! `OrderManager` wraps a `TradingExchange` with the order lifecycle logic
+//! strategies shouldn't repeat. It assigns client order ids, tracks each
+//! order's state machine (New → PartiallyFilled → Filled/Canceled/...) from
+//! REST responses and user-stream execution reports, drops out-of-order
+//! updates, and on `reconcile` flags orders the exchange reports but we never
+//! placed (unknown) and orders we think are working but the exchange doesn't
+//! list (orphaned).
+//!
+//! Cancel-all is verified rather than
+//! fire-and-forget: after the bulk cancel, open orders are polled until the
+//! symbol is flat or the deadline passes, stragglers are canceled one by one,
+//! and anything still working afterwards is raised as a critical incident.
+//! Use it for `RunnerAction::CancelAll`, kill switches and flattening.
+//!
+//! When the venue changes a symbol's tick or step size,
+//! `on_instrument_changed` re-quotes working orders that no longer sit on the
+//! grid and cancels ones the new filters rule out.
+//!
+//! With a `RiskEngine` attached, `place_order` clips protected prices and
+//! runs its pre-trade checks before anything is tracked or sent, and `kill_switch` engages it and
+//! verifies a cancel-all on every symbol with open orders.
+//!
+//! `IntentQueue` holds orders between the strategy and the wire. When
+//! connectivity drops, a `DisconnectPolicy` decides what happens to intents
+//! that were queued or in flight, and every decision is reported as an
+//! `IntentEvent`. In-flight intents always wait for reconciliation, since the
+//! exchange may have accepted them before the connection went away.
+
+use crate::binance::exchange::{parse_order_type, parse_status};
+use crate::binance::filters::InstrumentChanged;
+use crate::binance::{OrderUpdateEvent, TradeSide};
+use crate::client_id::ClientIdPolicy;
+use crate::errors::{ExchangeError, Result};
+use crate::incidents::{Incident, IncidentBus, IncidentKind, Severity};
+use crate::latency::{LatencyTrace, TickToTrade};
+use crate::risk::RiskEngine;
+use crate::traits::TradingExchange;
+use crate::types::*;
+use sriquant_core::prelude::*;
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// How hard to verify a cancel-all
+#[derive(Debug, Clone)]
+pub struct CancelVerifyConfig {
+    /// Give up polling for an empty book after this long
+    pub deadline: Duration,
+    pub poll_interval: Duration,
+    /// Cancel stragglers individually once the deadline passes
+    pub escalate: bool,
+}
+
+impl Default for CancelVerifyConfig {
+    fn default() -> Self {
+        Self {
+            deadline: Duration::from_secs(5),
+            poll_interval: Duration::from_millis(250),
+            escalate: true,
+        }
+    }
+}
+
+impl CancelVerifyConfig {
+    pub fn with_deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    pub fn with_escalation(mut self, escalate: bool) -> Self {
+        self.escalate = escalate;
+        self
+    }
+}
+
+/// Outcome of a verified cancel-all
+#[derive(Debug, Clone)]
+pub struct CancelAllReport {
+    pub symbol: String,
+    /// Orders the bulk cancel reported as canceled
+    pub bulk_canceled: usize,
+    /// `openOrders` queries made while waiting
+    pub polls: u32,
+    /// Order ids canceled individually after the deadline
+    pub escalated: Vec<String>,
+    /// Orders still open (or unverifiable) at the end
+    pub remaining: Vec<OrderResponse>,
+    /// Whether `remaining` comes from a successful `openOrders` query
+    pub verified: bool,
+    pub elapsed_ms: u64,
+}
+
+impl CancelAllReport {
+    /// The symbol was confirmed to have no working orders
+    pub fn is_flat(&self) -> bool {
+        self.verified && self.remaining.is_empty()
+    }
+}
+
+/// An order as the manager knows it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ManagedOrder {
+    pub client_order_id: String,
+    /// Assigned by the exchange on ack
+    pub order_id: Option<String>,
+    pub symbol: String,
+    pub side: OrderSide,
+    pub order_type: OrderType,
+    pub quantity: Fixed,
+    pub price: Option<Fixed>,
+    pub status: OrderStatus,
+    pub filled_quantity: Fixed,
+    pub average_price: Option<Fixed>,
+    /// `false` until the exchange has confirmed the order exists
+    pub acknowledged: bool,
+    /// Exchange time of the last applied update, in milliseconds
+    pub updated_at: u64,
+}
+
+impl ManagedOrder {
+    pub fn is_open(&self) -> bool {
+        matches!(self.status, OrderStatus::New | OrderStatus::PartiallyFilled)
+    }
+}
+
+/// Whether an order may move from `from` to `to`
+pub fn is_valid_transition(from: OrderStatus, to: OrderStatus) -> bool {
+    use OrderStatus::*;
+    match from {
+        New => to != New,
+        PartiallyFilled => !matches!(to, New | Rejected),
+        Filled | Canceled | Rejected | Expired => false,
+    }
+}
+
+/// What applying an update did
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateOutcome {
+    Applied(OrderStatus),
+    /// Older than what we already have (late or duplicate delivery)
+    Stale,
+    /// Not an order we placed
+    Unknown,
+}
+
+/// Differences found between local and exchange state
+#[derive(Debug, Clone, Default)]
+pub struct ReconcileReport {
+    /// Open orders updated from the exchange
+    pub updated: usize,
+    /// Client ids of exchange orders we never placed
+    pub unknown: Vec<String>,
+    /// Client ids we think are working that the exchange can't account for
+    pub orphaned: Vec<String>,
+}
+
+/// Order lifecycle manager over a trading venue
+pub struct OrderManager<E: TradingExchange> {
+    exchange: E,
+    cancel_config: CancelVerifyConfig,
+    incidents: Option<IncidentBus>,
+    risk: Option<RiskEngine>,
+    latency: Option<TickToTrade>,
+    client_ids: ClientIdPolicy,
+    orders: HashMap<String, ManagedOrder>,
+    unknown: HashMap<String, ManagedOrder>,
+}
+
+/// The request may or may not have reached the matching engine
+fn outcome_unknown(error: &ExchangeError) -> bool {
+    match error.root() {
+        ExchangeError::NetworkError(_) | ExchangeError::Timeout(_) | ExchangeError::ConnectionFailed(_) => true,
+        ExchangeError::BinanceApi(_, api) => matches!(api, crate::binance::BinanceApiError::Internal(_)),
+        _ => false,
+    }
+}
+
+/// Average fill price from cumulative quote and quantity
+fn average_fill(cumulative_quote: Fixed, filled: Fixed) -> Option<Fixed> {
+    if filled <= Fixed::ZERO {
+        return None;
+    }
+    cumulative_quote.checked_div(filled).ok().map(|p| p.round_dp(8))
+}
+
+impl<E: TradingExchange> OrderManager<E> {
+    pub fn new(exchange: E) -> Self {
+        Self {
+            client_ids: ClientIdPolicy::for_venue(exchange.name()),
+            exchange,
+            cancel_config: CancelVerifyConfig::default(),
+            incidents: None,
+            risk: None,
+            latency: None,
+            orders: HashMap::new(),
+            unknown: HashMap::new(),
+        }
+    }
+
+    pub fn with_cancel_config(mut self, config: CancelVerifyConfig) -> Self {
+        self.cancel_config = config;
+        self
+    }
+
+    /// Publish unverified cancels as incidents
+    pub fn with_incidents(mut self, bus: IncidentBus) -> Self {
+        self.incidents = Some(bus);
+        self
+    }
+
+    /// Run pre-trade checks on every `place_order`
+    pub fn with_risk_engine(mut self, engine: RiskEngine) -> Self {
+        self.risk = Some(engine);
+        self
+    }
+
+    /// Record tick-to-trade latency of orders placed with `place_order_traced`
+    pub fn with_latency_tracer(mut self, tracer: TickToTrade) -> Self {
+        self.latency = Some(tracer);
+        self
+    }
+
+    /// Override the client order id rules picked from the exchange name
+    pub fn with_client_id_policy(mut self, policy: ClientIdPolicy) -> Self {
+        self.client_ids = policy;
+        self
+    }
+
+    pub fn client_id_policy(&self) -> &ClientIdPolicy {
+        &self.client_ids
+    }
+
+    pub fn latency_tracer(&self) -> Option<&TickToTrade> {
+        self.latency.as_ref()
+    }
+
+    pub fn exchange(&self) -> &E {
+        &self.exchange
+    }
+
+    pub fn risk_engine(&self) -> Option<&RiskEngine> {
+        self.risk.as_ref()
+    }
+
+    /// For feeding last trades and resetting the kill switch
+    pub fn risk_engine_mut(&mut self) -> Option<&mut RiskEngine> {
+        self.risk.as_mut()
+    }
+
+    /// Submit an order under a client order id, tracking it from before the request goes out
+    ///
+    /// If the request fails in a way that leaves its fate unknown (timeout,
+    /// network, venue internal error) the order stays unacknowledged until
+    /// `reconcile` resolves it; other errors mark it rejected. Orders the
+    /// risk engine refuses are never tracked, nor are caller-supplied ids the
+    /// venue's `ClientIdPolicy` rejects or still holds.
+    pub async fn place_order(&mut self, mut request: OrderRequest) -> Result<ManagedOrder> {
+        if let Some(risk) = &self.risk {
+            if let Some(price) = risk.protect_price(&request)? {
+                request.price = Some(price);
+            }
+            risk.check(&request, self.open_orders(Some(&request.symbol)).len())?;
+        }
+        let client_order_id = self.claim_client_order_id(&mut request)?;
+        self.orders.insert(client_order_id.clone(), ManagedOrder {
+            client_order_id: client_order_id.clone(),
+            order_id: None,
+            symbol: request.symbol.clone(),
+            side: request.side,
+            order_type: request.order_type,
+            quantity: request.quantity,
+            price: request.price,
+            status: OrderStatus::New,
+            filled_quantity: Fixed::ZERO,
+            average_price: None,
+            acknowledged: false,
+            updated_at: 0,
+        });
+
+        match self.exchange.place_order(request).await {
+            Ok(response) => {
+                self.apply_response(&response);
+                Ok(self.orders[&client_order_id].clone())
+            }
+            Err(e) => {
+                if outcome_unknown(&e) {
+                    warn!("❓ Order {} outcome unknown, reconcile required: {}", client_order_id, e);
+                } else if let Some(order) = self.orders.get_mut(&client_order_id) {
+                    order.status = OrderStatus::Rejected;
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// The request's client order id, generated if missing, once the venue's policy allows it
+    fn claim_client_order_id(&self, request: &mut OrderRequest) -> Result<String> {
+        let Some(id) = &request.client_order_id else {
+            return Ok(request.client_order_id.insert(self.client_ids.generate()).clone());
+        };
+        self.client_ids.validate(id)?;
+        if self.orders.get(id).is_some_and(|order| !self.client_ids.reusable(order.is_open())) {
+            return Err(ExchangeError::InvalidOrder(format!("client order id `{id}` was already used on {}", self.client_ids.venue())));
+        }
+        Ok(id.clone())
+    }
+
+    /// `place_order` carrying the trace of the market data that triggered it
+    ///
+    /// The trace is marked sent here and finished by the order's first
+    /// execution report in `apply_update`.
+    pub async fn place_order_traced(&mut self, mut request: OrderRequest, trace: LatencyTrace) -> Result<ManagedOrder> {
+        // Checked before the trace is filed, so a reused id can't replace a live order's trace
+        let client_order_id = self.claim_client_order_id(&mut request)?;
+        if let Some(tracer) = &mut self.latency {
+            tracer.submit(&client_order_id, trace);
+        }
+        let result = self.place_order(request).await;
+        if let Some(tracer) = &mut self.latency
+            && self.orders.get(&client_order_id).is_none_or(|o| o.status == OrderStatus::Rejected)
+        {
+            tracer.discard(&client_order_id);
+        }
+        result
+    }
+
+    /// Cancel a tracked order by client order id
+    pub async fn cancel_order(&mut self, client_order_id: &str) -> Result<ManagedOrder> {
+        let order = self.orders.get(client_order_id).ok_or_else(|| ExchangeError::OrderNotFound(client_order_id.to_string()))?;
+        let order_id = order.order_id.clone().ok_or_else(|| ExchangeError::OrderNotFound(format!("{client_order_id} not acknowledged yet")))?;
+        let response = self.exchange.cancel_order(&order.symbol.clone(), &order_id).await?;
+        self.apply_response(&response);
+        Ok(self.orders[client_order_id].clone())
+    }
+
+    /// Apply a REST order response
+    pub fn apply_response(&mut self, response: &OrderResponse) -> UpdateOutcome {
+        self.apply(&response.client_order_id, &response.order_id, response.status, response.filled_quantity, response.average_price, response.update_time)
+    }
+
+    /// Apply a user-stream execution report
+    ///
+    /// Unknown orders are remembered and listed by `unknown_orders`.
+    pub fn apply_update(&mut self, event: &OrderUpdateEvent) -> UpdateOutcome {
+        // Cancels report the cancel request's id in `c` and the order's in `C`
+        let client_order_id = if event.original_client_order_id.is_empty() { &event.client_order_id } else { &event.original_client_order_id };
+        let Ok(status) = parse_status(&event.order_status) else {
+            warn!("⚠️  Unrecognised status {} for {}", event.order_status, client_order_id);
+            return UpdateOutcome::Stale;
+        };
+        let average_price = average_fill(event.cumulative_quote_asset_transacted_quantity, event.cumulative_filled_quantity);
+        let order_id = event.order_id.to_string();
+        let outcome = self.apply(client_order_id, &order_id, status, event.cumulative_filled_quantity, average_price, event.transaction_time);
+        if let Some(tracer) = &mut self.latency {
+            tracer.on_execution(client_order_id, nanos());
+        }
+
+        if outcome == UpdateOutcome::Unknown {
+            warn!("👻 Execution report for unknown order {} on {}", client_order_id, event.symbol);
+            let price = event.order_price;
+            self.unknown.insert(client_order_id.clone(), ManagedOrder {
+                client_order_id: client_order_id.clone(),
+                order_id: Some(order_id),
+                symbol: event.symbol.clone(),
+                side: match event.side {
+                    TradeSide::Buy => OrderSide::Buy,
+                    TradeSide::Sell => OrderSide::Sell,
+                },
+                order_type: parse_order_type(&event.order_type).unwrap_or(OrderType::Limit),
+                quantity: event.order_quantity,
+                price: (price > Fixed::ZERO).then_some(price),
+                status,
+                filled_quantity: event.cumulative_filled_quantity,
+                average_price,
+                acknowledged: true,
+                updated_at: event.transaction_time,
+            });
+        }
+        outcome
+    }
+
+    fn apply(&mut self, client_order_id: &str, order_id: &str, status: OrderStatus, filled: Fixed, average_price: Option<Fixed>, updated_at: u64) -> UpdateOutcome {
+        let Some(order) = self.orders.get_mut(client_order_id) else {
+            return UpdateOutcome::Unknown;
+        };
+        let advances = status != order.status && is_valid_transition(order.status, status);
+        let more_filled = status == order.status && filled > order.filled_quantity;
+        if order.acknowledged && !advances && !more_filled {
+            if status != order.status {
+                debug!("⏮️  Ignoring {} -> {} for {}", order.status, status, client_order_id);
+            }
+            return UpdateOutcome::Stale;
+        }
+        if filled < order.filled_quantity {
+            return UpdateOutcome::Stale;
+        }
+
+        if let Some(risk) = &mut self.risk
+            && filled > order.filled_quantity
+        {
+            risk.on_fill(&order.symbol, order.side, filled - order.filled_quantity);
+        }
+        order.order_id = Some(order_id.to_string());
+        order.acknowledged = true;
+        order.status = status;
+        order.filled_quantity = filled;
+        if average_price.is_some() {
+            order.average_price = average_price;
+        }
+        order.updated_at = order.updated_at.max(updated_at);
+        UpdateOutcome::Applied(status)
+    }
+
+    /// Compare tracked orders on `symbol` with the exchange's open orders
+    ///
+    /// Tracked orders missing from `openOrders` are looked up individually to
+    /// learn how they ended; those that still can't be accounted for are
+    /// reported as orphaned.
+    pub async fn reconcile(&mut self, symbol: &str) -> Result<ReconcileReport> {
+        let open = self.exchange.open_orders(Some(symbol)).await?;
+        let mut report = ReconcileReport::default();
+        let listed: HashSet<&str> = open.iter().map(|o| o.client_order_id.as_str()).collect();
+
+        let missing: Vec<(String, Option<String>)> = self.orders.values()
+            .filter(|o| o.symbol == symbol && o.is_open() && !listed.contains(o.client_order_id.as_str()))
+            .map(|o| (o.client_order_id.clone(), o.order_id.clone()))
+            .collect();
+
+        for response in &open {
+            if self.orders.contains_key(&response.client_order_id) {
+                if let UpdateOutcome::Applied(_) = self.apply_response(response) {
+                    report.updated += 1;
+                }
+            } else if !self.unknown.contains_key(&response.client_order_id) {
+                warn!("👻 Unknown open order {} on {}", response.client_order_id, symbol);
+                report.unknown.push(response.client_order_id.clone());
+                self.unknown.insert(response.client_order_id.clone(), ManagedOrder {
+                    client_order_id: response.client_order_id.clone(),
+                    order_id: Some(response.order_id.clone()),
+                    symbol: response.symbol.clone(),
+                    side: response.side,
+                    order_type: response.order_type,
+                    quantity: response.quantity,
+                    price: response.price,
+                    status: response.status,
+                    filled_quantity: response.filled_quantity,
+                    average_price: response.average_price,
+                    acknowledged: true,
+                    updated_at: response.update_time,
+                });
+            }
+        }
+
+        for (client_order_id, order_id) in missing {
+            let resolved = match order_id {
+                Some(order_id) => match self.exchange.get_order(symbol, &order_id).await {
+                    Ok(response) => {
+                        self.apply_response(&response);
+                        !self.orders[&client_order_id].is_open()
+                    }
+                    Err(e) => {
+                        debug!("Lookup of {} failed: {}", client_order_id, e);
+                        false
+                    }
+                },
+                None => false,
+            };
+            if !resolved {
+                warn!("🧭 Orphaned order {} on {}", client_order_id, symbol);
+                report.orphaned.push(client_order_id);
+            }
+        }
+
+        info!("🔄 Reconciled {}: {} updated, {} unknown, {} orphaned",
+              symbol, report.updated, report.unknown.len(), report.orphaned.len());
+        Ok(report)
+    }
+
+    pub fn order(&self, client_order_id: &str) -> Option<&ManagedOrder> {
+        self.orders.get(client_order_id)
+    }
+
+    pub fn orders_for_symbol(&self, symbol: &str) -> Vec<&ManagedOrder> {
+        self.orders.values().filter(|o| o.symbol == symbol).collect()
+    }
+
+    pub fn orders_with_status(&self, status: OrderStatus) -> Vec<&ManagedOrder> {
+        self.orders.values().filter(|o| o.status == status).collect()
+    }
+
+    /// Working orders, optionally for one symbol
+    pub fn open_orders(&self, symbol: Option<&str>) -> Vec<&ManagedOrder> {
+        self.orders.values()
+            .filter(|o| o.is_open() && symbol.is_none_or(|s| o.symbol == s))
+            .collect()
+    }
+
+    /// Orders seen at the exchange that this manager didn't place
+    pub fn unknown_orders(&self) -> impl Iterator<Item = &ManagedOrder> {
+        self.unknown.values()
+    }
+
+    /// Stop tracking finished orders
+    pub fn prune_closed(&mut self) -> usize {
+        let before = self.orders.len();
+        self.orders.retain(|_, o| o.is_open());
+        before - self.orders.len()
+    }
+
+    /// Cancel every order on `symbol` and confirm none are left working
+    ///
+    /// A failed bulk cancel or poll doesn't abort: the loop keeps checking
+    /// until the deadline, then escalates. Only the final state matters.
+    pub async fn cancel_all_verified(&self, symbol: &str) -> Result<CancelAllReport> {
+        let timer = PerfTimer::start(format!("cancel_all_verified {symbol}"));
+        let config = &self.cancel_config;
+
+        let bulk_canceled = match self.exchange.cancel_all_orders(symbol).await {
+            Ok(canceled) => canceled.len(),
+            Err(e) => {
+                warn!("⚠️  Bulk cancel for {} failed: {}", symbol, e);
+                0
+            }
+        };
+
+        let mut polls = 0;
+        let mut verified = false;
+        let mut remaining = Vec::new();
+        loop {
+            polls += 1;
+            match self.exchange.open_orders(Some(symbol)).await {
+                Ok(open) if open.is_empty() => {
+                    verified = true;
+                    remaining.clear();
+                    break;
+                }
+                Ok(open) => remaining = open,
+                Err(e) => warn!("⚠️  openOrders poll {} for {} failed: {}", polls, symbol, e),
+            }
+            if timer.elapsed_millis() as u128 >= config.deadline.as_millis() {
+                break;
+            }
+            monoio::time::sleep(config.poll_interval).await;
+        }
+
+        let mut escalated = Vec::new();
+        if !verified && config.escalate && !remaining.is_empty() {
+            warn!("🧹 {} orders on {} survived cancel-all, canceling individually", remaining.len(), symbol);
+            for order in &remaining {
+                match self.exchange.cancel_order(symbol, &order.order_id).await {
+                    Ok(_) => escalated.push(order.order_id.clone()),
+                    Err(e) => warn!("⚠️  Cancel of straggler {} failed: {}", order.order_id, e),
+                }
+            }
+            polls += 1;
+            match self.exchange.open_orders(Some(symbol)).await {
+                Ok(open) => {
+                    verified = true;
+                    remaining = open;
+                }
+                Err(e) => warn!("⚠️  Final openOrders check for {} failed: {}", symbol, e),
+            }
+        }
+
+        let report = CancelAllReport {
+            symbol: symbol.to_string(),
+            bulk_canceled,
+            polls,
+            escalated,
+            remaining,
+            verified,
+            elapsed_ms: timer.elapsed_millis(),
+        };
+        timer.log_elapsed();
+
+        if report.is_flat() {
+            info!("✅ {} flat after cancel-all ({} bulk, {} escalated, {} polls)",
+                  symbol, report.bulk_canceled, report.escalated.len(), report.polls);
+        } else {
+            self.alert_unverified(&report);
+        }
+        Ok(report)
+    }
+
+    /// Engage the risk engine's kill switch and cancel everything open
+    ///
+    /// Covers symbols with tracked open orders plus any in `symbols`, e.g.
+    /// ones traded by another process. Without a risk engine only the
+    /// cancels run. Orders gone from the book are looked up (see
+    /// `settle_closed`) rather than assumed canceled.
+    pub async fn kill_switch(&mut self, reason: &str, symbols: &[&str]) -> Result<Vec<CancelAllReport>> {
+        if let Some(risk) = &mut self.risk {
+            risk.kill(reason);
+        }
+        let mut targets: Vec<String> = self.open_orders(None).iter().map(|o| o.symbol.clone()).collect();
+        targets.extend(symbols.iter().map(|s| s.to_string()));
+        targets.sort_unstable();
+        targets.dedup();
+
+        let mut reports = Vec::with_capacity(targets.len());
+        for symbol in &targets {
+            let report = self.cancel_all_verified(symbol).await?;
+            if report.verified {
+                self.settle_closed(symbol, &report.remaining).await;
+            }
+            reports.push(report);
+        }
+        Ok(reports)
+    }
+
+    /// Look up how tracked orders that left the book during a cancel-all ended
+    ///
+    /// Some may have filled while the cancel raced them, so their final state
+    /// comes from the exchange rather than being assumed canceled. Orders that
+    /// were never acknowledged or can't be looked up stay open for the user
+    /// stream or `reconcile` to settle.
+    async fn settle_closed(&mut self, symbol: &str, remaining: &[OrderResponse]) {
+        let still_open: HashSet<&str> = remaining.iter().map(|o| o.order_id.as_str()).collect();
+        let closed: Vec<(String, String)> = self.orders.values()
+            .filter(|o| o.symbol == symbol && o.is_open())
+            .filter_map(|o| Some((o.client_order_id.clone(), o.order_id.clone()?)))
+            .filter(|(_, order_id)| !still_open.contains(order_id.as_str()))
+            .collect();
+        for (client_order_id, order_id) in closed {
+            match self.exchange.get_order(symbol, &order_id).await {
+                Ok(response) => {
+                    self.apply_response(&response);
+                }
+                Err(e) => debug!("Lookup of {} after cancel-all failed: {}", client_order_id, e),
+            }
+        }
+    }
+
+    /// Revalidate open orders on a symbol whose trading rules changed
+    ///
+    /// Orders still valid are left alone. Ones off the new tick/step grid are
+    /// canceled and re-placed for their remaining quantity at the snapped
+    /// price; ones no longer placeable at all are only canceled. Returns the
+    /// replacement orders.
+    pub async fn on_instrument_changed(&mut self, change: &InstrumentChanged) -> Result<Vec<ManagedOrder>> {
+        let open: Vec<ManagedOrder> = self.open_orders(Some(&change.symbol)).into_iter().cloned().collect();
+        let mut replaced = Vec::new();
+        let mut canceled = 0;
+        for order in open.iter().filter(|o| o.acknowledged) {
+            let remaining = order.quantity - order.filled_quantity;
+            let requote = match change.requote(order.price, remaining) {
+                Ok(None) => continue,
+                Ok(Some(requote)) => Some(requote),
+                Err(e) => {
+                    warn!("📐 {} no longer valid under new rules: {}", order.client_order_id, e);
+                    None
+                }
+            };
+            self.cancel_order(&order.client_order_id).await?;
+            canceled += 1;
+            if let Some((price, quantity)) = requote {
+                let request = OrderRequest {
+                    symbol: order.symbol.clone(),
+                    side: order.side,
+                    order_type: order.order_type,
+                    quantity,
+                    price,
+                    stop_price: None,
+                    time_in_force: None,
+                    client_order_id: None,
+                };
+                replaced.push(self.place_order(request).await?);
+            }
+        }
+
+        let message = format!(
+            "trading rules changed; {} of {} open orders canceled, {} re-quoted",
+            canceled, open.len(), replaced.len()
+        );
+        if let Some(bus) = &self.incidents {
+            bus.publish(
+                Incident::new(Severity::Warning, "oms", IncidentKind::InstrumentChanged, message)
+                    .with_symbol(&change.symbol)
+                    .with_context("tick_changed", change.tick_changed())
+                    .with_context("step_changed", change.step_changed()),
+            );
+        } else {
+            info!("📐 {}: {}", change.symbol, message);
+        }
+        Ok(replaced)
+    }
+
+    fn alert_unverified(&self, report: &CancelAllReport) {
+        let message = if report.verified {
+            format!("{} orders still working after cancel-all", report.remaining.len())
+        } else {
+            "could not confirm cancel-all".to_string()
+        };
+        if let Some(bus) = &self.incidents {
+            let order_ids: Vec<&str> = report.remaining.iter().map(|o| o.order_id.as_str()).collect();
+            bus.publish(
+                Incident::new(Severity::Critical, "oms", IncidentKind::CancelUnverified, message)
+                    .with_symbol(&report.symbol)
+                    .with_context("remaining", order_ids.join(","))
+                    .with_context("elapsed_ms", report.elapsed_ms),
+            );
+        } else {
+            warn!("🚨 {}: {}", report.symbol, message);
+        }
+    }
+}
+
+/// What to do with pending intents when the order connection drops
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DisconnectPolicy {
+    /// Discard queued intents; the strategy re-decides on fresh data
+    Drop,
+    /// Hold queued intents and resubmit them once state is reconciled
+    ParkAndResubmit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntentState {
+    Queued,
+    /// Sent, no ack yet
+    InFlight,
+    /// Waiting for reconciliation after a disconnect
+    Parked { was_in_flight: bool },
+}
+
+/// An order waiting to be sent or acknowledged
+#[derive(Debug, Clone)]
+pub struct PendingIntent {
+    /// Client order id, assigned on enqueue if the request had none
+    pub key: String,
+    pub request: OrderRequest,
+    pub queued_at: u64,
+    pub state: IntentState,
+}
+
+/// A decision taken about a pending intent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntentEvent {
+    /// Discarded by the drop policy (or for being parked too long)
+    Dropped { key: String, symbol: String, reason: String },
+    Parked { key: String, symbol: String, was_in_flight: bool },
+    /// Back in the queue after reconciliation
+    Requeued { key: String, symbol: String },
+    /// Reconciliation found the order at the exchange; it won't be resent
+    Landed { key: String, symbol: String },
+}
+
+fn dropped(intent: &PendingIntent, reason: &str) -> IntentEvent {
+    warn!("🗑️  Dropping {} intent {} ({})", intent.request.symbol, intent.key, reason);
+    IntentEvent::Dropped { key: intent.key.clone(), symbol: intent.request.symbol.clone(), reason: reason.to_string() }
+}
+
+/// Orders between the strategy and the wire
+#[derive(Debug)]
+pub struct IntentQueue {
+    policy: DisconnectPolicy,
+    max_park_nanos: Option<u64>,
+    intents: VecDeque<PendingIntent>,
+    connected: bool,
+    next_id: u64,
+}
+
+impl IntentQueue {
+    pub fn new(policy: DisconnectPolicy) -> Self {
+        Self {
+            policy,
+            max_park_nanos: None,
+            intents: VecDeque::new(),
+            connected: true,
+            next_id: 1,
+        }
+    }
+
+    /// Drop parked intents older than this at reconciliation instead of resubmitting
+    pub fn with_max_park_nanos(mut self, nanos: u64) -> Self {
+        self.max_park_nanos = Some(nanos);
+        self
+    }
+
+    pub fn policy(&self) -> DisconnectPolicy {
+        self.policy
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    pub fn len(&self) -> usize {
+        self.intents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intents.is_empty()
+    }
+
+    pub fn intents(&self) -> impl Iterator<Item = &PendingIntent> {
+        self.intents.iter()
+    }
+
+    /// Queue an order; while disconnected the policy applies immediately
+    ///
+    /// A client order id is assigned if missing so a resubmission is
+    /// recognisable as the same order.
+    pub fn enqueue(&mut self, mut request: OrderRequest, now: u64) -> (String, Option<IntentEvent>) {
+        let key = match &request.client_order_id {
+            Some(id) => id.clone(),
+            None => {
+                let id = format!("intent-{}-{}", now, self.next_id);
+                self.next_id += 1;
+                request.client_order_id = Some(id.clone());
+                id
+            }
+        };
+
+        let mut intent = PendingIntent { key: key.clone(), request, queued_at: now, state: IntentState::Queued };
+        let event = if self.connected {
+            None
+        } else {
+            match self.policy {
+                DisconnectPolicy::Drop => {
+                    return (key.clone(), Some(dropped(&intent, "disconnected")));
+                }
+                DisconnectPolicy::ParkAndResubmit => {
+                    intent.state = IntentState::Parked { was_in_flight: false };
+                    Some(IntentEvent::Parked { key: key.clone(), symbol: intent.request.symbol.clone(), was_in_flight: false })
+                }
+            }
+        };
+        self.intents.push_back(intent);
+        (key, event)
+    }
+
+    /// Take the next queued intent to send, marking it in flight
+    pub fn next_to_send(&mut self) -> Option<PendingIntent> {
+        if !self.connected {
+            return None;
+        }
+        let intent = self.intents.iter_mut().find(|i| i.state == IntentState::Queued)?;
+        intent.state = IntentState::InFlight;
+        Some(intent.clone())
+    }
+
+    /// The exchange acked or rejected the order; stop tracking it
+    pub fn complete(&mut self, key: &str) -> Option<PendingIntent> {
+        let index = self.intents.iter().position(|i| i.key == key)?;
+        self.intents.remove(index)
+    }
+
+    /// Apply the disconnect policy to everything pending
+    pub fn on_disconnect(&mut self) -> Vec<IntentEvent> {
+        self.connected = false;
+        let mut events = Vec::new();
+        let mut kept = VecDeque::with_capacity(self.intents.len());
+        for mut intent in self.intents.drain(..) {
+            let was_in_flight = match intent.state {
+                IntentState::Queued => false,
+                IntentState::InFlight => true,
+                IntentState::Parked { .. } => {
+                    kept.push_back(intent);
+                    continue;
+                }
+            };
+            if self.policy == DisconnectPolicy::Drop && !was_in_flight {
+                events.push(dropped(&intent, "disconnected"));
+                continue;
+            }
+            intent.state = IntentState::Parked { was_in_flight };
+            events.push(IntentEvent::Parked { key: intent.key.clone(), symbol: intent.request.symbol.clone(), was_in_flight });
+            kept.push_back(intent);
+        }
+        self.intents = kept;
+        let dropped_count = events.iter().filter(|e| matches!(e, IntentEvent::Dropped { .. })).count();
+        warn!("🔌 Order connection lost: {} intents parked, {} dropped ({:?})",
+              self.intents.len(), dropped_count, self.policy);
+        events
+    }
+
+    /// Resolve parked intents once the connection is back and orders are reconciled
+    ///
+    /// `known_client_ids` are the client order ids the exchange reports (open
+    /// orders plus recent history). Parked intents found there landed before
+    /// the disconnect; the rest are requeued or dropped per policy.
+    pub fn on_reconciled<'a>(&mut self, known_client_ids: impl IntoIterator<Item = &'a str>, now: u64) -> Vec<IntentEvent> {
+        self.connected = true;
+        let known: HashSet<&str> = known_client_ids.into_iter().collect();
+        let mut events = Vec::new();
+        let mut kept = VecDeque::with_capacity(self.intents.len());
+        for mut intent in self.intents.drain(..) {
+            let IntentState::Parked { .. } = intent.state else {
+                kept.push_back(intent);
+                continue;
+            };
+            let symbol = intent.request.symbol.clone();
+            if known.contains(intent.key.as_str()) {
+                debug!("📬 Parked intent {} landed before the disconnect", intent.key);
+                events.push(IntentEvent::Landed { key: intent.key, symbol });
+            } else if self.policy == DisconnectPolicy::Drop {
+                events.push(dropped(&intent, "lost in flight"));
+            } else if self.max_park_nanos.is_some_and(|max| now.saturating_sub(intent.queued_at) > max) {
+                events.push(dropped(&intent, "parked too long"));
+            } else {
+                intent.state = IntentState::Queued;
+                events.push(IntentEvent::Requeued { key: intent.key.clone(), symbol });
+                kept.push_back(intent);
+            }
+        }
+        self.intents = kept;
+        info!("🔌 Order connection reconciled: {} intents pending", self.intents.len());
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testkit::fx;
+    use crate::errors::ExchangeError;
+    use crate::traits::Exchange;
+    use async_trait::async_trait;
+    use std::cell::RefCell;
+    use std::collections::{HashMap, HashSet};
+
+    /// Venue whose bulk cancel misses `sticky` orders and never cancels `stuck` ones
+    struct FlakyVenue {
+        open: RefCell<Vec<OrderResponse>>,
+        closed: RefCell<Vec<OrderResponse>>,
+        sticky: HashSet<String>,
+        stuck: HashSet<String>,
+    }
+
+    impl FlakyVenue {
+        fn with_orders(ids: &[&str], sticky: &[&str], stuck: &[&str]) -> Self {
+            let order = |id: &str| OrderResponse {
+                order_id: id.to_string(),
+                client_order_id: format!("c{id}"),
+                symbol: "BTCUSDT".to_string(),
+                side: OrderSide::Buy,
+                order_type: OrderType::Limit,
+                quantity: Fixed::ONE,
+                price: Some(Fixed::ONE),
+                stop_price: None,
+                status: OrderStatus::New,
+                filled_quantity: Fixed::ZERO,
+                average_price: None,
+                time_in_force: None,
+                timestamp: 0,
+                update_time: 0,
+            };
+            Self {
+                open: RefCell::new(ids.iter().map(|id| order(id)).collect()),
+                closed: RefCell::new(Vec::new()),
+                sticky: sticky.iter().map(|s| s.to_string()).collect(),
+                stuck: stuck.iter().map(|s| s.to_string()).collect(),
+            }
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl Exchange for FlakyVenue {
+        fn name(&self) -> &str { "flaky" }
+        async fn ping(&self) -> Result<u64> { Ok(0) }
+        async fn server_time(&self) -> Result<u64> { Ok(0) }
+        async fn exchange_info(&self) -> Result<HashMap<String, Symbol>> { Ok(HashMap::new()) }
+        async fn account_info(&self) -> Result<AccountInfo> { Err(ExchangeError::FeatureNotSupported("flaky".to_string())) }
+        async fn balances(&self) -> Result<Vec<Balance>> { Ok(Vec::new()) }
+        async fn ticker(&self, symbol: &str) -> Result<Ticker> { Err(ExchangeError::SymbolNotFound(symbol.to_string())) }
+        async fn order_book(&self, symbol: &str, _: Option<u32>) -> Result<OrderBook> { Err(ExchangeError::SymbolNotFound(symbol.to_string())) }
+        async fn recent_trades(&self, _: &str, _: Option<u32>) -> Result<Vec<Trade>> { Ok(Vec::new()) }
+        async fn klines(&self, _: &str, _: &str, _: Option<u64>, _: Option<u64>, _: Option<u32>) -> Result<Vec<Kline>> { Ok(Vec::new()) }
+    }
+
+    #[async_trait(?Send)]
+    impl TradingExchange for FlakyVenue {
+        async fn place_order(&self, request: OrderRequest) -> Result<OrderResponse> {
+            if request.symbol == "TIMEOUT" {
+                return Err(ExchangeError::Timeout("order ack".to_string()));
+            }
+            let mut open = self.open.borrow_mut();
+            let order = OrderResponse {
+                order_id: (100 + open.len()).to_string(),
+                client_order_id: request.client_order_id.unwrap_or_default(),
+                symbol: request.symbol,
+                side: request.side,
+                order_type: request.order_type,
+                quantity: request.quantity,
+                price: request.price,
+                stop_price: None,
+                status: OrderStatus::New,
+                filled_quantity: Fixed::ZERO,
+                average_price: None,
+                time_in_force: None,
+                timestamp: 1,
+                update_time: 1,
+            };
+            open.push(order.clone());
+            Ok(order)
+        }
+        async fn cancel_order(&self, _: &str, order_id: &str) -> Result<OrderResponse> {
+            if self.stuck.contains(order_id) {
+                return Err(ExchangeError::OrderNotFound(order_id.to_string()));
+            }
+            let mut open = self.open.borrow_mut();
+            let index = open.iter().position(|o| o.order_id == order_id).ok_or_else(|| ExchangeError::OrderNotFound(order_id.to_string()))?;
+            let mut order = open.remove(index);
+            order.status = OrderStatus::Canceled;
+            self.closed.borrow_mut().push(order.clone());
+            Ok(order)
+        }
+        async fn cancel_all_orders(&self, _: &str) -> Result<Vec<OrderResponse>> {
+            let mut open = self.open.borrow_mut();
+            let (kept, mut canceled): (Vec<_>, Vec<_>) = open.drain(..).partition(|o| self.sticky.contains(&o.order_id) || self.stuck.contains(&o.order_id));
+            *open = kept;
+            canceled.iter_mut().for_each(|o| o.status = OrderStatus::Canceled);
+            self.closed.borrow_mut().extend(canceled.iter().cloned());
+            Ok(canceled)
+        }
+        async fn get_order(&self, _: &str, order_id: &str) -> Result<OrderResponse> {
+            self.open.borrow().iter().chain(self.closed.borrow().iter())
+                .find(|o| o.order_id == order_id)
+                .cloned()
+                .ok_or_else(|| ExchangeError::OrderNotFound(order_id.to_string()))
+        }
+        async fn open_orders(&self, _: Option<&str>) -> Result<Vec<OrderResponse>> { Ok(self.open.borrow().clone()) }
+        async fn order_history(&self, _: &str, _: Option<u64>, _: Option<u64>, _: Option<u32>) -> Result<Vec<OrderResponse>> { Ok(Vec::new()) }
+        async fn trade_history(&self, _: &str, _: Option<u64>, _: Option<u64>, _: Option<u32>) -> Result<Vec<Trade>> { Ok(Vec::new()) }
+    }
+
+    fn intent(symbol: &str) -> OrderRequest {
+        OrderRequest {
+            symbol: symbol.to_string(),
+            side: OrderSide::Buy,
+            order_type: OrderType::Limit,
+            quantity: Fixed::ONE,
+            price: Some(Fixed::ONE),
+            stop_price: None,
+            time_in_force: None,
+            client_order_id: None,
+        }
+    }
+
+    fn report(order: &ManagedOrder, status: &str, filled: &str, time: u64) -> OrderUpdateEvent {
+        OrderUpdateEvent {
+            event_time: time,
+            symbol: order.symbol.clone(),
+            client_order_id: order.client_order_id.clone(),
+            side: TradeSide::Buy,
+            order_type: "LIMIT".to_string(),
+            time_in_force: "GTC".to_string(),
+            order_quantity: order.quantity,
+            order_price: Fixed::ONE,
+            stop_price: Fixed::ZERO,
+            iceberg_quantity: Fixed::ZERO,
+            order_list_id: -1,
+            original_client_order_id: String::new(),
+            execution_type: "TRADE".to_string(),
+            order_status: status.to_string(),
+            order_reject_reason: "NONE".to_string(),
+            order_id: order.order_id.as_deref().unwrap_or("0").parse().unwrap(),
+            last_executed_quantity: Fixed::ZERO,
+            cumulative_filled_quantity: fx(filled),
+            last_executed_price: Fixed::ONE,
+            commission_amount: Fixed::ZERO,
+            commission_asset: Some("BNB".to_string()),
+            transaction_time: time,
+            trade_id: Some(time),
+            is_order_on_book: true,
+            is_trade_maker_side: true,
+            order_creation_time: 1,
+            cumulative_quote_asset_transacted_quantity: fx(filled),
+            last_quote_asset_transacted_quantity: Fixed::ZERO,
+            quote_order_quantity: Fixed::ZERO,
+        }
+    }
+
+    fn fast() -> CancelVerifyConfig {
+        CancelVerifyConfig::default()
+            .with_deadline(Duration::from_millis(20))
+            .with_poll_interval(Duration::from_millis(5))
+    }
+
+    #[monoio::test(timer_enabled = true)]
+    async fn test_stragglers_are_escalated() {
+        let venue = FlakyVenue::with_orders(&["1", "2", "3"], &["3"], &[]);
+        let oms = OrderManager::new(venue).with_cancel_config(fast());
+
+        let report = oms.cancel_all_verified("BTCUSDT").await.unwrap();
+        assert_eq!(report.bulk_canceled, 2);
+        assert_eq!(report.escalated, vec!["3"]);
+        assert!(report.polls >= 2);
+        assert!(report.is_flat());
+    }
+
+    #[monoio::test(timer_enabled = true)]
+    async fn test_stuck_orders_raise_incident() {
+        let bus = IncidentBus::new();
+        let venue = FlakyVenue::with_orders(&["1", "2"], &[], &["2"]);
+        let oms = OrderManager::new(venue).with_cancel_config(fast()).with_incidents(bus.clone());
+
+        let report = oms.cancel_all_verified("BTCUSDT").await.unwrap();
+        assert!(!report.is_flat());
+        assert_eq!(report.remaining.len(), 1);
+
+        let incidents = bus.recent(10);
+        assert_eq!(incidents.len(), 1);
+        assert_eq!(incidents[0].kind, IncidentKind::CancelUnverified);
+        assert_eq!(incidents[0].context["remaining"], "2");
+    }
+
+    #[monoio::test]
+    async fn test_instrument_change_requotes_off_grid_orders() {
+        use crate::binance::filters::{PriceFilter, SymbolRules};
+
+        let bus = IncidentBus::new();
+        let mut oms = OrderManager::new(FlakyVenue::with_orders(&[], &[], &[])).with_incidents(bus.clone());
+        let mut on_grid = intent("BTCUSDT");
+        on_grid.price = Some(fx("100.5"));
+        let mut off_grid = intent("BTCUSDT");
+        off_grid.price = Some(fx("100.4"));
+        let kept = oms.place_order(on_grid).await.unwrap();
+        let moved = oms.place_order(off_grid).await.unwrap();
+
+        let rules = |tick: &str| SymbolRules {
+            symbol: "BTCUSDT".to_string(),
+            price: Some(PriceFilter { min_price: Fixed::ZERO, max_price: Fixed::ZERO, tick_size: fx(tick) }),
+            ..SymbolRules::default()
+        };
+        let change = InstrumentChanged::detect(&rules("0.01"), &rules("0.5")).unwrap();
+        let replaced = oms.on_instrument_changed(&change).await.unwrap();
+
+        assert_eq!(replaced.len(), 1);
+        assert_eq!(replaced[0].price, Some(fx("100.5")));
+        assert_eq!(oms.order(&moved.client_order_id).unwrap().status, OrderStatus::Canceled);
+        assert!(oms.order(&kept.client_order_id).unwrap().is_open());
+        assert_eq!(bus.recent(1)[0].kind, IncidentKind::InstrumentChanged);
+    }
+
+    #[test]
+    fn test_drop_policy_discards_queued_but_reconciles_in_flight() {
+        let mut queue = IntentQueue::new(DisconnectPolicy::Drop);
+        let (sent, _) = queue.enqueue(intent("BTCUSDT"), 1);
+        let (queued, _) = queue.enqueue(intent("ETHUSDT"), 2);
+        assert_eq!(queue.next_to_send().unwrap().key, sent);
+
+        let events = queue.on_disconnect();
+        assert!(events.contains(&IntentEvent::Dropped { key: queued, symbol: "ETHUSDT".to_string(), reason: "disconnected".to_string() }));
+        assert!(events.contains(&IntentEvent::Parked { key: sent.clone(), symbol: "BTCUSDT".to_string(), was_in_flight: true }));
+        assert!(matches!(queue.enqueue(intent("BTCUSDT"), 3).1, Some(IntentEvent::Dropped { .. })));
+
+        let events = queue.on_reconciled([sent.as_str()], 4);
+        assert_eq!(events, vec![IntentEvent::Landed { key: sent, symbol: "BTCUSDT".to_string() }]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_park_policy_resubmits_after_reconcile() {
+        let mut queue = IntentQueue::new(DisconnectPolicy::ParkAndResubmit).with_max_park_nanos(100);
+        let (lost, _) = queue.enqueue(intent("BTCUSDT"), 1);
+        queue.next_to_send();
+        queue.on_disconnect();
+        let (stale, event) = queue.enqueue(intent("ETHUSDT"), 2);
+        assert!(matches!(event, Some(IntentEvent::Parked { was_in_flight: false, .. })));
+        assert!(queue.next_to_send().is_none());
+
+        // The in-flight order never reached the exchange: resend under the same id
+        let events = queue.on_reconciled(std::iter::empty(), 50);
+        assert_eq!(events.len(), 2);
+        assert_eq!(queue.next_to_send().unwrap().request.client_order_id.as_deref(), Some(lost.as_str()));
+
+        queue.on_disconnect();
+        let events = queue.on_reconciled(std::iter::empty(), 500);
+        assert!(events.iter().all(|e| matches!(e, IntentEvent::Dropped { .. })));
+        assert!(queue.intents().all(|i| i.key != stale));
+    }
+
+    #[test]
+    fn test_average_fill_stays_exact() {
+        assert_eq!(average_fill(fx("99999.99999999"), Fixed::ONE), Some(fx("99999.99999999")));
+        assert_eq!(average_fill(fx("0.3"), fx("0.1")), Some(fx("3")));
+        assert_eq!(average_fill(fx("1"), fx("3")), Some(fx("0.33333333")));
+        assert_eq!(average_fill(fx("1"), Fixed::ZERO), None);
+    }
+
+    #[monoio::test]
+    async fn test_client_ids_follow_venue_policy() {
+        let mut oms = OrderManager::new(FlakyVenue::with_orders(&[], &[], &[]));
+        let with_id = |id: &str| OrderRequest { client_order_id: Some(id.to_string()), ..intent("BTCUSDT") };
+        oms.place_order(with_id("mm-1")).await.unwrap();
+
+        // Still working on a venue that only forbids reuse among open orders
+        assert!(matches!(oms.place_order(with_id("mm-1")).await, Err(ExchangeError::InvalidOrder(_))));
+        assert!(matches!(oms.place_order(with_id("mm 2")).await, Err(ExchangeError::InvalidOrder(_))));
+        assert!(oms.order("mm 2").is_none());
+
+        // A rejected reuse leaves the live order's trace waiting for its execution
+        let mut oms = OrderManager::new(FlakyVenue::with_orders(&[], &[], &[]))
+            .with_latency_tracer(TickToTrade::new(sriquant_core::metrics::MetricsRegistry::new()));
+        oms.place_order_traced(with_id("mm-1"), LatencyTrace::begin(1_000)).await.unwrap();
+        assert!(oms.place_order_traced(with_id("mm-1"), LatencyTrace::begin(2_000)).await.is_err());
+        let tracer = oms.latency_tracer().unwrap();
+        assert_eq!(tracer.in_flight(), 1);
+        assert_eq!(tracer.pending("mm-1").map(|trace| trace.received_at), Some(1_000));
+    }
+
+    #[monoio::test]
+    async fn test_state_machine_ignores_out_of_order_reports() {
+        let mut oms = OrderManager::new(FlakyVenue::with_orders(&[], &[], &[]));
+        let order = oms.place_order(intent("BTCUSDT")).await.unwrap();
+        assert!(order.acknowledged);
+        assert!(order.client_order_id.starts_with("SRI"));
+
+        assert_eq!(oms.apply_update(&report(&order, "PARTIALLY_FILLED", "0.4", 2)), UpdateOutcome::Applied(OrderStatus::PartiallyFilled));
+        // The NEW report arrives late
+        assert_eq!(oms.apply_update(&report(&order, "NEW", "0", 1)), UpdateOutcome::Stale);
+        assert_eq!(oms.apply_update(&report(&order, "FILLED", "1", 3)), UpdateOutcome::Applied(OrderStatus::Filled));
+        assert_eq!(oms.apply_update(&report(&order, "CANCELED", "1", 4)), UpdateOutcome::Stale);
+
+        let tracked = oms.order(&order.client_order_id).unwrap();
+        assert_eq!(tracked.filled_quantity, Fixed::ONE);
+        assert_eq!(tracked.average_price, Some(Fixed::ONE));
+        assert_eq!(oms.orders_with_status(OrderStatus::Filled).len(), 1);
+        assert!(oms.open_orders(None).is_empty());
+    }
+
+    #[monoio::test]
+    async fn test_reconcile_finds_unknown_and_orphaned_orders() {
+        // "c1" was placed by something else
+        let mut oms = OrderManager::new(FlakyVenue::with_orders(&["1"], &[], &[]));
+        let vanished = oms.place_order(intent("BTCUSDT")).await.unwrap();
+        let kept = oms.place_order(intent("BTCUSDT")).await.unwrap();
+        oms.exchange().open.borrow_mut().retain(|o| o.client_order_id != vanished.client_order_id);
+
+        let lost = OrderRequest { client_order_id: Some("lost".to_string()), ..intent("TIMEOUT") };
+        assert!(oms.place_order(lost).await.is_err());
+        assert!(!oms.order("lost").unwrap().acknowledged);
+
+        let report = oms.reconcile("BTCUSDT").await.unwrap();
+        assert_eq!(report.unknown, vec!["c1"]);
+        assert_eq!(report.orphaned, vec![vanished.client_order_id.clone()]);
+        assert_eq!(oms.unknown_orders().count(), 1);
+        assert_eq!(oms.open_orders(Some("BTCUSDT")).len(), 2);
+        assert!(oms.orders_for_symbol("BTCUSDT").iter().any(|o| o.client_order_id == kept.client_order_id));
+        assert_eq!(oms.reconcile("TIMEOUT").await.unwrap().orphaned, vec!["lost"]);
+    }
+
+    #[monoio::test(timer_enabled = true)]
+    async fn test_risk_engine_gates_orders_and_kill_switch_flattens() {
+        let limits = crate::risk::RiskLimits::default().with_max_open_orders_per_symbol(2);
+        let mut oms = OrderManager::new(FlakyVenue::with_orders(&[], &[], &[])).with_risk_engine(RiskEngine::new(limits));
+        oms.place_order(intent("BTCUSDT")).await.unwrap();
+        oms.place_order(intent("BTCUSDT")).await.unwrap();
+        assert!(matches!(oms.place_order(intent("BTCUSDT")).await, Err(ExchangeError::RiskLimitExceeded(_))));
+        assert_eq!(oms.orders_for_symbol("BTCUSDT").len(), 2);
+
+        let reports = oms.kill_switch("drawdown", &[]).await.unwrap();
+        assert!(reports.len() == 1 && reports[0].is_flat());
+        assert!(oms.open_orders(None).is_empty());
+        assert!(oms.place_order(intent("ETHUSDT")).await.is_err());
+
+        oms.risk_engine_mut().unwrap().reset();
+        oms.place_order(intent("ETHUSDT")).await.unwrap();
+    }
+
+    #[monoio::test(timer_enabled = true)]
+    async fn test_kill_switch_keeps_fills_that_raced_the_cancel() {
+        let mut oms = OrderManager::new(FlakyVenue::with_orders(&[], &[], &[]))
+            .with_risk_engine(RiskEngine::new(crate::risk::RiskLimits::default()));
+        let resting = oms.place_order(intent("BTCUSDT")).await.unwrap();
+        let filled = oms.place_order(intent("BTCUSDT")).await.unwrap();
+        let unacked = OrderRequest { client_order_id: Some("unacked".to_string()), ..intent("TIMEOUT") };
+        assert!(oms.place_order(unacked).await.is_err());
+
+        // The venue fills one order before the cancel-all reaches it
+        {
+            let venue = oms.exchange();
+            let mut open = venue.open.borrow_mut();
+            let index = open.iter().position(|o| o.client_order_id == filled.client_order_id).unwrap();
+            let mut order = open.remove(index);
+            order.status = OrderStatus::Filled;
+            order.filled_quantity = Fixed::ONE;
+            order.average_price = Some(Fixed::ONE);
+            venue.closed.borrow_mut().push(order);
+        }
+
+        let reports = oms.kill_switch("drawdown", &[]).await.unwrap();
+        assert!(reports.iter().all(|r| r.is_flat()));
+        assert_eq!(oms.order(&resting.client_order_id).unwrap().status, OrderStatus::Canceled);
+        assert_eq!(oms.order(&filled.client_order_id).unwrap().status, OrderStatus::Filled);
+        assert_eq!(oms.risk_engine().unwrap().position("BTCUSDT"), Fixed::ONE);
+        // Never acknowledged, so left for the user stream or reconcile
+        assert!(oms.order("unacked").unwrap().is_open());
+
+        // A late FILLED report doesn't count the fill twice
+        let late = report(oms.order(&filled.client_order_id).unwrap(), "FILLED", "1", 5);
+        assert_eq!(oms.apply_update(&late), UpdateOutcome::Stale);
+        assert_eq!(oms.risk_engine().unwrap().position("BTCUSDT"), Fixed::ONE);
+    }
+}
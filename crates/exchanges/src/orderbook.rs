@@ -0,0 +1,384 @@
+//! Local order book maintained from depth diffs
+//!
+//! Follows Binance's procedure for a correct local book:
+//!
+//! 1. Buffer diff events from the `<symbol>@depth` stream
+//! 2. Fetch a REST snapshot (`order_book()`) and load it with `apply_snapshot`
+//! 3. Drop buffered events with `u <= lastUpdateId`; the first applied event
+//!    must straddle `lastUpdateId + 1`
+//! 4. Every following event must start at the previous event's `u + 1`
+//! 5. A quantity of zero removes the level
+//!
+//! A sequencing gap invalidates the book; it returns to `AwaitingSnapshot`
+//! and the caller fetches a new snapshot. This is the diff-stream engine; the
+//! plain `types::OrderBook` is a one-off snapshot.
+
+use crate::binance::rest::OrderBookResponse;
+use crate::errors::{ExchangeError, Result};
+use crate::types::{DepthUpdate, OrderBookLevel};
+use sriquant_core::fixed::bulk;
+use sriquant_core::prelude::*;
+
+use std::collections::{BTreeMap, VecDeque};
+use tracing::{debug, warn};
+
+/// Diff events buffered while waiting for a snapshot
+const MAX_BUFFERED_UPDATES: usize = 10_000;
+
+/// Synchronization state of the book
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookState {
+    /// Buffering diffs until a REST snapshot is applied
+    AwaitingSnapshot,
+    Synced,
+}
+
+/// What happened to a depth diff
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepthApplied {
+    /// Held until a snapshot arrives
+    Buffered,
+    /// Already covered by the snapshot
+    Stale,
+    Applied,
+}
+
+/// Locally maintained order book for one symbol
+#[derive(Debug, Clone)]
+pub struct OrderBook {
+    symbol: String,
+    /// Keyed by price; best bid is the last entry
+    bids: BTreeMap<Fixed, Fixed>,
+    /// Keyed by price; best ask is the first entry
+    asks: BTreeMap<Fixed, Fixed>,
+    last_update_id: u64,
+    state: BookState,
+    buffer: VecDeque<DepthUpdate>,
+    /// Exchange event time of the last applied diff
+    timestamp: u64,
+}
+
+impl OrderBook {
+    pub fn new(symbol: &str) -> Self {
+        Self {
+            symbol: symbol.to_uppercase(),
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+            last_update_id: 0,
+            state: BookState::AwaitingSnapshot,
+            buffer: VecDeque::new(),
+            timestamp: 0,
+        }
+    }
+
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub fn state(&self) -> BookState {
+        self.state
+    }
+
+    pub fn is_synced(&self) -> bool {
+        self.state == BookState::Synced
+    }
+
+    pub fn last_update_id(&self) -> u64 {
+        self.last_update_id
+    }
+
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    /// Synced book from a previously taken `to_snapshot`, e.g. one persisted
+    /// by `BookSnapshotStore`
+    pub fn restore(snapshot: &crate::types::OrderBook) -> Self {
+        let side = |levels: &[OrderBookLevel]| levels.iter().filter(|l| !l.quantity.is_zero()).map(|l| (l.price, l.quantity)).collect();
+        Self {
+            symbol: snapshot.symbol.to_uppercase(),
+            bids: side(&snapshot.bids),
+            asks: side(&snapshot.asks),
+            last_update_id: snapshot.update_id,
+            state: BookState::Synced,
+            buffer: VecDeque::new(),
+            timestamp: snapshot.timestamp,
+        }
+    }
+
+    /// Load a REST snapshot and replay buffered diffs on top of it
+    pub fn apply_snapshot(&mut self, snapshot: &OrderBookResponse) -> Result<()> {
+        let timer = PerfTimer::start("orderbook_snapshot_apply");
+        self.bids = Self::bulk_levels(&snapshot.bids)?;
+        self.asks = Self::bulk_levels(&snapshot.asks)?;
+        timer.log_elapsed();
+        self.last_update_id = snapshot.last_update_id;
+        self.state = BookState::Synced;
+        debug!("📚 {} snapshot loaded at {} ({} buffered diffs)", self.symbol, self.last_update_id, self.buffer.len());
+
+        let buffered: Vec<DepthUpdate> = self.buffer.drain(..).collect();
+        let mut first = true;
+        for update in &buffered {
+            if update.update_id <= self.last_update_id {
+                continue;
+            }
+            if first && update.first_update_id > self.last_update_id + 1 {
+                return Err(self.invalidate(format!(
+                    "snapshot {} is older than buffered diffs starting at {}", self.last_update_id, update.first_update_id
+                )));
+            }
+            first = false;
+            self.apply_diff(update)?;
+        }
+        Ok(())
+    }
+
+    /// Apply a diff from the depth stream
+    ///
+    /// Returns an error (and drops back to `AwaitingSnapshot`) on a sequence gap.
+    pub fn apply_depth(&mut self, update: &DepthUpdate) -> Result<DepthApplied> {
+        if update.symbol != self.symbol {
+            return Err(ExchangeError::InvalidSymbol(update.symbol.clone()));
+        }
+        if update.is_snapshot() {
+            return Err(ExchangeError::InvalidResponse(format!(
+                "{} partial depth snapshot can't be applied as a diff", self.symbol
+            )));
+        }
+        match self.state {
+            BookState::AwaitingSnapshot => {
+                if self.buffer.len() == MAX_BUFFERED_UPDATES {
+                    self.buffer.pop_front();
+                }
+                self.buffer.push_back(update.clone());
+                Ok(DepthApplied::Buffered)
+            }
+            BookState::Synced if update.update_id <= self.last_update_id => Ok(DepthApplied::Stale),
+            BookState::Synced => {
+                self.apply_diff(update)?;
+                Ok(DepthApplied::Applied)
+            }
+        }
+    }
+
+    fn apply_diff(&mut self, update: &DepthUpdate) -> Result<()> {
+        let expected = self.last_update_id + 1;
+        // The first diff after a snapshot may straddle it; later diffs must be contiguous
+        if update.first_update_id > expected {
+            return Err(self.invalidate(format!(
+                "gap: expected update {}, got {}..={}", expected, update.first_update_id, update.update_id
+            )));
+        }
+
+        for level in &update.bids {
+            Self::set_level(&mut self.bids, level.price, level.quantity);
+        }
+        for level in &update.asks {
+            Self::set_level(&mut self.asks, level.price, level.quantity);
+        }
+        self.last_update_id = update.update_id;
+        self.timestamp = update.timestamp;
+        Ok(())
+    }
+
+    /// Build one side of the book from snapshot levels in a single pass
+    ///
+    /// Levels are parsed into a preallocated array and put in ascending price
+    /// order (Binance sends bids descending, so usually a reverse; a sort only
+    /// if the input is unordered). `BTreeMap` then builds the tree bottom-up
+    /// from the sorted run instead of inserting 1000-5000 levels one by one.
+    fn bulk_levels(levels: &[[String; 2]]) -> Result<BTreeMap<Fixed, Fixed>> {
+        let mut parsed = Vec::with_capacity(levels.len());
+        for [price, quantity] in levels {
+            let quantity = Fixed::from_str_exact(quantity)?;
+            if !quantity.is_zero() {
+                parsed.push((Fixed::from_str_exact(price)?, quantity));
+            }
+        }
+
+        if parsed.windows(2).all(|w| w[0].0 > w[1].0) {
+            parsed.reverse();
+        } else if !parsed.windows(2).all(|w| w[0].0 < w[1].0) {
+            parsed.sort_by_key(|(price, _)| *price);
+        }
+        Ok(parsed.into_iter().collect())
+    }
+
+    fn set_level(side: &mut BTreeMap<Fixed, Fixed>, price: Fixed, quantity: Fixed) {
+        if quantity.is_zero() {
+            side.remove(&price);
+        } else {
+            side.insert(price, quantity);
+        }
+    }
+
+    fn invalidate(&mut self, reason: String) -> ExchangeError {
+        warn!("📚 {} book out of sync ({}), awaiting new snapshot", self.symbol, reason);
+        self.state = BookState::AwaitingSnapshot;
+        self.bids.clear();
+        self.asks.clear();
+        ExchangeError::InvalidResponse(format!("{} depth {}", self.symbol, reason))
+    }
+
+    pub fn best_bid(&self) -> Option<OrderBookLevel> {
+        self.bids.iter().next_back().map(|(price, quantity)| OrderBookLevel { price: *price, quantity: *quantity })
+    }
+
+    pub fn best_ask(&self) -> Option<OrderBookLevel> {
+        self.asks.iter().next().map(|(price, quantity)| OrderBookLevel { price: *price, quantity: *quantity })
+    }
+
+    /// Top `depth` bids, best first
+    pub fn bids(&self, depth: usize) -> Vec<OrderBookLevel> {
+        self.bids.iter().rev().take(depth).map(|(p, q)| OrderBookLevel { price: *p, quantity: *q }).collect()
+    }
+
+    /// Top `depth` asks, best first
+    pub fn asks(&self, depth: usize) -> Vec<OrderBookLevel> {
+        self.asks.iter().take(depth).map(|(p, q)| OrderBookLevel { price: *p, quantity: *q }).collect()
+    }
+
+    pub fn spread(&self) -> Option<Fixed> {
+        Some(self.best_ask()?.price - self.best_bid()?.price)
+    }
+
+    pub fn mid(&self) -> Option<Fixed> {
+        let two = Fixed::from_i64(2).ok()?;
+        Some((self.best_bid()?.price + self.best_ask()?.price) / two)
+    }
+
+    /// Size-weighted mid: leans toward the side with less resting quantity
+    pub fn microprice(&self) -> Option<Fixed> {
+        let bid = self.best_bid()?;
+        let ask = self.best_ask()?;
+        let total = bid.quantity + ask.quantity;
+        if total.is_zero() {
+            return self.mid();
+        }
+        let bid_weight = bid.quantity / total;
+        Some(ask.price * bid_weight + bid.price * (Fixed::ONE - bid_weight))
+    }
+
+    /// Quantity imbalance over the top `depth` levels, in `[-1, 1]`
+    /// (positive = more resting bids)
+    pub fn imbalance(&self, depth: usize) -> Option<Fixed> {
+        let sum = |levels: Vec<OrderBookLevel>| bulk::sum(&levels.iter().map(|l| l.quantity).collect::<Vec<_>>());
+        let bid_qty = sum(self.bids(depth));
+        let ask_qty = sum(self.asks(depth));
+        let total = bid_qty + ask_qty;
+        if total.is_zero() {
+            return None;
+        }
+        Some((bid_qty - ask_qty) / total)
+    }
+
+    /// Quantity-weighted price of the top `depth` levels, as (bids, asks)
+    pub fn depth_vwap(&self, depth: usize) -> (Option<Fixed>, Option<Fixed>) {
+        let vwap = |levels: Vec<OrderBookLevel>| {
+            let (prices, quantities): (Vec<Fixed>, Vec<Fixed>) = levels.iter().map(|l| (l.price, l.quantity)).unzip();
+            bulk::vwap(&prices, &quantities)
+        };
+        (vwap(self.bids(depth)), vwap(self.asks(depth)))
+    }
+
+    /// Snapshot as the exchange-agnostic book type
+    pub fn to_snapshot(&self, depth: usize) -> crate::types::OrderBook {
+        crate::types::OrderBook {
+            symbol: self.symbol.clone(),
+            bids: self.bids(depth),
+            asks: self.asks(depth),
+            timestamp: self.timestamp,
+            update_id: self.last_update_id,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testkit::fx;
+    use crate::types::{DepthKind, ExchangeId};
+
+    fn level(price: &str, qty: &str) -> OrderBookLevel {
+        OrderBookLevel { price: fx(price), quantity: fx(qty) }
+    }
+
+    fn diff(first: u64, last: u64, bids: Vec<OrderBookLevel>, asks: Vec<OrderBookLevel>) -> DepthUpdate {
+        DepthUpdate {
+            exchange: ExchangeId::Binance,
+            symbol: "BTCUSDT".to_string(),
+            bids,
+            asks,
+            timestamp: last,
+            first_update_id: first,
+            update_id: last,
+            kind: DepthKind::Diff,
+        }
+    }
+
+    fn snapshot(last_update_id: u64) -> OrderBookResponse {
+        OrderBookResponse {
+            last_update_id,
+            bids: vec![["100".into(), "2".into()], ["99".into(), "5".into()]],
+            asks: vec![["101".into(), "1".into()], ["102".into(), "4".into()]],
+        }
+    }
+
+    #[test]
+    fn test_snapshot_plus_buffered_diffs() {
+        let mut book = OrderBook::new("btcusdt");
+        assert_eq!(book.apply_depth(&diff(95, 100, vec![level("98", "1")], vec![])).unwrap(), DepthApplied::Buffered);
+        book.apply_depth(&diff(101, 105, vec![level("100", "0")], vec![level("101", "3")])).unwrap();
+
+        book.apply_snapshot(&snapshot(102)).unwrap();
+        assert!(book.is_synced());
+        assert_eq!(book.last_update_id(), 105);
+        // The 95..=100 diff predates the snapshot and was dropped
+        assert_eq!(book.bids(10), vec![level("99", "5")]);
+        assert_eq!(book.best_ask(), Some(level("101", "3")));
+
+        assert_eq!(book.apply_depth(&diff(100, 104, vec![], vec![])).unwrap(), DepthApplied::Stale);
+        book.apply_depth(&diff(106, 106, vec![level("100", "1")], vec![])).unwrap();
+        assert_eq!(book.mid(), Some(fx("100.5")));
+        // 1 bid vs 3 ask at the touch: microprice leans toward the bid
+        assert_eq!(book.microprice(), Some(fx("100.25")));
+        assert_eq!(book.imbalance(1), Some(fx("-0.5")));
+        assert_eq!(book.depth_vwap(2), (Some(fx("595") / fx("6")), Some(fx("711") / fx("7"))));
+    }
+
+    #[test]
+    fn test_gap_invalidates_book() {
+        let mut book = OrderBook::new("BTCUSDT");
+        book.apply_snapshot(&snapshot(10)).unwrap();
+        book.apply_depth(&diff(11, 12, vec![], vec![])).unwrap();
+
+        assert!(book.apply_depth(&diff(14, 15, vec![], vec![])).is_err());
+        assert_eq!(book.state(), BookState::AwaitingSnapshot);
+        assert!(book.best_bid().is_none());
+        assert_eq!(book.apply_depth(&diff(16, 16, vec![], vec![])).unwrap(), DepthApplied::Buffered);
+    }
+
+    #[test]
+    fn test_bulk_snapshot_handles_any_level_order() {
+        let levels = |prices: Vec<u32>| -> Vec<[String; 2]> { prices.into_iter().map(|p| [p.to_string(), "1".into()]).collect() };
+        let mut shuffled: Vec<u32> = (1..=5000).map(|i| (i * 7919) % 5000 + 5001).collect();
+        shuffled.push(9000);
+        let mut book = OrderBook::new("BTCUSDT");
+        book.apply_snapshot(&OrderBookResponse {
+            last_update_id: 1,
+            bids: levels((1..=5000).rev().collect()),
+            asks: {
+                let mut asks = levels(shuffled);
+                asks.push(["10001".into(), "0".into()]);
+                asks
+            },
+        })
+        .unwrap();
+
+        assert_eq!(book.best_bid(), Some(level("5000", "1")));
+        assert_eq!(book.bids(5000).len(), 5000);
+        // The zero-quantity level is dropped and the duplicate collapses
+        assert_eq!(book.best_ask(), Some(level("5001", "1")));
+        assert_eq!(book.asks(6000).len(), 5000);
+    }
+}
@@ -0,0 +1,161 @@
+//! Time-ranged pagination for REST history endpoints
+//!
+//! History endpoints (`allOrders`, `myTrades`, `klines`, `aggTrades`, ...)
+//! return at most a page of results per call for a `startTime`/`endTime`
+//! window, and some cap how wide that window may be. `paginate` walks a
+//! range page by page: each full page moves the cursor to the last item's
+//! timestamp, items repeated at the boundary millisecond are dropped by
+//! key, and a short page moves on to the next window.
+//!
+//! ```rust,ignore
+//! let orders = paginate(
+//!     |window| client.get_all_orders("BTCUSDT", Some(window.limit), Some(window.start), Some(window.end)),
+//!     start_ms,
+//!     end_ms,
+//!     1000,
+//! ).await?;
+//! ```
+
+use crate::errors::Result;
+
+use std::collections::HashSet;
+use std::future::Future;
+use tracing::{debug, warn};
+
+/// An item of a time-ranged history endpoint
+pub trait Paged {
+    /// Timestamp the endpoint filters and sorts by, in milliseconds
+    fn timestamp_ms(&self) -> u64;
+
+    /// Identity used to drop items repeated across pages
+    fn page_key(&self) -> u64;
+}
+
+/// One call's window; `start` and `end` are inclusive milliseconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageWindow {
+    pub start: u64,
+    pub end: u64,
+    pub limit: u32,
+}
+
+/// Walks a time range page by page
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Paginator {
+    max_per_call: u32,
+    max_window_ms: Option<u64>,
+}
+
+impl Paginator {
+    pub fn new(max_per_call: u32) -> Self {
+        Self { max_per_call: max_per_call.max(1), max_window_ms: None }
+    }
+
+    /// Split the range into windows no wider than this, for endpoints that
+    /// reject wider ranges (24h for `allOrders` and `myTrades`, 1h for `aggTrades`)
+    pub fn with_max_window_ms(mut self, max_window_ms: u64) -> Self {
+        self.max_window_ms = Some(max_window_ms.max(1));
+        self
+    }
+
+    /// Every item between `start` and `end` (inclusive), in time order
+    pub async fn run<T, F, Fut>(&self, mut fetch: F, start: u64, end: u64) -> Result<Vec<T>>
+    where
+        T: Paged,
+        F: FnMut(PageWindow) -> Fut,
+        Fut: Future<Output = Result<Vec<T>>>,
+    {
+        let mut items = Vec::new();
+        let mut seen = HashSet::new();
+        let mut cursor = start;
+        let mut calls = 0usize;
+
+        while cursor <= end {
+            let window_end = self.max_window_ms.map_or(end, |w| end.min(cursor.saturating_add(w - 1)));
+            let page = fetch(PageWindow { start: cursor, end: window_end, limit: self.max_per_call }).await?;
+            calls += 1;
+
+            let full = page.len() >= self.max_per_call as usize;
+            let last = page.iter().map(Paged::timestamp_ms).max();
+            items.extend(page.into_iter().filter(|item| {
+                (start..=end).contains(&item.timestamp_ms()) && seen.insert(item.page_key())
+            }));
+
+            cursor = match last {
+                // More may share the last millisecond, so the next page starts on it
+                Some(last) if full && last > cursor => last,
+                Some(last) if full => {
+                    warn!("📜 Over {} items at {}ms; skipping to the next millisecond", self.max_per_call, last);
+                    last + 1
+                }
+                _ if window_end >= end => break,
+                _ => window_end + 1,
+            };
+        }
+
+        items.sort_by_key(Paged::timestamp_ms);
+        debug!("📜 Paginated {} items from {} to {} in {} calls", items.len(), start, end, calls);
+        Ok(items)
+    }
+}
+
+/// Every item between `start` and `end` (inclusive), fetched `max_per_call` at a time
+pub async fn paginate<T, F, Fut>(fetch: F, start: u64, end: u64, max_per_call: u32) -> Result<Vec<T>>
+where
+    T: Paged,
+    F: FnMut(PageWindow) -> Fut,
+    Fut: Future<Output = Result<Vec<T>>>,
+{
+    Paginator::new(max_per_call).run(fetch, start, end).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Item {
+        id: u64,
+        time: u64,
+    }
+
+    impl Paged for Item {
+        fn timestamp_ms(&self) -> u64 {
+            self.time
+        }
+
+        fn page_key(&self) -> u64 {
+            self.id
+        }
+    }
+
+    #[monoio::test]
+    async fn test_paginate_dedups_boundaries_and_windows() {
+        // Three items share t=20, straddling the first page boundary
+        let history: Vec<Item> = [5, 10, 20, 20, 20, 30, 250, 260]
+            .iter()
+            .enumerate()
+            .map(|(id, time)| Item { id: id as u64, time: *time })
+            .collect();
+        let windows = RefCell::new(Vec::new());
+        let fetch = |window: PageWindow| {
+            windows.borrow_mut().push((window.start, window.end));
+            let page: Vec<Item> = history.iter()
+                .filter(|i| (window.start..=window.end).contains(&i.time))
+                .take(window.limit as usize)
+                .cloned()
+                .collect();
+            async move { Ok(page) }
+        };
+
+        let items = paginate(fetch, 10, 255, 3).await.unwrap();
+        assert_eq!(items.iter().map(|i| i.id).collect::<Vec<_>>(), [1, 2, 3, 4, 5, 6]);
+        assert_eq!(*windows.borrow(), [(10, 255), (20, 255), (21, 255)]);
+
+        windows.borrow_mut().clear();
+        let items = Paginator::new(3).with_max_window_ms(100).run(fetch, 0, 299).await.unwrap();
+        assert_eq!(items.len(), history.len());
+        assert_eq!(*windows.borrow(), [(0, 99), (20, 119), (21, 120), (121, 220), (221, 299)]);
+    }
+}
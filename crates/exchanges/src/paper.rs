@@ -0,0 +1,257 @@
+//! Paper fill simulator
+//!
+//! Fills strategy orders against live market data without sending anything to
+//! an exchange. Market orders and marketable limit orders fill at the touch;
+//! other limit orders rest until a trade prints through their price.
+
+use crate::types::*;
+use sriquant_core::prelude::*;
+
+use std::collections::HashMap;
+use tracing::debug;
+
+/// A simulated execution
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaperFill {
+    /// Id assigned by the simulator when the order was accepted
+    pub paper_order_id: u64,
+    pub symbol: String,
+    pub side: OrderSide,
+    pub price: Fixed,
+    pub quantity: Fixed,
+    pub timestamp: u64,
+    /// Filled while resting rather than on arrival
+    pub passive: bool,
+}
+
+/// Best bid/ask and last trade for a symbol
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TopOfBook {
+    pub bid: Option<Fixed>,
+    pub ask: Option<Fixed>,
+    pub last: Option<Fixed>,
+    pub timestamp: u64,
+}
+
+impl TopOfBook {
+    /// Mid price, falling back to the last trade
+    pub fn mid(&self) -> Option<Fixed> {
+        match (self.bid, self.ask) {
+            (Some(bid), Some(ask)) => Some((bid + ask) / Fixed::from_i64(2).ok()?),
+            _ => self.last,
+        }
+    }
+
+    /// Price an aggressive order on `side` would trade at
+    fn touch(&self, side: OrderSide) -> Option<Fixed> {
+        match side {
+            OrderSide::Buy => self.ask.or(self.last),
+            OrderSide::Sell => self.bid.or(self.last),
+        }
+    }
+}
+
+/// A limit order waiting for the market to trade through it
+#[derive(Debug, Clone)]
+struct RestingOrder {
+    id: u64,
+    request: OrderRequest,
+}
+
+/// Simulates fills from market data
+#[derive(Debug, Default)]
+pub struct PaperFillSimulator {
+    books: HashMap<String, TopOfBook>,
+    resting: Vec<RestingOrder>,
+    next_id: u64,
+}
+
+impl PaperFillSimulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Update market state; returns fills of resting orders this event triggered
+    pub fn on_market_data(&mut self, event: &MarketData) -> Vec<PaperFill> {
+        match event {
+            MarketData::OrderBook(book) => {
+                let top = self.books.entry(book.symbol.clone()).or_default();
+                top.bid = book.best_bid();
+                top.ask = book.best_ask();
+                top.timestamp = book.timestamp;
+                Vec::new()
+            }
+            MarketData::Ticker(ticker) => {
+                let top = self.books.entry(ticker.symbol.clone()).or_default();
+                top.last = Some(ticker.price);
+                top.timestamp = ticker.timestamp;
+                Vec::new()
+            }
+            MarketData::Kline(kline) => {
+                let top = self.books.entry(kline.symbol.clone()).or_default();
+                top.last = Some(kline.close);
+                top.timestamp = kline.close_time;
+                Vec::new()
+            }
+            MarketData::Trade(trade) => {
+                let top = self.books.entry(trade.symbol.clone()).or_default();
+                top.last = Some(trade.price);
+                top.timestamp = trade.timestamp;
+                self.fill_resting(trade)
+            }
+        }
+    }
+
+    /// Accept an order; returns its fill if it executes on arrival
+    pub fn submit(&mut self, request: &OrderRequest, now: u64) -> (u64, Option<PaperFill>) {
+        self.next_id += 1;
+        let id = self.next_id;
+
+        let top = self.books.get(&request.symbol).copied().unwrap_or_default();
+        let touch = top.touch(request.side);
+
+        let fill_price = match (request.order_type, request.price, touch) {
+            (OrderType::Market, _, Some(touch)) => Some(touch),
+            (OrderType::Limit, Some(limit), Some(touch)) if Self::crosses(request.side, limit, touch) => Some(touch),
+            _ => None,
+        };
+
+        if let Some(price) = fill_price {
+            let fill = PaperFill {
+                paper_order_id: id,
+                symbol: request.symbol.clone(),
+                side: request.side,
+                price,
+                quantity: request.quantity,
+                timestamp: now,
+                passive: false,
+            };
+            return (id, Some(fill));
+        }
+
+        if request.order_type == OrderType::Limit && request.price.is_some() {
+            debug!("📝 Paper order {} resting at {:?}", id, request.price);
+            self.resting.push(RestingOrder { id, request: request.clone() });
+        }
+        (id, None)
+    }
+
+    /// Cancel a resting order; returns `true` if it was resting
+    pub fn cancel(&mut self, paper_order_id: u64) -> bool {
+        let before = self.resting.len();
+        self.resting.retain(|o| o.id != paper_order_id);
+        self.resting.len() != before
+    }
+
+    /// Cancel all resting orders for a symbol; returns how many were canceled
+    pub fn cancel_symbol(&mut self, symbol: &str) -> usize {
+        let before = self.resting.len();
+        self.resting.retain(|o| o.request.symbol != symbol);
+        before - self.resting.len()
+    }
+
+    /// Latest market state for a symbol
+    pub fn top_of_book(&self, symbol: &str) -> Option<&TopOfBook> {
+        self.books.get(symbol)
+    }
+
+    /// Number of resting limit orders
+    pub fn resting_count(&self) -> usize {
+        self.resting.len()
+    }
+
+    fn crosses(side: OrderSide, limit: Fixed, price: Fixed) -> bool {
+        match side {
+            OrderSide::Buy => price <= limit,
+            OrderSide::Sell => price >= limit,
+        }
+    }
+
+    fn fill_resting(&mut self, trade: &Trade) -> Vec<PaperFill> {
+        let mut fills = Vec::new();
+        self.resting.retain(|order| {
+            let Some(limit) = order.request.price else { return true };
+            // Require a strict trade-through: trading at the limit doesn't prove queue priority
+            let through = match order.request.side {
+                OrderSide::Buy => trade.price < limit,
+                OrderSide::Sell => trade.price > limit,
+            };
+            if order.request.symbol != trade.symbol || !through {
+                return true;
+            }
+            fills.push(PaperFill {
+                paper_order_id: order.id,
+                symbol: order.request.symbol.clone(),
+                side: order.request.side,
+                price: limit,
+                quantity: order.request.quantity,
+                timestamp: trade.timestamp,
+                passive: true,
+            });
+            false
+        });
+        fills
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testkit::fx;
+
+    fn book(bid: &str, ask: &str) -> MarketData {
+        MarketData::OrderBook(OrderBook {
+            symbol: "BTCUSDT".to_string(),
+            bids: vec![OrderBookLevel { price: fx(bid), quantity: fx("1") }],
+            asks: vec![OrderBookLevel { price: fx(ask), quantity: fx("1") }],
+            timestamp: 1,
+            update_id: 1,
+        })
+    }
+
+    fn trade(price: &str) -> MarketData {
+        MarketData::Trade(Trade {
+            id: "1".to_string(),
+            symbol: "BTCUSDT".to_string(),
+            price: fx(price),
+            quantity: fx("0.1"),
+            side: OrderSide::Sell,
+            timestamp: 2,
+            is_buyer_maker: true,
+        })
+    }
+
+    fn order(order_type: OrderType, side: OrderSide, price: Option<&str>) -> OrderRequest {
+        OrderRequest {
+            symbol: "BTCUSDT".to_string(),
+            side,
+            order_type,
+            quantity: fx("0.01"),
+            price: price.map(fx),
+            stop_price: None,
+            time_in_force: None,
+            client_order_id: None,
+        }
+    }
+
+    #[test]
+    fn test_aggressive_and_resting_fills() {
+        let mut sim = PaperFillSimulator::new();
+        sim.on_market_data(&book("100", "101"));
+
+        let (_, fill) = sim.submit(&order(OrderType::Market, OrderSide::Buy, None), 1);
+        assert_eq!(fill.unwrap().price, fx("101"));
+
+        let (id, fill) = sim.submit(&order(OrderType::Limit, OrderSide::Buy, Some("99")), 1);
+        assert!(fill.is_none());
+        assert_eq!(sim.resting_count(), 1);
+
+        // Trading at the limit is not enough; trading through it fills
+        assert!(sim.on_market_data(&trade("99")).is_empty());
+        let fills = sim.on_market_data(&trade("98.5"));
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].paper_order_id, id);
+        assert!(fills[0].passive);
+        assert_eq!(sim.resting_count(), 0);
+    }
+}
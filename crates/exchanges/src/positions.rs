@@ -0,0 +1,300 @@
+//! Position and PnL tracking
+//!
+//! `PositionTracker` consumes fills from the user data stream
+//! (`OrderUpdateEvent` trade reports) or REST (`MyTradeResponse`) and keeps,
+//! per symbol, the signed net position, average entry price, realized PnL and
+//! commissions, plus unrealized PnL against the latest mark price. Fills seen
+//! on both paths are counted once by trade id.
+//!
+//! Realized and unrealized PnL are gross of commissions, which are totalled
+//! per asset since Binance may charge them in BNB.
+
+use crate::binance::rest::MyTradeResponse;
+use crate::binance::{AccountUpdateEvent, OrderUpdateEvent, TradeSide};
+use crate::errors::Result;
+use crate::types::OrderSide;
+use sriquant_core::prelude::*;
+
+use std::collections::{HashMap, HashSet};
+use tracing::debug;
+
+/// One execution
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionFill {
+    pub symbol: String,
+    pub side: OrderSide,
+    pub price: Fixed,
+    pub quantity: Fixed,
+    pub commission: Fixed,
+    pub commission_asset: String,
+    /// Exchange trade id, used to drop duplicates
+    pub trade_id: Option<u64>,
+    /// Milliseconds
+    pub time: u64,
+}
+
+/// Net position in one symbol
+#[derive(Debug, Clone, PartialEq)]
+pub struct Position {
+    pub symbol: String,
+    /// Positive long, negative short
+    pub quantity: Fixed,
+    /// Zero when flat
+    pub average_entry_price: Fixed,
+    pub realized_pnl: Fixed,
+    pub mark_price: Option<Fixed>,
+    /// Commission paid per asset
+    pub commissions: HashMap<String, Fixed>,
+    pub fills: u64,
+    pub last_fill_time: u64,
+}
+
+impl Position {
+    fn new(symbol: &str) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+            quantity: Fixed::ZERO,
+            average_entry_price: Fixed::ZERO,
+            realized_pnl: Fixed::ZERO,
+            mark_price: None,
+            commissions: HashMap::new(),
+            fills: 0,
+            last_fill_time: 0,
+        }
+    }
+
+    pub fn is_flat(&self) -> bool {
+        self.quantity.is_zero()
+    }
+
+    /// PnL of the open quantity at the mark; zero without a mark
+    pub fn unrealized_pnl(&self) -> Fixed {
+        match self.mark_price {
+            Some(mark) if !self.is_flat() => (mark - self.average_entry_price) * self.quantity,
+            _ => Fixed::ZERO,
+        }
+    }
+
+    /// Apply a fill; returns the PnL it realized
+    fn apply(&mut self, fill: &PositionFill) -> Fixed {
+        let signed = match fill.side {
+            OrderSide::Buy => fill.quantity,
+            OrderSide::Sell => Fixed::ZERO - fill.quantity,
+        };
+        let mut realized = Fixed::ZERO;
+
+        let opposite = (self.quantity > Fixed::ZERO && signed < Fixed::ZERO) || (self.quantity < Fixed::ZERO && signed > Fixed::ZERO);
+        if opposite {
+            let closing = fill.quantity.min(self.quantity.abs());
+            let was_long = self.quantity > Fixed::ZERO;
+            let per_unit = if was_long { fill.price - self.average_entry_price } else { self.average_entry_price - fill.price };
+            realized = per_unit * closing;
+            self.quantity += signed;
+            if self.quantity.is_zero() {
+                self.average_entry_price = Fixed::ZERO;
+            } else if (self.quantity > Fixed::ZERO) != was_long {
+                // Flipped through zero: the remainder opens at the fill price
+                self.average_entry_price = fill.price;
+            }
+        } else {
+            // Incremental mean keeps intermediate values inside the Fixed range
+            let total = self.quantity.abs() + fill.quantity;
+            if total > Fixed::ZERO {
+                self.average_entry_price += (fill.price - self.average_entry_price) * fill.quantity / total;
+            }
+            self.quantity += signed;
+        }
+
+        self.realized_pnl += realized;
+        if fill.commission > Fixed::ZERO {
+            *self.commissions.entry(fill.commission_asset.clone()).or_insert(Fixed::ZERO) += fill.commission;
+        }
+        self.fills += 1;
+        self.last_fill_time = self.last_fill_time.max(fill.time);
+        realized
+    }
+}
+
+/// Positions, PnL and balances across symbols
+#[derive(Debug, Clone, Default)]
+pub struct PositionTracker {
+    positions: HashMap<String, Position>,
+    seen_trades: HashSet<(String, u64)>,
+    balances: HashMap<String, Fixed>,
+}
+
+impl PositionTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply a fill; returns the realized PnL, or `None` for a duplicate
+    pub fn apply_fill(&mut self, fill: &PositionFill) -> Option<Fixed> {
+        if let Some(trade_id) = fill.trade_id
+            && !self.seen_trades.insert((fill.symbol.clone(), trade_id))
+        {
+            debug!("Skipping duplicate fill {} on {}", trade_id, fill.symbol);
+            return None;
+        }
+        let position = self.positions.entry(fill.symbol.clone()).or_insert_with(|| Position::new(&fill.symbol));
+        let realized = position.apply(fill);
+        debug!("📒 {} {} {} @ {}: position {}, realized {}", fill.symbol, fill.side, fill.quantity, fill.price, position.quantity, realized);
+        Some(realized)
+    }
+
+    /// Apply a user-stream execution report if it carries a trade
+    pub fn on_order_update(&mut self, event: &OrderUpdateEvent) -> Option<Fixed> {
+        if event.execution_type != "TRADE" || event.last_executed_quantity <= Fixed::ZERO {
+            return None;
+        }
+        self.apply_fill(&PositionFill {
+            symbol: event.symbol.clone(),
+            side: match event.side {
+                TradeSide::Buy => OrderSide::Buy,
+                TradeSide::Sell => OrderSide::Sell,
+            },
+            price: event.last_executed_price,
+            quantity: event.last_executed_quantity,
+            commission: event.commission_amount,
+            commission_asset: event.commission_asset.clone().unwrap_or_default(),
+            trade_id: event.trade_id,
+            time: event.transaction_time,
+        })
+    }
+
+    /// Apply a fill from `/api/v3/myTrades`
+    pub fn on_my_trade(&mut self, trade: &MyTradeResponse) -> Result<Option<Fixed>> {
+        Ok(self.apply_fill(&PositionFill {
+            symbol: trade.symbol.clone(),
+            side: if trade.is_buyer { OrderSide::Buy } else { OrderSide::Sell },
+            price: trade.price,
+            quantity: trade.qty,
+            commission: trade.commission,
+            commission_asset: trade.commission_asset.clone(),
+            trade_id: Some(trade.id),
+            time: trade.time,
+        }))
+    }
+
+    /// Latest mark for unrealized PnL
+    pub fn update_mark(&mut self, symbol: &str, price: Fixed) {
+        if let Some(position) = self.positions.get_mut(symbol) {
+            position.mark_price = Some(price);
+        }
+    }
+
+    pub fn position(&self, symbol: &str) -> Option<&Position> {
+        self.positions.get(symbol)
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = &Position> {
+        self.positions.values()
+    }
+
+    pub fn realized_pnl(&self) -> Fixed {
+        self.positions.values().fold(Fixed::ZERO, |acc, p| acc + p.realized_pnl)
+    }
+
+    pub fn unrealized_pnl(&self) -> Fixed {
+        self.positions.values().fold(Fixed::ZERO, |acc, p| acc + p.unrealized_pnl())
+    }
+
+    /// Commission paid in `asset` across all symbols
+    pub fn commission(&self, asset: &str) -> Fixed {
+        self.positions.values()
+            .filter_map(|p| p.commissions.get(asset))
+            .fold(Fixed::ZERO, |acc, c| acc + *c)
+    }
+
+    /// Replace an asset's total balance (free plus locked)
+    pub fn set_balance(&mut self, asset: &str, total: Fixed) {
+        self.balances.insert(asset.to_string(), total);
+    }
+
+    pub fn balance(&self, asset: &str) -> Fixed {
+        self.balances.get(asset).copied().unwrap_or(Fixed::ZERO)
+    }
+
+    /// Apply an `outboundAccountPosition` event
+    pub fn on_account_update(&mut self, event: &AccountUpdateEvent) {
+        for balance in &event.balances {
+            self.set_balance(&balance.asset, balance.free + balance.locked);
+        }
+    }
+
+    /// Quantity whose notional at `price` is `risk_pct` percent of the `quote_asset` balance
+    pub fn risk_sized_quantity(&self, quote_asset: &str, price: Fixed, risk_pct: Fixed) -> Result<Fixed> {
+        let risk_amount = self.balance(quote_asset) * (risk_pct / Fixed::from_i64(100)?);
+        Ok(risk_amount / price)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testkit::fx;
+
+    fn fill(side: OrderSide, price: &str, quantity: &str, trade_id: u64) -> PositionFill {
+        PositionFill {
+            symbol: "BTCUSDT".to_string(),
+            side,
+            price: fx(price),
+            quantity: fx(quantity),
+            commission: fx("0.01"),
+            commission_asset: "USDT".to_string(),
+            trade_id: Some(trade_id),
+            time: trade_id,
+        }
+    }
+
+    #[test]
+    fn test_average_entry_realized_and_flip() {
+        let mut tracker = PositionTracker::new();
+        tracker.apply_fill(&fill(OrderSide::Buy, "100", "1", 1));
+        tracker.apply_fill(&fill(OrderSide::Buy, "110", "1", 2));
+        assert_eq!(tracker.position("BTCUSDT").unwrap().average_entry_price, fx("105"));
+
+        tracker.update_mark("BTCUSDT", fx("120"));
+        assert_eq!(tracker.unrealized_pnl(), fx("30"));
+
+        // Sell 3: closes 2 at +15 each, opens 1 short at 120
+        assert_eq!(tracker.apply_fill(&fill(OrderSide::Sell, "120", "3", 3)), Some(fx("30")));
+        let position = tracker.position("BTCUSDT").unwrap();
+        assert_eq!(position.quantity, fx("-1"));
+        assert_eq!(position.average_entry_price, fx("120"));
+        assert_eq!(position.unrealized_pnl(), Fixed::ZERO);
+
+        // The same trade again (e.g. REST backfill after the stream) is ignored
+        assert_eq!(tracker.apply_fill(&fill(OrderSide::Sell, "120", "3", 3)), None);
+        assert_eq!(tracker.commission("USDT"), fx("0.03"));
+        assert_eq!(tracker.realized_pnl(), fx("30"));
+    }
+
+    #[test]
+    fn test_rest_trades_and_sizing() {
+        let mut tracker = PositionTracker::new();
+        let trade = MyTradeResponse {
+            symbol: "BTCUSDT".to_string(),
+            id: 7,
+            order_id: 1,
+            order_list_id: -1,
+            price: Fixed::from_str_exact("50000.00").unwrap(),
+            qty: Fixed::from_str_exact("0.002").unwrap(),
+            quote_qty: Fixed::from_str_exact("100.00").unwrap(),
+            commission: Fixed::from_str_exact("0.000002").unwrap(),
+            commission_asset: "BTC".to_string(),
+            time: 1,
+            is_buyer: false,
+            is_maker: true,
+            is_best_match: true,
+        };
+        tracker.on_my_trade(&trade).unwrap();
+        tracker.update_mark("BTCUSDT", fx("49000"));
+        assert_eq!(tracker.position("BTCUSDT").unwrap().quantity, fx("-0.002"));
+        assert_eq!(tracker.unrealized_pnl(), fx("2"));
+        assert_eq!(tracker.commission("BTC"), fx("0.000002"));
+
+        tracker.set_balance("USDT", fx("1000"));
+        assert_eq!(tracker.risk_sized_quantity("USDT", fx("50000"), fx("1")).unwrap(), fx("0.0002"));
+    }
+}
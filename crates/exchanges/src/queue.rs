@@ -0,0 +1,212 @@
+//! Queue position estimation for resting limit orders
+//!
+//! Exchanges don't report where an order sits in the FIFO queue at its price
+//! level, so the estimator infers it. On placement the order joins behind the
+//! visible level quantity. Trade prints at our price consume the queue ahead
+//! of us; level decreases not explained by trades are cancellations, assumed
+//! to come from ahead and behind in proportion; increases join behind us.
+//!
+//! Level quantities fed to the estimator are the venue's visible totals, which
+//! include our own order once it is on the book.
+
+use crate::types::*;
+use sriquant_core::prelude::*;
+
+use std::collections::HashMap;
+use tracing::debug;
+
+/// Estimated queue state of one resting order
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueuePosition {
+    pub order_id: String,
+    pub symbol: String,
+    pub side: OrderSide,
+    pub price: Fixed,
+    /// Our unfilled quantity
+    pub remaining: Fixed,
+    /// Estimated quantity ahead of us at our price
+    pub ahead: Fixed,
+    /// Estimated quantity behind us at our price
+    pub behind: Fixed,
+    /// Estimated quantity of ours already filled by prints at our level
+    pub estimated_filled: Fixed,
+    /// A trade printed through our price; we should have been filled
+    pub traded_through: bool,
+    pub placed_at: u64,
+}
+
+impl QueuePosition {
+    /// Share of the level ahead of us: 0.0 = front of the queue
+    pub fn queue_fraction(&self) -> f64 {
+        let total = (self.ahead + self.behind + self.remaining).to_f64_lossy();
+        if total <= 0.0 { 0.0 } else { self.ahead.to_f64_lossy() / total }
+    }
+
+    /// Whether a trade against our side at `price` reaches this order's level
+    fn consumed_by(&self, price: Fixed) -> bool {
+        match self.side {
+            OrderSide::Buy => price <= self.price,
+            OrderSide::Sell => price >= self.price,
+        }
+    }
+}
+
+/// Tracks queue positions of our resting orders
+#[derive(Debug, Default)]
+pub struct QueueEstimator {
+    orders: HashMap<String, QueuePosition>,
+}
+
+impl QueueEstimator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start tracking an order; `level_quantity` is the visible quantity at its
+    /// price just before it was placed
+    pub fn track(&mut self, order_id: &str, request: &OrderRequest, level_quantity: Fixed, placed_at: u64) {
+        let Some(price) = request.price else { return };
+        debug!("🧮 Tracking queue for {} {} @ {} behind {}", order_id, request.side, price, level_quantity);
+        self.orders.insert(order_id.to_string(), QueuePosition {
+            order_id: order_id.to_string(),
+            symbol: request.symbol.clone(),
+            side: request.side,
+            price,
+            remaining: request.quantity,
+            ahead: level_quantity.max(Fixed::ZERO),
+            behind: Fixed::ZERO,
+            estimated_filled: Fixed::ZERO,
+            traded_through: false,
+            placed_at,
+        });
+    }
+
+    /// Stop tracking (filled or canceled)
+    pub fn untrack(&mut self, order_id: &str) -> Option<QueuePosition> {
+        self.orders.remove(order_id)
+    }
+
+    /// Apply a confirmed fill of ours
+    pub fn on_own_fill(&mut self, order_id: &str, quantity: Fixed) {
+        if let Some(order) = self.orders.get_mut(order_id) {
+            order.remaining = (order.remaining - quantity).max(Fixed::ZERO);
+            order.ahead = Fixed::ZERO;
+        }
+    }
+
+    pub fn position(&self, order_id: &str) -> Option<&QueuePosition> {
+        self.orders.get(order_id)
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = &QueuePosition> {
+        self.orders.values()
+    }
+
+    /// A trade print; `aggressor` is the taker side
+    pub fn on_trade(&mut self, symbol: &str, price: Fixed, quantity: Fixed, aggressor: OrderSide) {
+        for order in self.orders.values_mut().filter(|o| o.symbol == symbol && o.side != aggressor) {
+            if !order.consumed_by(price) {
+                continue;
+            }
+            if price != order.price {
+                order.traded_through = true;
+                order.ahead = Fixed::ZERO;
+                continue;
+            }
+            let from_ahead = quantity.min(order.ahead);
+            order.ahead -= from_ahead;
+            let reaching_us = (quantity - from_ahead).min(order.remaining - order.estimated_filled);
+            order.estimated_filled += reaching_us.max(Fixed::ZERO);
+        }
+    }
+
+    /// New visible quantity at a price level (zero = level removed)
+    pub fn on_level(&mut self, symbol: &str, side: OrderSide, price: Fixed, quantity: Fixed) {
+        for order in self.orders.values_mut().filter(|o| o.symbol == symbol && o.side == side && o.price == price) {
+            let others_before = order.ahead + order.behind;
+            let others_now = (quantity - order.remaining).max(Fixed::ZERO);
+
+            if others_now >= others_before {
+                order.behind += others_now - others_before;
+            } else if others_before.is_positive() {
+                let canceled = others_before - others_now;
+                let from_ahead = canceled * order.ahead / others_before;
+                order.ahead = (order.ahead - from_ahead).max(Fixed::ZERO);
+                order.behind = (order.behind - (canceled - from_ahead)).max(Fixed::ZERO);
+            }
+        }
+    }
+
+    /// Feed normalized market data
+    pub fn on_market_data(&mut self, event: &MarketData) {
+        match event {
+            MarketData::Trade(trade) => {
+                let aggressor = if trade.is_buyer_maker { OrderSide::Sell } else { OrderSide::Buy };
+                self.on_trade(&trade.symbol, trade.price, trade.quantity, aggressor);
+            }
+            MarketData::OrderBook(book) => {
+                for order in self.orders.values().filter(|o| o.symbol == book.symbol).cloned().collect::<Vec<_>>() {
+                    let levels = match order.side {
+                        OrderSide::Buy => &book.bids,
+                        OrderSide::Sell => &book.asks,
+                    };
+                    // Our level may be beyond the depth shown; keep the estimate then
+                    if let Some(level) = levels.iter().find(|l| l.price == order.price) {
+                        self.on_level(&book.symbol, order.side, order.price, level.quantity);
+                    }
+                }
+            }
+            MarketData::Ticker(_) | MarketData::Kline(_) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testkit::fx;
+
+    fn bid(price: &str, qty: &str) -> OrderRequest {
+        OrderRequest {
+            symbol: "BTCUSDT".to_string(),
+            side: OrderSide::Buy,
+            order_type: OrderType::Limit,
+            quantity: fx(qty),
+            price: Some(fx(price)),
+            stop_price: None,
+            time_in_force: Some(TimeInForce::GoodTillCanceled),
+            client_order_id: None,
+        }
+    }
+
+    #[test]
+    fn test_queue_advances_on_trades_and_cancels() {
+        let mut queue = QueueEstimator::new();
+        queue.track("q1", &bid("100", "1"), fx("10"), 0);
+
+        // Our order shows up on the book, then 2 more join behind
+        queue.on_level("BTCUSDT", OrderSide::Buy, fx("100"), fx("11"));
+        queue.on_level("BTCUSDT", OrderSide::Buy, fx("100"), fx("13"));
+        let pos = queue.position("q1").unwrap();
+        assert_eq!((pos.ahead, pos.behind), (fx("10"), fx("2")));
+
+        // Sellers hit 4 at our price; buy-side prints don't touch bids
+        queue.on_trade("BTCUSDT", fx("100"), fx("4"), OrderSide::Sell);
+        queue.on_trade("BTCUSDT", fx("100"), fx("4"), OrderSide::Buy);
+        assert_eq!(queue.position("q1").unwrap().ahead, fx("6"));
+
+        // 4 canceled (level 13 - 4 traded - 4 canceled = 5): split 6:2 ahead:behind
+        queue.on_level("BTCUSDT", OrderSide::Buy, fx("100"), fx("5"));
+        let pos = queue.position("q1").unwrap();
+        assert_eq!((pos.ahead, pos.behind), (fx("3"), fx("1")));
+        assert!((pos.queue_fraction() - 0.6).abs() < 1e-9);
+
+        // Prints larger than the queue ahead reach us
+        queue.on_trade("BTCUSDT", fx("100"), fx("3.5"), OrderSide::Sell);
+        let pos = queue.position("q1").unwrap();
+        assert_eq!((pos.ahead, pos.estimated_filled), (Fixed::ZERO, fx("0.5")));
+
+        queue.on_trade("BTCUSDT", fx("99.9"), fx("0.1"), OrderSide::Sell);
+        assert!(queue.position("q1").unwrap().traded_through);
+    }
+}
@@ -0,0 +1,165 @@
+//! Quote throttling on insignificant top-of-book moves
+//!
+//! A market maker re-pricing on every book ticker cancels and replaces its
+//! quotes many times a second for moves of a tick or less, burning order
+//! rate limit and request weight without changing where it stands. With
+//! `QuoteGuard` a quote is only replaced once the reference price (usually
+//! the mid) has moved a threshold, in ticks or basis points, away from the
+//! reference it was priced from. `with_max_age_ms` still refreshes quotes
+//! that have rested long enough, so a slow drift is eventually followed.
+//!
+//! ```rust,ignore
+//! let mut guard = QuoteGuard::new(RequoteThreshold::Bps(Fixed::from_i64(2)?))
+//!     .with_threshold("BTCUSDT", RequoteThreshold::Ticks { count: 3, tick_size: tick });
+//! if let MarketDataEvent::BookTicker(book) = event {
+//!     if guard.check_book(&book, now_ms) {
+//!         replace_quotes(&book).await?;
+//!         guard.quoted(&book.symbol, book.mid_price(), now_ms);
+//!     }
+//! }
+//! ```
+
+use crate::types::BookTickerUpdate;
+use sriquant_core::prelude::*;
+
+use std::collections::HashMap;
+use tracing::debug;
+
+/// How far the reference price must move before quotes are replaced
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RequoteThreshold {
+    /// Whole ticks of the symbol's price filter
+    Ticks { count: u32, tick_size: Fixed },
+    /// Basis points of the price the quotes were made from
+    Bps(Fixed),
+}
+
+impl RequoteThreshold {
+    /// Whether a move from `from` to `to` reaches the threshold
+    pub fn is_significant(&self, from: Fixed, to: Fixed) -> bool {
+        let moved = (to - from).abs();
+        let times = |value: Fixed, n: i64| Fixed::from_i64(n).map_or_else(|_| Fixed::max(), |n| value.saturating_mul(n));
+        match *self {
+            Self::Ticks { count, tick_size } => moved >= times(tick_size, count as i64),
+            Self::Bps(bps) => times(moved, 10_000) >= from.abs().saturating_mul(bps),
+        }
+    }
+}
+
+/// Last quoted reference for a symbol
+#[derive(Debug, Clone, Copy)]
+struct Quoted {
+    reference: Fixed,
+    at_ms: u64,
+}
+
+/// Requotes and suppressed requotes so far
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QuoteGuardStats {
+    pub requotes: u64,
+    pub suppressed: u64,
+}
+
+/// Suppresses cancel-replace cycles for moves below a threshold
+#[derive(Debug, Clone)]
+pub struct QuoteGuard {
+    default_threshold: RequoteThreshold,
+    thresholds: HashMap<String, RequoteThreshold>,
+    max_age_ms: Option<u64>,
+    quoted: HashMap<String, Quoted>,
+    stats: QuoteGuardStats,
+}
+
+impl QuoteGuard {
+    /// Apply `threshold` to symbols without their own
+    pub fn new(threshold: RequoteThreshold) -> Self {
+        Self { default_threshold: threshold, thresholds: HashMap::new(), max_age_ms: None, quoted: HashMap::new(), stats: QuoteGuardStats::default() }
+    }
+
+    pub fn with_threshold(mut self, symbol: &str, threshold: RequoteThreshold) -> Self {
+        self.thresholds.insert(symbol.to_string(), threshold);
+        self
+    }
+
+    /// Requote anyway once quotes have rested this long
+    pub fn with_max_age_ms(mut self, max_age_ms: u64) -> Self {
+        self.max_age_ms = Some(max_age_ms);
+        self
+    }
+
+    pub fn threshold(&self, symbol: &str) -> RequoteThreshold {
+        self.thresholds.get(symbol).copied().unwrap_or(self.default_threshold)
+    }
+
+    /// Whether quotes for `symbol` should be replaced at `reference`
+    ///
+    /// Always true before the first `quoted` or after `forget`. A false
+    /// answer counts as a suppressed requote.
+    pub fn should_requote(&mut self, symbol: &str, reference: Fixed, now_ms: u64) -> bool {
+        let Some(last) = self.quoted.get(symbol) else {
+            return true;
+        };
+        let expired = self.max_age_ms.is_some_and(|max| now_ms.saturating_sub(last.at_ms) >= max);
+        if expired || self.threshold(symbol).is_significant(last.reference, reference) {
+            return true;
+        }
+        debug!("🪧 Holding {} quotes: {} is within threshold of {}", symbol, reference, last.reference);
+        self.stats.suppressed += 1;
+        false
+    }
+
+    /// `should_requote` against the book ticker's mid price
+    pub fn check_book(&mut self, book: &BookTickerUpdate, now_ms: u64) -> bool {
+        self.should_requote(&book.symbol, book.mid_price(), now_ms)
+    }
+
+    /// Record that quotes for `symbol` were (re)placed from `reference`
+    pub fn quoted(&mut self, symbol: &str, reference: Fixed, now_ms: u64) {
+        self.stats.requotes += 1;
+        self.quoted.insert(symbol.to_string(), Quoted { reference, at_ms: now_ms });
+    }
+
+    /// Drop the symbol's reference once its quotes are cancelled or filled
+    pub fn forget(&mut self, symbol: &str) {
+        self.quoted.remove(symbol);
+    }
+
+    /// Reference the symbol's current quotes were made from
+    pub fn last_reference(&self, symbol: &str) -> Option<Fixed> {
+        self.quoted.get(symbol).map(|q| q.reference)
+    }
+
+    pub fn stats(&self) -> QuoteGuardStats {
+        self.stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testkit::fx;
+
+    #[test]
+    fn test_holds_quotes_until_move_is_significant() {
+        let mut guard = QuoteGuard::new(RequoteThreshold::Bps(fx("2")))
+            .with_threshold("BTCUSDT", RequoteThreshold::Ticks { count: 3, tick_size: fx("0.01") })
+            .with_max_age_ms(5_000);
+
+        assert!(guard.should_requote("BTCUSDT", fx("50000.00"), 0));
+        guard.quoted("BTCUSDT", fx("50000.00"), 0);
+        assert!(!guard.should_requote("BTCUSDT", fx("50000.02"), 100));
+        assert!(!guard.should_requote("BTCUSDT", fx("49999.99"), 200));
+        assert!(guard.should_requote("BTCUSDT", fx("49999.97"), 300));
+        // Resting quotes are refreshed even without a move
+        assert!(guard.should_requote("BTCUSDT", fx("50000.00"), 5_000));
+
+        // 2 bps of 3000 is 0.6
+        guard.quoted("ETHUSDT", fx("3000"), 0);
+        assert!(!guard.should_requote("ETHUSDT", fx("3000.59"), 10));
+        assert!(guard.should_requote("ETHUSDT", fx("2999.4"), 10));
+
+        guard.forget("ETHUSDT");
+        assert!(guard.should_requote("ETHUSDT", fx("3000"), 20));
+        assert_eq!(guard.stats(), QuoteGuardStats { requotes: 2, suppressed: 3 });
+    }
+}
@@ -0,0 +1,366 @@
+//! Market data recording with gap markers and an integrity manifest
+//!
+//! `StreamRecorder` appends every received WebSocket text frame to a JSON
+//! lines file so it can be replayed through `process_message_content`. When
+//! the connection drops or a stream's exchange sequence jumps (depth diffs,
+//! trades, aggregate trades), it writes an explicit gap entry ahead of the
+//! frame that revealed it, so a backtest sees missing data instead of a
+//! quiet market.
+//!
+//! `finish` writes `<file>.manifest.json` next to the recording: entry,
+//! frame and gap counts, the first and last exchange id per stream, and a
+//! SHA-256 of the file. A recording without a manifest was not closed
+//! cleanly; `Recording::load` refuses it, and any recording whose checksum
+//! or counts disagree with its manifest.
+
+use crate::errors::{ExchangeError, Result};
+use crate::types::{DepthKind, MarketDataEvent, ReconnectInfo};
+use sriquant_core::prelude::*;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// Why data is missing
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum DataGap {
+    /// The connection dropped; nothing was received for `downtime_ms`
+    Disconnected { downtime_ms: u64, streams: Vec<String> },
+    /// A stream skipped ids `expected..received`
+    Sequence { stream: String, expected: u64, received: u64 },
+    /// Data lost for another reason the caller detected, e.g. a book resync
+    Other { stream: Option<String>, detail: String },
+}
+
+/// One line of a recording
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RecordedEntry {
+    Frame { seq: u64, received_at: u64, text: String },
+    Gap { seq: u64, received_at: u64, gap: DataGap },
+}
+
+impl RecordedEntry {
+    pub fn seq(&self) -> u64 {
+        match self {
+            Self::Frame { seq, .. } | Self::Gap { seq, .. } => *seq,
+        }
+    }
+}
+
+/// Frames and exchange id range of one sequenced stream
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StreamSummary {
+    pub frames: u64,
+    pub gaps: u64,
+    pub first_id: Option<u64>,
+    pub last_id: Option<u64>,
+}
+
+/// Written next to a finished recording as `<file>.manifest.json`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordingManifest {
+    /// File name of the recording, relative to the manifest
+    pub file: String,
+    /// Local receive time of the first and last entry, in milliseconds
+    pub started_at: Option<u64>,
+    pub ended_at: Option<u64>,
+    /// Lines in the file; entry `seq`s run from 0 to `entries - 1`
+    pub entries: u64,
+    pub frames: u64,
+    pub gaps: u64,
+    pub bytes: u64,
+    /// Hex SHA-256 of the recording
+    pub sha256: String,
+    /// Depth, trade and aggTrade streams by name, e.g. `btcusdt@depth`
+    pub streams: BTreeMap<String, StreamSummary>,
+}
+
+impl RecordingManifest {
+    /// No gaps were recorded
+    pub fn is_complete(&self) -> bool {
+        self.gaps == 0
+    }
+
+    /// Path of the manifest for a recording
+    pub fn path_for(recording: &Path) -> PathBuf {
+        let mut name = recording.file_name().unwrap_or_default().to_os_string();
+        name.push(".manifest.json");
+        recording.with_file_name(name)
+    }
+}
+
+/// Appends frames and gap markers to a recording file
+///
+/// ```rust,ignore
+/// let mut recorder = StreamRecorder::create("recordings/btcusdt-2026-10-17.jsonl")?;
+/// while let Some(text) = next_frame().await {
+///     let event = client.process_message_content(&text).ok();
+///     recorder.record(&text, event.as_ref())?;
+/// }
+/// let manifest = recorder.finish()?;
+/// ```
+pub struct StreamRecorder {
+    path: PathBuf,
+    out: BufWriter<File>,
+    hasher: Sha256,
+    manifest: RecordingManifest,
+    /// Last exchange id seen per sequenced stream
+    last_ids: HashMap<String, u64>,
+}
+
+impl StreamRecorder {
+    /// Start a new recording at `path`, replacing any file there
+    pub fn create(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        let out = BufWriter::new(File::create(&path)?);
+        let _ = std::fs::remove_file(RecordingManifest::path_for(&path));
+        info!("⏺️ Recording market data to {}", path.display());
+
+        let manifest = RecordingManifest {
+            file: path.file_name().unwrap_or_default().to_string_lossy().into_owned(),
+            ..RecordingManifest::default()
+        };
+        Ok(Self { path, out, hasher: Sha256::new(), manifest, last_ids: HashMap::new() })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Counts so far; the checksum is only filled in by `finish`
+    pub fn manifest(&self) -> &RecordingManifest {
+        &self.manifest
+    }
+
+    /// Record a received frame and, if it parsed, check its stream for a sequence gap
+    ///
+    /// Returns the gap written ahead of the frame, if any.
+    pub fn record(&mut self, text: &str, event: Option<&MarketDataEvent>) -> Result<Option<DataGap>> {
+        let received_at = nanos() / 1_000_000;
+        if let Some(MarketDataEvent::Reconnected(info)) = event {
+            self.record_reconnect(info)?;
+            return Ok(None);
+        }
+        let gap = event.and_then(|event| self.check_sequence(event));
+        if let Some(gap) = &gap {
+            warn!("⏺️ Gap in {}: {:?}", self.manifest.file, gap);
+            self.write_gap(received_at, gap.clone())?;
+        }
+
+        let seq = self.manifest.entries;
+        self.write(&RecordedEntry::Frame { seq, received_at, text: text.to_string() }, received_at)?;
+        self.manifest.frames += 1;
+        Ok(gap)
+    }
+
+    /// Mark the downtime of a reconnect; streams start a new id run afterwards
+    pub fn record_reconnect(&mut self, info: &ReconnectInfo) -> Result<()> {
+        self.last_ids.clear();
+        let gap = DataGap::Disconnected { downtime_ms: info.downtime_ms, streams: info.streams.clone() };
+        self.write_gap(nanos() / 1_000_000, gap)
+    }
+
+    /// Mark a gap detected outside the recorder
+    pub fn mark_gap(&mut self, gap: DataGap) -> Result<()> {
+        if let DataGap::Sequence { stream, .. } | DataGap::Other { stream: Some(stream), .. } = &gap {
+            self.last_ids.remove(stream);
+        }
+        self.write_gap(nanos() / 1_000_000, gap)
+    }
+
+    /// Flush the recording and write its manifest
+    pub fn finish(mut self) -> Result<RecordingManifest> {
+        self.out.flush()?;
+        self.out.get_ref().sync_all()?;
+        self.manifest.sha256 = hex::encode(self.hasher.finalize_reset());
+
+        let manifest_path = RecordingManifest::path_for(&self.path);
+        let tmp = manifest_path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(&self.manifest)?)?;
+        std::fs::rename(&tmp, &manifest_path)?;
+        info!(
+            "⏺️ Finished {}: {} frames, {} gaps, {} bytes",
+            self.manifest.file, self.manifest.frames, self.manifest.gaps, self.manifest.bytes
+        );
+        Ok(self.manifest)
+    }
+
+    /// Compare an event's exchange ids with the last ones seen on its stream
+    fn check_sequence(&mut self, event: &MarketDataEvent) -> Option<DataGap> {
+        // (stream, first id in the event, last id in the event, whether ids are contiguous)
+        let (stream, first, last, contiguous) = match event {
+            MarketDataEvent::Depth(d) => (
+                format!("{}@depth", d.symbol.to_lowercase()),
+                d.first_update_id,
+                d.update_id,
+                d.kind == DepthKind::Diff,
+            ),
+            MarketDataEvent::Trade(t) => (format!("{}@trade", t.symbol.to_lowercase()), t.trade_id, t.trade_id, true),
+            MarketDataEvent::AggTrade(t) => {
+                (format!("{}@aggTrade", t.symbol.to_lowercase()), t.agg_trade_id, t.agg_trade_id, true)
+            }
+            _ => return None,
+        };
+
+        let summary = self.manifest.streams.entry(stream.clone()).or_default();
+        summary.frames += 1;
+        summary.first_id.get_or_insert(first);
+        summary.last_id = Some(summary.last_id.map_or(last, |seen| seen.max(last)));
+
+        let previous = self.last_ids.insert(stream.clone(), last)?;
+        if last <= previous {
+            // Stale or repeated: nothing new, nothing missing
+            self.last_ids.insert(stream, previous);
+            return None;
+        }
+        let expected = previous + 1;
+        (contiguous && first > expected).then(|| {
+            summary.gaps += 1;
+            DataGap::Sequence { stream, expected, received: first }
+        })
+    }
+
+    fn write_gap(&mut self, received_at: u64, gap: DataGap) -> Result<()> {
+        let seq = self.manifest.entries;
+        self.write(&RecordedEntry::Gap { seq, received_at, gap }, received_at)?;
+        self.manifest.gaps += 1;
+        Ok(())
+    }
+
+    fn write(&mut self, entry: &RecordedEntry, received_at: u64) -> Result<()> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+        self.out.write_all(&line)?;
+        self.hasher.update(&line);
+
+        self.manifest.entries += 1;
+        self.manifest.bytes += line.len() as u64;
+        self.manifest.started_at.get_or_insert(received_at);
+        self.manifest.ended_at = Some(received_at);
+        Ok(())
+    }
+}
+
+/// A finished recording checked against its manifest
+#[derive(Debug, Clone)]
+pub struct Recording {
+    pub manifest: RecordingManifest,
+    pub entries: Vec<RecordedEntry>,
+}
+
+impl Recording {
+    /// Read a recording, failing unless it matches its manifest
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let manifest_path = RecordingManifest::path_for(path);
+        let manifest: RecordingManifest = serde_json::from_slice(&std::fs::read(&manifest_path).map_err(|e| {
+            ExchangeError::IoError(format!("{}: no manifest, recording was not finished ({e})", path.display()))
+        })?)?;
+
+        let mut hasher = Sha256::new();
+        let mut entries = Vec::new();
+        for line in BufReader::new(File::open(path)?).lines() {
+            let line = line?;
+            hasher.update(line.as_bytes());
+            hasher.update(b"\n");
+            entries.push(serde_json::from_str::<RecordedEntry>(&line)?);
+        }
+
+        let sha256 = hex::encode(hasher.finalize());
+        if sha256 != manifest.sha256 {
+            return Err(ExchangeError::IoError(format!("{}: checksum {} does not match manifest {}", path.display(), sha256, manifest.sha256)));
+        }
+        let gaps = entries.iter().filter(|e| matches!(e, RecordedEntry::Gap { .. })).count() as u64;
+        let in_order = entries.iter().enumerate().all(|(i, e)| e.seq() == i as u64);
+        if entries.len() as u64 != manifest.entries || gaps != manifest.gaps || !in_order {
+            return Err(ExchangeError::IoError(format!("{}: entries do not match manifest", path.display())));
+        }
+        Ok(Self { manifest, entries })
+    }
+
+    /// Gap markers with their position in the recording
+    pub fn gaps(&self) -> impl Iterator<Item = (u64, &DataGap)> {
+        self.entries.iter().filter_map(|entry| match entry {
+            RecordedEntry::Gap { seq, gap, .. } => Some((*seq, gap)),
+            RecordedEntry::Frame { .. } => None,
+        })
+    }
+
+    /// Received frame texts in order, for replay
+    pub fn frames(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().filter_map(|entry| match entry {
+            RecordedEntry::Frame { text, .. } => Some(text.as_str()),
+            RecordedEntry::Gap { .. } => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binance::{BinanceConfig, BinanceWebSocketClient};
+    use crate::types::ExchangeId;
+
+    fn diff(first: u64, last: u64) -> String {
+        format!(r#"{{"e":"depthUpdate","E":1,"s":"BTCUSDT","U":{first},"u":{last},"b":[["50000.00","1"]],"a":[]}}"#)
+    }
+
+    #[test]
+    fn test_records_gaps_and_verifies_manifest() {
+        let dir = std::env::temp_dir().join(format!("sriquant-recorder-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("btcusdt.jsonl");
+
+        let client = BinanceWebSocketClient::new(BinanceConfig::testnet());
+        let mut recorder = StreamRecorder::create(&path).unwrap();
+        let mut record = |text: String| {
+            let event = client.process_message_content(&text).ok();
+            recorder.record(&text, event.as_ref()).unwrap()
+        };
+        assert_eq!(record(diff(100, 105)), None);
+        assert_eq!(record(diff(106, 110)), None);
+        // Stale diffs overlapping what was seen are not gaps
+        assert_eq!(record(diff(104, 110)), None);
+        assert_eq!(record(diff(115, 120)), Some(DataGap::Sequence { stream: "btcusdt@depth".to_string(), expected: 111, received: 115 }));
+        assert_eq!(record("not json".to_string()), None);
+
+        recorder.record_reconnect(&ReconnectInfo {
+            exchange: ExchangeId::Binance,
+            attempts: 1,
+            streams: vec!["btcusdt@depth".to_string()],
+            downtime_ms: 1_500,
+        }).unwrap();
+        // A new id run after the reconnect is covered by the disconnect marker
+        let text = diff(500, 510);
+        let event = client.process_message_content(&text).ok();
+        assert_eq!(recorder.record(&text, event.as_ref()).unwrap(), None);
+
+        let manifest = recorder.finish().unwrap();
+        assert_eq!((manifest.entries, manifest.frames, manifest.gaps), (8, 6, 2));
+        assert!(!manifest.is_complete());
+        let depth = &manifest.streams["btcusdt@depth"];
+        assert_eq!((depth.frames, depth.gaps, depth.first_id, depth.last_id), (5, 1, Some(100), Some(510)));
+
+        let recording = Recording::load(&path).unwrap();
+        assert_eq!(recording.manifest, manifest);
+        assert_eq!(recording.gaps().map(|(seq, _)| seq).collect::<Vec<_>>(), vec![3, 6]);
+        assert_eq!(recording.frames().next(), Some(diff(100, 105).as_str()));
+
+        // Any edit to the file breaks the checksum
+        let tampered = std::fs::read_to_string(&path).unwrap().replace("50000.00", "50001.00");
+        std::fs::write(&path, tampered).unwrap();
+        assert!(Recording::load(&path).is_err());
+        std::fs::remove_file(RecordingManifest::path_for(&path)).unwrap();
+        assert!(Recording::load(&path).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,266 @@
+//! Signed, optionally encrypted parameter bundles from a remote endpoint
+//!
+//! Teams running many bots keep strategy parameters in one place and push
+//! them out, instead of editing config files on each host. A bundle is a
+//! JSON document published by a config service as:
+//!
+//! ```json
+//! {"name":"mm-fleet","version":7,"issued_at":1700000000000,
+//!  "nonce":"<base64, only when encrypted>","payload":"<base64>","signature":"<base64>"}
+//! ```
+//!
+//! `signature` is Ed25519 over `"{name}.{version}.{issued_at}.{payload}"`
+//! with `payload` as sent, so it is checked before anything is decrypted or
+//! parsed. An encrypted payload is AES-256-GCM with `name` as associated
+//! data. Bundles for another name, or not newer than the one applied, are
+//! never applied, so a replayed old bundle can't roll parameters back.
+
+use crate::errors::{ExchangeError, Result};
+use crate::http::MonoioHttpsClient;
+use sriquant_core::prelude::*;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use ring::aead::{AES_256_GCM, Aad, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::signature::{ED25519, Ed25519KeyPair, UnparsedPublicKey};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+/// A bundle as published, before verification
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignedBundle {
+    pub name: String,
+    pub version: u64,
+    /// Publisher time in milliseconds
+    pub issued_at: u64,
+    /// Present when `payload` is encrypted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    pub payload: String,
+    pub signature: String,
+}
+
+impl SignedBundle {
+    /// Sign `params`, encrypting them first when `encryption_key` is given
+    ///
+    /// For publishing tools and tests; bots only verify.
+    pub fn seal(
+        name: &str,
+        version: u64,
+        issued_at: u64,
+        params: &serde_json::Value,
+        signing_key: &Ed25519KeyPair,
+        encryption_key: Option<&[u8; 32]>,
+    ) -> Result<Self> {
+        let plaintext = serde_json::to_vec(params)?;
+        let (nonce, payload) = match encryption_key {
+            Some(key) => {
+                let mut nonce = [0u8; NONCE_LEN];
+                SystemRandom::new().fill(&mut nonce)
+                    .map_err(|_| ExchangeError::SigningError("nonce generation failed".to_string()))?;
+                let mut sealed = plaintext;
+                aead_key(key)?
+                    .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(name.as_bytes()), &mut sealed)
+                    .map_err(|_| ExchangeError::SigningError("bundle encryption failed".to_string()))?;
+                (Some(BASE64.encode(nonce)), BASE64.encode(sealed))
+            }
+            None => (None, BASE64.encode(plaintext)),
+        };
+        let signature = signing_key.sign(signed_message(name, version, issued_at, &payload).as_bytes());
+        Ok(Self { name: name.to_string(), version, issued_at, nonce, payload, signature: BASE64.encode(signature.as_ref()) })
+    }
+}
+
+/// Verified parameters
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParameterBundle {
+    pub name: String,
+    pub version: u64,
+    pub issued_at: u64,
+    pub params: serde_json::Value,
+}
+
+impl ParameterBundle {
+    /// Parameter at a `/`-separated path, e.g. `"spread/min_bps"`
+    pub fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let pointer = format!("/{}", path.trim_start_matches('/'));
+        let value = self.params.pointer(&pointer)
+            .ok_or_else(|| ExchangeError::ConfigurationError(format!("{} v{} has no parameter {path}", self.name, self.version)))?;
+        serde_json::from_value(value.clone())
+            .map_err(|e| ExchangeError::ConfigurationError(format!("parameter {path}: {e}")))
+    }
+
+    pub fn get_or<T: DeserializeOwned>(&self, path: &str, default: T) -> T {
+        self.get(path).unwrap_or(default)
+    }
+}
+
+/// Pulls bundles for one name and keeps the newest verified one
+///
+/// ```rust,ignore
+/// let mut remote = RemoteConfigClient::new("https://config.example.com/bundles/mm-fleet", "mm-fleet", &public_key)?
+///     .with_decryption_key(key);
+/// remote.refresh().await?; // at startup
+/// let min_spread: f64 = remote.current().unwrap().get("spread/min_bps")?;
+/// // later, on demand
+/// if remote.refresh().await? { strategy.reload(remote.current().unwrap()); }
+/// ```
+pub struct RemoteConfigClient {
+    url: String,
+    name: String,
+    public_key: Vec<u8>,
+    decryption_key: Option<[u8; 32]>,
+    client: MonoioHttpsClient,
+    current: Option<ParameterBundle>,
+}
+
+impl RemoteConfigClient {
+    /// `public_key` is the publisher's raw 32-byte Ed25519 key
+    pub fn new(url: impl Into<String>, name: impl Into<String>, public_key: &[u8]) -> Result<Self> {
+        if public_key.len() != 32 {
+            return Err(ExchangeError::ConfigurationError(format!(
+                "Ed25519 public key must be 32 bytes, got {}", public_key.len()
+            )));
+        }
+        Ok(Self {
+            url: url.into(),
+            name: name.into(),
+            public_key: public_key.to_vec(),
+            decryption_key: None,
+            client: MonoioHttpsClient::new()?,
+            current: None,
+        })
+    }
+
+    /// AES-256 key for encrypted bundles; without one they are rejected
+    pub fn with_decryption_key(mut self, key: [u8; 32]) -> Self {
+        self.decryption_key = Some(key);
+        self
+    }
+
+    pub fn with_http_client(mut self, client: MonoioHttpsClient) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// The newest applied bundle
+    pub fn current(&self) -> Option<&ParameterBundle> {
+        self.current.as_ref()
+    }
+
+    /// Fetch the endpoint and apply its bundle; true if parameters changed
+    pub async fn refresh(&mut self) -> Result<bool> {
+        let timer = PerfTimer::start("remote_config_refresh".to_string());
+        let response = self.client.get(&self.url).await?;
+        if !(200..300).contains(&response.status) {
+            return Err(ExchangeError::HttpError(response.status, response.body));
+        }
+        let bundle: SignedBundle = serde_json::from_str(&response.body)?;
+        let applied = self.apply(bundle);
+        timer.log_elapsed();
+        applied
+    }
+
+    /// Verify `bundle` and make it current if it is newer; true if applied
+    pub fn apply(&mut self, bundle: SignedBundle) -> Result<bool> {
+        let verified = self.verify(&bundle)?;
+        if let Some(current) = &self.current
+            && verified.version <= current.version
+        {
+            if verified.version < current.version {
+                warn!("⚠️ Ignoring {} v{}: older than applied v{}", verified.name, verified.version, current.version);
+            }
+            return Ok(false);
+        }
+        info!("🔧 Applied {} v{} issued at {}", verified.name, verified.version, verified.issued_at);
+        self.current = Some(verified);
+        Ok(true)
+    }
+
+    /// Check name and signature, then decrypt and parse the payload
+    pub fn verify(&self, bundle: &SignedBundle) -> Result<ParameterBundle> {
+        if bundle.name != self.name {
+            return Err(ExchangeError::ConfigurationError(format!(
+                "bundle is for {:?}, expected {:?}", bundle.name, self.name
+            )));
+        }
+        let signature = decode("signature", &bundle.signature)?;
+        UnparsedPublicKey::new(&ED25519, &self.public_key)
+            .verify(signed_message(&bundle.name, bundle.version, bundle.issued_at, &bundle.payload).as_bytes(), &signature)
+            .map_err(|_| ExchangeError::SigningError(format!("{} v{} signature verification failed", bundle.name, bundle.version)))?;
+
+        let mut payload = decode("payload", &bundle.payload)?;
+        let plaintext = match (&bundle.nonce, &self.decryption_key) {
+            (None, _) => &payload[..],
+            (Some(_), None) => {
+                return Err(ExchangeError::ConfigurationError(format!("{} is encrypted and no decryption key is set", bundle.name)));
+            }
+            (Some(nonce), Some(key)) => {
+                let nonce = Nonce::try_assume_unique_for_key(&decode("nonce", nonce)?)
+                    .map_err(|_| ExchangeError::InvalidResponse("bundle nonce must be 12 bytes".to_string()))?;
+                aead_key(key)?
+                    .open_in_place(nonce, Aad::from(bundle.name.as_bytes()), &mut payload)
+                    .map_err(|_| ExchangeError::SigningError(format!("{} v{} failed to decrypt", bundle.name, bundle.version)))?
+            }
+        };
+        Ok(ParameterBundle {
+            name: bundle.name.clone(),
+            version: bundle.version,
+            issued_at: bundle.issued_at,
+            params: serde_json::from_slice(plaintext)?,
+        })
+    }
+}
+
+fn signed_message(name: &str, version: u64, issued_at: u64, payload: &str) -> String {
+    format!("{name}.{version}.{issued_at}.{payload}")
+}
+
+fn aead_key(key: &[u8; 32]) -> Result<LessSafeKey> {
+    UnboundKey::new(&AES_256_GCM, key)
+        .map(LessSafeKey::new)
+        .map_err(|_| ExchangeError::ConfigurationError("invalid AES-256 key".to_string()))
+}
+
+fn decode(field: &str, value: &str) -> Result<Vec<u8>> {
+    BASE64.decode(value).map_err(|_| ExchangeError::InvalidResponse(format!("bundle {field} is not valid base64")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::signature::KeyPair;
+    use serde_json::json;
+
+    #[monoio::test]
+    async fn test_applies_only_verified_newer_bundles() {
+        let signer = Ed25519KeyPair::from_seed_unchecked(&[7u8; 32]).unwrap();
+        let key = [3u8; 32];
+        let mut remote = RemoteConfigClient::new("https://config.invalid/mm", "mm", signer.public_key().as_ref())
+            .unwrap()
+            .with_decryption_key(key);
+
+        let v2 = SignedBundle::seal("mm", 2, 1_000, &json!({"spread": {"min_bps": 4.5}}), &signer, Some(&key)).unwrap();
+        assert!(v2.nonce.is_some());
+        assert!(remote.apply(v2.clone()).unwrap());
+        assert_eq!(remote.current().unwrap().get::<f64>("spread/min_bps").unwrap(), 4.5);
+        assert_eq!(remote.current().unwrap().get_or("spread/max_bps", 10.0), 10.0);
+
+        // Replays and older versions don't roll back
+        let v1 = SignedBundle::seal("mm", 1, 900, &json!({"spread": {"min_bps": 1.0}}), &signer, None).unwrap();
+        assert!(!remote.apply(v2).unwrap());
+        assert!(!remote.apply(v1).unwrap());
+        assert_eq!(remote.current().unwrap().version, 2);
+
+        // Tampered payload, wrong signer, wrong name
+        let mut tampered = SignedBundle::seal("mm", 3, 1_100, &json!({"size": 1}), &signer, None).unwrap();
+        tampered.payload = BASE64.encode(br#"{"size":100}"#);
+        assert!(matches!(remote.apply(tampered), Err(ExchangeError::SigningError(_))));
+        let other = Ed25519KeyPair::from_seed_unchecked(&[8u8; 32]).unwrap();
+        assert!(remote.apply(SignedBundle::seal("mm", 3, 1_100, &json!({}), &other, None).unwrap()).is_err());
+        assert!(remote.apply(SignedBundle::seal("arb", 3, 1_100, &json!({}), &signer, None).unwrap()).is_err());
+        assert_eq!(remote.current().unwrap().version, 2);
+    }
+}
@@ -0,0 +1,382 @@
+//! Execution quality reports
+//!
+//! Each order intent carries the price the strategy saw when it decided to
+//! trade (mid at intent time). Fills are compared against that decision
+//! price and slippage is aggregated per strategy and symbol.
+//!
+//! Intents may also name the signal that produced them. Fills are then
+//! attributed per signal as well: each signal keeps its own position per
+//! symbol, so realized PnL, hit rate (share of position-reducing fills that
+//! closed at a profit) and slippage show which components of a multi-signal
+//! strategy actually make money. Untagged intents count under `untagged`.
+
+use crate::types::OrderSide;
+use sriquant_core::prelude::*;
+
+use std::collections::{BTreeMap, HashMap};
+
+/// Signal name for fills whose intent carries none
+pub const UNTAGGED_SIGNAL: &str = "untagged";
+
+/// An order a strategy decided to send, with the market it saw
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrderIntent {
+    /// Client order id, or a paper order key
+    pub key: String,
+    pub strategy: String,
+    pub symbol: String,
+    pub side: OrderSide,
+    /// Mid (or touch) when the decision was made
+    pub decision_price: Option<Fixed>,
+    pub decided_at: u64,
+    /// Signal that produced the order, for per-signal attribution
+    #[serde(default)]
+    pub signal: Option<String>,
+}
+
+impl OrderIntent {
+    pub fn with_signal(mut self, signal: &str) -> Self {
+        self.signal = Some(signal.to_string());
+        self
+    }
+
+    /// The intent's signal, or `UNTAGGED_SIGNAL`
+    pub fn signal_name(&self) -> &str {
+        self.signal.as_deref().unwrap_or(UNTAGGED_SIGNAL)
+    }
+}
+
+/// Slippage of one fill against its intent's decision price
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FillSlippage {
+    /// Positive = filled worse than the decision price
+    pub slippage_bps: f64,
+    /// Slippage cost in quote currency
+    pub cost: f64,
+}
+
+/// Aggregated slippage for one strategy/symbol
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SlippageStats {
+    pub fills: usize,
+    pub filled_quantity: f64,
+    /// Quantity-weighted mean slippage
+    pub mean_bps: f64,
+    pub worst_bps: f64,
+    /// Total slippage cost in quote currency
+    pub total_cost: f64,
+    /// Fills whose intent had no decision price
+    pub unpriced_fills: usize,
+}
+
+/// Slippage per (strategy, symbol)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SlippageReport {
+    pub entries: BTreeMap<String, BTreeMap<String, SlippageStats>>,
+}
+
+impl SlippageReport {
+    pub fn stats(&self, strategy: &str, symbol: &str) -> Option<&SlippageStats> {
+        self.entries.get(strategy)?.get(symbol)
+    }
+}
+
+impl std::fmt::Display for SlippageReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (strategy, symbols) in &self.entries {
+            for (symbol, stats) in symbols {
+                writeln!(
+                    f,
+                    "{strategy} {symbol}: {} fills, mean {:.2}bps, worst {:.2}bps, cost {:.4}",
+                    stats.fills, stats.mean_bps, stats.worst_bps, stats.total_cost,
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// PnL, hit rate and slippage of one signal across symbols
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SignalStats {
+    pub fills: usize,
+    pub filled_quantity: f64,
+    /// PnL closed out by this signal's own fills, in quote currency
+    pub realized_pnl: f64,
+    /// Open positions marked with `SlippageTracker::mark`
+    pub unrealized_pnl: f64,
+    /// Fills that reduced or flipped the signal's position
+    pub closing_fills: usize,
+    /// Closing fills that realized a profit
+    pub winning_fills: usize,
+    /// Quantity-weighted mean slippage over priced fills
+    pub mean_slippage_bps: f64,
+    pub slippage_cost: f64,
+    #[serde(skip)]
+    priced_quantity: f64,
+}
+
+impl SignalStats {
+    /// Share of closing fills that realized a profit; `None` before any closed
+    pub fn hit_rate(&self) -> Option<f64> {
+        (self.closing_fills > 0).then(|| self.winning_fills as f64 / self.closing_fills as f64)
+    }
+
+    pub fn total_pnl(&self) -> f64 {
+        self.realized_pnl + self.unrealized_pnl
+    }
+}
+
+/// Attribution per signal name
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SignalReport {
+    pub signals: BTreeMap<String, SignalStats>,
+}
+
+impl SignalReport {
+    pub fn stats(&self, signal: &str) -> Option<&SignalStats> {
+        self.signals.get(signal)
+    }
+}
+
+impl std::fmt::Display for SignalReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (signal, stats) in &self.signals {
+            let hit_rate = stats.hit_rate().map_or("-".to_string(), |h| format!("{:.1}%", h * 100.0));
+            writeln!(
+                f,
+                "{signal}: {} fills, pnl {:.4} ({:.4} realized), hit rate {hit_rate}, slippage {:.2}bps",
+                stats.fills, stats.total_pnl(), stats.realized_pnl, stats.mean_slippage_bps,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A signal's position in one symbol
+#[derive(Debug, Clone, Copy, Default)]
+struct SignalPosition {
+    /// Signed: positive long, negative short
+    quantity: f64,
+    average_price: f64,
+    unrealized_pnl: f64,
+}
+
+impl SignalPosition {
+    /// Apply a fill and return the PnL it realized, if it closed any quantity
+    fn apply(&mut self, side: OrderSide, price: f64, quantity: f64) -> Option<f64> {
+        let signed = match side {
+            OrderSide::Buy => quantity,
+            OrderSide::Sell => -quantity,
+        };
+        if self.quantity == 0.0 || self.quantity.signum() == signed.signum() {
+            let total = self.quantity + signed;
+            self.average_price = (self.average_price * self.quantity.abs() + price * quantity) / total.abs();
+            self.quantity = total;
+            return None;
+        }
+
+        let closed = quantity.min(self.quantity.abs());
+        let realized = closed * (price - self.average_price) * self.quantity.signum();
+        self.quantity += signed;
+        if self.quantity.abs() < f64::EPSILON {
+            self.quantity = 0.0;
+            self.average_price = 0.0;
+        } else if self.quantity.signum() == signed.signum() {
+            // Flipped: the remainder opens at the fill price
+            self.average_price = price;
+        }
+        Some(realized)
+    }
+}
+
+/// Matches fills to intents and accumulates slippage
+#[derive(Debug, Default)]
+pub struct SlippageTracker {
+    intents: HashMap<String, OrderIntent>,
+    report: SlippageReport,
+    signals: SignalReport,
+    /// (signal, symbol) -> position
+    positions: HashMap<(String, String), SignalPosition>,
+}
+
+impl SlippageTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_intent(&mut self, intent: OrderIntent) {
+        self.intents.insert(intent.key.clone(), intent);
+    }
+
+    /// Forget an intent once its order is done (filled or canceled)
+    pub fn close_intent(&mut self, key: &str) -> Option<OrderIntent> {
+        self.intents.remove(key)
+    }
+
+    /// Score a (partial) fill; `None` if the intent is unknown or unpriced
+    pub fn record_fill(&mut self, key: &str, price: Fixed, quantity: Fixed) -> Option<FillSlippage> {
+        let intent = self.intents.get(key)?;
+        let signal = intent.signal_name().to_string();
+        let position = self.positions.entry((signal.clone(), intent.symbol.clone())).or_default();
+        let realized = position.apply(intent.side, price.to_f64_lossy(), quantity.to_f64_lossy());
+        let signal_stats = self.signals.signals.entry(signal).or_default();
+        signal_stats.fills += 1;
+        signal_stats.filled_quantity += quantity.to_f64_lossy();
+        if let Some(pnl) = realized {
+            signal_stats.realized_pnl += pnl;
+            signal_stats.closing_fills += 1;
+            if pnl > 0.0 {
+                signal_stats.winning_fills += 1;
+            }
+        }
+
+        let stats = self
+            .report
+            .entries
+            .entry(intent.strategy.clone())
+            .or_default()
+            .entry(intent.symbol.clone())
+            .or_default();
+        stats.fills += 1;
+
+        let Some(decision) = intent.decision_price.map(|p| p.to_f64_lossy()).filter(|p| *p > 0.0) else {
+            stats.unpriced_fills += 1;
+            return None;
+        };
+
+        let price = price.to_f64_lossy();
+        let quantity = quantity.to_f64_lossy();
+        let adverse = match intent.side {
+            OrderSide::Buy => price - decision,
+            OrderSide::Sell => decision - price,
+        };
+        let slippage = FillSlippage {
+            slippage_bps: adverse / decision * 10_000.0,
+            cost: adverse * quantity,
+        };
+
+        let priced_quantity = stats.filled_quantity;
+        stats.filled_quantity += quantity;
+        if stats.filled_quantity > 0.0 {
+            stats.mean_bps = (stats.mean_bps * priced_quantity + slippage.slippage_bps * quantity) / stats.filled_quantity;
+        }
+        if stats.fills == stats.unpriced_fills + 1 || slippage.slippage_bps > stats.worst_bps {
+            stats.worst_bps = slippage.slippage_bps;
+        }
+        stats.total_cost += slippage.cost;
+
+        let priced_quantity = signal_stats.priced_quantity;
+        signal_stats.priced_quantity += quantity;
+        if signal_stats.priced_quantity > 0.0 {
+            signal_stats.mean_slippage_bps = (signal_stats.mean_slippage_bps * priced_quantity +
+                slippage.slippage_bps * quantity) / signal_stats.priced_quantity;
+        }
+        signal_stats.slippage_cost += slippage.cost;
+        Some(slippage)
+    }
+
+    /// Mark every signal's open position in `symbol` at `price`
+    pub fn mark(&mut self, symbol: &str, price: Fixed) {
+        let price = price.to_f64_lossy();
+        for ((_, position_symbol), position) in self.positions.iter_mut() {
+            if position_symbol == symbol {
+                position.unrealized_pnl = position.quantity * (price - position.average_price);
+            }
+        }
+        for (signal, stats) in self.signals.signals.iter_mut() {
+            stats.unrealized_pnl = self.positions.iter()
+                .filter(|((position_signal, _), _)| position_signal == signal)
+                .map(|(_, position)| position.unrealized_pnl)
+                .sum();
+        }
+    }
+
+    pub fn report(&self) -> &SlippageReport {
+        &self.report
+    }
+
+    /// PnL, hit rate and slippage per signal
+    pub fn signal_report(&self) -> &SignalReport {
+        &self.signals
+    }
+
+    /// Intents still awaiting fills
+    pub fn open_intents(&self) -> usize {
+        self.intents.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testkit::fx;
+
+    fn intent(key: &str, side: OrderSide, decision: Option<&str>) -> OrderIntent {
+        OrderIntent {
+            key: key.to_string(),
+            strategy: "mm".to_string(),
+            symbol: "BTCUSDT".to_string(),
+            side,
+            decision_price: decision.map(fx),
+            decided_at: 0,
+            signal: None,
+        }
+    }
+
+    #[test]
+    fn test_slippage_aggregation() {
+        let mut tracker = SlippageTracker::new();
+        tracker.record_intent(intent("a", OrderSide::Buy, Some("100")));
+        tracker.record_intent(intent("b", OrderSide::Sell, Some("100")));
+        tracker.record_intent(intent("c", OrderSide::Buy, None));
+
+        let buy = tracker.record_fill("a", fx("100.1"), fx("1")).unwrap();
+        assert!((buy.slippage_bps - 10.0).abs() < 1e-9);
+        // Selling above the decision price is price improvement
+        let sell = tracker.record_fill("b", fx("100.05"), fx("3")).unwrap();
+        assert!((sell.slippage_bps + 5.0).abs() < 1e-9);
+        assert!(tracker.record_fill("c", fx("100"), fx("1")).is_none());
+        assert!(tracker.record_fill("unknown", fx("100"), fx("1")).is_none());
+
+        let stats = tracker.report().stats("mm", "BTCUSDT").unwrap();
+        assert_eq!(stats.fills, 3);
+        assert_eq!(stats.unpriced_fills, 1);
+        assert!((stats.mean_bps - (10.0 - 15.0) / 4.0).abs() < 1e-9);
+        assert!((stats.worst_bps - 10.0).abs() < 1e-9);
+        assert!((stats.total_cost - (0.1 - 0.15)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_signal_attribution() {
+        let mut tracker = SlippageTracker::new();
+        tracker.record_intent(intent("m1", OrderSide::Buy, Some("100")).with_signal("momentum"));
+        tracker.record_intent(intent("m2", OrderSide::Sell, Some("110")).with_signal("momentum"));
+        tracker.record_intent(intent("r1", OrderSide::Sell, Some("100")).with_signal("reversion"));
+        tracker.record_intent(intent("r2", OrderSide::Buy, None).with_signal("reversion"));
+        tracker.record_intent(intent("u", OrderSide::Buy, Some("100")));
+
+        tracker.record_fill("m1", fx("100.1"), fx("2"));
+        tracker.record_fill("m2", fx("110"), fx("1"));
+        tracker.record_fill("r1", fx("100"), fx("1"));
+        tracker.record_fill("r2", fx("105"), fx("1"));
+        tracker.record_fill("u", fx("100"), fx("1"));
+        tracker.mark("BTCUSDT", fx("120"));
+
+        let signals = tracker.signal_report();
+        let momentum = signals.stats("momentum").unwrap();
+        assert!((momentum.realized_pnl - 9.9).abs() < 1e-9);
+        assert!((momentum.unrealized_pnl - 19.9).abs() < 1e-9);
+        assert_eq!(momentum.hit_rate(), Some(1.0));
+        assert!((momentum.mean_slippage_bps - 20.0 / 3.0).abs() < 1e-9);
+
+        // Short at 100, covered at 105; the unpriced cover still counts for PnL
+        let reversion = signals.stats("reversion").unwrap();
+        assert_eq!((reversion.fills, reversion.hit_rate()), (2, Some(0.0)));
+        assert!((reversion.realized_pnl + 5.0).abs() < 1e-9);
+        assert_eq!(reversion.unrealized_pnl, 0.0);
+
+        assert_eq!(signals.stats(UNTAGGED_SIGNAL).unwrap().hit_rate(), None);
+        assert!(signals.to_string().contains("momentum: 2 fills"));
+    }
+}
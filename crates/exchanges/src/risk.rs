@@ -0,0 +1,694 @@
+//! Pre-trade risk rules
+//!
+//! `OpenNotionalLimit` caps the gross notional a symbol (and the account) can
+//! carry: every working order's remaining quantity plus the absolute position,
+//! valued at the order's limit price or the latest mark. When cancels fail and
+//! a strategy keeps re-quoting, the working notional climbs until new intents
+//! are rejected instead of stacking quotes on the book.
+//!
+//! Notional is in the quote currency as `f64`, since account-wide totals
+//! exceed the `Fixed` range.
+//!
+//! `RiskEngine` runs the per-order checks: size, notional, open orders per
+//! symbol, resulting position, a price band around the last trade, how far
+//! a limit price crosses the opposite touch and, once `set_buying_power`
+//! has been called, the collateral to fund it. Prices outside the band or
+//! cross limit are rejected, or with `PriceProtection::Clip` pulled back to
+//! the limit by `protect_price` (which `OrderManager` applies first). Its
+//! kill switch rejects everything until reset; `OrderManager::kill_switch`
+//! engages it and cancels every open order.
+
+use crate::errors::{ExchangeError, Result};
+use crate::types::*;
+use sriquant_core::prelude::*;
+
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use tracing::{debug, info, warn};
+
+/// Notional caps in quote currency
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NotionalLimitConfig {
+    /// Cap for symbols without their own entry
+    pub default_symbol_cap: Option<f64>,
+    pub symbol_caps: HashMap<String, f64>,
+    /// Cap across all symbols
+    pub account_cap: Option<f64>,
+}
+
+impl NotionalLimitConfig {
+    pub fn with_default_symbol_cap(mut self, cap: f64) -> Self {
+        self.default_symbol_cap = Some(cap);
+        self
+    }
+
+    pub fn with_symbol_cap(mut self, symbol: &str, cap: f64) -> Self {
+        self.symbol_caps.insert(symbol.to_string(), cap);
+        self
+    }
+
+    pub fn with_account_cap(mut self, cap: f64) -> Self {
+        self.account_cap = Some(cap);
+        self
+    }
+
+    fn symbol_cap(&self, symbol: &str) -> Option<f64> {
+        self.symbol_caps.get(symbol).copied().or(self.default_symbol_cap)
+    }
+}
+
+/// An accepted order still resting or in flight
+#[derive(Debug, Clone)]
+struct WorkingOrder {
+    symbol: String,
+    side: OrderSide,
+    remaining: Fixed,
+    price: Option<Fixed>,
+}
+
+/// Gross working-plus-position notional per symbol and account
+#[derive(Debug, Clone, Default)]
+pub struct OpenNotionalLimit {
+    config: NotionalLimitConfig,
+    /// Keyed by client order id (or any caller-chosen key)
+    working: HashMap<String, WorkingOrder>,
+    positions: HashMap<String, Fixed>,
+    marks: HashMap<String, Fixed>,
+}
+
+impl OpenNotionalLimit {
+    pub fn new(config: NotionalLimitConfig) -> Self {
+        Self { config, ..Self::default() }
+    }
+
+    pub fn config(&self) -> &NotionalLimitConfig {
+        &self.config
+    }
+
+    /// Latest price used to value positions and market orders
+    pub fn update_mark(&mut self, symbol: &str, price: Fixed) {
+        self.marks.insert(symbol.to_string(), price);
+    }
+
+    /// Replace the position, e.g. after reconciling with the exchange
+    pub fn set_position(&mut self, symbol: &str, quantity: Fixed) {
+        self.positions.insert(symbol.to_string(), quantity);
+    }
+
+    fn order_notional(&self, symbol: &str, quantity: Fixed, price: Option<Fixed>) -> f64 {
+        let price = price.or_else(|| self.marks.get(symbol).copied()).unwrap_or(Fixed::ZERO);
+        quantity.to_f64_lossy() * price.to_f64_lossy()
+    }
+
+    /// Working orders plus absolute position for one symbol
+    pub fn symbol_notional(&self, symbol: &str) -> f64 {
+        let working: f64 = self.working.values()
+            .filter(|o| o.symbol == symbol)
+            .map(|o| self.order_notional(symbol, o.remaining, o.price))
+            .sum();
+        let position = self.positions.get(symbol).map(|q| q.abs()).unwrap_or(Fixed::ZERO);
+        working + self.order_notional(symbol, position, None)
+    }
+
+    /// Sum of `symbol_notional` over every symbol with orders or a position
+    pub fn account_notional(&self) -> f64 {
+        let mut symbols: Vec<&str> = self.working.values().map(|o| o.symbol.as_str()).collect();
+        symbols.extend(self.positions.keys().map(String::as_str));
+        symbols.sort_unstable();
+        symbols.dedup();
+        symbols.iter().map(|s| self.symbol_notional(s)).sum()
+    }
+
+    /// Reject an intent that would take the symbol or account over its cap
+    ///
+    /// A market order with no mark price can't be valued and is rejected
+    /// whenever a cap applies.
+    pub fn check(&self, request: &OrderRequest) -> Result<()> {
+        let symbol_cap = self.config.symbol_cap(&request.symbol);
+        if symbol_cap.is_none() && self.config.account_cap.is_none() {
+            return Ok(());
+        }
+        if request.price.is_none() && !self.marks.contains_key(&request.symbol) {
+            return Err(ExchangeError::RiskLimitExceeded(format!("no mark price to value {} order", request.symbol)));
+        }
+
+        let added = self.order_notional(&request.symbol, request.quantity, request.price);
+        if let Some(cap) = symbol_cap {
+            let total = self.symbol_notional(&request.symbol) + added;
+            if total > cap {
+                warn!("🛑 {} open notional {:.2} would exceed cap {:.2}", request.symbol, total, cap);
+                return Err(ExchangeError::RiskLimitExceeded(format!(
+                    "{} open notional {:.2} exceeds cap {:.2}", request.symbol, total, cap
+                )));
+            }
+        }
+        if let Some(cap) = self.config.account_cap {
+            let total = self.account_notional() + added;
+            if total > cap {
+                warn!("🛑 Account open notional {:.2} would exceed cap {:.2}", total, cap);
+                return Err(ExchangeError::RiskLimitExceeded(format!("account open notional {:.2} exceeds cap {:.2}", total, cap)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Count an order as working until it fills or is closed
+    pub fn track(&mut self, key: &str, request: &OrderRequest) {
+        self.working.insert(key.to_string(), WorkingOrder {
+            symbol: request.symbol.clone(),
+            side: request.side,
+            remaining: request.quantity,
+            price: request.price,
+        });
+    }
+
+    /// Move filled quantity from the working order into the position
+    pub fn on_fill(&mut self, key: &str, quantity: Fixed) {
+        let Some(order) = self.working.get_mut(key) else {
+            debug!("Fill for untracked order {}", key);
+            return;
+        };
+        let filled = quantity.min(order.remaining);
+        order.remaining -= filled;
+        let (symbol, side, done) = (order.symbol.clone(), order.side, order.remaining <= Fixed::ZERO);
+        if done {
+            self.working.remove(key);
+        }
+
+        let position = self.positions.entry(symbol).or_insert(Fixed::ZERO);
+        match side {
+            OrderSide::Buy => *position += filled,
+            OrderSide::Sell => *position -= filled,
+        }
+    }
+
+    /// Stop counting an order (canceled, rejected or expired)
+    pub fn close(&mut self, key: &str) {
+        self.working.remove(key);
+    }
+
+    /// Stop counting every order on a symbol, e.g. after a cancel-all
+    pub fn close_symbol(&mut self, symbol: &str) {
+        self.working.retain(|_, o| o.symbol != symbol);
+    }
+
+    pub fn working_count(&self) -> usize {
+        self.working.len()
+    }
+}
+
+/// Per-order limits; `None` disables a check
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RiskLimits {
+    pub max_order_quantity: Option<Fixed>,
+    /// Quote currency
+    pub max_order_notional: Option<f64>,
+    pub max_open_orders_per_symbol: Option<usize>,
+    /// Absolute net position after the order fills
+    pub max_position: Option<Fixed>,
+    /// Largest distance of a limit price from the last trade, in percent
+    pub price_band_pct: Option<f64>,
+    /// Furthest a limit price may cross the opposite best price, in ticks
+    pub max_cross_ticks: Option<u32>,
+    /// What happens to an aggressive price beyond the band or cross limit
+    #[serde(default)]
+    pub price_protection: PriceProtection,
+}
+
+/// Handling of limit prices that fail the band or trade-through checks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PriceProtection {
+    #[default]
+    Reject,
+    /// Move the price back to the furthest allowed tick
+    Clip,
+}
+
+impl RiskLimits {
+    pub fn with_max_order_quantity(mut self, quantity: Fixed) -> Self {
+        self.max_order_quantity = Some(quantity);
+        self
+    }
+
+    pub fn with_max_order_notional(mut self, notional: f64) -> Self {
+        self.max_order_notional = Some(notional);
+        self
+    }
+
+    pub fn with_max_open_orders_per_symbol(mut self, count: usize) -> Self {
+        self.max_open_orders_per_symbol = Some(count);
+        self
+    }
+
+    pub fn with_max_position(mut self, quantity: Fixed) -> Self {
+        self.max_position = Some(quantity);
+        self
+    }
+
+    pub fn with_price_band_pct(mut self, pct: f64) -> Self {
+        self.price_band_pct = Some(pct);
+        self
+    }
+
+    pub fn with_max_cross_ticks(mut self, ticks: u32) -> Self {
+        self.max_cross_ticks = Some(ticks);
+        self
+    }
+
+    pub fn with_price_protection(mut self, protection: PriceProtection) -> Self {
+        self.price_protection = protection;
+        self
+    }
+}
+
+/// Collateral available to fund new orders
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BuyingPower {
+    /// Each order is funded by the free balance of the asset it spends
+    Spot { free: HashMap<String, f64> },
+    /// Portfolio margin: every asset backs every order, so orders are
+    /// checked against the unified account's available margin in USD
+    UnifiedMargin { available_usd: f64 },
+}
+
+/// Pre-trade checks plus a kill switch
+#[derive(Debug, Clone, Default)]
+pub struct RiskEngine {
+    limits: RiskLimits,
+    last_trades: HashMap<String, Fixed>,
+    positions: HashMap<String, Fixed>,
+    kill_reason: Option<String>,
+    buying_power: Option<BuyingPower>,
+    /// Symbol -> (base, quote), for spot balance checks
+    pairs: HashMap<String, (String, String)>,
+    /// Symbol -> (best bid, best ask)
+    quotes: HashMap<String, (Fixed, Fixed)>,
+    tick_sizes: HashMap<String, Fixed>,
+}
+
+impl RiskEngine {
+    pub fn new(limits: RiskLimits) -> Self {
+        Self { limits, ..Self::default() }
+    }
+
+    pub fn limits(&self) -> &RiskLimits {
+        &self.limits
+    }
+
+    /// Register base/quote assets so spot balance checks know what an order
+    /// spends, and price precision as the tick size
+    pub fn with_symbols<'a>(mut self, symbols: impl IntoIterator<Item = &'a Symbol>) -> Self {
+        for symbol in symbols {
+            self.pairs.insert(symbol.symbol.clone(), (symbol.base_asset.clone(), symbol.quote_asset.clone()));
+            // 10^-precision, built directly: 10^precision itself leaves the Fixed range from 6 places on
+            let tick = Fixed::from(Decimal::new(1, symbol.price_precision.min(Decimal::MAX_SCALE)));
+            self.tick_sizes.entry(symbol.symbol.clone()).or_insert(tick);
+        }
+        self
+    }
+
+    /// Tick size for the cross limit, e.g. the symbol's `PRICE_FILTER` tick;
+    /// without one a limit price may not cross the opposite touch at all
+    pub fn with_tick_size(mut self, symbol: &str, tick: Fixed) -> Self {
+        self.tick_sizes.insert(symbol.to_string(), tick);
+        self
+    }
+
+    /// Check orders against `power` from now on, e.g. after each account refresh
+    pub fn set_buying_power(&mut self, power: BuyingPower) {
+        self.buying_power = Some(power);
+    }
+
+    pub fn buying_power(&self) -> Option<&BuyingPower> {
+        self.buying_power.as_ref()
+    }
+
+    /// Latest trade price, the centre of the price band
+    pub fn on_trade(&mut self, symbol: &str, price: Fixed) {
+        self.last_trades.insert(symbol.to_string(), price);
+    }
+
+    /// Latest best bid and ask, the reference for the cross limit
+    pub fn on_quote(&mut self, symbol: &str, bid: Fixed, ask: Fixed) {
+        self.quotes.insert(symbol.to_string(), (bid, ask));
+    }
+
+    /// Replace the position, e.g. after reconciling with the exchange
+    pub fn set_position(&mut self, symbol: &str, quantity: Fixed) {
+        self.positions.insert(symbol.to_string(), quantity);
+    }
+
+    pub fn on_fill(&mut self, symbol: &str, side: OrderSide, quantity: Fixed) {
+        let position = self.positions.entry(symbol.to_string()).or_insert(Fixed::ZERO);
+        match side {
+            OrderSide::Buy => *position += quantity,
+            OrderSide::Sell => *position -= quantity,
+        }
+    }
+
+    pub fn position(&self, symbol: &str) -> Fixed {
+        self.positions.get(symbol).copied().unwrap_or(Fixed::ZERO)
+    }
+
+    /// Reject every order until `reset`
+    pub fn kill(&mut self, reason: &str) {
+        warn!("🛑 Kill switch engaged: {}", reason);
+        self.kill_reason = Some(reason.to_string());
+    }
+
+    pub fn is_killed(&self) -> bool {
+        self.kill_reason.is_some()
+    }
+
+    pub fn reset(&mut self) {
+        if let Some(reason) = self.kill_reason.take() {
+            info!("✅ Kill switch reset (was: {})", reason);
+        }
+    }
+
+    /// Validate an order given how many orders are already open on its symbol
+    pub fn check(&self, request: &OrderRequest, open_orders: usize) -> Result<()> {
+        let reject = |reason: String| {
+            warn!("🛑 Rejecting {} {} {}: {}", request.symbol, request.side, request.quantity, reason);
+            Err(ExchangeError::RiskLimitExceeded(reason))
+        };
+        let limits = &self.limits;
+
+        if let Some(reason) = &self.kill_reason {
+            return reject(format!("kill switch engaged: {reason}"));
+        }
+        if let Some(max) = limits.max_order_quantity
+            && request.quantity > max
+        {
+            return reject(format!("quantity {} exceeds max {}", request.quantity, max));
+        }
+        if let Some(max) = limits.max_open_orders_per_symbol
+            && open_orders >= max
+        {
+            return reject(format!("{} open orders on {} (max {})", open_orders, request.symbol, max));
+        }
+
+        let last_trade = self.last_trades.get(&request.symbol).copied();
+        if let Some(max) = limits.max_order_notional {
+            let Some(price) = request.price.or(last_trade) else {
+                return reject(format!("no price to value {} order", request.symbol));
+            };
+            let notional = request.quantity.to_f64_lossy() * price.to_f64_lossy();
+            if notional > max {
+                return reject(format!("notional {notional:.2} exceeds max {max:.2}"));
+            }
+        }
+
+        if let Some(max) = limits.max_position {
+            let current = self.position(&request.symbol);
+            let projected = match request.side {
+                OrderSide::Buy => current + request.quantity,
+                OrderSide::Sell => current - request.quantity,
+            };
+            // Orders that shrink the position are always allowed
+            if projected.abs() > max && projected.abs() > current.abs() {
+                return reject(format!("position would reach {projected} (max {max})"));
+            }
+        }
+
+        // Under `Clip` the caller should have applied `protect_price` already
+        if let (Some(price), Some(allowed)) = (request.price, self.protect_price(request)?) {
+            return reject(format!("price {price} beyond protection limit {allowed}"));
+        }
+        if let (Some(band), Some(price), Some(last)) = (limits.price_band_pct, request.price, last_trade)
+            && last > Fixed::ZERO
+        {
+            let distance = (price.to_f64_lossy() - last.to_f64_lossy()).abs() / last.to_f64_lossy() * 100.0;
+            if distance > band {
+                return reject(format!("price {price} is {distance:.2}% from last trade {last} (band {band}%)"));
+            }
+        }
+
+        if let Some(power) = &self.buying_power {
+            self.check_buying_power(power, request, request.price.or(last_trade))?;
+        }
+        Ok(())
+    }
+
+    /// Furthest price an aggressive limit order may have, when its price is
+    /// beyond it
+    ///
+    /// Buys are capped at `ask + max_cross_ticks` and the top of the band,
+    /// sells floored at `bid - max_cross_ticks` and the bottom of the band.
+    /// Under `PriceProtection::Reject` a breach is an error instead; `Ok(None)`
+    /// means the price is fine as it is.
+    pub fn protect_price(&self, request: &OrderRequest) -> Result<Option<Fixed>> {
+        let Some(price) = request.price else { return Ok(None) };
+        let limits = &self.limits;
+        let tick = self.tick_sizes.get(&request.symbol).copied().unwrap_or(Fixed::ZERO);
+        let mut allowed: Option<(Fixed, String)> = None;
+        let mut tighten = |limit: Fixed, reason: String| {
+            let tighter = match (&allowed, request.side) {
+                (None, _) => true,
+                (Some((current, _)), OrderSide::Buy) => limit < *current,
+                (Some((current, _)), OrderSide::Sell) => limit > *current,
+            };
+            if tighter {
+                allowed = Some((limit, reason));
+            }
+        };
+
+        if let (Some(ticks), Some((bid, ask))) = (limits.max_cross_ticks, self.quotes.get(&request.symbol)) {
+            let reach = tick * Fixed::from_i64(i64::from(ticks))?;
+            match request.side {
+                OrderSide::Buy if price > *ask + reach => tighten(*ask + reach, format!("{ticks} ticks through ask {ask}")),
+                OrderSide::Sell if price < *bid - reach => tighten(*bid - reach, format!("{ticks} ticks through bid {bid}")),
+                _ => {}
+            }
+        }
+        if let (Some(band), Some(last)) = (limits.price_band_pct, self.last_trades.get(&request.symbol))
+            && *last > Fixed::ZERO
+        {
+            let factor = match request.side {
+                OrderSide::Buy => 1.0 + band / 100.0,
+                OrderSide::Sell => 1.0 - band / 100.0,
+            };
+            let edge = Fixed::from_f64(last.to_f64_lossy() * factor)?;
+            let edge = match request.side {
+                OrderSide::Buy => floor_to_tick(edge, tick),
+                OrderSide::Sell => ceil_to_tick(edge, tick),
+            };
+            match request.side {
+                OrderSide::Buy if price > edge => tighten(edge, format!("{band}% above last trade {last}")),
+                OrderSide::Sell if price < edge => tighten(edge, format!("{band}% below last trade {last}")),
+                _ => {}
+            }
+        }
+
+        let Some((limit, reason)) = allowed else { return Ok(None) };
+        match limits.price_protection {
+            PriceProtection::Reject => {
+                warn!("🛑 Rejecting {} {} @ {}: beyond {} ({})", request.symbol, request.side, price, limit, reason);
+                Err(ExchangeError::RiskLimitExceeded(format!("price {price} beyond {limit}: {reason}")))
+            }
+            PriceProtection::Clip => {
+                warn!("✂️ Clipping {} {} from {} to {} ({})", request.symbol, request.side, price, limit, reason);
+                Ok(Some(limit))
+            }
+        }
+    }
+
+    /// Orders without a price to value them, or on spot symbols with unknown
+    /// assets, pass; the exchange has the final say
+    fn check_buying_power(&self, power: &BuyingPower, request: &OrderRequest, price: Option<Fixed>) -> Result<()> {
+        let quantity = request.quantity.to_f64_lossy();
+        let notional = price.map(|p| quantity * p.to_f64_lossy());
+        let (needed, available, asset) = match power {
+            BuyingPower::Spot { free } => {
+                let Some((base, quote)) = self.pairs.get(&request.symbol) else { return Ok(()) };
+                let (asset, needed) = match request.side {
+                    OrderSide::Buy => (quote, notional),
+                    OrderSide::Sell => (base, Some(quantity)),
+                };
+                (needed, free.get(asset).copied().unwrap_or(0.0), asset.as_str())
+            }
+            BuyingPower::UnifiedMargin { available_usd } => (notional, *available_usd, "unified margin"),
+        };
+        match needed {
+            Some(needed) if needed > available => {
+                warn!("🛑 Rejecting {} {} {}: needs {:.8} {}, {:.8} available", request.symbol, request.side, request.quantity, needed, asset, available);
+                Err(ExchangeError::InsufficientBalance)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Largest multiple of `tick` not above `price`
+fn floor_to_tick(price: Fixed, tick: Fixed) -> Fixed {
+    if tick <= Fixed::ZERO {
+        return price;
+    }
+    (price / tick).trunc_with_scale(0) * tick
+}
+
+/// Smallest multiple of `tick` not below `price`
+fn ceil_to_tick(price: Fixed, tick: Fixed) -> Fixed {
+    let floor = floor_to_tick(price, tick);
+    if floor < price { floor + tick } else { floor }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testkit::fx;
+
+    fn quote(side: OrderSide, price: &str) -> OrderRequest {
+        OrderRequest {
+            symbol: "BTCUSDT".to_string(),
+            side,
+            order_type: OrderType::Limit,
+            quantity: fx("0.1"),
+            price: Some(fx(price)),
+            stop_price: None,
+            time_in_force: None,
+            client_order_id: None,
+        }
+    }
+
+    #[test]
+    fn test_stacked_quotes_hit_symbol_cap() {
+        let config = NotionalLimitConfig::default().with_symbol_cap("BTCUSDT", 25_000.0);
+        let mut limit = OpenNotionalLimit::new(config);
+        limit.update_mark("BTCUSDT", fx("100000"));
+
+        // Cancels failing: each re-quote adds another 10k of working notional
+        limit.check(&quote(OrderSide::Buy, "100000")).unwrap();
+        limit.track("a", &quote(OrderSide::Buy, "100000"));
+        limit.check(&quote(OrderSide::Sell, "100000")).unwrap();
+        limit.track("b", &quote(OrderSide::Sell, "100000"));
+        assert!(matches!(limit.check(&quote(OrderSide::Buy, "100000")), Err(ExchangeError::RiskLimitExceeded(_))));
+
+        // A fill moves notional into the position; closing the other frees room
+        limit.on_fill("a", fx("0.1"));
+        limit.close("b");
+        assert_eq!(limit.working_count(), 0);
+        assert!((limit.symbol_notional("BTCUSDT") - 10_000.0).abs() < 1e-6);
+        limit.check(&quote(OrderSide::Buy, "100000")).unwrap();
+    }
+
+    #[test]
+    fn test_account_cap_spans_symbols() {
+        let mut limit = OpenNotionalLimit::new(NotionalLimitConfig::default().with_account_cap(15_000.0));
+        limit.set_position("ETHUSDT", fx("-2"));
+        limit.update_mark("ETHUSDT", fx("4000"));
+        assert!((limit.account_notional() - 8_000.0).abs() < 1e-6);
+        assert!(limit.check(&OrderRequest { quantity: fx("0.05"), ..quote(OrderSide::Buy, "100000") }).is_ok());
+        assert!(limit.check(&OrderRequest { quantity: fx("0.1"), price: Some(fx("80000")), ..quote(OrderSide::Buy, "0") }).is_err());
+
+        let market = OrderRequest { order_type: OrderType::Market, price: None, ..quote(OrderSide::Buy, "0") };
+        assert!(limit.check(&market).is_err());
+    }
+
+    #[test]
+    fn test_risk_engine_checks_and_kill_switch() {
+        let limits = RiskLimits::default()
+            .with_max_order_quantity(fx("1"))
+            .with_max_order_notional(50_000.0)
+            .with_max_open_orders_per_symbol(2)
+            .with_max_position(fx("0.15"))
+            .with_price_band_pct(5.0);
+        let mut engine = RiskEngine::new(limits);
+        engine.on_trade("BTCUSDT", fx("100000"));
+
+        engine.check(&quote(OrderSide::Buy, "100000"), 0).unwrap();
+        assert!(engine.check(&OrderRequest { quantity: fx("2"), ..quote(OrderSide::Buy, "100") }, 0).is_err());
+        assert!(engine.check(&OrderRequest { quantity: fx("0.6"), ..quote(OrderSide::Buy, "100000") }, 0).is_err());
+        assert!(engine.check(&quote(OrderSide::Buy, "100000"), 2).is_err());
+        assert!(engine.check(&quote(OrderSide::Buy, "94000"), 0).is_err());
+
+        // Long 0.1: another buy breaches the position cap, a sell reduces it
+        engine.on_fill("BTCUSDT", OrderSide::Buy, fx("0.1"));
+        assert!(engine.check(&quote(OrderSide::Buy, "100000"), 0).is_err());
+        engine.check(&quote(OrderSide::Sell, "100000"), 0).unwrap();
+
+        engine.kill("manual");
+        assert!(matches!(engine.check(&quote(OrderSide::Sell, "100000"), 0), Err(ExchangeError::RiskLimitExceeded(_))));
+        engine.reset();
+        engine.check(&quote(OrderSide::Sell, "100000"), 0).unwrap();
+    }
+
+    #[test]
+    fn test_trade_through_and_band_protection() {
+        let limits = RiskLimits::default().with_max_cross_ticks(5).with_price_band_pct(2.0);
+        let mut engine = RiskEngine::new(limits.clone()).with_tick_size("BTCUSDT", fx("0.1"));
+        engine.on_trade("BTCUSDT", fx("50000"));
+        engine.on_quote("BTCUSDT", fx("49999.9"), fx("50000"));
+
+        // Passive and slightly aggressive prices pass untouched
+        assert_eq!(engine.protect_price(&quote(OrderSide::Buy, "50000.5")).unwrap(), None);
+        engine.check(&quote(OrderSide::Sell, "49999.4"), 0).unwrap();
+        // A fat-fingered buy 10 ticks through the ask is rejected
+        assert!(matches!(engine.check(&quote(OrderSide::Buy, "50001"), 0), Err(ExchangeError::RiskLimitExceeded(_))));
+
+        let mut clipping = RiskEngine::new(limits.with_max_cross_ticks(5_000).with_price_protection(PriceProtection::Clip))
+            .with_tick_size("BTCUSDT", fx("0.1"));
+        clipping.on_trade("BTCUSDT", fx("50000"));
+        clipping.on_quote("BTCUSDT", fx("49999.9"), fx("50000"));
+        // Whichever of the cross limit and the 2% band is tighter wins
+        assert_eq!(clipping.protect_price(&quote(OrderSide::Buy, "60000")).unwrap(), Some(fx("50500")));
+        clipping.on_quote("BTCUSDT", fx("49999.9"), fx("50900"));
+        assert_eq!(clipping.protect_price(&quote(OrderSide::Buy, "60000")).unwrap(), Some(fx("51000")));
+        assert_eq!(clipping.protect_price(&quote(OrderSide::Sell, "40000")).unwrap(), Some(fx("49499.9")));
+        assert!(clipping.check(&quote(OrderSide::Buy, "60000"), 0).is_err());
+        clipping.check(&quote(OrderSide::Buy, "51000"), 0).unwrap();
+    }
+
+    #[test]
+    fn test_unified_margin_replaces_spot_free_balance() {
+        let symbol = Symbol {
+            symbol: "BTCUSDT".to_string(),
+            base_asset: "BTC".to_string(),
+            quote_asset: "USDT".to_string(),
+            status: "TRADING".to_string(),
+            min_quantity: Fixed::ZERO,
+            max_quantity: Fixed::max(),
+            quantity_precision: 5,
+            min_price: Fixed::ZERO,
+            max_price: Fixed::max(),
+            price_precision: 2,
+            min_notional: Fixed::ZERO,
+        };
+        let mut engine = RiskEngine::new(RiskLimits::default()).with_symbols([&symbol]);
+
+        // Collateral held in ETH: spot has no USDT to buy with or BTC to sell
+        engine.set_buying_power(BuyingPower::Spot { free: HashMap::from([("ETH".to_string(), 20.0)]) });
+        assert!(matches!(engine.check(&quote(OrderSide::Buy, "100000"), 0), Err(ExchangeError::InsufficientBalance)));
+        assert!(engine.check(&quote(OrderSide::Sell, "100000"), 0).is_err());
+
+        // The same ETH counts towards the unified account
+        engine.set_buying_power(BuyingPower::UnifiedMargin { available_usd: 60_000.0 });
+        engine.check(&quote(OrderSide::Buy, "100000"), 0).unwrap();
+        engine.check(&quote(OrderSide::Sell, "100000"), 0).unwrap();
+        assert!(engine.check(&OrderRequest { quantity: fx("1"), ..quote(OrderSide::Buy, "100000") }, 0).is_err());
+    }
+
+    #[test]
+    fn test_tick_from_fine_price_precision() {
+        let symbol = Symbol {
+            symbol: "BTCUSDT".to_string(),
+            base_asset: "BTC".to_string(),
+            quote_asset: "USDT".to_string(),
+            status: "TRADING".to_string(),
+            min_quantity: Fixed::ZERO,
+            max_quantity: Fixed::max(),
+            quantity_precision: 5,
+            min_price: Fixed::ZERO,
+            max_price: Fixed::max(),
+            price_precision: 8,
+            min_notional: Fixed::ZERO,
+        };
+        let limits = RiskLimits::default().with_max_cross_ticks(5);
+        let mut engine = RiskEngine::new(limits).with_symbols([&symbol]);
+        engine.on_quote("BTCUSDT", fx("49999.99"), fx("50000"));
+
+        // Five ticks of 0.00000001 through the ask, not zero
+        engine.check(&quote(OrderSide::Buy, "50000.00000005"), 0).unwrap();
+        assert!(matches!(engine.check(&quote(OrderSide::Buy, "50000.00000006"), 0), Err(ExchangeError::RiskLimitExceeded(_))));
+    }
+}
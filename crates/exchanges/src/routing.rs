@@ -0,0 +1,256 @@
+//! Latency-based order routing across order-entry transports
+//!
+//! A venue may accept orders over several transports (REST, WebSocket API,
+//! FIX). The `OrderRouter` keeps an EWMA of ack latency and a sliding window
+//! of outcomes per transport, and routes each order via the fastest transport
+//! whose recent error rate is acceptable. Unhealthy transports are benched
+//! for a cooldown and then probed again.
+
+use crate::errors::{ExchangeError, Result};
+use sriquant_core::prelude::*;
+
+use std::collections::VecDeque;
+use std::future::Future;
+use tracing::{info, warn};
+
+/// An order-entry transport
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum OrderTransport {
+    Rest,
+    WsApi,
+    Fix,
+}
+
+impl OrderTransport {
+    pub fn label(&self) -> &'static str {
+        match self {
+            OrderTransport::Rest => "REST",
+            OrderTransport::WsApi => "WS-API",
+            OrderTransport::Fix => "FIX",
+        }
+    }
+}
+
+/// Router thresholds
+#[derive(Debug, Clone)]
+pub struct RouterConfig {
+    /// Weight of the newest latency sample in the EWMA
+    pub ewma_alpha: f64,
+    /// Outcomes kept per transport for the error rate
+    pub error_window: usize,
+    /// Error rate above which a transport is benched
+    pub max_error_rate: f64,
+    /// Outcomes required before the error rate is trusted
+    pub min_samples: usize,
+    /// How long a benched transport is skipped before being retried
+    pub cooldown_nanos: u64,
+}
+
+impl Default for RouterConfig {
+    fn default() -> Self {
+        Self {
+            ewma_alpha: 0.2,
+            error_window: 50,
+            max_error_rate: 0.2,
+            min_samples: 5,
+            cooldown_nanos: 30_000_000_000,
+        }
+    }
+}
+
+impl RouterConfig {
+    pub fn with_max_error_rate(mut self, rate: f64) -> Self {
+        self.max_error_rate = rate;
+        self
+    }
+
+    pub fn with_cooldown_nanos(mut self, nanos: u64) -> Self {
+        self.cooldown_nanos = nanos;
+        self
+    }
+}
+
+/// Observed health of one transport
+#[derive(Debug, Clone)]
+pub struct TransportStats {
+    pub transport: OrderTransport,
+    /// EWMA of ack latency; `None` until the first ack
+    pub ewma_latency_micros: Option<f64>,
+    outcomes: VecDeque<bool>,
+    /// Benched until this time (nanos), if unhealthy
+    pub benched_until: Option<u64>,
+}
+
+impl TransportStats {
+    fn new(transport: OrderTransport) -> Self {
+        Self {
+            transport,
+            ewma_latency_micros: None,
+            outcomes: VecDeque::new(),
+            benched_until: None,
+        }
+    }
+
+    /// Share of failed requests in the recent window
+    pub fn error_rate(&self) -> f64 {
+        if self.outcomes.is_empty() {
+            return 0.0;
+        }
+        self.outcomes.iter().filter(|ok| !**ok).count() as f64 / self.outcomes.len() as f64
+    }
+
+    pub fn samples(&self) -> usize {
+        self.outcomes.len()
+    }
+
+    fn is_available(&self, now: u64) -> bool {
+        self.benched_until.is_none_or(|until| now >= until)
+    }
+}
+
+/// Routes orders to the fastest healthy transport
+#[derive(Debug, Clone)]
+pub struct OrderRouter {
+    config: RouterConfig,
+    transports: Vec<TransportStats>,
+}
+
+impl OrderRouter {
+    /// Router over the given transports, in preference order for ties and cold starts
+    pub fn new(transports: &[OrderTransport], config: RouterConfig) -> Self {
+        Self {
+            config,
+            transports: transports.iter().copied().map(TransportStats::new).collect(),
+        }
+    }
+
+    fn stats_mut(&mut self, transport: OrderTransport) -> Option<&mut TransportStats> {
+        self.transports.iter_mut().find(|s| s.transport == transport)
+    }
+
+    pub fn stats(&self, transport: OrderTransport) -> Option<&TransportStats> {
+        self.transports.iter().find(|s| s.transport == transport)
+    }
+
+    /// Transport for the next order
+    ///
+    /// Unmeasured transports are tried first so every route gets a latency
+    /// estimate. If everything is benched, the soonest-recovering one is used.
+    pub fn select(&self, now: u64) -> Option<OrderTransport> {
+        let available = self.transports.iter().filter(|s| s.is_available(now));
+        let mut best: Option<&TransportStats> = None;
+        for stats in available {
+            let Some(latency) = stats.ewma_latency_micros else {
+                return Some(stats.transport);
+            };
+            if best.is_none_or(|b| latency < b.ewma_latency_micros.unwrap_or(f64::MAX)) {
+                best = Some(stats);
+            }
+        }
+        best.or_else(|| self.transports.iter().min_by_key(|s| s.benched_until.unwrap_or(0)))
+            .map(|s| s.transport)
+    }
+
+    /// Record an acknowledged order and its ack latency
+    pub fn record_ack(&mut self, transport: OrderTransport, latency_micros: f64) {
+        let alpha = self.config.ewma_alpha;
+        if let Some(stats) = self.stats_mut(transport) {
+            stats.ewma_latency_micros = Some(match stats.ewma_latency_micros {
+                Some(ewma) => alpha * latency_micros + (1.0 - alpha) * ewma,
+                None => latency_micros,
+            });
+            if stats.benched_until.take().is_some() {
+                info!("✅ {} transport recovered", transport.label());
+            }
+        }
+        self.push_outcome(transport, true, nanos());
+    }
+
+    /// Record a failed order attempt
+    pub fn record_error(&mut self, transport: OrderTransport, now: u64) {
+        self.push_outcome(transport, false, now);
+    }
+
+    fn push_outcome(&mut self, transport: OrderTransport, ok: bool, now: u64) {
+        let config = self.config.clone();
+        let Some(stats) = self.stats_mut(transport) else { return };
+        stats.outcomes.push_back(ok);
+        while stats.outcomes.len() > config.error_window {
+            stats.outcomes.pop_front();
+        }
+        let unhealthy = stats.samples() >= config.min_samples && stats.error_rate() > config.max_error_rate;
+        if !ok && unhealthy {
+            if stats.benched_until.is_none() {
+                warn!(
+                    "🚧 {} transport benched: error rate {:.0}%",
+                    transport.label(),
+                    stats.error_rate() * 100.0
+                );
+            }
+            stats.benched_until = Some(now + config.cooldown_nanos);
+        }
+    }
+
+    /// Send through the selected transport, recording latency or failure
+    ///
+    /// `send` performs the request on the transport it's given.
+    pub async fn route<T, F, Fut>(&mut self, send: F) -> Result<T>
+    where
+        F: FnOnce(OrderTransport) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let transport = self
+            .select(nanos())
+            .ok_or_else(|| ExchangeError::ConfigurationError("No order transports configured".to_string()))?;
+
+        let timer = PerfTimer::start(format!("order_via_{}", transport.label()));
+        match send(transport).await {
+            Ok(value) => {
+                self.record_ack(transport, timer.elapsed_micros() as f64);
+                Ok(value)
+            }
+            Err(e) => {
+                self.record_error(transport, nanos());
+                Err(e)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_routes_to_fastest_healthy_transport() {
+        let config = RouterConfig { min_samples: 2, ..RouterConfig::default() };
+        let mut router = OrderRouter::new(&[OrderTransport::Rest, OrderTransport::WsApi], config);
+
+        // Unmeasured transports are probed first
+        assert_eq!(router.select(0), Some(OrderTransport::Rest));
+        router.record_ack(OrderTransport::Rest, 900.0);
+        assert_eq!(router.select(0), Some(OrderTransport::WsApi));
+        router.record_ack(OrderTransport::WsApi, 300.0);
+        assert_eq!(router.select(0), Some(OrderTransport::WsApi));
+
+        // Errors bench WS-API until the cooldown passes
+        router.record_error(OrderTransport::WsApi, 1_000);
+        router.record_error(OrderTransport::WsApi, 1_000);
+        assert_eq!(router.select(2_000), Some(OrderTransport::Rest));
+        assert_eq!(router.select(1_000 + 30_000_000_000), Some(OrderTransport::WsApi));
+    }
+
+    #[monoio::test]
+    async fn test_route_records_outcome() {
+        let mut router = OrderRouter::new(&[OrderTransport::Rest], RouterConfig::default());
+        let sent = router.route(|t| async move { Ok(t) }).await.unwrap();
+        assert_eq!(sent, OrderTransport::Rest);
+        assert!(router.stats(OrderTransport::Rest).unwrap().ewma_latency_micros.is_some());
+
+        let failed: Result<()> = router
+            .route(|_| async { Err(ExchangeError::NetworkError("reset".to_string())) })
+            .await;
+        assert!(failed.is_err());
+        assert_eq!(router.stats(OrderTransport::Rest).unwrap().error_rate(), 0.5);
+    }
+}
@@ -0,0 +1,780 @@
+//! Strategy runner with paper, shadow and live environments
+//!
+//! The runner feeds market data to a strategy and routes the orders it
+//! computes according to the trading environment:
+//!
+//! - `Paper`: orders fill against the paper simulator only
+//! - `Shadow`: the strategy runs on live data; orders are logged and filled by
+//!   the simulator, then compared with what the live market actually did so a
+//!   human can review divergence before going live
+//! - `Live`: orders are handed back to the caller for submission
+//!
+//! Promotion from shadow to live goes through `promote_to_live`, which checks
+//! the divergence report against `PromotionCriteria` and requires a named
+//! reviewer.
+//!
+//! With warmup gates configured (see `warmup`), market data only reaches the
+//! strategy once every gate reports ready.
+//!
+//! Every order is recorded as an `OrderIntent` with the mid at decision time
+//! so fills can be scored for slippage (see `report`). Strategies that
+//! combine several signals name the one behind each order via
+//! `Strategy::signal`, and `signal_report` attributes PnL, hit rate and
+//! slippage per signal, marked at the latest trade price.
+//!
+//! With a `BarClock` (see `bars`), `on_bar_close` fires at each bar boundary
+//! on the exchange clock, from `poll_bars` or from a closed kline that
+//! arrives first, so bar strategies don't wait on the closing kline.
+//!
+//! An optional `OpenNotionalLimit` (see `risk`) rejects orders that would push
+//! a symbol's or the account's working-plus-position notional over its cap.
+
+use crate::bars::{BarClock, BarClose};
+use crate::errors::{ExchangeError, Result};
+use crate::paper::{PaperFill, PaperFillSimulator};
+use crate::report::{FillSlippage, OrderIntent, SignalReport, SlippageReport, SlippageTracker};
+use crate::risk::OpenNotionalLimit;
+use crate::switches::TradingSwitches;
+use crate::warmup::{WarmupGate, WarmupTracker};
+use crate::types::*;
+use sriquant_core::prelude::*;
+
+use std::collections::HashMap;
+use std::io::Write;
+use tracing::{debug, info, warn};
+
+/// Where a strategy's orders go
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TradingEnvironment {
+    Paper,
+    Shadow,
+    Live,
+}
+
+impl std::fmt::Display for TradingEnvironment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TradingEnvironment::Paper => write!(f, "PAPER"),
+            TradingEnvironment::Shadow => write!(f, "SHADOW"),
+            TradingEnvironment::Live => write!(f, "LIVE"),
+        }
+    }
+}
+
+/// A trading strategy driven by market data
+pub trait Strategy {
+    /// Strategy name used in logs and reports
+    fn name(&self) -> &str;
+
+    /// React to a market data event with orders to place
+    fn on_market_data(&mut self, event: &MarketData) -> Vec<OrderRequest>;
+
+    /// Notification of a fill (simulated in paper/shadow mode)
+    fn on_fill(&mut self, _fill: &PaperFill) {}
+
+    /// Historical bars to seed indicators during warmup, oldest first
+    fn on_warmup_bars(&mut self, _symbol: &str, _bars: &[Candle]) {}
+
+    /// A bar of a `BarClock` interval closed on the exchange clock
+    fn on_bar_close(&mut self, _bar: &BarClose) -> Vec<OrderRequest> {
+        Vec::new()
+    }
+
+    /// Name of the signal behind an order just returned, for per-signal attribution
+    fn signal(&self, _request: &OrderRequest) -> Option<String> {
+        None
+    }
+}
+
+/// What the caller should do with the outcome of an event
+#[derive(Debug, Clone)]
+pub enum RunnerAction {
+    /// Live mode: send this order to the exchange
+    Submit(OrderRequest),
+    /// Paper/shadow mode: the simulator filled an order
+    PaperFill(PaperFill),
+    /// Live mode: cancel working orders for a paused symbol
+    CancelAll(String),
+}
+
+/// An order computed in shadow mode and how it would have fared
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShadowOrder {
+    pub paper_order_id: u64,
+    pub decided_at: u64,
+    pub request: OrderRequest,
+    /// Mid price when the order was computed
+    pub decision_mid: Option<Fixed>,
+    pub simulated_fill: Option<PaperFill>,
+    /// First live trade price after the decision, the market's realizable price
+    pub next_trade_price: Option<Fixed>,
+}
+
+impl ShadowOrder {
+    /// How much better the simulated fill is than the live market, in basis
+    /// points (positive = simulator optimistic)
+    pub fn divergence_bps(&self) -> Option<f64> {
+        let fill = self.simulated_fill.as_ref()?;
+        let market = self.next_trade_price?.to_f64_lossy();
+        if market <= 0.0 {
+            return None;
+        }
+        let diff = (market - fill.price.to_f64_lossy()) / market * 10_000.0;
+        Some(match self.request.side {
+            OrderSide::Buy => diff,
+            OrderSide::Sell => -diff,
+        })
+    }
+}
+
+/// Summary of shadow trading for review
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DivergenceReport {
+    pub strategy: String,
+    pub orders: usize,
+    pub simulated_fills: usize,
+    /// Orders with both a simulated fill and a subsequent live trade
+    pub compared: usize,
+    pub mean_divergence_bps: f64,
+    pub max_abs_divergence_bps: f64,
+    pub orders_by_symbol: HashMap<String, usize>,
+}
+
+impl std::fmt::Display for DivergenceReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} shadow orders, {} simulated fills, {} compared, mean divergence {:.2}bps, max {:.2}bps",
+            self.strategy,
+            self.orders,
+            self.simulated_fills,
+            self.compared,
+            self.mean_divergence_bps,
+            self.max_abs_divergence_bps,
+        )
+    }
+}
+
+/// Thresholds a shadow run must meet before going live
+#[derive(Debug, Clone)]
+pub struct PromotionCriteria {
+    pub min_shadow_orders: usize,
+    pub min_compared: usize,
+    pub max_abs_divergence_bps: f64,
+}
+
+impl Default for PromotionCriteria {
+    fn default() -> Self {
+        Self {
+            min_shadow_orders: 50,
+            min_compared: 20,
+            max_abs_divergence_bps: 10.0,
+        }
+    }
+}
+
+/// Human sign-off for going live
+#[derive(Debug, Clone)]
+pub struct PromotionApproval {
+    pub reviewer: String,
+    /// Number of shadow orders in the report the reviewer signed off on
+    pub reviewed_orders: usize,
+}
+
+/// Runs a strategy in the configured environment
+pub struct StrategyRunner<S: Strategy> {
+    strategy: S,
+    environment: TradingEnvironment,
+    simulator: PaperFillSimulator,
+    shadow_log: Vec<ShadowOrder>,
+    criteria: PromotionCriteria,
+    switches: TradingSwitches,
+    slippage: SlippageTracker,
+    warmup: WarmupTracker,
+    notional_limit: Option<OpenNotionalLimit>,
+    bar_clock: Option<BarClock>,
+}
+
+/// Intent key for a simulator order
+fn paper_key(paper_order_id: u64) -> String {
+    format!("paper-{paper_order_id}")
+}
+
+impl<S: Strategy> StrategyRunner<S> {
+    pub fn new(strategy: S, environment: TradingEnvironment) -> Self {
+        info!("🏃 Runner for {} starting in {} mode", strategy.name(), environment);
+        Self {
+            strategy,
+            environment,
+            simulator: PaperFillSimulator::new(),
+            shadow_log: Vec::new(),
+            criteria: PromotionCriteria::default(),
+            switches: TradingSwitches::new(),
+            slippage: SlippageTracker::new(),
+            warmup: WarmupTracker::default(),
+            notional_limit: None,
+            bar_clock: None,
+        }
+    }
+
+    /// Hold market data back from the strategy until these gates are ready
+    pub fn with_warmup_gates(mut self, gates: &[WarmupGate]) -> Self {
+        self.warmup = WarmupTracker::new(gates);
+        self
+    }
+
+    pub fn warmup(&self) -> &WarmupTracker {
+        &self.warmup
+    }
+
+    /// Whether the strategy is receiving market data
+    pub fn is_ready(&self) -> bool {
+        self.warmup.is_ready()
+    }
+
+    pub fn mark_gate_ready(&mut self, gate: &WarmupGate) {
+        self.warmup.mark_ready(gate);
+        if self.warmup.is_ready() {
+            info!("🚦 {} warmed up, trading enabled", self.strategy.name());
+        }
+    }
+
+    pub fn mark_gate_failed(&mut self, gate: &WarmupGate, reason: &str) {
+        self.warmup.mark_failed(gate, reason);
+    }
+
+    /// Hand historical bars to the strategy's indicators
+    pub fn seed_bars(&mut self, symbol: &str, bars: &[Candle]) {
+        debug!("🕯️  Seeding {} with {} bars", symbol, bars.len());
+        self.strategy.on_warmup_bars(symbol, bars);
+    }
+
+    /// Seed the simulator's book (and the decision prices of the first orders)
+    pub fn seed_order_book(&mut self, book: OrderBook) {
+        self.simulator.on_market_data(&MarketData::OrderBook(book));
+    }
+
+    /// Share runtime per-symbol switches with an admin interface
+    pub fn with_switches(mut self, switches: TradingSwitches) -> Self {
+        self.switches = switches;
+        self
+    }
+
+    pub fn switches(&self) -> &TradingSwitches {
+        &self.switches
+    }
+
+    /// Reject orders that would exceed open notional caps
+    pub fn with_notional_limit(mut self, limit: OpenNotionalLimit) -> Self {
+        self.notional_limit = Some(limit);
+        self
+    }
+
+    pub fn notional_limit(&self) -> Option<&OpenNotionalLimit> {
+        self.notional_limit.as_ref()
+    }
+
+    /// For reconciling positions and closing canceled live orders
+    pub fn notional_limit_mut(&mut self) -> Option<&mut OpenNotionalLimit> {
+        self.notional_limit.as_mut()
+    }
+
+    /// Call `Strategy::on_bar_close` at this clock's bar boundaries
+    pub fn with_bar_clock(mut self, bar_clock: BarClock) -> Self {
+        self.bar_clock = Some(bar_clock);
+        self
+    }
+
+    pub fn bar_clock(&self) -> Option<&BarClock> {
+        self.bar_clock.as_ref()
+    }
+
+    /// Correct bar boundaries by a newly measured exchange clock offset
+    pub fn set_clock_offset_ms(&mut self, offset_ms: i64) {
+        if let Some(bar_clock) = self.bar_clock.as_mut() {
+            bar_clock.set_offset_ms(offset_ms);
+        }
+    }
+
+    pub fn with_promotion_criteria(mut self, criteria: PromotionCriteria) -> Self {
+        self.criteria = criteria;
+        self
+    }
+
+    pub fn environment(&self) -> TradingEnvironment {
+        self.environment
+    }
+
+    pub fn strategy(&self) -> &S {
+        &self.strategy
+    }
+
+    /// Feed one market data event through the strategy
+    pub fn on_market_data(&mut self, event: &MarketData) -> Vec<RunnerAction> {
+        let now = nanos();
+        let mut actions = Vec::new();
+
+        for symbol in self.switches.take_cancellations() {
+            match self.environment {
+                TradingEnvironment::Live => actions.push(RunnerAction::CancelAll(symbol)),
+                TradingEnvironment::Paper | TradingEnvironment::Shadow => {
+                    let canceled = self.simulator.cancel_symbol(&symbol);
+                    if let Some(limit) = self.notional_limit.as_mut() {
+                        limit.close_symbol(&symbol);
+                    }
+                    debug!("⏸️  Canceled {} paper orders for paused {}", canceled, symbol);
+                }
+            }
+        }
+
+        // Live prices resolve pending shadow comparisons before anything else
+        if let MarketData::Trade(trade) = event {
+            for shadow in self.shadow_log.iter_mut().filter(|s| s.next_trade_price.is_none()) {
+                if shadow.request.symbol == trade.symbol {
+                    shadow.next_trade_price = Some(trade.price);
+                }
+            }
+            self.slippage.mark(&trade.symbol, trade.price);
+        }
+
+        // The simulator tracks top of book in every mode so live intents get a decision price
+        let paper_fills = self.simulator.on_market_data(event);
+        if self.environment != TradingEnvironment::Live {
+            for fill in paper_fills {
+                self.record_fill(&fill);
+                actions.push(RunnerAction::PaperFill(fill));
+            }
+        }
+
+        let bar_close = match (event, self.bar_clock.as_mut()) {
+            (MarketData::Kline(kline), Some(bar_clock)) => bar_clock.on_kline(kline),
+            _ => None,
+        };
+
+        if !self.warmup.is_ready() {
+            return actions;
+        }
+
+        let requests = self.strategy.on_market_data(event);
+        self.route_orders(requests, now, &mut actions);
+        if let Some(bar) = bar_close {
+            let requests = self.strategy.on_bar_close(&bar);
+            self.route_orders(requests, now, &mut actions);
+        }
+        actions
+    }
+
+    /// Fire bar closes that are due on the exchange clock
+    ///
+    /// Call this whenever `BarClock::until_next_close` elapses, e.g. as the
+    /// receive timeout of the market data loop.
+    pub fn poll_bars(&mut self) -> Vec<RunnerAction> {
+        let closes = match self.bar_clock.as_mut() {
+            Some(bar_clock) => bar_clock.poll(),
+            None => return Vec::new(),
+        };
+        let mut actions = Vec::new();
+        for bar in closes {
+            actions.extend(self.on_bar_close(&bar));
+        }
+        actions
+    }
+
+    /// Hand one bar close to the strategy and route its orders
+    pub fn on_bar_close(&mut self, bar: &BarClose) -> Vec<RunnerAction> {
+        let mut actions = Vec::new();
+        if !self.warmup.is_ready() {
+            return actions;
+        }
+        debug!("🕯️  {} bar closed at {}", bar.interval, bar.close_time);
+        let requests = self.strategy.on_bar_close(bar);
+        self.route_orders(requests, nanos(), &mut actions);
+        actions
+    }
+
+    /// Apply switches and limits to a strategy's orders and route them by environment
+    fn route_orders(&mut self, requests: Vec<OrderRequest>, now: u64, actions: &mut Vec<RunnerAction>) {
+        for request in requests {
+            if !self.switches.is_enabled(&request.symbol) {
+                debug!("⏸️  Dropping {} order for paused {}", request.side, request.symbol);
+                continue;
+            }
+
+            let decision_mid = self.simulator.top_of_book(&request.symbol).and_then(|t| t.mid());
+            if let Some(limit) = self.notional_limit.as_mut() {
+                if let Some(mid) = decision_mid {
+                    limit.update_mark(&request.symbol, mid);
+                }
+                if let Err(e) = limit.check(&request) {
+                    debug!("🛑 Dropping {} {} order: {}", request.side, request.symbol, e);
+                    continue;
+                }
+            }
+
+            match self.environment {
+                TradingEnvironment::Live => {
+                    if let Some(client_order_id) = &request.client_order_id {
+                        self.record_intent(client_order_id.clone(), &request, decision_mid, now);
+                        if let Some(limit) = self.notional_limit.as_mut() {
+                            limit.track(client_order_id, &request);
+                        }
+                    }
+                    actions.push(RunnerAction::Submit(request));
+                }
+                TradingEnvironment::Paper | TradingEnvironment::Shadow => {
+                    let (paper_order_id, fill) = self.simulator.submit(&request, now);
+                    self.record_intent(paper_key(paper_order_id), &request, decision_mid, now);
+                    if let Some(limit) = self.notional_limit.as_mut() {
+                        limit.track(&paper_key(paper_order_id), &request);
+                    }
+
+                    if self.environment == TradingEnvironment::Shadow {
+                        info!("👤 Shadow {} {} {} @ {:?}", request.side, request.quantity, request.symbol, request.price);
+                        self.shadow_log.push(ShadowOrder {
+                            paper_order_id,
+                            decided_at: now,
+                            request,
+                            decision_mid,
+                            simulated_fill: None,
+                            next_trade_price: None,
+                        });
+                    }
+
+                    if let Some(fill) = fill {
+                        self.record_fill(&fill);
+                        actions.push(RunnerAction::PaperFill(fill));
+                    }
+                }
+            }
+        }
+    }
+
+    fn record_intent(&mut self, key: String, request: &OrderRequest, decision_price: Option<Fixed>, now: u64) {
+        self.slippage.record_intent(OrderIntent {
+            key,
+            strategy: self.strategy.name().to_string(),
+            symbol: request.symbol.clone(),
+            side: request.side,
+            decision_price,
+            decided_at: now,
+            signal: self.strategy.signal(request),
+        });
+    }
+
+    fn record_fill(&mut self, fill: &PaperFill) {
+        let key = paper_key(fill.paper_order_id);
+        self.slippage.record_fill(&key, fill.price, fill.quantity);
+        self.slippage.close_intent(&key);
+        if let Some(limit) = self.notional_limit.as_mut() {
+            limit.on_fill(&key, fill.quantity);
+        }
+        if let Some(shadow) = self.shadow_log.iter_mut().find(|s| s.paper_order_id == fill.paper_order_id) {
+            shadow.simulated_fill = Some(fill.clone());
+        }
+        self.strategy.on_fill(fill);
+    }
+
+    /// Score a live fill reported by the exchange against its intent
+    ///
+    /// Pass `done` once the order is fully filled or canceled.
+    pub fn record_live_fill(&mut self, client_order_id: &str, price: Fixed, quantity: Fixed, done: bool) -> Option<FillSlippage> {
+        let slippage = self.slippage.record_fill(client_order_id, price, quantity);
+        if let Some(limit) = self.notional_limit.as_mut() {
+            limit.on_fill(client_order_id, quantity);
+            if done {
+                limit.close(client_order_id);
+            }
+        }
+        if done {
+            self.slippage.close_intent(client_order_id);
+        }
+        slippage
+    }
+
+    /// Expected vs realized slippage per strategy and symbol
+    pub fn slippage_report(&self) -> &SlippageReport {
+        self.slippage.report()
+    }
+
+    /// PnL, hit rate and slippage per strategy signal
+    pub fn signal_report(&self) -> &SignalReport {
+        self.slippage.signal_report()
+    }
+
+    /// Shadow orders in decision order
+    pub fn shadow_log(&self) -> &[ShadowOrder] {
+        &self.shadow_log
+    }
+
+    /// Export the shadow log as JSON lines for review
+    pub fn write_shadow_log<W: Write>(&self, mut writer: W) -> Result<()> {
+        for order in &self.shadow_log {
+            serde_json::to_writer(&mut writer, order)?;
+            writer.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
+    /// Summarize shadow orders against the live market
+    pub fn divergence_report(&self) -> DivergenceReport {
+        let mut report = DivergenceReport {
+            strategy: self.strategy.name().to_string(),
+            orders: self.shadow_log.len(),
+            ..Default::default()
+        };
+
+        let mut total = 0.0;
+        for order in &self.shadow_log {
+            *report.orders_by_symbol.entry(order.request.symbol.clone()).or_default() += 1;
+            if order.simulated_fill.is_some() {
+                report.simulated_fills += 1;
+            }
+            if let Some(bps) = order.divergence_bps() {
+                report.compared += 1;
+                total += bps;
+                report.max_abs_divergence_bps = report.max_abs_divergence_bps.max(bps.abs());
+            }
+        }
+        if report.compared > 0 {
+            report.mean_divergence_bps = total / report.compared as f64;
+        }
+        report
+    }
+
+    /// Move from paper trading to shadow trading on live data
+    pub fn start_shadow(&mut self) -> Result<()> {
+        if self.environment != TradingEnvironment::Paper {
+            return Err(ExchangeError::ConfigurationError(format!(
+                "Shadow mode can only start from PAPER (currently {})", self.environment
+            )));
+        }
+        self.environment = TradingEnvironment::Shadow;
+        info!("👤 {} switched to SHADOW mode", self.strategy.name());
+        Ok(())
+    }
+
+    /// Flip the environment to live once the shadow run meets the criteria and
+    /// a reviewer has signed off on the current report
+    pub fn promote_to_live(&mut self, approval: &PromotionApproval) -> Result<()> {
+        if self.environment != TradingEnvironment::Shadow {
+            return Err(ExchangeError::ConfigurationError(format!(
+                "Promotion to LIVE requires SHADOW mode (currently {})", self.environment
+            )));
+        }
+
+        let report = self.divergence_report();
+        let reject = |reason: String| {
+            warn!("🚫 Promotion of {} rejected: {}", report.strategy, reason);
+            Err(ExchangeError::ConfigurationError(reason))
+        };
+
+        if approval.reviewer.trim().is_empty() {
+            return reject("Promotion requires a named reviewer".to_string());
+        }
+        if approval.reviewed_orders != report.orders {
+            return reject(format!(
+                "Reviewer signed off on {} orders but the report has {}", approval.reviewed_orders, report.orders
+            ));
+        }
+        if report.orders < self.criteria.min_shadow_orders {
+            return reject(format!("{} shadow orders, need {}", report.orders, self.criteria.min_shadow_orders));
+        }
+        if report.compared < self.criteria.min_compared {
+            return reject(format!("{} compared fills, need {}", report.compared, self.criteria.min_compared));
+        }
+        if report.max_abs_divergence_bps > self.criteria.max_abs_divergence_bps {
+            return reject(format!(
+                "Divergence {:.2}bps exceeds {:.2}bps", report.max_abs_divergence_bps, self.criteria.max_abs_divergence_bps
+            ));
+        }
+
+        self.environment = TradingEnvironment::Live;
+        info!("🟢 {} promoted to LIVE by {} ({})", report.strategy, approval.reviewer, report);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testkit::fx;
+    use crate::risk::NotionalLimitConfig;
+
+    /// Buys at market on every trade
+    struct Chaser;
+
+    impl Strategy for Chaser {
+        fn name(&self) -> &str {
+            "chaser"
+        }
+
+        fn on_market_data(&mut self, event: &MarketData) -> Vec<OrderRequest> {
+            let MarketData::Trade(trade) = event else { return Vec::new() };
+            vec![OrderRequest {
+                symbol: trade.symbol.clone(),
+                side: OrderSide::Buy,
+                order_type: OrderType::Market,
+                quantity: fx("0.01"),
+                price: None,
+                stop_price: None,
+                time_in_force: None,
+                client_order_id: None,
+            }]
+        }
+    }
+
+    fn trade(price: &str) -> MarketData {
+        MarketData::Trade(Trade {
+            id: "1".to_string(),
+            symbol: "BTCUSDT".to_string(),
+            price: fx(price),
+            quantity: fx("0.1"),
+            side: OrderSide::Buy,
+            timestamp: 0,
+            is_buyer_maker: false,
+        })
+    }
+
+    fn approval(orders: usize) -> PromotionApproval {
+        PromotionApproval { reviewer: "ops".to_string(), reviewed_orders: orders }
+    }
+
+    #[test]
+    fn test_shadow_logs_and_compares() {
+        let mut runner = StrategyRunner::new(Chaser, TradingEnvironment::Paper)
+            .with_promotion_criteria(PromotionCriteria { min_shadow_orders: 2, min_compared: 2, max_abs_divergence_bps: 5.0 });
+        runner.start_shadow().unwrap();
+
+        for price in ["100", "100", "100"] {
+            let actions = runner.on_market_data(&trade(price));
+            assert!(actions.iter().all(|a| matches!(a, RunnerAction::PaperFill(_))));
+        }
+
+        let report = runner.divergence_report();
+        assert_eq!(report.orders, 3);
+        assert_eq!(report.simulated_fills, 3);
+        // The last order has no subsequent trade yet
+        assert_eq!(report.compared, 2);
+        assert_eq!(report.max_abs_divergence_bps, 0.0);
+
+        let slippage = runner.slippage_report().stats("chaser", "BTCUSDT").unwrap();
+        assert_eq!(slippage.fills, 3);
+        assert_eq!(slippage.mean_bps, 0.0);
+
+        assert!(runner.promote_to_live(&approval(2)).is_err());
+        runner.promote_to_live(&approval(3)).unwrap();
+        assert_eq!(runner.environment(), TradingEnvironment::Live);
+        assert!(matches!(runner.on_market_data(&trade("100"))[..], [RunnerAction::Submit(_)]));
+    }
+
+    /// Buys on every one-minute bar close
+    struct BarBuyer;
+
+    impl Strategy for BarBuyer {
+        fn name(&self) -> &str {
+            "bar-buyer"
+        }
+
+        fn on_market_data(&mut self, _event: &MarketData) -> Vec<OrderRequest> {
+            Vec::new()
+        }
+
+        fn on_bar_close(&mut self, bar: &BarClose) -> Vec<OrderRequest> {
+            assert_eq!(bar.interval, "1m");
+            Chaser.on_market_data(&trade("100"))
+        }
+    }
+
+    #[test]
+    fn test_bar_close_fires_once_per_bar() {
+        let bars = BarClock::new(crate::bars::ExchangeClock::new()).with_interval("1m").unwrap();
+        let mut runner = StrategyRunner::new(BarBuyer, TradingEnvironment::Live).with_bar_clock(bars);
+
+        let kline = MarketData::Kline(Kline {
+            symbol: "BTCUSDT".to_string(),
+            interval: "1m".to_string(),
+            open_time: 0,
+            close_time: 59_999,
+            open: fx("1"),
+            high: fx("1"),
+            low: fx("1"),
+            close: fx("1"),
+            volume: fx("1"),
+            quote_volume: fx("1"),
+            number_of_trades: 1,
+            is_closed: true,
+        });
+        assert!(matches!(runner.on_market_data(&kline)[..], [RunnerAction::Submit(_)]));
+        assert!(runner.on_market_data(&kline).is_empty());
+
+        // The clock is far past that bar: the timer reports only the latest close
+        assert!(matches!(runner.poll_bars()[..], [RunnerAction::Submit(_)]));
+        assert!(runner.poll_bars().is_empty());
+    }
+
+    #[test]
+    fn test_paused_symbol_blocks_orders() {
+        let switches = TradingSwitches::new();
+        let mut runner = StrategyRunner::new(Chaser, TradingEnvironment::Live).with_switches(switches.clone());
+
+        switches.pause("BTCUSDT", "test", true);
+        let actions = runner.on_market_data(&trade("100"));
+        assert!(matches!(&actions[..], [RunnerAction::CancelAll(s)] if s == "BTCUSDT"));
+
+        switches.resume("BTCUSDT");
+        assert!(matches!(runner.on_market_data(&trade("100"))[..], [RunnerAction::Submit(_)]));
+    }
+
+    #[test]
+    fn test_notional_limit_drops_excess_orders() {
+        let limit = OpenNotionalLimit::new(NotionalLimitConfig::default().with_symbol_cap("BTCUSDT", 2.5));
+        let mut runner = StrategyRunner::new(Chaser, TradingEnvironment::Paper).with_notional_limit(limit);
+        let level = |price: &str| OrderBookLevel { price: fx(price), quantity: fx("1") };
+        runner.seed_order_book(OrderBook {
+            symbol: "BTCUSDT".to_string(),
+            bids: vec![level("99.9")],
+            asks: vec![level("100.1")],
+            timestamp: 0,
+            update_id: 1,
+        });
+
+        // Each order adds 0.01 * 100 = 1 USDT; the third would reach 3
+        for _ in 0..3 {
+            runner.on_market_data(&trade("100"));
+        }
+        let limit = runner.notional_limit().unwrap();
+        assert!((limit.symbol_notional("BTCUSDT") - 2.0).abs() < 0.01);
+        assert_eq!(runner.slippage_report().stats("chaser", "BTCUSDT").map(|s| s.fills).unwrap_or(0), 2);
+    }
+
+    #[test]
+    fn test_warmup_gates_hold_back_market_data() {
+        let mut runner = StrategyRunner::new(Chaser, TradingEnvironment::Live)
+            .with_warmup_gates(&[WarmupGate::TimeSync, WarmupGate::HistoricalBars]);
+
+        assert!(runner.on_market_data(&trade("100")).is_empty());
+        runner.mark_gate_ready(&WarmupGate::TimeSync);
+        assert!(runner.on_market_data(&trade("100")).is_empty());
+
+        runner.mark_gate_ready(&WarmupGate::HistoricalBars);
+        assert!(runner.is_ready());
+        assert!(matches!(runner.on_market_data(&trade("100"))[..], [RunnerAction::Submit(_)]));
+    }
+
+    #[test]
+    fn test_promotion_blocked_by_divergence() {
+        let mut runner = StrategyRunner::new(Chaser, TradingEnvironment::Paper)
+            .with_promotion_criteria(PromotionCriteria { min_shadow_orders: 1, min_compared: 1, max_abs_divergence_bps: 5.0 });
+        assert!(runner.promote_to_live(&approval(0)).is_err());
+        runner.start_shadow().unwrap();
+
+        // Simulator fills at 100, market next trades at 101: 99bps optimistic
+        runner.on_market_data(&trade("100"));
+        runner.on_market_data(&trade("101"));
+
+        let report = runner.divergence_report();
+        assert!(report.max_abs_divergence_bps > 90.0);
+        assert!(runner.promote_to_live(&approval(report.orders)).is_err());
+        assert_eq!(runner.environment(), TradingEnvironment::Shadow);
+    }
+}
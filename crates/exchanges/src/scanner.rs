@@ -0,0 +1,339 @@
+//! Market scanner for dynamic universe selection
+//!
+//! The scanner keeps the latest 24hr statistics per symbol (fed either from a
+//! full-market REST refresh or from a whole-market ticker stream), applies
+//! user-defined filters, and produces a ranked symbol list. Each scan also
+//! reports which symbols entered or left the universe so strategies can
+//! subscribe/unsubscribe incrementally.
+//!
+//! Volumes are kept as `f64`: quote volumes routinely exceed the range of
+//! `Fixed`, and they are only used for filtering and ranking.
+
+use sriquant_core::prelude::*;
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// Per-symbol market statistics used by the scanner
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScanTicker {
+    pub symbol: String,
+    pub last_price: Fixed,
+    pub open_price: Fixed,
+    pub high_price: Fixed,
+    pub low_price: Fixed,
+    pub bid_price: Option<Fixed>,
+    pub ask_price: Option<Fixed>,
+    pub volume: f64,
+    pub quote_volume: f64,
+    pub trade_count: u64,
+    pub timestamp: u64,
+}
+
+impl ScanTicker {
+    /// Bid-ask spread in basis points of the mid price
+    pub fn spread_bps(&self) -> Option<f64> {
+        let (bid, ask) = (self.bid_price?, self.ask_price?);
+        if bid <= Fixed::ZERO || ask <= Fixed::ZERO {
+            return None;
+        }
+        let mid = (bid.to_f64_lossy() + ask.to_f64_lossy()) / 2.0;
+        Some((ask - bid).to_f64_lossy() / mid * 10_000.0)
+    }
+
+    /// High-low range as a percentage of the low, a simple volatility proxy
+    pub fn range_percent(&self) -> f64 {
+        let low = self.low_price.to_f64_lossy();
+        if low <= 0.0 {
+            return 0.0;
+        }
+        (self.high_price - self.low_price).to_f64_lossy() / low * 100.0
+    }
+
+    /// Price change since open as a percentage
+    pub fn change_percent(&self) -> f64 {
+        let open = self.open_price.to_f64_lossy();
+        if open <= 0.0 {
+            return 0.0;
+        }
+        (self.last_price - self.open_price).to_f64_lossy() / open * 100.0
+    }
+}
+
+/// Filter applied to each ticker; all filters must pass
+#[derive(Clone)]
+pub enum ScanFilter {
+    MinQuoteVolume(f64),
+    MaxSpreadBps(f64),
+    MinRangePercent(f64),
+    MaxRangePercent(f64),
+    MinTradeCount(u64),
+    /// Symbol must end with this quote asset (e.g. "USDT")
+    QuoteAsset(String),
+    Custom(Arc<dyn Fn(&ScanTicker) -> bool + Send + Sync>),
+}
+
+impl std::fmt::Debug for ScanFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScanFilter::MinQuoteVolume(v) => write!(f, "MinQuoteVolume({v})"),
+            ScanFilter::MaxSpreadBps(v) => write!(f, "MaxSpreadBps({v})"),
+            ScanFilter::MinRangePercent(v) => write!(f, "MinRangePercent({v})"),
+            ScanFilter::MaxRangePercent(v) => write!(f, "MaxRangePercent({v})"),
+            ScanFilter::MinTradeCount(v) => write!(f, "MinTradeCount({v})"),
+            ScanFilter::QuoteAsset(v) => write!(f, "QuoteAsset({v})"),
+            ScanFilter::Custom(_) => write!(f, "Custom(..)"),
+        }
+    }
+}
+
+impl ScanFilter {
+    /// Check whether a ticker passes this filter
+    pub fn matches(&self, ticker: &ScanTicker) -> bool {
+        match self {
+            ScanFilter::MinQuoteVolume(min) => ticker.quote_volume >= *min,
+            // Symbols without a two-sided quote can't satisfy a spread bound
+            ScanFilter::MaxSpreadBps(max) => ticker.spread_bps().is_some_and(|s| s <= *max),
+            ScanFilter::MinRangePercent(min) => ticker.range_percent() >= *min,
+            ScanFilter::MaxRangePercent(max) => ticker.range_percent() <= *max,
+            ScanFilter::MinTradeCount(min) => ticker.trade_count >= *min,
+            ScanFilter::QuoteAsset(quote) => ticker.symbol.ends_with(quote.as_str()),
+            ScanFilter::Custom(f) => f(ticker),
+        }
+    }
+}
+
+/// Metric used to rank symbols that pass the filters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RankBy {
+    QuoteVolume,
+    RangePercent,
+    ChangePercent,
+    AbsChangePercent,
+    TradeCount,
+}
+
+impl RankBy {
+    fn score(&self, ticker: &ScanTicker) -> f64 {
+        match self {
+            RankBy::QuoteVolume => ticker.quote_volume,
+            RankBy::RangePercent => ticker.range_percent(),
+            RankBy::ChangePercent => ticker.change_percent(),
+            RankBy::AbsChangePercent => ticker.change_percent().abs(),
+            RankBy::TradeCount => ticker.trade_count as f64,
+        }
+    }
+}
+
+/// Scanner configuration
+#[derive(Debug, Clone)]
+pub struct ScannerConfig {
+    pub filters: Vec<ScanFilter>,
+    pub rank_by: RankBy,
+    /// Rank highest score first
+    pub descending: bool,
+    /// Maximum symbols in the ranked output
+    pub top_n: usize,
+}
+
+impl Default for ScannerConfig {
+    fn default() -> Self {
+        Self {
+            filters: Vec::new(),
+            rank_by: RankBy::QuoteVolume,
+            descending: true,
+            top_n: 20,
+        }
+    }
+}
+
+impl ScannerConfig {
+    pub fn with_filter(mut self, filter: ScanFilter) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    pub fn with_rank_by(mut self, rank_by: RankBy, descending: bool) -> Self {
+        self.rank_by = rank_by;
+        self.descending = descending;
+        self
+    }
+
+    pub fn with_top_n(mut self, top_n: usize) -> Self {
+        self.top_n = top_n;
+        self
+    }
+}
+
+/// A symbol's position in the ranked universe
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankedSymbol {
+    pub rank: usize,
+    pub symbol: String,
+    pub score: f64,
+}
+
+/// Output of a scan, including universe changes since the previous scan
+#[derive(Debug, Clone, Default)]
+pub struct ScanResult {
+    pub ranked: Vec<RankedSymbol>,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub scanned: usize,
+    pub scan_time_micros: u64,
+}
+
+impl ScanResult {
+    /// Ranked symbols in order
+    pub fn symbols(&self) -> Vec<&str> {
+        self.ranked.iter().map(|r| r.symbol.as_str()).collect()
+    }
+}
+
+/// Filter-and-rank market scanner
+#[derive(Debug)]
+pub struct MarketScanner {
+    config: ScannerConfig,
+    tickers: HashMap<String, ScanTicker>,
+    universe: Vec<String>,
+}
+
+impl MarketScanner {
+    pub fn new(config: ScannerConfig) -> Self {
+        Self {
+            config,
+            tickers: HashMap::new(),
+            universe: Vec::new(),
+        }
+    }
+
+    /// Update the latest statistics for one symbol (e.g. from a stream)
+    pub fn update(&mut self, ticker: ScanTicker) {
+        self.tickers.insert(ticker.symbol.clone(), ticker);
+    }
+
+    /// Update statistics for many symbols (e.g. from a full-market REST call)
+    pub fn update_all(&mut self, tickers: impl IntoIterator<Item = ScanTicker>) {
+        for ticker in tickers {
+            self.update(ticker);
+        }
+    }
+
+    /// Apply filters, rank, and report universe changes
+    pub fn scan(&mut self) -> ScanResult {
+        let timer = PerfTimer::start("scanner_scan");
+
+        let mut candidates: Vec<(f64, &ScanTicker)> = self
+            .tickers
+            .values()
+            .filter(|t| self.config.filters.iter().all(|f| f.matches(t)))
+            .map(|t| (self.config.rank_by.score(t), t))
+            .collect();
+
+        candidates.sort_by(|a, b| {
+            let ord = a.0.total_cmp(&b.0);
+            let ord = if self.config.descending { ord.reverse() } else { ord };
+            ord.then_with(|| a.1.symbol.cmp(&b.1.symbol))
+        });
+        candidates.truncate(self.config.top_n);
+
+        let ranked: Vec<RankedSymbol> = candidates
+            .into_iter()
+            .enumerate()
+            .map(|(i, (score, t))| RankedSymbol {
+                rank: i + 1,
+                symbol: t.symbol.clone(),
+                score,
+            })
+            .collect();
+
+        let new_universe: Vec<String> = ranked.iter().map(|r| r.symbol.clone()).collect();
+        let old: HashSet<&String> = self.universe.iter().collect();
+        let new: HashSet<&String> = new_universe.iter().collect();
+        let added = new_universe.iter().filter(|s| !old.contains(s)).cloned().collect();
+        let removed = self.universe.iter().filter(|s| !new.contains(s)).cloned().collect();
+        self.universe = new_universe;
+
+        ScanResult {
+            ranked,
+            added,
+            removed,
+            scanned: self.tickers.len(),
+            scan_time_micros: timer.elapsed_micros(),
+        }
+    }
+
+    /// Symbols selected by the most recent scan
+    pub fn universe(&self) -> &[String] {
+        &self.universe
+    }
+
+    /// Latest statistics for a symbol
+    pub fn ticker(&self, symbol: &str) -> Option<&ScanTicker> {
+        self.tickers.get(symbol)
+    }
+
+    pub fn config(&self) -> &ScannerConfig {
+        &self.config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ticker(symbol: &str, low: &str, high: &str, quote_volume: f64) -> ScanTicker {
+        ScanTicker {
+            symbol: symbol.to_string(),
+            last_price: Fixed::from_str_exact(high).unwrap(),
+            open_price: Fixed::from_str_exact(low).unwrap(),
+            high_price: Fixed::from_str_exact(high).unwrap(),
+            low_price: Fixed::from_str_exact(low).unwrap(),
+            bid_price: Some(Fixed::from_str_exact("99.99").unwrap()),
+            ask_price: Some(Fixed::from_str_exact("100.01").unwrap()),
+            volume: 1.0,
+            quote_volume,
+            trade_count: 10,
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn test_scan_filters_ranks_and_diffs() {
+        let config = ScannerConfig::default()
+            .with_filter(ScanFilter::QuoteAsset("USDT".to_string()))
+            .with_filter(ScanFilter::MinQuoteVolume(1_000.0))
+            .with_filter(ScanFilter::MaxSpreadBps(5.0))
+            .with_rank_by(RankBy::RangePercent, true)
+            .with_top_n(2);
+        let mut scanner = MarketScanner::new(config);
+
+        scanner.update_all(vec![
+            ticker("BTCUSDT", "100", "102", 5_000_000.0),
+            ticker("ETHUSDT", "100", "110", 2_000_000.0),
+            ticker("DOGEUSDT", "100", "105", 500.0),
+            ticker("ETHBTC", "100", "120", 9_000_000.0),
+            ticker("SOLUSDT", "100", "101", 1_500.0),
+        ]);
+
+        let result = scanner.scan();
+        assert_eq!(result.scanned, 5);
+        assert_eq!(result.symbols(), vec!["ETHUSDT", "BTCUSDT"]);
+        assert_eq!(result.added.len(), 2);
+
+        // SOL becomes more volatile and displaces BTC
+        scanner.update(ticker("SOLUSDT", "100", "115", 1_500.0));
+        let result = scanner.scan();
+        assert_eq!(result.symbols(), vec!["SOLUSDT", "ETHUSDT"]);
+        assert_eq!(result.added, vec!["SOLUSDT".to_string()]);
+        assert_eq!(result.removed, vec!["BTCUSDT".to_string()]);
+    }
+
+    #[test]
+    fn test_scan_ticker_metrics() {
+        let t = ticker("BTCUSDT", "100", "110", 0.0);
+        assert!((t.range_percent() - 10.0).abs() < 1e-9);
+        assert!((t.change_percent() - 10.0).abs() < 1e-9);
+        assert!((t.spread_bps().unwrap() - 2.0).abs() < 1e-9);
+    }
+}
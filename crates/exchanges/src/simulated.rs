@@ -0,0 +1,770 @@
+//! Simulated exchange backend
+//!
+//! `SimulatedExchange` wraps a live market data source (typically
+//! `BinanceExchange`) and implements the same trait surface, but fills orders
+//! locally: aggressive orders walk the latest order book snapshot, resting
+//! limit orders fill when a trade prints through their price or the book
+//! crosses them. Orders reach the simulated matching engine after a
+//! configurable latency, measured on the market data clock, and pay maker or
+//! taker fees from a `FeeTier`. No credentials are needed and nothing is sent
+//! to the venue.
+//!
+//! How much of a resting order fills is decided by a `FillModel`
+//! (`TradeThroughFill` unless `with_fill_model` picks another), so a run can be
+//! repeated under different fill assumptions.
+//!
+//! Cancels take effect immediately and market impact isn't modelled: the book
+//! isn't depleted by simulated fills.
+
+use crate::errors::{ExchangeError, Result};
+use crate::fees::FeeTier;
+use crate::fill_model::{FillContext, FillModel, TradeThroughFill};
+use crate::traits::{Exchange, StreamingExchange, TradingExchange};
+use crate::types::*;
+use sriquant_core::prelude::*;
+
+use async_trait::async_trait;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use tracing::{debug, info};
+
+/// Delay between `place_order` and the order reaching the matching engine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LatencyModel {
+    /// Orders match on submission
+    None,
+    Fixed { ms: u64 },
+    /// Uniformly distributed, from a seeded generator so runs are repeatable
+    Uniform { min_ms: u64, max_ms: u64, seed: u64 },
+}
+
+/// Simulator settings
+#[derive(Debug, Clone)]
+pub struct SimulatorConfig {
+    pub latency: LatencyModel,
+    pub fee_tier: FeeTier,
+    /// Starting balances by asset
+    pub balances: HashMap<String, Fixed>,
+}
+
+impl Default for SimulatorConfig {
+    fn default() -> Self {
+        Self {
+            latency: LatencyModel::None,
+            fee_tier: FeeTier::new("VIP0", 0.0, 10.0, 10.0),
+            balances: HashMap::new(),
+        }
+    }
+}
+
+impl SimulatorConfig {
+    pub fn with_latency(mut self, latency: LatencyModel) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    pub fn with_fee_tier(mut self, fee_tier: FeeTier) -> Self {
+        self.fee_tier = fee_tier;
+        self
+    }
+
+    pub fn with_balance(mut self, asset: &str, amount: Fixed) -> Self {
+        self.balances.insert(asset.to_string(), amount);
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SimOrder {
+    order: OrderResponse,
+    /// Market-clock time the order reaches the engine
+    arrives_at: u64,
+    arrived: bool,
+    /// Sum of price * quantity over fills
+    filled_notional: Fixed,
+    /// Volume traded at or through the limit since arrival
+    traded_quantity: Fixed,
+}
+
+impl SimOrder {
+    fn remaining(&self) -> Fixed {
+        self.order.quantity - self.order.filled_quantity
+    }
+
+    fn is_open(&self) -> bool {
+        matches!(self.order.status, OrderStatus::New | OrderStatus::PartiallyFilled)
+    }
+}
+
+#[derive(Debug)]
+struct SimState {
+    clock_ms: u64,
+    books: HashMap<String, OrderBook>,
+    orders: BTreeMap<u64, SimOrder>,
+    fills: Vec<Trade>,
+    balances: HashMap<String, Fixed>,
+    /// Symbol -> (base, quote), for balance accounting
+    pairs: HashMap<String, (String, String)>,
+    next_order_id: u64,
+    next_trade_id: u64,
+    rng: u64,
+    fill_model: Box<dyn FillModel>,
+}
+
+impl SimState {
+    fn latency(&mut self, model: LatencyModel) -> u64 {
+        match model {
+            LatencyModel::None => 0,
+            LatencyModel::Fixed { ms } => ms,
+            LatencyModel::Uniform { min_ms, max_ms, .. } => {
+                // xorshift64
+                self.rng ^= self.rng << 13;
+                self.rng ^= self.rng >> 7;
+                self.rng ^= self.rng << 17;
+                min_ms + self.rng % (max_ms.saturating_sub(min_ms) + 1)
+            }
+        }
+    }
+
+    fn now(&self) -> u64 {
+        if self.clock_ms > 0 { self.clock_ms } else { nanos() / 1_000_000 }
+    }
+
+    /// Apply a fill to the order, the balances and the fill history
+    fn fill(&mut self, order_id: u64, price: Fixed, quantity: Fixed, is_maker: bool, fee_tier: &FeeTier) {
+        let now = self.now();
+        let Some(sim) = self.orders.get_mut(&order_id) else { return };
+        sim.order.filled_quantity += quantity;
+        sim.filled_notional += price * quantity;
+        sim.order.average_price = Some(sim.filled_notional / sim.order.filled_quantity);
+        sim.order.status = if sim.remaining() > Fixed::ZERO { OrderStatus::PartiallyFilled } else { OrderStatus::Filled };
+        sim.order.update_time = now;
+        if !sim.is_open() {
+            self.fill_model.on_done(&sim.order);
+        }
+        let (symbol, side) = (sim.order.symbol.clone(), sim.order.side);
+
+        self.next_trade_id += 1;
+        self.fills.push(Trade {
+            id: self.next_trade_id.to_string(),
+            symbol: symbol.clone(),
+            price,
+            quantity,
+            side,
+            timestamp: now,
+            is_buyer_maker: (side == OrderSide::Buy) == is_maker,
+        });
+
+        // Fees are charged in the quote asset
+        if let Some((base, quote)) = self.pairs.get(&symbol).cloned() {
+            let notional = price * quantity;
+            let fee = Fixed::from_f64(notional.to_f64_lossy() * fee_tier.fee_bps(is_maker) / 10_000.0)
+                .map(|f| f.round_dp(8))
+                .unwrap_or(Fixed::ZERO);
+            let (base_delta, quote_delta) = match side {
+                OrderSide::Buy => (quantity, Fixed::ZERO - notional - fee),
+                OrderSide::Sell => (Fixed::ZERO - quantity, notional - fee),
+            };
+            *self.balances.entry(base).or_insert(Fixed::ZERO) += base_delta;
+            *self.balances.entry(quote).or_insert(Fixed::ZERO) += quote_delta;
+        }
+        debug!("🧪 Simulated fill {} {} {} @ {} (maker: {})", symbol, side, quantity, price, is_maker);
+    }
+
+    /// Match an arriving order against the book as a taker
+    fn take_liquidity(&mut self, order_id: u64, fee_tier: &FeeTier) {
+        let Some(sim) = self.orders.get(&order_id) else { return };
+        let order = sim.order.clone();
+        let Some(book) = self.books.get(&order.symbol) else {
+            if order.order_type == OrderType::Market {
+                self.finish(order_id, OrderStatus::Expired);
+            }
+            return;
+        };
+        let levels = match order.side {
+            OrderSide::Buy => &book.asks,
+            OrderSide::Sell => &book.bids,
+        };
+        let marketable = |price: Fixed| match (order.order_type, order.price, order.side) {
+            (OrderType::Market, _, _) | (_, None, _) => true,
+            (_, Some(limit), OrderSide::Buy) => price <= limit,
+            (_, Some(limit), OrderSide::Sell) => price >= limit,
+        };
+
+        let mut remaining = order.quantity - order.filled_quantity;
+        let mut executions = Vec::new();
+        for level in levels.iter().take_while(|l| marketable(l.price)) {
+            if remaining <= Fixed::ZERO {
+                break;
+            }
+            let quantity = remaining.min(level.quantity);
+            executions.push((level.price, quantity));
+            remaining -= quantity;
+        }
+
+        if order.time_in_force == Some(TimeInForce::FillOrKill) && remaining > Fixed::ZERO {
+            self.finish(order_id, OrderStatus::Expired);
+            return;
+        }
+        for (price, quantity) in executions {
+            self.fill(order_id, price, quantity, false, fee_tier);
+        }
+        let rests = order.order_type == OrderType::Limit && order.time_in_force != Some(TimeInForce::ImmediateOrCancel);
+        if remaining > Fixed::ZERO && !rests {
+            self.finish(order_id, OrderStatus::Expired);
+        }
+    }
+
+    fn finish(&mut self, order_id: u64, status: OrderStatus) {
+        let now = self.now();
+        if let Some(sim) = self.orders.get_mut(&order_id) {
+            sim.order.status = status;
+            sim.order.update_time = now;
+            self.fill_model.on_done(&sim.order);
+        }
+    }
+
+    /// Orders whose latency has elapsed take liquidity on arrival
+    fn process_arrivals(&mut self, fee_tier: &FeeTier) {
+        let due: Vec<u64> = self.orders.iter()
+            .filter(|(_, o)| !o.arrived && o.is_open() && o.arrives_at <= self.clock_ms)
+            .map(|(id, _)| *id)
+            .collect();
+        for id in due {
+            if let Some(sim) = self.orders.get_mut(&id) {
+                sim.arrived = true;
+            }
+            self.take_liquidity(id, fee_tier);
+            if let Some(sim) = self.orders.get(&id)
+                && sim.is_open()
+            {
+                self.fill_model.on_rest(&sim.order, self.books.get(&sim.order.symbol), self.clock_ms);
+            }
+        }
+    }
+
+    /// Fill resting limit orders as far as the fill model allows
+    ///
+    /// `trade` is the print being processed, `None` on a book update.
+    fn fill_resting(&mut self, symbol: &str, trade: Option<&Trade>, fee_tier: &FeeTier) {
+        let book = self.books.get(symbol);
+        let mut fills = Vec::new();
+        for (id, sim) in self.orders.iter_mut().filter(|(_, o)| o.arrived && o.is_open() && o.order.symbol == symbol) {
+            let Some(limit) = sim.order.price else { continue };
+            if let Some(trade) = trade {
+                let reaches = match sim.order.side {
+                    OrderSide::Buy => trade.price <= limit,
+                    OrderSide::Sell => trade.price >= limit,
+                };
+                if reaches {
+                    sim.traded_quantity += trade.quantity;
+                }
+            }
+            let ctx = FillContext {
+                order: &sim.order,
+                book,
+                trade,
+                resting_ms: self.clock_ms.saturating_sub(sim.arrives_at),
+                traded_quantity: sim.traded_quantity,
+            };
+            let quantity = self.fill_model.fill_quantity(&ctx).min(sim.remaining());
+            if quantity > Fixed::ZERO {
+                fills.push((*id, limit, quantity));
+            }
+        }
+        for (id, limit, quantity) in fills {
+            self.fill(id, limit, quantity, true, fee_tier);
+        }
+    }
+}
+
+/// Exchange that fills orders locally against live market data
+pub struct SimulatedExchange<E> {
+    market: E,
+    config: SimulatorConfig,
+    state: RefCell<SimState>,
+}
+
+impl<E> SimulatedExchange<E> {
+    pub fn new(market: E, config: SimulatorConfig) -> Self {
+        let seed = match config.latency {
+            LatencyModel::Uniform { seed, .. } => seed.max(1),
+            _ => 1,
+        };
+        info!("🧪 Simulated exchange with {:?} latency, {} fees", config.latency, config.fee_tier.name);
+        let state = SimState {
+            clock_ms: 0,
+            books: HashMap::new(),
+            orders: BTreeMap::new(),
+            fills: Vec::new(),
+            balances: config.balances.clone(),
+            pairs: HashMap::new(),
+            next_order_id: 0,
+            next_trade_id: 0,
+            rng: seed,
+            fill_model: Box::new(TradeThroughFill),
+        };
+        Self { market, config, state: RefCell::new(state) }
+    }
+
+    /// Register base/quote assets so fills move balances
+    pub fn with_symbols<'a>(self, symbols: impl IntoIterator<Item = &'a Symbol>) -> Self {
+        {
+            let mut state = self.state.borrow_mut();
+            for symbol in symbols {
+                state.pairs.insert(symbol.symbol.clone(), (symbol.base_asset.clone(), symbol.quote_asset.clone()));
+            }
+        }
+        self
+    }
+
+    /// Decide resting order fills with `model` instead of `TradeThroughFill`
+    pub fn with_fill_model(self, model: impl FillModel + 'static) -> Self {
+        info!("🧪 Simulating resting fills with the {} model", model.name());
+        self.state.borrow_mut().fill_model = Box::new(model);
+        self
+    }
+
+    /// The wrapped market data source
+    pub fn market(&self) -> &E {
+        &self.market
+    }
+
+    /// Feed a market data event into the simulator
+    ///
+    /// `next_event` does this automatically; call it directly when driving the
+    /// simulator from recorded data.
+    pub fn on_market_data(&self, event: &MarketData) {
+        let fee_tier = &self.config.fee_tier;
+        let mut state = self.state.borrow_mut();
+        let timestamp = match event {
+            MarketData::OrderBook(book) => book.timestamp,
+            MarketData::Trade(trade) => trade.timestamp,
+            MarketData::Ticker(ticker) => ticker.timestamp,
+            MarketData::Kline(kline) => kline.close_time,
+        };
+        state.clock_ms = state.clock_ms.max(timestamp);
+
+        match event {
+            MarketData::OrderBook(book) => {
+                state.books.insert(book.symbol.clone(), book.clone());
+                state.process_arrivals(fee_tier);
+                state.fill_model.on_market_data(event);
+                state.fill_resting(&book.symbol, None, fee_tier);
+            }
+            MarketData::Trade(trade) => {
+                state.process_arrivals(fee_tier);
+                state.fill_model.on_market_data(event);
+                state.fill_resting(&trade.symbol, Some(trade), fee_tier);
+            }
+            MarketData::Ticker(_) | MarketData::Kline(_) => {
+                state.process_arrivals(fee_tier);
+                state.fill_model.on_market_data(event);
+            }
+        }
+    }
+
+    fn find_order(&self, order_id: &str) -> Result<OrderResponse> {
+        let id: u64 = order_id.parse()
+            .map_err(|_| ExchangeError::InvalidOrder(format!("Invalid order id: {order_id}")))?;
+        self.state.borrow().orders.get(&id)
+            .map(|o| o.order.clone())
+            .ok_or_else(|| ExchangeError::OrderNotFound(order_id.to_string()))
+    }
+
+    fn check_balance(&self, request: &OrderRequest) -> Result<()> {
+        let state = self.state.borrow();
+        let Some((base, quote)) = state.pairs.get(&request.symbol) else {
+            return Ok(());
+        };
+        let book = state.books.get(&request.symbol);
+        let (asset, needed) = match request.side {
+            OrderSide::Buy => {
+                let price = request.price.or_else(|| book.and_then(|b| b.best_ask()));
+                (quote, price.map(|p| p * request.quantity).unwrap_or(Fixed::ZERO))
+            }
+            OrderSide::Sell => (base, request.quantity),
+        };
+        let available = state.balances.get(asset).copied().unwrap_or(Fixed::ZERO);
+        if needed > available {
+            return Err(ExchangeError::InvalidOrder(format!("insufficient {asset}: need {needed}, have {available}")));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait(?Send)]
+impl<E: Exchange> Exchange for SimulatedExchange<E> {
+    fn name(&self) -> &str {
+        "simulated"
+    }
+
+    async fn ping(&self) -> Result<u64> {
+        self.market.ping().await
+    }
+
+    async fn server_time(&self) -> Result<u64> {
+        self.market.server_time().await
+    }
+
+    async fn exchange_info(&self) -> Result<HashMap<String, Symbol>> {
+        self.market.exchange_info().await
+    }
+
+    async fn account_info(&self) -> Result<AccountInfo> {
+        Ok(AccountInfo {
+            account_type: "SIMULATED".to_string(),
+            can_trade: true,
+            can_withdraw: false,
+            can_deposit: false,
+            balances: self.balances().await?,
+            update_time: self.state.borrow().now(),
+        })
+    }
+
+    async fn balances(&self) -> Result<Vec<Balance>> {
+        let state = self.state.borrow();
+        let mut balances: Vec<Balance> = state.balances.iter()
+            .map(|(asset, free)| Balance { asset: asset.clone(), free: *free, locked: Fixed::ZERO })
+            .collect();
+        balances.sort_by(|a, b| a.asset.cmp(&b.asset));
+        Ok(balances)
+    }
+
+    async fn ticker(&self, symbol: &str) -> Result<Ticker> {
+        self.market.ticker(symbol).await
+    }
+
+    async fn order_book(&self, symbol: &str, limit: Option<u32>) -> Result<OrderBook> {
+        self.market.order_book(symbol, limit).await
+    }
+
+    async fn recent_trades(&self, symbol: &str, limit: Option<u32>) -> Result<Vec<Trade>> {
+        self.market.recent_trades(symbol, limit).await
+    }
+
+    async fn klines(
+        &self,
+        symbol: &str,
+        interval: &str,
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<Vec<Kline>> {
+        self.market.klines(symbol, interval, start_time, end_time, limit).await
+    }
+}
+
+#[async_trait(?Send)]
+impl<E: Exchange> TradingExchange for SimulatedExchange<E> {
+    async fn place_order(&self, request: OrderRequest) -> Result<OrderResponse> {
+        if request.quantity <= Fixed::ZERO {
+            return Err(ExchangeError::InvalidOrder("quantity must be positive".to_string()));
+        }
+        match (request.order_type, request.price) {
+            (OrderType::StopLoss | OrderType::StopLossLimit, _) => {
+                return Err(ExchangeError::FeatureNotSupported("stop orders aren't simulated".to_string()));
+            }
+            (OrderType::Limit, None) => return Err(ExchangeError::InvalidOrder("limit order requires a price".to_string())),
+            _ => {}
+        }
+        self.check_balance(&request)?;
+
+        let id = {
+            let mut state = self.state.borrow_mut();
+            state.next_order_id += 1;
+            let id = state.next_order_id;
+            let now = state.now();
+            let latency = state.latency(self.config.latency);
+            let order = OrderResponse {
+                order_id: id.to_string(),
+                client_order_id: request.client_order_id.clone().unwrap_or_else(|| format!("sim-{id}")),
+                symbol: request.symbol.clone(),
+                side: request.side,
+                order_type: request.order_type,
+                quantity: request.quantity,
+                price: request.price,
+                stop_price: request.stop_price,
+                status: OrderStatus::New,
+                filled_quantity: Fixed::ZERO,
+                average_price: None,
+                time_in_force: request.time_in_force,
+                timestamp: now,
+                update_time: now,
+            };
+            state.orders.insert(id, SimOrder { order, arrives_at: now + latency, arrived: false, filled_notional: Fixed::ZERO, traded_quantity: Fixed::ZERO });
+            if latency == 0 {
+                state.process_arrivals(&self.config.fee_tier);
+            }
+            id
+        };
+        self.find_order(&id.to_string())
+    }
+
+    async fn cancel_order(&self, _symbol: &str, order_id: &str) -> Result<OrderResponse> {
+        let order = self.find_order(order_id)?;
+        if !matches!(order.status, OrderStatus::New | OrderStatus::PartiallyFilled) {
+            return Err(ExchangeError::InvalidOrder(format!("order {order_id} is {}", order.status)));
+        }
+        let id = order_id.parse().unwrap_or_default();
+        self.state.borrow_mut().finish(id, OrderStatus::Canceled);
+        self.find_order(order_id)
+    }
+
+    async fn cancel_all_orders(&self, symbol: &str) -> Result<Vec<OrderResponse>> {
+        let open = self.open_orders(Some(symbol)).await?;
+        let mut canceled = Vec::with_capacity(open.len());
+        for order in open {
+            canceled.push(self.cancel_order(symbol, &order.order_id).await?);
+        }
+        Ok(canceled)
+    }
+
+    async fn get_order(&self, _symbol: &str, order_id: &str) -> Result<OrderResponse> {
+        self.find_order(order_id)
+    }
+
+    async fn open_orders(&self, symbol: Option<&str>) -> Result<Vec<OrderResponse>> {
+        Ok(self.state.borrow().orders.values()
+            .filter(|o| o.is_open() && symbol.is_none_or(|s| o.order.symbol == s))
+            .map(|o| o.order.clone())
+            .collect())
+    }
+
+    async fn order_history(
+        &self,
+        symbol: &str,
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<Vec<OrderResponse>> {
+        let state = self.state.borrow();
+        let orders = state.orders.values()
+            .map(|o| &o.order)
+            .filter(|o| o.symbol == symbol && in_range(o.timestamp, start_time, end_time));
+        Ok(take_last(orders.cloned().collect(), limit))
+    }
+
+    async fn trade_history(
+        &self,
+        symbol: &str,
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<Vec<Trade>> {
+        let state = self.state.borrow();
+        let fills = state.fills.iter()
+            .filter(|t| t.symbol == symbol && in_range(t.timestamp, start_time, end_time));
+        Ok(take_last(fills.cloned().collect(), limit))
+    }
+}
+
+fn in_range(time: u64, start_time: Option<u64>, end_time: Option<u64>) -> bool {
+    start_time.is_none_or(|start| time >= start) && end_time.is_none_or(|end| time <= end)
+}
+
+/// Most recent `limit` items, oldest first
+fn take_last<T>(mut items: Vec<T>, limit: Option<u32>) -> Vec<T> {
+    if let Some(limit) = limit {
+        let excess = items.len().saturating_sub(limit as usize);
+        items.drain(..excess);
+    }
+    items
+}
+
+#[async_trait(?Send)]
+impl<E: StreamingExchange> StreamingExchange for SimulatedExchange<E> {
+    async fn connect(&mut self) -> Result<()> {
+        self.market.connect().await
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        self.market.disconnect().await
+    }
+
+    async fn subscribe_ticker(&mut self, symbol: &str) -> Result<()> {
+        self.market.subscribe_ticker(symbol).await
+    }
+
+    async fn subscribe_trades(&mut self, symbol: &str) -> Result<()> {
+        self.market.subscribe_trades(symbol).await
+    }
+
+    async fn subscribe_order_book(&mut self, symbol: &str, levels: Option<u32>) -> Result<()> {
+        self.market.subscribe_order_book(symbol, levels).await
+    }
+
+    async fn subscribe_klines(&mut self, symbol: &str, interval: &str) -> Result<()> {
+        self.market.subscribe_klines(symbol, interval).await
+    }
+
+    async fn unsubscribe(&mut self, stream: &str) -> Result<()> {
+        self.market.unsubscribe(stream).await
+    }
+
+    /// Next live event, after it has been applied to the simulated book
+    async fn next_event(&mut self) -> Result<Option<MarketData>> {
+        let event = self.market.next_event().await?;
+        if let Some(event) = &event {
+            self.on_market_data(event);
+        }
+        Ok(event)
+    }
+
+    fn connection_status(&self) -> ConnectionStatus {
+        self.market.connection_status()
+    }
+
+    fn subscriptions(&self) -> Vec<Subscription> {
+        self.market.subscriptions()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testkit::fx;
+    use crate::fill_model::QueueFill;
+
+    /// Market data source with no venue behind it
+    struct Offline;
+
+    #[async_trait(?Send)]
+    impl Exchange for Offline {
+        fn name(&self) -> &str { "offline" }
+        async fn ping(&self) -> Result<u64> { Ok(0) }
+        async fn server_time(&self) -> Result<u64> { Ok(0) }
+        async fn exchange_info(&self) -> Result<HashMap<String, Symbol>> { Ok(HashMap::new()) }
+        async fn account_info(&self) -> Result<AccountInfo> { Err(ExchangeError::FeatureNotSupported("offline".to_string())) }
+        async fn balances(&self) -> Result<Vec<Balance>> { Ok(Vec::new()) }
+        async fn ticker(&self, symbol: &str) -> Result<Ticker> { Err(ExchangeError::SymbolNotFound(symbol.to_string())) }
+        async fn order_book(&self, symbol: &str, _: Option<u32>) -> Result<OrderBook> { Err(ExchangeError::SymbolNotFound(symbol.to_string())) }
+        async fn recent_trades(&self, _: &str, _: Option<u32>) -> Result<Vec<Trade>> { Ok(Vec::new()) }
+        async fn klines(&self, _: &str, _: &str, _: Option<u64>, _: Option<u64>, _: Option<u32>) -> Result<Vec<Kline>> { Ok(Vec::new()) }
+    }
+
+    fn book(timestamp: u64, bid: &str, ask: &str) -> MarketData {
+        MarketData::OrderBook(OrderBook {
+            symbol: "BTCUSDT".to_string(),
+            bids: vec![OrderBookLevel { price: fx(bid), quantity: fx("1") }],
+            asks: vec![
+                OrderBookLevel { price: fx(ask), quantity: fx("0.5") },
+                OrderBookLevel { price: fx(ask) + Fixed::ONE, quantity: fx("1") },
+            ],
+            timestamp,
+            update_id: timestamp,
+        })
+    }
+
+    fn order(order_type: OrderType, side: OrderSide, quantity: &str, price: Option<&str>) -> OrderRequest {
+        OrderRequest {
+            symbol: "BTCUSDT".to_string(),
+            side,
+            order_type,
+            quantity: fx(quantity),
+            price: price.map(fx),
+            stop_price: None,
+            time_in_force: None,
+            client_order_id: None,
+        }
+    }
+
+    fn simulator(latency: LatencyModel) -> SimulatedExchange<Offline> {
+        let symbol = Symbol {
+            symbol: "BTCUSDT".to_string(),
+            base_asset: "BTC".to_string(),
+            quote_asset: "USDT".to_string(),
+            status: "TRADING".to_string(),
+            min_quantity: Fixed::ZERO,
+            max_quantity: Fixed::max(),
+            quantity_precision: 5,
+            min_price: Fixed::ZERO,
+            max_price: Fixed::max(),
+            price_precision: 2,
+            min_notional: Fixed::ZERO,
+        };
+        let config = SimulatorConfig::default()
+            .with_latency(latency)
+            .with_balance("USDT", fx("1000"));
+        SimulatedExchange::new(Offline, config).with_symbols([&symbol])
+    }
+
+    #[monoio::test]
+    async fn test_market_order_walks_book_and_pays_taker_fee() {
+        let sim = simulator(LatencyModel::None);
+        sim.on_market_data(&book(1_000, "99", "100"));
+
+        let filled = sim.place_order(order(OrderType::Market, OrderSide::Buy, "1", None)).await.unwrap();
+        assert_eq!(filled.status, OrderStatus::Filled);
+        // 0.5 @ 100 + 0.5 @ 101
+        assert_eq!(filled.average_price, Some(fx("100.5")));
+        assert_eq!(sim.trade_history("BTCUSDT", None, None, None).await.unwrap().len(), 2);
+
+        let balances = sim.balances().await.unwrap();
+        assert_eq!(balances[0].free, fx("1"));
+        // 1000 - 100.5 - 10 bps
+        assert_eq!(balances[1].free, fx("899.3995"));
+
+        assert!(sim.place_order(order(OrderType::Market, OrderSide::Buy, "10", None)).await.is_err());
+    }
+
+    #[monoio::test]
+    async fn test_latency_and_resting_limit_orders() {
+        let sim = simulator(LatencyModel::Fixed { ms: 50 });
+        sim.on_market_data(&book(1_000, "99", "100"));
+
+        let taker = sim.place_order(order(OrderType::Limit, OrderSide::Buy, "0.1", Some("100"))).await.unwrap();
+        assert_eq!(taker.status, OrderStatus::New);
+        let maker = sim.place_order(order(OrderType::Limit, OrderSide::Buy, "0.1", Some("98"))).await.unwrap();
+
+        // Not arrived yet
+        sim.on_market_data(&book(1_020, "99", "100"));
+        assert_eq!(sim.get_order("BTCUSDT", &taker.order_id).await.unwrap().status, OrderStatus::New);
+        sim.on_market_data(&book(1_060, "99", "100"));
+        assert_eq!(sim.get_order("BTCUSDT", &taker.order_id).await.unwrap().status, OrderStatus::Filled);
+        assert_eq!(sim.open_orders(None).await.unwrap().len(), 1);
+
+        // A trade at the limit isn't enough; one through it fills the resting order
+        let trade = |price: &str| MarketData::Trade(Trade {
+            id: "1".to_string(),
+            symbol: "BTCUSDT".to_string(),
+            price: fx(price),
+            quantity: fx("1"),
+            side: OrderSide::Sell,
+            timestamp: 1_100,
+            is_buyer_maker: true,
+        });
+        sim.on_market_data(&trade("98"));
+        assert_eq!(sim.get_order("BTCUSDT", &maker.order_id).await.unwrap().status, OrderStatus::New);
+        sim.on_market_data(&trade("97.9"));
+        let filled = sim.get_order("BTCUSDT", &maker.order_id).await.unwrap();
+        assert_eq!(filled.status, OrderStatus::Filled);
+        assert_eq!(filled.average_price, Some(fx("98")));
+        assert!(sim.trade_history("BTCUSDT", None, None, Some(1)).await.unwrap()[0].is_buyer_maker);
+    }
+
+    #[monoio::test]
+    async fn test_queue_fill_model_needs_volume_ahead_to_trade() {
+        let sim = simulator(LatencyModel::None).with_fill_model(QueueFill::new());
+        sim.on_market_data(&book(1_000, "99", "100"));
+        // Joins behind the 1 BTC bid at 99
+        let maker = sim.place_order(order(OrderType::Limit, OrderSide::Buy, "0.5", Some("99"))).await.unwrap();
+
+        let sell = |quantity: &str, timestamp: u64| MarketData::Trade(Trade {
+            id: timestamp.to_string(),
+            symbol: "BTCUSDT".to_string(),
+            price: fx("99"),
+            quantity: fx(quantity),
+            side: OrderSide::Sell,
+            timestamp,
+            is_buyer_maker: true,
+        });
+        sim.on_market_data(&sell("0.8", 1_100));
+        assert_eq!(sim.get_order("BTCUSDT", &maker.order_id).await.unwrap().filled_quantity, Fixed::ZERO);
+        sim.on_market_data(&sell("0.4", 1_200));
+        let partial = sim.get_order("BTCUSDT", &maker.order_id).await.unwrap();
+        assert_eq!((partial.status, partial.filled_quantity), (OrderStatus::PartiallyFilled, fx("0.2")));
+        sim.on_market_data(&sell("1", 1_300));
+        assert_eq!(sim.get_order("BTCUSDT", &maker.order_id).await.unwrap().status, OrderStatus::Filled);
+    }
+}
@@ -0,0 +1,204 @@
+//! Persistent per-strategy key/value store
+//!
+//! Strategies keep a little state that must survive a restart: the last
+//! processed trade id, stops they have armed, parameters they have learned.
+//! `KvStore` holds that state in memory, one namespace per strategy, and
+//! persists each namespace as a JSON file in a directory.
+//!
+//! Reads and writes only touch memory. Disk I/O happens on a background
+//! writer thread, which coalesces bursts of writes to a namespace into one
+//! atomic file replace (write and sync a temp file, rename it, sync the
+//! directory), so a crash leaves either the old or the new file, never half
+//! of one. `flush().await` waits until everything set so far is on disk.
+
+use crate::disk_writer::{replace_atomic, BackgroundWriter};
+use crate::errors::{ExchangeError, Result};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tracing::{debug, info, warn};
+
+type Namespaces = HashMap<String, BTreeMap<String, Value>>;
+
+/// Directory-backed key/value store shared by all strategies in a process
+///
+/// ```rust,ignore
+/// let store = KvStore::open("state")?;
+/// let kv = store.namespace("momentum_btc")?;
+/// let resume_from: Option<u64> = kv.get("last_trade_id")?;
+/// kv.set("last_trade_id", &trade.trade_id)?;
+/// store.flush().await?;
+/// ```
+#[derive(Clone)]
+pub struct KvStore {
+    dir: PathBuf,
+    namespaces: Arc<Mutex<Namespaces>>,
+    /// Namespace and its serialized contents
+    writer: Arc<BackgroundWriter<(String, String)>>,
+}
+
+impl KvStore {
+    /// Open (creating if needed) a store in `dir` and load every namespace in it
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        std::fs::create_dir_all(&dir)?;
+
+        let mut namespaces = Namespaces::new();
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json")
+                && let Some(name) = path.file_stem().and_then(|s| s.to_str())
+            {
+                let values: BTreeMap<String, Value> = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+                namespaces.insert(name.to_string(), values);
+            }
+        }
+        info!("🗄️ Opened KV store at {} with {} namespaces", dir.display(), namespaces.len());
+
+        let writer_dir = dir.clone();
+        let writer = BackgroundWriter::spawn("KV store", move |saves| write_namespaces(&writer_dir, saves))?;
+
+        Ok(Self {
+            dir,
+            namespaces: Arc::new(Mutex::new(namespaces)),
+            writer: Arc::new(writer),
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Handle scoped to one strategy; the name becomes a file name, so it is
+    /// limited to letters, digits, `-` and `_`
+    pub fn namespace(&self, name: &str) -> Result<StrategyStore> {
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            return Err(ExchangeError::ConfigurationError(format!("invalid KV namespace {name:?}")));
+        }
+        Ok(StrategyStore { store: self.clone(), namespace: name.to_string() })
+    }
+
+    /// Namespaces that hold at least one key
+    pub fn namespaces(&self) -> Vec<String> {
+        let namespaces = self.lock();
+        let mut names: Vec<String> = namespaces.iter().filter(|(_, v)| !v.is_empty()).map(|(k, _)| k.clone()).collect();
+        names.sort();
+        names
+    }
+
+    /// Wait until every write so far is on disk
+    pub async fn flush(&self) -> Result<()> {
+        self.writer.flush().await
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Namespaces> {
+        self.namespaces.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Apply `change` to a namespace and queue the result for writing
+    fn update<R>(&self, namespace: &str, change: impl FnOnce(&mut BTreeMap<String, Value>) -> R) -> Result<R> {
+        let (result, contents) = {
+            let mut namespaces = self.lock();
+            let values = namespaces.entry(namespace.to_string()).or_default();
+            let result = change(values);
+            (result, serde_json::to_string_pretty(values)?)
+        };
+        self.writer.send((namespace.to_string(), contents))?;
+        Ok(result)
+    }
+}
+
+/// One strategy's view of a `KvStore`
+#[derive(Clone)]
+pub struct StrategyStore {
+    store: KvStore,
+    namespace: String,
+}
+
+impl StrategyStore {
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>> {
+        let namespaces = self.store.lock();
+        match namespaces.get(&self.namespace).and_then(|values| values.get(key)) {
+            Some(value) => Ok(Some(serde_json::from_value(value.clone())?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Store `value` under `key`; persisted in the background
+    pub fn set<T: Serialize + ?Sized>(&self, key: &str, value: &T) -> Result<()> {
+        let value = serde_json::to_value(value)?;
+        self.store.update(&self.namespace, |values| {
+            values.insert(key.to_string(), value);
+        })
+    }
+
+    /// Remove `key`; returns whether it was present
+    pub fn remove(&self, key: &str) -> Result<bool> {
+        self.store.update(&self.namespace, |values| values.remove(key).is_some())
+    }
+
+    pub fn keys(&self) -> Vec<String> {
+        self.store.lock().get(&self.namespace).map(|values| values.keys().cloned().collect()).unwrap_or_default()
+    }
+
+    /// Wait until every write so far is on disk
+    pub async fn flush(&self) -> Result<()> {
+        self.store.flush().await
+    }
+}
+
+/// Write a burst of saves, keeping only the newest contents of each namespace
+fn write_namespaces(dir: &Path, saves: Vec<(String, String)>) -> Option<String> {
+    let pending: BTreeMap<String, String> = saves.into_iter().collect();
+    let mut last_error = None;
+    for (namespace, contents) in pending {
+        match replace_atomic(&dir.join(format!("{namespace}.json")), contents.as_bytes()) {
+            Ok(()) => debug!("KV namespace {} saved", namespace),
+            Err(e) => {
+                warn!("❗ Failed to save KV namespace {}: {}", namespace, e);
+                last_error = Some(format!("{namespace}: {e}"));
+            }
+        }
+    }
+    last_error
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[monoio::test]
+    async fn test_values_survive_reopen() {
+        let dir = std::env::temp_dir().join(format!("sriquant-kv-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let store = KvStore::open(&dir).unwrap();
+        let momentum = store.namespace("momentum").unwrap();
+        let grid = store.namespace("grid").unwrap();
+        momentum.set("last_trade_id", &42u64).unwrap();
+        momentum.set("last_trade_id", &43u64).unwrap();
+        momentum.set("armed_stops", &vec!["49000.5".to_string()]).unwrap();
+        grid.set("spacing", "0.25").unwrap();
+        assert!(grid.remove("spacing").unwrap());
+        store.flush().await.unwrap();
+        drop((store, momentum, grid));
+
+        let reopened = KvStore::open(&dir).unwrap();
+        let momentum = reopened.namespace("momentum").unwrap();
+        assert_eq!(momentum.get::<u64>("last_trade_id").unwrap(), Some(43));
+        assert_eq!(momentum.keys(), vec!["armed_stops", "last_trade_id"]);
+        assert_eq!(reopened.namespace("grid").unwrap().get::<String>("spacing").unwrap(), None);
+        assert_eq!(reopened.namespaces(), vec!["momentum"]);
+
+        assert!(reopened.namespace("../escape").is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
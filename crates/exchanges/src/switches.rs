@@ -0,0 +1,197 @@
+//! Per-symbol trading switches
+//!
+//! Operators can pause and resume trading per symbol at runtime, e.g. around
+//! a listing event or a misbehaving feed, without restarting the process.
+//! `TradingSwitches` is a cheap cloneable handle: the admin side (CLI or
+//! endpoint) and the trading thread share the same state. Pausing can
+//! optionally request cancellation of working orders, which the order
+//! owner drains with `take_cancellations`.
+
+use crate::errors::{ExchangeError, Result};
+use crate::incidents::{Incident, IncidentBus, IncidentKind, Severity};
+use sriquant_core::prelude::*;
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, RwLock};
+use tracing::info;
+
+/// Pause state of one symbol
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolPause {
+    pub reason: String,
+    pub paused_at: u64,
+    pub cancel_working: bool,
+}
+
+#[derive(Debug, Default)]
+struct SwitchState {
+    paused: HashMap<String, SymbolPause>,
+    pending_cancels: VecDeque<String>,
+}
+
+/// Shared runtime trading switches
+#[derive(Debug, Clone, Default)]
+pub struct TradingSwitches {
+    state: Arc<RwLock<SwitchState>>,
+    incidents: Option<IncidentBus>,
+}
+
+impl TradingSwitches {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Publish pause/resume as incidents and serve the `incidents` admin command
+    pub fn with_incidents(mut self, bus: IncidentBus) -> Self {
+        self.incidents = Some(bus);
+        self
+    }
+
+    fn publish(&self, incident: Incident) {
+        if let Some(bus) = &self.incidents {
+            bus.publish(incident);
+        }
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, SwitchState> {
+        self.state.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, SwitchState> {
+        self.state.write().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Stop new orders for a symbol, optionally canceling its working orders
+    pub fn pause(&self, symbol: &str, reason: &str, cancel_working: bool) {
+        let symbol = symbol.to_uppercase();
+        let mut state = self.write();
+        if cancel_working {
+            state.pending_cancels.push_back(symbol.clone());
+        }
+        state.paused.insert(symbol.clone(), SymbolPause {
+            reason: reason.to_string(),
+            paused_at: nanos(),
+            cancel_working,
+        });
+        drop(state);
+        info!("⏸️  Trading paused for {} ({}), cancel working: {}", symbol, reason, cancel_working);
+        self.publish(
+            Incident::new(Severity::Warning, "switches", IncidentKind::TradingSwitch, format!("paused: {reason}"))
+                .with_symbol(&symbol)
+                .with_context("cancel_working", cancel_working),
+        );
+    }
+
+    /// Allow new orders again; returns `false` if the symbol wasn't paused
+    pub fn resume(&self, symbol: &str) -> bool {
+        let symbol = symbol.to_uppercase();
+        let resumed = self.write().paused.remove(&symbol).is_some();
+        if resumed {
+            info!("▶️  Trading resumed for {}", symbol);
+            self.publish(Incident::new(Severity::Info, "switches", IncidentKind::TradingSwitch, "resumed").with_symbol(&symbol));
+        }
+        resumed
+    }
+
+    pub fn is_enabled(&self, symbol: &str) -> bool {
+        !self.read().paused.contains_key(&symbol.to_uppercase())
+    }
+
+    /// Pause details for a symbol
+    pub fn pause_info(&self, symbol: &str) -> Option<SymbolPause> {
+        self.read().paused.get(&symbol.to_uppercase()).cloned()
+    }
+
+    /// Currently paused symbols, sorted
+    pub fn paused_symbols(&self) -> Vec<String> {
+        let mut symbols: Vec<String> = self.read().paused.keys().cloned().collect();
+        symbols.sort();
+        symbols
+    }
+
+    /// Symbols whose working orders should be canceled, oldest first
+    pub fn take_cancellations(&self) -> Vec<String> {
+        self.write().pending_cancels.drain(..).collect()
+    }
+
+    /// Apply an admin command and return a human-readable reply
+    ///
+    /// Commands: `pause <SYMBOL> [--cancel] [reason...]`, `resume <SYMBOL>`,
+    /// `status`, `incidents [count]`.
+    pub fn apply_command(&self, command: &str) -> Result<String> {
+        let mut parts = command.split_whitespace();
+        let invalid = |msg: &str| ExchangeError::ConfigurationError(format!("{msg}: '{command}'"));
+
+        match parts.next() {
+            Some("pause") => {
+                let symbol = parts.next().ok_or_else(|| invalid("Missing symbol"))?;
+                let mut cancel = false;
+                let mut reason = Vec::new();
+                for part in parts {
+                    if part == "--cancel" {
+                        cancel = true;
+                    } else {
+                        reason.push(part);
+                    }
+                }
+                let reason = if reason.is_empty() { "operator".to_string() } else { reason.join(" ") };
+                self.pause(symbol, &reason, cancel);
+                Ok(format!("paused {}", symbol.to_uppercase()))
+            }
+            Some("resume") => {
+                let symbol = parts.next().ok_or_else(|| invalid("Missing symbol"))?;
+                if self.resume(symbol) {
+                    Ok(format!("resumed {}", symbol.to_uppercase()))
+                } else {
+                    Ok(format!("{} was not paused", symbol.to_uppercase()))
+                }
+            }
+            Some("status") => {
+                let paused = self.paused_symbols();
+                if paused.is_empty() {
+                    Ok("all symbols enabled".to_string())
+                } else {
+                    Ok(format!("paused: {}", paused.join(", ")))
+                }
+            }
+            Some("incidents") => {
+                let bus = self.incidents.as_ref().ok_or_else(|| invalid("No incident bus attached"))?;
+                let limit = match parts.next() {
+                    Some(n) => n.parse().map_err(|_| invalid("Invalid count"))?,
+                    None => 20,
+                };
+                let lines: Vec<String> = bus.recent(limit).iter().map(|i| i.to_string()).collect();
+                if lines.is_empty() {
+                    Ok("no incidents".to_string())
+                } else {
+                    Ok(lines.join("\n"))
+                }
+            }
+            _ => Err(invalid("Unknown command")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pause_resume_and_cancellations() {
+        let switches = TradingSwitches::new();
+        let admin = switches.clone();
+
+        assert_eq!(admin.apply_command("pause btcusdt --cancel listing halt").unwrap(), "paused BTCUSDT");
+        admin.apply_command("pause ETHUSDT").unwrap();
+
+        assert!(!switches.is_enabled("BTCUSDT"));
+        assert_eq!(switches.pause_info("BTCUSDT").unwrap().reason, "listing halt");
+        assert_eq!(switches.take_cancellations(), vec!["BTCUSDT".to_string()]);
+        assert!(switches.take_cancellations().is_empty());
+
+        assert_eq!(admin.apply_command("status").unwrap(), "paused: BTCUSDT, ETHUSDT");
+        admin.apply_command("resume BTCUSDT").unwrap();
+        assert!(switches.is_enabled("BTCUSDT"));
+        assert!(admin.apply_command("halt everything").is_err());
+    }
+}
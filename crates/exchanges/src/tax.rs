@@ -0,0 +1,255 @@
+//! India crypto tax export
+//!
+//! Gains on virtual digital assets are taxed at a flat 30% (plus 4% cess)
+//! under section 115BBH: only the cost of acquisition is deductible, and a
+//! loss on one transfer can't be set off against gains elsewhere or carried
+//! forward. Section 194S adds 1% TDS on the sale consideration. The report
+//! replays journaled trades through a `CostBasisLedger` and emits one row per
+//! realized lot in INR, grouped by Indian financial year (April-March, IST).
+//!
+//! Amounts in INR are `f64`: rupee values of a single BTC already exceed the
+//! range of `Fixed`.
+
+use crate::accounting::{CostBasisLedger, CostBasisMethod, RealizedLot};
+use crate::errors::{ExchangeError, Result};
+use crate::journal::Journal;
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Write;
+
+/// IST is UTC+05:30
+const IST_OFFSET_MS: i64 = (5 * 60 + 30) * 60 * 1000;
+
+/// Rates applied by the report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndiaTaxConfig {
+    pub tax_rate: f64,
+    /// Health and education cess on the tax
+    pub cess_rate: f64,
+    /// TDS on sale consideration
+    pub tds_rate: f64,
+    pub method: CostBasisMethod,
+}
+
+impl Default for IndiaTaxConfig {
+    fn default() -> Self {
+        Self {
+            tax_rate: 0.30,
+            cess_rate: 0.04,
+            tds_rate: 0.01,
+            method: CostBasisMethod::Fifo,
+        }
+    }
+}
+
+impl IndiaTaxConfig {
+    pub fn with_method(mut self, method: CostBasisMethod) -> Self {
+        self.method = method;
+        self
+    }
+}
+
+/// One realized lot in INR
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndiaTaxRow {
+    /// e.g. "2025-26"
+    pub financial_year: String,
+    /// IST dates, YYYY-MM-DD
+    pub acquired_on: String,
+    pub transferred_on: String,
+    pub symbol: String,
+    pub quantity: String,
+    /// INR per unit of the quote currency at transfer time
+    pub inr_rate: f64,
+    pub cost_of_acquisition_inr: f64,
+    pub sale_consideration_inr: f64,
+    pub gain_inr: f64,
+    /// Losses are not allowed under 115BBH, so this is `max(gain, 0)`
+    pub taxable_gain_inr: f64,
+    pub tax_inr: f64,
+    pub tds_inr: f64,
+    pub open_trade_id: Option<u64>,
+    pub close_trade_id: u64,
+}
+
+/// Totals for one financial year
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IndiaTaxSummary {
+    pub transfers: usize,
+    pub sale_consideration_inr: f64,
+    pub taxable_gains_inr: f64,
+    /// Losses that can't be set off or carried forward
+    pub disallowed_losses_inr: f64,
+    pub tax_inr: f64,
+    pub cess_inr: f64,
+    pub tds_inr: f64,
+}
+
+/// Per-transfer gains and per-year totals
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IndiaTaxReport {
+    pub rows: Vec<IndiaTaxRow>,
+    pub years: BTreeMap<String, IndiaTaxSummary>,
+}
+
+impl IndiaTaxReport {
+    /// Build the report from journaled trades
+    ///
+    /// `inr_rate(symbol, time_ms)` returns INR per unit of the symbol's quote
+    /// currency (e.g. USDT/INR for BTCUSDT) at that time.
+    pub fn from_journal<F>(journal: &Journal, config: &IndiaTaxConfig, inr_rate: F) -> Result<Self>
+    where
+        F: Fn(&str, u64) -> Option<f64>,
+    {
+        let ledger = CostBasisLedger::from_journal(config.method, journal);
+        let mut report = Self::default();
+        for lot in ledger.realized() {
+            let rate = inr_rate(&lot.symbol, lot.disposed_at).ok_or_else(|| {
+                ExchangeError::ConfigurationError(format!("no INR rate for {} at {}", lot.symbol, lot.disposed_at))
+            })?;
+            report.push(Self::row(lot, rate, config)?, config);
+        }
+        Ok(report)
+    }
+
+    fn row(lot: &RealizedLot, rate: f64, config: &IndiaTaxConfig) -> Result<IndiaTaxRow> {
+        let cost = lot.cost_basis.to_f64_lossy() * rate;
+        let consideration = lot.proceeds.to_f64_lossy() * rate;
+        let gain = consideration - cost;
+        let taxable = gain.max(0.0);
+        Ok(IndiaTaxRow {
+            financial_year: financial_year(lot.disposed_at)?,
+            acquired_on: ist_date(lot.acquired_at)?,
+            transferred_on: ist_date(lot.disposed_at)?,
+            symbol: lot.symbol.clone(),
+            quantity: lot.quantity.to_string(),
+            inr_rate: rate,
+            cost_of_acquisition_inr: cost,
+            sale_consideration_inr: consideration,
+            gain_inr: gain,
+            taxable_gain_inr: taxable,
+            tax_inr: taxable * config.tax_rate,
+            tds_inr: consideration * config.tds_rate,
+            open_trade_id: lot.open_trade_id,
+            close_trade_id: lot.close_trade_id,
+        })
+    }
+
+    fn push(&mut self, row: IndiaTaxRow, config: &IndiaTaxConfig) {
+        let year = self.years.entry(row.financial_year.clone()).or_default();
+        year.transfers += 1;
+        year.sale_consideration_inr += row.sale_consideration_inr;
+        year.taxable_gains_inr += row.taxable_gain_inr;
+        year.disallowed_losses_inr += (-row.gain_inr).max(0.0);
+        year.tax_inr += row.tax_inr;
+        year.cess_inr += row.tax_inr * config.cess_rate;
+        year.tds_inr += row.tds_inr;
+        self.rows.push(row);
+    }
+
+    /// Export rows as CSV, one realized lot per line
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> Result<()> {
+        writeln!(
+            writer,
+            "financial_year,acquired_on,transferred_on,symbol,quantity,inr_rate,cost_of_acquisition_inr,\
+             sale_consideration_inr,gain_inr,taxable_gain_inr,tax_inr,tds_inr,open_trade_id,close_trade_id"
+        )?;
+        for row in &self.rows {
+            writeln!(
+                writer,
+                "{},{},{},{},{},{:.4},{:.2},{:.2},{:.2},{:.2},{:.2},{:.2},{},{}",
+                row.financial_year,
+                row.acquired_on,
+                row.transferred_on,
+                row.symbol,
+                row.quantity,
+                row.inr_rate,
+                row.cost_of_acquisition_inr,
+                row.sale_consideration_inr,
+                row.gain_inr,
+                row.taxable_gain_inr,
+                row.tax_inr,
+                row.tds_inr,
+                row.open_trade_id.map(|id| id.to_string()).unwrap_or_default(),
+                row.close_trade_id,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn ist(time_ms: u64) -> Result<chrono::NaiveDate> {
+    chrono::DateTime::from_timestamp_millis(time_ms as i64 + IST_OFFSET_MS)
+        .map(|t| t.date_naive())
+        .ok_or_else(|| ExchangeError::InvalidResponse(format!("timestamp out of range: {time_ms}")))
+}
+
+fn ist_date(time_ms: u64) -> Result<String> {
+    Ok(ist(time_ms)?.format("%Y-%m-%d").to_string())
+}
+
+/// Indian financial year (April to March) containing `time_ms`
+fn financial_year(time_ms: u64) -> Result<String> {
+    use chrono::Datelike;
+    let date = ist(time_ms)?;
+    let start = if date.month() >= 4 { date.year() } else { date.year() - 1 };
+    Ok(format!("{}-{:02}", start, (start + 1) % 100))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::journal::{JournalEvent, TradeRecord};
+    use crate::types::OrderSide;
+    use sriquant_core::prelude::*;
+
+    fn trade(id: u64, side: OrderSide, price: &str, time: u64) -> JournalEvent {
+        let price = Fixed::from_str_exact(price).unwrap();
+        JournalEvent::Trade(TradeRecord {
+            trade_id: id,
+            order_id: id,
+            symbol: "ETHUSDT".to_string(),
+            side,
+            price,
+            quantity: Fixed::ONE,
+            quote_quantity: price,
+            commission: Fixed::ZERO,
+            commission_asset: "USDT".to_string(),
+            is_maker: false,
+            time,
+        })
+    }
+
+    #[test]
+    fn test_losses_are_not_set_off() {
+        // 2025-03-31 20:00 UTC is already 1 April in IST
+        let april_ist = 1_743_451_200_000;
+        let mut journal = Journal::new();
+        for event in [
+            trade(1, OrderSide::Buy, "1000", 1_700_000_000_000),
+            trade(2, OrderSide::Buy, "3000", 1_700_000_000_000),
+            trade(3, OrderSide::Sell, "2000", april_ist),
+            trade(4, OrderSide::Sell, "2000", april_ist),
+        ] {
+            journal.record("binance", event);
+        }
+
+        let report = IndiaTaxReport::from_journal(&journal, &IndiaTaxConfig::default(), |_, _| Some(85.0)).unwrap();
+        assert_eq!(report.rows.len(), 2);
+        assert_eq!(report.rows[0].acquired_on, "2023-11-15");
+        assert_eq!(report.rows[0].transferred_on, "2025-04-01");
+
+        let year = &report.years["2025-26"];
+        assert!((year.taxable_gains_inr - 85_000.0).abs() < 1e-6);
+        assert!((year.disallowed_losses_inr - 85_000.0).abs() < 1e-6);
+        assert!((year.tax_inr - 25_500.0).abs() < 1e-6);
+        assert!((year.tds_inr - 3_400.0).abs() < 1e-6);
+
+        let mut csv = Vec::new();
+        report.write_csv(&mut csv).unwrap();
+        assert_eq!(String::from_utf8(csv).unwrap().lines().count(), 3);
+
+        assert!(IndiaTaxReport::from_journal(&journal, &IndiaTaxConfig::default(), |_, _| None).is_err());
+    }
+}
@@ -0,0 +1,546 @@
+//! Binance testnet bootstrap
+//!
+//! Most first runs against the testnet fail for the same handful of reasons:
+//! keys missing from the environment, mainnet keys used on the testnet, an
+//! Ed25519/RSA key where the client signs with HMAC, an IP whitelist, or a
+//! drifting clock. `bootstrap_testnet` checks each in turn and returns a
+//! `DoctorReport` whose failed checks say what to fix, instead of a bare
+//! -2015 from the first signed request.
+//!
+//! The testnet has no faucet endpoint: accounts are funded when the key is
+//! created and balances are reset about once a month.
+//!
+//! `MockBinance` stands in for the REST API in tests. Plugged into a client
+//! with `with_mock`, it answers in-process and enforces request-weight and
+//! order-count windows the way Binance does: usage headers on every
+//! response, 429 with `Retry-After` when a window is exceeded, and a 418
+//! ban, doubling on each repeat, for requests sent during the back-off.
+
+use crate::bars::ExchangeClock;
+use crate::binance::{BinanceApiError, BinanceConfig, BinanceRestClient};
+use crate::errors::ExchangeError;
+use crate::types::{OrderSide, OrderType};
+use sriquant_core::doctor::{CheckStatus, DoctorCheck, DoctorReport};
+use sriquant_core::prelude::*;
+
+use crate::binance::rate_limiter::{interval_ms, is_order_endpoint};
+use crate::binance::RateLimiter;
+use crate::http::HttpResponse;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use tracing::{debug, info, warn};
+
+const TESTNET_KEYS_URL: &str = "https://testnet.binance.vision";
+
+/// What `bootstrap_testnet` checks and whether it seeds orders
+#[derive(Debug, Clone)]
+pub struct BootstrapOptions {
+    /// Symbol checked for trading and used for seed orders
+    pub symbol: String,
+    /// Free balances the strategy needs, as (asset, minimum)
+    pub required_balances: Vec<(String, Fixed)>,
+    /// Place and cancel one small resting order to prove the key can trade
+    pub seed_orders: bool,
+    /// Clock offset above which signed requests start failing
+    pub max_clock_skew_ms: u64,
+}
+
+impl Default for BootstrapOptions {
+    fn default() -> Self {
+        Self {
+            symbol: "BTCUSDT".to_string(),
+            required_balances: vec![("USDT".to_string(), Fixed::from_i64(10).unwrap_or(Fixed::ZERO))],
+            seed_orders: false,
+            max_clock_skew_ms: 1_000,
+        }
+    }
+}
+
+impl BootstrapOptions {
+    pub fn with_symbol(mut self, symbol: impl Into<String>) -> Self {
+        self.symbol = symbol.into();
+        self
+    }
+
+    /// Require at least `minimum` free `asset`
+    pub fn with_required_balance(mut self, asset: impl Into<String>, minimum: Fixed) -> Self {
+        self.required_balances.push((asset.into(), minimum));
+        self
+    }
+
+    pub fn with_seed_orders(mut self, seed: bool) -> Self {
+        self.seed_orders = seed;
+        self
+    }
+
+    pub fn with_max_clock_skew_ms(mut self, max_clock_skew_ms: u64) -> Self {
+        self.max_clock_skew_ms = max_clock_skew_ms;
+        self
+    }
+}
+
+/// Outcome of `bootstrap_testnet`
+pub struct TestnetBootstrap {
+    pub report: DoctorReport,
+    /// Exchange time minus local time, when the server was reachable
+    pub clock_offset_ms: Option<i64>,
+    /// Ids of seed orders placed and cancelled
+    pub seeded_order_ids: Vec<u64>,
+    /// Ready-to-use client when the configuration loaded
+    pub client: Option<BinanceRestClient>,
+}
+
+impl TestnetBootstrap {
+    /// No check failed
+    pub fn is_ready(&self) -> bool {
+        !self.report.has_failures()
+    }
+}
+
+/// What to do about an error from the testnet
+pub fn diagnose(error: &ExchangeError) -> String {
+    match error.root() {
+        ExchangeError::MissingCredentials(var) => format!(
+            "{var} is not set. Create an HMAC key at {TESTNET_KEYS_URL} and export BINANCE_API_KEY and BINANCE_SECRET_KEY"
+        ),
+        ExchangeError::BinanceApi(_, BinanceApiError::InvalidApiKey) => format!(
+            "Key rejected (-2014/-2015). Keys from binance.com don't work on the testnet; create one at {TESTNET_KEYS_URL}. \
+             Also check the key's IP whitelist includes this host"
+        ),
+        ExchangeError::BinanceApi(_, BinanceApiError::InvalidSignature) => {
+            "Signature rejected (-1022). The secret doesn't match the key, or the key is Ed25519/RSA; this client signs with HMAC-SHA256, so use an HMAC key".to_string()
+        }
+        ExchangeError::BinanceApi(_, BinanceApiError::TimestampOutOfRecvWindow) => {
+            "Timestamp outside recvWindow (-1021). Sync the system clock (chrony or ntpdate) and retry".to_string()
+        }
+        ExchangeError::BinanceApi(_, BinanceApiError::IpBanned { .. } | BinanceApiError::RateLimited { .. }) => {
+            "Rate limited by the testnet. Wait a minute before retrying".to_string()
+        }
+        ExchangeError::HttpError(451 | 403, _) => {
+            "Access refused from this location (HTTP 403/451). The testnet blocks some regions and cloud IP ranges".to_string()
+        }
+        ExchangeError::NetworkError(_) | ExchangeError::Timeout(_) => {
+            format!("Cannot reach {TESTNET_KEYS_URL}. Check DNS, proxy and firewall settings")
+        }
+        other => other.to_string(),
+    }
+}
+
+fn fail(name: &str, error: &ExchangeError) -> DoctorCheck {
+    DoctorCheck::status(name, CheckStatus::Fail, diagnose(error))
+}
+
+/// Check the testnet is usable with the credentials in the environment
+///
+/// ```rust,ignore
+/// let bootstrap = bootstrap_testnet().await;
+/// bootstrap.report.log();
+/// if !bootstrap.is_ready() {
+///     return Ok(());
+/// }
+/// let client = bootstrap.client.unwrap();
+/// ```
+pub async fn bootstrap_testnet() -> TestnetBootstrap {
+    bootstrap_testnet_with(BootstrapOptions::default()).await
+}
+
+/// `bootstrap_testnet` with other balances, a different symbol or seed orders
+pub async fn bootstrap_testnet_with(options: BootstrapOptions) -> TestnetBootstrap {
+    let mut bootstrap = TestnetBootstrap { report: DoctorReport::new(), clock_offset_ms: None, seeded_order_ids: Vec::new(), client: None };
+    let timer = PerfTimer::start("testnet_bootstrap".to_string());
+
+    let config = match BinanceConfig::testnet().with_env_credentials() {
+        Ok(config) => {
+            bootstrap.report.add(DoctorCheck::status("credentials", CheckStatus::Pass, "loaded from environment"));
+            config
+        }
+        Err(e) => {
+            bootstrap.report.add(fail("credentials", &e));
+            return bootstrap;
+        }
+    };
+    let client = match BinanceRestClient::new(config).await {
+        Ok(client) => client,
+        Err(e) => {
+            bootstrap.report.add(fail("client", &e));
+            return bootstrap;
+        }
+    };
+
+    // Clock first: a skewed clock fails every signed check after it
+    let before = nanos() / 1_000_000;
+    match client.server_time().await {
+        Ok(server_time) => {
+            let offset = ExchangeClock::new().observe(before, server_time, nanos() / 1_000_000);
+            bootstrap.clock_offset_ms = Some(offset);
+            let max = options.max_clock_skew_ms as f64;
+            let mut check = DoctorCheck::measured("clock_skew", offset.unsigned_abs() as f64, max / 2.0, Some(max), "ms");
+            if check.status != CheckStatus::Pass {
+                check = check.with_detail("sync the system clock (chrony or ntpdate)");
+            }
+            bootstrap.report.add(check);
+        }
+        Err(e) => {
+            bootstrap.report.add(fail("server_time", &e));
+            return bootstrap;
+        }
+    }
+
+    match client.get_account_info().await {
+        Ok(account) => {
+            if account.can_trade && account.permissions.iter().any(|p| p == "SPOT") {
+                bootstrap.report.add(DoctorCheck::status("permissions", CheckStatus::Pass, format!("{} can trade spot", account.account_type)));
+            } else {
+                bootstrap.report.add(DoctorCheck::status(
+                    "permissions",
+                    CheckStatus::Fail,
+                    format!("key can't trade spot (permissions: {:?}); enable trading on the key at {TESTNET_KEYS_URL}", account.permissions),
+                ));
+            }
+            for (asset, minimum) in &options.required_balances {
+                let free = account.balances.iter()
+                    .find(|b| &b.asset == asset)
+                    .map_or(Fixed::ZERO, |b| b.free);
+                let check = if free >= *minimum {
+                    DoctorCheck::status(&format!("balance_{asset}"), CheckStatus::Pass, format!("{free} free"))
+                } else {
+                    DoctorCheck::status(
+                        &format!("balance_{asset}"),
+                        CheckStatus::Fail,
+                        format!("{free} free, need {minimum}; testnet balances reset monthly, or create a new key to get a funded account"),
+                    )
+                };
+                bootstrap.report.add(check);
+            }
+        }
+        Err(e) => bootstrap.report.add(fail("account", &e)),
+    }
+
+    if options.seed_orders && !bootstrap.report.has_failures() {
+        match seed_order(&client, &options.symbol).await {
+            Ok(order_id) => {
+                bootstrap.seeded_order_ids.push(order_id);
+                bootstrap.report.add(DoctorCheck::status("seed_order", CheckStatus::Pass, format!("placed and cancelled {order_id} on {}", options.symbol)));
+            }
+            Err(e) => bootstrap.report.add(fail("seed_order", &e)),
+        }
+    }
+
+    timer.log_elapsed();
+    if bootstrap.report.has_failures() {
+        warn!("❗ Testnet not ready; see the failed checks");
+    } else {
+        info!("✅ Testnet ready");
+    }
+    bootstrap.client = Some(client);
+    bootstrap
+}
+
+/// Rest the smallest valid buy well below the market, then cancel it
+async fn seed_order(client: &BinanceRestClient, symbol: &str) -> crate::errors::Result<u64> {
+    let info = client.exchange_info().await?;
+    let rules = info.symbols.iter()
+        .find(|s| s.symbol == symbol)
+        .ok_or_else(|| ExchangeError::InvalidSymbol(symbol.to_string()))?
+        .rules()?;
+    let last = client.get_symbol_price_ticker(symbol).await?.price;
+    let price = rules.round_price(last * Fixed::from_str_exact("0.8")?);
+    // Twice the minimum notional leaves room for the quantity rounding down
+    let min_notional = rules.notional.map(|n| n.min_notional).unwrap_or(Fixed::from_i64(10)?);
+    let quantity = min_notional * Fixed::from_i64(2)? / price;
+    let (price, quantity) = rules.validate_and_round(Some(price), quantity)?;
+
+    let order = client.place_order(symbol, OrderSide::Buy, OrderType::Limit, quantity, price).await?;
+    info!("🌱 Seed order {} resting: BUY {} {} @ {:?}", order.order_id, quantity, symbol, price);
+    client.cancel_order(symbol, order.order_id).await?;
+    Ok(order.order_id)
+}
+
+/// Counters of what a `MockBinance` answered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MockStats {
+    pub requests: u64,
+    /// 429 responses
+    pub rate_limited: u64,
+    /// 418 responses
+    pub banned: u64,
+}
+
+/// One epoch-aligned window, as Binance counts them
+#[derive(Debug, Clone)]
+struct MockWindow {
+    interval: String,
+    interval_ms: u64,
+    limit: u32,
+    start_ms: u64,
+    used: u32,
+}
+
+impl MockWindow {
+    fn new(interval: &str, limit: u32) -> Self {
+        let interval = interval.to_ascii_uppercase();
+        let interval_ms = interval_ms(&interval).unwrap_or_else(|| panic!("invalid mock window interval {interval:?}"));
+        Self { interval, interval_ms, limit, start_ms: 0, used: 0 }
+    }
+
+    fn roll(&mut self, now_ms: u64) {
+        let start = now_ms - now_ms % self.interval_ms;
+        if start != self.start_ms {
+            self.start_ms = start;
+            self.used = 0;
+        }
+    }
+
+    /// Whole seconds until the window resets, at least one
+    fn retry_after_secs(&self, now_ms: u64) -> u64 {
+        (self.start_ms + self.interval_ms).saturating_sub(now_ms).div_ceil(1000).max(1)
+    }
+
+    /// `interval` in exchangeInfo terms: ("SECOND", 10) for "10S"
+    fn interval_unit(&self) -> (&'static str, u32) {
+        let (num, unit) = self.interval.split_at(self.interval.len() - 1);
+        let unit = match unit {
+            "S" => "SECOND",
+            "M" => "MINUTE",
+            "H" => "HOUR",
+            _ => "DAY",
+        };
+        (unit, num.parse().unwrap_or(1))
+    }
+}
+
+struct MockState {
+    weight: MockWindow,
+    orders: MockWindow,
+    weights: RateLimiter,
+    /// Set by a 429; requests before it expires earn a ban
+    backoff_until_ms: Option<u64>,
+    banned_until_ms: Option<u64>,
+    ban_secs: u64,
+    bans: u32,
+    next_order_id: u64,
+    /// Orders placed, as `GET /api/v3/order` reports them
+    placed: Vec<serde_json::Value>,
+    responses: HashMap<(String, String), String>,
+    stats: MockStats,
+}
+
+/// In-process Binance REST API with rate limits
+///
+/// Clones share state, so a test can keep one to inspect `stats` while the
+/// client owns another. Answers ping, time, `POST /api/v3/order` (acked as
+/// NEW), `GET /api/v3/order` for orders it placed and `/api/v3/rateLimit/order`
+/// itself; other endpoints need a body from `with_response`.
+///
+/// ```rust,ignore
+/// let mock = MockBinance::new().with_weight_limit(50, "1M");
+/// let client = BinanceRestClient::new(config).await?.with_mock(mock.clone());
+/// ```
+#[derive(Clone)]
+pub struct MockBinance {
+    state: Rc<RefCell<MockState>>,
+}
+
+impl Default for MockBinance {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockBinance {
+    /// Binance spot's default limits: 6000 weight per minute, 100 orders per 10s
+    pub fn new() -> Self {
+        Self {
+            state: Rc::new(RefCell::new(MockState {
+                weight: MockWindow::new("1M", 6000),
+                orders: MockWindow::new("10S", 100),
+                weights: RateLimiter::default(),
+                backoff_until_ms: None,
+                banned_until_ms: None,
+                ban_secs: 120,
+                bans: 0,
+                next_order_id: 1,
+                placed: Vec::new(),
+                responses: HashMap::new(),
+                stats: MockStats::default(),
+            })),
+        }
+    }
+
+    /// Request-weight window, with an interval like "1M" or "10S"
+    pub fn with_weight_limit(self, limit: u32, interval: &str) -> Self {
+        self.state.borrow_mut().weight = MockWindow::new(interval, limit);
+        self
+    }
+
+    /// Order-count window, with an interval like "10S" or "1D"
+    pub fn with_order_limit(self, limit: u32, interval: &str) -> Self {
+        self.state.borrow_mut().orders = MockWindow::new(interval, limit);
+        self
+    }
+
+    /// Length of the first ban; each further ban doubles it
+    pub fn with_ban_secs(self, secs: u64) -> Self {
+        self.state.borrow_mut().ban_secs = secs;
+        self
+    }
+
+    /// Body returned for `method path` (path without the query)
+    pub fn with_response(self, method: &str, path: &str, body: impl Into<String>) -> Self {
+        self.state.borrow_mut().responses.insert((method.to_ascii_uppercase(), path.to_string()), body.into());
+        self
+    }
+
+    pub fn stats(&self) -> MockStats {
+        self.state.borrow().stats
+    }
+
+    /// Answer a request as Binance would
+    pub fn respond(&self, method: &str, url: &str) -> HttpResponse {
+        let now = nanos() / 1_000_000;
+        let url = url::Url::parse(url).ok();
+        let path = url.as_ref().map(|u| u.path().to_string()).unwrap_or_default();
+        let query: HashMap<String, String> = url.as_ref().map(|u| u.query_pairs().into_owned().collect()).unwrap_or_default();
+        let method = method.to_ascii_uppercase();
+
+        let mut state = self.state.borrow_mut();
+        let state = &mut *state;
+        state.stats.requests += 1;
+
+        if let Some(until) = state.banned_until_ms.filter(|until| *until > now) {
+            return state.ban_response(until, now);
+        }
+        if state.backoff_until_ms.is_some_and(|until| until > now) {
+            // Ignored Retry-After: the ban doubles with every repeat
+            let until = now + state.ban_secs.saturating_mul(1 << state.bans.min(10)) * 1000;
+            state.bans += 1;
+            state.banned_until_ms = Some(until);
+            state.backoff_until_ms = None;
+            warn!("🧪 Mock Binance banning for {}s after requests during back-off", (until - now) / 1000);
+            return state.ban_response(until, now);
+        }
+
+        state.weight.roll(now);
+        state.weight.used += state.weights.endpoint_weight(&path);
+        let mut headers = vec![(format!("X-MBX-USED-WEIGHT-{}", state.weight.interval), state.weight.used.to_string())];
+        if state.weight.used > state.weight.limit {
+            let retry_after = state.weight.retry_after_secs(now);
+            state.backoff_until_ms = Some(now + retry_after * 1000);
+            state.stats.rate_limited += 1;
+            headers.push(("Retry-After".to_string(), retry_after.to_string()));
+            let msg = format!(
+                "Too much request weight used; current limit is {} request weight per {} {}. Please use WebSocket Streams for live updates to avoid polling the API.",
+                state.weight.limit, state.weight.interval_unit().1, state.weight.interval_unit().0
+            );
+            return response(429, headers, -1003, &msg);
+        }
+
+        if is_order_endpoint(&method, &path) {
+            state.orders.roll(now);
+            state.orders.used += 1;
+            headers.push((format!("X-MBX-ORDER-COUNT-{}", state.orders.interval), state.orders.used.to_string()));
+            if state.orders.used > state.orders.limit {
+                // Binance sends no Retry-After for order-count rejections
+                state.stats.rate_limited += 1;
+                let (unit, num) = state.orders.interval_unit();
+                let msg = format!("Too many new orders; current limit is {} orders per {num} {unit}.", state.orders.limit);
+                return response(429, headers, -1015, &msg);
+            }
+        }
+
+        let body = match (method.as_str(), path.as_str()) {
+            (method, path) if state.responses.contains_key(&(method.to_string(), path.to_string())) => {
+                state.responses[&(method.to_string(), path.to_string())].clone()
+            }
+            ("GET", "/api/v3/ping") => "{}".to_string(),
+            ("GET", "/api/v3/time") => format!(r#"{{"serverTime":{now}}}"#),
+            ("GET", "/api/v3/rateLimit/order") => {
+                state.orders.roll(now);
+                let (unit, num) = state.orders.interval_unit();
+                serde_json::json!([{
+                    "rateLimitType": "ORDERS", "interval": unit, "intervalNum": num,
+                    "limit": state.orders.limit, "count": state.orders.used,
+                }])
+                .to_string()
+            }
+            ("POST", "/api/v3/order") => {
+                let order_id = state.next_order_id;
+                state.next_order_id += 1;
+                let param = |name: &str, default: &str| query.get(name).cloned().unwrap_or_else(|| default.to_string());
+                let ack = serde_json::json!({
+                    "symbol": param("symbol", ""), "orderId": order_id, "orderListId": -1,
+                    "clientOrderId": param("newClientOrderId", &format!("mock{order_id}")), "transactTime": now,
+                    "price": param("price", "0"), "origQty": param("quantity", "0"), "executedQty": "0",
+                    "cummulativeQuoteQty": "0", "status": "NEW", "timeInForce": param("timeInForce", "GTC"),
+                    "type": param("type", "LIMIT"), "side": param("side", "BUY"),
+                });
+                let mut placed = ack.clone();
+                if let Some(order) = placed.as_object_mut() {
+                    order.remove("transactTime");
+                    for (key, value) in [("stopPrice", "0"), ("icebergQty", "0"), ("origQuoteOrderQty", "0")] {
+                        order.insert(key.to_string(), value.into());
+                    }
+                    order.insert("time".to_string(), now.into());
+                    order.insert("updateTime".to_string(), now.into());
+                    order.insert("isWorking".to_string(), true.into());
+                }
+                state.placed.push(placed);
+                ack.to_string()
+            }
+            ("GET", "/api/v3/order") => {
+                let matches = |order: &&serde_json::Value| match (query.get("origClientOrderId"), query.get("orderId")) {
+                    (Some(id), _) => order["clientOrderId"] == id.as_str(),
+                    (None, Some(id)) => id.parse::<u64>().is_ok_and(|id| order["orderId"] == id),
+                    (None, None) => false,
+                };
+                match state.placed.iter().find(matches) {
+                    Some(order) => order.to_string(),
+                    None => return response(400, headers, -2013, "Order does not exist."),
+                }
+            }
+            _ => return response(404, headers, -1000, &format!("no mock response for {method} {path}")),
+        };
+        debug!("🧪 Mock Binance {} {} (weight {}/{})", method, path, state.weight.used, state.weight.limit);
+        HttpResponse { status: 200, headers, body }
+    }
+}
+
+impl MockState {
+    fn ban_response(&mut self, until: u64, now: u64) -> HttpResponse {
+        self.stats.banned += 1;
+        let headers = vec![("Retry-After".to_string(), ((until - now).div_ceil(1000)).to_string())];
+        let msg = format!("Way too much request weight used; IP banned until {until}. Please use WebSocket Streams for live updates to avoid bans.");
+        response(418, headers, -1003, &msg)
+    }
+}
+
+fn response(status: u16, headers: Vec<(String, String)>, code: i64, msg: &str) -> HttpResponse {
+    HttpResponse { status, headers, body: serde_json::json!({"code": code, "msg": msg}).to_string() }
+}
+
+/// `Fixed` from a decimal literal, for tests
+#[cfg(test)]
+pub(crate) fn fx(s: &str) -> Fixed {
+    Fixed::from_str_exact(s).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diagnose_names_the_fix() {
+        let mainnet_key = ExchangeError::BinanceApi(401, BinanceApiError::InvalidApiKey).with_endpoint("/api/v3/account");
+        assert!(diagnose(&mainnet_key).contains("binance.com"));
+
+        let ed25519 = ExchangeError::BinanceApi(400, BinanceApiError::InvalidSignature);
+        assert!(diagnose(&ed25519).contains("HMAC"));
+
+        let skew = ExchangeError::BinanceApi(400, BinanceApiError::TimestampOutOfRecvWindow);
+        assert!(diagnose(&skew).contains("clock"));
+
+        let missing = ExchangeError::MissingCredentials("BINANCE_API_KEY".to_string());
+        assert!(diagnose(&missing).starts_with("BINANCE_API_KEY is not set"));
+    }
+}
@@ -2,6 +2,10 @@
 //!
 //! High-performance architecture with async traits
 //! and high-performance abstractions.
+//!
+//! The market data and trading traits are `?Send`: implementations run on a
+//! single-threaded monoio runtime and hold `!Send` sockets, so share them
+//! with `Rc` rather than `Arc`.
 
 use crate::errors::Result;
 use crate::types::*;
@@ -10,8 +14,8 @@ use std::collections::HashMap;
 use sriquant_core::Fixed;
 
 /// Core exchange interface
-#[async_trait]
-pub trait Exchange: Send + Sync {
+#[async_trait(?Send)]
+pub trait Exchange {
     /// Get exchange name
     fn name(&self) -> &str;
     
@@ -51,7 +55,7 @@ pub trait Exchange: Send + Sync {
 }
 
 /// Trading interface for exchanges that support trading
-#[async_trait]
+#[async_trait(?Send)]
 pub trait TradingExchange: Exchange {
     /// Place a new order
     async fn place_order(&self, request: OrderRequest) -> Result<OrderResponse>;
@@ -88,8 +92,8 @@ pub trait TradingExchange: Exchange {
 }
 
 /// Streaming interface for real-time market data
-#[async_trait]
-pub trait StreamingExchange: Send + Sync {
+#[async_trait(?Send)]
+pub trait StreamingExchange {
     /// Connect to WebSocket streams
     async fn connect(&mut self) -> Result<()>;
     
@@ -122,7 +126,7 @@ pub trait StreamingExchange: Send + Sync {
 }
 
 /// Advanced trading features
-#[async_trait]
+#[async_trait(?Send)]
 pub trait AdvancedTradingExchange: TradingExchange {
     /// Place multiple orders atomically
     async fn place_batch_orders(&self, requests: Vec<OrderRequest>) -> Result<Vec<OrderResponse>>;
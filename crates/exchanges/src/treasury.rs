@@ -0,0 +1,417 @@
+//! Cross-venue balances and capital transfers
+//!
+//! `BalanceAggregator` keeps the latest balances reported by every venue and
+//! folds them into one `PortfolioView`, valued in a common asset through a
+//! `CurrencyConverter`. `TransferPlanner` turns target allocations into
+//! withdraw/deposit legs, choosing the cheapest network both venues support
+//! and checking withdrawal fees, minimums and free balances before anything
+//! is sent. Executing a plan — calling the venues' withdrawal APIs and
+//! waiting for the deposits to be credited — is left to the caller.
+//!
+//! ```rust,ignore
+//! let mut balances = BalanceAggregator::new().with_max_age_ms(60_000);
+//! balances.refresh_all(&[&binance, &bybit], now_ms).await;
+//! let view = balances.view(&converter, "USDT", now_ms);
+//!
+//! let plan = planner.rebalance(&balances, "USDT", &[("binance", target), ("bybit", target)]);
+//! for step in plan.steps() { /* withdraw, then await the deposit */ }
+//! ```
+
+use crate::conversion::CurrencyConverter;
+use crate::errors::{ExchangeError, Result};
+use crate::traits::Exchange;
+use crate::types::Balance;
+use sriquant_core::prelude::*;
+
+use std::collections::{BTreeMap, HashMap};
+use tracing::{debug, info, warn};
+
+/// Balances last reported by one venue
+#[derive(Debug, Clone)]
+pub struct VenueBalances {
+    pub venue: String,
+    pub balances: Vec<Balance>,
+    pub updated_at: u64,
+}
+
+/// One asset across every venue
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetHoldings {
+    pub asset: String,
+    pub free: Fixed,
+    pub locked: Fixed,
+    /// Free + locked per venue
+    pub by_venue: BTreeMap<String, Fixed>,
+}
+
+impl AssetHoldings {
+    pub fn total(&self) -> Fixed {
+        self.free + self.locked
+    }
+}
+
+/// Every venue's balances as one portfolio
+#[derive(Debug, Clone, PartialEq)]
+pub struct PortfolioView {
+    pub assets: BTreeMap<String, AssetHoldings>,
+    /// Total value in `valuation_asset`
+    pub value: f64,
+    pub valuation_asset: String,
+    /// Assets left out of `value` for lack of a price
+    pub unpriced: Vec<String>,
+    /// Venues whose balances are older than the aggregator's max age
+    pub stale_venues: Vec<String>,
+}
+
+/// Latest balances from all configured venues
+#[derive(Debug, Clone, Default)]
+pub struct BalanceAggregator {
+    venues: BTreeMap<String, VenueBalances>,
+    max_age_ms: Option<u64>,
+}
+
+impl BalanceAggregator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Flag venues whose balances are older than this in `PortfolioView::stale_venues`
+    pub fn with_max_age_ms(mut self, max_age_ms: u64) -> Self {
+        self.max_age_ms = Some(max_age_ms);
+        self
+    }
+
+    /// Replace a venue's balances
+    pub fn update(&mut self, venue: &str, balances: Vec<Balance>, now_ms: u64) {
+        self.venues.insert(venue.to_string(), VenueBalances { venue: venue.to_string(), balances, updated_at: now_ms });
+    }
+
+    /// Fetch balances from an exchange, keyed by its name
+    pub async fn refresh<E: Exchange + ?Sized>(&mut self, exchange: &E, now_ms: u64) -> Result<()> {
+        let balances = exchange.balances().await?;
+        debug!("💼 {} reported {} balances", exchange.name(), balances.len());
+        self.update(exchange.name(), balances, now_ms);
+        Ok(())
+    }
+
+    /// Refresh every venue; a failing venue keeps its last balances and is
+    /// returned with its error
+    pub async fn refresh_all(&mut self, exchanges: &[&dyn Exchange], now_ms: u64) -> Vec<(String, ExchangeError)> {
+        let mut failures = Vec::new();
+        for exchange in exchanges {
+            if let Err(e) = self.refresh(*exchange, now_ms).await {
+                warn!("⚠️ Balance refresh failed for {}: {}", exchange.name(), e);
+                failures.push((exchange.name().to_string(), e));
+            }
+        }
+        failures
+    }
+
+    pub fn venues(&self) -> impl Iterator<Item = &VenueBalances> {
+        self.venues.values()
+    }
+
+    pub fn balance(&self, venue: &str, asset: &str) -> Option<&Balance> {
+        self.venues.get(venue)?.balances.iter().find(|b| b.asset == asset)
+    }
+
+    /// Free balance of `asset` on `venue`, zero if unknown
+    pub fn free(&self, venue: &str, asset: &str) -> Fixed {
+        self.balance(venue, asset).map_or(Fixed::ZERO, |b| b.free)
+    }
+
+    /// Fold all venues into one view valued in `valuation_asset`
+    pub fn view(&self, converter: &CurrencyConverter, valuation_asset: &str, now_ms: u64) -> PortfolioView {
+        let mut assets: BTreeMap<String, AssetHoldings> = BTreeMap::new();
+        for venue in self.venues.values() {
+            for balance in venue.balances.iter().filter(|b| !b.total().is_zero()) {
+                let holdings = assets.entry(balance.asset.clone()).or_insert_with(|| AssetHoldings {
+                    asset: balance.asset.clone(),
+                    free: Fixed::ZERO,
+                    locked: Fixed::ZERO,
+                    by_venue: BTreeMap::new(),
+                });
+                holdings.free += balance.free;
+                holdings.locked += balance.locked;
+                *holdings.by_venue.entry(venue.venue.clone()).or_insert(Fixed::ZERO) += balance.total();
+            }
+        }
+
+        let totals: Vec<Balance> = assets.values()
+            .map(|h| Balance { asset: h.asset.clone(), free: h.free, locked: h.locked })
+            .collect();
+        let (value, unpriced) = converter.value_balances(&totals, valuation_asset, now_ms);
+        let stale_venues = match self.max_age_ms {
+            Some(max_age) => self.venues.values()
+                .filter(|v| now_ms.saturating_sub(v.updated_at) > max_age)
+                .map(|v| v.venue.clone())
+                .collect(),
+            None => Vec::new(),
+        };
+
+        PortfolioView { assets, value, valuation_asset: valuation_asset.to_string(), unpriced, stale_venues }
+    }
+}
+
+/// Withdrawal terms for an asset leaving a venue over one network
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WithdrawalRule {
+    pub venue: String,
+    pub asset: String,
+    /// e.g. "TRX", "ETH", "BSC"
+    pub network: String,
+    /// Deducted from the amount withdrawn
+    pub fee: Fixed,
+    pub min_amount: Fixed,
+    pub max_amount: Option<Fixed>,
+    pub enabled: bool,
+}
+
+impl WithdrawalRule {
+    pub fn new(venue: &str, asset: &str, network: &str, fee: Fixed, min_amount: Fixed) -> Self {
+        Self {
+            venue: venue.to_string(),
+            asset: asset.to_string(),
+            network: network.to_string(),
+            fee,
+            min_amount,
+            max_amount: None,
+            enabled: true,
+        }
+    }
+
+    pub fn with_max_amount(mut self, max_amount: Fixed) -> Self {
+        self.max_amount = Some(max_amount);
+        self
+    }
+}
+
+/// One transfer between venues
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransferLeg {
+    pub asset: String,
+    pub from: String,
+    pub to: String,
+    pub network: String,
+    /// Amount withdrawn from `from`
+    pub amount: Fixed,
+    pub fee: Fixed,
+    /// Amount credited on `to`
+    pub received: Fixed,
+}
+
+/// A step of a transfer plan, in execution order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransferStep {
+    Withdraw { venue: String, asset: String, network: String, amount: Fixed },
+    /// Wait until `venue` credits the deposit before using the funds there
+    AwaitDeposit { venue: String, asset: String, network: String, amount: Fixed },
+}
+
+/// Transfers that move capital towards the target allocation
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransferPlan {
+    pub legs: Vec<TransferLeg>,
+    /// Shortfalls that couldn't be covered, and why
+    pub unresolved: Vec<(String, String)>,
+}
+
+impl TransferPlan {
+    /// Withdrawals first, all funded from current free balances, then the deposits to await
+    pub fn steps(&self) -> Vec<TransferStep> {
+        let withdrawals = self.legs.iter().map(|leg| TransferStep::Withdraw {
+            venue: leg.from.clone(),
+            asset: leg.asset.clone(),
+            network: leg.network.clone(),
+            amount: leg.amount,
+        });
+        let deposits = self.legs.iter().map(|leg| TransferStep::AwaitDeposit {
+            venue: leg.to.clone(),
+            asset: leg.asset.clone(),
+            network: leg.network.clone(),
+            amount: leg.received,
+        });
+        withdrawals.chain(deposits).collect()
+    }
+
+    pub fn total_fees(&self) -> Fixed {
+        self.legs.iter().fold(Fixed::ZERO, |total, leg| total + leg.fee)
+    }
+}
+
+/// Plans withdrawals and deposits between venues
+#[derive(Debug, Clone, Default)]
+pub struct TransferPlanner {
+    /// (venue, asset) -> withdrawal networks
+    withdrawals: HashMap<(String, String), Vec<WithdrawalRule>>,
+    /// (venue, asset) -> networks deposits are accepted on
+    deposits: HashMap<(String, String), Vec<String>>,
+}
+
+impl TransferPlanner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_withdrawal(mut self, rule: WithdrawalRule) -> Self {
+        self.withdrawals.entry((rule.venue.clone(), rule.asset.clone())).or_default().push(rule);
+        self
+    }
+
+    pub fn with_deposit_network(mut self, venue: &str, asset: &str, network: &str) -> Self {
+        self.deposits.entry((venue.to_string(), asset.to_string())).or_default().push(network.to_string());
+        self
+    }
+
+    /// Cheapest enabled network `asset` can leave `from` and arrive at `to` on
+    fn route(&self, asset: &str, from: &str, to: &str) -> Option<&WithdrawalRule> {
+        let accepted = self.deposits.get(&(to.to_string(), asset.to_string()))?;
+        self.withdrawals.get(&(from.to_string(), asset.to_string()))?
+            .iter()
+            .filter(|rule| rule.enabled && accepted.contains(&rule.network))
+            .min_by_key(|rule| rule.fee)
+    }
+
+    /// Plan withdrawing `amount` of `asset` from `from` to `to`
+    pub fn plan_transfer(&self, balances: &BalanceAggregator, asset: &str, from: &str, to: &str, amount: Fixed) -> Result<TransferLeg> {
+        let rule = self.route(asset, from, to).ok_or_else(|| {
+            ExchangeError::ConfigurationError(format!("No network to move {asset} from {from} to {to}"))
+        })?;
+        if amount < rule.min_amount {
+            return Err(ExchangeError::InvalidOrder(format!(
+                "{amount} {asset} is below the {} minimum withdrawal of {} on {}", from, rule.min_amount, rule.network
+            )));
+        }
+        if rule.max_amount.is_some_and(|max| amount > max) {
+            return Err(ExchangeError::InvalidOrder(format!("{amount} {asset} exceeds the {from} withdrawal maximum on {}", rule.network)));
+        }
+        if amount <= rule.fee {
+            return Err(ExchangeError::InvalidOrder(format!("{amount} {asset} doesn't cover the {} withdrawal fee", rule.fee)));
+        }
+        if balances.free(from, asset) < amount {
+            return Err(ExchangeError::InsufficientBalance);
+        }
+        Ok(TransferLeg {
+            asset: asset.to_string(),
+            from: from.to_string(),
+            to: to.to_string(),
+            network: rule.network.clone(),
+            amount,
+            fee: rule.fee,
+            received: amount - rule.fee,
+        })
+    }
+
+    /// Move `asset` so each venue ends up with its target free balance
+    ///
+    /// Surpluses are matched to deficits largest first. A deficit is covered
+    /// including the withdrawal fee where the surplus allows, so the venue
+    /// receives its full shortfall. Shortfalls no transfer can cover are
+    /// listed in `unresolved`.
+    pub fn rebalance(&self, balances: &BalanceAggregator, asset: &str, targets: &[(&str, Fixed)]) -> TransferPlan {
+        let mut surpluses = Vec::new();
+        let mut deficits = Vec::new();
+        for (venue, target) in targets {
+            let free = balances.free(venue, asset);
+            if free > *target {
+                surpluses.push((venue.to_string(), free - *target));
+            } else if free < *target {
+                deficits.push((venue.to_string(), *target - free));
+            }
+        }
+        surpluses.sort_by_key(|s| std::cmp::Reverse(s.1));
+        deficits.sort_by_key(|d| std::cmp::Reverse(d.1));
+
+        let mut plan = TransferPlan::default();
+        for (to, mut needed) in deficits {
+            for (from, surplus) in surpluses.iter_mut() {
+                let Some(rule) = self.route(asset, from, &to) else { continue };
+                let amount = (needed + rule.fee).min(*surplus);
+                match self.plan_transfer(balances, asset, from, &to, amount) {
+                    Ok(leg) => {
+                        *surplus -= leg.amount;
+                        needed -= leg.received.min(needed);
+                        plan.legs.push(leg);
+                    }
+                    Err(e) => debug!("💸 Skipping {} {} -> {}: {}", asset, from, to, e),
+                }
+                if needed.is_zero() {
+                    break;
+                }
+            }
+            if !needed.is_zero() {
+                plan.unresolved.push((to.clone(), format!("{needed} {asset} short after transfers")));
+            }
+        }
+
+        info!("💸 Rebalance plan for {}: {} transfers, {} fees, {} unresolved",
+              asset, plan.legs.len(), plan.total_fees(), plan.unresolved.len());
+        plan
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixed(value: &str) -> Fixed {
+        Fixed::from_str_exact(value).unwrap()
+    }
+
+    fn usdt(free: &str) -> Vec<Balance> {
+        vec![Balance { asset: "USDT".to_string(), free: fixed(free), locked: Fixed::ZERO }]
+    }
+
+    #[test]
+    fn test_portfolio_view_across_venues() {
+        let mut balances = BalanceAggregator::new().with_max_age_ms(60_000);
+        balances.update("binance", vec![
+            Balance { asset: "BTC".to_string(), free: fixed("1.5"), locked: fixed("0.5") },
+            Balance { asset: "USDT".to_string(), free: fixed("1000"), locked: Fixed::ZERO },
+        ], 100_000);
+        balances.update("bybit", vec![
+            Balance { asset: "BTC".to_string(), free: fixed("1"), locked: Fixed::ZERO },
+            Balance { asset: "DOGE".to_string(), free: fixed("10"), locked: Fixed::ZERO },
+        ], 10_000);
+
+        let mut converter = CurrencyConverter::new();
+        converter.set_price("BTC", "USDT", 60_000.0, 100_000);
+        let view = balances.view(&converter, "USDT", 100_000);
+
+        let btc = &view.assets["BTC"];
+        assert_eq!((btc.total(), btc.by_venue["bybit"]), (fixed("3"), fixed("1")));
+        assert_eq!(view.value, 181_000.0);
+        assert_eq!(view.unpriced, vec!["DOGE".to_string()]);
+        assert_eq!(view.stale_venues, vec!["bybit".to_string()]);
+    }
+
+    #[test]
+    fn test_rebalance_plans_cheapest_route_with_fees() {
+        let mut balances = BalanceAggregator::new();
+        balances.update("binance", usdt("9001"), 0);
+        balances.update("bybit", usdt("1000"), 0);
+        balances.update("okx", usdt("5000"), 0);
+
+        let planner = TransferPlanner::new()
+            .with_withdrawal(WithdrawalRule::new("binance", "USDT", "ETH", fixed("4"), fixed("10")))
+            .with_withdrawal(WithdrawalRule::new("binance", "USDT", "TRX", fixed("1"), fixed("10")))
+            .with_deposit_network("bybit", "USDT", "TRX")
+            .with_deposit_network("bybit", "USDT", "ETH");
+
+        let target = fixed("5000");
+        let plan = planner.rebalance(&balances, "USDT", &[("binance", target), ("bybit", target), ("okx", target)]);
+        assert_eq!(plan.legs.len(), 1);
+        let leg = &plan.legs[0];
+        assert_eq!((leg.network.as_str(), leg.amount, leg.received), ("TRX", fixed("4001"), fixed("4000")));
+        assert!(plan.unresolved.is_empty());
+        assert!(matches!(&plan.steps()[1], TransferStep::AwaitDeposit { venue, .. } if venue == "bybit"));
+
+        // Below the minimum, and no route the other way
+        assert!(planner.plan_transfer(&balances, "USDT", "binance", "bybit", fixed("5")).is_err());
+        assert!(planner.plan_transfer(&balances, "USDT", "bybit", "binance", fixed("500")).is_err());
+        assert!(matches!(
+            planner.plan_transfer(&balances, "USDT", "binance", "bybit", fixed("9500")),
+            Err(ExchangeError::InsufficientBalance)
+        ));
+    }
+}
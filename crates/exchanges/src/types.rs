@@ -101,7 +101,7 @@ pub struct Symbol {
 }
 
 /// Generic order request
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct OrderRequest {
     pub symbol: String,
     pub side: OrderSide,
@@ -185,7 +185,7 @@ pub struct Trade {
 }
 
 /// Generic order book level
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct OrderBookLevel {
     pub price: Fixed,
     pub quantity: Fixed,
@@ -227,6 +227,77 @@ impl OrderBook {
             _ => None,
         }
     }
+    
+    /// Compute the minimal set of level changes that turn `previous` into `self`
+    /// 
+    /// Levels that disappeared are reported with a zero quantity, matching the
+    /// Binance depth-update convention.
+    pub fn diff(&self, previous: &OrderBook) -> BookDelta {
+        let mut bids = diff_levels(&previous.bids, &self.bids);
+        let asks = diff_levels(&previous.asks, &self.asks);
+        
+        // Keep book ordering: bids best-first (descending), asks best-first (ascending)
+        bids.reverse();
+        
+        BookDelta {
+            symbol: self.symbol.clone(),
+            previous_update_id: previous.update_id,
+            update_id: self.update_id,
+            timestamp: self.timestamp,
+            bids,
+            asks,
+        }
+    }
+}
+
+/// Changed price levels between two order book snapshots
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BookDelta {
+    pub symbol: String,
+    pub previous_update_id: u64,
+    pub update_id: u64,
+    pub timestamp: u64,
+    /// Changed bid levels; zero quantity means the level was removed
+    pub bids: Vec<OrderBookLevel>,
+    /// Changed ask levels; zero quantity means the level was removed
+    pub asks: Vec<OrderBookLevel>,
+}
+
+impl BookDelta {
+    /// Check if the snapshots were identical
+    pub fn is_empty(&self) -> bool {
+        self.bids.is_empty() && self.asks.is_empty()
+    }
+    
+    /// Total number of changed levels on both sides
+    pub fn len(&self) -> usize {
+        self.bids.len() + self.asks.len()
+    }
+}
+
+/// Diff one side of the book, returning changes in ascending price order
+fn diff_levels(previous: &[OrderBookLevel], current: &[OrderBookLevel]) -> Vec<OrderBookLevel> {
+    use std::collections::BTreeMap;
+    
+    let before: BTreeMap<Fixed, Fixed> = previous.iter().map(|l| (l.price, l.quantity)).collect();
+    let after: BTreeMap<Fixed, Fixed> = current.iter().map(|l| (l.price, l.quantity)).collect();
+    
+    let mut changes = Vec::new();
+    
+    for (price, quantity) in &after {
+        if before.get(price) != Some(quantity) {
+            changes.push(OrderBookLevel { price: *price, quantity: *quantity });
+        }
+    }
+    
+    for price in before.keys() {
+        if !after.contains_key(price) {
+            changes.push(OrderBookLevel { price: *price, quantity: Fixed::ZERO });
+        }
+    }
+    
+    changes.sort_by_key(|level| level.price);
+    changes
 }
 
 /// Generic kline/candlestick data
@@ -246,6 +317,31 @@ pub struct Kline {
     pub is_closed: bool,
 }
 
+/// Normalized OHLCV candle, independent of any exchange wire format
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Candle {
+    pub open_time: u64,
+    pub close_time: u64,
+    pub open: Fixed,
+    pub high: Fixed,
+    pub low: Fixed,
+    pub close: Fixed,
+    pub volume: Fixed,
+    pub number_of_trades: u32,
+}
+
+impl Candle {
+    /// Get the high-low range of the candle
+    pub fn range(&self) -> Fixed {
+        self.high - self.low
+    }
+    
+    /// Check if the candle closed above its open
+    pub fn is_bullish(&self) -> bool {
+        self.close > self.open
+    }
+}
+
 /// Generic market data event
 #[derive(Debug, Clone)]
 pub enum MarketData {
@@ -255,6 +351,266 @@ pub enum MarketData {
     Kline(Kline),
 }
 
+/// Venue an event or order came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExchangeId {
+    Binance,
+    Bybit,
+    Coinbase,
+    Nse,
+    Simulated,
+}
+
+impl ExchangeId {
+    /// Lowercase name, as returned by `Exchange::name`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExchangeId::Binance => "binance",
+            ExchangeId::Bybit => "bybit",
+            ExchangeId::Coinbase => "coinbase",
+            ExchangeId::Nse => "nse",
+            ExchangeId::Simulated => "simulated",
+        }
+    }
+}
+
+impl std::fmt::Display for ExchangeId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Streaming market data event from any venue
+#[derive(Debug, Clone)]
+pub enum MarketDataEvent {
+    Ticker(TickerUpdate),
+    Depth(DepthUpdate),
+    Trade(TradeUpdate),
+    AggTrade(AggTradeUpdate),
+    Kline(KlineUpdate),
+    MiniTicker(MiniTickerUpdate),
+    BookTicker(BookTickerUpdate),
+    /// Whole-market `!miniTicker@arr` update
+    MiniTickerBatch(Vec<MiniTickerUpdate>),
+    /// Whole-market book ticker updates delivered as an array
+    BookTickerBatch(Vec<BookTickerUpdate>),
+    /// The connection dropped and was re-established; updates in between are lost
+    Reconnected(ReconnectInfo),
+}
+
+/// Outcome of an automatic reconnect
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReconnectInfo {
+    pub exchange: ExchangeId,
+    pub attempts: u32,
+    /// Streams resubscribed on the new connection
+    pub streams: Vec<String>,
+    pub downtime_ms: u64,
+}
+
+impl MarketDataEvent {
+    /// Venue the event came from; `None` for an empty batch
+    pub fn exchange(&self) -> Option<ExchangeId> {
+        match self {
+            MarketDataEvent::Ticker(t) => Some(t.exchange),
+            MarketDataEvent::Depth(d) => Some(d.exchange),
+            MarketDataEvent::Trade(t) => Some(t.exchange),
+            MarketDataEvent::AggTrade(t) => Some(t.exchange),
+            MarketDataEvent::Kline(k) => Some(k.exchange),
+            MarketDataEvent::MiniTicker(t) => Some(t.exchange),
+            MarketDataEvent::BookTicker(t) => Some(t.exchange),
+            MarketDataEvent::MiniTickerBatch(batch) => batch.first().map(|t| t.exchange),
+            MarketDataEvent::BookTickerBatch(batch) => batch.first().map(|t| t.exchange),
+            MarketDataEvent::Reconnected(r) => Some(r.exchange),
+        }
+    }
+    
+    /// Symbol of a single-symbol event; `None` for batches
+    pub fn symbol(&self) -> Option<&str> {
+        match self {
+            MarketDataEvent::Ticker(t) => Some(&t.symbol),
+            MarketDataEvent::Depth(d) => Some(&d.symbol),
+            MarketDataEvent::Trade(t) => Some(&t.symbol),
+            MarketDataEvent::AggTrade(t) => Some(&t.symbol),
+            MarketDataEvent::Kline(k) => Some(&k.symbol),
+            MarketDataEvent::MiniTicker(t) => Some(&t.symbol),
+            MarketDataEvent::BookTicker(t) => Some(&t.symbol),
+            MarketDataEvent::MiniTickerBatch(_) | MarketDataEvent::BookTickerBatch(_) | MarketDataEvent::Reconnected(_) => None,
+        }
+    }
+    
+    /// Split batches into per-symbol events; single events are returned as-is
+    pub fn split_by_symbol(&self) -> Vec<MarketDataEvent> {
+        match self {
+            MarketDataEvent::MiniTickerBatch(batch) => {
+                batch.iter().cloned().map(MarketDataEvent::MiniTicker).collect()
+            }
+            MarketDataEvent::BookTickerBatch(batch) => {
+                batch.iter().cloned().map(MarketDataEvent::BookTicker).collect()
+            }
+            single => vec![single.clone()],
+        }
+    }
+}
+
+/// Mini ticker (24hr rolling window) update
+/// 
+/// Volumes routinely exceed the `Fixed` range and are kept exact beyond it
+/// (see `Fixed::from_str_unbounded`).
+#[derive(Debug, Clone)]
+pub struct MiniTickerUpdate {
+    pub exchange: ExchangeId,
+    pub symbol: String,
+    pub close: Fixed,
+    pub open: Fixed,
+    pub high: Fixed,
+    pub low: Fixed,
+    pub base_volume: Fixed,
+    pub quote_volume: Fixed,
+    pub timestamp: u64,
+}
+
+impl MiniTickerUpdate {
+    /// Convert into scanner statistics (no bid/ask or trade count in mini tickers)
+    pub fn to_scan_ticker(&self) -> crate::scanner::ScanTicker {
+        crate::scanner::ScanTicker {
+            symbol: self.symbol.clone(),
+            last_price: self.close,
+            open_price: self.open,
+            high_price: self.high,
+            low_price: self.low,
+            bid_price: None,
+            ask_price: None,
+            volume: self.base_volume.to_f64_lossy(),
+            quote_volume: self.quote_volume.to_f64_lossy(),
+            trade_count: 0,
+            timestamp: self.timestamp,
+        }
+    }
+}
+
+/// Best bid/ask update
+#[derive(Debug, Clone)]
+pub struct BookTickerUpdate {
+    pub exchange: ExchangeId,
+    pub symbol: String,
+    pub update_id: u64,
+    pub bid_price: Fixed,
+    pub bid_qty: Fixed,
+    pub ask_price: Fixed,
+    pub ask_qty: Fixed,
+}
+
+impl BookTickerUpdate {
+    pub fn spread(&self) -> Fixed {
+        self.ask_price - self.bid_price
+    }
+
+    pub fn mid_price(&self) -> Fixed {
+        (self.bid_price + self.ask_price) / Fixed::from_i64(2).unwrap()
+    }
+}
+
+/// Ticker update data
+#[derive(Debug, Clone)]
+pub struct TickerUpdate {
+    pub exchange: ExchangeId,
+    pub symbol: String,
+    pub price: Fixed,
+    pub price_change: Fixed,
+    pub volume: Fixed,
+    pub timestamp: u64,
+}
+
+/// Whether a depth event replaces the book or updates it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DepthKind {
+    /// Top levels of the book as they stand (Binance `@depth5/10/20`)
+    Snapshot,
+    /// Changed levels only, to apply to a local book (Binance `@depth`)
+    #[default]
+    Diff,
+}
+
+/// Depth/order book update data
+#[derive(Debug, Clone)]
+pub struct DepthUpdate {
+    pub exchange: ExchangeId,
+    pub symbol: String,
+    pub bids: Vec<OrderBookLevel>,
+    pub asks: Vec<OrderBookLevel>,
+    pub timestamp: u64,
+    /// First update id in the event (`U`); equals `update_id` for snapshots
+    pub first_update_id: u64,
+    /// Final update id in the event (`u`, or `lastUpdateId` for snapshots)
+    pub update_id: u64,
+    pub kind: DepthKind,
+}
+
+impl DepthUpdate {
+    pub fn is_snapshot(&self) -> bool {
+        self.kind == DepthKind::Snapshot
+    }
+}
+
+/// Trade update data
+#[derive(Debug, Clone)]
+pub struct TradeUpdate {
+    pub exchange: ExchangeId,
+    pub symbol: String,
+    pub price: Fixed,
+    pub quantity: Fixed,
+    pub side: TradeSide,
+    pub timestamp: u64,
+    pub trade_id: u64,
+}
+
+/// Aggregated trade: the fills of one taker order at one price
+#[derive(Debug, Clone)]
+pub struct AggTradeUpdate {
+    pub exchange: ExchangeId,
+    pub symbol: String,
+    pub agg_trade_id: u64,
+    pub price: Fixed,
+    pub quantity: Fixed,
+    pub first_trade_id: u64,
+    pub last_trade_id: u64,
+    /// Taker side
+    pub side: TradeSide,
+    pub timestamp: u64,
+}
+
+impl AggTradeUpdate {
+    /// Individual trades folded into this one
+    pub fn trade_count(&self) -> u64 {
+        self.last_trade_id.saturating_sub(self.first_trade_id) + 1
+    }
+}
+
+/// Kline/candlestick update data
+#[derive(Debug, Clone)]
+pub struct KlineUpdate {
+    pub exchange: ExchangeId,
+    pub symbol: String,
+    pub interval: String,
+    pub open_time: u64,
+    pub close_time: u64,
+    pub open: Fixed,
+    pub high: Fixed,
+    pub low: Fixed,
+    pub close: Fixed,
+    pub volume: Fixed,
+    pub is_closed: bool,
+}
+
+/// Trade side
+#[derive(Debug, Clone)]
+pub enum TradeSide {
+    Buy,
+    Sell,
+}
+
 /// Connection status
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ConnectionStatus {
@@ -321,6 +677,23 @@ mod tests {
         assert_eq!(balance.total().to_string(), "1.5");
     }
     
+    #[test]
+    fn test_candle_helpers() {
+        let candle = Candle {
+            open_time: 0,
+            close_time: 59_999,
+            open: Fixed::from_str_exact("100.0").unwrap(),
+            high: Fixed::from_str_exact("110.0").unwrap(),
+            low: Fixed::from_str_exact("95.0").unwrap(),
+            close: Fixed::from_str_exact("105.0").unwrap(),
+            volume: Fixed::from_str_exact("12.5").unwrap(),
+            number_of_trades: 42,
+        };
+        
+        assert_eq!(candle.range().to_string(), "15.0");
+        assert!(candle.is_bullish());
+    }
+    
     #[test]
     fn test_order_book_calculations() {
         let order_book = OrderBook {
@@ -347,4 +720,41 @@ mod tests {
         // Mid price should be (50000 + 50001) / 2 = 50000.5
         assert_eq!(order_book.mid_price().unwrap().to_string(), "50000.5");
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_order_book_diff() {
+        let level = |price: &str, quantity: &str| OrderBookLevel {
+            price: Fixed::from_str_exact(price).unwrap(),
+            quantity: Fixed::from_str_exact(quantity).unwrap(),
+        };
+        
+        let previous = OrderBook {
+            symbol: "BTCUSDT".to_string(),
+            bids: vec![level("100.0", "1.0"), level("99.0", "2.0"), level("98.0", "3.0")],
+            asks: vec![level("101.0", "1.0"), level("102.0", "2.0")],
+            timestamp: 1000,
+            update_id: 10,
+        };
+        
+        let current = OrderBook {
+            symbol: "BTCUSDT".to_string(),
+            bids: vec![level("100.0", "1.5"), level("99.0", "2.0"), level("97.0", "4.0")],
+            asks: vec![level("101.0", "1.0"), level("102.0", "2.0")],
+            timestamp: 2000,
+            update_id: 12,
+        };
+        
+        let delta = current.diff(&previous);
+        
+        assert_eq!(delta.previous_update_id, 10);
+        assert_eq!(delta.update_id, 12);
+        assert_eq!(delta.len(), 3);
+        assert_eq!(
+            delta.bids,
+            vec![level("100.0", "1.5"), level("98.0", "0"), level("97.0", "4.0")]
+        );
+        assert!(delta.asks.is_empty());
+        
+        assert!(current.diff(&current).is_empty());
+    }
+}
@@ -0,0 +1,211 @@
+//! Cold-start warmup with readiness gates
+//!
+//! Before a strategy sees live ticks it usually needs history for its
+//! indicators, seeded order books, a reconciled account and a verified clock.
+//! Each dependency is a `WarmupGate`; the `StrategyRunner` withholds market
+//! data from the strategy until every configured gate reports ready.
+//! `warm_up_binance` runs the standard gates against a Binance REST client.
+
+use crate::bars::ExchangeClock;
+use crate::binance::BinanceRestClient;
+use crate::errors::Result;
+use crate::runner::{Strategy, StrategyRunner};
+use crate::types::*;
+use sriquant_core::prelude::*;
+
+use std::collections::BTreeMap;
+use tracing::{info, warn};
+
+/// A dependency that must be ready before trading
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum WarmupGate {
+    HistoricalBars,
+    OrderBooks,
+    AccountReconciled,
+    TimeSync,
+    Custom(String),
+}
+
+impl WarmupGate {
+    /// The gates `warm_up_binance` satisfies
+    pub fn standard() -> Vec<WarmupGate> {
+        vec![
+            WarmupGate::TimeSync,
+            WarmupGate::AccountReconciled,
+            WarmupGate::HistoricalBars,
+            WarmupGate::OrderBooks,
+        ]
+    }
+}
+
+/// Readiness of one gate
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GateStatus {
+    Pending,
+    Ready,
+    Failed(String),
+}
+
+/// Tracks gate readiness
+#[derive(Debug, Clone, Default)]
+pub struct WarmupTracker {
+    gates: BTreeMap<WarmupGate, GateStatus>,
+}
+
+impl WarmupTracker {
+    pub fn new(gates: &[WarmupGate]) -> Self {
+        Self {
+            gates: gates.iter().cloned().map(|g| (g, GateStatus::Pending)).collect(),
+        }
+    }
+
+    /// Mark a gate ready; unknown gates are ignored
+    pub fn mark_ready(&mut self, gate: &WarmupGate) {
+        if let Some(status) = self.gates.get_mut(gate) {
+            info!("✅ Warmup gate {:?} ready", gate);
+            *status = GateStatus::Ready;
+        }
+    }
+
+    pub fn mark_failed(&mut self, gate: &WarmupGate, reason: &str) {
+        if let Some(status) = self.gates.get_mut(gate) {
+            warn!("❌ Warmup gate {:?} failed: {}", gate, reason);
+            *status = GateStatus::Failed(reason.to_string());
+        }
+    }
+
+    pub fn status(&self, gate: &WarmupGate) -> Option<&GateStatus> {
+        self.gates.get(gate)
+    }
+
+    /// Gates not yet ready (pending or failed)
+    pub fn outstanding(&self) -> Vec<&WarmupGate> {
+        self.gates.iter().filter(|(_, s)| **s != GateStatus::Ready).map(|(g, _)| g).collect()
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.gates.values().all(|s| *s == GateStatus::Ready)
+    }
+}
+
+/// What `warm_up_binance` should load
+#[derive(Debug, Clone)]
+pub struct WarmupPlan {
+    pub symbols: Vec<String>,
+    /// Kline interval for indicator history, e.g. "1m"
+    pub interval: String,
+    pub bars: u32,
+    pub book_depth: u32,
+    /// Largest tolerated |server - local| clock offset
+    pub max_clock_offset_ms: u64,
+}
+
+impl WarmupPlan {
+    pub fn new(symbols: &[&str], interval: &str, bars: u32) -> Self {
+        Self {
+            symbols: symbols.iter().map(|s| s.to_uppercase()).collect(),
+            interval: interval.to_string(),
+            bars,
+            book_depth: 100,
+            max_clock_offset_ms: 500,
+        }
+    }
+
+    pub fn with_book_depth(mut self, depth: u32) -> Self {
+        self.book_depth = depth;
+        self
+    }
+
+    pub fn with_max_clock_offset_ms(mut self, offset_ms: u64) -> Self {
+        self.max_clock_offset_ms = offset_ms;
+        self
+    }
+}
+
+/// Run the standard warmup gates against Binance
+///
+/// Gate failures are recorded on the runner rather than returned, so the
+/// caller can inspect `warmup()` and retry; transport errors are returned.
+///
+/// # Example
+/// ```rust,ignore
+/// let mut runner = StrategyRunner::new(strategy, TradingEnvironment::Paper)
+///     .with_warmup_gates(&WarmupGate::standard());
+/// warm_up_binance(&mut runner, &rest, &WarmupPlan::new(&["BTCUSDT"], "1m", 500)).await?;
+/// assert!(runner.is_ready());
+/// ```
+pub async fn warm_up_binance<S: Strategy>(
+    runner: &mut StrategyRunner<S>,
+    rest: &BinanceRestClient,
+    plan: &WarmupPlan,
+) -> Result<()> {
+    let _timer = PerfTimer::start("warm_up_binance");
+
+    let before = nanos() / 1_000_000;
+    let server_time = rest.server_time().await?;
+    let after = nanos() / 1_000_000;
+    let offset = ExchangeClock::new().observe(before, server_time, after);
+    runner.set_clock_offset_ms(offset);
+    if offset.unsigned_abs() <= plan.max_clock_offset_ms {
+        runner.mark_gate_ready(&WarmupGate::TimeSync);
+    } else {
+        runner.mark_gate_failed(&WarmupGate::TimeSync, &format!("clock offset {offset}ms"));
+    }
+
+    let account = rest.get_account_info_non_zero().await?;
+    if account.can_trade {
+        runner.mark_gate_ready(&WarmupGate::AccountReconciled);
+    } else {
+        runner.mark_gate_failed(&WarmupGate::AccountReconciled, "account cannot trade");
+    }
+
+    for symbol in &plan.symbols {
+        let bars = rest.get_klines(symbol, &plan.interval, None, None, Some(plan.bars)).await?;
+        runner.seed_bars(symbol, &bars);
+    }
+    runner.mark_gate_ready(&WarmupGate::HistoricalBars);
+
+    for symbol in &plan.symbols {
+        let snapshot = rest.order_book(symbol, Some(plan.book_depth)).await?;
+        let parse = |levels: &[[String; 2]]| -> Result<Vec<OrderBookLevel>> {
+            levels
+                .iter()
+                .map(|[price, quantity]| {
+                    Ok(OrderBookLevel { price: Fixed::from_str_exact(price)?, quantity: Fixed::from_str_exact(quantity)? })
+                })
+                .collect()
+        };
+        runner.seed_order_book(OrderBook {
+            symbol: symbol.clone(),
+            bids: parse(&snapshot.bids)?,
+            asks: parse(&snapshot.asks)?,
+            timestamp: server_time,
+            update_id: snapshot.last_update_id,
+        });
+    }
+    runner.mark_gate_ready(&WarmupGate::OrderBooks);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracker_readiness() {
+        let mut tracker = WarmupTracker::new(&WarmupGate::standard());
+        assert!(!tracker.is_ready());
+        assert_eq!(tracker.outstanding().len(), 4);
+
+        for gate in WarmupGate::standard() {
+            tracker.mark_ready(&gate);
+        }
+        tracker.mark_failed(&WarmupGate::TimeSync, "clock offset 900ms");
+        assert_eq!(tracker.outstanding(), vec![&WarmupGate::TimeSync]);
+
+        tracker.mark_ready(&WarmupGate::TimeSync);
+        assert!(tracker.is_ready());
+        assert!(WarmupTracker::default().is_ready());
+    }
+}
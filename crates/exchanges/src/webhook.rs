@@ -0,0 +1,286 @@
+//! Outbound webhooks for order and fill events
+//!
+//! Risk and reporting systems that only speak HTTP can follow the account
+//! without a gRPC integration: `WebhookSink` POSTs journal entries as JSON to
+//! a configured URL. Each body is signed with HMAC-SHA256 over
+//! `"{timestamp}.{body}"` and the result sent as
+//! `X-Sriquant-Signature: t=<ms>,v1=<hex>`; receivers check it with
+//! `verify_signature`.
+//!
+//! Delivery is at least once. The sink keeps a cursor into the journal and
+//! only moves past an entry after a 2xx, so a failed flush resumes from the
+//! same entry. `X-Sriquant-Delivery` carries the entry's dedupe key for
+//! receivers to drop repeats.
+
+use crate::errors::{ExchangeError, Result};
+use crate::http::MonoioHttpsClient;
+use crate::journal::{Journal, JournalEntry, JournalEvent};
+use sriquant_core::prelude::*;
+
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::{info, warn};
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub const SIGNATURE_HEADER: &str = "X-Sriquant-Signature";
+pub const DELIVERY_HEADER: &str = "X-Sriquant-Delivery";
+pub const EVENT_HEADER: &str = "X-Sriquant-Event";
+
+/// Where and what to deliver
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// Shared secret for the HMAC signature
+    pub secret: String,
+    /// Journal event kinds to deliver (see `JournalEvent::kind`)
+    pub kinds: Vec<String>,
+    /// Attempts per entry before a flush gives up
+    pub max_attempts: u32,
+    /// Wait before the first retry; doubled for each one after
+    pub retry_backoff: Duration,
+}
+
+impl WebhookConfig {
+    /// Deliver order lifecycle and fill events to `url`
+    pub fn new(url: impl Into<String>, secret: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            secret: secret.into(),
+            kinds: vec!["order".to_string(), "trade".to_string()],
+            max_attempts: 3,
+            retry_backoff: Duration::from_millis(500),
+        }
+    }
+
+    pub fn with_kinds(mut self, kinds: &[&str]) -> Self {
+        self.kinds = kinds.iter().map(|k| k.to_string()).collect();
+        self
+    }
+
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    pub fn with_retry_backoff(mut self, retry_backoff: Duration) -> Self {
+        self.retry_backoff = retry_backoff;
+        self
+    }
+
+    /// Whether events of this kind are delivered
+    pub fn accepts(&self, event: &JournalEvent) -> bool {
+        self.kinds.iter().any(|k| k == event.kind())
+    }
+
+    /// Body and headers for one entry, signed at `timestamp_ms`
+    pub fn build_request(&self, entry: &JournalEntry, timestamp_ms: u64) -> Result<(String, Vec<(String, String)>)> {
+        let payload = WebhookPayload {
+            kind: entry.event.kind(),
+            exchange: &entry.exchange,
+            sequence: entry.sequence,
+            recorded_at: entry.recorded_at,
+            event: &entry.event,
+        };
+        let body = serde_json::to_string(&payload)?;
+        let signature = sign(&self.secret, timestamp_ms, &body)?;
+        let headers = vec![
+            ("Content-Type".to_string(), "application/json".to_string()),
+            (EVENT_HEADER.to_string(), payload.kind.to_string()),
+            (DELIVERY_HEADER.to_string(), format!("{}:{}", entry.exchange, entry.event.dedupe_key())),
+            (SIGNATURE_HEADER.to_string(), format!("t={timestamp_ms},v1={signature}")),
+        ];
+        Ok((body, headers))
+    }
+}
+
+/// JSON body of a webhook POST
+#[derive(Debug, Serialize)]
+struct WebhookPayload<'a> {
+    kind: &'static str,
+    exchange: &'a str,
+    sequence: u64,
+    recorded_at: u64,
+    event: &'a JournalEvent,
+}
+
+/// Hex HMAC-SHA256 of `"{timestamp_ms}.{body}"`
+pub fn sign(secret: &str, timestamp_ms: u64, body: &str) -> Result<String> {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
+        .map_err(|e| ExchangeError::SigningError(format!("HMAC setup failed: {e}")))?;
+    mac.update(format!("{timestamp_ms}.{body}").as_bytes());
+    Ok(hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Check an `X-Sriquant-Signature` header on the receiving side
+///
+/// Rejects bodies signed more than `tolerance_ms` from `now_ms`, which stops
+/// a captured request being replayed later.
+pub fn verify_signature(secret: &str, header: &str, body: &str, now_ms: u64, tolerance_ms: u64) -> bool {
+    let mut timestamp = None;
+    let mut signature = None;
+    for part in header.split(',') {
+        match part.split_once('=') {
+            Some(("t", value)) => timestamp = value.parse::<u64>().ok(),
+            Some(("v1", value)) => signature = hex::decode(value).ok(),
+            _ => {}
+        }
+    }
+    let (Some(timestamp), Some(signature)) = (timestamp, signature) else {
+        return false;
+    };
+    if now_ms.abs_diff(timestamp) > tolerance_ms {
+        return false;
+    }
+    let Ok(mut mac) = HmacSha256::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(format!("{timestamp}.{body}").as_bytes());
+    mac.verify_slice(&signature).is_ok()
+}
+
+/// Delivers new journal entries to a webhook endpoint
+///
+/// ```rust,ignore
+/// let mut sink = WebhookSink::new(WebhookConfig::new("https://risk.example.com/hooks", secret))?;
+/// journal.record_order("binance", &order);
+/// sink.flush(&journal).await?;
+/// ```
+pub struct WebhookSink {
+    config: WebhookConfig,
+    client: MonoioHttpsClient,
+    /// Index of the first journal entry not yet delivered or skipped
+    cursor: usize,
+    delivered: u64,
+}
+
+impl WebhookSink {
+    pub fn new(config: WebhookConfig) -> Result<Self> {
+        Ok(Self { config, client: MonoioHttpsClient::new()?, cursor: 0, delivered: 0 })
+    }
+
+    /// Skip entries already in the journal, delivering only ones recorded later
+    pub fn starting_after(mut self, journal: &Journal) -> Self {
+        self.cursor = journal.len();
+        self
+    }
+
+    pub fn config(&self) -> &WebhookConfig {
+        &self.config
+    }
+
+    /// Entries delivered so far
+    pub fn delivered(&self) -> u64 {
+        self.delivered
+    }
+
+    /// Matching entries not yet delivered
+    pub fn pending<'a>(&'a self, journal: &'a Journal) -> impl Iterator<Item = &'a JournalEntry> + 'a {
+        journal.entries().iter().skip(self.cursor).filter(|e| self.config.accepts(&e.event))
+    }
+
+    /// POST every new matching entry in order; returns how many were delivered
+    ///
+    /// Stops at the first entry that still fails after `max_attempts`, which
+    /// the next flush retries.
+    pub async fn flush(&mut self, journal: &Journal) -> Result<usize> {
+        let timer = PerfTimer::start("webhook_flush".to_string());
+        let mut sent = 0;
+        while let Some(entry) = journal.entries().get(self.cursor) {
+            if self.config.accepts(&entry.event) {
+                self.deliver(entry).await?;
+                sent += 1;
+                self.delivered += 1;
+            }
+            self.cursor += 1;
+        }
+        if sent > 0 {
+            info!("📤 Delivered {} webhook events to {}", sent, self.config.url);
+        }
+        timer.log_elapsed();
+        Ok(sent)
+    }
+
+    async fn deliver(&self, entry: &JournalEntry) -> Result<()> {
+        let mut backoff = self.config.retry_backoff;
+        let mut attempt = 1;
+        loop {
+            let (body, headers) = self.config.build_request(entry, nanos() / 1_000_000)?;
+            let headers: HashMap<&str, &str> = headers.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+            let error = match self.client.request_with_headers("POST", &self.config.url, Some(&body), &headers).await {
+                Ok(response) if (200..300).contains(&response.status) => return Ok(()),
+                Ok(response) => ExchangeError::HttpError(response.status, response.body),
+                Err(e) => e,
+            };
+            // Client errors won't change on retry, except rate limiting
+            let retryable = !matches!(error, ExchangeError::HttpError(status, _) if (400..500).contains(&status) && status != 429);
+            if !retryable || attempt >= self.config.max_attempts {
+                warn!("❗ Webhook delivery of {} failed after {} attempts: {}", entry.event.dedupe_key(), attempt, error);
+                return Err(error);
+            }
+            monoio::time::sleep(backoff).await;
+            backoff *= 2;
+            attempt += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::incidents::{Incident, IncidentKind, Severity};
+    use crate::journal::OrderRecord;
+    use crate::types::{OrderSide, OrderStatus, OrderType};
+
+    fn order(status: OrderStatus, filled: &str) -> JournalEvent {
+        JournalEvent::Order(OrderRecord {
+            client_order_id: "sq-1".to_string(),
+            order_id: Some("42".to_string()),
+            symbol: "BTCUSDT".to_string(),
+            side: OrderSide::Buy,
+            order_type: Some(OrderType::Limit),
+            status,
+            quantity: Fixed::from_str_exact("0.002").unwrap(),
+            price: Some(Fixed::from_str_exact("50000").unwrap()),
+            filled_quantity: Fixed::from_str_exact(filled).unwrap(),
+            average_price: None,
+            update_time: 1_700_000_000_000,
+        })
+    }
+
+    #[test]
+    fn test_signed_request_verifies() {
+        let config = WebhookConfig::new("https://risk.example.com/hooks", "shh");
+        let mut journal = Journal::new();
+        journal.record("binance", order(OrderStatus::New, "0"));
+        let (body, headers) = config.build_request(&journal.entries()[0], 1_700_000_000_500).unwrap();
+
+        assert!(body.contains("\"kind\":\"order\""));
+        let header = |name: &str| headers.iter().find(|(k, _)| k == name).map(|(_, v)| v.clone()).unwrap();
+        assert_eq!(header(DELIVERY_HEADER), "binance:order:BTCUSDT:sq-1:NEW:0");
+
+        let signature = header(SIGNATURE_HEADER);
+        assert!(verify_signature("shh", &signature, &body, 1_700_000_001_000, 5_000));
+        assert!(!verify_signature("shh", &signature, &body.replace("BTCUSDT", "ETHUSDT"), 1_700_000_001_000, 5_000));
+        assert!(!verify_signature("other", &signature, &body, 1_700_000_001_000, 5_000));
+        // Replayed well after signing
+        assert!(!verify_signature("shh", &signature, &body, 1_700_000_600_000, 5_000));
+    }
+
+    #[test]
+    fn test_only_configured_kinds_are_pending() {
+        let mut journal = Journal::new();
+        journal.record("binance", order(OrderStatus::New, "0"));
+        journal.record("binance", JournalEvent::Incident(Incident::new(Severity::Warning, "oms", IncidentKind::ReconcileMismatch, "drift")));
+        journal.record("binance", order(OrderStatus::PartiallyFilled, "0.001"));
+
+        let config = WebhookConfig::new("https://risk.example.com/hooks", "shh");
+        assert_eq!(journal.entries().iter().filter(|e| config.accepts(&e.event)).count(), 2);
+        let incidents_only = config.with_kinds(&["incident"]);
+        assert!(incidents_only.accepts(&journal.entries()[1].event));
+        assert!(!incidents_only.accepts(&journal.entries()[0].event));
+    }
+}
@@ -7,17 +7,28 @@
 //! - Nanosecond precision timing
 //! - Zero-copy where possible
 
+use crate::cassette::{CassetteHandle, WsEvent};
+use crate::chaos::{self, Fault, FaultInjector};
 use crate::errors::{ExchangeError, Result};
-use crate::http::TlsStream;
+use crate::http::{TlsStream, with_deadline};
 use sriquant_core::{PerfTimer, nanos};
 
 use monoio::net::TcpStream;
 use tracing::{debug, info};
+use miniz_oxide::deflate::core::{CompressorOxide, create_comp_flags_from_zip_params};
+use miniz_oxide::deflate::stream::deflate;
+use miniz_oxide::inflate::stream::{InflateState, inflate};
+use miniz_oxide::{DataFormat, MZError, MZFlush};
+use std::ops::Range;
+use std::time::Duration;
 use url::Url;
 use base64::Engine;
 use sha1::{Sha1, Digest};
 use webpki_roots;
 
+/// Minimum free space offered to each socket read
+const READ_CHUNK: usize = 4096;
+
 /// WebSocket opcode constants
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -50,6 +61,8 @@ pub struct FrameHeader {
     pub fin: bool,
     pub opcode: OpCode,
     pub mask: Option<[u8; 4]>,
+    /// RSV1: the payload is deflated (permessage-deflate)
+    pub compressed: bool,
     pub payload_len: u64,
 }
 
@@ -68,6 +81,7 @@ impl Frame {
                 fin: true,
                 opcode: OpCode::Text,
                 mask: Some(Self::generate_mask()),
+                compressed: false,
                 payload_len: data.len() as u64,
             },
             payload: data.into_bytes(),
@@ -81,6 +95,7 @@ impl Frame {
                 fin: true,
                 opcode: OpCode::Binary,
                 mask: Some(Self::generate_mask()),
+                compressed: false,
                 payload_len: data.len() as u64,
             },
             payload: data,
@@ -94,6 +109,7 @@ impl Frame {
                 fin: true,
                 opcode: OpCode::Ping,
                 mask: Some(Self::generate_mask()),
+                compressed: false,
                 payload_len: data.len() as u64,
             },
             payload: data,
@@ -107,6 +123,7 @@ impl Frame {
                 fin: true,
                 opcode: OpCode::Pong,
                 mask: Some(Self::generate_mask()),
+                compressed: false,
                 payload_len: data.len() as u64,
             },
             payload: data,
@@ -124,6 +141,7 @@ impl Frame {
                 fin: true,
                 opcode: OpCode::Close,
                 mask: Some(Self::generate_mask()),
+                compressed: false,
                 payload_len: payload.len() as u64,
             },
             payload,
@@ -151,52 +169,78 @@ impl Frame {
 
     /// Serialize frame to bytes
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut frame = Vec::new();
+        let mut frame = Vec::with_capacity(14 + self.payload.len());
+        self.write_to(&mut frame);
+        frame
+    }
 
+    /// Append the encoded frame to `out`, masking the payload in place
+    pub fn write_to(&self, out: &mut Vec<u8>) {
         // First byte: FIN + RSV + Opcode
-        let first_byte = if self.header.fin { 0x80 } else { 0x00 } | (self.header.opcode as u8);
-        frame.push(first_byte);
+        let first_byte = if self.header.fin { 0x80 } else { 0x00 }
+            | if self.header.compressed { 0x40 } else { 0x00 }
+            | (self.header.opcode as u8);
+        out.push(first_byte);
 
         // Second byte: MASK + Payload length
         let mask_bit = if self.header.mask.is_some() { 0x80 } else { 0x00 };
         
         if self.header.payload_len < 126 {
-            frame.push(mask_bit | (self.header.payload_len as u8));
+            out.push(mask_bit | (self.header.payload_len as u8));
         } else if self.header.payload_len < 65536 {
-            frame.push(mask_bit | 126);
-            frame.extend_from_slice(&(self.header.payload_len as u16).to_be_bytes());
+            out.push(mask_bit | 126);
+            out.extend_from_slice(&(self.header.payload_len as u16).to_be_bytes());
         } else {
-            frame.push(mask_bit | 127);
-            frame.extend_from_slice(&self.header.payload_len.to_be_bytes());
+            out.push(mask_bit | 127);
+            out.extend_from_slice(&self.header.payload_len.to_be_bytes());
         }
 
         // Mask
         if let Some(mask) = self.header.mask {
-            frame.extend_from_slice(&mask);
+            out.extend_from_slice(&mask);
         }
 
         // Payload (masked if client)
-        let mut payload = self.payload.clone();
+        let payload_start = out.len();
+        out.extend_from_slice(&self.payload);
         if let Some(mask) = &self.header.mask {
-            Self::apply_mask(&mut payload, mask);
+            Self::apply_mask(&mut out[payload_start..], mask);
         }
-        frame.extend_from_slice(&payload);
-
-        frame
     }
 
     /// Parse frame from bytes
     pub fn from_bytes(data: &[u8]) -> Result<(Self, usize)> {
-        if data.len() < 2 {
-            return Err(ExchangeError::InvalidResponse("Insufficient data for WebSocket frame".to_string()));
+        let timer = PerfTimer::start("websocket_frame_parse".to_string());
+
+        let (header, offset) = FrameHeader::parse(data)?
+            .ok_or_else(|| ExchangeError::InvalidResponse("Insufficient data for WebSocket frame".to_string()))?;
+
+        let mut payload = data[offset..offset + header.payload_len as usize].to_vec();
+        if let Some(mask) = &header.mask {
+            Self::apply_mask(&mut payload, mask);
         }
 
-        let timer = PerfTimer::start("websocket_frame_parse".to_string());
+        let consumed = offset + payload.len();
+        timer.log_elapsed();
+        Ok((Frame { header, payload }, consumed))
+    }
+}
+
+impl FrameHeader {
+    /// Parse the header at the start of `data`
+    ///
+    /// Returns the header and its length once the whole frame is in `data`,
+    /// `None` while more bytes are needed.
+    pub fn parse(data: &[u8]) -> Result<Option<(Self, usize)>> {
+        if data.len() < 2 {
+            return Ok(None);
+        }
 
         let first_byte = data[0];
         let second_byte = data[1];
 
         let fin = (first_byte & 0x80) != 0;
+        let compressed = (first_byte & 0x40) != 0;
         let opcode = OpCode::from_u8(first_byte & 0x0f)
             .ok_or_else(|| ExchangeError::InvalidResponse("Invalid WebSocket opcode".to_string()))?;
 
@@ -208,14 +252,14 @@ impl Frame {
             payload_len_initial as u64
         } else if payload_len_initial == 126 {
             if data.len() < offset + 2 {
-                return Err(ExchangeError::InvalidResponse("Insufficient data for extended payload length".to_string()));
+                return Ok(None);
             }
             let len = u16::from_be_bytes([data[offset], data[offset + 1]]) as u64;
             offset += 2;
             len
         } else {
             if data.len() < offset + 8 {
-                return Err(ExchangeError::InvalidResponse("Insufficient data for extended payload length".to_string()));
+                return Ok(None);
             }
             let len = u64::from_be_bytes([
                 data[offset], data[offset + 1], data[offset + 2], data[offset + 3],
@@ -227,7 +271,7 @@ impl Frame {
 
         let mask = if masked {
             if data.len() < offset + 4 {
-                return Err(ExchangeError::InvalidResponse("Insufficient data for mask".to_string()));
+                return Ok(None);
             }
             let mask = [data[offset], data[offset + 1], data[offset + 2], data[offset + 3]];
             offset += 4;
@@ -236,42 +280,362 @@ impl Frame {
             None
         };
 
-        if data.len() < offset + payload_len as usize {
-            return Err(ExchangeError::InvalidResponse("Insufficient data for payload".to_string()));
+        if ((data.len() - offset) as u64) < payload_len {
+            return Ok(None);
         }
 
-        let mut payload = data[offset..offset + payload_len as usize].to_vec();
-        if let Some(mask) = &mask {
-            Self::apply_mask(&mut payload, mask);
+        Ok(Some((FrameHeader { fin, opcode, mask, compressed, payload_len }, offset)))
+    }
+}
+
+/// A received frame whose payload still lives in the connection's read buffer
+///
+/// Valid until the next receive; `to_frame` copies it out.
+#[derive(Debug, Clone)]
+pub struct FrameRef<'a> {
+    pub header: FrameHeader,
+    pub payload: &'a [u8],
+}
+
+impl FrameRef<'_> {
+    /// Payload of a text frame
+    pub fn text(&self) -> Result<&str> {
+        match self.header.opcode {
+            OpCode::Text => std::str::from_utf8(self.payload)
+                .map_err(|e| ExchangeError::InvalidResponse(format!("Invalid UTF-8 in text frame: {e}"))),
+            _ => Err(ExchangeError::InvalidResponse("Expected text frame".to_string())),
         }
+    }
 
-        let frame = Frame {
-            header: FrameHeader {
-                fin,
-                opcode,
-                mask,
-                payload_len,
-            },
-            payload,
+    pub fn to_frame(&self) -> Frame {
+        Frame { header: self.header.clone(), payload: self.payload.to_vec() }
+    }
+}
+
+/// Reusable read buffer for incoming frames
+///
+/// Bytes are read straight into its spare space and frames are unmasked in
+/// place, so a steady stream of frames needs no allocation once the buffer
+/// has grown to the largest frame seen. Consumed bytes are reclaimed by
+/// moving the unread tail to the front only when more space is needed.
+#[derive(Debug)]
+pub struct FrameBuffer {
+    data: Vec<u8>,
+    /// First unread byte
+    start: usize,
+    /// End of the bytes read so far
+    end: usize,
+}
+
+impl FrameBuffer {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self { data: vec![0; capacity.max(1)], start: 0, end: 0 }
+    }
+
+    /// Bytes read but not yet consumed as frames
+    pub fn unread(&self) -> &[u8] {
+        &self.data[self.start..self.end]
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.start = 0;
+        self.end = 0;
+    }
+
+    /// At least `min` bytes of free space to read into; call `commit` after
+    pub fn spare(&mut self, min: usize) -> &mut [u8] {
+        if self.data.len() - self.end < min {
+            // Reclaim consumed space before growing
+            self.data.copy_within(self.start..self.end, 0);
+            self.end -= self.start;
+            self.start = 0;
+            if self.data.len() - self.end < min {
+                let grown = (self.end + min).max(self.data.len() * 2);
+                self.data.resize(grown, 0);
+            }
+        }
+        &mut self.data[self.end..]
+    }
+
+    /// Mark `n` bytes of the spare space as read
+    pub fn commit(&mut self, n: usize) {
+        self.end = (self.end + n).min(self.data.len());
+    }
+
+    /// Copy `bytes` in, for data that didn't come from a socket read
+    pub fn extend_from_slice(&mut self, bytes: &[u8]) {
+        self.spare(bytes.len())[..bytes.len()].copy_from_slice(bytes);
+        self.commit(bytes.len());
+    }
+
+    /// Take the next complete frame off the buffer
+    ///
+    /// Returns its header and the position of its unmasked payload, for
+    /// `payload`; `None` until the whole frame has been read.
+    pub fn next_frame(&mut self) -> Result<Option<(FrameHeader, Range<usize>)>> {
+        let Some((header, header_len)) = FrameHeader::parse(self.unread())? else {
+            return Ok(None);
         };
+        let payload = self.start + header_len..self.start + header_len + header.payload_len as usize;
+        if let Some(mask) = &header.mask {
+            Frame::apply_mask(&mut self.data[payload.clone()], mask);
+        }
+        self.start = payload.end;
+        Ok(Some((header, payload)))
+    }
 
-        timer.log_elapsed();
-        Ok((frame, offset + payload_len as usize))
+    /// Payload of a frame returned by `next_frame`, valid until the next `spare`
+    pub fn payload(&self, range: Range<usize>) -> &[u8] {
+        &self.data[range]
+    }
+}
+
+/// Connection options
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WebSocketOptions {
+    /// Offer permessage-deflate; the server may still decline it. Turn off
+    /// where inflating every message costs more than the bytes saved.
+    pub compression: bool,
+    /// Bound on connecting (TCP, TLS and upgrade) and on each flush. Frame
+    /// reads are not bounded: a quiet stream is normal, so stalled streams
+    /// are left to ping and idle checks.
+    pub timeout: Option<Duration>,
+}
+
+impl Default for WebSocketOptions {
+    fn default() -> Self {
+        Self { compression: true, timeout: None }
+    }
+}
+
+impl WebSocketOptions {
+    pub fn with_compression(mut self, enabled: bool) -> Self {
+        self.compression = enabled;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+/// Trailer a sync flush ends with; stripped from sent messages and restored
+/// before inflating received ones (RFC 7692 7.2.1)
+const DEFLATE_TRAILER: [u8; 4] = [0x00, 0x00, 0xff, 0xff];
+
+/// Negotiated permessage-deflate state
+///
+/// Both directions keep their sliding window between messages unless the
+/// handshake agreed `*_no_context_takeover`.
+pub struct PerMessageDeflate {
+    inflater: Box<InflateState>,
+    compressor: Box<CompressorOxide>,
+    server_no_context_takeover: bool,
+    client_no_context_takeover: bool,
+}
+
+impl std::fmt::Debug for PerMessageDeflate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PerMessageDeflate")
+            .field("server_no_context_takeover", &self.server_no_context_takeover)
+            .field("client_no_context_takeover", &self.client_no_context_takeover)
+            .finish()
+    }
+}
+
+impl PerMessageDeflate {
+    /// Extension header value offered in the handshake
+    pub const OFFER: &'static str = "permessage-deflate";
+
+    /// State for the extension the server accepted, if it did
+    ///
+    /// `response` is the handshake response; parameters we didn't offer
+    /// (`client_max_window_bits`) are refused by failing the handshake.
+    pub fn from_handshake(response: &str) -> Result<Option<Self>> {
+        let Some(extensions) = response.lines().find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            name.trim().eq_ignore_ascii_case("sec-websocket-extensions").then(|| value.trim().to_string())
+        }) else {
+            return Ok(None);
+        };
+        let mut params = extensions.split(';').map(str::trim);
+        if params.next() != Some(Self::OFFER) {
+            return Ok(None);
+        }
+        let mut deflate = Self::new();
+        for param in params {
+            match param.split_once('=').map_or(param, |(name, _)| name.trim()) {
+                "server_no_context_takeover" => deflate.server_no_context_takeover = true,
+                "client_no_context_takeover" => deflate.client_no_context_takeover = true,
+                // Any window up to 32 KiB inflates fine
+                "server_max_window_bits" => {}
+                other => {
+                    return Err(ExchangeError::NetworkError(format!("WebSocket handshake failed: unsupported deflate parameter {other}")));
+                }
+            }
+        }
+        Ok(Some(deflate))
+    }
+
+    pub fn new() -> Self {
+        Self {
+            inflater: InflateState::new_boxed(DataFormat::Raw),
+            compressor: Box::new(CompressorOxide::new(create_comp_flags_from_zip_params(1, -15, 0))),
+            server_no_context_takeover: false,
+            client_no_context_takeover: false,
+        }
+    }
+
+    /// Inflate one received message into `out`, replacing its contents
+    pub fn inflate(&mut self, payload: &[u8], out: &mut Vec<u8>) -> Result<()> {
+        out.clear();
+        for input in [payload, &DEFLATE_TRAILER[..]] {
+            let mut input = input;
+            loop {
+                if out.capacity() - out.len() < 1024 {
+                    out.reserve(out.capacity().max(4096));
+                }
+                let start = out.len();
+                out.resize(out.capacity(), 0);
+                let result = inflate(&mut self.inflater, input, &mut out[start..], MZFlush::Sync);
+                out.truncate(start + result.bytes_written);
+                input = &input[result.bytes_consumed..];
+                match result.status {
+                    Ok(_) | Err(MZError::Buf) if input.is_empty() && result.bytes_written == 0 => break,
+                    Ok(_) => {}
+                    Err(MZError::Buf) if input.is_empty() => break,
+                    Err(e) => return Err(ExchangeError::InvalidResponse(format!("Failed to inflate WebSocket message: {e:?}"))),
+                }
+            }
+        }
+        if self.server_no_context_takeover {
+            self.inflater.reset(DataFormat::Raw);
+        }
+        Ok(())
+    }
+
+    /// Deflate one outgoing message
+    pub fn deflate(&mut self, payload: &[u8]) -> Result<Vec<u8>> {
+        let mut out = vec![0; payload.len() / 2 + 64];
+        let mut written = 0;
+        let mut input = payload;
+        loop {
+            let result = deflate(&mut self.compressor, input, &mut out[written..], MZFlush::Sync);
+            written += result.bytes_written;
+            input = &input[result.bytes_consumed..];
+            match result.status {
+                Ok(_) if input.is_empty() && written < out.len() => break,
+                Ok(_) | Err(MZError::Buf) => out.resize(out.len() * 2, 0),
+                Err(e) => return Err(ExchangeError::SerializationError(format!("Failed to deflate WebSocket message: {e:?}"))),
+            }
+        }
+        out.truncate(written);
+        if out.ends_with(&DEFLATE_TRAILER) {
+            out.truncate(written - DEFLATE_TRAILER.len());
+        }
+        if self.client_no_context_takeover {
+            self.compressor.reset();
+        }
+        Ok(out)
+    }
+}
+
+impl Default for PerMessageDeflate {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Where a received payload lives
+enum Payload {
+    Buffer(Range<usize>),
+    Inflated(Range<usize>),
+}
+
+impl Payload {
+    fn truncate_half(&mut self) {
+        let (Payload::Buffer(range) | Payload::Inflated(range)) = self;
+        range.end = range.start + range.len() / 2;
+    }
+
+    fn len(&self) -> usize {
+        let (Payload::Buffer(range) | Payload::Inflated(range)) = self;
+        range.len()
     }
 }
 
 /// Monoio-native WebSocket client
 pub struct MonoioWebSocket {
-    stream: TlsStream,
+    /// `None` when replaying from a cassette
+    stream: Option<TlsStream>,
     url: Url,
     connected: bool,
     close_sent: bool,
-    buffer: Vec<u8>,
+    buffer: FrameBuffer,
+    /// Encoded frames not yet written; reused between sends
+    write_buf: Vec<u8>,
+    queued_frames: usize,
+    cassette: Option<(CassetteHandle, usize)>,
+    faults: Option<FaultInjector>,
+    options: WebSocketOptions,
+    /// Set when the server accepted permessage-deflate
+    deflate: Option<PerMessageDeflate>,
+    /// Last inflated message, reused between receives
+    inflated: Vec<u8>,
 }
 
 impl MonoioWebSocket {
     /// Create a new WebSocket connection
     pub async fn connect(url: Url) -> Result<Self> {
+        Self::connect_with_cassette(url, None).await
+    }
+
+    /// Create a WebSocket connection that records to, or replays from, a cassette
+    pub async fn connect_with_cassette(url: Url, cassette: Option<&CassetteHandle>) -> Result<Self> {
+        Self::connect_with_options(url, cassette, WebSocketOptions::default()).await
+    }
+
+    /// `connect_with_cassette` with explicit connection options
+    pub async fn connect_with_options(url: Url, cassette: Option<&CassetteHandle>, options: WebSocketOptions) -> Result<Self> {
+        if let Some(cassette) = cassette {
+            let session = cassette.open_ws_session(url.as_str())?;
+            if cassette.is_replay() {
+                info!("📼 Replaying WebSocket: {}", url);
+                return Ok(Self {
+                    stream: None,
+                    url,
+                    connected: true,
+                    close_sent: false,
+                    buffer: FrameBuffer::with_capacity(READ_CHUNK),
+                    write_buf: Vec::new(),
+                    queued_frames: 0,
+                    cassette: Some((cassette.clone(), session)),
+                    faults: None,
+                    options,
+                    deflate: None,
+                    inflated: Vec::new(),
+                });
+            }
+
+            let mut websocket = Self::connect_bounded(url, options).await?;
+            websocket.cassette = Some((cassette.clone(), session));
+            return Ok(websocket);
+        }
+
+        Self::connect_bounded(url, options).await
+    }
+
+    async fn connect_bounded(url: Url, options: WebSocketOptions) -> Result<Self> {
+        let what = format!("WebSocket connect to {}", url.host_str().unwrap_or_default());
+        with_deadline(options.timeout, &what, Self::connect_live(url, options)).await
+    }
+
+    async fn connect_live(url: Url, options: WebSocketOptions) -> Result<Self> {
         let timer = PerfTimer::start("websocket_connect".to_string());
         
         info!("🔗 Connecting to WebSocket: {}", url);
@@ -316,11 +680,18 @@ impl MonoioWebSocket {
         debug!("✅ TLS handshake completed");
 
         let mut websocket = Self {
-            stream: tls_stream,
+            stream: Some(tls_stream),
             url: url.clone(),
             connected: false,
             close_sent: false,
-            buffer: Vec::with_capacity(8192),
+            buffer: FrameBuffer::with_capacity(2 * READ_CHUNK),
+            write_buf: Vec::with_capacity(256),
+            queued_frames: 0,
+            cassette: None,
+            faults: None,
+            options,
+            deflate: None,
+            inflated: Vec::new(),
         };
 
         // Perform WebSocket handshake
@@ -343,6 +714,11 @@ impl MonoioWebSocket {
         let path = if self.url.path().is_empty() { "/" } else { self.url.path() };
         let query = self.url.query().map(|q| format!("?{q}")).unwrap_or_default();
         let host = self.url.host_str().unwrap();
+        let extensions = if self.options.compression {
+            format!("Sec-WebSocket-Extensions: {}\r\n", PerMessageDeflate::OFFER)
+        } else {
+            String::new()
+        };
 
         let handshake_request = format!(
             "GET {path}{query} HTTP/1.1\r\n\
@@ -351,25 +727,36 @@ impl MonoioWebSocket {
              Connection: Upgrade\r\n\
              Sec-WebSocket-Key: {ws_key}\r\n\
              Sec-WebSocket-Version: 13\r\n\
+             {extensions}\
              \r\n"
         );
 
         debug!("Sending WebSocket handshake request");
 
         // Send handshake request
-        self.stream.write_all(handshake_request.as_bytes()).await
+        self.live_stream()?.write_all(handshake_request.as_bytes()).await
             .map_err(|e| ExchangeError::NetworkError(format!("Failed to send handshake: {e}")))?;
 
         // Read handshake response
         let mut response_buffer = vec![0u8; 4096];
-        let bytes_read = self.stream.read(&mut response_buffer).await
+        let bytes_read = self.live_stream()?.read(&mut response_buffer).await
             .map_err(|e| ExchangeError::NetworkError(format!("Failed to read handshake response: {e}")))?;
 
-        let response = String::from_utf8_lossy(&response_buffer[..bytes_read]);
+        let received = &response_buffer[..bytes_read];
+        let header_end = received.windows(4).position(|w| w == b"\r\n\r\n").map_or(received.len(), |i| i + 4);
+        let response = String::from_utf8_lossy(&received[..header_end]);
         debug!("Received handshake response: {}", response);
 
         // Validate handshake response
         self.validate_handshake_response(&response, &ws_key)?;
+        if self.options.compression {
+            self.deflate = PerMessageDeflate::from_handshake(&response)?;
+            if self.deflate.is_some() {
+                debug!("🗜️ permessage-deflate negotiated");
+            }
+        }
+        // Frames sent right behind the handshake response
+        self.buffer.extend_from_slice(&received[header_end..]);
 
         self.connected = true;
         timer.log_elapsed();
@@ -378,6 +765,31 @@ impl MonoioWebSocket {
         Ok(())
     }
 
+    /// Inject faults into text and binary frames sent and received
+    pub fn with_faults(mut self, faults: FaultInjector) -> Self {
+        self.faults = Some(faults);
+        self
+    }
+
+    /// Fault for a data frame, if faults are injected
+    fn next_fault(&self, opcode: OpCode) -> Option<Fault> {
+        if !matches!(opcode, OpCode::Text | OpCode::Binary) {
+            return None;
+        }
+        self.faults.as_ref()?.next_fault()
+    }
+
+    /// Whether messages on this connection are compressed
+    pub fn is_compressed(&self) -> bool {
+        self.deflate.is_some()
+    }
+
+    /// Underlying TLS stream of a live connection
+    fn live_stream(&mut self) -> Result<&mut TlsStream> {
+        self.stream.as_mut()
+            .ok_or_else(|| ExchangeError::NetworkError("WebSocket has no live stream".to_string()))
+    }
+
     /// Generate WebSocket key for handshake
     fn generate_websocket_key(&self) -> String {
         let timestamp = nanos();
@@ -411,26 +823,114 @@ impl MonoioWebSocket {
         base64::engine::general_purpose::STANDARD.encode(hash)
     }
 
-    /// Send a frame
+    /// Send a frame, together with any queued before it
     pub async fn send_frame(&mut self, frame: Frame) -> Result<()> {
+        self.queue_frame(frame).await?;
+        self.flush().await
+    }
+
+    /// Encode a frame behind those already queued without writing it
+    ///
+    /// Queued frames go out in a single write on the next `flush` or
+    /// `send_frame`, saving a TLS record and syscall per frame when many
+    /// small messages are sent at once.
+    pub async fn queue_frame(&mut self, mut frame: Frame) -> Result<()> {
         if !self.connected || self.close_sent {
             return Err(ExchangeError::NetworkError("WebSocket not connected".to_string()));
         }
 
-        let timer = PerfTimer::start("websocket_send_frame".to_string());
-        let frame_bytes = frame.to_bytes();
+        match self.next_fault(frame.header.opcode) {
+            Some(Fault::Delay(delay)) => monoio::time::sleep(delay).await,
+            Some(Fault::Drop) => return Ok(()),
+            Some(fault @ Fault::PartialWrite) => {
+                // Half the frame reaches the wire, then the connection dies
+                let start = self.write_buf.len();
+                frame.write_to(&mut self.write_buf);
+                let cut = start + (self.write_buf.len() - start) / 2;
+                if let Some(stream) = self.stream.as_mut() {
+                    let _ = stream.write_all(&self.write_buf[..cut]).await;
+                }
+                self.write_buf.clear();
+                self.queued_frames = 0;
+                self.connected = false;
+                return Err(chaos::injected(fault, "WebSocket send"));
+            }
+            Some(Fault::Malformed) => {
+                frame.payload = chaos::truncate(&frame.payload);
+                frame.header.payload_len = frame.payload.len() as u64;
+            }
+            None => {}
+        }
 
-        debug!("Sending WebSocket frame: {:?} ({} bytes)", frame.header.opcode, frame_bytes.len());
+        if let Some((cassette, session)) = &self.cassette
+            && matches!(frame.header.opcode, OpCode::Text)
+        {
+            let text = String::from_utf8_lossy(&frame.payload).into_owned();
+            if cassette.is_replay() {
+                cassette.replay_ws_sent(*session, &text);
+            } else {
+                cassette.record_ws(*session, WsEvent::Sent(text));
+            }
+        }
+
+        if self.stream.is_none() {
+            // Replay: nothing goes on the wire
+            if matches!(frame.header.opcode, OpCode::Close) {
+                self.close_sent = true;
+            }
+            return Ok(());
+        }
 
-        self.stream.write_all(&frame_bytes).await
-            .map_err(|e| ExchangeError::NetworkError(format!("Failed to send frame: {e}")))?;
+        if let Some(deflate) = &mut self.deflate
+            && matches!(frame.header.opcode, OpCode::Text | OpCode::Binary)
+        {
+            frame.payload = deflate.deflate(&frame.payload)?;
+            frame.header.payload_len = frame.payload.len() as u64;
+            frame.header.compressed = true;
+        }
+        frame.write_to(&mut self.write_buf);
+        self.queued_frames += 1;
+        debug!("Queued WebSocket frame: {:?} ({} bytes pending)", frame.header.opcode, self.write_buf.len());
 
         if matches!(frame.header.opcode, OpCode::Close) {
             self.close_sent = true;
         }
+        Ok(())
+    }
+
+    /// Write every queued frame
+    pub async fn flush(&mut self) -> Result<()> {
+        if self.write_buf.is_empty() {
+            return Ok(());
+        }
+        let timer = PerfTimer::start("websocket_send_frame".to_string());
+        debug!("Sending {} WebSocket frames ({} bytes)", self.queued_frames, self.write_buf.len());
+
+        let stream = self.stream.as_mut()
+            .ok_or_else(|| ExchangeError::NetworkError("WebSocket has no live stream".to_string()))?;
+        let write = async {
+            stream.write_all(&self.write_buf).await
+                .map_err(|e| ExchangeError::NetworkError(format!("Failed to send frame: {e}")))
+        };
+        let result = with_deadline(self.options.timeout, "WebSocket write", write).await;
+        self.write_buf.clear();
+        self.queued_frames = 0;
 
         timer.log_elapsed();
-        Ok(())
+        result
+    }
+
+    /// Frames waiting for `flush`
+    pub fn queued_frames(&self) -> usize {
+        self.queued_frames
+    }
+
+    /// Send text messages in a single write
+    pub async fn send_texts(&mut self, messages: impl IntoIterator<Item = String>) -> Result<()> {
+        for message in messages {
+            self.queue_frame(Frame::text(message)).await?;
+        }
+        self.flush().await
     }
 
     /// Send text message
@@ -459,23 +959,94 @@ impl MonoioWebSocket {
 
     /// Receive next frame
     pub async fn receive_frame(&mut self) -> Result<Frame> {
+        Ok(self.receive_ref().await?.to_frame())
+    }
+
+    /// Receive the next frame without copying its payload out of the read buffer
+    pub async fn receive_ref(&mut self) -> Result<FrameRef<'_>> {
+        loop {
+            let (mut header, mut payload) = self.read_frame().await?;
+            match self.next_fault(header.opcode) {
+                Some(Fault::Delay(delay)) => monoio::time::sleep(delay).await,
+                Some(Fault::Drop) => continue,
+                Some(fault @ Fault::PartialWrite) => {
+                    // The connection dies part way through the frame
+                    self.connected = false;
+                    self.buffer.clear();
+                    return Err(chaos::injected(fault, "WebSocket receive"));
+                }
+                Some(Fault::Malformed) => {
+                    payload.truncate_half();
+                    header.payload_len = payload.len() as u64;
+                }
+                None => {}
+            }
+            let payload = match payload {
+                Payload::Buffer(range) => self.buffer.payload(range),
+                Payload::Inflated(range) => &self.inflated[range],
+            };
+            return Ok(FrameRef { header, payload });
+        }
+    }
+
+    /// Read the next frame off the wire or the cassette into the read buffer
+    async fn read_frame(&mut self) -> Result<(FrameHeader, Payload)> {
+        let (mut header, range) = self.read_raw_frame().await?;
+        if !header.compressed {
+            return Ok((header, Payload::Buffer(range)));
+        }
+        let deflate = self.deflate.as_mut()
+            .ok_or_else(|| ExchangeError::InvalidResponse("Compressed frame without permessage-deflate".to_string()))?;
+        deflate.inflate(self.buffer.payload(range), &mut self.inflated)?;
+        header.compressed = false;
+        header.payload_len = self.inflated.len() as u64;
+        if let Some((cassette, session)) = &self.cassette
+            && matches!(header.opcode, OpCode::Text)
+        {
+            let text = String::from_utf8_lossy(&self.inflated).into_owned();
+            cassette.record_ws(*session, WsEvent::Received(text));
+        }
+        Ok((header, Payload::Inflated(0..self.inflated.len())))
+    }
+
+    /// Read the next frame as sent, still compressed if it was
+    async fn read_raw_frame(&mut self) -> Result<(FrameHeader, Range<usize>)> {
         if !self.connected {
             return Err(ExchangeError::NetworkError("WebSocket not connected".to_string()));
         }
 
+        if let Some((cassette, session)) = &self.cassette
+            && cassette.is_replay()
+        {
+            return match cassette.replay_ws_received(*session) {
+                Some(text) => {
+                    // Stands in for an unmasked server frame; nothing else is buffered on replay
+                    self.buffer.clear();
+                    self.buffer.extend_from_slice(text.as_bytes());
+                    self.buffer.clear();
+                    let header = FrameHeader { fin: true, opcode: OpCode::Text, mask: None, compressed: false, payload_len: text.len() as u64 };
+                    Ok((header, 0..text.len()))
+                }
+                None => {
+                    self.connected = false;
+                    Err(ExchangeError::NetworkError("WebSocket cassette exhausted".to_string()))
+                }
+            };
+        }
+
         let timer = PerfTimer::start("websocket_receive_frame".to_string());
 
         loop {
             // Try to parse a frame from the buffer
-            if let Ok((frame, consumed)) = Frame::from_bytes(&self.buffer) {
-                self.buffer.drain(..consumed);
+            if let Some((header, payload)) = self.buffer.next_frame()? {
                 timer.log_elapsed();
 
                 // Handle control frames automatically
-                match frame.header.opcode {
+                match header.opcode {
                     OpCode::Ping => {
                         debug!("Received ping, sending pong");
-                        self.pong(frame.payload.clone()).await?;
+                        let data = self.buffer.payload(payload).to_vec();
+                        self.pong(data).await?;
                         continue; // Continue reading for next frame
                     }
                     OpCode::Close => {
@@ -485,35 +1056,39 @@ impl MonoioWebSocket {
                             let _ = self.send_frame(close_frame).await;
                         }
                         self.connected = false;
-                        return Ok(frame);
+                        return Ok((header, payload));
+                    }
+                    OpCode::Text => {
+                        // Compressed frames are recorded once inflated
+                        if let Some((cassette, session)) = &self.cassette
+                            && !header.compressed
+                        {
+                            let text = String::from_utf8_lossy(self.buffer.payload(payload.clone())).into_owned();
+                            cassette.record_ws(*session, WsEvent::Received(text));
+                        }
+                        return Ok((header, payload));
                     }
-                    _ => return Ok(frame),
+                    _ => return Ok((header, payload)),
                 }
             }
 
-            // Need more data
-            let mut temp_buffer = vec![0u8; 4096];
-            let bytes_read = self.stream.read(&mut temp_buffer).await
+            // Need more data: read straight into the buffer's free space
+            let stream = self.stream.as_mut()
+                .ok_or_else(|| ExchangeError::NetworkError("WebSocket has no live stream".to_string()))?;
+            let bytes_read = stream.read(self.buffer.spare(READ_CHUNK)).await
                 .map_err(|e| ExchangeError::NetworkError(format!("Failed to read frame: {e}")))?;
 
             if bytes_read == 0 {
                 return Err(ExchangeError::NetworkError("WebSocket connection closed by peer".to_string()));
             }
 
-            self.buffer.extend_from_slice(&temp_buffer[..bytes_read]);
+            self.buffer.commit(bytes_read);
         }
     }
 
     /// Receive next text message
     pub async fn receive_text(&mut self) -> Result<String> {
-        let frame = self.receive_frame().await?;
-        match frame.header.opcode {
-            OpCode::Text => {
-                String::from_utf8(frame.payload)
-                    .map_err(|e| ExchangeError::InvalidResponse(format!("Invalid UTF-8 in text frame: {e}")))
-            }
-            _ => Err(ExchangeError::InvalidResponse("Expected text frame".to_string())),
-        }
+        Ok(self.receive_ref().await?.text()?.to_owned())
     }
 
     /// Close the WebSocket connection
@@ -571,6 +1146,70 @@ mod tests {
         assert!(bytes[1] & 0x80 != 0); // Check mask bit
     }
 
+    #[test]
+    fn test_frame_buffer_parses_split_frames_in_place() {
+        let mut wire = Vec::new();
+        for i in 0..100 {
+            Frame::text(format!("{{\"u\":{i}}}")).write_to(&mut wire);
+        }
+        let big = Frame::binary(vec![7u8; 70_000]);
+        assert_eq!(Frame::from_bytes(&big.to_bytes()).unwrap().0.payload, big.payload);
+
+        // Fed in odd-sized reads, as a socket would
+        let mut buffer = FrameBuffer::with_capacity(64);
+        let mut received = Vec::new();
+        for chunk in wire.chunks(37) {
+            buffer.extend_from_slice(chunk);
+            while let Some((header, payload)) = buffer.next_frame().unwrap() {
+                assert_eq!(header.opcode, OpCode::Text);
+                received.push(String::from_utf8(buffer.payload(payload).to_vec()).unwrap());
+            }
+        }
+        assert_eq!(received.len(), 100);
+        assert_eq!(received[42], "{\"u\":42}");
+        assert!(buffer.unread().is_empty());
+        // Consumed space is reused rather than the buffer growing with the stream
+        assert!(buffer.capacity() <= 128, "{}", buffer.capacity());
+    }
+
+    #[test]
+    fn test_permessage_deflate_round_trip() {
+        let response = "HTTP/1.1 101 Switching Protocols\r\n\
+                        Sec-WebSocket-Extensions: permessage-deflate; server_no_context_takeover\r\n\r\n";
+        let mut server = PerMessageDeflate::from_handshake(response).unwrap().unwrap();
+        assert!(server.server_no_context_takeover && !server.client_no_context_takeover);
+        assert!(PerMessageDeflate::from_handshake("HTTP/1.1 101 Switching Protocols\r\n\r\n").unwrap().is_none());
+        assert!(PerMessageDeflate::from_handshake("Sec-WebSocket-Extensions: permessage-deflate; client_max_window_bits=10\r\n").is_err());
+
+        // RFC 7692 7.2.3.1: "Hello" as a single compressed message
+        let mut inflated = Vec::new();
+        server.inflate(&[0xf2, 0x48, 0xcd, 0xc9, 0xc9, 0x07, 0x00], &mut inflated).unwrap();
+        assert_eq!(inflated, b"Hello");
+
+        // Messages sharing a window, framed and read back through the buffer
+        let (mut client, mut reader) = (PerMessageDeflate::new(), PerMessageDeflate::new());
+        let message = r#"{"e":"depthUpdate","s":"BTCUSDT","b":[["50000.00","1.000"]],"a":[]}"#.repeat(20);
+        let mut wire = Vec::new();
+        for _ in 0..3 {
+            let mut frame = Frame::text(String::new());
+            frame.payload = client.deflate(message.as_bytes()).unwrap();
+            frame.header.payload_len = frame.payload.len() as u64;
+            frame.header.compressed = true;
+            assert!(frame.payload.len() < message.len() / 4);
+            frame.write_to(&mut wire);
+        }
+        let mut buffer = FrameBuffer::with_capacity(64);
+        buffer.extend_from_slice(&wire);
+        let mut count = 0;
+        while let Some((header, payload)) = buffer.next_frame().unwrap() {
+            assert!(header.compressed);
+            reader.inflate(buffer.payload(payload), &mut inflated).unwrap();
+            assert_eq!(inflated, message.as_bytes());
+            count += 1;
+        }
+        assert_eq!(count, 3);
+    }
+
     #[test]
     fn test_websocket_key_generation() {
         // Create a fake/mock websocket for testing key generation only
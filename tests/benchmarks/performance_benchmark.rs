@@ -1,18 +1,48 @@
 //! Performance benchmarking suite for SriQuant.ai
 //!
 //! Measures and compares performance of key components:
-//! - Fixed-point arithmetic vs floating-point
+//! - Fixed-point arithmetic vs floating-point, and decimal parsing
+//! - Bulk slice sum and VWAP vs the naive loop
 //! - ID generation throughput
 //! - Timing precision and overhead
 //! - Memory allocation patterns
+//! - Order book snapshot apply (resync after reconnect)
+//! - WebSocket frame reads, time and allocations per message
+//! - False sharing and cross-core channel handoff
 //! - Network latency simulation
 
+use sriquant_core::fixed::bulk;
 use sriquant_core::prelude::*;
 use sriquant_exchanges::prelude::*;
-use std::time::Instant;
+use std::alloc::{GlobalAlloc, Layout, System};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use tracing::{info, warn};
 
+/// System allocator that counts allocations, for allocations-per-message figures
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
 /// Benchmark result statistics
 #[derive(Debug, Clone)]
 pub struct BenchmarkStats {
@@ -110,6 +140,12 @@ pub struct PerformanceBenchmark {
     results: HashMap<String, BenchmarkStats>,
 }
 
+impl Default for PerformanceBenchmark {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl PerformanceBenchmark {
     pub fn new() -> Self {
         Self {
@@ -133,6 +169,10 @@ impl PerformanceBenchmark {
         self.benchmark_memory_allocation().await;
         self.benchmark_serialization().await;
         self.benchmark_hash_operations().await;
+        self.benchmark_snapshot_apply().await;
+        self.benchmark_ws_frame_reads().await;
+        self.benchmark_false_sharing().await;
+        self.benchmark_cross_core_handoff().await;
         
         self.print_summary();
     }
@@ -231,6 +271,69 @@ impl PerformanceBenchmark {
         let f64_add_stats = BenchmarkStats::from_samples("F64 Addition".to_string(), f64_add_samples);
         f64_add_stats.print_summary();
         self.results.insert("f64_addition".to_string(), f64_add_stats);
+        
+        // Parsing a depth level field: string path vs byte-slice parser
+        let level = std::hint::black_box("43127.45000000");
+        let mut str_parse_samples = Vec::with_capacity(ITERATIONS);
+        for _ in 0..ITERATIONS {
+            let start = nanos();
+            let _result = Fixed::from_str_exact(level);
+            let end = nanos();
+            str_parse_samples.push(end - start);
+        }
+        
+        let str_parse_stats = BenchmarkStats::from_samples("Fixed Parse (from_str_exact)".to_string(), str_parse_samples);
+        str_parse_stats.print_summary();
+        self.results.insert("fixed_parse_str".to_string(), str_parse_stats);
+        
+        let mut ascii_parse_samples = Vec::with_capacity(ITERATIONS);
+        for _ in 0..ITERATIONS {
+            let start = nanos();
+            let _result = Fixed::from_ascii(level.as_bytes());
+            let end = nanos();
+            ascii_parse_samples.push(end - start);
+        }
+        
+        let ascii_parse_stats = BenchmarkStats::from_samples("Fixed Parse (from_ascii)".to_string(), ascii_parse_samples);
+        ascii_parse_stats.print_summary();
+        self.results.insert("fixed_parse_ascii".to_string(), ascii_parse_stats);
+        
+        // Sum and VWAP over a 1000-level book side: naive fold vs bulk helpers
+        const LEVELS: usize = 1_000;
+        let prices: Vec<Fixed> = (0..LEVELS)
+            .map(|i| Fixed::from_str_exact(&format!("{}.{:08}", 43_000 + i, i * 7_919 % 100_000_000)).unwrap())
+            .collect();
+        let quantities: Vec<Fixed> = (0..LEVELS)
+            .map(|i| Fixed::from_str_exact(&format!("0.{:08}", (i + 1) * 104_729 % 100_000_000)).unwrap())
+            .collect();
+        let (prices, quantities) = (std::hint::black_box(&prices), std::hint::black_box(&quantities));
+        
+        let naive_sum = || Some(quantities.iter().fold(Fixed::ZERO, |acc, q| acc + *q));
+        let naive_vwap = || {
+            let notional = prices.iter().zip(quantities).fold(Fixed::ZERO, |acc, (p, q)| acc + *p * *q);
+            Some(notional / quantities.iter().fold(Fixed::ZERO, |acc, q| acc + *q))
+        };
+        type Case<'a> = (&'a str, &'a str, &'a dyn Fn() -> Option<Fixed>);
+        let bulk_cases: [Case; 4] = [
+            ("Fixed Sum x1000 (naive)", "fixed_sum_naive", &naive_sum),
+            ("Fixed Sum x1000 (bulk)", "fixed_sum_bulk", &|| Some(bulk::sum(quantities))),
+            ("Fixed VWAP x1000 (naive)", "fixed_vwap_naive", &naive_vwap),
+            ("Fixed VWAP x1000 (bulk)", "fixed_vwap_bulk", &|| bulk::vwap(prices, quantities)),
+        ];
+        for (name, key, run) in bulk_cases {
+            let mut samples = Vec::with_capacity(ITERATIONS / 10);
+            for _ in 0..ITERATIONS / 10 {
+                let start = nanos();
+                std::hint::black_box(run());
+                let end = nanos();
+                samples.push(end - start);
+            }
+            
+            let stats = BenchmarkStats::from_samples(name.to_string(), samples);
+            stats.print_summary();
+            info!("   {:.0}M values/s at the median", LEVELS as f64 / stats.p50_nanos.max(1) as f64 * 1_000.0);
+            self.results.insert(key.to_string(), stats);
+        }
     }
     
     /// Benchmark ID generation performance
@@ -413,6 +516,221 @@ impl PerformanceBenchmark {
         self.results.insert("hashmap_lookup".to_string(), lookup_stats);
     }
     
+    /// Benchmark loading deep depth snapshots into the local order book
+    async fn benchmark_snapshot_apply(&mut self) {
+        use sriquant_exchanges::binance::rest::OrderBookResponse;
+        use sriquant_exchanges::orderbook::OrderBook as LocalBook;
+
+        const ITERATIONS: usize = 200;
+        info!("📚 Benchmarking order book snapshot apply...");
+
+        for levels in [1000usize, 5000] {
+            let side = |start: f64, step: f64| -> Vec<[String; 2]> {
+                (0..levels)
+                    .map(|i| [format!("{:.2}", start + step * i as f64), "0.12345".to_string()])
+                    .collect()
+            };
+            let snapshot = OrderBookResponse {
+                last_update_id: 1,
+                bids: side(50_000.0, -0.01),
+                asks: side(50_000.01, 0.01),
+            };
+
+            let mut book = LocalBook::new("BTCUSDT");
+            let mut samples = Vec::with_capacity(ITERATIONS);
+            for _ in 0..ITERATIONS {
+                let start = nanos();
+                book.apply_snapshot(&snapshot).unwrap();
+                let end = nanos();
+                samples.push(end - start);
+            }
+
+            let stats = BenchmarkStats::from_samples(format!("Snapshot Apply ({levels} levels/side)"), samples);
+            stats.print_summary();
+            self.results.insert(format!("snapshot_apply_{levels}"), stats);
+        }
+    }
+    
+    /// Read depth20-sized frames the way the WebSocket client used to (fresh
+    /// 4 KiB temp buffer per read, copied payload, drained buffer) and through
+    /// the reusable `FrameBuffer`
+    async fn benchmark_ws_frame_reads(&mut self) {
+        use sriquant_exchanges::websocket::{Frame, FrameBuffer, FrameHeader, OpCode};
+
+        const MESSAGES: usize = 20_000;
+        const READ: usize = 4096;
+        info!("📡 Benchmarking WebSocket frame reads...");
+
+        // Unmasked server frames, ~1 KiB like a 100ms depth20 update
+        let levels: Vec<String> = (0..20).map(|i| format!("[\"{:.2}\",\"0.12345\"]", 50_000.0 + i as f64)).collect();
+        let body = format!("{{\"lastUpdateId\":1,\"bids\":[{0}],\"asks\":[{0}]}}", levels.join(","));
+        let mut wire = Vec::new();
+        for _ in 0..MESSAGES {
+            let payload = body.as_bytes().to_vec();
+            let header = FrameHeader { fin: true, opcode: OpCode::Text, mask: None, compressed: false, payload_len: payload.len() as u64 };
+            Frame { header, payload }.write_to(&mut wire);
+        }
+
+        // Previous read path
+        let mut samples = Vec::with_capacity(MESSAGES);
+        let mut buffer: Vec<u8> = Vec::with_capacity(8192);
+        let mut chunks = wire.chunks(READ);
+        let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+        let mut received = 0;
+        while received < MESSAGES {
+            let start = nanos();
+            let frame = loop {
+                if let Ok((frame, consumed)) = Frame::from_bytes(&buffer) {
+                    buffer.drain(..consumed);
+                    break frame;
+                }
+                let chunk = chunks.next().unwrap();
+                let mut temp_buffer = vec![0u8; READ];
+                temp_buffer[..chunk.len()].copy_from_slice(chunk);
+                buffer.extend_from_slice(&temp_buffer[..chunk.len()]);
+            };
+            std::hint::black_box(&frame.payload);
+            samples.push(nanos() - start);
+            received += 1;
+        }
+        let legacy_allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+        let legacy = BenchmarkStats::from_samples("WS Frame Read (copying)".to_string(), samples);
+        legacy.print_summary();
+
+        // Reusable frame buffer
+        let mut samples = Vec::with_capacity(MESSAGES);
+        let mut buffer = FrameBuffer::with_capacity(2 * READ);
+        let mut chunks = wire.chunks(READ);
+        let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+        let mut received = 0;
+        while received < MESSAGES {
+            let start = nanos();
+            let (_, payload) = loop {
+                if let Some(frame) = buffer.next_frame().unwrap() {
+                    break frame;
+                }
+                let chunk = chunks.next().unwrap();
+                buffer.spare(READ)[..chunk.len()].copy_from_slice(chunk);
+                buffer.commit(chunk.len());
+            };
+            std::hint::black_box(buffer.payload(payload));
+            samples.push(nanos() - start);
+            received += 1;
+        }
+        let reused_allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+        let reused = BenchmarkStats::from_samples("WS Frame Read (FrameBuffer)".to_string(), samples);
+        reused.print_summary();
+
+        info!(
+            "   Allocations per message: {:.2} copying vs {:.4} FrameBuffer",
+            legacy_allocations as f64 / MESSAGES as f64,
+            reused_allocations as f64 / MESSAGES as f64,
+        );
+        self.results.insert("ws_frame_read_copying".to_string(), legacy);
+        self.results.insert("ws_frame_read_buffer".to_string(), reused);
+    }
+
+    /// Two threads hammering neighbouring counters vs counters on separate lines
+    ///
+    /// Only meaningful with the threads on different physical cores.
+    async fn benchmark_false_sharing(&mut self) {
+        use sriquant_core::CachePadded;
+        use std::sync::atomic::{AtomicU64, Ordering};
+        use std::sync::Arc;
+
+        const OPS: u64 = 2_000_000;
+        const RUNS: usize = 5;
+        info!("🧱 Benchmarking false sharing ({} cores available)...", get_cpu_count());
+
+        /// Both counters 8 bytes apart, on one line
+        struct Adjacent([AtomicU64; 2]);
+        /// Each counter on its own line
+        struct Padded([CachePadded<AtomicU64>; 2]);
+
+        /// Average ns per increment with one thread per counter
+        fn hammer<S: Send + Sync + 'static>(shared: Arc<S>, counter: fn(&S, usize) -> &AtomicU64) -> u64 {
+            let start = nanos();
+            let handles: Vec<_> = (0..2).map(|core| {
+                let shared = Arc::clone(&shared);
+                std::thread::spawn(move || {
+                    let _ = bind_to_cpu_set(core);
+                    for _ in 0..OPS {
+                        counter(&shared, core).fetch_add(1, Ordering::Relaxed);
+                    }
+                })
+            }).collect();
+            handles.into_iter().for_each(|h| h.join().unwrap());
+            (nanos() - start) / OPS
+        }
+
+        let mut adjacent = Vec::with_capacity(RUNS);
+        let mut padded = Vec::with_capacity(RUNS);
+        for _ in 0..RUNS {
+            let shared = Arc::new(Adjacent([AtomicU64::new(0), AtomicU64::new(0)]));
+            adjacent.push(hammer(shared, |s, i| &s.0[i]));
+            let shared = Arc::new(Padded([CachePadded::new(AtomicU64::new(0)), CachePadded::new(AtomicU64::new(0))]));
+            padded.push(hammer(shared, |s, i| &s.0[i]));
+        }
+
+        for (key, name, samples) in [
+            ("false_sharing_adjacent", "False Sharing (adjacent counters)", adjacent),
+            ("false_sharing_padded", "False Sharing (padded counters)", padded),
+        ] {
+            let stats = BenchmarkStats::from_samples(name.to_string(), samples);
+            stats.print_summary();
+            self.results.insert(key.to_string(), stats);
+        }
+    }
+
+    /// Round trip through a pair of bounded channels between two threads
+    async fn benchmark_cross_core_handoff(&mut self) {
+        use sriquant_core::channel::bounded;
+
+        const ROUND_TRIPS: usize = 100_000;
+        info!("🔁 Benchmarking cross-core channel handoff...");
+
+        let (ping_tx, mut ping_rx) = bounded::<u64>(1024);
+        let (pong_tx, mut pong_rx) = bounded::<u64>(1024);
+        let echo = std::thread::spawn(move || {
+            let _ = bind_to_cpu_set(1);
+            for _ in 0..ROUND_TRIPS {
+                let value = loop {
+                    if let Ok(value) = ping_rx.try_recv() {
+                        break value;
+                    }
+                    std::hint::spin_loop();
+                };
+                while pong_tx.try_send(value).is_err() {
+                    std::hint::spin_loop();
+                }
+            }
+        });
+
+        let mut samples = Vec::with_capacity(ROUND_TRIPS);
+        for i in 0..ROUND_TRIPS as u64 {
+            let start = nanos();
+            while ping_tx.try_send(i).is_err() {
+                std::hint::spin_loop();
+            }
+            loop {
+                if pong_rx.try_recv().is_ok() {
+                    break;
+                }
+                std::hint::spin_loop();
+            }
+            samples.push(nanos() - start);
+            // On a single core the echo thread only runs when we yield
+            if get_cpu_count() < 2 {
+                std::thread::yield_now();
+            }
+        }
+        echo.join().unwrap();
+
+        let stats = BenchmarkStats::from_samples("Cross-Core Handoff (round trip)".to_string(), samples);
+        stats.print_summary();
+        self.results.insert("cross_core_handoff".to_string(), stats);
+    }
+    
     /// Print comprehensive benchmark summary
     pub fn print_summary(&self) {
         info!("🏁 Performance Benchmark Summary");
@@ -10,7 +10,7 @@ use sriquant_exchanges::binance::{BinanceConfig, BinanceRestClient};
 use sriquant_core::prelude::*;
 use std::env;
 
-#[monoio::main]
+#[monoio::main(enable_timer = true)]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Initialize logging
     tracing_subscriber::fmt::init();
@@ -91,8 +91,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             
             // Show non-zero balances
             let non_zero_balances: Vec<_> = account.balances.iter()
-                .filter(|b| b.free.parse::<f64>().unwrap_or(0.0) > 0.0 || 
-                           b.locked.parse::<f64>().unwrap_or(0.0) > 0.0)
+                .filter(|b| b.free > Fixed::ZERO || 
+                           b.locked > Fixed::ZERO)
                 .collect();
                 
             if !non_zero_balances.is_empty() {
@@ -141,6 +141,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         time_in_force: Some("GTC"), // Good Till Cancelled
         stop_price: None,
         iceberg_qty: None,
+        new_client_order_id: None,
     };
     
     match rest_client.test_new_order(&test_order_params).await {
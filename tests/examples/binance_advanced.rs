@@ -3,7 +3,7 @@
 //! Demonstrates:
 //! - Live order placement and management using the new simplified API
 //! - Real-time market data streaming
-//! - Portfolio tracking with fixed-point arithmetic
+//! - Position and PnL tracking with `PositionTracker`
 //! - Risk management and position sizing
 //! - High-performance latency monitoring
 //! 
@@ -25,6 +25,7 @@
 use sriquant_core::prelude::*;
 use sriquant_exchanges::binance::{BinanceConfig, BinanceExchange, BinanceRestClient};
 use sriquant_exchanges::prelude::*;
+use sriquant_exchanges::PositionTracker;
 use sriquant_exchanges::types::{OrderSide, OrderType};
 use tracing::{info, warn, error, debug};
 use std::collections::HashMap;
@@ -54,78 +55,19 @@ impl Default for TradingConfig {
     }
 }
 
-/// Portfolio tracker using fixed-point arithmetic
-#[derive(Debug)]
-pub struct Portfolio {
-    balances: HashMap<String, Fixed>,
-    positions: HashMap<String, Position>,
-    total_value_usdt: Fixed,
-    unrealized_pnl: Fixed,
-    realized_pnl: Fixed,
-    last_update: u64,
-}
-
-#[derive(Debug, Clone)]
-pub struct Position {
-    symbol: String,
-    size: Fixed,
-    entry_price: Fixed,
-    current_price: Fixed,
-    unrealized_pnl: Fixed,
-    side: OrderSide,
-    timestamp: u64,
-}
-
-impl Portfolio {
-    pub fn new() -> Self {
-        Self {
-            balances: HashMap::new(),
-            positions: HashMap::new(),
-            total_value_usdt: Fixed::ZERO,
-            unrealized_pnl: Fixed::ZERO,
-            realized_pnl: Fixed::ZERO,
-            last_update: 0,
-        }
-    }
-    
-    pub fn update_balance(&mut self, asset: &str, balance: Fixed) {
-        self.balances.insert(asset.to_string(), balance);
-        self.last_update = nanos() / 1_000_000; // Convert to milliseconds
-    }
-    
-    pub fn get_balance(&self, asset: &str) -> Fixed {
-        self.balances.get(asset).copied().unwrap_or(Fixed::ZERO)
-    }
-    
-    pub fn calculate_position_size(&self, price: Fixed, risk_pct: Fixed) -> Result<Fixed> {
-        let usdt_balance = self.get_balance("USDT");
-        let risk_amount = usdt_balance * (risk_pct / Fixed::from_i64(100)?);
-        let position_size = risk_amount / price;
-        Ok(position_size)
-    }
-    
-    pub fn update_position(&mut self, symbol: &str, price: Fixed) {
-        if let Some(position) = self.positions.get_mut(symbol) {
-            position.current_price = price;
-            position.unrealized_pnl = match position.side {
-                OrderSide::Buy => (price - position.entry_price) * position.size,
-                OrderSide::Sell => (position.entry_price - price) * position.size,
-            };
-        }
-    }
-}
-
 /// Advanced trading bot
+#[allow(dead_code)]
 pub struct AdvancedTradingBot {
     exchange: BinanceExchange,
     rest_client: BinanceRestClient,
     config: TradingConfig,
-    portfolio: Portfolio,
+    positions: PositionTracker,
     active_orders: HashMap<String, (u64, u64)>, // client_order_id -> (order_id, timestamp_ms)
     performance_metrics: PerformanceTracker,
 }
 
 #[derive(Debug)]
+#[allow(dead_code)]
 pub struct PerformanceTracker {
     total_trades: u64,
     winning_trades: u64,
@@ -136,6 +78,12 @@ pub struct PerformanceTracker {
     latency_samples: Vec<u64>,
 }
 
+impl Default for PerformanceTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl PerformanceTracker {
     pub fn new() -> Self {
         Self {
@@ -225,7 +173,7 @@ impl AdvancedTradingBot {
             exchange,
             rest_client,
             config,
-            portfolio: Portfolio::new(),
+            positions: PositionTracker::new(),
             active_orders: HashMap::new(),
             performance_metrics: PerformanceTracker::new(),
         })
@@ -264,7 +212,7 @@ impl AdvancedTradingBot {
             let timer = PerfTimer::start("trading_iteration".to_string());
             
             // Update portfolio every 10 iterations
-            if iteration % 10 == 0 {
+            if iteration.is_multiple_of(10) {
                 if let Err(e) = self.update_portfolio().await {
                     error!("Failed to update portfolio: {}", e);
                 }
@@ -284,7 +232,7 @@ impl AdvancedTradingBot {
             self.performance_metrics.record_latency(elapsed);
             
             // Print performance every 100 iterations
-            if iteration % 100 == 0 {
+            if iteration.is_multiple_of(100) {
                 self.performance_metrics.print_summary();
                 self.print_portfolio_summary();
             }
@@ -292,8 +240,6 @@ impl AdvancedTradingBot {
             // Sleep for a short time (adjust based on strategy needs)
             monoio::time::sleep(Duration::from_millis(100)).await;
         }
-        
-        Ok(())
     }
     
     async fn update_portfolio(&mut self) -> Result<()> {
@@ -302,15 +248,15 @@ impl AdvancedTradingBot {
         let timer = PerfTimer::start("portfolio_update".to_string());
         
         // Fetch real account info from the exchange
-        match self.rest_client.get_account_info().await {
+        match self.rest_client.get_account_info_non_zero().await {
             Ok(account_info) => {
                 for balance in &account_info.balances {
-                    let free = Fixed::from_str_exact(&balance.free).unwrap_or(Fixed::ZERO);
-                    let locked = Fixed::from_str_exact(&balance.locked).unwrap_or(Fixed::ZERO);
+                    let free = balance.free;
+                    let locked = balance.locked;
                     let total = free + locked;
                     
                     if total > Fixed::ZERO {
-                        self.portfolio.update_balance(&balance.asset, total);
+                        self.positions.set_balance(&balance.asset, total);
                         debug!("  {}: {} (free: {}, locked: {})", balance.asset, total, free, locked);
                     }
                 }
@@ -318,8 +264,8 @@ impl AdvancedTradingBot {
             Err(e) => {
                 warn!("Failed to fetch account info: {}. Using default balances.", e);
                 // Fallback to default balances for testing
-                self.portfolio.update_balance("USDT", Fixed::from_str_exact("1000.0")?);
-                self.portfolio.update_balance("BTC", Fixed::from_str_exact("0.02")?);
+                self.positions.set_balance("USDT", Fixed::from_str_exact("1000.0")?);
+                self.positions.set_balance("BTC", Fixed::from_str_exact("0.02")?);
             }
         }
         
@@ -340,15 +286,15 @@ impl AdvancedTradingBot {
     async fn check_trading_signals(&mut self) -> Result<()> {
         // Get real-time market data
         let ticker = self.rest_client.get_symbol_price_ticker(&self.config.symbol).await?;
-        let current_price = Fixed::from_str_exact(&ticker.price)?;
+        let current_price = ticker.price;
         
         debug!("Current {} price: ${}", self.config.symbol, current_price);
         
-        // Update position with current price
-        self.portfolio.update_position(&self.config.symbol, current_price);
+        // Mark the position to the current price
+        self.positions.update_mark(&self.config.symbol, current_price);
         
         // Check if we should place a new order
-        let usdt_balance = self.portfolio.get_balance("USDT");
+        let usdt_balance = self.positions.balance("USDT");
         if usdt_balance > Fixed::from_str_exact("100.0")? {
             // Example: Simple market making strategy
             self.place_limit_orders(current_price).await?;
@@ -389,7 +335,8 @@ impl AdvancedTradingBot {
             return Ok(());
         }
         
-        let position_size = self.portfolio.calculate_position_size(
+        let position_size = self.positions.risk_sized_quantity(
+            "USDT",
             current_price, 
             self.config.risk_per_trade
         )?;
@@ -433,11 +380,9 @@ impl AdvancedTradingBot {
         let klines = self.rest_client.get_klines(&self.config.symbol, "1h", None, None, Some(5)).await?;
         let elapsed = timer.elapsed_micros();
         info!("📈 Retrieved {} klines ({}μs)", klines.len(), elapsed);
-        for (i, kline) in klines.iter().enumerate() {
-            if let Ok((open, high, low, close, volume)) = kline.ohlcv() {
-                debug!("  Kline {}: O:{} H:{} L:{} C:{} V:{}", 
-                    i, open, high, low, close, volume);
-            }
+        for (i, candle) in klines.iter().enumerate() {
+            debug!("  Kline {}: O:{} H:{} L:{} C:{} V:{}", 
+                i, candle.open, candle.high, candle.low, candle.close, candle.volume);
         }
         
         // Test get_all_orders (last 24 hours)
@@ -513,24 +458,16 @@ impl AdvancedTradingBot {
                             // Get trades for this order
                             match self.rest_client.get_order_trades(&self.config.symbol, *order_id).await {
                                 Ok(trades) => {
-                                    let mut total_value = Fixed::ZERO;
-                                    let mut total_commission = Fixed::ZERO;
+                                    let mut realized = Fixed::ZERO;
                                     
                                     for trade in &trades {
-                                        let qty = Fixed::from_str_exact(&trade.qty)?;
-                                        let price = Fixed::from_str_exact(&trade.price)?;
-                                        let commission = Fixed::from_str_exact(&trade.commission)?;
-                                        
-                                        total_value = total_value + (qty * price);
-                                        total_commission = total_commission + commission;
+                                        realized += self.positions.on_my_trade(trade)?.unwrap_or(Fixed::ZERO);
                                         
                                         debug!("  Trade {}: {} @ {} - Fee: {} {}", 
                                             trade.id, trade.qty, trade.price, trade.commission, trade.commission_asset);
                                     }
                                     
-                                    // Simple profit calculation (this is just an example)
-                                    let profit = Fixed::from_i64(10)? - total_commission; // Placeholder profit
-                                    self.performance_metrics.record_trade(profit);
+                                    self.performance_metrics.record_trade(realized);
                                 }
                                 Err(e) => {
                                     warn!("Failed to get trades for order {}: {}", order_id, e);
@@ -555,11 +492,14 @@ impl AdvancedTradingBot {
     
     fn print_portfolio_summary(&self) {
         info!("💼 Portfolio Summary:");
-        info!("   USDT Balance: ${}", self.portfolio.get_balance("USDT"));
-        info!("   BTC Balance: {} BTC", self.portfolio.get_balance("BTC"));
+        info!("   USDT Balance: ${}", self.positions.balance("USDT"));
+        info!("   BTC Balance: {} BTC", self.positions.balance("BTC"));
         info!("   Active Orders: {}", self.active_orders.len());
-        info!("   Unrealized PnL: ${}", self.portfolio.unrealized_pnl);
-        info!("   Realized PnL: ${}", self.portfolio.realized_pnl);
+        if let Some(position) = self.positions.position(&self.config.symbol) {
+            info!("   Position: {} @ {}", position.quantity, position.average_entry_price);
+        }
+        info!("   Unrealized PnL: ${}", self.positions.unrealized_pnl());
+        info!("   Realized PnL: ${}", self.positions.realized_pnl());
     }
 }
 
@@ -611,13 +551,13 @@ mod tests {
     
     #[test]
     fn test_portfolio_calculations() {
-        let mut portfolio = Portfolio::new();
-        portfolio.update_balance("USDT", Fixed::from_str_exact("1000.0").unwrap());
+        let mut positions = PositionTracker::new();
+        positions.set_balance("USDT", Fixed::from_str_exact("1000.0").unwrap());
         
         let price = Fixed::from_str_exact("50000.0").unwrap();
         let risk_pct = Fixed::from_str_exact("1.0").unwrap();
         
-        let position_size = portfolio.calculate_position_size(price, risk_pct).unwrap();
+        let position_size = positions.risk_sized_quantity("USDT", price, risk_pct).unwrap();
         assert_eq!(position_size.to_string(), "0.00020"); // $10 / $50000 = 0.0002 BTC
     }
     
@@ -11,7 +11,7 @@ use sriquant_core::prelude::*;
 use sriquant_exchanges::binance::{BinanceConfig, BinanceExchange};
 use tracing::{info, error};
 
-#[monoio::main]
+#[monoio::main(enable_timer = true)]
 async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
     // Load environment variables
     dotenv::dotenv().ok();
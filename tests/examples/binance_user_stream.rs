@@ -1,5 +1,5 @@
 //! Binance User Data Stream - Production System
-//! 
+//!
 //! Production-ready implementation with:
 //! - Automatic reconnection
 //! - Listen key keepalive management
@@ -7,104 +7,14 @@
 //! - Performance monitoring
 
 use sriquant_core::prelude::*;
-use sriquant_exchanges::binance::{BinanceConfig, BinanceUserStreamClient, BinanceRestClient, UserDataEvent, TradeSide};
-use tracing::{info, error, warn};
-use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
-use std::time::Duration;
-use monoio::time::{sleep, interval};
-
-/// Production user stream manager
-struct UserStreamManager {
-    config: BinanceConfig,
-    rest_client: Arc<BinanceRestClient>,
-    listen_key: String,
-    running: Arc<AtomicBool>,
-    last_message_time: Arc<AtomicU64>,
-}
-
-impl UserStreamManager {
-    async fn new(config: BinanceConfig) -> Result<Self, Box<dyn std::error::Error>> {
-        let rest_client = Arc::new(BinanceRestClient::new(config.clone()).await?);
-        let listen_key = rest_client.create_listen_key().await?;
-        
-        Ok(Self {
-            config,
-            rest_client,
-            listen_key,
-            running: Arc::new(AtomicBool::new(true)),
-            last_message_time: Arc::new(AtomicU64::new(nanos())),
-        })
-    }
-    
-    /// Start keepalive task
-    async fn start_keepalive(&self) {
-        let rest_client = self.rest_client.clone();
-        let listen_key = self.listen_key.clone();
-        let running = self.running.clone();
-        
-        monoio::spawn(async move {
-            let mut keepalive_interval = interval(Duration::from_secs(30 * 60)); // 30 minutes
-            
-            while running.load(Ordering::Relaxed) {
-                keepalive_interval.tick().await;
-                
-                match rest_client.keepalive_listen_key(&listen_key).await {
-                    Ok(_) => info!("✅ Listen key keepalive successful"),
-                    Err(e) => error!("❌ Listen key keepalive failed: {}", e),
-                }
-            }
-            
-            info!("🛑 Keepalive task stopped");
-        });
-        
-        info!("🔄 Keepalive task started (30-minute interval)");
-    }
-    
-    /// Monitor connection health
-    async fn start_health_monitor(&self) {
-        let last_message_time = self.last_message_time.clone();
-        let running = self.running.clone();
-        
-        monoio::spawn(async move {
-            let mut health_interval = interval(Duration::from_secs(60)); // Check every minute
-            
-            while running.load(Ordering::Relaxed) {
-                health_interval.tick().await;
-                
-                let last_msg_ns = last_message_time.load(Ordering::Relaxed);
-                let now_ns = nanos();
-                let elapsed_s = (now_ns - last_msg_ns) / 1_000_000_000;
-                
-                if elapsed_s > 300 { // 5 minutes without messages
-                    warn!("⚠️ No messages received for {} seconds", elapsed_s);
-                }
-            }
-            
-            info!("🛑 Health monitor stopped");
-        });
-        
-        info!("🏥 Health monitor started");
-    }
-    
-    /// Clean shutdown
-    async fn shutdown(&self) -> Result<(), Box<dyn std::error::Error>> {
-        info!("🛑 Initiating shutdown...");
-        self.running.store(false, Ordering::Relaxed);
-        
-        // Close listen key
-        self.rest_client.close_listen_key(&self.listen_key).await?;
-        info!("✅ Listen key closed");
-        
-        Ok(())
-    }
-}
+use sriquant_exchanges::binance::{BinanceConfig, TradeSide, UserDataEvent, UserStreamManager};
+use tracing::{error, info, warn};
 
 #[monoio::main(enable_timer = true)]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Load environment variables
     dotenv::dotenv().ok();
-    
+
     // Production logging setup
     tracing_subscriber::fmt()
         .with_max_level(tracing::Level::INFO)
@@ -112,9 +22,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .with_thread_ids(true)
         .with_line_number(true)
         .init();
-    
+
     info!("🚀 Starting SriQuant.ai Binance User Data Stream - Production Mode");
-    
+
     // Load configuration with error handling
     let config = match BinanceConfig::testnet().with_env_credentials() {
         Ok(config) => {
@@ -127,218 +37,183 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             return Err(e.into());
         }
     };
-    
-    // Create user stream manager
-    let manager = UserStreamManager::new(config.clone()).await?;
-    info!("✅ User stream manager initialized");
-    
-    // Start background tasks
-    manager.start_keepalive().await;
-    manager.start_health_monitor().await;
-    
-    // Main event loop with reconnection
-    let mut reconnect_attempts = 0;
-    const MAX_RECONNECT_ATTEMPTS: u32 = 10;
-    
+
+    // The manager creates the listen key, keeps it alive every 30 minutes,
+    // and renews it and reconnects when the stream drops
+    let mut manager = UserStreamManager::new(config).await?;
+    manager.connect().await?;
+    info!("✅ Connected to user data stream");
+
     // Statistics tracking
     let session_start_time = nanos();
     let mut total_message_count = 0;
     let mut total_account_updates = 0;
     let mut total_balance_updates = 0;
     let mut total_order_updates = 0;
-    
+
+    info!("📊 Monitoring user data events...");
+    info!("   💡 Place orders on Binance testnet to see real-time updates");
+    info!("   📌 Press Ctrl+C for graceful shutdown\n");
+
+    // Process messages
     loop {
-        // Create user stream client
-        let mut ws_client = BinanceUserStreamClient::new(config.clone());
-        
-        // Connect with current listen key
-        match ws_client.connect(&manager.listen_key).await {
-            Ok(_) => {
-                info!("✅ Connected to user data stream");
-                reconnect_attempts = 0;
-            }
-            Err(e) => {
-                error!("❌ Failed to connect: {}", e);
-                
-                // Try to get a new listen key
-                match manager.rest_client.create_listen_key().await {
-                    Ok(new_key) => {
-                        warn!("🔑 Created new listen key after connection failure");
-                        continue;
-                    }
-                    Err(e) => {
-                        error!("❌ Failed to create new listen key: {}", e);
-                        break;
-                    }
-                }
-            }
-        }
-        
-        info!("📊 Monitoring user data events...");
-        info!("   💡 Place orders on Binance testnet to see real-time updates");
-        info!("   📌 Press Ctrl+C for graceful shutdown\n");
-        
-        // Process messages
-        loop {
-            match ws_client.receive_event().await {
-                Ok(event) => {
-                    total_message_count += 1;
-                    manager.last_message_time.store(nanos(), Ordering::Relaxed);
-                    
-                    match event {
-                        UserDataEvent::AccountUpdate(account) => {
-                            total_account_updates += 1;
+        match manager.next_event().await {
+            Ok(event) => {
+                total_message_count += 1;
+
+                match event {
+                    UserDataEvent::AccountUpdate(account) => {
+                        total_account_updates += 1;
                         info!("👤 ACCOUNT UPDATE #{}", total_account_updates);
                         info!("   Event Time: {}", account.event_time);
                         info!("   Last Update: {}", account.last_account_update);
                         info!("   Balances: {} assets", account.balances.len());
-                        
+
                         // Show non-zero balances
                         for balance in &account.balances {
                             if balance.free > Fixed::ZERO || balance.locked > Fixed::ZERO {
-                                info!("   💰 {}: Free={} Locked={}", 
-                                    balance.asset, balance.free, balance.locked);
+                                info!(
+                                    "   💰 {}: Free={} Locked={}",
+                                    balance.asset, balance.free, balance.locked
+                                );
                             }
                         }
                         info!("");
-                    },
-                    
+                    }
+
                     UserDataEvent::BalanceUpdate(balance) => {
                         total_balance_updates += 1;
-                        let emoji = if balance.balance_delta > Fixed::ZERO { "📈" } else { "📉" };
+                        let emoji = if balance.balance_delta > Fixed::ZERO {
+                            "📈"
+                        } else {
+                            "📉"
+                        };
                         info!("{} BALANCE UPDATE #{}", emoji, total_balance_updates);
                         info!("   Asset: {}", balance.asset);
-                        info!("   Delta: {}{}", 
-                            if balance.balance_delta > Fixed::ZERO { "+" } else { "" },
+                        info!(
+                            "   Delta: {}{}",
+                            if balance.balance_delta > Fixed::ZERO {
+                                "+"
+                            } else {
+                                ""
+                            },
                             balance.balance_delta
                         );
                         info!("   Event Time: {}", balance.event_time);
                         info!("   Clear Time: {}", balance.clear_time);
                         info!("");
-                    },
-                    
+                    }
+
                     UserDataEvent::OrderUpdate(order) => {
                         total_order_updates += 1;
                         let side_emoji = match order.side {
                             TradeSide::Buy => "🟢",
                             TradeSide::Sell => "🔴",
                         };
-                        
+
                         info!("{} ORDER UPDATE #{}", side_emoji, total_order_updates);
                         info!("   Symbol: {}", order.symbol);
                         info!("   Order ID: {}", order.order_id);
                         info!("   Client Order ID: {}", order.client_order_id);
-                        info!("   Side: {} | Type: {} | TIF: {}", 
-                            match order.side { TradeSide::Buy => "BUY", TradeSide::Sell => "SELL" },
+                        info!(
+                            "   Side: {} | Type: {} | TIF: {}",
+                            match order.side {
+                                TradeSide::Buy => "BUY",
+                                TradeSide::Sell => "SELL",
+                            },
                             order.order_type,
                             order.time_in_force
                         );
-                        info!("   Price: {} | Quantity: {}", order.order_price, order.order_quantity);
-                        info!("   Status: {} | Execution: {}", order.order_status, order.execution_type);
+                        info!(
+                            "   Price: {} | Quantity: {}",
+                            order.order_price, order.order_quantity
+                        );
+                        info!(
+                            "   Status: {} | Execution: {}",
+                            order.order_status, order.execution_type
+                        );
                         let fill_percentage = if order.order_quantity > Fixed::ZERO {
                             let ratio = order.cumulative_filled_quantity / order.order_quantity;
                             ratio.to_f64() * 100.0
                         } else {
                             0.0
                         };
-                        info!("   Filled: {} / {} ({:.1}%)", 
-                            order.cumulative_filled_quantity,
-                            order.order_quantity,
-                            fill_percentage
+                        info!(
+                            "   Filled: {} / {} ({:.1}%)",
+                            order.cumulative_filled_quantity, order.order_quantity, fill_percentage
                         );
-                        
+
                         if order.last_executed_quantity > Fixed::ZERO {
-                            info!("   Last Fill: {} @ {} (Trade ID: {})",
+                            info!(
+                                "   Last Fill: {} @ {} (Trade ID: {})",
                                 order.last_executed_quantity,
                                 order.last_executed_price,
-                                order.trade_id
+                                order.trade_id.map_or_else(|| "-".to_string(), |id| id.to_string())
                             );
                         }
-                        
+
                         if order.commission_amount > Fixed::ZERO {
-                            info!("   Commission: {} {}", order.commission_amount, order.commission_asset);
+                            info!(
+                                "   Commission: {} {}",
+                                order.commission_amount, order.commission_asset.as_deref().unwrap_or("-")
+                            );
                         }
-                        
-                        if !order.order_reject_reason.is_empty() && order.order_reject_reason != "NONE" {
+
+                        if !order.order_reject_reason.is_empty()
+                            && order.order_reject_reason != "NONE"
+                        {
                             warn!("   ⚠️ Reject Reason: {}", order.order_reject_reason);
                         }
-                        
-                        info!("");
-                    },
-                }
-                
-                    // Print statistics every 10 messages
-                    if total_message_count % 10 == 0 {
-                        let elapsed_s = (nanos() - session_start_time) as f64 / 1_000_000_000.0;
-                        info!("📊 Session Statistics: {} messages in {:.1}s ({:.1} msg/s)",
-                            total_message_count, elapsed_s, total_message_count as f64 / elapsed_s
-                        );
-                        info!("   Account Updates: {} | Balance Updates: {} | Order Updates: {}",
-                            total_account_updates, total_balance_updates, total_order_updates
-                        );
+
                         info!("");
                     }
-            },
+                }
+
+                // Print statistics every 10 messages
+                if total_message_count % 10 == 0 {
+                    let elapsed_s = (nanos() - session_start_time) as f64 / 1_000_000_000.0;
+                    info!(
+                        "📊 Session Statistics: {} messages in {:.1}s ({:.1} msg/s)",
+                        total_message_count,
+                        elapsed_s,
+                        total_message_count as f64 / elapsed_s
+                    );
+                    info!(
+                        "   Account Updates: {} | Balance Updates: {} | Order Updates: {}",
+                        total_account_updates, total_balance_updates, total_order_updates
+                    );
+                    info!("");
+                }
+            }
             Err(e) => {
+                // Only returned once every reconnect attempt has failed
                 error!("❌ User stream error: {}", e);
-                
-                // Connection lost, break inner loop to reconnect
-                warn!("🔄 Connection lost, attempting to reconnect...");
                 break;
             }
         }
-        
-        // Check if we should continue running
-        if !manager.running.load(Ordering::Relaxed) {
-            info!("🛑 Shutdown requested");
-            break;
-        }
     }
-        
-        // Reconnection logic
-        reconnect_attempts += 1;
-        if reconnect_attempts >= MAX_RECONNECT_ATTEMPTS {
-            error!("❌ Maximum reconnection attempts ({}) reached", MAX_RECONNECT_ATTEMPTS);
-            break;
-        }
-        
-        // Exponential backoff for reconnection
-        let backoff_seconds = std::cmp::min(2u64.pow(reconnect_attempts), 60);
-        warn!("⏳ Waiting {} seconds before reconnection attempt {}/{}", 
-            backoff_seconds, reconnect_attempts, MAX_RECONNECT_ATTEMPTS);
-        sleep(Duration::from_secs(backoff_seconds)).await;
-        
-        // Try to get a new listen key
-        match manager.rest_client.create_listen_key().await {
-            Ok(new_key) => {
-                info!("🔑 Created new listen key for reconnection");
-                // Update manager's listen key (in a real system, this would be thread-safe)
-                // For now, we'll just use the new key in the next iteration
-            }
-            Err(e) => {
-                error!("❌ Failed to create new listen key: {}", e);
-                continue;
-            }
-        }
-    }
-    
+
     // Graceful shutdown
     manager.shutdown().await?;
-    
+    info!("✅ Listen key closed");
+
     // Final statistics
     let total_elapsed_s = (nanos() - session_start_time) as f64 / 1_000_000_000.0;
     info!("\n📈 Production Session Summary:");
     info!("   Total Duration: {:.1}s", total_elapsed_s);
     info!("   Total Messages: {}", total_message_count);
     if total_elapsed_s > 0.0 {
-        info!("   Average Rate: {:.2} msg/s", total_message_count as f64 / total_elapsed_s);
+        info!(
+            "   Average Rate: {:.2} msg/s",
+            total_message_count as f64 / total_elapsed_s
+        );
     }
     info!("   Account Updates: {}", total_account_updates);
     info!("   Balance Updates: {}", total_balance_updates);
     info!("   Order Updates: {}", total_order_updates);
-    info!("   Reconnection Attempts: {}", reconnect_attempts);
-    
+    info!("   Reconnections: {}", manager.reconnects());
+
     info!("\n✅ User stream monitor shutdown complete");
-    
+
     Ok(())
-}
\ No newline at end of file
+}
@@ -4,10 +4,19 @@
 
 use sriquant_core::prelude::*;
 use sriquant_exchanges::binance::{BinanceConfig, BinanceWebSocketClient};
-use sriquant_exchanges::binance::websocket::{MarketDataEvent, TradeSide};
+use sriquant_exchanges::{MarketDataEvent, TradeSide};
+use sriquant_exchanges::mux::{EventMux, Muxed};
+use std::time::Duration;
 use tracing::{info, error};
 
-#[monoio::main]
+/// Everything the example's event loop reacts to
+enum Event {
+    Market(MarketDataEvent),
+    Stats,
+    Deadline,
+}
+
+#[monoio::main(enable_timer = true)]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Load environment variables from .env file
     dotenv::dotenv().ok();
@@ -52,23 +61,45 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("✅ WebSocket connected successfully with multiple streams");
     
     // Test market data streams
-    test_market_data_streams(&mut ws_client, 30).await?;
+    test_market_data_streams(ws_client, 30).await?;
     
     Ok(())
 }
 
-async fn test_market_data_streams(ws_client: &mut BinanceWebSocketClient, duration_seconds: u64) -> Result<(), Box<dyn std::error::Error>> {
+async fn test_market_data_streams(mut ws_client: BinanceWebSocketClient, duration_seconds: u64) -> Result<(), Box<dyn std::error::Error>> {
     info!("🎯 Starting market data streams (will run for {} seconds)...", duration_seconds);
     info!("   Watch for live price updates, trades, and order book changes");
     
+    // One loop over both symbols' events, a stats timer and the deadline
+    let mut mux = EventMux::new();
+    mux.add_channel_map("btcusdt", ws_client.subscribe_symbol_events("BTCUSDT"), Event::Market);
+    mux.add_channel_map("ethusdt", ws_client.subscribe_symbol_events("ETHUSDT"), Event::Market);
+    mux.add_interval("stats", Duration::from_secs(10), || Event::Stats);
+    mux.add_interval("deadline", Duration::from_secs(duration_seconds), || Event::Deadline);
+    
+    // The reader fans events out to the symbol channels
+    let reader = monoio::spawn(async move {
+        loop {
+            if let Err(e) = ws_client.receive_message().await {
+                error!("❌ WebSocket error: {}", e);
+                break;
+            }
+        }
+        info!("🔌 Disconnecting WebSocket...");
+        ws_client.close().await
+    });
+    
     // Stream real-time data 
     let start_time = nanos();
-    let duration_ns = duration_seconds * 1_000_000_000u64;
     let mut message_count = 0;
     
-    while (nanos() - start_time) < duration_ns {
-        match ws_client.receive_message().await {
-            Ok(event) => {
+    while let Some(Muxed { event, .. }) = mux.next().await {
+        match event {
+            Event::Deadline => break,
+            Event::Stats => {
+                info!("⏱️  {} messages so far", message_count);
+            }
+            Event::Market(event) => {
                 message_count += 1;
                 
                 // Log different types of market data with enhanced formatting
@@ -84,9 +115,9 @@ async fn test_market_data_streams(ws_client: &mut BinanceWebSocketClient, durati
                         );
                     },
                     MarketDataEvent::Depth(depth) => {
-                        let best_bid = depth.bids.get(0).map(|b| b.price.to_string()).unwrap_or("N/A".to_string());
-                        let best_ask = depth.asks.get(0).map(|a| a.price.to_string()).unwrap_or("N/A".to_string());
-                        let spread = if let (Some(bid), Some(ask)) = (depth.bids.get(0), depth.asks.get(0)) {
+                        let best_bid = depth.bids.first().map(|b| b.price.to_string()).unwrap_or("N/A".to_string());
+                        let best_ask = depth.asks.first().map(|a| a.price.to_string()).unwrap_or("N/A".to_string());
+                        let spread = if let (Some(bid), Some(ask)) = (depth.bids.first(), depth.asks.first()) {
                             format!("${:.2}", ask.price - bid.price)
                         } else {
                             "N/A".to_string()
@@ -116,6 +147,9 @@ async fn test_market_data_streams(ws_client: &mut BinanceWebSocketClient, durati
                             trade.trade_id
                         );
                     },
+                    MarketDataEvent::AggTrade(agg) => {
+                        info!("🧺 AGG TRADE: {} {} @ ${} ({} trades)", agg.symbol, agg.quantity, agg.price, agg.trade_count());
+                    },
                     MarketDataEvent::Kline(kline) => {
                         let status = if kline.is_closed { "CLOSED" } else { "LIVE" };
                         info!("📈 KLINE: {} ({}) - O:${} H:${} L:${} C:${} V:{}", 
@@ -127,17 +161,23 @@ async fn test_market_data_streams(ws_client: &mut BinanceWebSocketClient, durati
                             kline.close,
                             kline.volume
                         );
+                    },
+                    MarketDataEvent::MiniTicker(mini) => {
+                        info!("🔹 MINI: {} = ${}", mini.symbol, mini.close);
+                    },
+                    MarketDataEvent::BookTicker(book) => {
+                        info!("📗 BOOK: {} - Bid: ${} | Ask: ${}", book.symbol, book.bid_price, book.ask_price);
+                    },
+                    MarketDataEvent::MiniTickerBatch(batch) => {
+                        info!("🔹 MINI BATCH: {} symbols", batch.len());
+                    },
+                    MarketDataEvent::BookTickerBatch(batch) => {
+                        info!("📗 BOOK BATCH: {} symbols", batch.len());
+                    },
+                    MarketDataEvent::Reconnected(reconnect) => {
+                        info!("🔄 RECONNECTED: {} streams after {} attempts", reconnect.streams.len(), reconnect.attempts);
                     }
                 }
-                
-                // Add small delay to prevent flooding (using simple loop delay)
-                if message_count % 10 == 0 {
-                    for _ in 0..1000000 { /* Simple CPU delay */ }
-                }
-            },
-            Err(e) => {
-                error!("❌ WebSocket error: {}", e);
-                break;
             }
         }
     }
@@ -152,9 +192,8 @@ async fn test_market_data_streams(ws_client: &mut BinanceWebSocketClient, durati
         );
     }
     
-    // Clean disconnect
-    info!("🔌 Disconnecting WebSocket...");
-    ws_client.close().await?;
+    // Dropping the loop's receivers doesn't stop the reader; it ends with the process
+    drop(reader);
     info!("✅ Market data test completed successfully");
     
     Ok(())
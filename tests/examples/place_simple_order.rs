@@ -38,7 +38,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Get current price
     info!("\n💱 Getting BTCUSDT price...");
     let ticker = client.get_symbol_price_ticker("BTCUSDT").await?;
-    let current_price = Fixed::from_str_exact(&ticker.price).unwrap_or(Fixed::ZERO);
+    let current_price = ticker.price;
     info!("📈 Current price: ${}", current_price);
     
     // Calculate a buy price 10% below market (to ensure it doesn't execute immediately)
@@ -57,6 +57,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         time_in_force: Some("GTC"),
         stop_price: None,
         iceberg_qty: None,
+        new_client_order_id: None,
     };
     
     match client.new_order(&order_params).await {
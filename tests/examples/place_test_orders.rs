@@ -3,8 +3,8 @@
 //! This script places various types of orders to demonstrate user stream functionality
 
 use sriquant_core::prelude::*;
-use sriquant_exchanges::binance::{BinanceConfig, BinanceRestClient};
 use sriquant_exchanges::binance::rest::TestOrderParams;
+use sriquant_exchanges::testkit::bootstrap_testnet;
 use tracing::{info, error};
 
 #[monoio::main(enable_timer = true)]
@@ -19,20 +19,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     info!("🚀 Starting Binance Testnet Order Placement Script");
     
-    // Load configuration
-    let config = match BinanceConfig::testnet().with_env_credentials() {
-        Ok(config) => {
-            info!("✅ API credentials loaded from environment");
-            config
-        }
-        Err(e) => {
-            error!("❌ Failed to load API credentials: {}", e);
-            return Err(e.into());
+    // Check keys, permissions, clock and balances before placing anything
+    let bootstrap = bootstrap_testnet().await;
+    bootstrap.report.log();
+    let client = match bootstrap.client {
+        Some(client) if !bootstrap.report.has_failures() => client,
+        _ => {
+            error!("❌ Testnet not ready; fix the failed checks above");
+            return Err("testnet bootstrap failed".into());
         }
     };
-    
-    // Create REST client
-    let client = BinanceRestClient::new(config).await?;
     info!("✅ REST client initialized");
     
     // Get account info first
@@ -43,8 +39,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Show USDT balance
     for balance in &account.balances {
         if balance.asset == "USDT" {
-            let free = Fixed::from_str_exact(&balance.free).unwrap_or(Fixed::ZERO);
-            let locked = Fixed::from_str_exact(&balance.locked).unwrap_or(Fixed::ZERO);
+            let free = balance.free;
+            let locked = balance.locked;
             if free > Fixed::ZERO || locked > Fixed::ZERO {
                 info!("💰 USDT Balance: Free={} Locked={}", free, locked);
             }
@@ -54,7 +50,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Get current BTC price
     info!("\n💱 Getting current BTCUSDT price...");
     let ticker = client.get_symbol_price_ticker("BTCUSDT").await?;
-    let current_price = Fixed::from_str_exact(&ticker.price).unwrap_or(Fixed::ZERO);
+    let current_price = ticker.price;
     info!("📈 Current BTCUSDT price: ${}", current_price);
     
     // Calculate order prices - round to 2 decimal places
@@ -80,6 +76,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         time_in_force: Some("GTC"),
         stop_price: None,
         iceberg_qty: None,
+        new_client_order_id: None,
     };
     match client.new_order(&buy_order_params).await {
         Ok(order) => {
@@ -109,6 +106,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         time_in_force: Some("GTC"),
         stop_price: None,
         iceberg_qty: None,
+        new_client_order_id: None,
     };
     match client.new_order(&sell_order_params).await {
         Ok(order) => {
@@ -137,6 +135,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         time_in_force: None,
         stop_price: None,
         iceberg_qty: None,
+        new_client_order_id: None,
     };
     match client.new_order(&market_order_params).await {
         Ok(order) => {
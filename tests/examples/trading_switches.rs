@@ -0,0 +1,48 @@
+//! Runtime per-symbol trading switches from the command line
+//!
+//! Demonstrates:
+//! - Pausing and resuming trading per symbol without restarting
+//! - Optional cancellation of working orders on pause
+//! - Sharing switches between an admin thread and the trading loop
+//!
+//! Type commands on stdin while the example runs:
+//!   pause BTCUSDT --cancel maintenance
+//!   resume BTCUSDT
+//!   status
+
+use sriquant_core::prelude::*;
+use sriquant_exchanges::TradingSwitches;
+use std::io::BufRead;
+use std::time::Duration;
+use tracing::{info, warn};
+
+fn main() {
+    init_logging();
+    info!("🚀 Starting SriQuant.ai trading switches example");
+
+    let switches = TradingSwitches::new();
+    let admin = switches.clone();
+
+    // Admin CLI on its own thread; the trading loop never blocks on stdin
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lock().lines().map_while(|l| l.ok()) {
+            match admin.apply_command(&line) {
+                Ok(reply) => info!("🛠️  {}", reply),
+                Err(e) => warn!("🛠️  {}", e),
+            }
+        }
+    });
+
+    let symbols = ["BTCUSDT", "ETHUSDT"];
+    loop {
+        for symbol in switches.take_cancellations() {
+            info!("❌ Canceling working orders for {}", symbol);
+        }
+        for symbol in symbols {
+            if switches.is_enabled(symbol) {
+                info!("📈 {} trading", symbol);
+            }
+        }
+        std::thread::sleep(Duration::from_secs(2));
+    }
+}
@@ -6,6 +6,7 @@ use sriquant_core::prelude::*;
 use sriquant_exchanges::binance::{BinanceConfig, BinanceRestClient, BinanceUserStreamClient, UserDataEvent, TradeSide};
 use sriquant_exchanges::binance::rest::TestOrderParams;
 use tracing::{info, error};
+use std::rc::Rc;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use monoio::time::sleep;
@@ -36,7 +37,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     };
     
     // Create REST client
-    let rest_client = Arc::new(BinanceRestClient::new(config.clone()).await?);
+    let rest_client = Rc::new(BinanceRestClient::new(config.clone()).await?);
     info!("✅ REST client initialized");
     
     // Create listen key
@@ -114,7 +115,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     // Get current price
     let ticker = rest_client.get_symbol_price_ticker("BTCUSDT").await?;
-    let current_price = Fixed::from_str_exact(&ticker.price).unwrap_or(Fixed::ZERO);
+    let current_price = ticker.price;
     info!("📈 Current BTCUSDT price: ${}", current_price);
     
     // Order 1: Buy order 10% below market
@@ -131,6 +132,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         time_in_force: Some("GTC"),
         stop_price: None,
         iceberg_qty: None,
+        new_client_order_id: None,
     };
     
     match rest_client.new_order(&buy_params).await {
@@ -167,6 +169,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         time_in_force: Some("GTC"),
         stop_price: None,
         iceberg_qty: None,
+        new_client_order_id: None,
     };
     
     match rest_client.new_order(&sell_params).await {
@@ -53,7 +53,7 @@ mod connectivity_tests {
     use super::*;
 
     #[rstest]
-    #[monoio::test]
+    #[monoio::test(timer_enabled = true)]
     async fn test_ping_latency(test_config: BinanceConfig) {
         let client = BinanceRestClient::new(test_config).await
             .expect("Failed to create REST client");
@@ -67,7 +67,7 @@ mod connectivity_tests {
     }
 
     #[rstest]
-    #[monoio::test]
+    #[monoio::test(timer_enabled = true)]
     async fn test_server_time(test_config: BinanceConfig) {
         let client = BinanceRestClient::new(test_config).await
             .expect("Failed to create REST client");
@@ -92,7 +92,7 @@ mod market_data_tests {
     use super::*;
 
     #[rstest]
-    #[monoio::test]
+    #[monoio::test(timer_enabled = true)]
     async fn test_24hr_ticker(
         test_config: BinanceConfig,
         #[values("BTCUSDT", "ETHUSDT", "BNBUSDT")] symbol: &str
@@ -105,17 +105,10 @@ mod market_data_tests {
         
         // Validate ticker data
         assert_eq!(ticker.symbol, symbol);
-        assert!(!ticker.last_price.is_empty());
-        assert!(!ticker.volume.is_empty());
         
-        // Parse and validate numeric values
-        let price = Fixed::from_str_exact(&ticker.last_price)
-            .expect("Invalid price format");
-        assert!(price > Fixed::ZERO, "Price should be positive");
-        
-        let volume = Fixed::from_str_exact(&ticker.volume)
-            .expect("Invalid volume format");
-        assert!(volume >= Fixed::ZERO, "Volume should be non-negative");
+        // Validate numeric values
+        assert!(ticker.last_price > Fixed::ZERO, "Price should be positive");
+        assert!(ticker.volume >= Fixed::ZERO, "Volume should be non-negative");
     }
 
     #[rstest]
@@ -123,7 +116,7 @@ mod market_data_tests {
     #[case("5m", 60)]
     #[case("1h", 24)]
     #[case("1d", 7)]
-    #[monoio::test]
+    #[monoio::test(timer_enabled = true)]
     async fn test_klines(
         test_config: BinanceConfig,
         #[case] interval: &str,
@@ -140,20 +133,19 @@ mod market_data_tests {
         assert!(!klines.is_empty(), "Should return at least one kline");
         
         // Validate first kline
-        let (open, high, low, close, volume) = klines[0].ohlcv()
-            .expect("Failed to parse OHLCV");
-        
-        assert!(open > Fixed::ZERO);
-        assert!(high >= low, "High should be >= Low");
-        assert!(high >= open, "High should be >= Open");
-        assert!(high >= close, "High should be >= Close");
-        assert!(low <= open, "Low should be <= Open");
-        assert!(low <= close, "Low should be <= Close");
-        assert!(volume >= Fixed::ZERO);
+        let candle = &klines[0];
+        
+        assert!(candle.open > Fixed::ZERO);
+        assert!(candle.high >= candle.low, "High should be >= Low");
+        assert!(candle.high >= candle.open, "High should be >= Open");
+        assert!(candle.high >= candle.close, "High should be >= Close");
+        assert!(candle.low <= candle.open, "Low should be <= Open");
+        assert!(candle.low <= candle.close, "Low should be <= Close");
+        assert!(candle.volume >= Fixed::ZERO);
     }
 
     #[rstest]
-    #[monoio::test]
+    #[monoio::test(timer_enabled = true)]
     async fn test_order_book(
         test_config: BinanceConfig,
         #[values(5, 10, 20)] limit: u16
@@ -243,7 +235,7 @@ mod trading_tests {
 
     /// Test order history retrieval
     #[rstest]
-    #[monoio::test]
+    #[monoio::test(timer_enabled = true)]
     async fn test_get_all_orders(test_config: BinanceConfig) {
         let client = BinanceRestClient::new(test_config).await
             .expect("Failed to create REST client");
@@ -262,15 +254,14 @@ mod trading_tests {
             assert!(!order.order_type.is_empty());
             
             // Validate quantities
-            let orig_qty = Fixed::from_str_exact(&order.orig_qty)
-                .expect("Invalid original quantity");
-            assert!(orig_qty > Fixed::ZERO);
+            assert!(order.orig_qty > Fixed::ZERO);
         }
     }
 
     /// Test account info retrieval
     #[rstest]
-    #[monoio::test]
+    #[monoio::test(timer_enabled = true)]
+    #[allow(unused_comparisons, clippy::absurd_extreme_comparisons)]
     async fn test_account_info(test_config: BinanceConfig) {
         let client = BinanceRestClient::new(test_config).await
             .expect("Failed to create REST client");
@@ -288,8 +279,8 @@ mod trading_tests {
                 for balance in &account.balances {
                     if balance.asset == "USDT" {
                         found_usdt = true;
-                        let free = Fixed::from_str_exact(&balance.free).unwrap_or(Fixed::ZERO);
-                        let locked = Fixed::from_str_exact(&balance.locked).unwrap_or(Fixed::ZERO);
+                        let free = balance.free;
+                        let locked = balance.locked;
                         assert!(free + locked >= Fixed::ZERO);
                     }
                     if balance.asset == "BTC" {
@@ -318,7 +309,7 @@ mod performance_tests {
 
     /// Test sequential API calls performance
     #[rstest]
-    #[monoio::test]
+    #[monoio::test(timer_enabled = true)]
     async fn test_sequential_requests(test_config: BinanceConfig) {
         let client = BinanceRestClient::new(test_config).await
             .expect("Failed to create REST client");
@@ -341,7 +332,7 @@ mod performance_tests {
         assert_eq!(results.len(), 3);
         for ticker in results {
             assert!(!ticker.symbol.is_empty());
-            assert!(!ticker.price.is_empty());
+            assert!(ticker.price > Fixed::ZERO);
         }
         
         // Performance check - should complete in reasonable time
@@ -398,7 +389,7 @@ mod integration_tests {
         // 1. Get current price
         let ticker = client.get_symbol_price_ticker("BTCUSDT").await
             .expect("Failed to get price");
-        let current_price = Fixed::from_str_exact(&ticker.price).unwrap();
+        let current_price = ticker.price;
         
         // 2. Place a limit order far from market price
         let order_price = (current_price * Fixed::from_str_exact("0.5").unwrap()).round_dp(2);
@@ -447,7 +438,7 @@ mod integration_tests {
 
     /// Test portfolio management workflow
     #[rstest]
-    #[monoio::test]
+    #[monoio::test(timer_enabled = true)]
     async fn test_portfolio_workflow(test_config: BinanceConfig) {
         let client = BinanceRestClient::new(test_config).await
             .expect("Failed to create REST client");
@@ -457,8 +448,8 @@ mod integration_tests {
         match client.get_account_info().await {
             Ok(account) => {
                 for balance in account.balances {
-                    let free = Fixed::from_str_exact(&balance.free).unwrap_or(Fixed::ZERO);
-                    let locked = Fixed::from_str_exact(&balance.locked).unwrap_or(Fixed::ZERO);
+                    let free = balance.free;
+                    let locked = balance.locked;
                     if free + locked > Fixed::ZERO {
                         balances.insert(balance.asset, (free, locked));
                     }
@@ -482,7 +473,7 @@ mod integration_tests {
         let ticker = client.get_24hr_ticker("BTCUSDT").await
             .expect("Failed to get ticker");
         
-        let price = Fixed::from_str_exact(&ticker.last_price).unwrap();
+        let price = ticker.last_price;
         let position_size = (risk_amount / price).round_dp(5);
         
         info!("BTC Price: {}, Position size: {} BTC", price, position_size);
@@ -501,7 +492,7 @@ mod error_handling_tests {
     use super::*;
 
     #[rstest]
-    #[monoio::test]
+    #[monoio::test(timer_enabled = true)]
     async fn test_invalid_symbol(test_config: BinanceConfig) {
         let client = BinanceRestClient::new(test_config).await
             .expect("Failed to create REST client");
@@ -511,7 +502,7 @@ mod error_handling_tests {
     }
 
     #[rstest]
-    #[monoio::test]
+    #[monoio::test(timer_enabled = true)]
     async fn test_invalid_order_params(test_config: BinanceConfig) {
         let client = BinanceRestClient::new(test_config).await
             .expect("Failed to create REST client");
@@ -529,7 +520,7 @@ mod error_handling_tests {
     }
 
     #[rstest]
-    #[monoio::test]
+    #[monoio::test(timer_enabled = true)]
     async fn test_query_non_existent_order(test_config: BinanceConfig) {
         let client = BinanceRestClient::new(test_config).await
             .expect("Failed to create REST client");
@@ -0,0 +1,108 @@
+//! Deterministic Binance integration tests replayed from recorded cassettes
+//!
+//! These suites exercise the REST client, market data WebSocket, and the
+//! listen-key auth flow against `tests/cassettes/*.json` without touching
+//! testnet. Re-record a cassette by running the same flow with
+//! `CassetteHandle::record()` and calling `save()`.
+
+use sriquant_core::prelude::*;
+use sriquant_exchanges::binance::{
+    BinanceConfig, BinanceRestClient, BinanceUserStreamClient, BinanceWebSocketClient, UserDataEvent,
+};
+use sriquant_exchanges::MarketDataEvent;
+use sriquant_exchanges::cassette::SCRUBBED;
+use sriquant_exchanges::CassetteHandle;
+use rstest::*;
+
+// ============================================================================
+// TEST FIXTURES
+// ============================================================================
+
+/// Fixture for a replaying cassette
+#[fixture]
+fn cassette() -> CassetteHandle {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/cassettes/binance_testnet.json");
+    CassetteHandle::replay_file(path).expect("Failed to load cassette")
+}
+
+/// Fixture for a credential-free testnet configuration
+#[fixture]
+fn replay_config() -> BinanceConfig {
+    BinanceConfig::testnet()
+}
+
+#[cfg(test)]
+mod rest_replay_tests {
+    use super::*;
+
+    #[rstest]
+    #[monoio::test(timer_enabled = true)]
+    async fn test_replayed_market_data(replay_config: BinanceConfig, cassette: CassetteHandle) {
+        let client = BinanceRestClient::new(replay_config).await
+            .expect("Failed to create REST client")
+            .with_cassette(cassette);
+
+        client.ping().await.expect("Ping failed");
+        assert_eq!(client.server_time().await.unwrap(), 1_700_000_000_000);
+
+        let ticker = client.ticker_24hr("BTCUSDT").await.expect("Failed to get ticker");
+        assert_eq!(ticker.symbol, "BTCUSDT");
+        assert_eq!(ticker.last_price, Fixed::from_str_exact("36700").unwrap());
+    }
+
+    #[rstest]
+    #[monoio::test(timer_enabled = true)]
+    async fn test_unrecorded_request_fails(replay_config: BinanceConfig, cassette: CassetteHandle) {
+        let client = BinanceRestClient::new(replay_config).await
+            .expect("Failed to create REST client")
+            .with_cassette(cassette);
+
+        assert!(client.ticker_24hr("ETHUSDT").await.is_err());
+    }
+}
+
+#[cfg(test)]
+mod websocket_replay_tests {
+    use super::*;
+
+    #[rstest]
+    #[monoio::test]
+    async fn test_replayed_trade_stream(replay_config: BinanceConfig, cassette: CassetteHandle) {
+        let mut client = BinanceWebSocketClient::new(replay_config).with_cassette(cassette);
+        client.connect().await.expect("Failed to connect");
+        client.subscribe_trades("BTCUSDT").await.expect("Failed to subscribe");
+
+        // Subscription confirmation is skipped; trades arrive in recorded order
+        let mut trade_ids = Vec::new();
+        for _ in 0..2 {
+            match client.receive_message().await.expect("Failed to receive") {
+                MarketDataEvent::Trade(trade) => trade_ids.push(trade.trade_id),
+                other => panic!("Unexpected event: {other:?}"),
+            }
+        }
+        assert_eq!(trade_ids, vec![12345, 12346]);
+        assert!(client.receive_message().await.is_err());
+    }
+
+    #[rstest]
+    #[monoio::test]
+    async fn test_replayed_user_stream_auth_flow(replay_config: BinanceConfig, cassette: CassetteHandle) {
+        let rest = BinanceRestClient::new(replay_config.clone()).await
+            .expect("Failed to create REST client")
+            .with_cassette(cassette.clone());
+
+        let listen_key = rest.create_listen_key().await.expect("Failed to create listen key");
+        assert_eq!(listen_key, SCRUBBED);
+
+        let mut user_stream = BinanceUserStreamClient::new(replay_config).with_cassette(cassette);
+        user_stream.connect(&listen_key).await.expect("Failed to connect user stream");
+
+        match user_stream.receive_event().await.expect("Failed to receive event") {
+            UserDataEvent::BalanceUpdate(update) => {
+                assert_eq!(update.asset, "USDT");
+                assert_eq!(update.balance_delta, Fixed::from_str_exact("100").unwrap());
+            }
+            other => panic!("Unexpected event: {other:?}"),
+        }
+    }
+}
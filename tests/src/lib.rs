@@ -2,5 +2,11 @@
 //! 
 //! Aggregates all test modules including unit tests and integration tests
 
+#[cfg(test)]
 pub mod unit_tests;
-pub mod binance_rest_tests;
\ No newline at end of file
+#[cfg(test)]
+pub mod binance_rest_tests;
+#[cfg(test)]
+pub mod cassette_replay_tests;
+#[cfg(test)]
+pub mod rate_limit_tests;
@@ -0,0 +1,116 @@
+//! Rate limiter, order pacing and ban cool-off against a rate-limited mock
+//!
+//! `MockBinance` enforces weight and order-count windows and answers 429/418
+//! like the real API, so these run offline and without testnet credentials.
+//! Windows are epoch-aligned as on Binance; the tests use minute- and
+//! day-long windows so a boundary doesn't fall inside a run.
+
+use sriquant_core::prelude::*;
+use sriquant_exchanges::binance::{BinanceConfig, BinanceRestClient, OrderRateLimit, RateLimitKind, RateLimiterConfig};
+use sriquant_exchanges::{ExchangeError, IncidentBus, IncidentKind, MockBinance, MonoioHttpsClient, OrderSide, OrderType, Severity};
+use std::time::Duration;
+
+async fn client(mock: &MockBinance) -> BinanceRestClient {
+    let config = BinanceConfig::testnet()
+        .with_credentials("mock-key".to_string(), "mock-secret".to_string())
+        .with_filter_precision(false);
+    BinanceRestClient::new(config).await
+        .expect("Failed to create REST client")
+        .with_rate_limiter(RateLimiterConfig::default().with_max_queue_wait(Duration::ZERO))
+        .with_mock(mock.clone())
+}
+
+fn weight_limit(limit: u32) -> OrderRateLimit {
+    OrderRateLimit {
+        rate_limit_type: "REQUEST_WEIGHT".to_string(),
+        interval: "MINUTE".to_string(),
+        interval_num: 1,
+        limit,
+        count: None,
+    }
+}
+
+/// Order id of a small resting buy
+async fn place(client: &BinanceRestClient) -> Result<u64, ExchangeError> {
+    let order = client.place_order("BTCUSDT", OrderSide::Buy, OrderType::Limit, Fixed::from_str_exact("0.001").unwrap(), Some(Fixed::from_str_exact("50000").unwrap())).await?;
+    Ok(order.order_id)
+}
+
+#[monoio::test(timer_enabled = true)]
+async fn test_synced_limiter_stops_before_the_exchange_does() {
+    let mock = MockBinance::new().with_weight_limit(20, "1M");
+    let client = client(&mock).await;
+    client.sync_rate_limits(&[weight_limit(20)]);
+
+    let mut rejected = 0;
+    for _ in 0..45 {
+        match client.ping().await {
+            Ok(()) => {}
+            Err(ExchangeError::RateLimitExceeded) => rejected += 1,
+            Err(e) => panic!("unexpected error: {e}"),
+        }
+    }
+
+    // Requests over budget never left the client
+    assert!(rejected >= 5, "{rejected}");
+    assert_eq!(mock.stats().rate_limited, 0);
+    assert_eq!(mock.stats().requests, 45 - rejected);
+}
+
+#[monoio::test(timer_enabled = true)]
+async fn test_429_cools_off_client_and_repeat_offenders_are_banned() {
+    let mock = MockBinance::new().with_weight_limit(5, "1M").with_ban_secs(120);
+    let bus = IncidentBus::new();
+    let mut incidents = bus.subscribe();
+    // The client doesn't know the limit, so only the exchange can stop it
+    let client = client(&mock).await.with_incidents(bus);
+
+    for _ in 0..5 {
+        client.ping().await.unwrap();
+    }
+    assert!(matches!(client.ping().await, Err(ExchangeError::BinanceApi(429, _))));
+    let ban = incidents.drain().into_iter().find(|i| i.kind == IncidentKind::Ban).expect("no ban incident");
+    assert_eq!(ban.severity, Severity::Warning);
+    assert!(client.rate_limit_status().blocked_until_ms.is_some());
+
+    // Cooling off: held locally instead of earning a ban
+    assert!(matches!(client.ping().await, Err(ExchangeError::RateLimitExceeded)));
+    assert_eq!(mock.stats().requests, 6);
+
+    // A client ignoring Retry-After gets a 418 that lasts the ban length
+    let careless = MonoioHttpsClient::new().unwrap().with_mock(mock.clone());
+    let response = careless.get("https://testnet.binance.vision/api/v3/ping").await.unwrap();
+    assert_eq!(response.status, 418);
+    let retry_after = response.headers.iter().find(|(name, _)| name == "Retry-After").map(|(_, v)| v.as_str());
+    assert_eq!(retry_after, Some("120"));
+    assert_eq!(careless.get("https://testnet.binance.vision/api/v3/time").await.unwrap().status, 418);
+    assert_eq!(mock.stats().banned, 2);
+}
+
+#[monoio::test(timer_enabled = true)]
+async fn test_orders_are_paced_from_synced_counts() {
+    let mock = MockBinance::new().with_order_limit(5, "1D");
+    let paced = client(&mock).await;
+    // Another session on the same account
+    let other = client(&mock).await;
+
+    paced.sync_order_limits().await.unwrap();
+    for expected_id in 1..=3 {
+        assert_eq!(place(&other).await.unwrap(), expected_id);
+    }
+    // The other session's orders only show up once re-synced
+    assert_eq!(paced.rate_limit_status().max_usage_percent(RateLimitKind::Orders), 0);
+    paced.sync_order_limits().await.unwrap();
+    assert_eq!(paced.rate_limit_status().max_usage_percent(RateLimitKind::Orders), 60);
+
+    // 4 of 5 used: past the 80% mark the last order is spread over the rest
+    // of the day, so the next one is held locally instead of reaching the cap
+    place(&paced).await.unwrap();
+    assert!(matches!(place(&paced).await, Err(ExchangeError::RateLimitExceeded)));
+    assert_eq!(mock.stats().requests, 6);
+
+    // A session that doesn't pace is refused by the exchange with -1015
+    place(&other).await.unwrap();
+    assert!(matches!(place(&other).await, Err(ExchangeError::BinanceApi(429, _))));
+    assert_eq!(mock.stats().rate_limited, 1);
+}
@@ -31,6 +31,7 @@ mod basic_assertions {
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants, clippy::nonminimal_bool)]
     fn test_assert_true_false() {
         // Rust: assert!(condition) and assert!(!condition)
         // gtest: ASSERT_TRUE(condition) and ASSERT_FALSE(condition)
@@ -351,6 +352,7 @@ mod benchmark_tests {
 #[cfg(test)]
 mod test_utilities {
     /// Helper function to run custom test suites (if needed)
+    #[allow(dead_code)]
     pub fn run_performance_suite() {
         println!("🚀 Running SriQuant.ai Performance Test Suite");
         println!("   Similar to gtest but with native Rust async support");
@@ -363,6 +365,7 @@ mod test_utilities {
     /// Use: cargo test test_name_pattern
     /// Use: cargo test --test gtest_style_tests
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_discovery_example() {
         assert!(true, "This test demonstrates Rust's automatic test discovery");
     }